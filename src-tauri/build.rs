@@ -1,3 +1,316 @@
+use std::fmt::Write as _;
+use std::path::Path;
+
 fn main() {
+    generate_command_registry();
     tauri_build::build()
 }
+
+// Command palette registry (see src/palette.rs): every command in main.rs's invoke handler, described
+// from its commands.rs signature, section header, and leading comment, plus a dispatcher that calls it
+// by id, the capability from its `// capability: <name>` line (see src/permissions.rs), and (feature
+// "bindings", see src/bindings.rs) its TypeScript signature.
+// Written to $OUT_DIR/command_registry.rs and included at the end of commands.rs.
+// A registered command without a capability line, or with an unknown one, fails the build.
+fn generate_command_registry() {
+    println!("cargo:rerun-if-changed=src/main.rs");
+    println!("cargo:rerun-if-changed=src/commands.rs");
+    let main_rs = std::fs::read_to_string("src/main.rs").expect("read src/main.rs");
+    let commands_rs = std::fs::read_to_string("src/commands.rs").expect("read src/commands.rs");
+    let registered = registered_commands(&main_rs);
+
+    let mut specs = String::new();
+    let mut arms = String::new();
+    let mut bindings = String::new();
+    let mut capabilities = String::new();
+    let mut missing = Vec::new();
+    for command in parse_commands(&commands_rs) {
+        if !registered.contains(&command.fn_name) {
+            continue;
+        }
+        match command.capability.as_deref().map(capability_variant) {
+            Some(Some(variant)) => {
+                writeln!(capabilities, "        {:?} => crate::permissions::Capability::{},", command.id, variant).unwrap()
+            }
+            Some(None) => missing.push(format!("{} (unknown capability {:?})", command.fn_name, command.capability.as_deref().unwrap_or_default())),
+            None => missing.push(command.fn_name.clone()),
+        }
+        let mut params = String::new();
+        let mut call_args = Vec::new();
+        let mut ts_params = Vec::new();
+        for (name, ty) in &command.params {
+            if ty.contains("State<") {
+                call_args.push("crate::palette::state(app)?".to_string());
+            } else if ty.contains("AppHandle") {
+                call_args.push("app.clone()".to_string());
+            } else {
+                let name = camel_case(name);
+                write!(params, "crate::palette::ParamSpec {{ name: {:?}, rust_type: {:?} }}, ", name, ty).unwrap();
+                call_args.push(format!("crate::palette::arg(args, {:?})?", name));
+                ts_params.push(format!(
+                    "crate::bindings::param::<{}>(out, {:?}, {})",
+                    ty,
+                    name,
+                    ty.starts_with("Option<")
+                ));
+            }
+        }
+        writeln!(
+            specs,
+            "    crate::palette::CommandSpec {{ id: {:?}, category: {:?}, description: {:?}, params: &[{}] }},",
+            command.id, command.category, command.description, params
+        )
+        .unwrap();
+        writeln!(
+            bindings,
+            "    {{\n        let params = vec![{}];\n        let result = crate::bindings::ts::<{}>(out);\n        out.command({:?}, params, result);\n    }}",
+            ts_params.join(", "),
+            command.result,
+            command.id
+        )
+        .unwrap();
+        // action_invoke would recurse into itself
+        if command.fn_name == "action_invoke" {
+            continue;
+        }
+        let call = format!("{}({})", command.fn_name, call_args.join(", "));
+        let call = if command.is_async { format!("Box::pin({}).await", call) } else { call };
+        writeln!(arms, "        {:?} => crate::palette::to_value({})?,", command.id, call).unwrap();
+    }
+
+    if !missing.is_empty() {
+        panic!(
+            "commands without a `// capability: <name>` line above #[tauri::command] (names: {}): {}",
+            CAPABILITIES.join(", "),
+            missing.join(", ")
+        );
+    }
+
+    let generated = format!(
+        "// @generated by build.rs from src/main.rs and src/commands.rs; do not edit\n\n\
+         pub(crate) static REGISTERED_COMMANDS: &[crate::palette::CommandSpec] = &[\n{specs}];\n\n\
+         // Ok(None) when no registered command has this id\n\
+         pub(crate) async fn invoke_registered(\n    app: &tauri::AppHandle,\n    id: &str,\n    args: &serde_json::Value,\n\
+         ) -> Result<Option<serde_json::Value>, String> {{\n    let value = match id {{\n{arms}        _ => return Ok(None),\n    }};\n    Ok(Some(value))\n}}\n\n\
+         // Capability each registered command declares; None for ids that aren't registered commands\n\
+         pub(crate) fn command_capability(id: &str) -> Option<crate::permissions::Capability> {{\n    Some(match id {{\n{capabilities}        _ => return None,\n    }})\n}}\n\n\
+         #[cfg(feature = \"bindings\")]\n\
+         pub(crate) fn command_bindings(out: &mut crate::bindings::Bindings) {{\n{bindings}}}\n"
+    );
+    let out = Path::new(&std::env::var("OUT_DIR").expect("OUT_DIR")).join("command_registry.rs");
+    std::fs::write(out, generated).expect("write command_registry.rs");
+}
+
+// `// capability:` names (src/permissions.rs Capability, kebab-case)
+const CAPABILITIES: &[&str] = &[
+    "browsing", "history", "content", "ai", "agent", "network", "files", "open-external", "capture", "secrets",
+    "settings", "system",
+];
+
+struct Command {
+    id: String,
+    fn_name: String,
+    category: String,
+    description: String,
+    capability: Option<String>,
+    is_async: bool,
+    params: Vec<(String, String)>,
+    result: String, // T of the command's Result<T, String>
+}
+
+// `commands::name` entries inside tauri::generate_handler![...]
+fn registered_commands(main_rs: &str) -> Vec<String> {
+    let Some(start) = main_rs.find("generate_handler![") else {
+        return Vec::new();
+    };
+    let body = &main_rs[start..];
+    let body = &body[..body.find(']').unwrap_or(body.len())];
+    body.lines()
+        .map(|line| line.split("//").next().unwrap_or_default().trim().trim_end_matches(','))
+        .filter_map(|entry| entry.strip_prefix("commands::"))
+        .map(str::to_string)
+        .collect()
+}
+
+fn parse_commands(source: &str) -> Vec<Command> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut commands = Vec::new();
+    let mut category = "General".to_string();
+    let mut comment: Vec<String> = Vec::new();
+    let mut capability = None;
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i].trim();
+        if line.starts_with("// ====") {
+            // Section header: a title line between two rules
+            if let (Some(title), Some(rule)) = (lines.get(i + 1), lines.get(i + 2)) {
+                if rule.trim().starts_with("// ====") {
+                    category = section_name(title.trim().trim_start_matches("//").trim());
+                    comment.clear();
+                    capability = None;
+                    i += 3;
+                    continue;
+                }
+            }
+            i += 1;
+            continue;
+        }
+        if let Some(text) = line.strip_prefix("//") {
+            if let Some(name) = text.trim().strip_prefix("capability:") {
+                capability = Some(name.trim().to_string());
+                i += 1;
+                continue;
+            }
+            comment.push(text.trim_start_matches('/').trim().to_string());
+            i += 1;
+            continue;
+        }
+        if line.starts_with("#[tauri::command") {
+            let id = line
+                .split("name = \"")
+                .nth(1)
+                .and_then(|rest| rest.split('"').next())
+                .map(str::to_string);
+            // The signature runs from `pub fn` / `pub async fn` to the opening brace
+            let mut signature = String::new();
+            let mut j = i + 1;
+            while j < lines.len() {
+                let current = lines[j];
+                signature.push_str(current.split("//").next().unwrap_or_default());
+                signature.push(' ');
+                if current.trim_end().ends_with('{') {
+                    break;
+                }
+                j += 1;
+            }
+            if let Some(mut command) = parse_signature(&signature, std::mem::take(&mut comment).join(" "), category.clone()) {
+                if let Some(id) = id {
+                    command.id = id;
+                }
+                command.capability = capability.take();
+                commands.push(command);
+            }
+            i = j + 1;
+            continue;
+        }
+        if !line.starts_with("#[") {
+            comment.clear();
+            capability = None;
+        }
+        i += 1;
+    }
+    commands
+}
+
+fn parse_signature(signature: &str, description: String, category: String) -> Option<Command> {
+    let is_async = signature.contains("async fn ");
+    let after_fn = &signature[signature.find("fn ")? + 3..];
+    let open = after_fn.find('(')?;
+    let fn_name = after_fn[..open].trim().to_string();
+    // Matching close paren of the parameter list
+    let mut depth = 0;
+    let mut close = None;
+    for (index, c) in after_fn[open..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    close = Some(open + index);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    let close = close?;
+    // `-> Result<T, String>`; commands without a return type resolve to ()
+    let returns = after_fn[close + 1..].trim().trim_end_matches('{').trim();
+    let result = returns
+        .strip_prefix("->")
+        .map(str::trim)
+        .and_then(|r| r.strip_prefix("Result<"))
+        .and_then(|r| r.strip_suffix('>'))
+        .and_then(|r| split_top_level(r).into_iter().next())
+        .map(|r| r.split_whitespace().collect::<Vec<_>>().join(" "))
+        .unwrap_or_else(|| "()".to_string());
+    let params = split_top_level(&after_fn[open + 1..close])
+        .into_iter()
+        .filter_map(|param| {
+            let (name, ty) = param.split_once(':')?;
+            let name = name.trim().trim_start_matches("mut ").trim().to_string();
+            Some((name, ty.split_whitespace().collect::<Vec<_>>().join(" ")))
+        })
+        .collect();
+    Some(Command { id: fn_name.clone(), fn_name, category, description, capability: None, is_async, params, result })
+}
+
+// "open-external" -> "OpenExternal"; None for names that aren't capabilities
+fn capability_variant(name: &str) -> Option<String> {
+    CAPABILITIES.contains(&name).then(|| {
+        name.split('-').map(|word| word[..1].to_uppercase() + &word[1..]).collect()
+    })
+}
+
+// Split on commas outside <...>, (...) and [...]
+fn split_top_level(list: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut current = String::new();
+    for c in list.chars() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    parts.push(current);
+    parts.into_iter().map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect()
+}
+
+// "HISTORY COMMANDS (Frontend API)" -> "History"; acronyms keep their capitals
+fn section_name(title: &str) -> String {
+    let title = title.split('(').next().unwrap_or_default().trim();
+    let title = title.strip_suffix("COMMANDS").unwrap_or(title).trim();
+    let words: Vec<String> = title
+        .split_whitespace()
+        .enumerate()
+        .map(|(index, word)| {
+            if matches!(word, "AI" | "API" | "OCR" | "LLM" | "IPC" | "DNS" | "DOH" | "URL" | "PDF" | "CSV") {
+                word.to_string()
+            } else if index == 0 {
+                let lower = word.to_lowercase();
+                lower[..1].to_uppercase() + &lower[1..]
+            } else {
+                word.to_lowercase()
+            }
+        })
+        .collect();
+    if words.is_empty() {
+        "General".to_string()
+    } else {
+        words.join(" ")
+    }
+}
+
+// Tauri's default argument naming: snake_case parameter -> camelCase key
+fn camel_case(name: &str) -> String {
+    let mut out = String::new();
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper = !out.is_empty();
+        } else if upper {
+            out.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
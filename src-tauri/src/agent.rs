@@ -1,191 +1,205 @@
-// Agent System - Intent Detection + Planner + Tools
-// Offline-first agent that acts on tabs/files/pages
+// Agent System - Tool-calling loop over the tools registry
+// The model sees each enabled tool's JSON schema and replies with one tool call or a final answer;
+// calls that need approval wait in approvals until the user decides
 
 use serde::{Deserialize, Serialize};
-use crate::ai::{AIService, Intent, AIConfig};
-use crate::search::SearchEngine;
+use serde_json::Value;
+use tauri::Manager;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AgentPlan {
-    pub intent: Intent,
-    pub steps: Vec<PlanStep>,
-}
+use crate::agent_runs::RunRecorder;
+use crate::ai::AIService;
+use crate::approvals::{self, ProposedAction};
+use crate::context_window::{ContextPacker, ContextReport, Fit, Priority};
+use crate::tools::{self, ToolCallRecord};
+
+pub const MAX_TOOL_CALLS: usize = 4;
+// Tool output shown to the model per call
+const RESULT_CHARS: usize = 4000;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PlanStep {
-    pub tool: Tool,
-    pub action: String,
-    pub params: serde_json::Value,
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct AgentAnswer {
+    pub run_id: String,                // Transcript id for agent_run_get / agent_run_replay
+    pub query: String,
+    pub answer: String,
+    pub calls: Vec<ToolCallRecord>,
+    pub elapsed_ms: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum Tool {
-    Browser,   // Browser actions (open tab, navigate, etc.)
-    Search,    // Search tool (offline search)
-    Notes,     // Notes tool (read/write notes)
-    Summarize, // Summarize content
-    Compare,   // Compare items
+#[serde(tag = "type", rename_all = "kebab-case", rename_all_fields = "camelCase")]
+pub enum AgentEvent {
+    ToolCall { round: usize, tool: String, arguments: Value },
+    AwaitingApproval { round: usize, action: ProposedAction },
+    ToolResult { round: usize, record: ToolCallRecord },
+    ContextTrimmed { round: usize, report: ContextReport },
+    Answer { answer: AgentAnswer },
+    Failed { error: String },
 }
 
-pub struct Agent {
-    ai_service: AIService,
-    search_engine: Option<SearchEngine>,
+// What the model asked for in one turn
+#[derive(Debug, Clone, PartialEq)]
+pub enum AgentReply {
+    Call { tool: String, arguments: Value },
+    Answer(String),
 }
 
-impl Agent {
-    pub fn new(ai_config: AIConfig) -> Self {
-        Self {
-            ai_service: AIService::new(ai_config),
-            search_engine: None,
+// Let the model call tools until it answers (or runs out of calls).
+// `emit` sees every event, including the final Answer/Failed; all of it goes into the run transcript.
+pub async fn run(
+    app: &tauri::AppHandle,
+    request_id: &str,
+    query: &str,
+    emit: impl Fn(AgentEvent) + Send + Sync,
+) -> Result<AgentAnswer, AgentError> {
+    let recorder = RunRecorder::start(app, "agent", Some(request_id), query);
+    let emit = |event: AgentEvent| {
+        recorder.event(&event);
+        emit(event);
+    };
+    let result = run_inner(app, &recorder, query, &emit).await;
+    match &result {
+        Ok(answer) => {
+            emit(AgentEvent::Answer { answer: answer.clone() });
+            recorder.finish(Some(&answer.answer), None);
+        }
+        Err(e) => {
+            emit(AgentEvent::Failed { error: e.to_string() });
+            recorder.finish(None, Some(&e.to_string()));
         }
     }
+    result
+}
 
-    pub fn with_search_engine(mut self, search_engine: SearchEngine) -> Self {
-        self.search_engine = Some(search_engine);
-        self
-    }
-
-    // Detect intent from user query
-    pub fn detect_intent(&self, query: &str) -> Result<Intent, AgentError> {
-        self.ai_service
-            .detect_intent(query)
-            .map_err(|e| AgentError::AIError(e))
+async fn run_inner(
+    app: &tauri::AppHandle,
+    recorder: &RunRecorder,
+    query: &str,
+    emit: &(impl Fn(AgentEvent) + Send + Sync),
+) -> Result<AgentAnswer, AgentError> {
+    let started = std::time::Instant::now();
+    let query = query.trim();
+    if query.is_empty() {
+        return Err(AgentError::EmptyQuery);
     }
-
-    // Create a plan based on intent
-    pub async fn plan(&self, intent: Intent, query: &str) -> Result<AgentPlan, AgentError> {
-        let steps = match intent {
-            Intent::Search => {
-                vec![PlanStep {
-                    tool: Tool::Search,
-                    action: "search".to_string(),
-                    params: serde_json::json!({ "query": query }),
-                }]
-            }
-            Intent::Summarize => {
-                vec![PlanStep {
-                    tool: Tool::Summarize,
-                    action: "summarize".to_string(),
-                    params: serde_json::json!({ "content": query }),
-                }]
-            }
-            Intent::Compare => {
-                vec![PlanStep {
-                    tool: Tool::Compare,
-                    action: "compare".to_string(),
-                    params: serde_json::json!({ "items": query }),
-                }]
-            }
-            Intent::Act => {
-                // For "act" intent, parse action from query
-                vec![PlanStep {
-                    tool: Tool::Browser,
-                    action: "navigate".to_string(),
-                    params: serde_json::json!({ "url": query }),
-                }]
-            }
-            Intent::Question => {
-                // For questions, search first, then summarize
-                vec![
-                    PlanStep {
-                        tool: Tool::Search,
-                        action: "search".to_string(),
-                        params: serde_json::json!({ "query": query }),
-                    },
-                    PlanStep {
-                        tool: Tool::Summarize,
-                        action: "summarize".to_string(),
-                        params: serde_json::json!({ "query": query }),
-                    },
-                ]
+    let language = crate::language::detect_language(query).language;
+    let registry = tools::registry();
+    let schema = registry.prompt_schema();
+    let mut calls: Vec<ToolCallRecord> = Vec::new();
+    let mut transcript = String::new();
+
+    for round in 1..=MAX_TOOL_CALLS + 1 {
+        // Out of calls: the last turn must answer from what has been gathered
+        let (tool_list, budget_note) = if round > MAX_TOOL_CALLS {
+            ("(none)", "You have used all tool calls. Answer now with what you have.")
+        } else {
+            (schema.as_str(), "")
+        };
+        // Earlier calls are the first to go when the window fills up
+        let ai = app.state::<AIService>();
+        let packed = ContextPacker::new(ai.model())
+            .text("query", query, Priority::Required, Fit::KeepStart)
+            .text("budget", budget_note, Priority::Required, Fit::Whole)
+            .text("tools", tool_list, Priority::High, Fit::Whole)
+            .text("transcript", &transcript, Priority::Normal, Fit::KeepEnd)
+            .render("agent_tools", Some(&language), None);
+        if packed.report.trimmed() {
+            emit(AgentEvent::ContextTrimmed { round, report: packed.report });
+        }
+        let prompt = packed.prompt;
+        let asked = std::time::Instant::now();
+        let response = ai.complete_fresh(&prompt).await;
+        recorder.prompt(&ai, "agent_tools", &prompt, &response, asked.elapsed());
+        let response = response.map_err(AgentError::AIError)?;
+
+        let (tool, arguments) = match parse_reply(&response) {
+            AgentReply::Answer(answer) => {
+                return Ok(AgentAnswer {
+                    run_id: recorder.id().to_string(),
+                    query: query.to_string(),
+                    answer,
+                    calls,
+                    elapsed_ms: started.elapsed().as_millis() as u64,
+                });
             }
+            AgentReply::Call { .. } if round > MAX_TOOL_CALLS => return Err(AgentError::TooManyCalls),
+            AgentReply::Call { tool, arguments } => (tool, arguments),
         };
 
-        Ok(AgentPlan { intent, steps })
-    }
-
-    // Execute a plan step
-    pub async fn execute_step(&self, step: &PlanStep) -> Result<ToolResult, AgentError> {
-        match step.tool {
-            Tool::Search => {
-                if let Some(ref search) = self.search_engine {
-                    let query = step.params["query"]
-                        .as_str()
-                        .ok_or_else(|| AgentError::InvalidParams)?;
-                    let results = search
-                        .search(query, 10)
-                        .map_err(|e| AgentError::SearchError(e.to_string()))?;
-                    Ok(ToolResult::SearchResults(results.len()))
-                } else {
-                    Err(AgentError::ToolUnavailable("Search engine not available".to_string()))
-                }
-            }
-            Tool::Browser => {
-                // Browser actions are handled by browser.rs
-                // This is a placeholder
-                Ok(ToolResult::Success)
-            }
-            Tool::Notes => {
-                // Notes are handled by db.rs
-                Ok(ToolResult::Success)
-            }
-            Tool::Summarize => {
-                // Use AI service to summarize
-                let content = step.params["content"]
-                    .as_str()
-                    .unwrap_or("");
-                let summary = self.ai_service
-                    .complete(&format!("Summarize: {}", content))
-                    .map_err(|e| AgentError::AIError(e))?;
-                Ok(ToolResult::Summary(summary))
-            }
-            Tool::Compare => {
-                // Use AI service to compare
-                let items = step.params["items"]
-                    .as_str()
-                    .unwrap_or("");
-                let comparison = self.ai_service
-                    .complete(&format!("Compare: {}", items))
-                    .map_err(|e| AgentError::AIError(e))?;
-                Ok(ToolResult::Comparison(comparison))
-            }
+        emit(AgentEvent::ToolCall { round, tool: tool.clone(), arguments: arguments.clone() });
+        recorder.tool_call();
+        let record = approvals::submit(app, recorder.id(), &tool, arguments, |action| {
+            emit(AgentEvent::AwaitingApproval { round, action: action.clone() });
+        })
+        .await;
+        if let Some(error) = &record.error {
+            tracing::debug!(target: "ai", "Agent: Tool {} failed: {}", tool, error);
         }
+        emit(AgentEvent::ToolResult { round, record: record.clone() });
+
+        // Failures go back to the model too, so it can fix its arguments or try something else
+        let result = match (&record.output, &record.error) {
+            (Some(output), _) => truncate_chars(&output.to_string(), RESULT_CHARS).to_string(),
+            (None, error) => format!("ERROR: {}", error.as_deref().unwrap_or("unknown")),
+        };
+        transcript.push_str(&format!(
+            "Call {}: {} {}\nResult: {}\n\n",
+            round,
+            record.tool,
+            record.arguments,
+            result
+        ));
+        calls.push(record);
     }
+    Err(AgentError::TooManyCalls)
+}
 
-    // Execute full plan
-    pub async fn execute_plan(&self, plan: AgentPlan) -> Result<Vec<ToolResult>, AgentError> {
-        let mut results = Vec::new();
-        for step in plan.steps {
-            let result = self.execute_step(&step).await?;
-            results.push(result);
+// Replies are JSON ({"tool", "arguments"} or {"answer"}), possibly inside a code fence;
+// anything that isn't a tool call is taken as the answer
+pub fn parse_reply(response: &str) -> AgentReply {
+    let trimmed = response.trim();
+    let json = match (trimmed.find('{'), trimmed.rfind('}')) {
+        (Some(start), Some(end)) if start < end => serde_json::from_str::<Value>(&trimmed[start..=end]).ok(),
+        _ => None,
+    };
+    if let Some(object) = json.as_ref().and_then(Value::as_object) {
+        if let Some(tool) = object.get("tool").or_else(|| object.get("name")).and_then(Value::as_str) {
+            let arguments = object.get("arguments").or_else(|| object.get("parameters")).cloned().unwrap_or(Value::Null);
+            // Some models double-encode the arguments object
+            let arguments = match arguments {
+                Value::String(s) => serde_json::from_str(&s).unwrap_or(Value::String(s)),
+                other => other,
+            };
+            return AgentReply::Call { tool: tool.to_string(), arguments };
+        }
+        if let Some(answer) = object.get("answer").and_then(Value::as_str) {
+            return AgentReply::Answer(answer.trim().to_string());
         }
-        Ok(results)
     }
+    AgentReply::Answer(trimmed.to_string())
 }
 
-#[derive(Debug, Clone)]
-pub enum ToolResult {
-    Success,
-    SearchResults(usize),
-    Summary(String),
-    Comparison(String),
+fn truncate_chars(text: &str, max: usize) -> &str {
+    match text.char_indices().nth(max) {
+        Some((i, _)) => &text[..i],
+        None => text,
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum AgentError {
     AIError(crate::ai::AIError),
-    ToolUnavailable(String),
-    InvalidParams,
-    SearchError(String),
+    EmptyQuery,
+    TooManyCalls,
 }
 
 impl std::fmt::Display for AgentError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             AgentError::AIError(e) => write!(f, "AI error: {}", e),
-            AgentError::ToolUnavailable(msg) => write!(f, "Tool unavailable: {}", msg),
-            AgentError::InvalidParams => write!(f, "Invalid parameters"),
-            AgentError::SearchError(msg) => write!(f, "Search error: {}", msg),
+            AgentError::EmptyQuery => write!(f, "Query is empty"),
+            AgentError::TooManyCalls => write!(f, "Agent kept calling tools after {} calls without answering", MAX_TOOL_CALLS),
         }
     }
 }
@@ -0,0 +1,302 @@
+// Agent Runs - Transcript of each agent and research run: prompts, model, token usage, tool calls, and events
+// Kept in SQLite while history is allowed, so past runs can be listed, inspected, and replayed without the model
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+use crate::ai::{AIError, AIService};
+use crate::db::Database;
+use crate::privacy::PrivacyEnforcer;
+
+// Older runs are pruned at startup
+const MAX_RUNS: usize = 500;
+const DEFAULT_LIMIT: usize = 50;
+// Longest pause kept between events when replaying in real time
+const MAX_REPLAY_GAP: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum RunStatus {
+    Running,
+    Done,
+    Failed,
+    Interrupted,                       // The app closed mid-run
+}
+
+impl RunStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RunStatus::Running => "running",
+            RunStatus::Done => "done",
+            RunStatus::Failed => "failed",
+            RunStatus::Interrupted => "interrupted",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        [RunStatus::Running, RunStatus::Done, RunStatus::Failed, RunStatus::Interrupted]
+            .into_iter()
+            .find(|s| s.as_str() == value)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct AgentRunSummary {
+    pub id: String,
+    pub kind: String,                  // "agent" or "research"
+    pub request_id: Option<String>,    // Frontend id the live events were tagged with
+    pub query: String,
+    pub status: RunStatus,
+    pub provider: Option<String>,      // Of the last prompt
+    pub model: Option<String>,
+    pub prompt_count: u32,
+    pub prompt_tokens: u64,            // Estimated unless the provider reports usage
+    pub completion_tokens: u64,
+    pub tool_calls: u32,
+    pub started_at: i64,               // Unix ms
+    pub finished_at: Option<i64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum EntryKind {
+    Prompt,                            // data is a PromptRecord
+    Event,                             // data is the event exactly as it was emitted
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct RunEntry {
+    pub seq: u64,
+    pub timestamp: i64,                // Unix ms
+    pub kind: EntryKind,
+    pub data: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptRecord {
+    pub task: String,
+    pub provider: String,
+    pub model: String,
+    pub prompt: String,
+    pub response: Option<String>,
+    pub error: Option<String>,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct AgentRun {
+    #[serde(flatten)]
+    pub summary: AgentRunSummary,
+    pub answer: Option<String>,
+    pub entries: Vec<RunEntry>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct RunFilter {
+    pub kind: Option<String>,
+    pub query: Option<String>,         // Substring of the run's query
+    pub status: Option<RunStatus>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+// Writes one run's transcript as it happens; does nothing but hand out an id when history is off
+pub struct RunRecorder {
+    id: String,
+    db: Option<Database>,
+    seq: AtomicU64,
+}
+
+impl RunRecorder {
+    pub fn start(app: &tauri::AppHandle, kind: &str, request_id: Option<&str>, query: &str) -> Self {
+        let id = uuid::Uuid::new_v4().to_string();
+        let keeps_history = app
+            .try_state::<Mutex<PrivacyEnforcer>>()
+            .is_some_and(|p| p.lock().unwrap().can_save_history());
+        let db = app.try_state::<Database>().filter(|_| keeps_history).map(|db| db.inner().clone());
+        let summary = AgentRunSummary {
+            id: id.clone(),
+            kind: kind.to_string(),
+            request_id: request_id.map(str::to_string),
+            query: query.trim().to_string(),
+            status: RunStatus::Running,
+            provider: None,
+            model: None,
+            prompt_count: 0,
+            prompt_tokens: 0,
+            completion_tokens: 0,
+            tool_calls: 0,
+            started_at: chrono::Utc::now().timestamp_millis(),
+            finished_at: None,
+            error: None,
+        };
+        let db = db.filter(|db| match db.agent_run_insert(&summary) {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!(target: "db", "Agent runs: Failed to start transcript: {}", e);
+                false
+            }
+        });
+        Self { id, db, seq: AtomicU64::new(0) }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    // Log a model call and add its token usage to the run
+    pub fn prompt(&self, ai: &AIService, task: &str, prompt: &str, result: &Result<String, AIError>, elapsed: Duration) {
+        let Some(db) = &self.db else { return };
+        let record = PromptRecord {
+            task: task.to_string(),
+            provider: ai.provider_id().to_string(),
+            model: ai.model().to_string(),
+            prompt: prompt.to_string(),
+            response: result.as_ref().ok().cloned(),
+            error: result.as_ref().err().map(|e| e.to_string()),
+            prompt_tokens: crate::text::estimate_tokens(prompt) as u64,
+            completion_tokens: result.as_ref().map_or(0, |r| crate::text::estimate_tokens(r) as u64),
+            duration_ms: elapsed.as_millis() as u64,
+        };
+        if let Err(e) = db.agent_run_add_usage(&self.id, &record) {
+            tracing::warn!(target: "db", "Agent runs: Failed to record usage: {}", e);
+        }
+        self.append(EntryKind::Prompt, serde_json::to_value(&record).unwrap_or(Value::Null));
+    }
+
+    pub fn event(&self, event: &impl Serialize) {
+        if self.db.is_some() {
+            self.append(EntryKind::Event, serde_json::to_value(event).unwrap_or(Value::Null));
+        }
+    }
+
+    pub fn tool_call(&self) {
+        if let Some(db) = &self.db {
+            if let Err(e) = db.agent_run_count_tool_call(&self.id) {
+                tracing::warn!(target: "db", "Agent runs: Failed to count tool call: {}", e);
+            }
+        }
+    }
+
+    pub fn finish(&self, answer: Option<&str>, error: Option<&str>) {
+        let Some(db) = &self.db else { return };
+        let status = if error.is_some() { RunStatus::Failed } else { RunStatus::Done };
+        if let Err(e) = db.agent_run_finish(&self.id, status, chrono::Utc::now().timestamp_millis(), answer, error) {
+            tracing::warn!(target: "db", "Agent runs: Failed to finish transcript: {}", e);
+        }
+    }
+
+    fn append(&self, kind: EntryKind, data: Value) {
+        let Some(db) = &self.db else { return };
+        let entry = RunEntry {
+            seq: self.seq.fetch_add(1, Ordering::Relaxed),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            kind,
+            data,
+        };
+        if let Err(e) = db.agent_run_entry_insert(&self.id, &entry) {
+            tracing::warn!(target: "db", "Agent runs: Failed to append entry: {}", e);
+        }
+    }
+}
+
+// Runs left "running" by a crash are marked interrupted; only the newest MAX_RUNS are kept
+pub fn init(db: &Database) {
+    if let Err(e) = db.agent_runs_interrupt_running() {
+        tracing::warn!(target: "db", "Agent runs: Failed to close interrupted runs: {}", e);
+    }
+    match db.agent_runs_prune(MAX_RUNS) {
+        Ok(0) => {}
+        Ok(n) => tracing::debug!(target: "db", "Agent runs: Pruned {} old runs", n),
+        Err(e) => tracing::warn!(target: "db", "Agent runs: Failed to prune: {}", e),
+    }
+}
+
+// Newest first
+pub fn list(db: &Database, filter: &RunFilter) -> Result<Vec<AgentRunSummary>, RunLogError> {
+    db.agent_runs_list(
+        filter.kind.as_deref(),
+        filter.query.as_deref().map(str::trim).filter(|q| !q.is_empty()),
+        filter.status,
+        filter.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_RUNS),
+        filter.offset.unwrap_or(0),
+    )
+    .map_err(|e| RunLogError::Storage(e.to_string()))
+}
+
+pub fn get(db: &Database, id: &str) -> Result<AgentRun, RunLogError> {
+    db.agent_run_get(id)
+        .map_err(|e| RunLogError::Storage(e.to_string()))?
+        .ok_or_else(|| RunLogError::NotFound(id.to_string()))
+}
+
+// Re-emit a stored run's events on the channel they first went out on ("agent:event" or
+// "research:event"), tagged replay: true. `realtime` keeps the original pacing (gaps capped at 2s).
+pub async fn replay(app: &tauri::AppHandle, id: &str, request_id: Option<String>, realtime: bool) -> Result<usize, RunLogError> {
+    let run = get(&app.state::<Database>(), id)?;
+    let channel = match run.summary.kind.as_str() {
+        "research" => "research:event",
+        _ => "agent:event",
+    };
+    let request_id = request_id.or(run.summary.request_id.clone()).unwrap_or_else(|| run.summary.id.clone());
+    let mut last: Option<i64> = None;
+    let mut emitted = 0;
+    for entry in run.entries.iter().filter(|e| e.kind == EntryKind::Event) {
+        if realtime {
+            if let Some(last) = last {
+                let gap = Duration::from_millis(entry.timestamp.saturating_sub(last).max(0) as u64);
+                tokio::time::sleep(gap.min(MAX_REPLAY_GAP)).await;
+            }
+            last = Some(entry.timestamp);
+        }
+        let _ = app.emit(
+            channel,
+            serde_json::json!({ "requestId": &request_id, "runId": &run.summary.id, "replay": true, "event": &entry.data }),
+        );
+        emitted += 1;
+    }
+    Ok(emitted)
+}
+
+pub fn delete(db: &Database, id: &str) -> Result<(), RunLogError> {
+    match db.agent_run_delete(id) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(RunLogError::NotFound(id.to_string())),
+        Err(e) => Err(RunLogError::Storage(e.to_string())),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum RunLogError {
+    NotFound(String),
+    Storage(String),
+}
+
+impl std::fmt::Display for RunLogError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RunLogError::NotFound(id) => write!(f, "No agent run with id '{}'", id),
+            RunLogError::Storage(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for RunLogError {}
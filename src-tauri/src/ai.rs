@@ -3,8 +3,13 @@
 
 use serde::{Deserialize, Serialize};
 use std::process::{Command, Stdio};
+use std::sync::OnceLock;
 use tokio::process::Command as TokioCommand;
 
+use crate::coalesce::{self, Coalescer, Join};
+use crate::llm_cache::LlmCache;
+use crate::markdown::{MarkdownChunk, MarkdownSegmenter};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AIConfig {
     pub provider: AIProvider,
@@ -22,6 +27,10 @@ pub enum AIProvider {
 pub struct AIService {
     config: AIConfig,
     ollama_available: bool,
+    cache: OnceLock<LlmCache>,         // Attached in setup once the database is open
+    // Identical requests already running, shared instead of sent to the model again
+    task_flights: Coalescer<(), Result<String, AIError>>,
+    stream_flights: Coalescer<MarkdownChunk, Result<String, AIError>>,
 }
 
 impl AIService {
@@ -32,9 +41,34 @@ impl AIService {
         Self {
             config,
             ollama_available,
+            cache: OnceLock::new(),
+            task_flights: Coalescer::new(),
+            stream_flights: Coalescer::new(),
+        }
+    }
+
+    // Route completions through the response cache
+    pub fn attach_cache(&self, cache: LlmCache) {
+        if self.cache.set(cache).is_err() {
+            tracing::warn!(target: "ai", "Response cache already attached");
+        }
+    }
+
+    pub fn cache(&self) -> Option<&LlmCache> {
+        self.cache.get()
+    }
+
+    pub fn provider_id(&self) -> &'static str {
+        match self.config.provider {
+            AIProvider::Ollama => "ollama",
+            AIProvider::LlamaCpp => "llamacpp",
         }
     }
 
+    pub fn model(&self) -> &str {
+        &self.config.model
+    }
+
     // Check if AI service is available
     pub fn is_available(&self) -> bool {
         match self.config.provider {
@@ -46,16 +80,105 @@ impl AIService {
         }
     }
 
-    // Generate completion (blocking)
+    // Generate completion (blocking; exact-match cache only)
     pub fn complete(&self, prompt: &str) -> Result<String, AIError> {
+        if let Some(response) = self.cache().and_then(|c| c.get("complete", &self.config.model, prompt)) {
+            return Ok(response);
+        }
+
+        let response = self.complete_uncached(prompt)?;
+        if let Some(cache) = self.cache() {
+            cache.store_exact("complete", &self.config.model, prompt, &response);
+        }
+        Ok(response)
+    }
+
+    // Completion for a named task; also reuses answers to near-identical prompts,
+    // and joins an identical request that is still running instead of starting another
+    pub async fn complete_task(&self, task: &str, prompt: &str) -> Result<String, AIError> {
+        if let Some(cache) = self.cache() {
+            if let Some(hit) = cache.lookup(task, &self.config.model, prompt).await {
+                return Ok(hit.response);
+            }
+        }
+
+        let key = coalesce::key(task, &[&self.config.model, prompt.trim()]);
+        loop {
+            match self.task_flights.join(&key) {
+                Join::Follower(flight) => {
+                    // None: the first caller was cancelled, so run it here (or join whoever did)
+                    if let Some(result) = flight.follow(|_| {}).await {
+                        tracing::debug!(target: "ai", "Coalesced a {} request with an identical one in flight", task);
+                        return result;
+                    }
+                }
+                Join::Leader(leader) => {
+                    // The active mode profile decides how many model calls may run at once
+                    let _slot = crate::modes::llm_slot().await;
+                    let result = self.complete_uncached_async(prompt).await;
+                    if let (Ok(response), Some(cache)) = (&result, self.cache()) {
+                        cache.store(task, &self.config.model, prompt, response).await;
+                    }
+                    leader.finish(result.clone());
+                    return result;
+                }
+            }
+        }
+    }
+
+    // Completion that skips the cache entirely (agent turns act on live tool results)
+    pub async fn complete_fresh(&self, prompt: &str) -> Result<String, AIError> {
+        let _slot = crate::modes::llm_slot().await;
+        self.complete_uncached_async(prompt).await
+    }
+
+    fn complete_uncached(&self, prompt: &str) -> Result<String, AIError> {
         if !self.is_available() {
             return Err(AIError::ServiceUnavailable);
         }
+        self.check_budget()?;
 
-        match self.config.provider {
+        let response = match self.config.provider {
             AIProvider::Ollama => self.complete_ollama(prompt),
             AIProvider::LlamaCpp => Err(AIError::NotImplemented),
+        }?;
+        self.record_usage(prompt, &response);
+        Ok(response)
+    }
+
+    // Same as complete_uncached, without blocking the runtime thread while the model runs
+    async fn complete_uncached_async(&self, prompt: &str) -> Result<String, AIError> {
+        if !self.is_available() {
+            return Err(AIError::ServiceUnavailable);
         }
+        self.check_budget()?;
+
+        let response = match self.config.provider {
+            AIProvider::Ollama => self.complete_ollama_async(prompt).await,
+            AIProvider::LlamaCpp => Err(AIError::NotImplemented),
+        }?;
+        self.record_usage(prompt, &response);
+        Ok(response)
+    }
+
+    // Local models have nothing cheaper to fall back to, so only a blocking budget stops them
+    fn check_budget(&self) -> Result<(), AIError> {
+        let check = crate::llm_usage::check(self.provider_id(), &self.config.model);
+        match check.decision {
+            crate::llm_usage::BudgetDecision::Block => Err(AIError::BudgetExceeded(check.reason.unwrap_or_default())),
+            _ => Ok(()),
+        }
+    }
+
+    // The CLI doesn't report token counts, so they are estimated from the text
+    fn record_usage(&self, prompt: &str, response: &str) {
+        crate::llm_usage::record(
+            self.provider_id(),
+            &self.config.model,
+            crate::text::estimate_tokens(prompt) as u64,
+            crate::text::estimate_tokens(response) as u64,
+            false,
+        );
     }
 
     // Generate completion with streaming (async)
@@ -66,6 +189,7 @@ impl AIService {
         if !self.is_available() {
             return Err(AIError::ServiceUnavailable);
         }
+        self.check_budget()?;
 
         match self.config.provider {
             AIProvider::Ollama => self.complete_ollama_stream(prompt).await,
@@ -73,6 +197,76 @@ impl AIService {
         }
     }
 
+    // Stream a completion, handing each token to `on_chunk` with the markdown blocks it completed.
+    // Returns the full text. A caller with the same prompt as a running stream gets that stream's
+    // tokens (from the start) rather than a second model call.
+    pub async fn complete_stream_markdown(
+        &self,
+        prompt: &str,
+        mut on_chunk: impl FnMut(MarkdownChunk),
+    ) -> Result<String, AIError> {
+        let key = coalesce::key("stream", &[&self.config.model, prompt.trim()]);
+        loop {
+            match self.stream_flights.join(&key) {
+                Join::Follower(flight) => {
+                    if let Some(result) = flight.follow(&mut on_chunk).await {
+                        tracing::debug!(target: "ai", "Coalesced a streamed completion with an identical one in flight");
+                        return result;
+                    }
+                }
+                Join::Leader(leader) => {
+                    let result = self
+                        .stream_markdown_uncached(prompt, |chunk| {
+                            leader.push(chunk.clone());
+                            on_chunk(chunk);
+                        })
+                        .await;
+                    leader.finish(result.clone());
+                    return result;
+                }
+            }
+        }
+    }
+
+    async fn stream_markdown_uncached(
+        &self,
+        prompt: &str,
+        mut on_chunk: impl FnMut(MarkdownChunk),
+    ) -> Result<String, AIError> {
+        let _slot = crate::modes::llm_slot().await;
+        let mut reader = self.complete_stream(prompt).await?;
+        let mut segmenter = MarkdownSegmenter::new();
+        let mut text = String::new();
+        let mut pending: Vec<u8> = Vec::new();    // Bytes of a UTF-8 character split across reads
+        let mut buf = [0u8; 1024];
+        loop {
+            let read = reader.read(&mut buf).await.map_err(|e| AIError::ExecutionFailed(e.to_string()))?;
+            if read == 0 {
+                break;
+            }
+            pending.extend_from_slice(&buf[..read]);
+            let valid = match std::str::from_utf8(&pending) {
+                Ok(s) => s.len(),
+                Err(e) if e.error_len().is_none() => e.valid_up_to(),
+                Err(_) => return Err(AIError::InvalidResponse),
+            };
+            let token = String::from_utf8_lossy(&pending[..valid]).into_owned();
+            pending.drain(..valid);
+            if token.is_empty() {
+                continue;
+            }
+            let events = segmenter.push(&token);
+            text.push_str(&token);
+            on_chunk(MarkdownChunk { token, events });
+        }
+        let events = segmenter.finish();
+        if !events.is_empty() {
+            on_chunk(MarkdownChunk { token: String::new(), events });
+        }
+        self.record_usage(prompt, &text);
+        Ok(text)
+    }
+
     // Ollama completion (blocking)
     fn complete_ollama(&self, prompt: &str) -> Result<String, AIError> {
         let output = Command::new("ollama")
@@ -83,14 +277,22 @@ impl AIService {
             .stderr(Stdio::piped())
             .output()
             .map_err(|e| AIError::ExecutionFailed(e.to_string()))?;
+        ollama_response(output)
+    }
 
-        if !output.status.success() {
-            let error = String::from_utf8_lossy(&output.stderr);
-            return Err(AIError::ExecutionFailed(error.to_string()));
-        }
-
-        let response = String::from_utf8_lossy(&output.stdout);
-        Ok(response.trim().to_string())
+    // Ollama completion (async)
+    async fn complete_ollama_async(&self, prompt: &str) -> Result<String, AIError> {
+        let output = TokioCommand::new("ollama")
+            .arg("run")
+            .arg(&self.config.model)
+            .arg(prompt)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .output()
+            .await
+            .map_err(|e| AIError::ExecutionFailed(e.to_string()))?;
+        ollama_response(output)
     }
 
     // Ollama streaming completion (async)
@@ -118,7 +320,7 @@ impl AIService {
                 let mut reader = TokioBufReader::new(stderr);
                 let mut line = String::new();
                 while reader.read_line(&mut line).await.is_ok() && !line.is_empty() {
-                    eprintln!("[Ollama] {}", line.trim());
+                    tracing::debug!(target: "ai", "ollama: {}", line.trim());
                     line.clear();
                 }
             });
@@ -135,11 +337,8 @@ impl AIService {
 
     // Detect intent from user query (for agent system)
     pub fn detect_intent(&self, query: &str) -> Result<Intent, AIError> {
-        // Simple intent detection prompt
-        let prompt = format!(
-            "Analyze this user query and determine the intent. Respond with only one word: search, summarize, compare, act, or question.\n\nQuery: {}",
-            query
-        );
+        // Intent detection prompt (answer is parsed as a single English word)
+        let prompt = crate::prompts::render("intent", None, None, &[("query", query)]);
 
         let response = self.complete(&prompt)?;
         let intent_str = response.trim().to_lowercase();
@@ -157,7 +356,17 @@ impl AIService {
     }
 }
 
+fn ollama_response(output: std::process::Output) -> Result<String, AIError> {
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        return Err(AIError::ExecutionFailed(error.to_string()));
+    }
+    let response = String::from_utf8_lossy(&output.stdout);
+    Ok(response.trim().to_string())
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
 pub enum Intent {
     Search,     // User wants to search
     Summarize,  // User wants to summarize content
@@ -172,6 +381,7 @@ pub enum AIError {
     ExecutionFailed(String),
     NotImplemented,
     InvalidResponse,
+    BudgetExceeded(String),            // A [llmBudget] limit set to block
 }
 
 impl std::fmt::Display for AIError {
@@ -181,6 +391,7 @@ impl std::fmt::Display for AIError {
             AIError::ExecutionFailed(msg) => write!(f, "AI execution failed: {}", msg),
             AIError::NotImplemented => write!(f, "Feature not implemented"),
             AIError::InvalidResponse => write!(f, "Invalid AI response"),
+            AIError::BudgetExceeded(reason) => write!(f, "LLM budget exceeded: {}", reason),
         }
     }
 }
@@ -188,4 +399,4 @@ impl std::fmt::Display for AIError {
 impl std::error::Error for AIError {}
 
 // Async imports
-use tokio::io::{AsyncBufReadExt, BufReader as TokioBufReader};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader as TokioBufReader};
@@ -0,0 +1,306 @@
+// API Keys - Provider keys kept in secure storage and checked with a cheap provider call
+// The LLM router and market-data clients look keys up here; legacy env vars are migrated once
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::secure_store::{SecureStore, SecureStoreError};
+
+const ENTRY_PREFIX: &str = "apikey.";
+const TEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    OpenAI,
+    Anthropic,
+    Groq,
+    Mistral,
+    HuggingFace,
+    Brave,
+    Finnhub,
+    N8n,                               // Local n8n's public API, for listing workflows
+}
+
+impl Provider {
+    pub const ALL: [Provider; 8] = [
+        Provider::OpenAI,
+        Provider::Anthropic,
+        Provider::Groq,
+        Provider::Mistral,
+        Provider::HuggingFace,
+        Provider::Brave,
+        Provider::Finnhub,
+        Provider::N8n,
+    ];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Provider::OpenAI => "openai",
+            Provider::Anthropic => "anthropic",
+            Provider::Groq => "groq",
+            Provider::Mistral => "mistral",
+            Provider::HuggingFace => "huggingface",
+            Provider::Brave => "brave",
+            Provider::Finnhub => "finnhub",
+            Provider::N8n => "n8n",
+        }
+    }
+
+    pub fn parse(id: &str) -> Result<Self, ApiKeyError> {
+        Self::ALL
+            .into_iter()
+            .find(|p| p.id() == id.trim().to_lowercase())
+            .ok_or_else(|| ApiKeyError::UnknownProvider(id.to_string()))
+    }
+
+    // Env vars this provider used to be configured with
+    fn env_vars(&self) -> &'static [&'static str] {
+        match self {
+            Provider::OpenAI => &["OPENAI_API_KEY"],
+            Provider::Anthropic => &["ANTHROPIC_API_KEY"],
+            Provider::Groq => &["GROQ_API_KEY"],
+            Provider::Mistral => &["MISTRAL_API_KEY"],
+            Provider::HuggingFace => &["HUGGINGFACE_API_KEY", "HF_TOKEN"],
+            Provider::Brave => &["BRAVE_SEARCH_API_KEY", "BRAVE_API_KEY"],
+            Provider::Finnhub => &["FINNHUB_API_KEY"],
+            Provider::N8n => &["N8N_API_KEY"],
+        }
+    }
+
+    fn entry(&self) -> String {
+        format!("{}{}", ENTRY_PREFIX, self.id())
+    }
+}
+
+// What the vault holds per provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredKey {
+    key: String,
+    added_at: i64,
+    last_tested_at: Option<i64>,
+    last_test_ok: Option<bool>,
+}
+
+// Safe to send to the frontend: never includes the key itself
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyInfo {
+    pub provider: Provider,
+    pub configured: bool,
+    pub hint: Option<String>,          // Last four characters
+    pub added_at: Option<i64>,
+    pub last_tested_at: Option<i64>,
+    pub last_test_ok: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyTest {
+    pub provider: Provider,
+    pub ok: bool,
+    pub status: Option<u16>,
+    pub latency_ms: u64,
+    pub message: String,
+}
+
+#[derive(Clone)]
+pub struct ApiKeyStore {
+    store: SecureStore,
+    http: crate::http::Client,
+}
+
+impl ApiKeyStore {
+    pub fn new(store: SecureStore) -> Self {
+        Self { store, http: crate::http::Client::new(TEST_TIMEOUT).with_retries(1).with_purpose("api-key-test") }
+    }
+
+    // Key for a provider, for the LLM router and market-data clients
+    pub fn get(&self, provider: Provider) -> Option<String> {
+        self.stored(provider).map(|s| s.key)
+    }
+
+    pub fn set(&self, provider: Provider, key: &str) -> Result<ApiKeyInfo, ApiKeyError> {
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(ApiKeyError::EmptyKey);
+        }
+        if key.chars().any(char::is_whitespace) {
+            return Err(ApiKeyError::Malformed("key contains whitespace".to_string()));
+        }
+        let stored = StoredKey {
+            key: key.to_string(),
+            added_at: chrono::Utc::now().timestamp(),
+            last_tested_at: None,
+            last_test_ok: None,
+        };
+        self.save(provider, &stored)?;
+        Ok(info(provider, Some(&stored)))
+    }
+
+    pub fn remove(&self, provider: Provider) -> Result<bool, ApiKeyError> {
+        Ok(self.store.remove(&provider.entry())?)
+    }
+
+    pub fn list(&self) -> Vec<ApiKeyInfo> {
+        Provider::ALL
+            .into_iter()
+            .map(|p| info(p, self.stored(p).as_ref()))
+            .collect()
+    }
+
+    // Copy keys from legacy env vars into the vault (existing entries win); returns providers migrated
+    pub fn migrate_env(&self) -> Vec<Provider> {
+        let mut migrated = Vec::new();
+        for provider in Provider::ALL {
+            if self.stored(provider).is_some() {
+                continue;
+            }
+            let value = provider
+                .env_vars()
+                .iter()
+                .filter_map(|var| std::env::var(var).ok())
+                .find(|v| !v.trim().is_empty());
+            if let Some(value) = value {
+                match self.set(provider, &value) {
+                    Ok(_) => migrated.push(provider),
+                    Err(e) => tracing::warn!(target: "app", "API keys: Not migrating {}: {}", provider.id(), e),
+                }
+            }
+        }
+        migrated
+    }
+
+    // One cheap authenticated call (model list, account info, or a single quote)
+    pub async fn test(&self, provider: Provider) -> Result<ApiKeyTest, ApiKeyError> {
+        let mut stored = self.stored(provider).ok_or(ApiKeyError::NotConfigured(provider.id().to_string()))?;
+        let started = std::time::Instant::now();
+        let result = self.http.send(self.request(provider, &stored.key)).await;
+        let latency_ms = started.elapsed().as_millis() as u64;
+
+        let test = match result {
+            Ok(response) => {
+                let status = response.status();
+                let message = match status.as_u16() {
+                    200..=299 => "Key is valid".to_string(),
+                    401 | 403 => "Key was rejected".to_string(),
+                    429 => "Key is valid but rate limited".to_string(),
+                    code => format!("Unexpected response (HTTP {})", code),
+                };
+                ApiKeyTest {
+                    provider,
+                    ok: status.is_success() || status.as_u16() == 429,
+                    status: Some(status.as_u16()),
+                    latency_ms,
+                    message,
+                }
+            }
+            // Network trouble says nothing about the key; don't record a verdict
+            Err(e) => {
+                return Ok(ApiKeyTest {
+                    provider,
+                    ok: false,
+                    status: None,
+                    latency_ms,
+                    message: format!("Provider unreachable: {}", e),
+                })
+            }
+        };
+
+        stored.last_tested_at = Some(chrono::Utc::now().timestamp());
+        stored.last_test_ok = Some(test.ok);
+        self.save(provider, &stored)?;
+        Ok(test)
+    }
+
+    fn request(&self, provider: Provider, key: &str) -> reqwest::RequestBuilder {
+        let config = crate::config::current();
+        match provider {
+            Provider::OpenAI => self
+                .http
+                .get(format!("{}/models", config.providers.openai_base_url.trim_end_matches('/')))
+                .bearer_auth(key),
+            Provider::Anthropic => self
+                .http
+                .get(format!("{}/models", config.providers.anthropic_base_url.trim_end_matches('/')))
+                .header("x-api-key", key)
+                .header("anthropic-version", "2023-06-01"),
+            Provider::Groq => self.http.get("https://api.groq.com/openai/v1/models").bearer_auth(key),
+            Provider::Mistral => self.http.get("https://api.mistral.ai/v1/models").bearer_auth(key),
+            Provider::HuggingFace => self.http.get("https://huggingface.co/api/whoami-v2").bearer_auth(key),
+            Provider::Brave => self
+                .http
+                .get("https://api.search.brave.com/res/v1/web/search")
+                .query(&[("q", "test"), ("count", "1")])
+                .header("X-Subscription-Token", key),
+            Provider::Finnhub => self
+                .http
+                .get("https://finnhub.io/api/v1/quote")
+                .query(&[("symbol", "AAPL")])
+                .header("X-Finnhub-Token", key),
+            Provider::N8n => self
+                .http
+                .get("http://127.0.0.1:5678/api/v1/workflows")
+                .query(&[("limit", "1")])
+                .header("X-N8N-API-KEY", key),
+        }
+    }
+
+    fn stored(&self, provider: Provider) -> Option<StoredKey> {
+        let json = self.store.get(&provider.entry())?;
+        serde_json::from_str(&json).ok()
+    }
+
+    fn save(&self, provider: Provider, stored: &StoredKey) -> Result<(), ApiKeyError> {
+        let json = serde_json::to_string(stored).map_err(|e| ApiKeyError::Malformed(e.to_string()))?;
+        Ok(self.store.set(&provider.entry(), &json)?)
+    }
+}
+
+fn info(provider: Provider, stored: Option<&StoredKey>) -> ApiKeyInfo {
+    ApiKeyInfo {
+        provider,
+        configured: stored.is_some(),
+        hint: stored.map(|s| {
+            let chars: Vec<char> = s.key.chars().collect();
+            let tail: String = chars[chars.len().saturating_sub(4)..].iter().collect();
+            format!("…{}", tail)
+        }),
+        added_at: stored.map(|s| s.added_at),
+        last_tested_at: stored.and_then(|s| s.last_tested_at),
+        last_test_ok: stored.and_then(|s| s.last_test_ok),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ApiKeyError {
+    UnknownProvider(String),
+    NotConfigured(String),
+    EmptyKey,
+    Malformed(String),
+    Storage(String),
+}
+
+impl From<SecureStoreError> for ApiKeyError {
+    fn from(e: SecureStoreError) -> Self {
+        ApiKeyError::Storage(e.to_string())
+    }
+}
+
+impl std::fmt::Display for ApiKeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiKeyError::UnknownProvider(p) => write!(f, "Unknown provider: {}", p),
+            ApiKeyError::NotConfigured(p) => write!(f, "No API key configured for {}", p),
+            ApiKeyError::EmptyKey => write!(f, "API key is empty"),
+            ApiKeyError::Malformed(msg) => write!(f, "Invalid API key: {}", msg),
+            ApiKeyError::Storage(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ApiKeyError {}
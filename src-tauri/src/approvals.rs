@@ -0,0 +1,357 @@
+// Approvals - Proposed agent actions wait in a pending table until the user approves or rejects them
+// [approvals] decides what runs without asking: risk levels in autoApprove, overridden per tool by rules
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+use tokio::sync::oneshot;
+
+use crate::config::ApprovalConfig;
+use crate::db::Database;
+use crate::privacy::PrivacyEnforcer;
+use crate::tools::{self, Risk, ToolCallRecord};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum ApprovalRule {
+    Auto,
+    Ask,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum ActionStatus {
+    Pending,
+    Approved,                          // Running now
+    Executed,
+    Failed,
+    Rejected,
+    Expired,                           // Nobody decided before the agent gave up waiting (or the app restarted)
+}
+
+impl ActionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActionStatus::Pending => "pending",
+            ActionStatus::Approved => "approved",
+            ActionStatus::Executed => "executed",
+            ActionStatus::Failed => "failed",
+            ActionStatus::Rejected => "rejected",
+            ActionStatus::Expired => "expired",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        [ActionStatus::Pending, ActionStatus::Approved, ActionStatus::Executed, ActionStatus::Failed, ActionStatus::Rejected, ActionStatus::Expired]
+            .into_iter()
+            .find(|s| s.as_str() == value)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ActionRequest {
+    pub tool: String,
+    #[serde(default)]
+    pub arguments: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ProposedAction {
+    pub id: String,
+    pub tool: String,
+    pub arguments: Value,
+    pub risk: Risk,
+    pub source: String,                // "agent", "frontend", or "mcp"
+    pub run_id: Option<String>,        // Agent request that proposed it
+    pub status: ActionStatus,
+    pub auto_approved: bool,
+    pub created_at: i64,               // Unix ms
+    pub decided_at: Option<i64>,
+    pub result: Option<Value>,
+    pub error: Option<String>,
+}
+
+// Agents blocked on a pending action, by action id
+fn waiters() -> &'static Mutex<HashMap<String, oneshot::Sender<ProposedAction>>> {
+    static WAITERS: OnceLock<Mutex<HashMap<String, oneshot::Sender<ProposedAction>>>> = OnceLock::new();
+    WAITERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+// Whatever was still pending when the app last closed can no longer be waited on
+pub fn init(db: &Database) {
+    match db.agent_actions_expire_pending(chrono::Utc::now().timestamp_millis()) {
+        Ok(0) => {}
+        Ok(n) => tracing::info!(target: "ai", "Approvals: Expired {} actions left pending by the last session", n),
+        Err(e) => tracing::warn!(target: "db", "Approvals: Failed to expire stale actions: {}", e),
+    }
+}
+
+pub fn requires_approval(config: &ApprovalConfig, tool: &str, risk: Risk) -> bool {
+    if !config.enabled {
+        return false;
+    }
+    match config.rules.get(tool) {
+        Some(ApprovalRule::Auto) => false,
+        Some(ApprovalRule::Ask) => true,
+        None => !config.auto_approve.contains(&risk),
+    }
+}
+
+// Auto-approved actions run right away; the rest are stored as pending and announced
+// with "agent-actions-proposed". Invalid requests come back failed without being stored.
+pub async fn propose(
+    app: &tauri::AppHandle,
+    source: &str,
+    run_id: Option<&str>,
+    requests: Vec<ActionRequest>,
+) -> Result<Vec<ProposedAction>, ApprovalError> {
+    Ok(propose_inner(app, source, run_id, requests, false, None).await?.0)
+}
+
+// `wait` registers a decision receiver per pending action before anyone is told about it;
+// `ask` overrides the [approvals] rules for callers with their own (MCP clients)
+async fn propose_inner(
+    app: &tauri::AppHandle,
+    source: &str,
+    run_id: Option<&str>,
+    requests: Vec<ActionRequest>,
+    wait: bool,
+    ask: Option<bool>,
+) -> Result<(Vec<ProposedAction>, HashMap<String, oneshot::Receiver<ProposedAction>>), ApprovalError> {
+    let config = crate::config::current().approvals;
+    let db = app.state::<Database>();
+    let mut actions = Vec::with_capacity(requests.len());
+    let mut proposed = Vec::new();
+    let mut receivers = HashMap::new();
+
+    for request in requests {
+        let mut action = ProposedAction {
+            id: uuid::Uuid::new_v4().to_string(),
+            tool: request.tool.clone(),
+            arguments: request.arguments.clone(),
+            risk: Risk::Low,
+            source: source.to_string(),
+            run_id: run_id.map(str::to_string),
+            status: ActionStatus::Pending,
+            auto_approved: false,
+            created_at: chrono::Utc::now().timestamp_millis(),
+            decided_at: None,
+            result: None,
+            error: None,
+        };
+        let (spec, arguments) = match tools::registry().check(&request.tool, request.arguments) {
+            Ok(checked) => checked,
+            Err(e) => {
+                action.status = ActionStatus::Failed;
+                action.decided_at = Some(action.created_at);
+                action.error = Some(e.to_string());
+                actions.push(action);
+                continue;
+            }
+        };
+        action.risk = spec.risk;
+        action.arguments = arguments;
+
+        if ask.unwrap_or_else(|| requires_approval(&config, &action.tool, action.risk)) {
+            db.agent_action_insert(&action).map_err(|e| ApprovalError::Storage(e.to_string()))?;
+            if wait {
+                let (sender, receiver) = oneshot::channel();
+                waiters().lock().unwrap().insert(action.id.clone(), sender);
+                receivers.insert(action.id.clone(), receiver);
+            }
+            proposed.push(action.clone());
+        } else {
+            action.auto_approved = true;
+            execute(app, &mut action).await;
+            if keeps_history(app) {
+                if let Err(e) = db.agent_action_insert(&action) {
+                    tracing::warn!(target: "db", "Approvals: Failed to record action: {}", e);
+                }
+            }
+        }
+        actions.push(action);
+    }
+
+    if !proposed.is_empty() {
+        tracing::debug!(target: "ai", "Approvals: {} actions awaiting approval", proposed.len());
+        let _ = app.emit("agent-actions-proposed", &proposed);
+    }
+    Ok((actions, receivers))
+}
+
+// One agent tool call: runs now if auto-approved, otherwise waits (up to the configured timeout) for a decision.
+// `on_pending` is told when the call starts waiting.
+pub async fn submit(
+    app: &tauri::AppHandle,
+    run_id: &str,
+    tool: &str,
+    arguments: Value,
+    on_pending: impl FnOnce(&ProposedAction),
+) -> ToolCallRecord {
+    submit_from(app, "agent", Some(run_id), tool, arguments, None, on_pending).await
+}
+
+// submit() for other callers: `ask` decides whether to wait for the user instead of the [approvals] rules
+pub async fn submit_from(
+    app: &tauri::AppHandle,
+    source: &str,
+    run_id: Option<&str>,
+    tool: &str,
+    arguments: Value,
+    ask: Option<bool>,
+    on_pending: impl FnOnce(&ProposedAction),
+) -> ToolCallRecord {
+    let started = std::time::Instant::now();
+    let request = ActionRequest { tool: tool.to_string(), arguments: arguments.clone() };
+    let (mut action, receiver) = match propose_inner(app, source, run_id, vec![request], true, ask).await {
+        Ok((mut actions, mut receivers)) => {
+            let action = actions.remove(0);
+            let receiver = receivers.remove(&action.id);
+            (action, receiver)
+        }
+        Err(e) => {
+            return ToolCallRecord {
+                tool: tool.to_string(),
+                arguments,
+                ok: false,
+                output: None,
+                error: Some(e.to_string()),
+                duration_ms: started.elapsed().as_millis() as u64,
+            };
+        }
+    };
+
+    if let Some(receiver) = receiver {
+        on_pending(&action);
+        let timeout = Duration::from_secs(crate::config::current().approvals.timeout_secs);
+        action = match tokio::time::timeout(timeout, receiver).await {
+            Ok(Ok(decided)) => decided,
+            _ => {
+                waiters().lock().unwrap().remove(&action.id);
+                expire(app, &action.id).unwrap_or(action)
+            }
+        };
+    }
+
+    ToolCallRecord {
+        tool: action.tool,
+        arguments: action.arguments,
+        ok: action.status == ActionStatus::Executed,
+        output: action.result,
+        error: match action.status {
+            ActionStatus::Rejected => Some("The user declined this action".to_string()),
+            ActionStatus::Expired => Some("The action was not approved in time".to_string()),
+            _ => action.error,
+        },
+        duration_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+// Run approved actions in the order given; ids that were already decided are returned as they are
+pub async fn approve(app: &tauri::AppHandle, ids: &[String]) -> Result<Vec<ProposedAction>, ApprovalError> {
+    let mut decided = Vec::with_capacity(ids.len());
+    for id in ids {
+        let (mut action, claimed) = claim(app, id, ActionStatus::Approved)?;
+        if claimed {
+            execute(app, &mut action).await;
+            finish(app, &action)?;
+        }
+        decided.push(action);
+    }
+    Ok(decided)
+}
+
+pub fn reject(app: &tauri::AppHandle, ids: &[String]) -> Result<Vec<ProposedAction>, ApprovalError> {
+    let mut decided = Vec::with_capacity(ids.len());
+    for id in ids {
+        let (action, claimed) = claim(app, id, ActionStatus::Rejected)?;
+        if claimed {
+            finish(app, &action)?;
+        }
+        decided.push(action);
+    }
+    Ok(decided)
+}
+
+pub fn pending(app: &tauri::AppHandle) -> Result<Vec<ProposedAction>, ApprovalError> {
+    app.state::<Database>()
+        .agent_actions_by_status(ActionStatus::Pending)
+        .map_err(|e| ApprovalError::Storage(e.to_string()))
+}
+
+async fn execute(app: &tauri::AppHandle, action: &mut ProposedAction) {
+    let record = tools::registry().call_recorded(app, &action.tool, action.arguments.clone()).await;
+    action.status = if record.ok { ActionStatus::Executed } else { ActionStatus::Failed };
+    action.decided_at.get_or_insert(chrono::Utc::now().timestamp_millis());
+    action.result = record.output;
+    action.error = record.error;
+}
+
+// Move a pending action to `status`; the flag is false when someone else decided it first,
+// in which case the action is returned as stored
+fn claim(app: &tauri::AppHandle, id: &str, status: ActionStatus) -> Result<(ProposedAction, bool), ApprovalError> {
+    let db = app.state::<Database>();
+    let now = chrono::Utc::now().timestamp_millis();
+    let claimed = db.agent_action_decide(id, status, now).map_err(|e| ApprovalError::Storage(e.to_string()))?;
+    let mut action = db
+        .agent_action_get(id)
+        .map_err(|e| ApprovalError::Storage(e.to_string()))?
+        .ok_or_else(|| ApprovalError::NotFound(id.to_string()))?;
+    if claimed {
+        action.status = status;
+        action.decided_at = Some(now);
+    }
+    Ok((action, claimed))
+}
+
+// Store the outcome, wake the agent waiting on it, and drop the row when history is off
+fn finish(app: &tauri::AppHandle, action: &ProposedAction) -> Result<(), ApprovalError> {
+    let db = app.state::<Database>();
+    if keeps_history(app) {
+        db.agent_action_update(action).map_err(|e| ApprovalError::Storage(e.to_string()))?;
+    } else {
+        db.agent_action_delete(&action.id).map_err(|e| ApprovalError::Storage(e.to_string()))?;
+    }
+    if let Some(waiter) = waiters().lock().unwrap().remove(&action.id) {
+        let _ = waiter.send(action.clone());
+    }
+    Ok(())
+}
+
+fn expire(app: &tauri::AppHandle, id: &str) -> Option<ProposedAction> {
+    let (action, claimed) = claim(app, id, ActionStatus::Expired).ok()?;
+    if claimed {
+        finish(app, &action).ok()?;
+    }
+    Some(action)
+}
+
+fn keeps_history(app: &tauri::AppHandle) -> bool {
+    app.try_state::<Mutex<PrivacyEnforcer>>().is_some_and(|p| p.lock().unwrap().can_save_history())
+}
+
+#[derive(Debug, Clone)]
+pub enum ApprovalError {
+    NotFound(String),
+    Storage(String),
+}
+
+impl std::fmt::Display for ApprovalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApprovalError::NotFound(id) => write!(f, "No proposed action with id '{}'", id),
+            ApprovalError::Storage(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ApprovalError {}
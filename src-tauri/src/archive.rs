@@ -0,0 +1,405 @@
+// Page Archiving - Self-contained single-file HTML snapshots
+// Subresources (CSS, images, fonts, icons) are inlined as data: URIs; scripts are dropped
+
+use base64::Engine;
+use futures::stream::{self, StreamExt};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::extractor;
+
+const MAX_RESOURCE_BYTES: usize = 10 * 1024 * 1024;
+const MAX_TOTAL_BYTES: usize = 60 * 1024 * 1024;
+const CONCURRENT_FETCHES: usize = 6;
+const RESOURCE_TIMEOUT: Duration = Duration::from_secs(20);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveEntry {
+    pub id: String,
+    pub url: String,
+    pub title: String,
+    pub tab_id: Option<String>,
+    pub file_path: String,
+    pub size_bytes: i64,
+    pub resource_count: i64,
+    pub created_at: i64,
+}
+
+pub struct Snapshot {
+    pub title: String,
+    pub html: String,
+    pub resource_count: usize,
+    pub failed_resources: usize,
+}
+
+fn regexes() -> &'static ArchiveRegexes {
+    static RE: OnceLock<ArchiveRegexes> = OnceLock::new();
+    RE.get_or_init(|| ArchiveRegexes {
+        script: Regex::new(r"(?is)<script\b[^>]*>.*?</script\s*>").unwrap(),
+        link: Regex::new(r"(?is)<link\b[^>]*>").unwrap(),
+        style_block: Regex::new(r"(?is)(<style\b[^>]*>)(.*?)(</style\s*>)").unwrap(),
+        src_attr: Regex::new(r#"(?is)\b(src|poster|href)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap(),
+        srcset_attr: Regex::new(r#"(?is)\s(?:srcset|integrity|crossorigin)\s*=\s*(?:"[^"]*"|'[^']*')"#).unwrap(),
+        css_url: Regex::new(r#"(?i)url\(\s*(?:"([^"]*)"|'([^']*)'|([^)'"]*))\s*\)"#).unwrap(),
+        css_import: Regex::new(r#"(?i)@import\s+(?:url\()?\s*["']?([^"')\s;]+)["']?\s*\)?[^;]*;"#).unwrap(),
+        head: Regex::new(r"(?i)<head\b[^>]*>").unwrap(),
+        tag: Regex::new(r"(?s)<[a-zA-Z][^>]*>").unwrap(),
+        tag_name: Regex::new(r"(?i)^<\s*([a-z0-9]+)").unwrap(),
+        rel: Regex::new(r#"(?i)\brel\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>]+))"#).unwrap(),
+    })
+}
+
+struct ArchiveRegexes {
+    script: Regex,
+    link: Regex,
+    style_block: Regex,
+    src_attr: Regex,
+    srcset_attr: Regex,
+    css_url: Regex,
+    css_import: Regex,
+    head: Regex,
+    tag: Regex,
+    tag_name: Regex,
+    rel: Regex,
+}
+
+// Fetch a page and build a self-contained HTML snapshot
+pub async fn snapshot_page(url: &str) -> Result<Snapshot, ArchiveError> {
+    let base = url::Url::parse(url).map_err(|e| ArchiveError::InvalidUrl(e.to_string()))?;
+    let html = extractor::fetch_html(url)
+        .await
+        .map_err(|e| ArchiveError::FetchFailed(e.to_string()))?;
+    let title = extractor::extract(&html, Some(url)).title;
+
+    let re = regexes();
+    let html = re.script.replace_all(&html, "").into_owned();
+    let html = re.srcset_attr.replace_all(&html, "").into_owned();
+
+    // One retry per resource; a missing image shouldn't stall the whole snapshot
+    let client = crate::http::Client::new(RESOURCE_TIMEOUT).with_retries(1).with_purpose("archive");
+    let mut fetcher = ResourceFetcher::new(client);
+
+    // 1. Stylesheets: fetch, inline their own url() references, replace <link> with <style>
+    let mut stylesheets: HashMap<String, String> = HashMap::new();
+    for link in re.link.find_iter(&html) {
+        let tag = link.as_str();
+        if link_rel(tag).contains("stylesheet") {
+            if let Some(href) = attr_value(tag, "href").and_then(|h| extractor::resolve_url(Some(&base), &h)) {
+                stylesheets.insert(tag.to_string(), href);
+            }
+        }
+    }
+    let css_sources = fetcher.fetch_text_all(stylesheets.values().cloned().collect()).await;
+    let mut inlined_css: HashMap<String, String> = HashMap::new();
+    for (href, css) in css_sources {
+        let css_base = url::Url::parse(&href).ok();
+        let css = fetcher.inline_css(&css, css_base.as_ref()).await;
+        inlined_css.insert(href, css);
+    }
+    let html = re
+        .link
+        .replace_all(&html, |caps: &regex::Captures| {
+            let tag = &caps[0];
+            match stylesheets.get(tag).and_then(|href| inlined_css.get(href)) {
+                Some(css) => format!("<style>{}</style>", css),
+                None if link_rel(tag).contains("stylesheet") => String::new(),
+                None => tag.to_string(),
+            }
+        })
+        .into_owned();
+
+    // 2. Inline <style> blocks' url() references
+    let mut styles = Vec::new();
+    for caps in re.style_block.captures_iter(&html) {
+        styles.push(caps[2].to_string());
+    }
+    let mut rewritten_styles = HashMap::new();
+    for css in styles {
+        let inlined = fetcher.inline_css(&css, Some(&base)).await;
+        rewritten_styles.insert(css, inlined);
+    }
+    let html = re
+        .style_block
+        .replace_all(&html, |caps: &regex::Captures| {
+            let body = rewritten_styles.get(&caps[2]).map(String::as_str).unwrap_or(&caps[2]);
+            format!("{}{}{}", &caps[1], body, &caps[3])
+        })
+        .into_owned();
+
+    // 3. Element src/poster attributes and icon links
+    let mut wanted: HashSet<String> = HashSet::new();
+    for caps in re.src_attr.captures_iter(&html) {
+        let attr = caps[1].to_lowercase();
+        let value = caps.get(2).or(caps.get(3)).map(|m| m.as_str()).unwrap_or("");
+        if attr == "href" {
+            continue; // Only icon <link>s are inlined; handled below
+        }
+        if let Some(abs) = extractor::resolve_url(Some(&base), value) {
+            wanted.insert(abs);
+        }
+    }
+    for link in re.link.find_iter(&html) {
+        if link_rel(link.as_str()).contains("icon") {
+            if let Some(abs) = attr_value(link.as_str(), "href").and_then(|h| extractor::resolve_url(Some(&base), &h)) {
+                wanted.insert(abs);
+            }
+        }
+    }
+    fetcher.fetch_data_uris(wanted.into_iter().collect()).await;
+
+    let html = rewrite_tags(&html, &base, &fetcher);
+
+    // 4. Lock the snapshot down: no network access, links still resolve against the origin
+    let header = format!(
+        "<meta http-equiv=\"Content-Security-Policy\" content=\"default-src 'none'; img-src data:; style-src 'unsafe-inline'; font-src data:; media-src data:\">\n<base href=\"{}\">\n<!-- Archived by Regen from {} on {} -->",
+        base.as_str().replace('"', "%22"),
+        base.as_str().replace("--", "%2D%2D"),
+        chrono::Utc::now().to_rfc3339(),
+    );
+    let html = match re.head.find(&html) {
+        Some(head) => format!("{}\n{}{}", &html[..head.end()], header, &html[head.end()..]),
+        None => format!("<head>{}</head>{}", header, html),
+    };
+
+    Ok(Snapshot {
+        title,
+        html,
+        resource_count: fetcher.data_uris.values().filter(|v| v.is_some()).count()
+            + inlined_css.len(),
+        failed_resources: fetcher.data_uris.values().filter(|v| v.is_none()).count(),
+    })
+}
+
+// Write a snapshot to disk, returning its size in bytes
+pub fn write_snapshot(path: &Path, snapshot: &Snapshot) -> Result<u64, ArchiveError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| ArchiveError::Io(e.to_string()))?;
+    }
+    std::fs::write(path, snapshot.html.as_bytes()).map_err(|e| ArchiveError::Io(e.to_string()))?;
+    Ok(snapshot.html.len() as u64)
+}
+
+// Replace src/poster attributes (and icon hrefs) with fetched data: URIs
+fn rewrite_tags(html: &str, base: &url::Url, fetcher: &ResourceFetcher) -> String {
+    let re = regexes();
+    re.tag
+        .replace_all(html, |caps: &regex::Captures| {
+            let tag = &caps[0];
+            let name = re
+                .tag_name
+                .captures(tag)
+                .map(|c| c[1].to_lowercase())
+                .unwrap_or_default();
+            let is_icon = name == "link" && link_rel(tag).contains("icon");
+            re.src_attr
+                .replace_all(tag, |attr: &regex::Captures| {
+                    let key = attr[1].to_lowercase();
+                    if key == "href" && !is_icon {
+                        return attr[0].to_string();
+                    }
+                    let value = attr.get(2).or(attr.get(3)).map(|m| m.as_str()).unwrap_or("");
+                    match extractor::resolve_url(Some(base), value)
+                        .and_then(|abs| fetcher.data_uri(&abs))
+                    {
+                        Some(data) => format!("{}=\"{}\"", &attr[1], data),
+                        None => attr[0].to_string(),
+                    }
+                })
+                .into_owned()
+        })
+        .into_owned()
+}
+
+fn link_rel(tag: &str) -> String {
+    regexes()
+        .rel
+        .captures(tag)
+        .and_then(|c| c.get(1).or(c.get(2)).or(c.get(3)))
+        .map(|m| m.as_str().to_lowercase())
+        .unwrap_or_default()
+}
+
+fn attr_value(tag: &str, name: &str) -> Option<String> {
+    regexes()
+        .src_attr
+        .captures_iter(tag)
+        .find(|c| c[1].eq_ignore_ascii_case(name))
+        .and_then(|c| c.get(2).or(c.get(3)).map(|m| m.as_str().to_string()))
+}
+
+struct ResourceFetcher {
+    client: crate::http::Client,
+    data_uris: HashMap<String, Option<String>>,
+    total_bytes: usize,
+}
+
+impl ResourceFetcher {
+    fn new(client: crate::http::Client) -> Self {
+        Self {
+            client,
+            data_uris: HashMap::new(),
+            total_bytes: 0,
+        }
+    }
+
+    fn data_uri(&self, url: &str) -> Option<String> {
+        self.data_uris.get(url).cloned().flatten()
+    }
+
+    async fn fetch_bytes(client: &crate::http::Client, url: String) -> (String, Option<(Vec<u8>, Option<String>)>) {
+        let mut response = match client.send(client.get(&url)).await {
+            Ok(r) if r.status().is_success() => r,
+            _ => return (url, None),
+        };
+        if response.content_length().is_some_and(|len| len > MAX_RESOURCE_BYTES as u64) {
+            return (url, None);
+        }
+        let mime = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.split(';').next().unwrap_or(v).trim().to_string());
+        // Content-Length can be missing or wrong, so the cap is also enforced while reading
+        let mut bytes = Vec::new();
+        loop {
+            match response.chunk().await {
+                Ok(Some(chunk)) if bytes.len() + chunk.len() <= MAX_RESOURCE_BYTES => bytes.extend_from_slice(&chunk),
+                Ok(None) => return (url, Some((bytes, mime))),
+                _ => return (url, None),
+            }
+        }
+    }
+
+    async fn fetch_text_all(&mut self, urls: Vec<String>) -> Vec<(String, String)> {
+        let client = self.client.clone();
+        let results: Vec<_> = stream::iter(urls)
+            .map(|url| Self::fetch_bytes(&client, url))
+            .buffer_unordered(CONCURRENT_FETCHES)
+            .collect()
+            .await;
+        results
+            .into_iter()
+            .filter_map(|(url, body)| {
+                let (bytes, _) = body?;
+                self.total_bytes += bytes.len();
+                Some((url, String::from_utf8_lossy(&bytes).into_owned()))
+            })
+            .collect()
+    }
+
+    async fn fetch_data_uris(&mut self, urls: Vec<String>) {
+        let pending: Vec<String> = urls
+            .into_iter()
+            .filter(|u| !self.data_uris.contains_key(u))
+            .collect();
+        let client = self.client.clone();
+        let results: Vec<_> = stream::iter(pending)
+            .map(|url| Self::fetch_bytes(&client, url))
+            .buffer_unordered(CONCURRENT_FETCHES)
+            .collect()
+            .await;
+        for (url, body) in results {
+            let encoded = body.and_then(|(bytes, mime)| {
+                if self.total_bytes + bytes.len() > MAX_TOTAL_BYTES {
+                    return None;
+                }
+                self.total_bytes += bytes.len();
+                let mime = mime.unwrap_or_else(|| guess_mime(&url).to_string());
+                Some(format!(
+                    "data:{};base64,{}",
+                    mime,
+                    base64::engine::general_purpose::STANDARD.encode(&bytes)
+                ))
+            });
+            self.data_uris.insert(url, encoded);
+        }
+    }
+
+    // Inline url() references (fonts, backgrounds) and one level of @import
+    async fn inline_css(&mut self, css: &str, base: Option<&url::Url>) -> String {
+        let re = regexes();
+
+        let imports: Vec<String> = re
+            .css_import
+            .captures_iter(css)
+            .filter_map(|c| extractor::resolve_url(base, &c[1]))
+            .collect();
+        let imported: HashMap<String, String> = self.fetch_text_all(imports).await.into_iter().collect();
+        let css = re
+            .css_import
+            .replace_all(css, |caps: &regex::Captures| {
+                extractor::resolve_url(base, &caps[1])
+                    .and_then(|u| imported.get(&u).cloned())
+                    .unwrap_or_default()
+            })
+            .into_owned();
+
+        let urls: Vec<String> = re
+            .css_url
+            .captures_iter(&css)
+            .filter_map(|c| {
+                let raw = c.get(1).or(c.get(2)).or(c.get(3))?.as_str();
+                extractor::resolve_url(base, raw)
+            })
+            .collect();
+        self.fetch_data_uris(urls).await;
+
+        re.css_url
+            .replace_all(&css, |caps: &regex::Captures| {
+                let raw = caps.get(1).or(caps.get(2)).or(caps.get(3)).map(|m| m.as_str()).unwrap_or("");
+                match extractor::resolve_url(base, raw).and_then(|u| self.data_uri(&u)) {
+                    Some(data) => format!("url(\"{}\")", data),
+                    None => caps[0].to_string(),
+                }
+            })
+            .into_owned()
+    }
+}
+
+fn guess_mime(url: &str) -> &'static str {
+    let path = url.split(['?', '#']).next().unwrap_or(url).to_lowercase();
+    let ext = path.rsplit('.').next().unwrap_or("");
+    match ext {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "svg" => "image/svg+xml",
+        "ico" => "image/x-icon",
+        "css" => "text/css",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "mp3" => "audio/mpeg",
+        _ => "application/octet-stream",
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ArchiveError {
+    InvalidUrl(String),
+    FetchFailed(String),
+    Io(String),
+}
+
+impl std::fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArchiveError::InvalidUrl(msg) => write!(f, "Invalid URL: {}", msg),
+            ArchiveError::FetchFailed(msg) => write!(f, "Failed to fetch page: {}", msg),
+            ArchiveError::Io(msg) => write!(f, "Failed to write archive: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ArchiveError {}
@@ -0,0 +1,362 @@
+// Archive Files - Inspect and extract zip, tar, and tar.gz files (e.g. downloads) without shelling out
+// Not to be confused with archive.rs, which saves web pages. Extraction never writes outside the
+// destination (zip-slip), skips links and special files, caps the entry count and unpacked size, and
+// reports progress through a callback that the command turns into "archive:progress" events.
+
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const MAX_ENTRIES: usize = 100_000;
+pub const MAX_TOTAL_BYTES: u64 = 4 * 1024 * 1024 * 1024; // Unpacked, per extraction
+// Entries over 1 MB that inflate more than this are treated as zip bombs
+const MAX_RATIO: u64 = 1000;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "kebab-case")]
+pub enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl ArchiveFormat {
+    // By extension, falling back to the file's magic bytes
+    pub fn detect(path: &Path) -> Result<Self, ArchiveError> {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_ascii_lowercase();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            return Ok(ArchiveFormat::TarGz);
+        }
+        if name.ends_with(".tar") {
+            return Ok(ArchiveFormat::Tar);
+        }
+        if name.ends_with(".zip") {
+            return Ok(ArchiveFormat::Zip);
+        }
+        let mut head = Vec::with_capacity(512);
+        open(path)?.take(512).read_to_end(&mut head)?;
+        if head.starts_with(b"PK\x03\x04") {
+            Ok(ArchiveFormat::Zip)
+        } else if head.starts_with(&[0x1f, 0x8b]) {
+            Ok(ArchiveFormat::TarGz)
+        } else if head.get(257..262) == Some(b"ustar") {
+            Ok(ArchiveFormat::Tar)
+        } else {
+            Err(ArchiveError::Unsupported(path.display().to_string()))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum EntryKind {
+    File,
+    Directory,
+    Link,                              // Symlinks and hard links; never extracted
+    Special,                           // Devices, FIFOs; never extracted
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveEntry {
+    pub name: String,                  // Path inside the archive, as stored
+    pub kind: EntryKind,
+    pub size: u64,
+    pub compressed_size: Option<u64>,  // Zip only
+    pub safe: bool,                    // False when the path is absolute or climbs out with ".."
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveListing {
+    pub format: ArchiveFormat,
+    pub entries: Vec<ArchiveEntry>,
+    pub total_size: u64,               // Unpacked size of all files
+    pub unsafe_entries: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractProgress {
+    pub archive: String,
+    pub entry: String,                 // Last entry handled
+    pub done: usize,
+    pub total: usize,                  // Entries selected for extraction
+    pub bytes: u64,                    // Written so far
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedEntry {
+    pub name: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractReport {
+    pub dest: String,
+    pub extracted: usize,              // Files and directories written
+    pub bytes: u64,
+    pub skipped: Vec<SkippedEntry>,
+}
+
+struct RawEntry<'a> {
+    name: String,
+    kind: EntryKind,
+    size: u64,
+    compressed_size: Option<u64>,
+    reader: &'a mut dyn Read,
+}
+
+enum Outcome {
+    Written(u64),
+    Skipped(&'static str),
+}
+
+pub fn list(path: &Path) -> Result<ArchiveListing, ArchiveError> {
+    let format = ArchiveFormat::detect(path)?;
+    let mut entries = Vec::new();
+    for_each_entry(path, format, |entry| {
+        entries.push(ArchiveEntry {
+            safe: safe_relative(&entry.name).is_some(),
+            name: entry.name,
+            kind: entry.kind,
+            size: entry.size,
+            compressed_size: entry.compressed_size,
+        });
+        Ok(())
+    })?;
+    let total_size = entries.iter().filter(|e| e.kind == EntryKind::File).map(|e| e.size).sum();
+    let unsafe_entries = entries.iter().filter(|e| !e.safe).count();
+    Ok(ArchiveListing { format, entries, total_size, unsafe_entries })
+}
+
+// Extract everything, or only `selected` entries (a directory name selects its contents), into `dest`
+pub fn extract(
+    path: &Path,
+    dest: &Path,
+    selected: Option<&[String]>,
+    mut progress: impl FnMut(ExtractProgress),
+) -> Result<ExtractReport, ArchiveError> {
+    let listing = list(path)?;
+    let wanted = |name: &str| selected.is_none_or(|selected| selected.iter().any(|s| selects(s, name)));
+    for name in selected.unwrap_or_default() {
+        if !listing.entries.iter().any(|e| selects(name, &e.name)) {
+            return Err(ArchiveError::EntryNotFound(name.clone()));
+        }
+    }
+    let total = listing.entries.iter().filter(|e| wanted(&e.name)).count();
+    let planned: u64 = listing
+        .entries
+        .iter()
+        .filter(|e| e.kind == EntryKind::File && wanted(&e.name))
+        .map(|e| e.size)
+        .sum();
+    if planned > MAX_TOTAL_BYTES {
+        return Err(ArchiveError::TooLarge(planned));
+    }
+
+    fs::create_dir_all(dest)?;
+    let root = dest.canonicalize()?;
+    let archive = path.display().to_string();
+    let mut report = ExtractReport { dest: root.display().to_string(), extracted: 0, bytes: 0, skipped: Vec::new() };
+    let mut done = 0;
+    let mut last_progress: Option<Instant> = None;
+    for_each_entry(path, listing.format, |entry| {
+        if !wanted(&entry.name) {
+            return Ok(());
+        }
+        let name = entry.name.clone();
+        // Header sizes can lie, so the budget is enforced on the bytes actually written
+        match write_entry(&root, entry, MAX_TOTAL_BYTES - report.bytes)? {
+            Outcome::Written(bytes) => {
+                report.extracted += 1;
+                report.bytes += bytes;
+            }
+            Outcome::Skipped(reason) => report.skipped.push(SkippedEntry { name: name.clone(), reason: reason.to_string() }),
+        }
+        done += 1;
+        if done == total || last_progress.is_none_or(|at| at.elapsed() >= PROGRESS_INTERVAL) {
+            last_progress = Some(Instant::now());
+            progress(ExtractProgress { archive: archive.clone(), entry: name, done, total, bytes: report.bytes });
+        }
+        Ok(())
+    })?;
+    if !report.skipped.is_empty() {
+        tracing::warn!(target: "app", "Archive: Skipped {} entries of {}", report.skipped.len(), archive);
+    }
+    Ok(report)
+}
+
+fn for_each_entry(
+    path: &Path,
+    format: ArchiveFormat,
+    mut visit: impl FnMut(RawEntry) -> Result<(), ArchiveError>,
+) -> Result<(), ArchiveError> {
+    let file = BufReader::new(open(path)?);
+    match format {
+        ArchiveFormat::Zip => {
+            let mut archive = zip::ZipArchive::new(file).map_err(|e| ArchiveError::Invalid(e.to_string()))?;
+            if archive.len() > MAX_ENTRIES {
+                return Err(ArchiveError::TooManyEntries(archive.len()));
+            }
+            for index in 0..archive.len() {
+                let mut entry = archive.by_index(index).map_err(|e| ArchiveError::Invalid(e.to_string()))?;
+                let kind = if entry.is_dir() {
+                    EntryKind::Directory
+                } else if entry.is_symlink() {
+                    EntryKind::Link
+                } else {
+                    EntryKind::File
+                };
+                let name = entry.name().to_string();
+                let (size, compressed_size) = (entry.size(), entry.compressed_size());
+                visit(RawEntry { name, kind, size, compressed_size: Some(compressed_size), reader: &mut entry })?;
+            }
+        }
+        ArchiveFormat::Tar | ArchiveFormat::TarGz => {
+            let reader: Box<dyn Read> = if format == ArchiveFormat::TarGz {
+                Box::new(flate2::read::GzDecoder::new(file))
+            } else {
+                Box::new(file)
+            };
+            let mut archive = tar::Archive::new(reader);
+            let entries = archive.entries().map_err(|e| ArchiveError::Invalid(e.to_string()))?;
+            for (index, entry) in entries.enumerate() {
+                if index >= MAX_ENTRIES {
+                    return Err(ArchiveError::TooManyEntries(index + 1));
+                }
+                let mut entry = entry.map_err(|e| ArchiveError::Invalid(e.to_string()))?;
+                let entry_type = entry.header().entry_type();
+                let kind = if entry_type.is_dir() {
+                    EntryKind::Directory
+                } else if entry_type.is_symlink() || entry_type.is_hard_link() {
+                    EntryKind::Link
+                } else if entry_type.is_file() || entry_type.is_contiguous() {
+                    EntryKind::File
+                } else {
+                    EntryKind::Special
+                };
+                let name = String::from_utf8_lossy(&entry.path_bytes()).into_owned();
+                let size = entry.size();
+                visit(RawEntry { name, kind, size, compressed_size: None, reader: &mut entry })?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_entry(root: &Path, entry: RawEntry, budget: u64) -> Result<Outcome, ArchiveError> {
+    let Some(relative) = safe_relative(&entry.name) else {
+        return Ok(Outcome::Skipped("path would land outside the destination"));
+    };
+    let target = root.join(relative);
+    match entry.kind {
+        EntryKind::Directory => {
+            fs::create_dir_all(&target)?;
+            Ok(Outcome::Written(0))
+        }
+        EntryKind::Link | EntryKind::Special => Ok(Outcome::Skipped("links and special files are not extracted")),
+        EntryKind::File => {
+            if let Some(compressed) = entry.compressed_size {
+                if entry.size > 1024 * 1024 && entry.size / compressed.max(1) > MAX_RATIO {
+                    return Ok(Outcome::Skipped("compression ratio is suspiciously high"));
+                }
+            }
+            let parent = target.parent().unwrap_or(root);
+            fs::create_dir_all(parent)?;
+            // A symlink already in the destination could still lead outside it
+            let escapes = !parent.canonicalize()?.starts_with(root)
+                || target.symlink_metadata().is_ok_and(|m| m.file_type().is_symlink());
+            if escapes {
+                return Ok(Outcome::Skipped("path would land outside the destination"));
+            }
+            let mut file = File::create(&target)?;
+            let written = io::copy(&mut entry.reader.take(budget + 1), &mut file)?;
+            if written > budget {
+                drop(file);
+                let _ = fs::remove_file(&target);
+                return Err(ArchiveError::TooLarge(MAX_TOTAL_BYTES - budget + written));
+            }
+            Ok(Outcome::Written(written))
+        }
+    }
+}
+
+// Relative path of an entry under the destination, or None if it is absolute or climbs out with ".."
+fn safe_relative(name: &str) -> Option<PathBuf> {
+    let mut relative = PathBuf::new();
+    // Backslashes are separators in zips written on Windows
+    for component in Path::new(&name.replace('\\', "/")).components() {
+        match component {
+            Component::Normal(part) => relative.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    (!relative.as_os_str().is_empty()).then_some(relative)
+}
+
+fn selects(selected: &str, name: &str) -> bool {
+    let selected = selected.trim_end_matches('/');
+    let name = name.trim_end_matches('/');
+    name == selected || name.strip_prefix(selected).is_some_and(|rest| rest.starts_with('/'))
+}
+
+fn open(path: &Path) -> Result<File, ArchiveError> {
+    File::open(path).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => ArchiveError::NotFound(path.display().to_string()),
+        _ => ArchiveError::Io(e.to_string()),
+    })
+}
+
+#[derive(Debug, Clone)]
+pub enum ArchiveError {
+    NotFound(String),
+    Unsupported(String),
+    Invalid(String),
+    TooManyEntries(usize),
+    TooLarge(u64),
+    EntryNotFound(String),
+    Io(String),
+}
+
+impl From<io::Error> for ArchiveError {
+    fn from(e: io::Error) -> Self {
+        ArchiveError::Io(e.to_string())
+    }
+}
+
+impl std::fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArchiveError::NotFound(path) => write!(f, "Archive {} not found", path),
+            ArchiveError::Unsupported(path) => write!(f, "{} is not a zip, tar, or tar.gz archive", path),
+            ArchiveError::Invalid(e) => write!(f, "Invalid archive: {}", e),
+            ArchiveError::TooManyEntries(count) => {
+                write!(f, "Archive has more than {} entries ({})", MAX_ENTRIES, count)
+            }
+            ArchiveError::TooLarge(bytes) => write!(
+                f,
+                "Archive unpacks to {} MB, over the {} MB limit",
+                bytes / (1024 * 1024),
+                MAX_TOTAL_BYTES / (1024 * 1024)
+            ),
+            ArchiveError::EntryNotFound(name) => write!(f, "No entry named {} in the archive", name),
+            ArchiveError::Io(e) => write!(f, "Archive I/O error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ArchiveError {}
@@ -0,0 +1,447 @@
+// Autofill - Address, contact, and card profiles kept in secure storage, matched against page forms
+// The frontend sends a form's field descriptors and injects the field -> value mappings it gets back
+
+use serde::{Deserialize, Serialize};
+
+use crate::secure_store::{SecureStore, SecureStoreError};
+use crate::state::PrivacyMode;
+
+const ENTRY_PREFIX: &str = "autofill.";
+const MAX_FIELDS: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum ProfileKind {
+    Address,
+    Contact,
+    Card,
+}
+
+// Cards keep metadata only; the number and CVV are never stored, so checkout still needs the user
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct AutofillProfile {
+    pub id: String,                    // Empty on save -> new profile
+    pub kind: Option<ProfileKind>,
+    pub label: String,
+    pub full_name: String,
+    pub given_name: String,
+    pub family_name: String,
+    pub email: String,
+    pub phone: String,
+    pub organization: String,
+    pub address_line1: String,
+    pub address_line2: String,
+    pub city: String,
+    pub region: String,                // State / province
+    pub postal_code: String,
+    pub country: String,
+    pub cardholder: String,
+    pub card_brand: String,
+    pub card_last4: String,
+    pub card_exp_month: Option<u8>,
+    pub card_exp_year: Option<u16>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+// One input as the frontend sees it; any attribute may be missing
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct FormField {
+    pub key: String,                   // Frontend handle echoed back (selector, index)
+    pub name: Option<String>,
+    pub id: Option<String>,
+    pub autocomplete: Option<String>,
+    pub label: Option<String>,
+    pub placeholder: Option<String>,
+    pub input_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct FieldFill {
+    pub key: String,
+    pub field: String,                 // Autocomplete token the field was recognised as
+    pub value: String,
+    pub profile_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct AutofillMatch {
+    pub fills: Vec<FieldFill>,
+    pub unmatched: Vec<String>,        // Keys of fields that weren't recognised or had no value
+}
+
+// Field kinds by autocomplete token (WHATWG names)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Slot {
+    Name,
+    GivenName,
+    FamilyName,
+    Email,
+    Tel,
+    Organization,
+    StreetAddress,
+    AddressLine1,
+    AddressLine2,
+    City,
+    Region,
+    PostalCode,
+    Country,
+    CardName,
+    CardType,
+    CardExp,
+    CardExpMonth,
+    CardExpYear,
+}
+
+impl Slot {
+    fn token(&self) -> &'static str {
+        match self {
+            Slot::Name => "name",
+            Slot::GivenName => "given-name",
+            Slot::FamilyName => "family-name",
+            Slot::Email => "email",
+            Slot::Tel => "tel",
+            Slot::Organization => "organization",
+            Slot::StreetAddress => "street-address",
+            Slot::AddressLine1 => "address-line1",
+            Slot::AddressLine2 => "address-line2",
+            Slot::City => "address-level2",
+            Slot::Region => "address-level1",
+            Slot::PostalCode => "postal-code",
+            Slot::Country => "country",
+            Slot::CardName => "cc-name",
+            Slot::CardType => "cc-type",
+            Slot::CardExp => "cc-exp",
+            Slot::CardExpMonth => "cc-exp-month",
+            Slot::CardExpYear => "cc-exp-year",
+        }
+    }
+
+    fn from_token(token: &str) -> Option<Self> {
+        Some(match token {
+            "name" => Slot::Name,
+            "given-name" => Slot::GivenName,
+            "family-name" => Slot::FamilyName,
+            "email" => Slot::Email,
+            "tel" | "tel-national" => Slot::Tel,
+            "organization" => Slot::Organization,
+            "street-address" => Slot::StreetAddress,
+            "address-line1" => Slot::AddressLine1,
+            "address-line2" => Slot::AddressLine2,
+            "address-level2" => Slot::City,
+            "address-level1" => Slot::Region,
+            "postal-code" => Slot::PostalCode,
+            "country" | "country-name" => Slot::Country,
+            "cc-name" => Slot::CardName,
+            "cc-type" => Slot::CardType,
+            "cc-exp" => Slot::CardExp,
+            "cc-exp-month" => Slot::CardExpMonth,
+            "cc-exp-year" => Slot::CardExpYear,
+            _ => return None,
+        })
+    }
+
+    fn is_card(&self) -> bool {
+        matches!(self, Slot::CardName | Slot::CardType | Slot::CardExp | Slot::CardExpMonth | Slot::CardExpYear)
+    }
+
+    fn value(&self, profile: &AutofillProfile) -> Option<String> {
+        let value = match self {
+            Slot::Name => {
+                if profile.full_name.trim().is_empty() {
+                    format!("{} {}", profile.given_name.trim(), profile.family_name.trim()).trim().to_string()
+                } else {
+                    profile.full_name.clone()
+                }
+            }
+            Slot::GivenName => profile.given_name.clone(),
+            Slot::FamilyName => profile.family_name.clone(),
+            Slot::Email => profile.email.clone(),
+            Slot::Tel => profile.phone.clone(),
+            Slot::Organization => profile.organization.clone(),
+            Slot::StreetAddress => [profile.address_line1.trim(), profile.address_line2.trim()]
+                .into_iter()
+                .filter(|l| !l.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            Slot::AddressLine1 => profile.address_line1.clone(),
+            Slot::AddressLine2 => profile.address_line2.clone(),
+            Slot::City => profile.city.clone(),
+            Slot::Region => profile.region.clone(),
+            Slot::PostalCode => profile.postal_code.clone(),
+            Slot::Country => profile.country.clone(),
+            Slot::CardName => profile.cardholder.clone(),
+            Slot::CardType => profile.card_brand.clone(),
+            Slot::CardExp => match (profile.card_exp_month, profile.card_exp_year) {
+                (Some(m), Some(y)) => format!("{:02}/{:02}", m, y % 100),
+                _ => String::new(),
+            },
+            Slot::CardExpMonth => profile.card_exp_month.map(|m| format!("{:02}", m)).unwrap_or_default(),
+            Slot::CardExpYear => profile.card_exp_year.map(|y| y.to_string()).unwrap_or_default(),
+        };
+        let value = value.trim().to_string();
+        (!value.is_empty()).then_some(value)
+    }
+}
+
+// Substrings of name/id/label -> slot, most specific first ("email" before "mail", "cc-name" before "name")
+const HINTS: &[(&str, Slot)] = &[
+    ("cardholder", Slot::CardName),
+    ("nameoncard", Slot::CardName),
+    ("ccname", Slot::CardName),
+    ("cardtype", Slot::CardType),
+    ("cardbrand", Slot::CardType),
+    ("expmonth", Slot::CardExpMonth),
+    ("expirymonth", Slot::CardExpMonth),
+    ("expirationmonth", Slot::CardExpMonth),
+    ("expyear", Slot::CardExpYear),
+    ("expiryyear", Slot::CardExpYear),
+    ("expirationyear", Slot::CardExpYear),
+    ("expiry", Slot::CardExp),
+    ("expiration", Slot::CardExp),
+    ("email", Slot::Email),
+    ("mail", Slot::Email),
+    ("phone", Slot::Tel),
+    ("mobile", Slot::Tel),
+    ("tel", Slot::Tel),
+    ("fullname", Slot::Name),
+    ("firstname", Slot::GivenName),
+    ("givenname", Slot::GivenName),
+    ("fname", Slot::GivenName),
+    ("lastname", Slot::FamilyName),
+    ("familyname", Slot::FamilyName),
+    ("surname", Slot::FamilyName),
+    ("lname", Slot::FamilyName),
+    ("company", Slot::Organization),
+    ("organization", Slot::Organization),
+    ("organisation", Slot::Organization),
+    ("address2", Slot::AddressLine2),
+    ("addressline2", Slot::AddressLine2),
+    ("apartment", Slot::AddressLine2),
+    ("address1", Slot::AddressLine1),
+    ("addressline1", Slot::AddressLine1),
+    ("street", Slot::AddressLine1),
+    ("address", Slot::AddressLine1),
+    ("city", Slot::City),
+    ("town", Slot::City),
+    ("state", Slot::Region),
+    ("province", Slot::Region),
+    ("region", Slot::Region),
+    ("zip", Slot::PostalCode),
+    ("postcode", Slot::PostalCode),
+    ("postalcode", Slot::PostalCode),
+    ("pincode", Slot::PostalCode),
+    ("country", Slot::Country),
+    ("name", Slot::Name),
+];
+
+// Fields autofill must never touch, whatever they're named
+const SKIPPED_INPUT_TYPES: &[&str] = &["password", "hidden", "file", "submit", "button", "checkbox", "radio"];
+const UNFILLABLE_HINTS: &[&str] = &["cardnumber", "ccnumber", "cvv", "cvc", "securitycode", "username", "login", "otp"];
+
+#[derive(Clone)]
+pub struct AutofillStore {
+    store: SecureStore,
+}
+
+impl AutofillStore {
+    pub fn new(store: SecureStore) -> Self {
+        Self { store }
+    }
+
+    // Most recently updated first
+    pub fn list(&self) -> Vec<AutofillProfile> {
+        let mut profiles: Vec<AutofillProfile> = self
+            .store
+            .names(ENTRY_PREFIX)
+            .iter()
+            .filter_map(|name| self.store.get(name))
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect();
+        profiles.sort_by(|a, b| b.updated_at.cmp(&a.updated_at).then_with(|| a.label.cmp(&b.label)));
+        profiles
+    }
+
+    pub fn get(&self, id: &str) -> Option<AutofillProfile> {
+        let json = self.store.get(&entry(id))?;
+        serde_json::from_str(&json).ok()
+    }
+
+    pub fn save(&self, mut profile: AutofillProfile) -> Result<AutofillProfile, AutofillError> {
+        validate(&mut profile)?;
+        let now = chrono::Utc::now().timestamp();
+        match self.get(profile.id.trim()) {
+            Some(existing) => profile.created_at = existing.created_at,
+            None => {
+                if profile.id.trim().is_empty() {
+                    profile.id = uuid::Uuid::new_v4().to_string();
+                }
+                profile.created_at = now;
+            }
+        }
+        profile.id = profile.id.trim().to_string();
+        profile.updated_at = now;
+
+        let json = serde_json::to_string(&profile).map_err(|e| AutofillError::Invalid(e.to_string()))?;
+        self.store.set(&entry(&profile.id), &json)?;
+        Ok(profile)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<bool, AutofillError> {
+        Ok(self.store.remove(&entry(id))?)
+    }
+
+    // Field -> value mappings for a form. With no profile_id, the newest address/contact profile fills
+    // personal fields and the newest card fills card fields; profiles are never mixed within one group.
+    pub fn match_form(&self, fields: &[FormField], profile_id: Option<&str>, mode: PrivacyMode) -> Result<AutofillMatch, AutofillError> {
+        if matches!(mode, PrivacyMode::Ghost) {
+            return Err(AutofillError::Blocked);
+        }
+        if fields.len() > MAX_FIELDS {
+            return Err(AutofillError::Invalid(format!("form has {} fields (max {})", fields.len(), MAX_FIELDS)));
+        }
+
+        let profiles = self.list();
+        let (personal, card) = match profile_id.map(str::trim).filter(|id| !id.is_empty()) {
+            Some(id) => {
+                let profile = profiles
+                    .iter()
+                    .find(|p| p.id == id)
+                    .ok_or_else(|| AutofillError::NotFound(id.to_string()))?;
+                match profile.kind {
+                    Some(ProfileKind::Card) => (None, Some(profile)),
+                    _ => (Some(profile), None),
+                }
+            }
+            None => (
+                profiles.iter().find(|p| p.kind != Some(ProfileKind::Card)),
+                profiles.iter().find(|p| p.kind == Some(ProfileKind::Card)),
+            ),
+        };
+        // Private windows still fill addresses but keep payment details out of the page
+        let card = card.filter(|_| matches!(mode, PrivacyMode::Normal));
+
+        let mut fills = Vec::new();
+        let mut unmatched = Vec::new();
+        for field in fields {
+            let fill = classify(field).and_then(|slot| {
+                let profile = if slot.is_card() { card } else { personal }?;
+                slot.value(profile).map(|value| FieldFill {
+                    key: field.key.clone(),
+                    field: slot.token().to_string(),
+                    value,
+                    profile_id: profile.id.clone(),
+                })
+            });
+            match fill {
+                Some(fill) => fills.push(fill),
+                None => unmatched.push(field.key.clone()),
+            }
+        }
+        Ok(AutofillMatch { fills, unmatched })
+    }
+}
+
+fn entry(id: &str) -> String {
+    format!("{}{}", ENTRY_PREFIX, id)
+}
+
+fn validate(profile: &mut AutofillProfile) -> Result<(), AutofillError> {
+    let kind = profile.kind.ok_or_else(|| AutofillError::Invalid("kind is required (address, contact, or card)".to_string()))?;
+    if !profile.email.is_empty() && !profile.email.contains('@') {
+        return Err(AutofillError::Invalid(format!("'{}' is not an email address", profile.email)));
+    }
+    if kind == ProfileKind::Card {
+        let last4 = profile.card_last4.trim();
+        // A full number pasted here is cut to its last four digits rather than stored
+        let digits: String = last4.chars().filter(char::is_ascii_digit).collect();
+        if !last4.is_empty() && digits.len() < 4 {
+            return Err(AutofillError::Invalid("card last4 must be 4 digits".to_string()));
+        }
+        profile.card_last4 = digits[digits.len().saturating_sub(4)..].to_string();
+        if profile.card_exp_month.is_some_and(|m| !(1..=12).contains(&m)) {
+            return Err(AutofillError::Invalid("card expiry month must be 1-12".to_string()));
+        }
+        if profile.card_exp_year.is_some_and(|y| !(2000..=2100).contains(&y)) {
+            return Err(AutofillError::Invalid("card expiry year must be four digits".to_string()));
+        }
+    }
+    if profile.label.trim().is_empty() {
+        profile.label = match kind {
+            ProfileKind::Card if !profile.card_last4.is_empty() => format!("Card …{}", profile.card_last4),
+            _ if !profile.full_name.trim().is_empty() => profile.full_name.trim().to_string(),
+            _ if !profile.given_name.trim().is_empty() => format!("{} {}", profile.given_name.trim(), profile.family_name.trim()).trim().to_string(),
+            ProfileKind::Address => "Address".to_string(),
+            ProfileKind::Contact => "Contact".to_string(),
+            ProfileKind::Card => "Card".to_string(),
+        };
+    }
+    Ok(())
+}
+
+// Autocomplete attribute first (minus section-/shipping/billing qualifiers), then name/id/label hints
+fn classify(field: &FormField) -> Option<Slot> {
+    let input_type = field.input_type.as_deref().unwrap_or("text").to_lowercase();
+    if SKIPPED_INPUT_TYPES.contains(&input_type.as_str()) {
+        return None;
+    }
+    if let Some(autocomplete) = field.autocomplete.as_deref() {
+        let autocomplete = autocomplete.to_lowercase();
+        if autocomplete.trim() == "off" {
+            return None;
+        }
+        if let Some(slot) = autocomplete.split_whitespace().find_map(Slot::from_token) {
+            return Some(slot);
+        }
+    }
+    match input_type.as_str() {
+        "email" => return Some(Slot::Email),
+        "tel" => return Some(Slot::Tel),
+        _ => {}
+    }
+
+    [&field.name, &field.id, &field.label, &field.placeholder]
+        .into_iter()
+        .flatten()
+        .map(|text| text.to_lowercase().chars().filter(char::is_ascii_alphanumeric).collect::<String>())
+        .filter(|text| !text.is_empty())
+        // Card numbers and security codes are never stored, and account fields aren't ours to fill
+        .take_while(|text| !UNFILLABLE_HINTS.iter().any(|h| text.contains(h)))
+        .find_map(|text| HINTS.iter().find(|(hint, _)| text.contains(hint)).map(|(_, slot)| *slot))
+}
+
+#[derive(Debug, Clone)]
+pub enum AutofillError {
+    NotFound(String),
+    Invalid(String),
+    Blocked,
+    Storage(String),
+}
+
+impl From<SecureStoreError> for AutofillError {
+    fn from(e: SecureStoreError) -> Self {
+        AutofillError::Storage(e.to_string())
+    }
+}
+
+impl std::fmt::Display for AutofillError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AutofillError::NotFound(id) => write!(f, "Autofill profile not found: {}", id),
+            AutofillError::Invalid(msg) => write!(f, "Invalid autofill data: {}", msg),
+            AutofillError::Blocked => write!(f, "Autofill is off in Ghost mode"),
+            AutofillError::Storage(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AutofillError {}
@@ -0,0 +1,599 @@
+// Backtest - Replays daily bars against a declarative signal rule and scores the result
+// Signals are read on a bar's close and filled at the next bar's open; stops and targets fill intrabar.
+// Results (trades, stats, equity curve) are saved so the Trade mode can compare strategies later.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::db::Database;
+use crate::ohlc::Bar;
+use crate::privacy::PrivacyEnforcer;
+
+const MAX_PERIOD: usize = 400;
+const MAX_CONDITIONS: usize = 8;
+const MAX_SPAN_DAYS: i64 = 20 * 365;
+const DAY_SECS: i64 = 86_400;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    #[default]
+    Long,
+    Short,
+}
+
+// A price field ("close", "high", ...), an indicator on closes ("sma(20)", "ema(9)", "rsi(14)"), or a number
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(untagged)]
+pub enum Operand {
+    Value(f64),
+    Series(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Condition {
+    CrossAbove { left: Operand, right: Operand },
+    CrossBelow { left: Operand, right: Operand },
+    Above { left: Operand, right: Operand },
+    Below { left: Operand, right: Operand },
+}
+
+fn default_capital() -> f64 {
+    100_000.0
+}
+
+// Entry fires when every entry condition holds; the position closes when any exit condition holds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct StrategySpec {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub side: Side,
+    pub entry: Vec<Condition>,
+    #[serde(default)]
+    pub exit: Vec<Condition>,
+    #[serde(default)]
+    pub stop_loss_pct: Option<f64>,
+    #[serde(default)]
+    pub take_profit_pct: Option<f64>,
+    #[serde(default = "default_capital")]
+    pub initial_capital: f64,
+    #[serde(default)]
+    pub fee_pct: f64,                  // Charged on each fill's notional
+}
+
+// "6mo", "1y", "2y", "5y", "10y", or an explicit span in unix seconds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(untagged)]
+pub enum BacktestRange {
+    Preset(String),
+    Span { from: i64, to: i64 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct Trade {
+    pub side: Side,
+    pub entry_time: i64,
+    pub entry_price: f64,
+    pub exit_time: i64,
+    pub exit_price: f64,
+    pub quantity: f64,
+    pub pnl: f64,                      // After fees
+    pub return_pct: f64,
+    pub bars_held: usize,
+    pub exit_reason: String,           // "signal", "stopLoss", "takeProfit", or "end"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct EquityPoint {
+    pub time: i64,
+    pub equity: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct BacktestStats {
+    pub trades: usize,
+    pub wins: usize,
+    pub losses: usize,
+    pub win_rate: f64,                 // Percent of closed trades with positive P&L
+    pub net_pnl: f64,
+    pub total_return_pct: f64,
+    pub buy_and_hold_pct: f64,         // Same bars, bought on the first open and held
+    pub max_drawdown_pct: f64,
+    pub profit_factor: Option<f64>,    // Gross profit over gross loss; None without losing trades
+    pub avg_trade_pct: f64,
+    pub exposure_pct: f64,             // Share of bars spent in a position
+    pub final_equity: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct BacktestResult {
+    pub id: String,
+    pub symbol: String,
+    pub strategy: StrategySpec,
+    pub from: i64,
+    pub to: i64,
+    pub bars: usize,
+    pub trades: Vec<Trade>,
+    pub stats: BacktestStats,
+    pub equity: Vec<EquityPoint>,
+    pub saved: bool,
+    pub created_at: i64,
+}
+
+// What a series name in a condition refers to
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Series {
+    Open,
+    High,
+    Low,
+    Close,
+    Volume,
+    Sma(usize),
+    Ema(usize),
+    Rsi(usize),
+}
+
+impl Series {
+    fn parse(name: &str) -> Result<Self, BacktestError> {
+        let name = name.trim().to_lowercase().replace(' ', "");
+        let invalid = || BacktestError::InvalidStrategy(format!("unknown series '{}'", name));
+        let simple = match name.as_str() {
+            "open" => Some(Series::Open),
+            "high" => Some(Series::High),
+            "low" => Some(Series::Low),
+            "close" | "price" => Some(Series::Close),
+            "volume" => Some(Series::Volume),
+            _ => None,
+        };
+        if let Some(series) = simple {
+            return Ok(series);
+        }
+        let (function, rest) = name.split_once('(').ok_or_else(invalid)?;
+        let period: usize = rest.strip_suffix(')').and_then(|p| p.parse().ok()).ok_or_else(invalid)?;
+        if !(1..=MAX_PERIOD).contains(&period) {
+            return Err(BacktestError::InvalidStrategy(format!("'{}': period must be between 1 and {}", name, MAX_PERIOD)));
+        }
+        match function {
+            "sma" => Ok(Series::Sma(period)),
+            "ema" => Ok(Series::Ema(period)),
+            "rsi" => Ok(Series::Rsi(period)),
+            _ => Err(invalid()),
+        }
+    }
+
+    // Bars needed before the series has a value
+    fn warmup(&self) -> usize {
+        match self {
+            Series::Sma(n) | Series::Ema(n) => *n,
+            Series::Rsi(n) => n + 1,
+            _ => 0,
+        }
+    }
+
+    fn compute(&self, bars: &[Bar]) -> Vec<Option<f64>> {
+        let closes: Vec<f64> = bars.iter().map(|b| b.close).collect();
+        match self {
+            Series::Open => bars.iter().map(|b| Some(b.open)).collect(),
+            Series::High => bars.iter().map(|b| Some(b.high)).collect(),
+            Series::Low => bars.iter().map(|b| Some(b.low)).collect(),
+            Series::Close => closes.into_iter().map(Some).collect(),
+            Series::Volume => bars.iter().map(|b| Some(b.volume)).collect(),
+            Series::Sma(n) => sma(&closes, *n),
+            Series::Ema(n) => ema(&closes, *n),
+            Series::Rsi(n) => rsi(&closes, *n),
+        }
+    }
+}
+
+pub(crate) fn sma(values: &[f64], n: usize) -> Vec<Option<f64>> {
+    let mut out = vec![None; values.len()];
+    let mut sum = 0.0;
+    for (i, value) in values.iter().enumerate() {
+        sum += value;
+        if i >= n {
+            sum -= values[i - n];
+        }
+        if i + 1 >= n {
+            out[i] = Some(sum / n as f64);
+        }
+    }
+    out
+}
+
+// Seeded with the SMA of the first n values
+pub(crate) fn ema(values: &[f64], n: usize) -> Vec<Option<f64>> {
+    let mut out = vec![None; values.len()];
+    if values.len() < n {
+        return out;
+    }
+    let alpha = 2.0 / (n as f64 + 1.0);
+    let mut current = values[..n].iter().sum::<f64>() / n as f64;
+    out[n - 1] = Some(current);
+    for i in n..values.len() {
+        current += alpha * (values[i] - current);
+        out[i] = Some(current);
+    }
+    out
+}
+
+// Wilder's smoothing
+pub(crate) fn rsi(values: &[f64], n: usize) -> Vec<Option<f64>> {
+    let mut out = vec![None; values.len()];
+    if values.len() <= n {
+        return out;
+    }
+    let change = |i: usize| values[i] - values[i - 1];
+    let (mut gain, mut loss) = (1..=n).fold((0.0, 0.0), |(g, l), i| (g + change(i).max(0.0), l + (-change(i)).max(0.0)));
+    gain /= n as f64;
+    loss /= n as f64;
+    let value = |gain: f64, loss: f64| if loss == 0.0 { 100.0 } else { 100.0 - 100.0 / (1.0 + gain / loss) };
+    out[n] = Some(value(gain, loss));
+    for (i, slot) in out.iter_mut().enumerate().skip(n + 1) {
+        gain = (gain * (n as f64 - 1.0) + change(i).max(0.0)) / n as f64;
+        loss = (loss * (n as f64 - 1.0) + (-change(i)).max(0.0)) / n as f64;
+        *slot = Some(value(gain, loss));
+    }
+    out
+}
+
+impl BacktestRange {
+    fn resolve(&self, now: i64) -> Result<(i64, i64), BacktestError> {
+        let (from, to) = match self {
+            BacktestRange::Preset(preset) => {
+                let days = match preset.trim().to_lowercase().as_str() {
+                    "3mo" => 91,
+                    "6mo" => 182,
+                    "1y" => 365,
+                    "2y" => 2 * 365,
+                    "5y" => 5 * 365,
+                    "10y" => 10 * 365,
+                    other => return Err(BacktestError::InvalidRange(format!("unknown range '{}'", other))),
+                };
+                (now - days * DAY_SECS, now)
+            }
+            BacktestRange::Span { from, to } => (*from, (*to).min(now)),
+        };
+        if from >= to {
+            return Err(BacktestError::InvalidRange("'from' must be before 'to'".to_string()));
+        }
+        if to - from > MAX_SPAN_DAYS * DAY_SECS {
+            return Err(BacktestError::InvalidRange(format!("ranges are limited to {} years", MAX_SPAN_DAYS / 365)));
+        }
+        Ok((from, to))
+    }
+}
+
+impl StrategySpec {
+    fn validate(&self) -> Result<(), BacktestError> {
+        let invalid = |msg: &str| Err(BacktestError::InvalidStrategy(msg.to_string()));
+        if self.entry.is_empty() {
+            return invalid("at least one entry condition is required");
+        }
+        if self.entry.len() > MAX_CONDITIONS || self.exit.len() > MAX_CONDITIONS {
+            return invalid("too many conditions");
+        }
+        if self.exit.is_empty() && self.stop_loss_pct.is_none() && self.take_profit_pct.is_none() {
+            return invalid("an exit condition, stopLossPct, or takeProfitPct is required");
+        }
+        for pct in [self.stop_loss_pct, self.take_profit_pct].into_iter().flatten() {
+            if !(pct > 0.0 && pct < 100.0) {
+                return invalid("stopLossPct and takeProfitPct must be between 0 and 100");
+            }
+        }
+        if !(self.initial_capital.is_finite() && self.initial_capital > 0.0) {
+            return invalid("initialCapital must be positive");
+        }
+        if !(0.0..=5.0).contains(&self.fee_pct) {
+            return invalid("feePct must be between 0 and 5");
+        }
+        for condition in self.entry.iter().chain(&self.exit) {
+            let (left, right) = condition.operands();
+            if let (Operand::Value(_), Operand::Value(_)) = (left, right) {
+                return invalid("a condition needs at least one series");
+            }
+            for operand in [left, right] {
+                match operand {
+                    Operand::Series(name) => {
+                        Series::parse(name)?;
+                    }
+                    Operand::Value(v) if !v.is_finite() => return invalid("condition values must be finite"),
+                    Operand::Value(_) => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn series(&self) -> Vec<Series> {
+        let mut series = Vec::new();
+        for condition in self.entry.iter().chain(&self.exit) {
+            let (left, right) = condition.operands();
+            for operand in [left, right] {
+                if let Operand::Series(name) = operand {
+                    if let Ok(parsed) = Series::parse(name) {
+                        if !series.contains(&parsed) {
+                            series.push(parsed);
+                        }
+                    }
+                }
+            }
+        }
+        series
+    }
+}
+
+impl Condition {
+    fn operands(&self) -> (&Operand, &Operand) {
+        match self {
+            Condition::CrossAbove { left, right }
+            | Condition::CrossBelow { left, right }
+            | Condition::Above { left, right }
+            | Condition::Below { left, right } => (left, right),
+        }
+    }
+
+    fn holds(&self, values: &Values, i: usize) -> bool {
+        let (left, right) = self.operands();
+        let at = |i: usize| Some((values.get(left, i)?, values.get(right, i)?));
+        match self {
+            Condition::Above { .. } => at(i).is_some_and(|(l, r)| l > r),
+            Condition::Below { .. } => at(i).is_some_and(|(l, r)| l < r),
+            Condition::CrossAbove { .. } if i > 0 => {
+                matches!((at(i - 1), at(i)), (Some((pl, pr)), Some((l, r))) if pl <= pr && l > r)
+            }
+            Condition::CrossBelow { .. } if i > 0 => {
+                matches!((at(i - 1), at(i)), (Some((pl, pr)), Some((l, r))) if pl >= pr && l < r)
+            }
+            _ => false,
+        }
+    }
+}
+
+// Every series the strategy mentions, computed once over all bars
+struct Values {
+    series: HashMap<String, Vec<Option<f64>>>,
+}
+
+impl Values {
+    fn get(&self, operand: &Operand, i: usize) -> Option<f64> {
+        match operand {
+            Operand::Value(v) => Some(*v),
+            Operand::Series(name) => *self.series.get(name)?.get(i)?,
+        }
+    }
+}
+
+pub async fn run(app: &tauri::AppHandle, symbol: &str, strategy: StrategySpec, range: &BacktestRange) -> Result<BacktestResult, BacktestError> {
+    strategy.validate()?;
+    let symbol = symbol.trim().to_uppercase();
+    let now = chrono::Utc::now().timestamp();
+    let (from, to) = range.resolve(now)?;
+    // Trading days run at about 5/7 of calendar days; fetch enough history for the slowest indicator
+    let warmup = strategy.series().iter().map(Series::warmup).max().unwrap_or(0);
+    let warmup_from = from - ((warmup as i64 * 7 / 5) + 10) * DAY_SECS;
+
+    let can_store = app.state::<Mutex<PrivacyEnforcer>>().lock().unwrap().can_write_to_disk();
+    let db = app.state::<Database>().inner().clone();
+    let bars = crate::ohlc::daily_bars(&db, &symbol, warmup_from, to, can_store)
+        .await
+        .map_err(|e| BacktestError::Data(e.to_string()))?;
+    let start = bars.iter().position(|b| b.time >= from).ok_or_else(|| BacktestError::NoData(symbol.clone()))?;
+    if bars.len() - start < 2 {
+        return Err(BacktestError::NoData(symbol));
+    }
+
+    let (trades, equity) = simulate(&strategy, &bars, start);
+    let stats = score(&strategy, &bars[start..], &trades, &equity);
+    let mut result = BacktestResult {
+        id: uuid::Uuid::new_v4().to_string(),
+        symbol,
+        strategy,
+        from,
+        to,
+        bars: bars.len() - start,
+        trades,
+        stats,
+        equity,
+        saved: false,
+        created_at: now,
+    };
+    if can_store {
+        result.saved = true;
+        let saved = result.clone();
+        tauri::async_runtime::spawn_blocking(move || db.backtest_save(&saved))
+            .await
+            .map_err(|e| BacktestError::Storage(e.to_string()))?
+            .map_err(|e| BacktestError::Storage(e.to_string()))?;
+    }
+    tracing::info!(
+        target: "app",
+        "Backtest: {} over {} bars, {} trades, {:.1}% return",
+        result.symbol,
+        result.bars,
+        result.stats.trades,
+        result.stats.total_return_pct
+    );
+    Ok(result)
+}
+
+struct Position {
+    entry_index: usize,
+    entry_price: f64,
+    quantity: f64,
+    entry_fee: f64,
+}
+
+fn simulate(strategy: &StrategySpec, bars: &[Bar], start: usize) -> (Vec<Trade>, Vec<EquityPoint>) {
+    let values = Values {
+        series: strategy
+            .entry
+            .iter()
+            .chain(&strategy.exit)
+            .flat_map(|c| {
+                let (left, right) = c.operands();
+                [left, right]
+            })
+            .filter_map(|operand| match operand {
+                Operand::Series(name) => Some((name.clone(), Series::parse(name).ok()?.compute(bars))),
+                Operand::Value(_) => None,
+            })
+            .collect(),
+    };
+    let direction = if strategy.side == Side::Long { 1.0 } else { -1.0 };
+    let fee_rate = strategy.fee_pct / 100.0;
+    let mut cash = strategy.initial_capital;
+    let mut position: Option<Position> = None;
+    let mut trades = Vec::new();
+    let mut equity = Vec::with_capacity(bars.len() - start);
+    // Decided on the previous close, filled at this bar's open
+    let mut pending_entry = false;
+    let mut pending_exit = false;
+
+    let close = |position: Position, index: usize, price: f64, reason: &str, cash: &mut f64, trades: &mut Vec<Trade>| {
+        let exit_fee = position.quantity * price * fee_rate;
+        let gross = direction * (price - position.entry_price) * position.quantity;
+        let pnl = gross - position.entry_fee - exit_fee;
+        *cash += pnl;
+        trades.push(Trade {
+            side: strategy.side,
+            entry_time: bars[position.entry_index].time,
+            entry_price: position.entry_price,
+            exit_time: bars[index].time,
+            exit_price: price,
+            quantity: position.quantity,
+            pnl,
+            return_pct: pnl / (position.entry_price * position.quantity) * 100.0,
+            bars_held: index - position.entry_index,
+            exit_reason: reason.to_string(),
+        });
+    };
+
+    for i in start..bars.len() {
+        let bar = bars[i];
+        if let Some(open) = position.take() {
+            if pending_exit {
+                close(open, i, bar.open, "signal", &mut cash, &mut trades);
+            } else {
+                position = Some(open);
+            }
+        } else if pending_entry && cash > 0.0 {
+            // All-in: the fee comes out of the capital committed
+            let quantity = cash / (bar.open * (1.0 + fee_rate));
+            position = Some(Position { entry_index: i, entry_price: bar.open, quantity, entry_fee: quantity * bar.open * fee_rate });
+        }
+        pending_entry = false;
+        pending_exit = false;
+
+        // Stops and targets fill intrabar, at the open when the bar gaps through them; stops win ties
+        if let Some(open) = position.take() {
+            let level = |pct: f64, adverse: bool| {
+                let sign = if adverse { -direction } else { direction };
+                open.entry_price * (1.0 + sign * pct / 100.0)
+            };
+            let stop = strategy.stop_loss_pct.map(|pct| level(pct, true));
+            let target = strategy.take_profit_pct.map(|pct| level(pct, false));
+            let (worst, best) = if direction > 0.0 { (bar.low, bar.high) } else { (bar.high, bar.low) };
+            let beyond = |price: f64, level: f64| direction * (price - level);
+            if let Some(stop) = stop.filter(|s| beyond(worst, *s) <= 0.0) {
+                let fill = if beyond(bar.open, stop) < 0.0 { bar.open } else { stop };
+                close(open, i, fill, "stopLoss", &mut cash, &mut trades);
+            } else if let Some(target) = target.filter(|t| beyond(best, *t) >= 0.0) {
+                let fill = if beyond(bar.open, target) > 0.0 { bar.open } else { target };
+                close(open, i, fill, "takeProfit", &mut cash, &mut trades);
+            } else {
+                position = Some(open);
+            }
+        }
+
+        if i + 1 < bars.len() {
+            if position.is_some() {
+                pending_exit = strategy.exit.iter().any(|c| c.holds(&values, i));
+            } else {
+                pending_entry = strategy.entry.iter().all(|c| c.holds(&values, i));
+            }
+        } else if let Some(open) = position.take() {
+            close(open, i, bar.close, "end", &mut cash, &mut trades);
+        }
+
+        let marked = match &position {
+            Some(open) => cash + direction * (bar.close - open.entry_price) * open.quantity - open.entry_fee,
+            None => cash,
+        };
+        equity.push(EquityPoint { time: bar.time, equity: marked });
+    }
+    (trades, equity)
+}
+
+fn score(strategy: &StrategySpec, bars: &[Bar], trades: &[Trade], equity: &[EquityPoint]) -> BacktestStats {
+    let capital = strategy.initial_capital;
+    let final_equity = equity.last().map(|p| p.equity).unwrap_or(capital);
+    let wins = trades.iter().filter(|t| t.pnl > 0.0).count();
+    let gross_profit: f64 = trades.iter().filter(|t| t.pnl > 0.0).map(|t| t.pnl).sum();
+    let gross_loss: f64 = trades.iter().filter(|t| t.pnl < 0.0).map(|t| -t.pnl).sum();
+    let mut peak = capital;
+    let mut max_drawdown = 0.0_f64;
+    for point in equity {
+        peak = peak.max(point.equity);
+        if peak > 0.0 {
+            max_drawdown = max_drawdown.max((peak - point.equity) / peak * 100.0);
+        }
+    }
+    let held: usize = trades.iter().map(|t| t.bars_held.max(1)).sum();
+    let first_open = bars.first().map(|b| b.open).unwrap_or(0.0);
+    let last_close = bars.last().map(|b| b.close).unwrap_or(0.0);
+    BacktestStats {
+        trades: trades.len(),
+        wins,
+        losses: trades.len() - wins,
+        win_rate: if trades.is_empty() { 0.0 } else { wins as f64 / trades.len() as f64 * 100.0 },
+        net_pnl: final_equity - capital,
+        total_return_pct: (final_equity / capital - 1.0) * 100.0,
+        buy_and_hold_pct: if first_open > 0.0 { (last_close / first_open - 1.0) * 100.0 } else { 0.0 },
+        max_drawdown_pct: max_drawdown,
+        profit_factor: (gross_loss > 0.0).then(|| gross_profit / gross_loss),
+        avg_trade_pct: if trades.is_empty() { 0.0 } else { trades.iter().map(|t| t.return_pct).sum::<f64>() / trades.len() as f64 },
+        exposure_pct: if bars.is_empty() { 0.0 } else { (held as f64 / bars.len() as f64 * 100.0).min(100.0) },
+        final_equity,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum BacktestError {
+    InvalidStrategy(String),
+    InvalidRange(String),
+    NoData(String),
+    Data(String),                      // Price history couldn't be loaded
+    Storage(String),
+}
+
+impl std::fmt::Display for BacktestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BacktestError::InvalidStrategy(msg) => write!(f, "Invalid strategy: {}", msg),
+            BacktestError::InvalidRange(msg) => write!(f, "Invalid range: {}", msg),
+            BacktestError::NoData(symbol) => write!(f, "Not enough price history for {} in this range", symbol),
+            BacktestError::Data(e) => write!(f, "{}", e),
+            BacktestError::Storage(e) => write!(f, "Storage error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for BacktestError {}
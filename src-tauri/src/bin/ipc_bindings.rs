@@ -0,0 +1,44 @@
+// Regenerates src/lib/bindings.ts from the Rust command and event types.
+// With --check it only compares, exiting non-zero when the file is missing or out of date (for CI).
+
+use std::path::Path;
+use std::process::ExitCode;
+
+use omnibrowser_tauri::bindings;
+
+fn main() -> ExitCode {
+    let check = std::env::args().skip(1).any(|arg| arg == "--check");
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(bindings::OUTPUT_PATH);
+
+    let generated = match bindings::generate() {
+        Ok(generated) => generated,
+        Err(e) => {
+            eprintln!("ipc-bindings: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let current = std::fs::read_to_string(&path).ok();
+
+    if check {
+        if current.as_deref() == Some(generated.as_str()) {
+            println!("ipc-bindings: {} is up to date", path.display());
+            return ExitCode::SUCCESS;
+        }
+        eprintln!(
+            "ipc-bindings: {} is out of date; run `cargo run --features bindings --bin ipc-bindings` in src-tauri",
+            path.display()
+        );
+        return ExitCode::FAILURE;
+    }
+
+    if current.as_deref() == Some(generated.as_str()) {
+        println!("ipc-bindings: {} unchanged", path.display());
+        return ExitCode::SUCCESS;
+    }
+    if let Err(e) = std::fs::write(&path, generated) {
+        eprintln!("ipc-bindings: Failed to write {}: {}", path.display(), e);
+        return ExitCode::FAILURE;
+    }
+    println!("ipc-bindings: Wrote {}", path.display());
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,156 @@
+// IPC Bindings - TypeScript types for command arguments, results and event payloads (feature "bindings")
+// Command signatures come from the build.rs registry the command palette uses, so every command in the
+// invoke handler is covered; typed event payloads are listed in `events` below.
+// `cargo run --features bindings --bin ipc-bindings` rewrites src/lib/bindings.ts; `-- --check` fails if it is stale.
+
+use std::collections::BTreeMap;
+
+use ts_rs::{TypeVisitor, TS};
+
+use crate::services::{binaries::CapabilityReport, global_shortcut_service::ShortcutTriggered};
+
+// Relative to src-tauri
+pub const OUTPUT_PATH: &str = "../src/lib/bindings.ts";
+
+const HEADER: &str = "// @generated by `cargo run --features bindings --bin ipc-bindings` in src-tauri; do not edit\n";
+
+#[derive(Default)]
+pub struct Bindings {
+    decls: BTreeMap<String, String>, // TS name -> `export type ...`
+    conflicts: Vec<String>,
+    commands: Vec<String>,
+    events: Vec<String>,
+}
+
+impl Bindings {
+    pub fn command(&mut self, id: &str, params: Vec<(String, String)>, result: String) {
+        let args: Vec<String> = params.into_iter().map(|(name, ty)| format!("{}: {}", name, ty)).collect();
+        let args = if args.is_empty() { "{}".to_string() } else { format!("{{ {} }}", args.join("; ")) };
+        self.commands.push(format!("  {:?}: {{ args: {}; result: {} }};", id, args, result));
+    }
+
+    pub fn event<T: TS + 'static + ?Sized>(&mut self, name: &str) {
+        let payload = ts::<T>(self);
+        self.events.push(format!("  {:?}: {};", name, payload));
+    }
+
+    fn render(self) -> Result<String, BindingsError> {
+        if !self.conflicts.is_empty() {
+            return Err(BindingsError::NameConflict(self.conflicts));
+        }
+        let mut out = String::from(HEADER);
+        for decl in self.decls.values() {
+            out.push('\n');
+            out.push_str(decl);
+            out.push('\n');
+        }
+        out.push_str("\nexport type Commands = {\n");
+        out.push_str(&self.commands.join("\n"));
+        out.push_str("\n};\n\nexport type Events = {\n");
+        out.push_str(&self.events.join("\n"));
+        out.push_str("\n};\n");
+        // ts-rs maps u64/i64 to bigint, but serde_json sends them as plain JSON numbers
+        Ok(out.replace("bigint", "number"))
+    }
+}
+
+impl TypeVisitor for Bindings {
+    // Declares T (once) if it is one of ours, then everything it refers to
+    fn visit<T: TS + 'static + ?Sized>(&mut self) {
+        if T::output_path().is_none() {
+            return;
+        }
+        let name = T::ident();
+        let mut decl = T::docs().unwrap_or_default();
+        decl.push_str("export ");
+        decl.push_str(&T::decl());
+        // Two Rust types behind one name only matter if they declare different shapes
+        if let Some(existing) = self.decls.get(&name) {
+            if *existing != decl {
+                self.conflicts.push(format!("{} ({})", name, std::any::type_name::<T>()));
+            }
+            return;
+        }
+        self.decls.insert(name, decl);
+        T::visit_dependencies(self);
+    }
+}
+
+// TS name of T, declaring T and its dependencies; used by the generated command_bindings
+pub fn ts<T: TS + 'static + ?Sized>(out: &mut Bindings) -> String {
+    out.visit::<T>();
+    T::visit_dependencies(out);
+    T::visit_generics(out);
+    T::name()
+}
+
+// Option parameters may be left out by the caller
+pub fn param<T: TS + 'static + ?Sized>(out: &mut Bindings, name: &str, optional: bool) -> (String, String) {
+    let key = if optional { format!("{}?", name) } else { name.to_string() };
+    (key, ts::<T>(out))
+}
+
+// The whole bindings.ts
+pub fn generate() -> Result<String, BindingsError> {
+    let mut out = Bindings::default();
+    crate::commands::command_bindings(&mut out);
+    events(&mut out);
+    out.render()
+}
+
+// Events with a typed payload; the streaming channels (ai:stream, agent:event, ...) carry ad-hoc JSON
+fn events(out: &mut Bindings) {
+    out.event::<Vec<crate::approvals::ProposedAction>>("agent-actions-proposed");
+    out.event::<crate::archive_files::ExtractProgress>("archive:progress");
+    out.event::<crate::clipboard::ClipboardSuggestion>("clipboard-suggestion");
+    out.event::<crate::config::AppConfig>("config:changed");
+    out.event::<String>("config:error");
+    out.event::<crate::digest::Digest>("digest:ready");
+    out.event::<crate::verify::DownloadVerification>("downloads:verified");
+    out.event::<Vec<crate::feeds::RefreshResult>>("feeds:updated");
+    out.event::<Vec<String>>("filter-rules:changed");
+    out.event::<crate::focus::FocusBlocked>("focus:blocked");
+    out.event::<crate::focus::FocusRecord>("focus:ended");
+    out.event::<crate::focus::FocusProgress>("focus:progress");
+    out.event::<crate::modes::ActiveProfile>("mode-profile-changed");
+    out.event::<crate::warmup::WarmupStatus>("models:warmup");
+    out.event::<crate::notifications::NotificationResponse>("notification:action");
+    out.event::<crate::notifications::Notification>("notification:posted");
+    out.event::<crate::connectivity::ConnectivityStatus>("offline-changed");
+    out.event::<crate::omnibox::RemoteSuggestions>("omnibox:remote");
+    out.event::<crate::onboarding::StepState>("onboarding:progress");
+    out.event::<()>("passwords-locked");
+    out.event::<String>("privacy-mode-changed");
+    out.event::<String>("profile:switched");
+    out.event::<crate::resources::ResourceAction>("resource:action");
+    out.event::<crate::selection::SelectionJob>("selection-action:done");
+    out.event::<CapabilityReport>("services:capabilities");
+    out.event::<ShortcutTriggered>("shortcut-action");
+    out.event::<crate::tab_discard::DiscardRequest>("tab-discard-request");
+    out.event::<crate::tab_discard::TabDiscarded>("tab-discarded");
+    out.event::<crate::browser::NavigationState>("tab-history-changed");
+    out.event::<crate::stability::TabRecovered>("tab-recovered");
+    out.event::<crate::tab_discard::TabRestored>("tab-restored");
+    out.event::<crate::stability::TabUnresponsive>("tab-unresponsive");
+    out.event::<crate::tray::TrayAction>("tray-action");
+    out.event::<crate::stability::WatchdogPing>("watchdog:ping");
+}
+
+#[derive(Debug, Clone)]
+pub enum BindingsError {
+    NameConflict(Vec<String>),
+}
+
+impl std::fmt::Display for BindingsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BindingsError::NameConflict(names) => write!(
+                f,
+                "Types share a TypeScript name (add #[ts(rename = \"...\")]): {}",
+                names.join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BindingsError {}
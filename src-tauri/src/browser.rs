@@ -6,6 +6,7 @@ use std::sync::{Arc, Mutex};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct Tab {
     pub id: String,
@@ -23,15 +24,138 @@ pub struct Tab {
     pub is_sleeping: bool,             // Tab is frozen/unloaded
     pub privacy_mode: String,          // Serialized as string for IPC
     pub app_mode: String,              // Serialized as string for IPC
-    #[serde(skip_serializing)]
+    #[serde(skip_serializing, default)]
     pub crash_count: u32,              // For safe mode detection (internal only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_id: Option<String>,
+    #[serde(default)]
+    pub history: TabHistory,           // Back/forward stack, persisted with the session
 }
 
+// Oldest entries fall off past this depth
+pub const MAX_HISTORY_DEPTH: usize = 50;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS), ts(rename = "NavigationEntry"))]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub url: String,
+    #[serde(default)]
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct TabHistory {
+    pub entries: Vec<HistoryEntry>,
+    pub index: usize,                  // Current entry
+}
+
+impl TabHistory {
+    fn starting_at(url: &str) -> Self {
+        Self { entries: vec![HistoryEntry { url: url.to_string(), title: String::new() }], index: 0 }
+    }
+
+    // Drops forward entries, like a browser does on a new navigation
+    fn push(&mut self, url: &str) {
+        self.entries.truncate(self.index + 1);
+        self.entries.push(HistoryEntry { url: url.to_string(), title: String::new() });
+        if self.entries.len() > MAX_HISTORY_DEPTH {
+            let excess = self.entries.len() - MAX_HISTORY_DEPTH;
+            self.entries.drain(..excess);
+        }
+        self.index = self.entries.len() - 1;
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        self.index > 0
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        self.index + 1 < self.entries.len()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct NavigationState {
+    pub tab_id: String,
+    pub url: String,
+    pub index: usize,
+    pub length: usize,
+    pub can_go_back: bool,
+    pub can_go_forward: bool,
+}
+
+// Chrome-style palette so groups look the same in every window
+pub const GROUP_COLORS: &[&str] = &["grey", "blue", "red", "yellow", "green", "pink", "purple", "cyan", "orange"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct TabGroup {
+    pub id: String,
+    pub name: String,
+    pub color: String,
+    #[serde(default)]
+    pub collapsed: bool,
+    pub created_at: i64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub closed_tabs: Vec<Tab>,         // Tabs of a closed group, kept for restore
+}
+
+impl TabGroup {
+    pub fn is_closed(&self) -> bool {
+        !self.closed_tabs.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct TabGroupInfo {
+    pub id: String,
+    pub name: String,
+    pub color: String,
+    pub collapsed: bool,
+    pub closed: bool,
+    pub created_at: i64,
+    pub tab_ids: Vec<String>,          // Open tabs, or the closed tabs' ids while closed
+}
+
+
+// Window label split views default to (the app has one main window today)
+pub const MAIN_WINDOW: &str = "main";
+// Either pane keeps at least this share of the window
+pub const MIN_SPLIT_RATIO: f64 = 0.15;
+pub const MAX_SPLIT_RATIO: f64 = 0.85;
+
+// Two panes side by side in one window, each showing a tab
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SplitView {
+    pub window: String,
+    pub left_tab_id: String,
+    pub right_tab_id: String,
+    pub ratio: f64,                    // Left pane's share of the width
+    pub created_at: i64,
+}
+
+impl SplitView {
+    pub fn contains(&self, tab_id: &str) -> bool {
+        self.left_tab_id == tab_id || self.right_tab_id == tab_id
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct TabManager {
     tabs: Arc<Mutex<HashMap<String, Tab>>>,
     active_tab_id: Arc<Mutex<Option<String>>>,
+    groups: Arc<Mutex<HashMap<String, TabGroup>>>,
+    layouts: Arc<Mutex<HashMap<String, SplitView>>>,  // Keyed by window label; no entry means a single pane
     max_crash_count: u32,              // Threshold for safe mode
 }
 
@@ -40,6 +164,8 @@ impl TabManager {
         Self {
             tabs: Arc::new(Mutex::new(HashMap::new())),
             active_tab_id: Arc::new(Mutex::new(None)),
+            groups: Arc::new(Mutex::new(HashMap::new())),
+            layouts: Arc::new(Mutex::new(HashMap::new())),
             max_crash_count,
         }
     }
@@ -51,6 +177,7 @@ impl TabManager {
         privacy_mode: crate::state::PrivacyMode,
         app_mode: crate::state::AppMode,
     ) -> Result<String, String> {
+        crate::focus::check_navigation(&url)?;
         let id = format!("tab-{}", uuid::Uuid::new_v4());
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -86,6 +213,8 @@ impl TabManager {
             privacy_mode: privacy_str.to_string(),
             app_mode: mode_str.to_string(),
             crash_count: 0,
+            group_id: None,
+            history: TabHistory::starting_at(&url),
         };
 
         let mut tabs = self.tabs.lock().unwrap();
@@ -114,26 +243,48 @@ impl TabManager {
             tab.is_active
         };
 
-        tabs.remove(id);
+        let removed = tabs.remove(id);
 
         // If deleted tab was active, activate another tab
         if was_active {
-            if let Some(first_tab) = tabs.values().next() {
-                let first_id = first_tab.id.clone();
-                *self.active_tab_id.lock().unwrap() = Some(first_id.clone());
-                if let Some(tab) = tabs.get_mut(&first_id) {
-                    tab.is_active = true;
-                }
-            } else {
-                *self.active_tab_id.lock().unwrap() = None;
+            self.activate_any(&mut tabs);
+        }
+
+        // A group disappears with its last tab (groups lock is always taken before tabs)
+        let emptied = removed
+            .and_then(|t| t.group_id)
+            .filter(|g| !tabs.values().any(|t| t.group_id.as_deref() == Some(g)));
+        drop(tabs);
+        if let Some(group_id) = emptied {
+            let mut groups = self.groups.lock().unwrap();
+            if groups.get(&group_id).map(|g| !g.is_closed()).unwrap_or(false) {
+                groups.remove(&group_id);
             }
         }
+        // Closing either pane's tab collapses its window back to one pane
+        self.layouts.lock().unwrap().retain(|_, split| !split.contains(id));
 
         Ok(())
     }
 
+    // Activate some remaining tab (after the active one went away)
+    fn activate_any(&self, tabs: &mut HashMap<String, Tab>) {
+        if let Some(first_tab) = tabs.values().next() {
+            let first_id = first_tab.id.clone();
+            *self.active_tab_id.lock().unwrap() = Some(first_id.clone());
+            if let Some(tab) = tabs.get_mut(&first_id) {
+                tab.is_active = true;
+            }
+        } else {
+            *self.active_tab_id.lock().unwrap() = None;
+        }
+    }
+
     // Update tab (URL, title, etc.)
     pub fn update_tab(&self, id: &str, updates: TabUpdate) -> Result<(), String> {
+        if let Some(url) = &updates.url {
+            crate::focus::check_navigation(url)?;
+        }
         let mut tabs = self.tabs.lock().unwrap();
         
         let tab = tabs.get_mut(id).ok_or_else(|| format!("Tab {} not found", id))?;
@@ -142,6 +293,9 @@ impl TabManager {
             tab.url = url;
         }
         if let Some(title) = updates.title {
+            if let Some(entry) = tab.history.entries.get_mut(tab.history.index) {
+                entry.title = title.clone();
+            }
             tab.title = title;
         }
         if let Some(favicon) = updates.favicon {
@@ -222,6 +376,67 @@ impl TabManager {
         }
     }
 
+    // Load a new page in the tab, recording it in its back/forward history
+    pub fn navigate(&self, id: &str, url: &str) -> Result<NavigationState, String> {
+        crate::focus::check_navigation(url)?;
+        let mut tabs = self.tabs.lock().unwrap();
+        let tab = tabs.get_mut(id).ok_or_else(|| format!("Tab {} not found", id))?;
+        Self::ensure_history(tab);
+        // Reloading the current page isn't a new entry
+        if tab.history.entries[tab.history.index].url != url {
+            tab.history.push(url);
+        }
+        tab.url = url.to_string();
+        tab.is_sleeping = false;
+        Ok(Self::navigation_state(tab))
+    }
+
+    // Step through history (-1 back, +1 forward)
+    pub fn go(&self, id: &str, delta: isize) -> Result<NavigationState, String> {
+        let mut tabs = self.tabs.lock().unwrap();
+        let tab = tabs.get_mut(id).ok_or_else(|| format!("Tab {} not found", id))?;
+        Self::ensure_history(tab);
+        let target = tab.history.index as isize + delta;
+        if target < 0 || target >= tab.history.entries.len() as isize {
+            return Err(if delta < 0 { "Nothing to go back to".to_string() } else { "Nothing to go forward to".to_string() });
+        }
+        let entry = tab.history.entries[target as usize].clone();
+        crate::focus::check_navigation(&entry.url)?;
+        tab.history.index = target as usize;
+        tab.url = entry.url;
+        if !entry.title.is_empty() {
+            tab.title = entry.title;
+        }
+        tab.is_sleeping = false;
+        Ok(Self::navigation_state(tab))
+    }
+
+    pub fn navigation(&self, id: &str) -> Result<NavigationState, String> {
+        let mut tabs = self.tabs.lock().unwrap();
+        let tab = tabs.get_mut(id).ok_or_else(|| format!("Tab {} not found", id))?;
+        Self::ensure_history(tab);
+        Ok(Self::navigation_state(tab))
+    }
+
+    // Tabs from sessions saved before history start with their current page
+    fn ensure_history(tab: &mut Tab) {
+        if tab.history.entries.is_empty() {
+            tab.history = TabHistory::starting_at(&tab.url);
+        }
+        tab.history.index = tab.history.index.min(tab.history.entries.len() - 1);
+    }
+
+    fn navigation_state(tab: &Tab) -> NavigationState {
+        NavigationState {
+            tab_id: tab.id.clone(),
+            url: tab.url.clone(),
+            index: tab.history.index,
+            length: tab.history.entries.len(),
+            can_go_back: tab.history.can_go_back(),
+            can_go_forward: tab.history.can_go_forward(),
+        }
+    }
+
     // Freeze/unload a tab (for memory management)
     pub fn freeze_tab(&self, id: &str) -> Result<(), String> {
         let mut tabs = self.tabs.lock().unwrap();
@@ -264,15 +479,322 @@ impl TabManager {
         Ok(())
     }
 
-    // Save session to database
-    pub fn save_session(&self, db: &crate::db::Database) -> Result<(), String> {
+    // ============================================================================
+    // TAB GROUPS
+    // ============================================================================
+
+    pub fn create_group(&self, name: &str, color: &str) -> Result<TabGroup, String> {
+        let color = normalize_color(color)?;
+        let group = TabGroup {
+            id: format!("group-{}", uuid::Uuid::new_v4()),
+            name: name.trim().to_string(),
+            color,
+            collapsed: false,
+            created_at: chrono::Utc::now().timestamp(),
+            closed_tabs: Vec::new(),
+        };
+        self.groups.lock().unwrap().insert(group.id.clone(), group.clone());
+        Ok(group)
+    }
+
+    pub fn update_group(
+        &self,
+        group_id: &str,
+        name: Option<String>,
+        color: Option<String>,
+        collapsed: Option<bool>,
+    ) -> Result<TabGroup, String> {
+        let mut groups = self.groups.lock().unwrap();
+        let group = groups.get_mut(group_id).ok_or_else(|| format!("Tab group {} not found", group_id))?;
+        if let Some(color) = color {
+            group.color = normalize_color(&color)?;
+        }
+        if let Some(name) = name {
+            group.name = name.trim().to_string();
+        }
+        if let Some(collapsed) = collapsed {
+            group.collapsed = collapsed;
+        }
+        Ok(group.clone())
+    }
+
+    // Move a tab into a group, or out of any group with None
+    pub fn assign_group(&self, tab_id: &str, group_id: Option<&str>) -> Result<(), String> {
+        let groups = self.groups.lock().unwrap();
+        if let Some(group_id) = group_id {
+            let group = groups.get(group_id).ok_or_else(|| format!("Tab group {} not found", group_id))?;
+            if group.is_closed() {
+                return Err(format!("Tab group {} is closed; restore it first", group_id));
+            }
+        }
+        let mut tabs = self.tabs.lock().unwrap();
+        let tab = tabs.get_mut(tab_id).ok_or_else(|| format!("Tab {} not found", tab_id))?;
+        tab.group_id = group_id.map(|g| g.to_string());
+        Ok(())
+    }
+
+    pub fn list_groups(&self) -> Vec<TabGroupInfo> {
+        let groups = self.groups.lock().unwrap();
+        let tabs = self.tabs.lock().unwrap();
+        let mut list: Vec<TabGroupInfo> = groups
+            .values()
+            .map(|group| {
+                let tab_ids = if group.is_closed() {
+                    group.closed_tabs.iter().map(|t| t.id.clone()).collect()
+                } else {
+                    let mut members: Vec<&Tab> = tabs
+                        .values()
+                        .filter(|t| t.group_id.as_deref() == Some(&group.id))
+                        .collect();
+                    members.sort_by_key(|t| t.created_at);
+                    members.into_iter().map(|t| t.id.clone()).collect()
+                };
+                TabGroupInfo {
+                    id: group.id.clone(),
+                    name: group.name.clone(),
+                    color: group.color.clone(),
+                    collapsed: group.collapsed,
+                    closed: group.is_closed(),
+                    created_at: group.created_at,
+                    tab_ids,
+                }
+            })
+            .collect();
+        list.sort_by_key(|g| g.created_at);
+        list
+    }
+
+    // Sleep every tab in the group except the active one; returns how many were put to sleep
+    pub fn sleep_group(&self, group_id: &str) -> Result<usize, String> {
+        self.ensure_group(group_id)?;
+        let mut tabs = self.tabs.lock().unwrap();
+        let mut slept = 0;
+        for tab in tabs.values_mut() {
+            if tab.group_id.as_deref() == Some(group_id) && !tab.is_active && !tab.is_sleeping {
+                tab.is_sleeping = true;
+                slept += 1;
+            }
+        }
+        Ok(slept)
+    }
+
+    // Close the group's tabs but keep them in the group so it can be restored
+    pub fn close_group(&self, group_id: &str) -> Result<usize, String> {
+        let mut groups = self.groups.lock().unwrap();
+        let group = groups.get_mut(group_id).ok_or_else(|| format!("Tab group {} not found", group_id))?;
+        let mut tabs = self.tabs.lock().unwrap();
+        let ids: Vec<String> = tabs
+            .values()
+            .filter(|t| t.group_id.as_deref() == Some(group_id))
+            .map(|t| t.id.clone())
+            .collect();
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        let mut closed: Vec<Tab> = ids.iter().filter_map(|id| tabs.remove(id)).collect();
+        closed.sort_by_key(|t| t.created_at);
+        let was_active = closed.iter().any(|t| t.is_active);
+        for tab in closed.iter_mut() {
+            tab.is_active = false;
+        }
+        let count = closed.len();
+        group.closed_tabs = closed;
+        if was_active {
+            self.activate_any(&mut tabs);
+        }
+        self.prune_layouts(&tabs);
+        Ok(count)
+    }
+
+    // Reopen a closed group's tabs (asleep, so they load on demand); returns their ids
+    pub fn restore_group(&self, group_id: &str) -> Result<Vec<String>, String> {
+        let mut groups = self.groups.lock().unwrap();
+        let group = groups.get_mut(group_id).ok_or_else(|| format!("Tab group {} not found", group_id))?;
+        let restored = std::mem::take(&mut group.closed_tabs);
+        let mut tabs = self.tabs.lock().unwrap();
+        let mut ids = Vec::with_capacity(restored.len());
+        for mut tab in restored {
+            tab.is_sleeping = true;
+            tab.group_id = Some(group_id.to_string());
+            ids.push(tab.id.clone());
+            tabs.insert(tab.id.clone(), tab);
+        }
+        if self.active_tab_id.lock().unwrap().is_none() {
+            self.activate_any(&mut tabs);
+        }
+        Ok(ids)
+    }
+
+    // Remove a group; its tabs are closed with it or left ungrouped
+    pub fn delete_group(&self, group_id: &str, close_tabs: bool) -> Result<(), String> {
+        self.groups
+            .lock()
+            .unwrap()
+            .remove(group_id)
+            .ok_or_else(|| format!("Tab group {} not found", group_id))?;
+        let mut tabs = self.tabs.lock().unwrap();
+        if close_tabs {
+            let before = tabs.len();
+            tabs.retain(|_, t| t.group_id.as_deref() != Some(group_id));
+            let active_gone = self
+                .active_tab_id
+                .lock()
+                .unwrap()
+                .as_ref()
+                .map(|id| !tabs.contains_key(id))
+                .unwrap_or(false);
+            if before != tabs.len() && active_gone {
+                self.activate_any(&mut tabs);
+            }
+            self.prune_layouts(&tabs);
+        } else {
+            for tab in tabs.values_mut().filter(|t| t.group_id.as_deref() == Some(group_id)) {
+                tab.group_id = None;
+            }
+        }
+        Ok(())
+    }
+
+    fn ensure_group(&self, group_id: &str) -> Result<(), String> {
+        if self.groups.lock().unwrap().contains_key(group_id) {
+            Ok(())
+        } else {
+            Err(format!("Tab group {} not found", group_id))
+        }
+    }
+
+    pub fn groups_snapshot(&self) -> Vec<TabGroup> {
+        self.groups.lock().unwrap().values().cloned().collect()
+    }
+
+    // Replace all groups; tabs pointing at groups that no longer exist are ungrouped
+    pub fn replace_groups(&self, groups: Vec<TabGroup>) {
+        let mut map = self.groups.lock().unwrap();
+        map.clear();
+        for group in groups {
+            map.insert(group.id.clone(), group);
+        }
+        for tab in self.tabs.lock().unwrap().values_mut() {
+            if tab.group_id.as_ref().map(|g| !map.contains_key(g)).unwrap_or(false) {
+                tab.group_id = None;
+            }
+        }
+    }
+
+    // Current tab set and active tab id
+    pub fn snapshot(&self) -> (Option<String>, Vec<Tab>) {
         let tabs = self.list_tabs();
         let active_id = self.active_tab_id.lock().unwrap().clone();
+        (active_id, tabs)
+    }
+
+    // Replace all tabs (used by session restore and checkpoints)
+    pub fn replace_tabs(&self, active_id: Option<String>, tabs: Vec<Tab>) {
+        let groups = self.groups.lock().unwrap();
+        let mut tabs_map = self.tabs.lock().unwrap();
+        tabs_map.clear();
+
+        for mut tab in tabs {
+            tab.is_active = false;
+            // Checkpoints may predate a group that was deleted since
+            if tab.group_id.as_ref().map(|g| !groups.contains_key(g)).unwrap_or(false) {
+                tab.group_id = None;
+            }
+            tabs_map.insert(tab.id.clone(), tab);
+        }
+
+        let active_id = active_id.filter(|id| tabs_map.contains_key(id));
+        if let Some(id) = &active_id {
+            // Activate the restored active tab
+            if let Some(tab) = tabs_map.get_mut(id) {
+                tab.is_active = true;
+            }
+        }
+        *self.active_tab_id.lock().unwrap() = active_id;
+        self.prune_layouts(&tabs_map);
+    }
+
+    // ============================================================================
+    // SPLIT VIEW
+    // ============================================================================
+
+    // Show two tabs side by side in a window, replacing its current split
+    pub fn set_split(&self, window: &str, left_tab_id: &str, right_tab_id: &str, ratio: f64) -> Result<SplitView, String> {
+        if left_tab_id == right_tab_id {
+            return Err("A split view needs two different tabs".to_string());
+        }
+        if !ratio.is_finite() || !(MIN_SPLIT_RATIO..=MAX_SPLIT_RATIO).contains(&ratio) {
+            return Err(format!("Split ratio must be between {} and {}", MIN_SPLIT_RATIO, MAX_SPLIT_RATIO));
+        }
+        {
+            let tabs = self.tabs.lock().unwrap();
+            for id in [left_tab_id, right_tab_id] {
+                if !tabs.contains_key(id) {
+                    return Err(format!("Tab {} not found", id));
+                }
+            }
+        }
+        let split = SplitView {
+            window: window.to_string(),
+            left_tab_id: left_tab_id.to_string(),
+            right_tab_id: right_tab_id.to_string(),
+            ratio,
+            created_at: chrono::Utc::now().timestamp(),
+        };
+        let mut layouts = self.layouts.lock().unwrap();
+        // A tab is shown in one pane at a time
+        layouts.retain(|w, s| w == window || !(s.contains(left_tab_id) || s.contains(right_tab_id)));
+        layouts.insert(window.to_string(), split.clone());
+        Ok(split)
+    }
+
+    // Back to a single pane; returns whether the window was split
+    pub fn reset_layout(&self, window: &str) -> bool {
+        self.layouts.lock().unwrap().remove(window).is_some()
+    }
+
+    pub fn layout(&self, window: &str) -> Option<SplitView> {
+        self.layouts.lock().unwrap().get(window).cloned()
+    }
+
+    // Drop splits showing a tab that is gone (caller holds the tabs lock)
+    fn prune_layouts(&self, tabs: &HashMap<String, Tab>) {
+        self.layouts
+            .lock()
+            .unwrap()
+            .retain(|_, split| tabs.contains_key(&split.left_tab_id) && tabs.contains_key(&split.right_tab_id));
+    }
+
+    pub fn layouts_snapshot(&self) -> Vec<SplitView> {
+        self.layouts.lock().unwrap().values().cloned().collect()
+    }
+
+    // Replace all split views; ones whose tabs no longer exist are dropped
+    pub fn replace_layouts(&self, layouts: Vec<SplitView>) {
+        let tabs = self.tabs.lock().unwrap();
+        {
+            let mut map = self.layouts.lock().unwrap();
+            map.clear();
+            for split in layouts {
+                map.insert(split.window.clone(), split);
+            }
+        }
+        self.prune_layouts(&tabs);
+    }
+
+    // Save session to database
+    pub fn save_session(&self, db: &crate::db::Database) -> Result<(), String> {
+        let (active_id, tabs) = self.snapshot();
         
         let tabs_json = serde_json::to_string(&tabs)
             .map_err(|e| format!("Failed to serialize tabs: {}", e))?;
+        let groups_json = serde_json::to_string(&self.groups_snapshot())
+            .map_err(|e| format!("Failed to serialize tab groups: {}", e))?;
+        let layout_json = serde_json::to_string(&self.layouts_snapshot())
+            .map_err(|e| format!("Failed to serialize split views: {}", e))?;
         
-        db.save_session(active_id.as_deref(), &tabs_json)
+        db.save_session(active_id.as_deref(), &tabs_json, Some(&groups_json), Some(&layout_json))
             .map_err(|e| format!("Failed to save session: {}", e))?;
         
         Ok(())
@@ -283,30 +805,40 @@ impl TabManager {
         let session = db.load_session()
             .map_err(|e| format!("Failed to load session: {}", e))?;
         
-        if let Some((active_id, tabs_json)) = session {
+        if let Some((active_id, tabs_json, groups_json)) = session {
             let tabs: Vec<Tab> = serde_json::from_str(&tabs_json)
                 .map_err(|e| format!("Failed to deserialize tabs: {}", e))?;
-            
-            let mut tabs_map = self.tabs.lock().unwrap();
-            tabs_map.clear();
-            
-            for tab in tabs {
-                tabs_map.insert(tab.id.clone(), tab);
-            }
-            
-            if let Some(id) = active_id.clone() {
-                *self.active_tab_id.lock().unwrap() = Some(id.clone());
-                // Activate the restored active tab
-                if let Some(tab) = tabs_map.get_mut(&id) {
-                    tab.is_active = true;
-                }
-            }
+            // Sessions saved before tab groups have no groups column
+            let groups: Vec<TabGroup> = match groups_json {
+                Some(json) => serde_json::from_str(&json)
+                    .map_err(|e| format!("Failed to deserialize tab groups: {}", e))?,
+                None => Vec::new(),
+            };
+            self.replace_groups(groups);
+            self.replace_tabs(active_id, tabs);
+            // Sessions saved before split views have no layout column
+            let layouts: Vec<SplitView> = match db.load_session_layout()
+                .map_err(|e| format!("Failed to load split views: {}", e))? {
+                Some(json) => serde_json::from_str(&json)
+                    .map_err(|e| format!("Failed to deserialize split views: {}", e))?,
+                None => Vec::new(),
+            };
+            self.replace_layouts(layouts);
         }
         
         Ok(())
     }
 }
 
+fn normalize_color(color: &str) -> Result<String, String> {
+    let color = color.trim().to_lowercase();
+    if GROUP_COLORS.contains(&color.as_str()) {
+        Ok(color)
+    } else {
+        Err(format!("Unknown group color '{}' (expected one of {})", color, GROUP_COLORS.join(", ")))
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct TabUpdate {
     pub url: Option<String>,
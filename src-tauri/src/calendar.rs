@@ -0,0 +1,880 @@
+// Calendar - Local event store for the assistant: .ics import, recurring events, and spoken event creation
+// Everything lives in SQLite so schedule questions are answered offline; times are Unix seconds, shown in local time
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::db::Database;
+
+const MAX_ICS_BYTES: u64 = 20 * 1024 * 1024;
+pub const MAX_UPCOMING_DAYS: u32 = 366;
+// Recurrence expansion stops here even if the rule never ends
+const MAX_OCCURRENCES: usize = 5_000;
+const DEFAULT_EVENT_SECS: i64 = 3600;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarEvent {
+    pub id: String,
+    pub uid: String,                   // iCalendar UID; importing the same file again updates instead of duplicating
+    pub title: String,
+    pub starts_at: i64,                // Unix seconds; for upcoming() results, this occurrence's start
+    pub ends_at: i64,
+    pub all_day: bool,
+    pub location: Option<String>,
+    pub description: Option<String>,
+    pub rrule: Option<String>,         // RFC 5545 RRULE value (FREQ=WEEKLY;BYDAY=MO,...)
+    pub exdates: Vec<i64>,             // Starts of cancelled occurrences
+    pub source: String,                // "ics", "manual", or "voice"
+    pub created_at: i64,
+}
+
+// An event to create; end defaults to an hour after start (or the whole day)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct NewEvent {
+    pub title: String,
+    pub starts_at: i64,
+    pub ends_at: Option<i64>,
+    #[serde(default)]
+    pub all_day: bool,
+    pub location: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub added: usize,
+    pub updated: usize,
+    pub skipped: usize,                // Cancelled, or missing a start
+}
+
+pub fn add(db: &Database, event: NewEvent, source: &str) -> Result<CalendarEvent, CalendarError> {
+    let title = event.title.trim();
+    if title.is_empty() {
+        return Err(CalendarError::Invalid("title is empty".to_string()));
+    }
+    let ends_at = event.ends_at.unwrap_or(event.starts_at + if event.all_day { 86_400 } else { DEFAULT_EVENT_SECS });
+    if ends_at < event.starts_at {
+        return Err(CalendarError::Invalid("the event ends before it starts".to_string()));
+    }
+    let id = uuid::Uuid::new_v4().to_string();
+    let event = CalendarEvent {
+        uid: format!("{}@regen", id),
+        id,
+        title: title.to_string(),
+        starts_at: event.starts_at,
+        ends_at,
+        all_day: event.all_day,
+        location: event.location.filter(|l| !l.trim().is_empty()),
+        description: event.description.filter(|d| !d.trim().is_empty()),
+        rrule: None,
+        exdates: Vec::new(),
+        source: source.to_string(),
+        created_at: chrono::Utc::now().timestamp(),
+    };
+    db.calendar_event_save(&event).map_err(|e| CalendarError::Storage(e.to_string()))?;
+    Ok(event)
+}
+
+pub fn import_ics(db: &Database, path: &std::path::Path) -> Result<ImportReport, CalendarError> {
+    let size = std::fs::metadata(path).map_err(|e| CalendarError::Io(e.to_string()))?.len();
+    if size > MAX_ICS_BYTES {
+        return Err(CalendarError::Invalid(format!("file is larger than {} MB", MAX_ICS_BYTES / (1024 * 1024))));
+    }
+    let text = std::fs::read_to_string(path).map_err(|e| CalendarError::Io(e.to_string()))?;
+    let (events, skipped) = parse_ics(&text)?;
+    let mut report = ImportReport { skipped, ..Default::default() };
+    for event in events {
+        match db.calendar_event_save(&event).map_err(|e| CalendarError::Storage(e.to_string()))? {
+            true => report.added += 1,
+            false => report.updated += 1,
+        }
+    }
+    tracing::info!(target: "db", "Calendar: Imported {} (added {}, updated {}, skipped {})", path.display(), report.added, report.updated, report.skipped);
+    Ok(report)
+}
+
+// Occurrences overlapping [from, to), recurring events expanded, soonest first
+pub fn between(db: &Database, from: i64, to: i64) -> Result<Vec<CalendarEvent>, CalendarError> {
+    let events = db.calendar_events_between(from, to).map_err(|e| CalendarError::Storage(e.to_string()))?;
+    let mut occurrences: Vec<CalendarEvent> = events
+        .into_iter()
+        .flat_map(|event| {
+            occurrences(&event, from, to)
+                .into_iter()
+                .map(|(starts_at, ends_at)| CalendarEvent { starts_at, ends_at, ..event.clone() })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    occurrences.sort_by(|a, b| a.starts_at.cmp(&b.starts_at).then_with(|| a.title.cmp(&b.title)));
+    Ok(occurrences)
+}
+
+// From now until midnight `days` days ahead (1 is the rest of today)
+pub fn upcoming(db: &Database, days: u32) -> Result<Vec<CalendarEvent>, CalendarError> {
+    let now = Local::now();
+    let to = local_midnight(now.date_naive() + Duration::days(days.clamp(1, MAX_UPCOMING_DAYS) as i64));
+    between(db, now.timestamp(), to)
+}
+
+// ============================================================================
+// ICS PARSING
+// ============================================================================
+
+// VEVENTs as events, plus how many were skipped (cancelled or without DTSTART)
+pub fn parse_ics(text: &str) -> Result<(Vec<CalendarEvent>, usize), CalendarError> {
+    if !text.contains("BEGIN:VCALENDAR") {
+        return Err(CalendarError::Invalid("not an iCalendar file".to_string()));
+    }
+    // Unfold: a line starting with a space or tab continues the previous one
+    let mut lines: Vec<String> = Vec::new();
+    for raw in text.split('\n') {
+        let raw = raw.trim_end_matches('\r');
+        match (raw.strip_prefix(' ').or_else(|| raw.strip_prefix('\t')), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(raw.to_string()),
+        }
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let mut events: Vec<CalendarEvent> = Vec::new();
+    let mut overridden: Vec<(String, i64)> = Vec::new();
+    let mut skipped = 0;
+    let mut stack: Vec<String> = Vec::new();
+    let mut props: Vec<(String, Vec<(String, String)>, String)> = Vec::new();
+
+    for line in &lines {
+        let Some((name, params, value)) = parse_property(line) else {
+            continue;
+        };
+        match name.as_str() {
+            "BEGIN" => {
+                stack.push(value.to_uppercase());
+                if stack.last().map(String::as_str) == Some("VEVENT") {
+                    props.clear();
+                }
+            }
+            "END" => {
+                let ended = stack.pop();
+                if ended.as_deref() == Some("VEVENT") {
+                    match build_event(&props, now) {
+                        Some((event, recurrence_of)) => {
+                            if let Some(original) = recurrence_of {
+                                overridden.push(original);
+                            }
+                            events.push(event);
+                        }
+                        None => skipped += 1,
+                    }
+                }
+            }
+            // Only the event's own properties; alarms nested in it have their own DESCRIPTION etc.
+            _ if stack.last().map(String::as_str) == Some("VEVENT") => props.push((name, params, value)),
+            _ => {}
+        }
+    }
+
+    // A modified occurrence replaces the one its series would have produced
+    for (uid, start) in overridden {
+        if let Some(series) = events.iter_mut().find(|e| e.uid == uid && e.rrule.is_some()) {
+            series.exdates.push(start);
+        }
+    }
+    Ok((events, skipped))
+}
+
+// NAME;PARAM=VALUE;...:value, with colons allowed inside quoted parameter values
+fn parse_property(line: &str) -> Option<(String, Vec<(String, String)>, String)> {
+    let mut in_quotes = false;
+    let split = line.char_indices().find(|(_, c)| {
+        if *c == '"' {
+            in_quotes = !in_quotes;
+        }
+        *c == ':' && !in_quotes
+    })?;
+    let (head, value) = (&line[..split.0], &line[split.0 + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next()?.trim().to_uppercase();
+    let params = parts
+        .filter_map(|p| p.split_once('='))
+        .map(|(k, v)| (k.trim().to_uppercase(), v.trim_matches('"').to_string()))
+        .collect();
+    Some((name, params, value.to_string()))
+}
+
+// The event, and for a modified occurrence (RECURRENCE-ID) the series UID and original start it replaces
+fn build_event(props: &[(String, Vec<(String, String)>, String)], now: i64) -> Option<(CalendarEvent, Option<(String, i64)>)> {
+    let prop = |name: &str| props.iter().find(|(n, _, _)| n == name);
+    let text = |name: &str| prop(name).map(|(_, _, v)| unescape(v)).filter(|v| !v.trim().is_empty());
+
+    if text("STATUS").is_some_and(|s| s.eq_ignore_ascii_case("CANCELLED")) {
+        return None;
+    }
+    let (_, start_params, start_value) = prop("DTSTART")?;
+    let (starts_at, all_day) = parse_time(start_value, start_params)?;
+    let ends_at = match (prop("DTEND"), prop("DURATION")) {
+        (Some((_, params, value)), _) => parse_time(value, params).map(|(t, _)| t),
+        (None, Some((_, _, value))) => parse_duration(value).map(|d| starts_at + d),
+        (None, None) => None,
+    }
+    .filter(|end| *end >= starts_at)
+    .unwrap_or(if all_day { starts_at + 86_400 } else { starts_at });
+
+    let exdates = props
+        .iter()
+        .filter(|(n, _, _)| n == "EXDATE")
+        .flat_map(|(_, params, value)| value.split(',').filter_map(|v| parse_time(v, params).map(|(t, _)| t)).collect::<Vec<_>>())
+        .collect();
+    let id = uuid::Uuid::new_v4().to_string();
+    let series_uid = text("UID").unwrap_or_else(|| format!("{}@regen", id));
+    let recurrence = prop("RECURRENCE-ID").and_then(|(_, params, value)| parse_time(value, params));
+    // Overrides share the series UID; give them their own so they're stored beside it
+    let uid = match recurrence {
+        Some((original, _)) => format!("{}#{}", series_uid, original),
+        None => series_uid.clone(),
+    };
+
+    let event = CalendarEvent {
+        id,
+        uid,
+        title: text("SUMMARY").unwrap_or_else(|| "(No title)".to_string()),
+        starts_at,
+        ends_at,
+        all_day,
+        location: text("LOCATION"),
+        description: text("DESCRIPTION"),
+        rrule: if recurrence.is_some() { None } else { text("RRULE") },
+        exdates,
+        source: "ics".to_string(),
+        created_at: now,
+    };
+    Some((event, recurrence.map(|(original, _)| (series_uid, original))))
+}
+
+// DATE (local midnight), UTC (…Z), TZID-qualified, or floating (local) times; the flag is true for DATE
+fn parse_time(value: &str, params: &[(String, String)]) -> Option<(i64, bool)> {
+    let value = value.trim();
+    let param = |name: &str| params.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str());
+    if param("VALUE") == Some("DATE") || (value.len() == 8 && value.chars().all(|c| c.is_ascii_digit())) {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        return Some((local_midnight(date), true));
+    }
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some((naive.and_utc().timestamp(), false));
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    // Windows-style zone names ("Pacific Standard Time") aren't in the tz database; read those as local
+    let zoned = param("TZID")
+        .and_then(|tz| tz.trim_start_matches('/').parse::<chrono_tz::Tz>().ok())
+        .and_then(|tz| tz.from_local_datetime(&naive).earliest().map(|t| t.timestamp()));
+    Some((zoned.or_else(|| local_timestamp(naive))?, false))
+}
+
+// RFC 5545 durations: P1W, P1D, PT1H30M, -PT15M
+fn parse_duration(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let (sign, rest) = match value.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, value.trim_start_matches('+')),
+    };
+    let rest = rest.strip_prefix('P')?;
+    let mut total = 0i64;
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in rest.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' => in_time = true,
+            unit => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                total += n * match (unit, in_time) {
+                    ('W', false) => 7 * 86_400,
+                    ('D', false) => 86_400,
+                    ('H', true) => 3600,
+                    ('M', true) => 60,
+                    ('S', true) => 1,
+                    _ => return None,
+                };
+            }
+        }
+    }
+    Some(sign * total)
+}
+
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out.trim().to_string()
+}
+
+// ============================================================================
+// RECURRENCE
+// ============================================================================
+
+// (start, end) of each occurrence overlapping [from, to). Supports FREQ DAILY/WEEKLY/MONTHLY/YEARLY with
+// INTERVAL, COUNT, UNTIL, and BYDAY on weekly rules; other rules yield just the first occurrence.
+fn occurrences(event: &CalendarEvent, from: i64, to: i64) -> Vec<(i64, i64)> {
+    let length = event.ends_at - event.starts_at;
+    let overlaps = |start: i64| start < to && start + length.max(1) > from;
+    let Some(rule) = event.rrule.as_deref().and_then(Rule::parse) else {
+        return if overlaps(event.starts_at) { vec![(event.starts_at, event.ends_at)] } else { Vec::new() };
+    };
+    let Some(first) = Local.timestamp_opt(event.starts_at, 0).earliest().map(|t| t.naive_local()) else {
+        return Vec::new();
+    };
+
+    // Without COUNT, start near `from` instead of walking a years-old series from its first date
+    let period_secs = rule.interval
+        * match rule.freq {
+            Freq::Daily => 86_400,
+            Freq::Weekly => 7 * 86_400,
+            Freq::Monthly => 28 * 86_400,
+            Freq::Yearly => 365 * 86_400,
+        };
+    let first_period = match rule.count {
+        Some(_) => 0,
+        None => ((from - event.starts_at - length) / period_secs - 1).max(0),
+    };
+
+    let mut found = Vec::new();
+    let mut produced = 0;
+    // Candidates in order; wall-clock time is kept across DST changes. Monthly rules on the 31st and
+    // yearly ones on Feb 29 produce nothing in some periods, so the loop is bounded by periods, not results.
+    'periods: for period in first_period..first_period + MAX_OCCURRENCES as i64 {
+        let candidates: Vec<NaiveDateTime> = match rule.freq {
+            Freq::Daily => vec![first + Duration::days(period * rule.interval)],
+            Freq::Weekly if rule.by_day.is_empty() => vec![first + Duration::weeks(period * rule.interval)],
+            Freq::Weekly => {
+                let week_start = first.date() - Duration::days(first.weekday().num_days_from_monday() as i64)
+                    + Duration::weeks(period * rule.interval);
+                let mut days: Vec<NaiveDateTime> = rule
+                    .by_day
+                    .iter()
+                    .map(|day| (week_start + Duration::days(day.num_days_from_monday() as i64)).and_time(first.time()))
+                    .filter(|candidate| *candidate >= first)
+                    .collect();
+                days.sort();
+                days
+            }
+            Freq::Monthly => {
+                let months = first.month0() as i64 + period * rule.interval;
+                let year = first.year() + (months / 12) as i32;
+                NaiveDate::from_ymd_opt(year, (months % 12) as u32 + 1, first.day())
+                    .map(|d| vec![d.and_time(first.time())])
+                    .unwrap_or_default()
+            }
+            Freq::Yearly => NaiveDate::from_ymd_opt(first.year() + (period * rule.interval) as i32, first.month(), first.day())
+                .map(|d| vec![d.and_time(first.time())])
+                .unwrap_or_default(),
+        };
+        for candidate in candidates {
+            let Some(start) = local_timestamp(candidate) else {
+                continue;
+            };
+            if start >= to || rule.until.is_some_and(|until| start > until) || rule.count.is_some_and(|count| produced >= count) {
+                break 'periods;
+            }
+            produced += 1;
+            if !event.exdates.contains(&start) && overlaps(start) {
+                found.push((start, start + length));
+            }
+        }
+    }
+    found
+}
+
+enum Freq {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+struct Rule {
+    freq: Freq,
+    interval: i64,
+    count: Option<usize>,
+    until: Option<i64>,
+    by_day: Vec<Weekday>,
+}
+
+impl Rule {
+    fn parse(rrule: &str) -> Option<Self> {
+        let mut rule = Rule { freq: Freq::Daily, interval: 1, count: None, until: None, by_day: Vec::new() };
+        let mut freq = None;
+        for part in rrule.split(';') {
+            let (key, value) = part.split_once('=')?;
+            match key.to_uppercase().as_str() {
+                "FREQ" => {
+                    freq = Some(match value.to_uppercase().as_str() {
+                        "DAILY" => Freq::Daily,
+                        "WEEKLY" => Freq::Weekly,
+                        "MONTHLY" => Freq::Monthly,
+                        "YEARLY" => Freq::Yearly,
+                        _ => return None,
+                    })
+                }
+                "INTERVAL" => rule.interval = value.parse::<i64>().ok().filter(|n| *n > 0)?,
+                "COUNT" => rule.count = value.parse().ok(),
+                "UNTIL" => rule.until = parse_time(value, &[]).map(|(t, all_day)| if all_day { t + 86_399 } else { t }),
+                // Ordinals (1MO, -1FR) only mean something on monthly rules, which ignore BYDAY
+                "BYDAY" => {
+                    rule.by_day = value
+                        .split(',')
+                        .filter_map(|day| match day.trim_start_matches(|c: char| c.is_ascii_digit() || c == '-' || c == '+') {
+                            "MO" => Some(Weekday::Mon),
+                            "TU" => Some(Weekday::Tue),
+                            "WE" => Some(Weekday::Wed),
+                            "TH" => Some(Weekday::Thu),
+                            "FR" => Some(Weekday::Fri),
+                            "SA" => Some(Weekday::Sat),
+                            "SU" => Some(Weekday::Sun),
+                            _ => None,
+                        })
+                        .collect()
+                }
+                _ => {}
+            }
+        }
+        rule.freq = freq?;
+        Some(rule)
+    }
+}
+
+// ============================================================================
+// NATURAL LANGUAGE
+// ============================================================================
+
+// Words that ask to create an event, in English and Hinglish ("kal 3 baje meeting add karo")
+// "schedule" only counts as the first word; elsewhere it is the noun ("what's my schedule")
+const ADD_WORDS: &[&str] = &["add", "create", "book", "put", "set", "daal", "dal", "rakh", "rakho", "likh", "likho"];
+const QUESTION_WORDS: &[&str] = &["what", "what's", "whats", "when", "do", "does", "is", "are", "any", "show", "list", "tell", "how", "kya", "kab", "kaun", "mera", "meri", "mere"];
+// Dropped from a spoken title
+const FILLER_WORDS: &[&str] = &[
+    "a", "an", "the", "to", "my", "in", "on", "at", "for", "calendar", "event", "please", "up", "new", "schedule",
+    "karo", "kar", "kardo", "do", "dena", "dijiye", "ko", "ka", "ki", "ke", "mein", "me", "mai", "par", "pe", "ek", "hai",
+];
+const SCHEDULE_WORDS: &[&str] = &["calendar", "schedule", "agenda", "meeting", "meetings", "event", "events", "appointment", "appointments", "plan", "plans"];
+
+// "Add dentist appointment friday at 5pm", "kal 3 baje meeting add karo"; None without a day or time
+pub fn parse_event(text: &str, now: DateTime<Local>) -> Option<NewEvent> {
+    let mut words = Words::new(text);
+    if !wants_add(text, &words) {
+        return None;
+    }
+    let date = words.take_date(now.date_naive());
+    let time = words.take_time();
+    let length = words.take_length();
+    if date.is_none() && time.is_none() {
+        return None;
+    }
+    words.take_all(ADD_WORDS);
+    words.take_all(FILLER_WORDS);
+    let title = words.rest();
+    let title = if title.is_empty() {
+        "Event".to_string()
+    } else {
+        let mut chars = title.chars();
+        chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default()
+    };
+
+    let (starts_at, all_day) = match time {
+        Some(time) => {
+            // A time alone means its next occurrence
+            let today = now.date_naive();
+            let date = date.unwrap_or(if today.and_time(time) > now.naive_local() { today } else { today + Duration::days(1) });
+            (local_timestamp(date.and_time(time))?, false)
+        }
+        None => (local_midnight(date?), true),
+    };
+    Some(NewEvent {
+        title,
+        starts_at,
+        ends_at: length.filter(|_| !all_day).map(|secs| starts_at + secs),
+        all_day,
+        location: None,
+        description: None,
+    })
+}
+
+// "What's on my calendar tomorrow", "kal ka schedule kya hai": the [from, to) window asked about and a label
+// for it ("today", "tomorrow", "on Friday", "this week"); None when it isn't a schedule question
+pub fn parse_schedule_query(text: &str, now: DateTime<Local>) -> Option<(i64, i64, String)> {
+    let mut words = Words::new(text);
+    if !words.lower.iter().any(|w| SCHEDULE_WORDS.contains(&w.as_str())) || wants_add(text, &words) {
+        return None;
+    }
+    let today = now.date_naive();
+    if words.lower.iter().any(|w| matches!(w.as_str(), "week" | "hafte" | "hafta" | "saptah")) {
+        return Some((now.timestamp(), local_midnight(today + Duration::days(7)), "this week".to_string()));
+    }
+    let date = words.take_date(today).unwrap_or(today);
+    let from = if date == today { now.timestamp() } else { local_midnight(date) };
+    let label = match (date - today).num_days() {
+        0 => "today".to_string(),
+        1 => "tomorrow".to_string(),
+        _ => format!("on {}", date.format("%A, %B %-d")),
+    };
+    Some((from, local_midnight(date + Duration::days(1)), label))
+}
+
+// An instruction to create something, not a question about the schedule
+fn wants_add(text: &str, words: &Words) -> bool {
+    let question = text.trim_end().ends_with('?')
+        || words.lower.first().is_some_and(|w| QUESTION_WORDS.contains(&w.as_str()))
+        || words.lower.iter().any(|w| matches!(w.as_str(), "kya" | "kaun"));
+    let verb = words.lower.first().is_some_and(|w| w == "schedule") || words.lower.iter().any(|w| ADD_WORDS.contains(&w.as_str()));
+    verb && !question
+}
+
+// "Meeting at 3:00 PM", or "Holiday (all day)"
+pub fn describe(event: &CalendarEvent) -> String {
+    if event.all_day {
+        return format!("{} (all day)", event.title);
+    }
+    match Local.timestamp_opt(event.starts_at, 0).earliest() {
+        Some(start) => format!("{} at {}", event.title, start.format("%-I:%M %p")),
+        None => event.title.clone(),
+    }
+}
+
+// "tomorrow at 3:00 PM", "on Friday, October 23", for confirming a created event
+pub fn describe_when(starts_at: i64, all_day: bool, now: DateTime<Local>) -> String {
+    let Some(start) = Local.timestamp_opt(starts_at, 0).earliest() else {
+        return String::new();
+    };
+    let day = match (start.date_naive() - now.date_naive()).num_days() {
+        0 => "today".to_string(),
+        1 => "tomorrow".to_string(),
+        _ => format!("on {}", start.format("%A, %B %-d")),
+    };
+    if all_day {
+        day
+    } else {
+        format!("{} at {}", day, start.format("%-I:%M %p"))
+    }
+}
+
+// Tokens of a sentence with the ones already understood marked off, so the rest can become the title
+struct Words {
+    original: Vec<String>,
+    lower: Vec<String>,
+    used: Vec<bool>,
+}
+
+impl Words {
+    fn new(text: &str) -> Self {
+        let original: Vec<String> = text
+            .split_whitespace()
+            .map(|w| w.trim_matches(|c: char| matches!(c, ',' | '.' | '!' | '?' | '"' | '\'')).to_string())
+            .filter(|w| !w.is_empty())
+            .collect();
+        let lower = original.iter().map(|w| w.to_lowercase()).collect();
+        let used = vec![false; original.len()];
+        Self { original, lower, used }
+    }
+
+    fn find_seq(&self, seq: &[&str]) -> Option<usize> {
+        (0..self.lower.len().saturating_sub(seq.len() - 1))
+            .find(|i| seq.iter().enumerate().all(|(j, w)| !self.used[i + j] && self.lower[i + j] == *w))
+    }
+
+    fn take_all(&mut self, words: &[&str]) {
+        for i in 0..self.lower.len() {
+            if words.contains(&self.lower[i].as_str()) {
+                self.used[i] = true;
+            }
+        }
+    }
+
+    fn rest(&self) -> String {
+        (0..self.original.len()).filter(|i| !self.used[*i]).map(|i| self.original[i].as_str()).collect::<Vec<_>>().join(" ")
+    }
+
+    fn take_date(&mut self, today: NaiveDate) -> Option<NaiveDate> {
+        for (seq, offset) in [
+            (&["day", "after", "tomorrow"][..], 2),
+            (&["parson"][..], 2),
+            (&["parso"][..], 2),
+            (&["tomorrow"][..], 1),
+            // Hindi "kal" is both yesterday and tomorrow; for plans it's tomorrow
+            (&["kal"][..], 1),
+            (&["today"][..], 0),
+            (&["tonight"][..], 0),
+            (&["aaj"][..], 0),
+        ] {
+            if let Some(i) = self.find_seq(seq) {
+                self.used[i..i + seq.len()].iter_mut().for_each(|u| *u = true);
+                return Some(today + Duration::days(offset));
+            }
+        }
+        for i in 0..self.lower.len() {
+            if self.used[i] {
+                continue;
+            }
+            if let Some(weekday) = weekday(&self.lower[i]) {
+                self.used[i] = true;
+                if i > 0 && matches!(self.lower[i - 1].as_str(), "next" | "this" | "agle" | "is") {
+                    self.used[i - 1] = true;
+                }
+                // Said on that weekday, it means next week's
+                let ahead = (weekday.num_days_from_monday() as i64 - today.weekday().num_days_from_monday() as i64).rem_euclid(7);
+                return Some(today + Duration::days(if ahead == 0 { 7 } else { ahead }));
+            }
+            if let Ok(date) = NaiveDate::parse_from_str(&self.lower[i], "%Y-%m-%d") {
+                self.used[i] = true;
+                return Some(date);
+            }
+            // "25 march", "march 25", "25th march"
+            if let Some(month) = month(&self.lower[i]) {
+                let day_at = |j: usize| self.lower.get(j).filter(|_| !self.used[j]).and_then(|w| day_of_month(w)).map(|d| (j, d));
+                let day = day_at(i + 1).or_else(|| i.checked_sub(1).and_then(day_at));
+                if let Some((j, day)) = day {
+                    let this_year = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+                    let date = if this_year < today { NaiveDate::from_ymd_opt(today.year() + 1, month, day)? } else { this_year };
+                    self.used[i] = true;
+                    self.used[j] = true;
+                    return Some(date);
+                }
+            }
+        }
+        None
+    }
+
+    fn take_time(&mut self) -> Option<NaiveTime> {
+        let period = self.take_period();
+        for i in 0..self.lower.len() {
+            if self.used[i] {
+                continue;
+            }
+            let word = self.lower[i].as_str();
+            if let Some((hour, minute)) = spoken_fraction(word, self.lower.get(i + 1).map(String::as_str)) {
+                self.used[i] = true;
+                self.used[i + 1] = true;
+                self.take_next(i + 2, &["baje", "bje", "o'clock", "oclock"]);
+                return to_time(hour, minute, period);
+            }
+            // "3pm", "3:30", "15:30", "3:30pm"
+            let (digits, suffix) = match word.find(|c: char| !c.is_ascii_digit() && c != ':') {
+                Some(at) => word.split_at(at),
+                None => (word, ""),
+            };
+            if digits.is_empty() || !matches!(suffix, "" | "am" | "pm" | "a.m" | "p.m") {
+                continue;
+            }
+            let (hour, minute) = match digits.split_once(':') {
+                Some((h, m)) => (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?),
+                None => (digits.parse::<u32>().ok()?, 0),
+            };
+            let next = self.lower.get(i + 1).map(String::as_str).filter(|_| !self.used.get(i + 1).copied().unwrap_or(true));
+            let meridiem = match suffix {
+                "am" | "a.m" => Some(false),
+                "pm" | "p.m" => Some(true),
+                _ => match next {
+                    Some("am" | "a.m" | "a.m.") => Some(false),
+                    Some("pm" | "p.m" | "p.m.") => Some(true),
+                    _ => None,
+                },
+            };
+            // A bare number is only a time with a clock word around it ("at 3", "3 baje", "3 o'clock")
+            let clock_word = next.is_some_and(|n| matches!(n, "baje" | "bje" | "o'clock" | "oclock"));
+            let after_at = i > 0 && matches!(self.lower[i - 1].as_str(), "at" | "@");
+            if meridiem.is_none() && !clock_word && !after_at && !digits.contains(':') {
+                continue;
+            }
+            self.used[i] = true;
+            if after_at {
+                self.used[i - 1] = true;
+            }
+            if (meridiem.is_some() && suffix.is_empty()) || clock_word {
+                self.used[i + 1] = true;
+            }
+            let period = match meridiem {
+                Some(true) => Some(Period::Pm),
+                Some(false) => Some(Period::Am),
+                None => period,
+            };
+            return to_time(hour, minute, period);
+        }
+        match period {
+            Some(Period::Noon) => NaiveTime::from_hms_opt(12, 0, 0),
+            Some(Period::Midnight) => NaiveTime::from_hms_opt(0, 0, 0),
+            _ => None,
+        }
+    }
+
+    // Morning/evening words that say which half of the day a bare hour is in
+    fn take_period(&mut self) -> Option<Period> {
+        for i in 0..self.lower.len() {
+            let period = match self.lower[i].as_str() {
+                "morning" | "subah" | "savere" | "sawere" => Period::Am,
+                "afternoon" | "dopahar" | "dopehar" | "evening" | "shaam" | "sham" | "night" | "raat" | "tonight" => Period::Pm,
+                "noon" => Period::Noon,
+                "midnight" => Period::Midnight,
+                _ => continue,
+            };
+            self.used[i] = true;
+            self.take_next(i + 1, &["ko", "mein", "me"]);
+            return Some(period);
+        }
+        None
+    }
+
+    // "for 30 minutes", "2 ghante", "for an hour"
+    fn take_length(&mut self) -> Option<i64> {
+        for i in 0..self.lower.len() {
+            let unit = match self.lower[i].as_str() {
+                "minute" | "minutes" | "min" | "mins" | "minat" => 60,
+                "hour" | "hours" | "hr" | "hrs" | "ghanta" | "ghante" | "ghanto" => 3600,
+                _ => continue,
+            };
+            let Some(j) = i.checked_sub(1).filter(|j| !self.used[*j]) else {
+                continue;
+            };
+            let amount = match self.lower[j].as_str() {
+                "a" | "an" | "ek" => 1.0,
+                "half" | "aadha" => 0.5,
+                "dedh" => 1.5,
+                "dhai" => 2.5,
+                n => match n.parse::<f64>() {
+                    Ok(n) => n,
+                    Err(_) => continue,
+                },
+            };
+            self.used[i] = true;
+            self.used[j] = true;
+            if j > 0 && self.lower[j - 1] == "for" {
+                self.used[j - 1] = true;
+            }
+            return Some((amount * unit as f64) as i64);
+        }
+        None
+    }
+
+    fn take_next(&mut self, i: usize, words: &[&str]) {
+        if i < self.lower.len() && !self.used[i] && words.contains(&self.lower[i].as_str()) {
+            self.used[i] = true;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Period {
+    Am,
+    Pm,
+    Noon,
+    Midnight,
+}
+
+// Hinglish clock fractions: "saade 4" (4:30), "sawa 4" (4:15), "paune 4" (3:45), "dedh baje" (1:30), "dhai baje" (2:30)
+fn spoken_fraction(word: &str, next: Option<&str>) -> Option<(u32, u32)> {
+    let next_hour = next.and_then(|n| n.parse::<u32>().ok());
+    match (word, next) {
+        ("dedh", Some("baje" | "bje")) => Some((1, 30)),
+        ("dhai", Some("baje" | "bje")) => Some((2, 30)),
+        ("saade" | "sade" | "saadhe", _) => next_hour.map(|h| (h, 30)),
+        ("sawa" | "sava", _) => next_hour.map(|h| (h, 15)),
+        ("paune" | "pone", _) => next_hour.filter(|h| *h > 0).map(|h| (h - 1, 45)),
+        _ => None,
+    }
+}
+
+// Bare hours without am/pm: 1-7 are afternoon/evening, 8-11 morning, 12 noon
+fn to_time(hour: u32, minute: u32, period: Option<Period>) -> Option<NaiveTime> {
+    if hour > 23 || minute > 59 {
+        return None;
+    }
+    let hour = match (hour, period) {
+        (h, _) if h > 12 => h,
+        (12, Some(Period::Am | Period::Midnight)) => 0,
+        (12, _) => 12,
+        (h, Some(Period::Pm)) => h + 12,
+        (h, Some(Period::Am)) => h,
+        (h @ 1..=7, _) => h + 12,
+        (h, _) => h,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+fn weekday(word: &str) -> Option<Weekday> {
+    Some(match word {
+        "monday" | "mon" | "somvar" | "somwar" => Weekday::Mon,
+        "tuesday" | "tue" | "tues" | "mangalvar" | "mangalwar" => Weekday::Tue,
+        "wednesday" | "wed" | "budhvar" | "budhwar" => Weekday::Wed,
+        "thursday" | "thu" | "thurs" | "guruvar" | "guruwar" | "veervar" => Weekday::Thu,
+        "friday" | "fri" | "shukravar" | "shukrawar" => Weekday::Fri,
+        "saturday" | "sat" | "shanivar" | "shaniwar" => Weekday::Sat,
+        "sunday" | "sun" | "ravivar" | "raviwar" | "itvaar" | "itwar" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+fn month(word: &str) -> Option<u32> {
+    const MONTHS: [&str; 12] = [
+        "january", "february", "march", "april", "may", "june", "july", "august", "september", "october", "november", "december",
+    ];
+    MONTHS
+        .iter()
+        .position(|m| word == *m || (word.len() >= 3 && m.starts_with(word) && word.len() <= 4))
+        .map(|i| i as u32 + 1)
+}
+
+// "25", "25th", "1st"
+fn day_of_month(word: &str) -> Option<u32> {
+    let digits = word.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let suffix = &word[digits.len()..];
+    if !matches!(suffix, "" | "st" | "nd" | "rd" | "th") {
+        return None;
+    }
+    digits.parse::<u32>().ok().filter(|d| (1..=31).contains(d))
+}
+
+fn local_timestamp(naive: NaiveDateTime) -> Option<i64> {
+    // Skipped by a DST jump: use the moment right after it
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .or_else(|| Local.from_local_datetime(&(naive + Duration::hours(1))).earliest())
+        .map(|t| t.timestamp())
+}
+
+fn local_midnight(date: NaiveDate) -> i64 {
+    local_timestamp(date.and_time(NaiveTime::MIN)).unwrap_or_else(|| date.and_time(NaiveTime::MIN).and_utc().timestamp())
+}
+
+#[derive(Debug, Clone)]
+pub enum CalendarError {
+    Invalid(String),
+    Io(String),
+    Storage(String),
+}
+
+impl std::fmt::Display for CalendarError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CalendarError::Invalid(msg) => write!(f, "Invalid calendar data: {}", msg),
+            CalendarError::Io(msg) => write!(f, "Failed to read calendar file: {}", msg),
+            CalendarError::Storage(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for CalendarError {}
@@ -0,0 +1,278 @@
+// Capture - Window and region screenshots saved as PNG, plus OCR through a local tesseract
+// Webviews can't render themselves to an image, so the OS capture tool grabs the window's rectangle on screen
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+use std::time::Instant;
+use tauri::Manager;
+
+use crate::services::binaries;
+
+// Formats tesseract reads
+const OCR_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "tif", "tiff", "bmp", "webp"];
+
+// Pixels relative to the captured window's content area
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct Region {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct Screenshot {
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+    pub tab_id: Option<String>,        // None for a full-window capture
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct OcrResult {
+    pub path: String,
+    pub text: String,
+    pub language: String,              // Tesseract language codes used ("eng", "eng+deu")
+    pub engine: String,
+    pub elapsed_ms: u64,
+}
+
+// Screen rectangle in physical pixels
+#[derive(Debug, Clone, Copy)]
+struct Rect {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+// The tab's own webview window if it has one, else the main window
+pub fn capture(
+    app: &tauri::AppHandle,
+    tab_id: Option<&str>,
+    region: Option<Region>,
+    dir: &Path,
+) -> Result<Screenshot, CaptureError> {
+    let window = tab_id
+        .and_then(|id| app.get_webview_window(id))
+        .or_else(|| app.get_webview_window("main"))
+        .or_else(|| app.webview_windows().into_values().next())
+        .ok_or(CaptureError::NoWindow)?;
+    let position = window.inner_position().map_err(|e| CaptureError::Failed(e.to_string()))?;
+    let size = window.inner_size().map_err(|e| CaptureError::Failed(e.to_string()))?;
+    let scale = window.scale_factor().unwrap_or(1.0);
+
+    let rect = match region {
+        Some(region) => {
+            let inside = region.x >= 0
+                && region.y >= 0
+                && region.width > 0
+                && region.height > 0
+                && region.x as u32 + region.width <= size.width
+                && region.y as u32 + region.height <= size.height;
+            if !inside {
+                return Err(CaptureError::InvalidRegion(format!(
+                    "{}x{}+{}+{} is outside the {}x{} window",
+                    region.width, region.height, region.x, region.y, size.width, size.height
+                )));
+            }
+            Rect { x: position.x + region.x, y: position.y + region.y, width: region.width, height: region.height }
+        }
+        None => Rect { x: position.x, y: position.y, width: size.width, height: size.height },
+    };
+
+    let created_at = chrono::Utc::now();
+    let path = dir.join(format!(
+        "screenshot-{}-{}.png",
+        created_at.format("%Y%m%d-%H%M%S"),
+        &uuid::Uuid::new_v4().to_string()[..8]
+    ));
+    grab(rect, scale, &path)?;
+
+    let (width, height) = png_size(&path).unwrap_or((rect.width, rect.height));
+    Ok(Screenshot {
+        path: path.to_string_lossy().to_string(),
+        width,
+        height,
+        tab_id: tab_id.map(str::to_string),
+        created_at: created_at.timestamp(),
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn grab(rect: Rect, scale: f64, path: &Path) -> Result<(), CaptureError> {
+    // screencapture takes points, not pixels
+    let points = |v: f64| (v / scale).round() as i64;
+    let area = format!(
+        "-R{},{},{},{}",
+        points(rect.x as f64),
+        points(rect.y as f64),
+        points(rect.width as f64),
+        points(rect.height as f64)
+    );
+    run(Command::new("screencapture").args(["-x", "-t", "png", &area]).arg(path), "screencapture", path)
+}
+
+#[cfg(target_os = "linux")]
+fn grab(rect: Rect, scale: f64, path: &Path) -> Result<(), CaptureError> {
+    // Wayland compositors only allow capture through grim (layout coordinates are logical)
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() && which::which("grim").is_ok() {
+        let logical = |v: f64| (v / scale).round() as i64;
+        let geometry = format!(
+            "{},{} {}x{}",
+            logical(rect.x as f64),
+            logical(rect.y as f64),
+            logical(rect.width as f64),
+            logical(rect.height as f64)
+        );
+        return run(Command::new("grim").args(["-g", &geometry]).arg(path), "grim", path);
+    }
+    let geometry = format!("{}x{}+{}+{}", rect.width, rect.height, rect.x, rect.y);
+    if which::which("maim").is_ok() {
+        return run(Command::new("maim").args(["-g", &geometry]).arg(path), "maim", path);
+    }
+    if which::which("import").is_ok() {
+        return run(Command::new("import").args(["-window", "root", "-crop", &geometry]).arg(path), "import", path);
+    }
+    Err(CaptureError::ToolMissing("grim (Wayland), maim, or ImageMagick import".to_string()))
+}
+
+#[cfg(target_os = "windows")]
+fn grab(rect: Rect, _scale: f64, path: &Path) -> Result<(), CaptureError> {
+    let script = format!(
+        "Add-Type -AssemblyName System.Drawing; \
+         $b = New-Object System.Drawing.Bitmap {w},{h}; \
+         $g = [System.Drawing.Graphics]::FromImage($b); \
+         $g.CopyFromScreen({x},{y},0,0,$b.Size); \
+         $b.Save('{path}', [System.Drawing.Imaging.ImageFormat]::Png)",
+        w = rect.width,
+        h = rect.height,
+        x = rect.x,
+        y = rect.y,
+        path = path.to_string_lossy().replace('\'', "''"),
+    );
+    run(Command::new("powershell").args(["-NoProfile", "-NonInteractive", "-Command", &script]), "powershell", path)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn grab(_rect: Rect, _scale: f64, _path: &Path) -> Result<(), CaptureError> {
+    Err(CaptureError::ToolMissing("a screen capture tool for this platform".to_string()))
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux", target_os = "windows"))]
+fn run(command: &mut Command, tool: &str, path: &Path) -> Result<(), CaptureError> {
+    let output = command.output().map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => CaptureError::ToolMissing(tool.to_string()),
+        _ => CaptureError::Failed(format!("{}: {}", tool, e)),
+    })?;
+    if !output.status.success() || !path.is_file() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(CaptureError::Failed(format!("{} exited with {}: {}", tool, output.status, stderr.trim())));
+    }
+    Ok(())
+}
+
+// Width and height from the IHDR chunk
+fn png_size(path: &Path) -> Option<(u32, u32)> {
+    let mut header = [0u8; 24];
+    std::io::Read::read_exact(&mut std::fs::File::open(path).ok()?, &mut header).ok()?;
+    if &header[..8] != b"\x89PNG\r\n\x1a\n" || &header[12..16] != b"IHDR" {
+        return None;
+    }
+    let width = u32::from_be_bytes(header[16..20].try_into().ok()?);
+    let height = u32::from_be_bytes(header[20..24].try_into().ok()?);
+    Some((width, height))
+}
+
+// Text in an image via tesseract (bundled in bin/ or on PATH)
+pub fn ocr(app: &tauri::AppHandle, path: &Path, language: Option<&str>) -> Result<OcrResult, CaptureError> {
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    if !OCR_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(CaptureError::InvalidImage(format!("unsupported format '{}'", extension)));
+    }
+    if !path.is_file() {
+        return Err(CaptureError::InvalidImage(format!("{} not found", path.display())));
+    }
+    let language = language.map(str::trim).filter(|l| !l.is_empty()).unwrap_or("eng");
+    if !language.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '+') {
+        return Err(CaptureError::InvalidImage(format!("invalid OCR language '{}'", language)));
+    }
+
+    let dirs = binaries::search_dirs(app.path().resource_dir().ok().as_deref());
+    let tesseract = binaries::locate("tesseract", &dirs)
+        .filter(|b| b.executable)
+        .ok_or_else(|| CaptureError::ToolMissing("tesseract".to_string()))?;
+
+    let started = Instant::now();
+    let output = Command::new(&tesseract.path)
+        .arg(path)
+        .args(["stdout", "-l", language])
+        .output()
+        .map_err(|e| CaptureError::Ocr(e.to_string()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(CaptureError::Ocr(format!("tesseract exited with {}: {}", output.status, stderr.trim())));
+    }
+
+    Ok(OcrResult {
+        path: path.to_string_lossy().to_string(),
+        text: normalize_ocr_text(&String::from_utf8_lossy(&output.stdout)),
+        language: language.to_string(),
+        engine: "tesseract".to_string(),
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+// Tesseract pads paragraphs with blank lines and ends with a form feed
+fn normalize_ocr_text(raw: &str) -> String {
+    let mut out = String::new();
+    let mut blank = false;
+    for line in raw.replace('\u{c}', "").lines() {
+        let line = line.trim_end();
+        if line.trim().is_empty() {
+            blank = !out.is_empty();
+            continue;
+        }
+        if blank {
+            out.push_str("\n\n");
+        } else if !out.is_empty() {
+            out.push('\n');
+        }
+        blank = false;
+        out.push_str(line);
+    }
+    out
+}
+
+#[derive(Debug, Clone)]
+pub enum CaptureError {
+    NoWindow,
+    InvalidRegion(String),
+    InvalidImage(String),
+    ToolMissing(String),
+    Failed(String),
+    Ocr(String),
+}
+
+impl std::fmt::Display for CaptureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CaptureError::NoWindow => write!(f, "No window to capture"),
+            CaptureError::InvalidRegion(msg) => write!(f, "Invalid capture region: {}", msg),
+            CaptureError::InvalidImage(msg) => write!(f, "Cannot OCR image: {}", msg),
+            CaptureError::ToolMissing(tool) => write!(f, "Not installed: {}", tool),
+            CaptureError::Failed(msg) => write!(f, "Capture failed: {}", msg),
+            CaptureError::Ocr(msg) => write!(f, "OCR failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for CaptureError {}
@@ -0,0 +1,399 @@
+// Chunker - Token-budgeted splitting that keeps markdown/HTML structure intact
+// Code blocks, tables, and HTML <pre>/<table>/<ul>/<ol> stay whole when they fit; when they don't they are
+// split by line or row (fences reopened, table headers repeated). Chunks prefer to start at headings.
+
+use crate::text::estimate_tokens;
+
+// Summaries get half the model's window; the rest is the prompt template and the answer
+const SUMMARY_SHARE: usize = 2;
+// More than this per call gains little and leaves the map step with nothing to parallelize
+const MAX_SUMMARY_TOKENS: usize = 6000;
+const MIN_CHUNK_TOKENS: usize = 64;
+// Retrieval chunks: small enough to embed precisely, well inside the embedding model's window
+const RETRIEVAL_TOKENS: usize = 300;
+const EMBED_MARGIN_TOKENS: usize = 16;
+// Cost of the blank line joining two blocks
+const SEPARATOR_TOKENS: usize = 1;
+
+// Context windows by model family; the longest matching prefix wins
+const CONTEXT_WINDOWS: &[(&str, usize)] = &[
+    ("all-minilm", 256),
+    ("bge-m3", 8192),
+    ("deepseek-r1", 131_072),
+    ("gemma", 8192),
+    ("gemma2", 8192),
+    ("gemma3", 131_072),
+    ("llama2", 4096),
+    ("llama3", 8192),
+    ("llama3.1", 131_072),
+    ("llama3.2", 131_072),
+    ("llama3.3", 131_072),
+    ("llava", 4096),
+    ("mistral", 32_768),
+    ("mistral-nemo", 131_072),
+    ("mixtral", 32_768),
+    ("mxbai-embed-large", 512),
+    ("nomic-embed-text", 8192),
+    ("phi3", 4096),
+    ("phi3.5", 131_072),
+    ("phi4", 16_384),
+    ("qwen2", 32_768),
+    ("qwen2.5", 32_768),
+    ("snowflake-arctic-embed", 512),
+];
+const DEFAULT_CONTEXT: usize = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkOptions {
+    pub max_tokens: usize,
+    pub overlap_tokens: usize,         // Trailing text of each chunk repeated at the start of the next
+}
+
+impl ChunkOptions {
+    // Map-step chunks for summarizing with `model`
+    pub fn summary(model: &str) -> Self {
+        let max_tokens = (context_tokens(model) / SUMMARY_SHARE).clamp(MIN_CHUNK_TOKENS, MAX_SUMMARY_TOKENS);
+        ChunkOptions { max_tokens, overlap_tokens: 0 }
+    }
+
+    // Retrieval chunks for embedding with `model`, overlapping per [chunking] overlapTokens
+    pub fn retrieval(model: &str) -> Self {
+        let window = context_tokens(model).saturating_sub(EMBED_MARGIN_TOKENS);
+        let max_tokens = RETRIEVAL_TOKENS.min(window).max(MIN_CHUNK_TOKENS);
+        let overlap_tokens = crate::config::current().chunking.overlap_tokens.min(max_tokens / 4);
+        ChunkOptions { max_tokens, overlap_tokens }
+    }
+}
+
+// Context window `model` actually runs with: a [chunking] contextTokens override, else the smaller of
+// the family's window and the num_ctx Ollama is run with
+pub fn context_tokens(model: &str) -> usize {
+    let config = crate::config::current().chunking;
+    let model = model.trim().to_lowercase();
+    let base = model.split(':').next().unwrap_or_default();
+    if let Some(tokens) = config.context_tokens.get(&model).or_else(|| config.context_tokens.get(base)) {
+        return *tokens;
+    }
+    let family = if model.contains("128k") {
+        131_072
+    } else if model.contains("-4k") {
+        4096
+    } else {
+        CONTEXT_WINDOWS
+            .iter()
+            .filter(|(prefix, _)| base.starts_with(prefix))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, tokens)| *tokens)
+            .unwrap_or(DEFAULT_CONTEXT)
+    };
+    family.min(config.ollama_context)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Heading,
+    Code,                              // Fenced code or HTML <pre>
+    Table,                             // Markdown pipe table or HTML <table>
+    Html,                              // HTML list or other block kept together
+    Prose,
+}
+
+#[derive(Debug, Clone)]
+struct Block {
+    kind: Kind,
+    text: String,
+    tokens: usize,
+}
+
+impl Block {
+    fn new(kind: Kind, text: String) -> Self {
+        let tokens = estimate_tokens(&text);
+        Block { kind, text, tokens }
+    }
+}
+
+// Split `text` into chunks of at most `options.max_tokens` (estimated), in document order
+pub fn split(text: &str, options: &ChunkOptions) -> Vec<String> {
+    let max_tokens = options.max_tokens.max(1);
+    let overlap_tokens = options.overlap_tokens.min(max_tokens / 2);
+    let blocks: Vec<Block> = parse_blocks(text)
+        .into_iter()
+        .flat_map(|block| if block.tokens > max_tokens { split_block(block, max_tokens) } else { vec![block] })
+        .collect();
+
+    let mut chunks: Vec<String> = Vec::new();
+    let mut current: Vec<Block> = Vec::new();
+    let mut current_tokens = 0;
+    for block in blocks {
+        let full = !current.is_empty() && current_tokens + SEPARATOR_TOKENS + block.tokens > max_tokens;
+        // A heading past the middle of a chunk starts the next one rather than ending up split from its section
+        let section_break = block.kind == Kind::Heading && current_tokens >= max_tokens / 2;
+        if full || section_break {
+            // Don't leave a heading dangling at the end of a chunk
+            let mut carried: Vec<Block> = Vec::new();
+            while current.len() > 1 && current.last().is_some_and(|b| b.kind == Kind::Heading) {
+                carried.insert(0, current.pop().unwrap());
+            }
+            if carried.is_empty() && !section_break && overlap_tokens > 0 {
+                carried = overlap(&current, overlap_tokens.min(max_tokens.saturating_sub(block.tokens + SEPARATOR_TOKENS)));
+            }
+            chunks.push(join(&current));
+            current_tokens = carried.iter().map(|b| b.tokens + SEPARATOR_TOKENS).sum();
+            current = carried;
+        }
+        current_tokens += block.tokens + SEPARATOR_TOKENS;
+        current.push(block);
+    }
+    if !current.is_empty() {
+        chunks.push(join(&current));
+    }
+    chunks
+}
+
+// The first `max_tokens` (estimated) of `text`, cut at a word boundary where there is one
+pub fn truncate_tokens(text: &str, max_tokens: usize) -> String {
+    if estimate_tokens(text) <= max_tokens {
+        return text.to_string();
+    }
+    hard_split(text, max_tokens.max(1)).into_iter().next().unwrap_or_default()
+}
+
+fn join(blocks: &[Block]) -> String {
+    blocks.iter().map(|b| b.text.as_str()).collect::<Vec<_>>().join("\n\n")
+}
+
+// Trailing prose of a chunk that fits `budget`: whole blocks, then sentences of the last one that doesn't fit
+fn overlap(blocks: &[Block], budget: usize) -> Vec<Block> {
+    let mut taken: Vec<Block> = Vec::new();
+    let mut used = 0;
+    for block in blocks.iter().rev() {
+        if block.kind != Kind::Prose {
+            break;
+        }
+        if used + block.tokens + SEPARATOR_TOKENS <= budget {
+            used += block.tokens + SEPARATOR_TOKENS;
+            taken.insert(0, block.clone());
+            continue;
+        }
+        let mut sentences: Vec<String> = Vec::new();
+        for sentence in crate::text::split_sentences(&block.text).into_iter().rev() {
+            let tokens = estimate_tokens(&sentence) + SEPARATOR_TOKENS;
+            if used + tokens > budget {
+                break;
+            }
+            used += tokens;
+            sentences.insert(0, sentence);
+        }
+        if !sentences.is_empty() {
+            taken.insert(0, Block::new(Kind::Prose, sentences.join(" ")));
+        }
+        break;
+    }
+    // Repeating the whole chunk would only duplicate it
+    if taken.len() == blocks.len() && join(&taken) == join(blocks) {
+        return Vec::new();
+    }
+    taken
+}
+
+fn parse_blocks(text: &str) -> Vec<Block> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let flush = |paragraph: &mut Vec<&str>, blocks: &mut Vec<Block>| {
+        if !paragraph.is_empty() {
+            blocks.push(Block::new(Kind::Prose, paragraph.join("\n")));
+            paragraph.clear();
+        }
+    };
+
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim_start();
+        let lower = trimmed.to_lowercase();
+
+        if trimmed.is_empty() {
+            flush(&mut paragraph, &mut blocks);
+            i += 1;
+        } else if let Some(fence) = ["```", "~~~"].into_iter().find(|f| trimmed.starts_with(f)) {
+            flush(&mut paragraph, &mut blocks);
+            let end = (i + 1..lines.len()).find(|&j| lines[j].trim_start().starts_with(fence)).unwrap_or(lines.len() - 1);
+            blocks.push(Block::new(Kind::Code, lines[i..=end].join("\n")));
+            i = end + 1;
+        } else if let Some((tag, kind)) = HTML_BLOCKS.iter().find(|(tag, _)| starts_tag(&lower, tag)) {
+            flush(&mut paragraph, &mut blocks);
+            let close = format!("</{}>", tag);
+            let end = (i..lines.len()).find(|&j| lines[j].to_lowercase().contains(&close)).unwrap_or(lines.len() - 1);
+            blocks.push(Block::new(*kind, lines[i..=end].join("\n")));
+            i = end + 1;
+        } else if trimmed.starts_with('|') {
+            flush(&mut paragraph, &mut blocks);
+            let end = (i..lines.len()).take_while(|&j| lines[j].trim_start().starts_with('|')).last().unwrap_or(i);
+            blocks.push(Block::new(Kind::Table, lines[i..=end].join("\n")));
+            i = end + 1;
+        } else if is_heading(trimmed, &lower) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block::new(Kind::Heading, line.trim_end().to_string()));
+            i += 1;
+        } else {
+            paragraph.push(line.trim_end());
+            i += 1;
+        }
+    }
+    flush(&mut paragraph, &mut blocks);
+    blocks
+}
+
+const HTML_BLOCKS: &[(&str, Kind)] = &[("pre", Kind::Code), ("table", Kind::Table), ("ul", Kind::Html), ("ol", Kind::Html)];
+
+fn starts_tag(lower: &str, tag: &str) -> bool {
+    lower
+        .strip_prefix('<')
+        .and_then(|rest| rest.strip_prefix(tag))
+        .is_some_and(|rest| rest.starts_with(['>', ' ', '\t']) || rest.is_empty())
+}
+
+fn is_heading(trimmed: &str, lower: &str) -> bool {
+    let hashes = trimmed.chars().take_while(|c| *c == '#').count();
+    let markdown = (1..=6).contains(&hashes) && trimmed[hashes..].starts_with(' ');
+    let html = (1..=6).any(|level| starts_tag(lower, &format!("h{}", level)));
+    markdown || html
+}
+
+// A block over the budget, as pieces that each fit
+fn split_block(block: Block, max_tokens: usize) -> Vec<Block> {
+    match block.kind {
+        Kind::Code => split_code(&block.text, max_tokens),
+        Kind::Table => split_table(&block.text, max_tokens),
+        Kind::Heading | Kind::Html => pack_lines(block.kind, "", "", &block.text.lines().collect::<Vec<_>>(), max_tokens),
+        Kind::Prose => {
+            // Lines first (list items), then sentences, then words
+            let sentences: Vec<String> = block
+                .text
+                .lines()
+                .flat_map(|line| {
+                    if estimate_tokens(line) <= max_tokens {
+                        vec![line.to_string()]
+                    } else {
+                        crate::text::split_sentences(line)
+                    }
+                })
+                .flat_map(|piece| if estimate_tokens(&piece) <= max_tokens { vec![piece] } else { hard_split(&piece, max_tokens) })
+                .collect();
+            pack_pieces(&sentences, " ", max_tokens).into_iter().map(|text| Block::new(Kind::Prose, text)).collect()
+        }
+    }
+}
+
+// Fenced code split by line; every piece is reopened and closed with the original fence
+fn split_code(text: &str, max_tokens: usize) -> Vec<Block> {
+    let lines: Vec<&str> = text.lines().collect();
+    let first = lines.first().map(|l| l.trim_start()).unwrap_or_default();
+    let fence = ["```", "~~~"].into_iter().find(|f| first.starts_with(f));
+    match fence {
+        Some(fence) => {
+            let closed = lines.len() > 1 && lines.last().is_some_and(|l| l.trim_start().starts_with(fence));
+            let body = &lines[1..if closed { lines.len() - 1 } else { lines.len() }];
+            pack_lines(Kind::Code, first, fence, body, max_tokens)
+        }
+        // <pre>: plain line packing
+        None => pack_lines(Kind::Code, "", "", &lines, max_tokens),
+    }
+}
+
+// Table split by row; the header (and its separator row) starts every piece
+fn split_table(text: &str, max_tokens: usize) -> Vec<Block> {
+    let lines: Vec<&str> = text.lines().collect();
+    let separator = |l: &str| l.trim().trim_matches('|').split('|').all(|c| c.trim().chars().all(|c| matches!(c, '-' | ':')));
+    let header_rows = if lines.len() > 1 && lines[0].trim_start().starts_with('|') && separator(lines[1]) { 2 } else { 0 };
+    let header = lines[..header_rows].join("\n");
+    // Don't let a huge header leave no room for rows
+    let header = if estimate_tokens(&header) > max_tokens / 2 { String::new() } else { header };
+    pack_lines(Kind::Table, &header, "", &lines[header_rows.min(lines.len())..], max_tokens)
+}
+
+// Lines packed into pieces of at most `max_tokens`, each wrapped in `open` ... `close` when given
+fn pack_lines(kind: Kind, open: &str, close: &str, lines: &[&str], max_tokens: usize) -> Vec<Block> {
+    let frame = estimate_tokens(open) + estimate_tokens(close) + 2;
+    let budget = max_tokens.saturating_sub(frame).max(1);
+    let pieces: Vec<String> = lines
+        .iter()
+        .flat_map(|line| if estimate_tokens(line) <= budget { vec![line.to_string()] } else { hard_split(line, budget) })
+        .collect();
+    pack_pieces(&pieces, "\n", budget)
+        .into_iter()
+        .map(|body| {
+            let text = [open, body.as_str(), close].into_iter().filter(|p| !p.is_empty()).collect::<Vec<_>>().join("\n");
+            Block::new(kind, text)
+        })
+        .collect()
+}
+
+// Consecutive pieces joined by `separator` while they fit
+fn pack_pieces(pieces: &[String], separator: &str, max_tokens: usize) -> Vec<String> {
+    let mut packed: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut current_tokens = 0;
+    for piece in pieces {
+        let tokens = estimate_tokens(piece);
+        if !current.is_empty() && current_tokens + SEPARATOR_TOKENS + tokens > max_tokens {
+            packed.push(std::mem::take(&mut current));
+            current_tokens = 0;
+        }
+        if !current.is_empty() {
+            current.push_str(separator);
+            current_tokens += SEPARATOR_TOKENS;
+        }
+        current.push_str(piece);
+        current_tokens += tokens;
+    }
+    if !current.is_empty() {
+        packed.push(current);
+    }
+    packed
+}
+
+// Last resort for a single sentence or line over the budget: break between words, and inside a word
+// (URLs, base64, unspaced scripts) when even one word doesn't fit
+fn hard_split(text: &str, max_tokens: usize) -> Vec<String> {
+    let mut pieces: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut current_tokens = 0;
+    for word in text.split_inclusive(char::is_whitespace) {
+        let tokens = estimate_tokens(word);
+        if current_tokens + tokens <= max_tokens {
+            current.push_str(word);
+            current_tokens += tokens;
+            continue;
+        }
+        if !current.trim().is_empty() {
+            pieces.push(current.trim().to_string());
+        }
+        current.clear();
+        current_tokens = 0;
+        if tokens <= max_tokens {
+            current.push_str(word);
+            current_tokens = tokens;
+            continue;
+        }
+        // Slices sized from the word's own characters-per-token, shrunk in the rare case one still estimates over
+        let chars: Vec<char> = word.trim().chars().collect();
+        let per_token = (chars.len() / tokens.max(1)).max(1);
+        let mut start = 0;
+        while start < chars.len() {
+            let mut end = (start + max_tokens * per_token).min(chars.len());
+            let mut slice: String = chars[start..end].iter().collect();
+            while end > start + 1 && estimate_tokens(&slice) > max_tokens {
+                end = start + (end - start) * 3 / 4;
+                slice = chars[start..end].iter().collect();
+            }
+            pieces.push(slice);
+            start = end;
+        }
+    }
+    if !current.trim().is_empty() {
+        pieces.push(current.trim().to_string());
+    }
+    pieces
+}
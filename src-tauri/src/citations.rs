@@ -0,0 +1,364 @@
+// Citations - BibTeX / RIS entries for the sources of a research session
+// Each distinct page becomes one entry: authors, date, and site come from the extractor, highlights become notes
+
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::research::ResearchSession;
+
+const CONCURRENT_FETCHES: usize = 4;
+const MAX_SOURCES: usize = 200;
+// Tracking parameters that make the same page look like two sources
+const TRACKING_PARAMS: &[&str] = &["fbclid", "gclid", "mc_cid", "mc_eid", "ref", "ref_src", "igshid"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum CitationFormat {
+    Bibtex,
+    Ris,
+}
+
+impl CitationFormat {
+    pub fn parse(format: &str) -> Result<Self, CitationError> {
+        match format.to_lowercase().as_str() {
+            "bibtex" | "bib" => Ok(CitationFormat::Bibtex),
+            "ris" => Ok(CitationFormat::Ris),
+            other => Err(CitationError::UnsupportedFormat(other.to_string())),
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            CitationFormat::Bibtex => "bib",
+            CitationFormat::Ris => "ris",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct Citation {
+    pub key: String,                   // BibTeX key, e.g. "smith2024climate"
+    pub url: String,
+    pub title: String,
+    pub authors: Vec<String>,          // As written on the page ("Jane Smith", "Reuters")
+    pub published: Option<String>,     // YYYY-MM-DD, or just YYYY
+    pub site_name: Option<String>,
+    pub language: Option<String>,
+    pub accessed: String,              // YYYY-MM-DD
+    pub notes: Vec<String>,            // Highlighted passages
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct CitationExport {
+    pub path: String,
+    pub format: CitationFormat,
+    pub citations: Vec<Citation>,
+    pub duplicates: usize,             // Sources dropped as the same page as an earlier one
+    pub content: String,
+}
+
+// One citation per distinct source, in session order; `fetch` looks up authors/date/site on each page
+pub async fn collect(session: &ResearchSession, fetch: bool) -> (Vec<Citation>, usize) {
+    let accessed = chrono::DateTime::from_timestamp_millis(session.updated_at)
+        .unwrap_or_else(chrono::Utc::now)
+        .format("%Y-%m-%d")
+        .to_string();
+
+    let mut citations: Vec<Citation> = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut duplicates = 0;
+    for (url, title) in sources(session) {
+        let Some(normalized) = normalize_url(&url) else {
+            continue;
+        };
+        if seen.contains_key(&normalized) {
+            duplicates += 1;
+            continue;
+        }
+        seen.insert(normalized, citations.len());
+        citations.push(Citation {
+            key: String::new(),
+            url,
+            title,
+            authors: Vec::new(),
+            published: None,
+            site_name: None,
+            language: None,
+            accessed: accessed.clone(),
+            notes: Vec::new(),
+        });
+        if citations.len() == MAX_SOURCES {
+            break;
+        }
+    }
+    for highlight in &session.highlights {
+        if let Some(&i) = normalize_url(&highlight.url).and_then(|n| seen.get(&n)) {
+            citations[i].notes.push(highlight.text.trim().to_string());
+        }
+    }
+
+    if fetch {
+        let pages: Vec<_> = stream::iter(citations.iter().map(|c| c.url.clone()).enumerate())
+            .map(|(i, url)| async move { (i, crate::extractor::extract_url(&url).await) })
+            .buffer_unordered(CONCURRENT_FETCHES)
+            .collect()
+            .await;
+        for (i, page) in pages {
+            match page {
+                Ok(page) => enrich(&mut citations[i], &page),
+                Err(e) => tracing::debug!(target: "app", "Citations: No metadata for {}: {}", citations[i].url, e),
+            }
+        }
+    }
+
+    // Redirected, mobile, and print URLs of one page share its title once metadata is in
+    let mut titles: HashMap<(String, String), usize> = HashMap::new();
+    let mut distinct: Vec<Citation> = Vec::with_capacity(citations.len());
+    for citation in citations {
+        let title = (citation.title.to_lowercase(), host(&citation.url));
+        match titles.get(&title) {
+            Some(&i) if !citation.title.is_empty() && citation.title != citation.url => {
+                distinct[i].notes.extend(citation.notes);
+                duplicates += 1;
+            }
+            _ => {
+                titles.insert(title, distinct.len());
+                distinct.push(citation);
+            }
+        }
+    }
+    let mut citations = distinct;
+
+    let mut keys = HashSet::new();
+    for citation in &mut citations {
+        citation.key = unique_key(&base_key(citation), &mut keys);
+    }
+    (citations, duplicates)
+}
+
+pub fn render(citations: &[Citation], format: CitationFormat) -> String {
+    match format {
+        CitationFormat::Bibtex => citations.iter().map(to_bibtex).collect::<Vec<_>>().join("\n"),
+        CitationFormat::Ris => citations.iter().map(to_ris).collect(),
+    }
+}
+
+// (url, title) for every tab, summary, highlight, note, and metadata source
+fn sources(session: &ResearchSession) -> Vec<(String, String)> {
+    let titled = session.tabs.iter().map(|t| (t.url.clone(), t.title.clone()));
+    let untitled = session
+        .summaries
+        .iter()
+        .map(|s| s.url.clone())
+        .chain(session.highlights.iter().map(|h| h.url.clone()))
+        .chain(session.notes.iter().filter_map(|n| n.url.clone()))
+        .chain(session.metadata.sources.iter().cloned())
+        .map(|url| (url.clone(), url));
+    titled.chain(untitled).filter(|(url, _)| !url.trim().is_empty()).collect()
+}
+
+// Same page regardless of scheme, "www.", fragment, trailing slash, or tracking parameters; None for non-web URLs
+fn normalize_url(url: &str) -> Option<String> {
+    let mut parsed = url::Url::parse(url.trim()).ok()?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return None;
+    }
+    parsed.set_fragment(None);
+    let query: Vec<(String, String)> = parsed
+        .query_pairs()
+        .filter(|(k, _)| !k.starts_with("utm_") && !TRACKING_PARAMS.contains(&k.as_ref()))
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    let query = (!query.is_empty()).then(|| url::form_urlencoded::Serializer::new(String::new()).extend_pairs(query).finish());
+    let host = parsed.host_str()?.trim_start_matches("www.").to_lowercase();
+    let path = parsed.path().trim_end_matches('/');
+    Some(match query {
+        Some(query) => format!("{}{}?{}", host, path, query),
+        None => format!("{}{}", host, path),
+    })
+}
+
+fn host(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.trim_start_matches("www.").to_string()))
+        .unwrap_or_default()
+}
+
+fn enrich(citation: &mut Citation, page: &crate::extractor::ExtractedPage) {
+    if !page.title.trim().is_empty() {
+        citation.title = page.title.trim().to_string();
+    }
+    citation.authors = page.byline.as_deref().map(split_authors).unwrap_or_default();
+    citation.published = page.published_at.as_deref().and_then(parse_date);
+    citation.site_name = page.site_name.clone().filter(|s| !s.trim().is_empty());
+    citation.language = page.language.clone().filter(|l| !l.trim().is_empty());
+}
+
+// "By Jane Smith and Raj Patel" -> ["Jane Smith", "Raj Patel"]; profile URLs are not names
+fn split_authors(byline: &str) -> Vec<String> {
+    let byline = byline.trim();
+    let byline = ["By ", "by ", "BY "].iter().find_map(|p| byline.strip_prefix(p)).unwrap_or(byline);
+    byline
+        .split([',', ';', '&', '|'])
+        .flat_map(|part| part.split(" and "))
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty() && !name.contains("://") && name.chars().count() <= 80)
+        .collect()
+}
+
+// ISO 8601 timestamps and dates; anything else keeps only a leading year
+fn parse_date(text: &str) -> Option<String> {
+    let text = text.trim();
+    if let Some(date) = text.get(..10).and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok()) {
+        return Some(date.format("%Y-%m-%d").to_string());
+    }
+    let year = text.get(..4).filter(|y| y.chars().all(|c| c.is_ascii_digit()))?;
+    (1000..=2999).contains(&year.parse::<u32>().ok()?).then(|| year.to_string())
+}
+
+// First author's surname (or the site) + year + first significant title word
+fn base_key(citation: &Citation) -> String {
+    let ascii = |text: &str| text.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_lowercase();
+    let who = citation
+        .authors
+        .first()
+        .and_then(|a| a.split_whitespace().last())
+        .map(ascii)
+        .filter(|w| !w.is_empty())
+        .unwrap_or_else(|| ascii(host(&citation.url).split('.').next().unwrap_or_default()));
+    let year = citation.published.as_deref().map(|d| &d[..4]).unwrap_or_default();
+    let word = citation
+        .title
+        .split_whitespace()
+        .map(ascii)
+        .find(|w| w.len() > 3 && !matches!(w.as_str(), "with" | "from" | "that" | "this" | "what" | "your"))
+        .unwrap_or_default();
+    let key = format!("{}{}{}", who, year, word);
+    if key.is_empty() {
+        "source".to_string()
+    } else {
+        key
+    }
+}
+
+// smith2024climate, smith2024climatea, ..., then numbered
+fn unique_key(base: &str, keys: &mut HashSet<String>) -> String {
+    let key = std::iter::once(base.to_string())
+        .chain(('a'..='z').map(|suffix| format!("{}{}", base, suffix)))
+        .chain((2..).map(|n| format!("{}{}", base, n)))
+        .find(|key| !keys.contains(key))
+        .unwrap_or_default();
+    keys.insert(key.clone());
+    key
+}
+
+// A person's name as "Last, First"; names of organisations (one word, or many) are braced so BibTeX keeps them whole
+fn bibtex_author(name: &str) -> String {
+    let words: Vec<&str> = name.split_whitespace().collect();
+    if (2..=4).contains(&words.len()) && words.iter().all(|w| w.chars().next().is_some_and(char::is_uppercase)) {
+        let (last, first) = words.split_last().unwrap();
+        format!("{}, {}", bibtex_escape(last), bibtex_escape(&first.join(" ")))
+    } else {
+        format!("{{{}}}", bibtex_escape(name))
+    }
+}
+
+fn bibtex_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\textbackslash{}"),
+            '{' | '}' | '&' | '%' | '$' | '#' | '_' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '~' => out.push_str("\\textasciitilde{}"),
+            '^' => out.push_str("\\textasciicircum{}"),
+            '\n' | '\r' => out.push(' '),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+// @misc with biblatex's url/urldate, which natbib styles read too
+fn to_bibtex(citation: &Citation) -> String {
+    let mut fields = vec![("title", format!("{{{}}}", bibtex_escape(&citation.title)))];
+    if !citation.authors.is_empty() {
+        fields.push(("author", citation.authors.iter().map(|a| bibtex_author(a)).collect::<Vec<_>>().join(" and ")));
+    }
+    if let Some(published) = &citation.published {
+        fields.push(("year", published[..4].to_string()));
+        if published.len() == 10 {
+            fields.push(("date", published.clone()));
+        }
+    }
+    if let Some(site) = &citation.site_name {
+        fields.push(("howpublished", bibtex_escape(site)));
+    }
+    // url is verbatim in biblatex and hyperref; only braces would break the entry
+    fields.push(("url", citation.url.replace(['{', '}'], "")));
+    fields.push(("urldate", citation.accessed.clone()));
+    if let Some(language) = &citation.language {
+        fields.push(("language", bibtex_escape(language)));
+    }
+    if !citation.notes.is_empty() {
+        let quotes: Vec<String> = citation.notes.iter().map(|n| format!("``{}''", bibtex_escape(n))).collect();
+        fields.push(("annote", quotes.join("; ")));
+    }
+    let body: Vec<String> = fields.iter().map(|(name, value)| format!("  {} = {{{}}}", name, value)).collect();
+    format!("@misc{{{},\n{}\n}}\n", citation.key, body.join(",\n"))
+}
+
+fn to_ris(citation: &Citation) -> String {
+    let line = |tag: &str, value: &str| format!("{}  - {}\r\n", tag, value.replace(['\r', '\n'], " ").trim());
+    let mut entry = line("TY", "ELEC");
+    entry.push_str(&line("ID", &citation.key));
+    entry.push_str(&line("TI", &citation.title));
+    for author in &citation.authors {
+        entry.push_str(&line("AU", author));
+    }
+    if let Some(published) = &citation.published {
+        entry.push_str(&line("PY", &published[..4]));
+        if published.len() == 10 {
+            entry.push_str(&line("DA", &format!("{}/", published.replace('-', "/"))));
+        }
+    }
+    if let Some(site) = &citation.site_name {
+        entry.push_str(&line("T2", site));
+    }
+    entry.push_str(&line("UR", &citation.url));
+    entry.push_str(&line("Y2", &citation.accessed.replace('-', "/")));
+    if let Some(language) = &citation.language {
+        entry.push_str(&line("LA", language));
+    }
+    for note in &citation.notes {
+        entry.push_str(&line("N1", note));
+    }
+    entry.push_str("ER  - \r\n\r\n");
+    entry
+}
+
+#[derive(Debug, Clone)]
+pub enum CitationError {
+    UnsupportedFormat(String),
+    NoSources,
+}
+
+impl std::fmt::Display for CitationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CitationError::UnsupportedFormat(format) => write!(f, "Unsupported citation format: {} (use bibtex or ris)", format),
+            CitationError::NoSources => write!(f, "The session has no web sources to cite"),
+        }
+    }
+}
+
+impl std::error::Error for CitationError {}
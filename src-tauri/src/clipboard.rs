@@ -0,0 +1,189 @@
+// Clipboard Monitor - Opt-in watcher that offers actions for copied URLs, web addresses, and stock symbols
+// Only the whole clipboard is matched against a strict allowlist; nothing else is inspected, stored, or logged
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::privacy::PrivacyEnforcer;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(1000);
+// Longer clipboard contents are never a single URL or symbol
+const MAX_CANDIDATE_LEN: usize = 2048;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub enum ClipboardKind {
+    Url,
+    Address,                           // Web address without a scheme, e.g. example.com/page
+    Symbol,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub enum ClipboardAction {
+    OpenInTab,
+    AddToResearch,
+    GetQuote,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardSuggestion {
+    pub kind: ClipboardKind,
+    pub value: String,                 // Normalized: full URL, or bare ticker with its exchange
+    pub actions: Vec<ClipboardAction>,
+    pub detected_at: i64,
+}
+
+struct Patterns {
+    address: Regex,
+    dollar_symbol: Regex,
+    prefixed_symbol: Regex,
+    suffixed_symbol: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static RE: OnceLock<Patterns> = OnceLock::new();
+    RE.get_or_init(|| Patterns {
+        address: Regex::new(r"^(?i)(www\.)?[a-z0-9-]+(\.[a-z0-9-]+)*\.[a-z]{2,24}(:\d{1,5})?(/\S*)?$").unwrap(),
+        dollar_symbol: Regex::new(r"^\$([A-Z]{1,5})$").unwrap(),
+        prefixed_symbol: Regex::new(r"^(NASDAQ|NYSE|AMEX|NSE|BSE|LSE|TSX):([A-Z0-9&.-]{1,12})$").unwrap(),
+        suffixed_symbol: Regex::new(r"^([A-Z0-9&-]{1,12})\.(NS|BO|L|TO)$").unwrap(),
+    })
+}
+
+// Classify copied text; None unless the whole text is one allowlisted value
+pub fn detect(text: &str) -> Option<(ClipboardKind, String)> {
+    let text = text.trim();
+    if text.is_empty() || text.len() > MAX_CANDIDATE_LEN || text.contains(char::is_whitespace) {
+        return None;
+    }
+
+    // Before URL parsing: "NSE:INFY" is also a valid URL
+    let re = patterns();
+    if let Some(caps) = re.dollar_symbol.captures(text) {
+        return Some((ClipboardKind::Symbol, caps[1].to_string()));
+    }
+    if re.prefixed_symbol.is_match(text) || re.suffixed_symbol.is_match(text) {
+        return Some((ClipboardKind::Symbol, text.to_string()));
+    }
+
+    if let Ok(url) = url::Url::parse(text) {
+        let web = matches!(url.scheme(), "http" | "https") && url.host_str().is_some();
+        return web.then(|| (ClipboardKind::Url, url.to_string()));
+    }
+    // File names like report.pdf look like domains; require a known-looking web address
+    if re.address.is_match(text) && !text.contains('@') {
+        let candidate = format!("https://{}", text);
+        let url = url::Url::parse(&candidate).ok()?;
+        let host = url.host_str()?;
+        let tld = host.rsplit('.').next()?;
+        if !FILE_EXTENSIONS.contains(&tld.to_ascii_lowercase().as_str()) {
+            return Some((ClipboardKind::Address, url.to_string()));
+        }
+    }
+    None
+}
+
+const FILE_EXTENSIONS: &[&str] = &[
+    "pdf", "txt", "md", "doc", "docx", "xls", "xlsx", "csv", "json", "toml", "yaml", "yml", "rs", "js", "ts",
+    "py", "png", "jpg", "jpeg", "gif", "zip", "tar", "gz", "exe", "html", "htm",
+];
+
+pub fn suggestion_for(text: &str) -> Option<ClipboardSuggestion> {
+    let (kind, value) = detect(text)?;
+    let actions = match kind {
+        ClipboardKind::Url | ClipboardKind::Address => vec![ClipboardAction::OpenInTab, ClipboardAction::AddToResearch],
+        ClipboardKind::Symbol => vec![ClipboardAction::GetQuote, ClipboardAction::AddToResearch],
+    };
+    Some(ClipboardSuggestion { kind, value, actions, detected_at: chrono::Utc::now().timestamp() })
+}
+
+struct Monitor {
+    enabled: AtomicBool,
+    generation: AtomicU64,             // Bumped on each enable so an older watcher loop exits
+    last_seen: Mutex<Option<u64>>,     // Hash only; clipboard text is never kept
+}
+
+fn monitor() -> &'static Monitor {
+    static MONITOR: OnceLock<Monitor> = OnceLock::new();
+    MONITOR.get_or_init(|| Monitor {
+        enabled: AtomicBool::new(false),
+        generation: AtomicU64::new(0),
+        last_seen: Mutex::new(None),
+    })
+}
+
+pub fn is_enabled() -> bool {
+    monitor().enabled.load(Ordering::SeqCst)
+}
+
+pub fn enable(app: tauri::AppHandle) {
+    let monitor = monitor();
+    if monitor.enabled.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let generation = monitor.generation.fetch_add(1, Ordering::SeqCst) + 1;
+    // Whatever is on the clipboard already was copied before the user opted in
+    *monitor.last_seen.lock().unwrap() = app.clipboard().read_text().ok().map(|t| fingerprint(&t));
+
+    tauri::async_runtime::spawn(async move {
+        tracing::info!(target: "privacy", "Clipboard: Monitor enabled");
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            if !is_enabled() || monitor.generation.load(Ordering::SeqCst) != generation {
+                break;
+            }
+            poll(&app);
+        }
+    });
+}
+
+pub fn disable() {
+    if monitor().enabled.swap(false, Ordering::SeqCst) {
+        *monitor().last_seen.lock().unwrap() = None;
+        tracing::info!(target: "privacy", "Clipboard: Monitor disabled");
+    }
+}
+
+fn poll(app: &tauri::AppHandle) {
+    // Ghost mode leaves no trace, including reacting to the clipboard
+    let ghost = app
+        .try_state::<Mutex<PrivacyEnforcer>>()
+        .map(|e| !e.lock().unwrap().can_persist_clipboard())
+        .unwrap_or(false);
+    // Suggestions are interruptions; focus mode holds them back
+    if ghost || crate::modes::background_paused() {
+        return;
+    }
+    let Ok(text) = app.clipboard().read_text() else {
+        return;
+    };
+    let hash = fingerprint(&text);
+    {
+        let mut last_seen = monitor().last_seen.lock().unwrap();
+        if *last_seen == Some(hash) {
+            return;
+        }
+        *last_seen = Some(hash);
+    }
+    if let Some(suggestion) = suggestion_for(&text) {
+        let _ = app.emit("clipboard-suggestion", suggestion);
+    }
+}
+
+fn fingerprint(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,265 @@
+// Clustering - Spherical k-means over embeddings, k chosen by silhouette
+// Used to group history into topics; labels come from the LLM with a keyword fallback
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::embeddings::cosine;
+
+const MAX_ITERATIONS: usize = 50;
+const MAX_K: usize = 12;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub url: String,
+    pub title: String,
+    pub visited_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryCluster {
+    pub label: String,
+    pub keywords: Vec<String>,
+    pub entries: Vec<HistoryEntry>,
+    pub first_visit: i64,
+    pub last_visit: i64,
+}
+
+// Cluster normalized vectors; returns one cluster index per input
+pub fn cluster(vectors: &[Vec<f32>]) -> Vec<usize> {
+    let n = vectors.len();
+    if n < 4 {
+        return vec![0; n];
+    }
+
+    // Pairwise cosine distances, shared by every candidate k
+    let distances: Vec<Vec<f32>> = vectors
+        .iter()
+        .map(|a| vectors.iter().map(|b| 1.0 - cosine(a, b)).collect())
+        .collect();
+
+    let max_k = MAX_K.min(n / 2);
+    let mut best: Option<(f32, Vec<usize>)> = None;
+    for k in 2..=max_k {
+        let assignments = kmeans(vectors, k, 0x5eed + k as u64);
+        let score = silhouette(&distances, &assignments, k);
+        if best.as_ref().map(|(s, _)| score > *s).unwrap_or(true) {
+            best = Some((score, assignments));
+        }
+    }
+    best.map(|(_, a)| a).unwrap_or_else(|| vec![0; n])
+}
+
+// Spherical k-means with k-means++ seeding (deterministic for a given seed)
+pub fn kmeans(vectors: &[Vec<f32>], k: usize, seed: u64) -> Vec<usize> {
+    let mut rng = XorShift(seed.max(1));
+    let mut centroids = seed_centroids(vectors, k, &mut rng);
+    let mut assignments = vec![0usize; vectors.len()];
+
+    for _ in 0..MAX_ITERATIONS {
+        let mut changed = false;
+        for (i, v) in vectors.iter().enumerate() {
+            let nearest = nearest(v, &centroids);
+            if assignments[i] != nearest {
+                assignments[i] = nearest;
+                changed = true;
+            }
+        }
+
+        let dims = vectors[0].len();
+        let mut sums = vec![vec![0f32; dims]; k];
+        for (v, &c) in vectors.iter().zip(&assignments) {
+            sums[c].iter_mut().zip(v).for_each(|(s, x)| *s += x);
+        }
+        for (c, sum) in sums.into_iter().enumerate() {
+            // Empty clusters keep their old centroid
+            if sum.iter().any(|x| *x != 0.0) {
+                centroids[c] = crate::embeddings::normalize(sum);
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+    assignments
+}
+
+fn seed_centroids(vectors: &[Vec<f32>], k: usize, rng: &mut XorShift) -> Vec<Vec<f32>> {
+    let mut centroids = vec![vectors[rng.below(vectors.len())].clone()];
+    while centroids.len() < k {
+        let distances: Vec<f32> = vectors
+            .iter()
+            .map(|v| {
+                let d = 1.0 - centroids.iter().map(|c| cosine(v, c)).fold(f32::MIN, f32::max);
+                d.max(0.0).powi(2)
+            })
+            .collect();
+        let total: f32 = distances.iter().sum();
+        if total <= 0.0 {
+            centroids.push(vectors[rng.below(vectors.len())].clone());
+            continue;
+        }
+        let mut target = rng.unit() * total;
+        let mut chosen = vectors.len() - 1;
+        for (i, d) in distances.iter().enumerate() {
+            target -= d;
+            if target <= 0.0 {
+                chosen = i;
+                break;
+            }
+        }
+        centroids.push(vectors[chosen].clone());
+    }
+    centroids
+}
+
+fn nearest(v: &[f32], centroids: &[Vec<f32>]) -> usize {
+    centroids
+        .iter()
+        .enumerate()
+        .map(|(i, c)| (i, cosine(v, c)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+// Mean silhouette over a precomputed distance matrix
+fn silhouette(distances: &[Vec<f32>], assignments: &[usize], k: usize) -> f32 {
+    let n = distances.len();
+    let mut total = 0.0;
+    for i in 0..n {
+        let mut sums = vec![0f32; k];
+        let mut counts = vec![0usize; k];
+        for j in 0..n {
+            if i != j {
+                sums[assignments[j]] += distances[i][j];
+                counts[assignments[j]] += 1;
+            }
+        }
+        let own = assignments[i];
+        if counts[own] == 0 {
+            continue; // Singletons score 0
+        }
+        let a = sums[own] / counts[own] as f32;
+        let b = (0..k)
+            .filter(|&c| c != own && counts[c] > 0)
+            .map(|c| sums[c] / counts[c] as f32)
+            .fold(f32::MAX, f32::min);
+        if b == f32::MAX {
+            continue;
+        }
+        total += (b - a) / a.max(b).max(f32::EPSILON);
+    }
+    total / n as f32
+}
+
+// Build clusters from assignments; labels default to top keywords until the LLM names them
+pub fn group(entries: Vec<HistoryEntry>, assignments: &[usize]) -> Vec<HistoryCluster> {
+    let mut groups: HashMap<usize, Vec<HistoryEntry>> = HashMap::new();
+    for (entry, &c) in entries.into_iter().zip(assignments) {
+        groups.entry(c).or_default().push(entry);
+    }
+
+    let mut clusters: Vec<HistoryCluster> = groups
+        .into_values()
+        .map(|mut entries| {
+            entries.sort_by(|a, b| b.visited_at.cmp(&a.visited_at));
+            let titles: Vec<&str> = entries.iter().map(|e| e.title.as_str()).collect();
+            let keywords = keywords(&titles, 5);
+            let label = if keywords.is_empty() {
+                "Miscellaneous".to_string()
+            } else {
+                keywords.iter().take(3).cloned().collect::<Vec<_>>().join(", ")
+            };
+            HistoryCluster {
+                label,
+                keywords,
+                first_visit: entries.iter().map(|e| e.visited_at).min().unwrap_or(0),
+                last_visit: entries.iter().map(|e| e.visited_at).max().unwrap_or(0),
+                entries,
+            }
+        })
+        .collect();
+
+    // Biggest topics first, most recent breaking ties
+    clusters.sort_by(|a, b| b.entries.len().cmp(&a.entries.len()).then(b.last_visit.cmp(&a.last_visit)));
+    clusters
+}
+
+// Most frequent meaningful title words, used as keywords and as the fallback label
+pub fn keywords(titles: &[&str], count: usize) -> Vec<String> {
+    const STOPWORDS: &[&str] = &[
+        "the", "and", "for", "with", "from", "that", "this", "your", "you", "are", "how", "what",
+        "why", "new", "com", "www", "http", "https", "home", "page", "official", "site", "about",
+        "into", "over", "our", "all", "can", "has", "have", "was", "will", "not", "but",
+    ];
+    let mut freq: HashMap<String, usize> = HashMap::new();
+    for title in titles {
+        let mut seen = std::collections::HashSet::new();
+        for word in title.split(|c: char| !c.is_alphanumeric()) {
+            let word = word.to_lowercase();
+            if word.chars().count() < 3 || STOPWORDS.contains(&word.as_str()) || word.chars().all(|c| c.is_ascii_digit()) {
+                continue;
+            }
+            if seen.insert(word.clone()) {
+                *freq.entry(word).or_default() += 1;
+            }
+        }
+    }
+    let mut words: Vec<(String, usize)> = freq.into_iter().collect();
+    words.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    words.into_iter().take(count).map(|(w, _)| w).collect()
+}
+
+// One prompt for all clusters; the model answers "<n>: <label>" per line
+pub fn label_prompt(clusters: &[HistoryCluster]) -> String {
+    let mut groups = String::new();
+    for (i, cluster) in clusters.iter().enumerate() {
+        groups.push_str(&format!("Group {}:\n", i + 1));
+        for entry in cluster.entries.iter().take(8) {
+            groups.push_str(&format!("- {}\n", entry.title));
+        }
+        groups.push('\n');
+    }
+    crate::prompts::render("history_cluster_labels", None, None, &[("groups", groups.trim_end())])
+}
+
+pub fn parse_labels(response: &str, count: usize) -> Vec<Option<String>> {
+    let mut labels = vec![None; count];
+    for line in response.lines() {
+        let line = line.trim().trim_start_matches(['-', '*']).trim();
+        let line = line.strip_prefix("Group ").unwrap_or(line);
+        let Some((number, label)) = line.split_once([':', '.', ')']) else { continue };
+        let Ok(index) = number.trim().parse::<usize>() else { continue };
+        let label = label.trim().trim_matches(['"', '*']).trim();
+        if index >= 1 && index <= count && !label.is_empty() {
+            labels[index - 1] = Some(label.to_string());
+        }
+    }
+    labels
+}
+
+// Minimal PRNG so seeding is reproducible without another dependency
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn unit(&mut self) -> f32 {
+        (self.next() >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
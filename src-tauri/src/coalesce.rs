@@ -0,0 +1,125 @@
+// Coalesce - Shares one upstream call between identical requests that are in flight at the same time
+// The first caller for a key runs the call; later callers replay its streamed chunks and get its result
+
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+// Chunks streamed so far and, once the leader is done, its result (None if it gave up)
+struct State<C, R> {
+    chunks: Vec<C>,
+    done: Option<Option<R>>,
+}
+
+pub struct Flight<C, R> {
+    state: Mutex<State<C, R>>,
+    changed: Notify,
+}
+
+pub struct Coalescer<C, R> {
+    flights: Arc<Mutex<HashMap<String, Arc<Flight<C, R>>>>>,
+}
+
+pub enum Join<C, R> {
+    Leader(Leader<C, R>),
+    Follower(Arc<Flight<C, R>>),
+}
+
+// Held by the caller doing the work; dropping it without `finish` releases the followers
+pub struct Leader<C, R> {
+    key: String,
+    flight: Arc<Flight<C, R>>,
+    flights: Arc<Mutex<HashMap<String, Arc<Flight<C, R>>>>>,
+    finished: bool,
+}
+
+impl<C: Clone, R: Clone> Coalescer<C, R> {
+    pub fn new() -> Self {
+        Coalescer { flights: Arc::new(Mutex::new(HashMap::new())) }
+    }
+
+    pub fn join(&self, key: &str) -> Join<C, R> {
+        let mut flights = self.flights.lock().unwrap();
+        if let Some(flight) = flights.get(key) {
+            return Join::Follower(flight.clone());
+        }
+        let flight = Arc::new(Flight { state: Mutex::new(State { chunks: Vec::new(), done: None }), changed: Notify::new() });
+        flights.insert(key.to_string(), flight.clone());
+        Join::Leader(Leader { key: key.to_string(), flight, flights: self.flights.clone(), finished: false })
+    }
+}
+
+impl<C: Clone, R: Clone> Default for Coalescer<C, R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C, R> Leader<C, R> {
+    pub fn push(&self, chunk: C) {
+        self.flight.state.lock().unwrap().chunks.push(chunk);
+        self.flight.changed.notify_waiters();
+    }
+
+    pub fn finish(mut self, result: R) {
+        self.settle(Some(result));
+    }
+
+    fn settle(&mut self, result: Option<R>) {
+        self.finished = true;
+        // Callers arriving from now on start a new call rather than joining a finished one
+        if let Ok(mut flights) = self.flights.lock() {
+            if flights.get(&self.key).is_some_and(|f| Arc::ptr_eq(f, &self.flight)) {
+                flights.remove(&self.key);
+            }
+        }
+        if let Ok(mut state) = self.flight.state.lock() {
+            state.done = Some(result);
+        }
+        self.flight.changed.notify_waiters();
+    }
+}
+
+impl<C, R> Drop for Leader<C, R> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.settle(None);
+        }
+    }
+}
+
+impl<C: Clone, R: Clone> Flight<C, R> {
+    // Replay every chunk (already streamed and still to come) and wait for the result.
+    // None means the leader was dropped before finishing; the caller should run the call itself.
+    pub async fn follow(&self, mut on_chunk: impl FnMut(C)) -> Option<R> {
+        let mut seen = 0;
+        loop {
+            // Registered before looking, so a chunk pushed in between still wakes us
+            let changed = self.changed.notified();
+            tokio::pin!(changed);
+            changed.as_mut().enable();
+
+            let (chunks, done) = {
+                let state = self.state.lock().unwrap();
+                (state.chunks[seen..].to_vec(), state.done.clone())
+            };
+            seen += chunks.len();
+            chunks.into_iter().for_each(&mut on_chunk);
+            if let Some(result) = done {
+                return result;
+            }
+            changed.await;
+        }
+    }
+}
+
+// Key for a request: the kind of call plus a digest of everything that determines its result
+pub fn key(kind: &str, parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part.as_bytes());
+        hasher.update([0u8]);
+    }
+    format!("{}:{:x}", kind, hasher.finalize())
+}
@@ -4,24 +4,131 @@
 use serde::{Deserialize, Serialize};
 use std::sync::Mutex;
 use crate::state::{AppState, PrivacyMode as StatePrivacyMode, AppMode};
-use crate::browser::{TabManager, TabUpdate};
+use crate::browser::{NavigationState, SplitView, TabGroup, TabGroupInfo, TabManager, TabUpdate, MAIN_WINDOW};
 use crate::db::{Database, PageCache};
 use crate::search::SearchEngine;
-use crate::privacy::PrivacyEnforcer;
+use crate::privacy::{self, PrivacyEnforcer};
 use crate::ai::AIService;
 use crate::stability;
+use crate::language::{self, LanguageDetection};
+use crate::extractor;
+use crate::reader::{self, ReaderView};
+use crate::archive::{self, ArchiveEntry};
+use crate::session::{self, CheckpointSummary, SessionCheckpoint};
+use crate::profiles::{ProfileInfo, ProfileManager};
+use crate::research::{self, ExportFormat, ResearchSession};
+use crate::notes::{self, Note};
+use crate::highlights::{self, Highlight, ResolvedHighlight, Selector};
+use crate::embeddings;
+use crate::clustering::{self, HistoryCluster, HistoryEntry};
+use crate::frecency::{self, TopSite};
+use crate::tab_discard::{DiscardStats, TabDiscarder};
+use crate::tab_metrics::{self, TabMetrics};
+use crate::clipboard;
+use crate::focus::{self, FocusProgress, FocusRecord, FocusSession, FocusStats};
+use crate::usage::{self, UsageReport};
+use crate::modes::{self, ModeProfile};
+use crate::webrtc_guard::{self, LeakTestReport, PlatformSupport};
+use crate::doh::{self, DohStatus};
+use crate::proxy::{self, ProxyTestReport};
+use crate::network_audit::{self, AuditFilter, AuditList};
+use crate::markdown::{self, MarkdownEvent};
+use crate::research_agent::{self, ResearchOptions, ResearchReport};
+use crate::tools::{self, ToolInfo};
+use crate::agent::{self, AgentAnswer};
+use crate::approvals::{self, ActionRequest, ProposedAction};
+use crate::agent_runs::{self, AgentRun, AgentRunSummary, RunFilter};
+use crate::llm_usage::{self, BudgetCheck};
+use crate::summarizer::{self, DocumentSummary, PresetSummary, SummaryOptions, SummaryPreset};
+use crate::documents::{self, DocumentAnswer, ProcessOptions, ProcessedBook, ProcessedDocument};
+use crate::tables::{self, Table};
+use crate::images::{self, ImageCaption, ImageInfo};
+use crate::capture::{self, OcrResult, Region, Screenshot};
+use crate::selection::{self, JobStatus, SelectionAction, SelectionJob, SelectionPayload};
+use crate::services::global_shortcut_service::{self, ShortcutBinding};
+use crate::services::hotword_service::{self, HotwordStatus};
+use crate::services::api_server::{self, ApiServerStatus};
+use crate::floating_windows::{self, FloatingAction, FloatingKind, FloatingWindowState};
+use crate::recovery::{self, RecoveryInfo, RecoverySnapshot};
+use crate::onboarding::{self, ImportSource, OnboardingStatus, OnboardingStep, StepState};
+use crate::userscripts::{self, Injection, UserScript, UserScriptInput};
+use crate::filter_rules::{self, FilterRule, RuleCheck, RuleTestResult, RuleType, TestRequest};
+use crate::palette::{self, PaletteAction};
+use crate::permissions;
+use crate::files::{self, DocType, FileFilter, FileHandle, RecentFile};
+use crate::archive_files::{self, ArchiveListing, ExtractReport};
+use crate::verify::{self, ChecksumCheck, DownloadVerification, HashAlgo, SignatureCheck, VerifyRequest};
+use crate::mcp::{self, McpStatus};
+use crate::workflows::{self, Workflow, WorkflowRun};
+use crate::calendar::{self, CalendarEvent, ImportReport, NewEvent};
+use crate::email::{self, EmailDraft, EmailHandoff};
+use crate::citations::{self, CitationExport, CitationFormat};
+use crate::similarity::{self, SimilarityReport};
+use crate::research_multilingual::{self, MultilingualReport};
+use crate::spellcheck::{self, DictionaryInfo, SpellChecker, SpellReport, SpellingFix};
+use crate::redaction::{self, RedactedText, RedactionLogEntry};
+use crate::provider_health::{self, HealthReport};
+use crate::warmup::{self, WarmupStatus};
+use crate::notifications::{self, Notification, NotificationAction, NotificationKind, NotificationRequest, NotificationResponse};
+use crate::grounding::{self, GroundingReport, SourceChunk};
+use crate::prompts::{self, PromptInfo};
+use crate::llm_cache::CacheStats;
+use crate::hardware::{self, SystemCapabilities};
+use crate::resources::{ResourceManager, ResourceReport};
+use crate::logging::{self, LogLevels};
+use crate::config::{self, AppConfig};
+use crate::apikeys::{ApiKeyInfo, ApiKeyStore, ApiKeyTest, Provider};
+use crate::secure_store::SecureStore;
+use crate::autofill::{AutofillMatch, AutofillProfile, AutofillStore, FormField};
+use crate::passwords::{self, BreachCheck, Credential, LockStatus, PasswordPolicy, PasswordVault};
+use crate::http::{self, HttpStats};
+use crate::connectivity::{self, ConnectivityStatus};
+use crate::web_search::{self, WebSearchResponse};
+use crate::omnibox::{self, OmniboxResponse};
+use crate::instant_answers::{self, InstantAnswer};
+use crate::fx::{self, Conversion, RateTable};
+use crate::trade_news::{self, NewsResponse};
+use crate::options_chain::{self, OptionChain};
+use crate::ohlc::{self, Quote};
+use crate::backtest::{self, BacktestRange, BacktestResult, StrategySpec};
+use crate::journal::{self, JournalItem, JournalRange, JournalReport, TradeLog, TradeLogInput};
+use crate::wispr::{self, WisprInput, WisprResult};
+use crate::local_index::{IndexKind, LocalIndex, LocalSearchFilters, LocalSearchResponse};
+use crate::semantic_history::{self, SemanticHistory, SemanticHistoryResponse};
+use crate::feeds::{self, DiscoveredFeed, EntryFilter, Feed, FeedEntry, FeedError, Feeds, RefreshResult};
+use crate::digest::{self, Digest, DigestRange};
+use crate::diagnostics::{self, DiagnosticsExport, DiagnosticsInput};
+use crate::services::binaries::CapabilityReport;
+use crate::services::supervisor::{ServiceStatus, ServiceSupervisor};
+use tauri::{Emitter, Manager};
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
 pub struct SystemInfo {
     total_ram_gb: f64,
     available_ram_gb: f64,
     cpu_cores: usize,
 }
 
+// Resolve (and create) a subdirectory of the active profile's data directory
+fn app_data_path(app: &tauri::AppHandle, subdir: &str) -> Result<std::path::PathBuf, String> {
+    let root = match app.try_state::<ProfileManager>() {
+        Some(profiles) => profiles.data_dir(),
+        None => app
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("App data directory unavailable: {}", e))?,
+    };
+    let dir = root.join(subdir);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    Ok(dir)
+}
+
 // ============================================================================
 // TAB COMMANDS
 // ============================================================================
 
+// capability: browsing
 #[tauri::command]
 pub async fn tabs_create(
     url: String,
@@ -59,7 +166,7 @@ pub async fn tabs_create(
         let enforcer = privacy_enforcer.lock().unwrap();
         if enforcer.should_use_tor() {
             // Ghost mode requires TOR - log for future integration
-            eprintln!("[Privacy] Ghost mode tab created - TOR routing required (integration pending)");
+            tracing::info!(target: "privacy", "Ghost mode tab created - TOR routing required (integration pending)");
         }
     }
 
@@ -76,19 +183,24 @@ pub async fn tabs_create(
     result
 }
 
+// capability: browsing
 #[tauri::command]
 pub async fn tabs_delete(
     id: String,
     tab_manager: tauri::State<'_, TabManager>,
+    discarder: tauri::State<'_, TabDiscarder>,
     db: tauri::State<'_, Database>,
 ) -> Result<(), String> {
     let result = tab_manager.delete_tab(&id);
     if result.is_ok() {
+        discarder.forget(&id);
+        tab_metrics::forget(&id);
         let _ = tab_manager.save_session(&db);
     }
     result
 }
 
+// capability: browsing
 #[tauri::command]
 pub async fn tabs_list(
     tab_manager: tauri::State<'_, TabManager>,
@@ -97,6 +209,7 @@ pub async fn tabs_list(
     Ok(tabs.into_iter().map(|t| serde_json::to_value(t).unwrap()).collect())
 }
 
+// capability: browsing
 #[tauri::command]
 pub async fn tabs_get_active(
     tab_manager: tauri::State<'_, TabManager>,
@@ -104,10 +217,13 @@ pub async fn tabs_get_active(
     Ok(tab_manager.get_active_tab().map(|t| serde_json::to_value(t).unwrap()))
 }
 
+// capability: browsing
 #[tauri::command]
 pub async fn tabs_set_active(
     id: String,
+    app: tauri::AppHandle,
     tab_manager: tauri::State<'_, TabManager>,
+    discarder: tauri::State<'_, TabDiscarder>,
     db: tauri::State<'_, Database>,
     privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
 ) -> Result<(), String> {
@@ -115,6 +231,13 @@ pub async fn tabs_set_active(
     
     // Auto-save session (if privacy mode allows)
     if result.is_ok() {
+        // Hands a discarded tab's snapshot back via "tab-restored"
+        discarder.restore(&app, &id);
+        let tab = tab_manager.get_tab(&id);
+        usage::activate(&db, tab.as_ref(), usage::recording_allowed(&app));
+        if let Some(tab) = &tab {
+            modes::on_tab_activated(&app, &tab.app_mode);
+        }
         let enforcer = privacy_enforcer.lock().unwrap();
         if enforcer.can_write_to_disk() {
         let _ = tab_manager.save_session(&db);
@@ -124,6 +247,7 @@ pub async fn tabs_set_active(
     result
 }
 
+// capability: browsing
 #[tauri::command]
 pub async fn tabs_update(
     id: String,
@@ -135,6 +259,7 @@ pub async fn tabs_update(
     privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
 ) -> Result<(), String> {
     let mut update = TabUpdate::new();
+    let url_changed = url.is_some();
     if let Some(u) = url {
         update = update.with_url(u);
     }
@@ -148,6 +273,13 @@ pub async fn tabs_update(
     
     // Auto-save session (if privacy mode allows)
     if result.is_ok() {
+        // In-page navigation of the foreground tab moves its time to the new domain
+        if url_changed {
+            if let Some(tab) = tab_manager.get_active_tab().filter(|t| t.id == id) {
+                let allowed = privacy_enforcer.lock().unwrap().can_save_history();
+                usage::activate(&db, Some(&tab), allowed);
+            }
+        }
         let enforcer = privacy_enforcer.lock().unwrap();
         if enforcer.can_write_to_disk() {
         let _ = tab_manager.save_session(&db);
@@ -157,10 +289,496 @@ pub async fn tabs_update(
     result
 }
 
+// ============================================================================
+// TAB NAVIGATION COMMANDS
+// ============================================================================
+
+// Every change is mirrored to "tab-history-changed" so back/forward buttons follow Rust's state
+fn navigation_changed(
+    app: &tauri::AppHandle,
+    result: Result<NavigationState, String>,
+    tab_manager: &TabManager,
+    db: &Database,
+    privacy_enforcer: &Mutex<PrivacyEnforcer>,
+) -> Result<NavigationState, String> {
+    let state = result?;
+    let _ = app.emit("tab-history-changed", &state);
+    if let Some(tab) = tab_manager.get_active_tab().filter(|t| t.id == state.tab_id) {
+        usage::activate(db, Some(&tab), usage::recording_allowed(app));
+    }
+    autosave_session(tab_manager, db, privacy_enforcer);
+    Ok(state)
+}
+
+// capability: browsing
+#[tauri::command]
+pub async fn tabs_navigate(
+    id: String,
+    url: String,
+    app: tauri::AppHandle,
+    tab_manager: tauri::State<'_, TabManager>,
+    db: tauri::State<'_, Database>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<NavigationState, String> {
+    let url = url.trim();
+    if url.is_empty() {
+        return Err("URL is empty".to_string());
+    }
+    navigation_changed(&app, tab_manager.navigate(&id, url), &tab_manager, &db, &privacy_enforcer)
+}
+
+// capability: browsing
+#[tauri::command]
+pub async fn tabs_back(
+    id: String,
+    app: tauri::AppHandle,
+    tab_manager: tauri::State<'_, TabManager>,
+    db: tauri::State<'_, Database>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<NavigationState, String> {
+    navigation_changed(&app, tab_manager.go(&id, -1), &tab_manager, &db, &privacy_enforcer)
+}
+
+// capability: browsing
+#[tauri::command]
+pub async fn tabs_forward(
+    id: String,
+    app: tauri::AppHandle,
+    tab_manager: tauri::State<'_, TabManager>,
+    db: tauri::State<'_, Database>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<NavigationState, String> {
+    navigation_changed(&app, tab_manager.go(&id, 1), &tab_manager, &db, &privacy_enforcer)
+}
+
+// Current back/forward state, e.g. after a session restore
+// capability: browsing
+#[tauri::command]
+pub async fn tabs_history(
+    id: String,
+    tab_manager: tauri::State<'_, TabManager>,
+) -> Result<NavigationState, String> {
+    tab_manager.navigation(&id)
+}
+
+// ============================================================================
+// TAB DISCARD COMMANDS
+// ============================================================================
+
+// Frontend's reply to "tab-discard-request" with the tab's saved state (None if nothing to keep)
+// capability: browsing
+#[tauri::command]
+pub async fn tabs_discard_ack(
+    tab_id: String,
+    snapshot: Option<serde_json::Value>,
+    app: tauri::AppHandle,
+    tab_manager: tauri::State<'_, TabManager>,
+    discarder: tauri::State<'_, TabDiscarder>,
+) -> Result<(), String> {
+    discarder.acknowledge(&app, &tab_manager, &tab_id, snapshot)
+}
+
+// Discard a background tab now instead of waiting for it to go idle
+// capability: browsing
+#[tauri::command]
+pub async fn tabs_discard(
+    tab_id: String,
+    app: tauri::AppHandle,
+    tab_manager: tauri::State<'_, TabManager>,
+    discarder: tauri::State<'_, TabDiscarder>,
+) -> Result<bool, String> {
+    discarder.request(&app, &tab_manager, &tab_id)
+}
+
+// capability: browsing
+#[tauri::command]
+pub async fn tabs_discard_stats(
+    tab_manager: tauri::State<'_, TabManager>,
+    discarder: tauri::State<'_, TabDiscarder>,
+) -> Result<DiscardStats, String> {
+    Ok(discarder.stats(&tab_manager))
+}
+
+// ============================================================================
+// TAB METRICS COMMANDS
+// ============================================================================
+
+// Per-tab memory, CPU, and network for the task manager, heaviest first
+// capability: browsing
+#[tauri::command]
+pub async fn tabs_metrics(
+    tab_manager: tauri::State<'_, TabManager>,
+) -> Result<Vec<TabMetrics>, String> {
+    Ok(tab_metrics::collect(&tab_manager))
+}
+
+// Periodic sample from a tab's webview
+// capability: browsing
+#[tauri::command]
+pub async fn tabs_report_usage(
+    tab_id: String,
+    heap_bytes: Option<u64>,
+    cpu_time_ms: Option<u64>,
+    tab_manager: tauri::State<'_, TabManager>,
+) -> Result<(), String> {
+    if tab_manager.get_tab(&tab_id).is_none() {
+        return Err(format!("Tab {} not found", tab_id));
+    }
+    tab_metrics::report_usage(&tab_id, heap_bytes, cpu_time_ms);
+    Ok(())
+}
+
+// ============================================================================
+// TAB GROUP COMMANDS
+// ============================================================================
+
+// Persist tabs and groups after a group change (if privacy mode allows)
+fn autosave_session(tab_manager: &TabManager, db: &Database, privacy_enforcer: &Mutex<PrivacyEnforcer>) {
+    if privacy_enforcer.lock().unwrap().can_write_to_disk() {
+        let _ = tab_manager.save_session(db);
+    }
+}
+
+// capability: browsing
+#[tauri::command]
+pub async fn tabs_group_create(
+    name: String,
+    color: String,
+    tab_manager: tauri::State<'_, TabManager>,
+    db: tauri::State<'_, Database>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<TabGroup, String> {
+    let group = tab_manager.create_group(&name, &color)?;
+    autosave_session(&tab_manager, &db, &privacy_enforcer);
+    Ok(group)
+}
+
+// Rename, recolor, or collapse a group
+// capability: browsing
+#[tauri::command]
+pub async fn tabs_group_update(
+    group_id: String,
+    name: Option<String>,
+    color: Option<String>,
+    collapsed: Option<bool>,
+    tab_manager: tauri::State<'_, TabManager>,
+    db: tauri::State<'_, Database>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<TabGroup, String> {
+    let group = tab_manager.update_group(&group_id, name, color, collapsed)?;
+    autosave_session(&tab_manager, &db, &privacy_enforcer);
+    Ok(group)
+}
+
+// group_id None removes the tab from its group
+// capability: browsing
+#[tauri::command]
+pub async fn tabs_assign_group(
+    tab_id: String,
+    group_id: Option<String>,
+    tab_manager: tauri::State<'_, TabManager>,
+    db: tauri::State<'_, Database>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<(), String> {
+    tab_manager.assign_group(&tab_id, group_id.as_deref())?;
+    autosave_session(&tab_manager, &db, &privacy_enforcer);
+    Ok(())
+}
+
+// capability: browsing
+#[tauri::command]
+pub async fn tabs_list_groups(
+    tab_manager: tauri::State<'_, TabManager>,
+) -> Result<Vec<TabGroupInfo>, String> {
+    Ok(tab_manager.list_groups())
+}
+
+// Put every background tab in the group to sleep; returns how many were slept
+// capability: browsing
+#[tauri::command]
+pub async fn tabs_group_sleep(
+    group_id: String,
+    tab_manager: tauri::State<'_, TabManager>,
+    db: tauri::State<'_, Database>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<usize, String> {
+    let slept = tab_manager.sleep_group(&group_id)?;
+    autosave_session(&tab_manager, &db, &privacy_enforcer);
+    Ok(slept)
+}
+
+// Close the group's tabs, keeping them for tabs_group_restore
+// capability: browsing
+#[tauri::command]
+pub async fn tabs_group_close(
+    group_id: String,
+    tab_manager: tauri::State<'_, TabManager>,
+    db: tauri::State<'_, Database>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<usize, String> {
+    let closed = tab_manager.close_group(&group_id)?;
+    autosave_session(&tab_manager, &db, &privacy_enforcer);
+    Ok(closed)
+}
+
+// Reopen a closed group's tabs; returns their ids
+// capability: browsing
+#[tauri::command]
+pub async fn tabs_group_restore(
+    group_id: String,
+    tab_manager: tauri::State<'_, TabManager>,
+    db: tauri::State<'_, Database>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<Vec<String>, String> {
+    let restored = tab_manager.restore_group(&group_id)?;
+    autosave_session(&tab_manager, &db, &privacy_enforcer);
+    Ok(restored)
+}
+
+// Delete a group; its tabs are closed too when close_tabs is set, otherwise ungrouped
+// capability: browsing
+#[tauri::command]
+pub async fn tabs_group_delete(
+    group_id: String,
+    close_tabs: Option<bool>,
+    tab_manager: tauri::State<'_, TabManager>,
+    db: tauri::State<'_, Database>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<(), String> {
+    tab_manager.delete_group(&group_id, close_tabs.unwrap_or(false))?;
+    autosave_session(&tab_manager, &db, &privacy_enforcer);
+    Ok(())
+}
+
+// ============================================================================
+// SPLIT VIEW COMMANDS
+// ============================================================================
+
+// Changes are mirrored to "layout-changed" ({window, split}) so every pane host follows Rust's state
+fn layout_changed(app: &tauri::AppHandle, window: &str, split: Option<&SplitView>) {
+    let _ = app.emit("layout-changed", serde_json::json!({ "window": window, "split": split }));
+}
+
+// Show tab_a (left) and tab_b (right) side by side; ratio is the left pane's share (default half)
+// capability: browsing
+#[tauri::command]
+pub async fn layout_set_split(
+    tab_a: String,
+    tab_b: String,
+    ratio: Option<f64>,
+    window: Option<String>,
+    app: tauri::AppHandle,
+    tab_manager: tauri::State<'_, TabManager>,
+    db: tauri::State<'_, Database>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<SplitView, String> {
+    let window = window.unwrap_or_else(|| MAIN_WINDOW.to_string());
+    let split = tab_manager.set_split(&window, &tab_a, &tab_b, ratio.unwrap_or(0.5))?;
+    autosave_session(&tab_manager, &db, &privacy_enforcer);
+    layout_changed(&app, &window, Some(&split));
+    Ok(split)
+}
+
+// Back to a single pane; returns whether the window was split
+// capability: browsing
+#[tauri::command]
+pub async fn layout_reset(
+    window: Option<String>,
+    app: tauri::AppHandle,
+    tab_manager: tauri::State<'_, TabManager>,
+    db: tauri::State<'_, Database>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<bool, String> {
+    let window = window.unwrap_or_else(|| MAIN_WINDOW.to_string());
+    let was_split = tab_manager.reset_layout(&window);
+    if was_split {
+        autosave_session(&tab_manager, &db, &privacy_enforcer);
+        layout_changed(&app, &window, None);
+    }
+    Ok(was_split)
+}
+
+// capability: browsing
+#[tauri::command]
+pub async fn layout_get(
+    window: Option<String>,
+    tab_manager: tauri::State<'_, TabManager>,
+) -> Result<Option<SplitView>, String> {
+    Ok(tab_manager.layout(window.as_deref().unwrap_or(MAIN_WINDOW)))
+}
+
+// ============================================================================
+// SESSION CHECKPOINT COMMANDS
+// ============================================================================
+
+// capability: browsing
+#[tauri::command]
+pub async fn session_checkpoint(
+    name: Option<String>,
+    tab_manager: tauri::State<'_, TabManager>,
+    db: tauri::State<'_, Database>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<CheckpointSummary, String> {
+    // PRIVACY ENFORCEMENT: Checkpoints are persisted to SQLite
+    privacy_enforcer
+        .lock()
+        .unwrap()
+        .enforce_disk_write()
+        .map_err(|e| e.to_string())?;
+
+    let previous = db.list_checkpoints(1).map_err(|e| e.to_string())?;
+    let (active_id, tabs) = tab_manager.snapshot();
+    let checkpoint = SessionCheckpoint::new(name, active_id, tabs);
+    db.save_checkpoint(&checkpoint).map_err(|e| e.to_string())?;
+
+    Ok(checkpoint.summary(previous.first()))
+}
+
+// capability: browsing
+#[tauri::command]
+pub async fn session_list_checkpoints(
+    limit: Option<usize>,
+    db: tauri::State<'_, Database>,
+) -> Result<Vec<CheckpointSummary>, String> {
+    let limit = limit.unwrap_or(session::MAX_CHECKPOINTS);
+    // Fetch one extra so the oldest listed checkpoint still gets a diff
+    let checkpoints = db.list_checkpoints(limit + 1).map_err(|e| e.to_string())?;
+
+    Ok(checkpoints
+        .iter()
+        .take(limit)
+        .enumerate()
+        .map(|(i, checkpoint)| checkpoint.summary(checkpoints.get(i + 1)))
+        .collect())
+}
+
+// capability: browsing
+#[tauri::command]
+pub async fn session_restore_checkpoint(
+    id: String,
+    tab_manager: tauri::State<'_, TabManager>,
+    db: tauri::State<'_, Database>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<CheckpointSummary, String> {
+    let checkpoint = db
+        .get_checkpoint(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Checkpoint {} not found", id))?;
+
+    // Keep the pre-restore state as an unnamed checkpoint so the restore can be undone
+    let (active_id, tabs) = tab_manager.snapshot();
+    let before = SessionCheckpoint::new(None, active_id, tabs);
+    let can_persist = privacy_enforcer.lock().unwrap().can_write_to_disk();
+    if can_persist {
+        db.save_checkpoint(&before).map_err(|e| e.to_string())?;
+    }
+
+    tab_manager.replace_tabs(checkpoint.active_tab_id.clone(), checkpoint.tabs.clone());
+    if can_persist {
+        let _ = tab_manager.save_session(&db);
+    }
+
+    Ok(checkpoint.summary(Some(&before)))
+}
+
+// ============================================================================
+// PROFILE COMMANDS
+// ============================================================================
+
+// capability: settings
+#[tauri::command]
+pub async fn profile_create(
+    name: String,
+    profiles: tauri::State<'_, ProfileManager>,
+) -> Result<ProfileInfo, String> {
+    profiles.create(&name).map_err(|e| e.to_string())
+}
+
+// capability: settings
+#[tauri::command]
+pub async fn profile_list(
+    profiles: tauri::State<'_, ProfileManager>,
+) -> Result<Vec<ProfileInfo>, String> {
+    Ok(profiles.list())
+}
+
+// capability: settings
+#[tauri::command]
+pub async fn profile_switch(
+    name: String,
+    app: tauri::AppHandle,
+    profiles: tauri::State<'_, ProfileManager>,
+    tab_manager: tauri::State<'_, TabManager>,
+    db: tauri::State<'_, Database>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<ProfileInfo, String> {
+    if profiles.active() != name {
+        // Persist the outgoing profile's tabs before its database is closed
+        if privacy_enforcer.lock().unwrap().can_write_to_disk() {
+            let _ = tab_manager.save_session(&db);
+        }
+
+        profiles.set_active(&name).map_err(|e| e.to_string())?;
+        db.reopen(profiles.database_path())
+            .map_err(|e| format!("Failed to open profile database: {}", e))?;
+        std::fs::create_dir_all(profiles.downloads_dir()).ok();
+        let passwords = app.try_state::<PasswordVault>();
+        reopen_secure_storage(
+            &profiles,
+            app.try_state::<SecureStore>().as_deref(),
+            passwords.as_deref(),
+        )?;
+        if passwords.is_some() {
+            let _ = app.emit("passwords-locked", ());
+        }
+        if let Some(local_index) = app.try_state::<LocalIndex>() {
+            local_index.resync();
+        }
+
+        tab_manager.replace_tabs(None, Vec::new());
+        if let Err(e) = tab_manager.restore_session(&db) {
+            tracing::warn!(target: "app", "Failed to restore session for profile {}: {}", name, e);
+        }
+        if tab_manager.list_tabs().is_empty() {
+            tab_manager.create_tab("about:blank".to_string(), StatePrivacyMode::Normal, AppMode::Browse)?;
+        }
+
+        proxy::apply(&app, &config::current().proxy);
+
+        tracing::info!(target: "app", "Switched to profile {}", name);
+        let _ = app.emit("profile:switched", &name);
+    }
+
+    profiles
+        .list()
+        .into_iter()
+        .find(|p| p.active)
+        .ok_or_else(|| format!("Profile {} not found", name))
+}
+
+// Point secure storage at the active profile. The password vault is locked first: its key was
+// derived from the previous profile's master password
+fn reopen_secure_storage(
+    profiles: &ProfileManager,
+    secure_store: Option<&SecureStore>,
+    passwords: Option<&PasswordVault>,
+) -> Result<(), String> {
+    if let Some(passwords) = passwords {
+        passwords.lock();
+    }
+    if let Some(secure_store) = secure_store {
+        secure_store
+            .reopen(profiles.secure_storage_dir())
+            .map_err(|e| format!("Failed to open profile secure storage: {}", e))?;
+    }
+    Ok(())
+}
+
 // ============================================================================
 // SETTINGS COMMANDS
 // ============================================================================
 
+// capability: settings
 #[tauri::command]
 pub async fn settings_get_language(
     app_state: tauri::State<'_, AppState>,
@@ -169,6 +787,7 @@ pub async fn settings_get_language(
     Ok(settings.language.clone())
 }
 
+// capability: settings
 #[tauri::command]
 pub async fn settings_set_language(
     language: String,
@@ -179,6 +798,7 @@ pub async fn settings_set_language(
     Ok(())
 }
 
+// capability: settings
 #[tauri::command]
 pub async fn settings_get_all(
     app_state: tauri::State<'_, AppState>,
@@ -187,6 +807,7 @@ pub async fn settings_get_all(
     Ok(serde_json::to_value(&*settings).map_err(|e| e.to_string())?)
 }
 
+// capability: settings
 #[tauri::command]
 pub async fn settings_get_low_ram_mode(
     app_state: tauri::State<'_, AppState>,
@@ -194,6 +815,7 @@ pub async fn settings_get_low_ram_mode(
     Ok(app_state.get_low_ram_mode())
 }
 
+// capability: settings
 #[tauri::command]
 pub async fn settings_set_low_ram_mode(
     enabled: bool,
@@ -207,6 +829,7 @@ pub async fn settings_set_low_ram_mode(
 // PRIVACY COMMANDS
 // ============================================================================
 
+// capability: settings
 #[tauri::command]
 pub async fn privacy_get_mode(
     privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
@@ -224,10 +847,11 @@ pub async fn privacy_get_mode(
     }))
 }
 
+// capability: settings
 #[tauri::command]
 pub async fn privacy_set_mode(
     mode: String,
-    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+    app: tauri::AppHandle,
 ) -> Result<serde_json::Value, String> {
     let privacy_mode = match mode.as_str() {
         "normal" => StatePrivacyMode::Normal,
@@ -236,9 +860,8 @@ pub async fn privacy_set_mode(
         _ => return Err(format!("Invalid privacy mode: {}", mode)),
     };
 
-    let mut enforcer = privacy_enforcer.lock().unwrap();
-    let policy = enforcer.set_mode(privacy_mode);
-    
+    let policy = privacy::apply_privacy_mode(&app, privacy_mode)?;
+
     Ok(serde_json::json!({
         "mode": format!("{:?}", policy.mode),
         "allowDiskWrites": policy.allow_disk_writes,
@@ -250,10 +873,112 @@ pub async fn privacy_set_mode(
     }))
 }
 
+// Local STUN self-check: would WebRTC expose the public or local IP in the current mode?
+// capability: network
+#[tauri::command]
+pub async fn privacy_leak_test(
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<LeakTestReport, String> {
+    let mode = privacy_enforcer.lock().unwrap().get_policy().mode.clone();
+    Ok(webrtc_guard::leak_test(&mode).await)
+}
+
+// Guard script for the current mode, for the frontend to inject into tab frames
+// capability: browsing
+#[tauri::command]
+pub async fn privacy_webrtc_guard_script(
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<String, String> {
+    let mode = privacy_enforcer.lock().unwrap().get_policy().mode.clone();
+    Ok(webrtc_guard::guard_script(webrtc_guard::WebRtcPolicy::for_mode(&mode)))
+}
+
+// capability: settings
+#[tauri::command]
+pub async fn privacy_webrtc_support() -> Result<PlatformSupport, String> {
+    Ok(webrtc_guard::support())
+}
+
+// Switch the DNS-over-HTTPS provider and save it as dns.provider
+// capability: settings
+#[tauri::command]
+pub async fn doh_set_provider(provider: String, app: tauri::AppHandle) -> Result<DohStatus, String> {
+    let provider = provider.trim().to_lowercase();
+    doh::set_provider(&provider).map_err(|e| e.to_string())?;
+    match config::store().set("dns.provider", serde_json::Value::String(provider)) {
+        Ok(updated) => {
+            let _ = app.emit("config:changed", &updated);
+        }
+        Err(config::ConfigError::NotLoaded) => {}
+        Err(e) => return Err(e.to_string()),
+    }
+    Ok(doh::status())
+}
+
+// capability: settings
+#[tauri::command]
+pub async fn doh_status() -> Result<DohStatus, String> {
+    Ok(doh::status())
+}
+
+// capability: settings
+#[tauri::command]
+pub async fn doh_clear_cache() -> Result<DohStatus, String> {
+    doh::clear_cache();
+    Ok(doh::status())
+}
+
+// Save a proxy URL globally, or for one profile ("" connects that profile directly; None removes the override)
+// capability: settings
+#[tauri::command]
+pub async fn proxy_set(
+    url: Option<String>,
+    profile: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<AppConfig, String> {
+    let url = url.map(|u| u.trim().to_string());
+    let (key, value) = match profile {
+        Some(profile) => {
+            let mut profiles = config::current().proxy.profiles;
+            match url {
+                Some(url) => profiles.insert(profile, url),
+                None => profiles.remove(&profile),
+            };
+            ("proxy.profiles", serde_json::to_value(profiles).map_err(|e| e.to_string())?)
+        }
+        None => ("proxy.url", url.filter(|u| !u.is_empty()).map(serde_json::Value::String).unwrap_or_default()),
+    };
+    let updated = config::store().set(key, value).map_err(|e| e.to_string())?;
+    config::apply(&app, &updated);
+    let _ = app.emit("config:changed", &updated);
+    Ok(updated)
+}
+
+// Check a proxy (the given URL, or the active profile's) by fetching the exit IP through it
+// capability: network
+#[tauri::command]
+pub async fn proxy_test(proxy: Option<String>, app: tauri::AppHandle) -> Result<ProxyTestReport, String> {
+    Ok(proxy::test(&app, &config::current().proxy, proxy).await)
+}
+
+// Outbound backend requests matching the filter, with a per-host summary
+// capability: system
+#[tauri::command]
+pub async fn network_audit_list(filter: Option<AuditFilter>) -> Result<AuditList, String> {
+    network_audit::list(&filter.unwrap_or_default()).map_err(|e| e.to_string())
+}
+
+// capability: system
+#[tauri::command]
+pub async fn network_audit_clear() -> Result<usize, String> {
+    network_audit::clear().map_err(|e| e.to_string())
+}
+
 // ============================================================================
 // DATABASE COMMANDS
 // ============================================================================
 
+// capability: history
 #[tauri::command]
 pub async fn db_save_page(
     url: String,
@@ -261,6 +986,8 @@ pub async fn db_save_page(
     content: String,
     language: Option<String>,
     db: tauri::State<'_, Database>,
+    local_index: tauri::State<'_, LocalIndex>,
+    semantic_history: tauri::State<'_, SemanticHistory>,
     privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
 ) -> Result<(), String> {
     // PRIVACY ENFORCEMENT: Check if cache is allowed
@@ -273,6 +1000,12 @@ pub async fn db_save_page(
     }
     drop(enforcer); // Release lock before database operation
 
+    // Auto-detect language when the frontend doesn't supply one
+    let language = language.or_else(|| {
+        let detection = language::detect_language(&content);
+        (detection.language != "und").then_some(detection.language)
+    });
+
     let cache = PageCache {
         id: uuid::Uuid::new_v4().to_string(),
         url,
@@ -286,9 +1019,12 @@ pub async fn db_save_page(
         language: language,
     };
     db.save_page(&cache).map_err(|e| e.to_string())?;
+    local_index.page_saved(&cache);
+    semantic_history.visited(&cache.url);
     Ok(())
 }
 
+// capability: history
 #[tauri::command]
 pub async fn db_get_page(
     url: String,
@@ -301,6 +1037,7 @@ pub async fn db_get_page(
     }
 }
 
+// capability: history
 #[tauri::command]
 pub async fn db_search(
     query: String,
@@ -319,11 +1056,14 @@ pub async fn db_search(
     Ok(results.into_iter().map(|r| serde_json::to_value(r).unwrap()).collect())
 }
 
+// capability: history
 #[tauri::command]
 pub async fn db_add_history(
     url: String,
     title: String,
     db: tauri::State<'_, Database>,
+    semantic_history: tauri::State<'_, SemanticHistory>,
+    feeds: tauri::State<'_, Feeds>,
     privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
 ) -> Result<(), String> {
     // PRIVACY ENFORCEMENT: Check if history is allowed
@@ -334,9 +1074,12 @@ pub async fn db_add_history(
     drop(enforcer); // Release lock before database operation
 
     db.add_history(&url, &title).map_err(|e| e.to_string())?;
+    semantic_history.visited(&url);
+    feeds.visited(&url);
     Ok(())
 }
 
+// capability: history
 #[tauri::command]
 pub async fn db_get_history(
     limit: Option<usize>,
@@ -346,6 +1089,7 @@ pub async fn db_get_history(
     Ok(history.into_iter().map(|h| serde_json::to_value(h).unwrap()).collect())
 }
 
+// capability: history
 #[tauri::command]
 pub async fn db_clear_history(
     db: tauri::State<'_, Database>,
@@ -354,6 +1098,7 @@ pub async fn db_clear_history(
     Ok(())
 }
 
+// capability: history
 #[tauri::command]
 pub async fn db_search_history(
     query: String,
@@ -363,6 +1108,7 @@ pub async fn db_search_history(
     Ok(history.into_iter().map(|h| serde_json::to_value(h).unwrap()).collect())
 }
 
+// capability: history
 #[tauri::command]
 pub async fn db_delete_history_url(
     url: String,
@@ -373,10 +1119,622 @@ pub async fn db_delete_history_url(
 }
 
 // ============================================================================
-// HISTORY COMMANDS (Frontend API)
+// NOTES COMMANDS
 // ============================================================================
 
-#[tauri::command(name = "history:list")]
+// capability: content
+#[tauri::command]
+pub async fn note_create(
+    content: String,
+    title: Option<String>,
+    url: Option<String>,
+    tags: Option<Vec<String>>,
+    db: tauri::State<'_, Database>,
+    local_index: tauri::State<'_, LocalIndex>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<Note, String> {
+    privacy_enforcer
+        .lock()
+        .unwrap()
+        .enforce_disk_write()
+        .map_err(|e| e.to_string())?;
+
+    let note = Note::new(content, title, url, tags.unwrap_or_default());
+    db.save_note(&note).map_err(|e| e.to_string())?;
+    local_index.note_saved(&note);
+    Ok(db.get_note(&note.id).map_err(|e| e.to_string())?.unwrap_or(note))
+}
+
+// capability: content
+#[tauri::command]
+pub async fn note_update(
+    id: String,
+    content: Option<String>,
+    title: Option<String>,
+    url: Option<String>,
+    tags: Option<Vec<String>>,
+    db: tauri::State<'_, Database>,
+    local_index: tauri::State<'_, LocalIndex>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<Note, String> {
+    privacy_enforcer
+        .lock()
+        .unwrap()
+        .enforce_disk_write()
+        .map_err(|e| e.to_string())?;
+
+    let mut note = db
+        .get_note(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Note {} not found", id))?;
+
+    if let Some(content) = content {
+        note.language = language::detect_language(&content).language;
+        note.content = content;
+    }
+    // Empty strings clear the optional fields
+    if let Some(title) = title {
+        note.title = Some(title).filter(|t| !t.trim().is_empty());
+    }
+    if let Some(url) = url {
+        note.url = Some(url).filter(|u| !u.trim().is_empty());
+    }
+    if let Some(tags) = tags {
+        note.tags = notes::normalize_tags(tags);
+    }
+    note.updated_at = chrono::Utc::now().timestamp();
+
+    db.save_note(&note).map_err(|e| e.to_string())?;
+    local_index.note_saved(&note);
+    Ok(db.get_note(&id).map_err(|e| e.to_string())?.unwrap_or(note))
+}
+
+// capability: content
+#[tauri::command]
+pub async fn note_delete(
+    id: String,
+    db: tauri::State<'_, Database>,
+    local_index: tauri::State<'_, LocalIndex>,
+) -> Result<(), String> {
+    db.delete_note(&id).map_err(|e| e.to_string())?;
+    local_index.removed(IndexKind::Notes, &id);
+    Ok(())
+}
+
+// capability: content
+#[tauri::command]
+pub async fn note_get(
+    id: String,
+    db: tauri::State<'_, Database>,
+) -> Result<Option<Note>, String> {
+    db.get_note(&id).map_err(|e| e.to_string())
+}
+
+// capability: content
+#[tauri::command]
+pub async fn note_list(
+    tag: Option<String>,
+    limit: Option<usize>,
+    db: tauri::State<'_, Database>,
+) -> Result<Vec<Note>, String> {
+    let tag = tag.map(|t| t.trim().trim_start_matches('#').to_lowercase());
+    db.list_notes(tag.as_deref(), limit.unwrap_or(200)).map_err(|e| e.to_string())
+}
+
+// capability: content
+#[tauri::command]
+pub async fn note_search(
+    query: String,
+    limit: Option<usize>,
+    db: tauri::State<'_, Database>,
+) -> Result<Vec<Note>, String> {
+    match notes::fts_query(&query) {
+        Some(fts_query) => db
+            .search_notes(&fts_query, limit.unwrap_or(50))
+            .map_err(|e| e.to_string()),
+        None => Ok(Vec::new()),
+    }
+}
+
+// capability: content
+#[tauri::command]
+pub async fn note_tags(
+    db: tauri::State<'_, Database>,
+) -> Result<Vec<serde_json::Value>, String> {
+    let tags = db.list_note_tags().map_err(|e| e.to_string())?;
+    Ok(tags
+        .into_iter()
+        .map(|(tag, count)| serde_json::json!({ "tag": tag, "count": count }))
+        .collect())
+}
+
+// Spell-check a note against its language's dictionary (or `language`), offline
+// capability: content
+#[tauri::command]
+pub async fn note_spellcheck(
+    id: String,
+    language: Option<String>,
+    db: tauri::State<'_, Database>,
+    app: tauri::AppHandle,
+) -> Result<SpellReport, String> {
+    let note = db
+        .get_note(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Note {} not found", id))?;
+    let language = language.unwrap_or(note.language);
+    tauri::async_runtime::spawn_blocking(move || app.state::<SpellChecker>().check(&note.content, Some(&language)))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+// Apply spelling fixes to a note. Without `fixes`, every misspelling with a single suggestion is corrected.
+// Fixes whose word has moved since the check are skipped.
+// capability: content
+#[tauri::command]
+pub async fn note_apply_spelling(
+    id: String,
+    fixes: Option<Vec<SpellingFix>>,
+    db: tauri::State<'_, Database>,
+    local_index: tauri::State<'_, LocalIndex>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+    app: tauri::AppHandle,
+) -> Result<Note, String> {
+    privacy_enforcer
+        .lock()
+        .unwrap()
+        .enforce_disk_write()
+        .map_err(|e| e.to_string())?;
+
+    let mut note = db
+        .get_note(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Note {} not found", id))?;
+    let fixes = match fixes {
+        Some(fixes) => fixes,
+        None => {
+            let (content, language) = (note.content.clone(), note.language.clone());
+            let report = tauri::async_runtime::spawn_blocking(move || app.state::<SpellChecker>().check(&content, Some(&language)))
+                .await
+                .map_err(|e| e.to_string())?
+                .map_err(|e| e.to_string())?;
+            spellcheck::confident_fixes(&report)
+        }
+    };
+    let (content, applied) = spellcheck::apply_fixes(&note.content, &fixes);
+    if applied == 0 {
+        return Ok(note);
+    }
+    note.content = content;
+    note.updated_at = chrono::Utc::now().timestamp();
+    db.save_note(&note).map_err(|e| e.to_string())?;
+    local_index.note_saved(&note);
+    tracing::info!(target: "app", "Spellcheck: Applied {} of {} fixes to note {}", applied, fixes.len(), id);
+    Ok(db.get_note(&id).map_err(|e| e.to_string())?.unwrap_or(note))
+}
+
+// ============================================================================
+// HIGHLIGHT COMMANDS
+// ============================================================================
+
+// capability: content
+#[tauri::command]
+pub async fn highlight_add(
+    url: String,
+    selectors: Vec<Selector>,
+    color: Option<String>,
+    note: Option<String>,
+    db: tauri::State<'_, Database>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<Highlight, String> {
+    privacy_enforcer
+        .lock()
+        .unwrap()
+        .enforce_disk_write()
+        .map_err(|e| e.to_string())?;
+
+    let highlight = Highlight::new(url, selectors, color, note).map_err(|e| e.to_string())?;
+    db.save_highlight(&highlight).map_err(|e| e.to_string())?;
+    Ok(highlight)
+}
+
+// capability: content
+#[tauri::command]
+pub async fn highlight_list_for_url(
+    url: String,
+    db: tauri::State<'_, Database>,
+) -> Result<Vec<Highlight>, String> {
+    let url = notes::normalize_url(&url).unwrap_or(url);
+    db.list_highlights_for_url(&url).map_err(|e| e.to_string())
+}
+
+// capability: content
+#[tauri::command]
+pub async fn highlight_delete(
+    id: String,
+    db: tauri::State<'_, Database>,
+) -> Result<(), String> {
+    db.delete_highlight(&id).map_err(|e| e.to_string())
+}
+
+// Highlights for a page plus anchoring status, verified against the cached page text when available
+// capability: content
+#[tauri::command]
+pub async fn highlight_resolve(
+    url: String,
+    db: tauri::State<'_, Database>,
+) -> Result<Vec<ResolvedHighlight>, String> {
+    let key = notes::normalize_url(&url).unwrap_or_else(|| url.clone());
+    let highlights = db.list_highlights_for_url(&key).map_err(|e| e.to_string())?;
+    let page = db.get_page(&url).map_err(|e| e.to_string())?;
+    let page_text = page.as_ref().map(|p| p.content.as_str());
+
+    Ok(highlights
+        .into_iter()
+        .map(|h| highlights::resolve(h, page_text))
+        .collect())
+}
+
+// ============================================================================
+// SELECTION ACTION COMMANDS
+// ============================================================================
+
+// Context-menu actions on selected text: "highlight" and "note" finish here, "summarize",
+// "translate" and "research" return a running job and finish with "selection-action:done"
+// capability: content
+#[tauri::command]
+pub async fn selection_action(
+    action: String,
+    payload: SelectionPayload,
+    app: tauri::AppHandle,
+    db: tauri::State<'_, Database>,
+    local_index: tauri::State<'_, LocalIndex>,
+    app_state: tauri::State<'_, AppState>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<SelectionJob, String> {
+    let action = SelectionAction::parse(&action).map_err(|e| e.to_string())?;
+    selection::validate(&payload).map_err(|e| e.to_string())?;
+
+    let started = std::time::Instant::now();
+    let result = match action {
+        SelectionAction::Highlight | SelectionAction::Note => {
+            privacy_enforcer
+                .lock()
+                .unwrap()
+                .enforce_disk_write()
+                .map_err(|e| e.to_string())?;
+            let saved = if action == SelectionAction::Highlight {
+                selection::highlight(&db, &payload).map(|h| serde_json::to_value(h).unwrap_or_default())
+            } else {
+                selection::note(&db, &local_index, &payload).map(|n| serde_json::to_value(n).unwrap_or_default())
+            };
+            saved.map_err(|e| e.to_string())?
+        }
+        SelectionAction::Summarize | SelectionAction::Translate | SelectionAction::Research => {
+            let language = app_state.settings.lock().unwrap().language.clone();
+            return Ok(selection::spawn_job(app, action, payload, language));
+        }
+    };
+
+    Ok(SelectionJob {
+        job_id: format!("sel-{}", uuid::Uuid::new_v4()),
+        action,
+        status: JobStatus::Done,
+        result: Some(result),
+        error: None,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+// ============================================================================
+// READER MODE COMMANDS
+// ============================================================================
+
+// capability: content
+#[tauri::command]
+pub async fn reader_mode(
+    url_or_html: String,
+    base_url: Option<String>,
+    force_refresh: Option<bool>,
+    db: tauri::State<'_, Database>,
+    local_index: tauri::State<'_, LocalIndex>,
+    semantic_history: tauri::State<'_, SemanticHistory>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<ReaderView, String> {
+    let trimmed = url_or_html.trim();
+    let is_url = trimmed.starts_with("http://") || trimmed.starts_with("https://");
+    let page_url = if is_url { Some(trimmed.to_string()) } else { base_url.clone() };
+
+    // PRIVACY ENFORCEMENT: Reader views are only cached when the cache is allowed
+    let can_cache = {
+        let enforcer = privacy_enforcer.lock().unwrap();
+        enforcer.can_use_cache() && enforcer.can_write_to_disk()
+    };
+
+    // Offline, a cached copy beats a refresh that can't happen. Supplied HTML is always rendered.
+    let offline = is_url && connectivity::is_offline();
+    if is_url && can_cache && (!force_refresh.unwrap_or(false) || offline) {
+        if let Some(url) = page_url.as_deref() {
+            if let Ok(Some(json)) = db.get_reader_view(url) {
+                if let Ok(mut view) = serde_json::from_str::<ReaderView>(&json) {
+                    view.cached = true;
+                    return Ok(view);
+                }
+            }
+        }
+    }
+
+    if offline {
+        return Err("Offline, and this page has no cached copy".to_string());
+    }
+    let page = if is_url {
+        extractor::extract_url(trimmed).await.map_err(|e| e.to_string())?
+    } else {
+        extractor::extract(&url_or_html, base_url.as_deref())
+    };
+    if page.word_count == 0 {
+        return Err("No readable content found".to_string());
+    }
+
+    let view = reader::render(&page);
+
+    if let (true, Some(url)) = (can_cache, page_url) {
+        let cache = PageCache {
+            id: uuid::Uuid::new_v4().to_string(),
+            url: url.clone(),
+            title: page.title.clone(),
+            content: page.text.clone(),
+            html: None,
+            cached_at: chrono::Utc::now().timestamp(),
+            language: page.language.clone().or_else(|| {
+                let detection = language::detect_language(&page.text);
+                (detection.language != "und").then_some(detection.language)
+            }),
+        };
+        db.save_page(&cache).map_err(|e| e.to_string())?;
+        local_index.page_saved(&cache);
+        semantic_history.visited(&url);
+        let json = serde_json::to_string(&view).map_err(|e| e.to_string())?;
+        db.save_reader_view(&url, &json).map_err(|e| e.to_string())?;
+    }
+
+    Ok(view)
+}
+
+// ============================================================================
+// ARCHIVE COMMANDS
+// ============================================================================
+
+// capability: history
+#[tauri::command]
+pub async fn archive_page(
+    url: String,
+    tab_id: Option<String>,
+    app: tauri::AppHandle,
+    db: tauri::State<'_, Database>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<ArchiveEntry, String> {
+    // PRIVACY ENFORCEMENT: Archives are files on disk
+    privacy_enforcer
+        .lock()
+        .unwrap()
+        .enforce_disk_write()
+        .map_err(|e| e.to_string())?;
+
+    let snapshot = archive::snapshot_page(&url).await.map_err(|e| e.to_string())?;
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let path = app_data_path(&app, "archives")?.join(format!("{}.html", id));
+    let size = archive::write_snapshot(&path, &snapshot).map_err(|e| e.to_string())?;
+
+    let entry = ArchiveEntry {
+        id,
+        url,
+        title: snapshot.title,
+        tab_id,
+        file_path: path.to_string_lossy().to_string(),
+        size_bytes: size as i64,
+        resource_count: snapshot.resource_count as i64,
+        created_at: chrono::Utc::now().timestamp(),
+    };
+    db.save_archive(&entry).map_err(|e| e.to_string())?;
+
+    if snapshot.failed_resources > 0 {
+        tracing::warn!(target: "app", "Archive: {} subresources could not be inlined for {}", snapshot.failed_resources, entry.url);
+    }
+
+    Ok(entry)
+}
+
+// capability: history
+#[tauri::command]
+pub async fn archive_list(
+    limit: Option<usize>,
+    db: tauri::State<'_, Database>,
+) -> Result<Vec<ArchiveEntry>, String> {
+    db.list_archives(limit.unwrap_or(200)).map_err(|e| e.to_string())
+}
+
+// capability: history
+#[tauri::command]
+pub async fn archive_open(
+    id: String,
+    db: tauri::State<'_, Database>,
+) -> Result<serde_json::Value, String> {
+    let entry = db
+        .get_archive(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Archive {} not found", id))?;
+    let html = std::fs::read_to_string(&entry.file_path)
+        .map_err(|e| format!("Failed to read archive file: {}", e))?;
+
+    Ok(serde_json::json!({
+        "archive": entry,
+        "html": html,
+    }))
+}
+
+// capability: history
+#[tauri::command]
+pub async fn archive_delete(
+    id: String,
+    db: tauri::State<'_, Database>,
+) -> Result<(), String> {
+    if let Some(entry) = db.get_archive(&id).map_err(|e| e.to_string())? {
+        let _ = std::fs::remove_file(&entry.file_path);
+    }
+    db.delete_archive(&id).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// ============================================================================
+// RESEARCH SESSION COMMANDS
+// ============================================================================
+
+// capability: content
+#[tauri::command]
+pub async fn research_session_save(
+    session: ResearchSession,
+    db: tauri::State<'_, Database>,
+    local_index: tauri::State<'_, LocalIndex>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<(), String> {
+    privacy_enforcer
+        .lock()
+        .unwrap()
+        .enforce_disk_write()
+        .map_err(|e| e.to_string())?;
+    db.save_research_session(&session).map_err(|e| e.to_string())?;
+    local_index.session_saved(&session);
+    Ok(())
+}
+
+// capability: content
+#[tauri::command]
+pub async fn research_session_get(
+    id: String,
+    db: tauri::State<'_, Database>,
+) -> Result<Option<ResearchSession>, String> {
+    db.get_research_session(&id).map_err(|e| e.to_string())
+}
+
+// capability: content
+#[tauri::command]
+pub async fn research_session_list(
+    limit: Option<usize>,
+    db: tauri::State<'_, Database>,
+) -> Result<Vec<ResearchSession>, String> {
+    db.list_research_sessions(limit.unwrap_or(100)).map_err(|e| e.to_string())
+}
+
+// capability: content
+#[tauri::command]
+pub async fn research_session_delete(
+    id: String,
+    db: tauri::State<'_, Database>,
+    local_index: tauri::State<'_, LocalIndex>,
+) -> Result<(), String> {
+    db.delete_research_session(&id).map_err(|e| e.to_string())?;
+    local_index.removed(IndexKind::Sessions, &id);
+    Ok(())
+}
+
+// capability: files
+#[tauri::command]
+pub async fn session_export(
+    session_id: String,
+    format: String,
+    path: Option<String>,
+    app: tauri::AppHandle,
+    db: tauri::State<'_, Database>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<serde_json::Value, String> {
+    let format = ExportFormat::parse(&format).map_err(|e| e.to_string())?;
+    let session = db
+        .get_research_session(&session_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Research session {} not found", session_id))?;
+    let contents = research::export(&session, format);
+
+    // An explicit path (from a save dialog) is honoured; otherwise write under exports/
+    privacy_enforcer
+        .lock()
+        .unwrap()
+        .enforce_disk_write()
+        .map_err(|e| e.to_string())?;
+    let path = match path {
+        Some(path) => std::path::PathBuf::from(files::resolve(&path).map_err(|e| e.to_string())?),
+        None => app_data_path(&app, "exports")?.join(format!("{}.{}", session.id, format.extension())),
+    };
+    std::fs::write(&path, &contents).map_err(|e| format!("Failed to write export: {}", e))?;
+
+    Ok(serde_json::json!({
+        "path": path.to_string_lossy(),
+        "format": format.extension(),
+        "content": contents,
+    }))
+}
+
+// BibTeX ("bibtex") or RIS ("ris") entries for the session's sources, one per distinct page.
+// fetch_metadata (default true) reads authors/dates from the pages; path comes from a save dialog.
+// capability: files
+#[tauri::command]
+pub async fn citations_export(
+    session_id: String,
+    format: String,
+    path: Option<String>,
+    fetch_metadata: Option<bool>,
+    app: tauri::AppHandle,
+    db: tauri::State<'_, Database>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<CitationExport, String> {
+    let format = CitationFormat::parse(&format).map_err(|e| e.to_string())?;
+    let session = db
+        .get_research_session(&session_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Research session {} not found", session_id))?;
+    privacy_enforcer.lock().unwrap().enforce_disk_write().map_err(|e| e.to_string())?;
+
+    let (citations, duplicates) = citations::collect(&session, fetch_metadata.unwrap_or(true)).await;
+    if citations.is_empty() {
+        return Err(citations::CitationError::NoSources.to_string());
+    }
+    let content = citations::render(&citations, format);
+    let path = match path {
+        Some(path) => std::path::PathBuf::from(files::resolve(&path).map_err(|e| e.to_string())?),
+        None => app_data_path(&app, "exports")?.join(format!("{}.{}", session.id, format.extension())),
+    };
+    std::fs::write(&path, &content).map_err(|e| format!("Failed to write citations: {}", e))?;
+
+    Ok(CitationExport { path: path.to_string_lossy().to_string(), format, citations, duplicates, content })
+}
+
+// capability: files
+#[tauri::command]
+pub async fn session_import(
+    path: String,
+    db: tauri::State<'_, Database>,
+    local_index: tauri::State<'_, LocalIndex>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<ResearchSession, String> {
+    let path = files::resolve(&path).map_err(|e| e.to_string())?;
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let session = research::import(&contents).map_err(|e| e.to_string())?;
+
+    if privacy_enforcer.lock().unwrap().can_write_to_disk() {
+        db.save_research_session(&session).map_err(|e| e.to_string())?;
+        local_index.session_saved(&session);
+    }
+    Ok(session)
+}
+
+// ============================================================================
+// HISTORY COMMANDS (Frontend API)
+// ============================================================================
+
+// capability: history
+#[tauri::command(name = "history:list")]
 pub async fn history_list(
     db: tauri::State<'_, Database>,
 ) -> Result<Vec<serde_json::Value>, String> {
@@ -392,6 +1750,7 @@ pub async fn history_list(
     }).collect())
 }
 
+// capability: history
 #[tauri::command(name = "history:clear")]
 pub async fn history_clear(
     db: tauri::State<'_, Database>,
@@ -400,6 +1759,7 @@ pub async fn history_clear(
     Ok(())
 }
 
+// capability: history
 #[tauri::command(name = "history:search")]
 pub async fn history_search(
     query: String,
@@ -417,6 +1777,7 @@ pub async fn history_search(
     }).collect())
 }
 
+// capability: history
 #[tauri::command(name = "history:deleteUrl")]
 pub async fn history_delete_url(
     url: String,
@@ -426,21 +1787,101 @@ pub async fn history_delete_url(
     Ok(())
 }
 
-// ============================================================================
-// DOWNLOADS COMMANDS (Frontend API)
-// ============================================================================
-
-#[tauri::command(name = "downloads:list")]
-pub async fn downloads_list(
+// Group recent history into topics (embeddings + k-means, labelled by the LLM when available)
+// capability: history
+#[tauri::command]
+pub async fn history_clusters(
+    days: Option<u32>,
     db: tauri::State<'_, Database>,
-) -> Result<Vec<serde_json::Value>, String> {
-    let downloads = db.get_downloads(None).map_err(|e| e.to_string())?;
-    Ok(downloads.into_iter().map(|(id, url, filename, path, status, progress, received_bytes, total_bytes, created_at, completed_at, checksum, safety_status)| {
-        serde_json::json!({
-            "id": id,
-            "url": url,
-            "filename": filename,
-            "path": path,
+    ai_service: tauri::State<'_, AIService>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<Vec<HistoryCluster>, String> {
+    let since = chrono::Utc::now().timestamp() - days.unwrap_or(7) as i64 * 86_400;
+    let history = db.get_history_since(since, 300).map_err(|e| e.to_string())?;
+    if history.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Title plus the start of the cached page text, when we have it
+    let mut entries = Vec::with_capacity(history.len());
+    let mut items = Vec::with_capacity(history.len());
+    for (url, title, visited_at) in history {
+        let snippet = db
+            .get_page(&url)
+            .ok()
+            .flatten()
+            .map(|p| p.content.chars().take(500).collect::<String>())
+            .unwrap_or_default();
+        let text = format!("{}\n{}\n{}", title, url, snippet);
+        items.push((embeddings::content_key(&text), text));
+        entries.push(HistoryEntry { url, title, visited_at });
+    }
+
+    let can_cache = privacy_enforcer.lock().unwrap().can_use_cache();
+    let embedded = if can_cache {
+        embeddings::embed_cached(&db, &items).await
+    } else {
+        let texts: Vec<String> = items.into_iter().map(|(_, t)| t).collect();
+        embeddings::embed(&texts).await
+    };
+
+    let assignments = clustering::cluster(&embedded.vectors);
+    let mut clusters = clustering::group(entries, &assignments);
+
+    if clusters.len() > 1 && ai_service.is_available() {
+        match ai_service.complete_task("history_cluster_labels", &clustering::label_prompt(&clusters)).await {
+            Ok(response) => {
+                let labels = clustering::parse_labels(&response, clusters.len());
+                for (cluster, label) in clusters.iter_mut().zip(labels) {
+                    if let Some(label) = label {
+                        cluster.label = label;
+                    }
+                }
+            }
+            Err(e) => tracing::warn!(target: "app", "History: Cluster labelling failed, keeping keyword labels: {}", e),
+        }
+    }
+
+    Ok(clusters)
+}
+
+// History entries ranked by meaning ("that article about battery degradation") blended with frecency
+// capability: history
+#[tauri::command]
+pub async fn history_semantic_search(
+    query: String,
+    k: Option<usize>,
+    db: tauri::State<'_, Database>,
+) -> Result<SemanticHistoryResponse, String> {
+    semantic_history::search(&db, &query, k).await.map_err(|e| e.to_string())
+}
+
+// Most frecent sites (one page per host) for the new-tab page
+// capability: history
+#[tauri::command]
+pub async fn history_top_sites(
+    limit: Option<usize>,
+    db: tauri::State<'_, Database>,
+) -> Result<Vec<TopSite>, String> {
+    frecency::top_sites(&db, limit.unwrap_or(8).clamp(1, 50)).map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// DOWNLOADS COMMANDS (Frontend API)
+// ============================================================================
+
+// capability: history
+#[tauri::command(name = "downloads:list")]
+pub async fn downloads_list(
+    db: tauri::State<'_, Database>,
+) -> Result<Vec<serde_json::Value>, String> {
+    let downloads = db.get_downloads(None).map_err(|e| e.to_string())?;
+    Ok(downloads.into_iter().map(|(id, url, filename, path, status, progress, received_bytes, total_bytes, created_at, completed_at, checksum, safety_status, verification)| {
+        serde_json::json!({
+            "id": id,
+            "url": url,
+            "filename": filename,
+            "path": path,
             "status": status,
             "progress": progress,
             "receivedBytes": received_bytes,
@@ -449,10 +1890,12 @@ pub async fn downloads_list(
             "completedAt": completed_at.map(|t| t * 1000),
             "checksum": checksum,
             "safety": safety_status.map(|s| serde_json::json!({"status": s})),
+            "verification": verification.and_then(|json| serde_json::from_str::<serde_json::Value>(&json).ok()),
         })
     }).collect())
 }
 
+// capability: open-external
 #[tauri::command(name = "downloads:openFile")]
 pub async fn downloads_open_file(path: String) -> Result<(), String> {
     use std::process::Command;
@@ -484,6 +1927,7 @@ pub async fn downloads_open_file(path: String) -> Result<(), String> {
     Ok(())
 }
 
+// capability: open-external
 #[tauri::command(name = "downloads:showInFolder")]
 pub async fn downloads_show_in_folder(path: String) -> Result<(), String> {
     use std::path::PathBuf;
@@ -517,160 +1961,2464 @@ pub async fn downloads_show_in_folder(path: String) -> Result<(), String> {
             .spawn()
             .map_err(|e| format!("Failed to show in folder: {}", e))?;
     }
-    
-    Ok(())
+    
+    Ok(())
+}
+
+// capability: history
+#[tauri::command(name = "downloads:getQueue")]
+pub async fn downloads_get_queue(
+    db: tauri::State<'_, Database>,
+) -> Result<serde_json::Value, String> {
+    // Count active and queued downloads
+    let downloads = db.get_downloads(None).map_err(|e| e.to_string())?;
+    let active = downloads.iter().filter(|d| d.4 == "downloading" || d.4 == "verifying").count();
+    let queued = downloads.iter().filter(|d| d.4 == "pending").count();
+    
+    Ok(serde_json::json!({
+        "active": active,
+        "queued": queued,
+        "maxConcurrent": 3,
+    }))
+}
+
+// Check (or re-check) a finished download against a checksum and/or detached signature
+// capability: files
+#[tauri::command(name = "downloads:verify")]
+pub async fn downloads_verify(id: String, verify: VerifyRequest, app: tauri::AppHandle) -> Result<DownloadVerification, String> {
+    verify::verify_download(app, id, verify).await.map_err(|e| e.to_string())
+}
+
+// Compare a file's digest with an expected hex hash (or "sha256:<hex>", or a SHA256SUMS line).
+// The algorithm is inferred from the digest length when not given.
+// capability: files
+#[tauri::command]
+pub async fn verify_file(path: String, expected_hash: String, algo: Option<HashAlgo>) -> Result<ChecksumCheck, String> {
+    let path = files::resolve(&path).map_err(|e| e.to_string())?;
+    tauri::async_runtime::spawn_blocking(move || verify::verify_file(std::path::Path::new(&path), &expected_hash, algo))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+// Check a detached minisign or GPG signature; sig and key are the text or a path to it.
+// Without a key, GPG uses the user's keyring.
+// capability: files
+#[tauri::command]
+pub async fn verify_signature(path: String, sig: String, key: Option<String>, app: tauri::AppHandle) -> Result<SignatureCheck, String> {
+    let path = files::resolve(&path).map_err(|e| e.to_string())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        verify::verify_signature(&app, std::path::Path::new(&path), &sig, key.as_deref())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+// capability: history
+#[tauri::command(name = "downloads:save")]
+pub async fn downloads_save(
+    id: String,
+    url: String,
+    filename: Option<String>,
+    path: Option<String>,
+    status: String,
+    progress: f64,
+    received_bytes: i64,
+    total_bytes: Option<i64>,
+    checksum: Option<String>,
+    safety_status: Option<String>,
+    verify: Option<VerifyRequest>,
+    app: tauri::AppHandle,
+    db: tauri::State<'_, Database>,
+) -> Result<(), String> {
+    let finished = status == "completed" && db.download_status(&id).ok().flatten().as_deref() != Some("completed");
+    db.save_download(
+        &id,
+        &url,
+        filename.as_deref(),
+        path.as_deref(),
+        &status,
+        progress,
+        received_bytes,
+        total_bytes,
+        checksum.as_deref(),
+        safety_status.as_deref(),
+    ).map_err(|e| e.to_string())?;
+    if finished {
+        let name = filename.clone().unwrap_or_else(|| url.clone());
+        notifications::post(&app, NotificationRequest {
+            title: "Download complete".to_string(),
+            body: name,
+            actions: vec![
+                NotificationAction { id: "open".to_string(), title: "Open".to_string() },
+                NotificationAction { id: "show-in-folder".to_string(), title: "Show in folder".to_string() },
+            ],
+            kind: NotificationKind::Download,
+            payload: Some(serde_json::json!({ "downloadId": id, "path": path })),
+            ..Default::default()
+        });
+    }
+    // Optional post-download step; the result lands on the row and in "downloads:verified"
+    if let Some(request) = verify.filter(|_| finished) {
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = verify::verify_download(app, id, request).await {
+                tracing::warn!(target: "app", "Verify: {}", e);
+            }
+        });
+    }
+    Ok(())
+}
+
+// capability: history
+#[tauri::command(name = "downloads:delete")]
+pub async fn downloads_delete(
+    id: String,
+    db: tauri::State<'_, Database>,
+) -> Result<(), String> {
+    db.delete_download(&id).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// ============================================================================
+// FEED COMMANDS
+// ============================================================================
+
+// Subscribe to an RSS/Atom URL, or to the feed a page links to
+// capability: network
+#[tauri::command]
+pub async fn feed_subscribe(
+    url: String,
+    db: tauri::State<'_, Database>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<Feed, String> {
+    if !privacy_enforcer.lock().unwrap().can_write_to_disk() {
+        return Err("Disk writes blocked in Ghost mode".to_string());
+    }
+    feeds::subscribe(&db, &url).await.map_err(|e| e.to_string())
+}
+
+// capability: content
+#[tauri::command]
+pub async fn feed_unsubscribe(feed_id: String, db: tauri::State<'_, Database>) -> Result<bool, String> {
+    db.feed_delete(&feed_id).map_err(|e| e.to_string())
+}
+
+// Subscriptions with unread counts
+// capability: content
+#[tauri::command]
+pub async fn feed_list(db: tauri::State<'_, Database>) -> Result<Vec<Feed>, String> {
+    db.feed_list().map_err(|e| e.to_string())
+}
+
+// Entries newest first, filtered by feed, read state, text, and date
+// capability: content
+#[tauri::command]
+pub async fn feed_list_entries(
+    filter: Option<EntryFilter>,
+    db: tauri::State<'_, Database>,
+) -> Result<Vec<FeedEntry>, String> {
+    feeds::list_entries(&db, &filter.unwrap_or_default()).map_err(|e| e.to_string())
+}
+
+// Mark entries, or every entry of a feed, read (default) or unread
+// capability: content
+#[tauri::command]
+pub async fn feed_mark_read(
+    entry_ids: Option<Vec<String>>,
+    feed_id: Option<String>,
+    read: Option<bool>,
+    db: tauri::State<'_, Database>,
+) -> Result<usize, String> {
+    db.feed_mark_read(&entry_ids.unwrap_or_default(), feed_id.as_deref(), read.unwrap_or(true))
+        .map_err(|e| e.to_string())
+}
+
+// Refresh one feed, or all of them, now instead of waiting for the background refresh
+// capability: network
+#[tauri::command]
+pub async fn feed_refresh(
+    feed_id: Option<String>,
+    db: tauri::State<'_, Database>,
+) -> Result<Vec<RefreshResult>, String> {
+    let targets = match feed_id {
+        Some(id) => vec![db
+            .feed_get(&id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| FeedError::NotFound(id).to_string())?],
+        None => db.feed_list().map_err(|e| e.to_string())?,
+    };
+    Ok(feeds::refresh_all(&db, targets).await)
+}
+
+// Feeds found on the site of `url` while browsing, marked if already subscribed
+// capability: network
+#[tauri::command]
+pub async fn feed_discovered(url: String, db: tauri::State<'_, Database>) -> Result<Vec<DiscoveredFeed>, String> {
+    feeds::discovered(&db, &url).map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// DIGEST COMMANDS
+// ============================================================================
+
+// Markdown digest of notes, highlights, key pages, and agent answers for a period ("day", "week",
+// "month", or { from, to } in unix seconds); saved as a "digest" note and a research session
+// capability: content
+#[tauri::command]
+pub async fn digest_generate(range: Option<DigestRange>, app: tauri::AppHandle) -> Result<Digest, String> {
+    let range = range.unwrap_or_else(|| DigestRange::Preset("week".to_string()));
+    digest::generate(&app, &range).await.map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// AI COMMANDS
+// ============================================================================
+
+// capability: ai
+#[tauri::command]
+pub async fn ai_complete(
+    prompt: String,
+    ai_service: tauri::State<'_, AIService>,
+) -> Result<String, String> {
+    ai_service.complete(&prompt).map_err(|e| e.to_string())
+}
+
+// Stream a completion as "ai:stream" events ({requestId, token, events}); "ai:stream-end" carries the full text.
+// Events are markdown blocks completed by the token, so views render progressively without re-parsing.
+// capability: ai
+#[tauri::command]
+pub async fn ai_complete_stream(
+    prompt: String,
+    request_id: String,
+    app: tauri::AppHandle,
+    ai_service: tauri::State<'_, AIService>,
+) -> Result<String, String> {
+    let result = ai_service
+        .complete_stream_markdown(&prompt, |chunk| {
+            let _ = app.emit(
+                "ai:stream",
+                serde_json::json!({ "requestId": &request_id, "token": chunk.token, "events": chunk.events }),
+            );
+        })
+        .await
+        .map_err(|e| e.to_string());
+    let _ = app.emit(
+        "ai:stream-end",
+        serde_json::json!({
+            "requestId": &request_id,
+            "text": result.as_ref().ok(),
+            "error": result.as_ref().err(),
+        }),
+    );
+    result
+}
+
+// Block events for a finished markdown document (same shape as the streamed ones)
+// capability: ai
+#[tauri::command]
+pub async fn markdown_segment(text: String) -> Result<Vec<MarkdownEvent>, String> {
+    Ok(markdown::segment(&text))
+}
+
+// capability: ai
+#[tauri::command]
+pub async fn ai_detect_intent(
+    query: String,
+    ai_service: tauri::State<'_, AIService>,
+) -> Result<String, String> {
+    let intent = ai_service.detect_intent(&query).map_err(|e| e.to_string())?;
+    Ok(format!("{:?}", intent))
+}
+
+// capability: ai
+#[tauri::command]
+pub async fn language_detect(text: String) -> Result<LanguageDetection, String> {
+    Ok(language::detect_language(&text))
+}
+
+// One vector; `model` defaults to the configured embedding model
+// capability: ai
+#[tauri::command]
+pub async fn embed_text(text: String, model: Option<String>) -> Result<Vec<f32>, String> {
+    let model = model.unwrap_or_else(embeddings::default_model);
+    let embedded = embeddings::embed_with(&model, &[text]).await;
+    embedded.vectors.into_iter().next().ok_or_else(|| "No vector returned".to_string())
+}
+
+// Vectors in input order, with the model, source, and dimensions the vector index needs to keep them apart
+// capability: ai
+#[tauri::command]
+pub async fn embed_batch(texts: Vec<String>, model: Option<String>) -> Result<embeddings::Embeddings, String> {
+    if texts.len() > embeddings::MAX_BATCH {
+        return Err(format!("At most {} texts per batch", embeddings::MAX_BATCH));
+    }
+    let model = model.unwrap_or_else(embeddings::default_model);
+    Ok(embeddings::embed_with(&model, &texts).await)
+}
+
+// Plan the question into sub-queries, research each, and synthesize a cited answer.
+// Progress streams as "research:event" ({requestId, event}: plan, step-*, synthesis-token, done/failed).
+// capability: ai
+#[tauri::command]
+pub async fn research_run(
+    query: String,
+    request_id: String,
+    options: Option<ResearchOptions>,
+    app: tauri::AppHandle,
+) -> Result<ResearchReport, String> {
+    let (emitter, tag) = (app.clone(), request_id.clone());
+    let notifier = app.clone();
+    let report = research_agent::run(app, Some(&request_id), query, options.unwrap_or_default(), move |event| {
+        let _ = emitter.emit("research:event", serde_json::json!({ "requestId": &tag, "event": event }));
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    if !notifications::app_focused(&notifier) {
+        notifications::post(&notifier, NotificationRequest {
+            title: "Research finished".to_string(),
+            body: report.query.clone(),
+            actions: vec![NotificationAction { id: "view".to_string(), title: "View".to_string() }],
+            kind: NotificationKind::Job,
+            payload: Some(serde_json::json!({ "requestId": request_id, "runId": report.run_id })),
+            ..Default::default()
+        });
+    }
+    Ok(report)
+}
+
+// Research a question asked in any language against English sources and answer in `answer_lang`
+// (default: the question's language), with a glossary keeping technical terms consistent.
+// Progress streams as "research-multilingual:event" ({requestId, event}: stage, detected, query-translated,
+// research, glossary, answer-token, done/failed).
+// capability: ai
+#[tauri::command]
+pub async fn research_multilingual(
+    query: String,
+    answer_lang: Option<String>,
+    request_id: String,
+    options: Option<ResearchOptions>,
+    app: tauri::AppHandle,
+) -> Result<MultilingualReport, String> {
+    let (emitter, tag) = (app.clone(), request_id.clone());
+    research_multilingual::run(app, Some(&request_id), query, answer_lang, options.unwrap_or_default(), move |event| {
+        let _ = emitter.emit("research-multilingual:event", serde_json::json!({ "requestId": &tag, "event": event }));
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// Summarize a long document: chunks are summarized in parallel, then merged in order.
+// Progress streams as "summarize:event" ({requestId, event}: plan, chunk-*, summary-token, done/failed).
+// capability: ai
+#[tauri::command]
+pub async fn summarize_document(
+    text: String,
+    request_id: String,
+    options: Option<SummaryOptions>,
+    app: tauri::AppHandle,
+) -> Result<DocumentSummary, String> {
+    let (emitter, tag) = (app.clone(), request_id);
+    summarizer::summarize(app, text, options.unwrap_or_default(), move |event| {
+        let _ = emitter.emit("summarize:event", serde_json::json!({ "requestId": &tag, "event": event }));
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// Structured summary: preset is "tldr", "brief", "detailed", "eli5", or "exam-notes";
+// language defaults to the text's. Repeat requests for the same text come from the cache.
+// capability: ai
+#[tauri::command]
+pub async fn summarize_v2(
+    text: String,
+    preset: String,
+    language: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<PresetSummary, String> {
+    let preset = SummaryPreset::parse(&preset).map_err(|e| e.to_string())?;
+    summarizer::summarize_preset(app, text, preset, language).await.map_err(|e| e.to_string())
+}
+
+// Extract a PDF's text page by page and index it for document_ask (not in Private/Ghost mode)
+// capability: files
+#[tauri::command]
+pub async fn process_pdf(
+    path: String,
+    extract_text: Option<bool>,
+    extract_tables: Option<bool>,
+    summarize: Option<bool>,
+    app: tauri::AppHandle,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<ProcessedDocument, String> {
+    let path = files::resolve(&path).map_err(|e| e.to_string())?;
+    let index = privacy_enforcer.lock().unwrap().can_write_to_disk();
+    let options = ProcessOptions { extract_text, extract_tables, summarize };
+    let document = documents::process(&app, &path, &options, index).await.map_err(|e| e.to_string())?;
+    tables::remember(&document.tables);
+    Ok(document)
+}
+
+// Same as process_pdf for DOCX, spreadsheets (one table per sheet), and plain text.
+// Formatting is not kept; extract_formatting is accepted for the frontend's call shape.
+// capability: files
+#[tauri::command]
+pub async fn process_doc(
+    path: String,
+    extract_text: Option<bool>,
+    extract_formatting: Option<bool>,
+    summarize: Option<bool>,
+    app: tauri::AppHandle,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<ProcessedDocument, String> {
+    let path = files::resolve(&path).map_err(|e| e.to_string())?;
+    let _ = extract_formatting;
+    let index = privacy_enforcer.lock().unwrap().can_write_to_disk();
+    let options = ProcessOptions { extract_text, extract_tables: Some(true), summarize };
+    let document = documents::process(&app, &path, &options, index).await.map_err(|e| e.to_string())?;
+    tables::remember(&document.tables);
+    Ok(document)
+}
+
+// Chapters and metadata of an EPUB or plain-text ebook, indexed for document_ask and local search
+// (not in Private/Ghost mode). `summarize` adds a summary per chapter, one model pass each.
+// capability: files
+#[tauri::command]
+pub async fn process_epub(
+    path: String,
+    summarize: Option<bool>,
+    app: tauri::AppHandle,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<ProcessedBook, String> {
+    let path = files::resolve(&path).map_err(|e| e.to_string())?;
+    let index = privacy_enforcer.lock().unwrap().can_write_to_disk();
+    documents::process_book(&app, &path, summarize.unwrap_or(false), index).await.map_err(|e| e.to_string())
+}
+
+// Answer a question about a processed document from its closest chunks.
+// Streams as "document:event" ({requestId, event}: references, answer-token, done/failed).
+// capability: ai
+#[tauri::command]
+pub async fn document_ask(
+    doc_id: String,
+    question: String,
+    request_id: String,
+    top_k: Option<usize>,
+    app: tauri::AppHandle,
+) -> Result<DocumentAnswer, String> {
+    let emitter = app.clone();
+    documents::ask(&app, &doc_id, &question, top_k, move |event| {
+        let _ = emitter.emit("document:event", serde_json::json!({ "requestId": &request_id, "event": event }));
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// capability: ai
+#[tauri::command]
+pub async fn document_delete(
+    doc_id: String,
+    db: tauri::State<'_, Database>,
+    local_index: tauri::State<'_, LocalIndex>,
+) -> Result<bool, String> {
+    let deleted = db.document_delete(&doc_id).map_err(|e| e.to_string())?;
+    local_index.removed(IndexKind::Documents, &doc_id);
+    Ok(deleted)
+}
+
+// Tables from a page URL, raw HTML, or a local PDF/spreadsheet path or file handle
+// capability: files
+#[tauri::command]
+pub async fn extract_tables(source: String, base_url: Option<String>, app: tauri::AppHandle) -> Result<Vec<Table>, String> {
+    let source = files::resolve(&source).map_err(|e| e.to_string())?;
+    let trimmed = source.trim();
+    let found = if trimmed.starts_with("http://") || trimmed.starts_with("https://") {
+        extractor::extract_url(trimmed).await.map_err(|e| e.to_string())?.tables
+    } else if !trimmed.starts_with('<') && std::path::Path::new(trimmed).is_file() {
+        let options = ProcessOptions { extract_text: Some(false), extract_tables: Some(true), summarize: None };
+        documents::process(&app, trimmed, &options, false).await.map_err(|e| e.to_string())?.tables
+    } else {
+        extractor::extract(&source, base_url.as_deref()).tables
+    };
+    tables::remember(&found);
+    Ok(found)
+}
+
+// CSV text of a table returned earlier by extract_tables, process_pdf, or process_doc
+// capability: content
+#[tauri::command]
+pub fn table_export_csv(table_id: String) -> Result<String, String> {
+    tables::export_csv(&table_id).map_err(|e| e.to_string())
+}
+
+// Dimensions, EXIF, and GPS of a local image; stored for Images mode search
+// capability: files
+#[tauri::command]
+pub async fn image_inspect(path: String, app: tauri::AppHandle) -> Result<ImageInfo, String> {
+    let path = files::resolve(&path).map_err(|e| e.to_string())?;
+    let file = path.clone();
+    let info = tauri::async_runtime::spawn_blocking(move || images::inspect(&file))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+    images::remember(&app, &path, |record| info.apply(record));
+    Ok(info)
+}
+
+// OCR for Images mode; the recognized text is stored so the image is searchable by it
+// capability: files
+#[tauri::command]
+pub async fn image_ocr(
+    path: String,
+    language: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<OcrResult, String> {
+    let path = files::resolve(&path).map_err(|e| e.to_string())?;
+    let handle = app.clone();
+    let file = path.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || capture::ocr(&handle, std::path::Path::new(&file), language.as_deref()))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+    let text = result.text.trim().to_string();
+    images::remember(&app, &path, |record| record.ocr_text = (!text.is_empty()).then_some(text));
+    Ok(result)
+}
+
+// Caption a local image with an Ollama vision model (ai.visionModel, else an installed llava-style model)
+// capability: files
+#[tauri::command]
+pub async fn image_caption(path: String, app: tauri::AppHandle) -> Result<ImageCaption, String> {
+    let path = files::resolve(&path).map_err(|e| e.to_string())?;
+    let caption = images::caption(&path).await.map_err(|e| e.to_string())?;
+    images::remember(&app, &path, |record| {
+        record.caption = Some(caption.caption.clone());
+        record.caption_model = Some(caption.model.clone());
+    });
+    Ok(caption)
+}
+
+// Download every image on a page that passes the size/format filters into `folder`
+// (default: "<host> images" in the downloads folder) and return the manifest
+// capability: files
+#[tauri::command]
+pub async fn images_collect(
+    url: String,
+    min_width: Option<u32>,
+    formats: Option<Vec<String>>,
+    folder: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<images::ImageManifest, String> {
+    let formats = formats
+        .unwrap_or_default()
+        .iter()
+        .map(|name| images::ImageFormat::parse(name).ok_or_else(|| format!("Unknown image format: {}", name)))
+        .collect::<Result<Vec<_>, String>>()?;
+    let folder = match folder {
+        Some(folder) => std::path::PathBuf::from(folder),
+        None => {
+            let downloads = match (app.path().download_dir(), app.try_state::<ProfileManager>()) {
+                (Ok(dir), _) => dir,
+                (Err(_), Some(profiles)) => profiles.downloads_dir(),
+                (Err(e), None) => return Err(format!("No downloads folder: {}", e)),
+            };
+            let host = url::Url::parse(&url).ok().and_then(|u| u.host_str().map(str::to_string)).unwrap_or_else(|| "page".to_string());
+            downloads.join(format!("{} images", host))
+        }
+    };
+    images::collect(&app, &url, &folder, min_width.unwrap_or(0), &formats).await.map_err(|e| e.to_string())
+}
+
+// Score each sentence of a generated answer against the retrieved sources
+// capability: ai
+#[tauri::command]
+pub async fn research_check_grounding(
+    answer: String,
+    sources: Vec<SourceChunk>,
+    request_id: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<GroundingReport, String> {
+    let report = grounding::check(&answer, &sources).await;
+
+    // Streaming research views attach this to their final_summary
+    if let Some(request_id) = request_id {
+        let _ = app.emit(
+            "research:grounding",
+            serde_json::json!({ "requestId": request_id, "grounding": &report }),
+        );
+    }
+    Ok(report)
+}
+
+// Flag sentences of a note that closely copy cached source pages (verbatim or paraphrased)
+// capability: ai
+#[tauri::command]
+pub async fn similarity_check(note_id: String, db: tauri::State<'_, Database>) -> Result<SimilarityReport, String> {
+    similarity::check(&db, &note_id).await.map_err(|e| e.to_string())
+}
+
+// capability: ai
+#[tauri::command]
+pub async fn prompt_list() -> Result<Vec<PromptInfo>, String> {
+    Ok(prompts::registry().list())
+}
+
+// Override a template (or reset it to the built-in default when `template` is empty/absent)
+// capability: settings
+#[tauri::command]
+pub async fn prompt_override(
+    task: String,
+    language: Option<String>,
+    mode: Option<String>,
+    template: Option<String>,
+) -> Result<Vec<PromptInfo>, String> {
+    prompts::registry()
+        .set_override(&task, language.as_deref().unwrap_or("en"), mode.as_deref(), template)
+        .map_err(|e| e.to_string())?;
+    Ok(prompts::registry().list())
+}
+
+// capability: ai
+#[tauri::command]
+pub async fn llm_cache_stats(
+    ai_service: tauri::State<'_, AIService>,
+) -> Result<CacheStats, String> {
+    ai_service
+        .cache()
+        .map(|c| c.stats())
+        .ok_or_else(|| "LLM cache not initialized".to_string())
+}
+
+// capability: ai
+#[tauri::command]
+pub async fn llm_cache_clear(
+    task: Option<String>,
+    ai_service: tauri::State<'_, AIService>,
+) -> Result<usize, String> {
+    let cache = ai_service
+        .cache()
+        .ok_or_else(|| "LLM cache not initialized".to_string())?;
+    cache.clear(task.as_deref())
+}
+
+// ============================================================================
+// TAB CRASH RECOVERY COMMANDS
+// ============================================================================
+
+// capability: browsing
+#[tauri::command]
+pub async fn tabs_record_crash(
+    id: String,
+    tab_manager: tauri::State<'_, TabManager>,
+    db: tauri::State<'_, Database>,
+    safe_mode: tauri::State<'_, stability::SafeMode>,
+) -> Result<bool, String> {
+    // Returns true if crash threshold exceeded (should enter safe mode)
+    let should_safe_mode = tab_manager.record_tab_crash(&id)?;
+    
+    if should_safe_mode {
+        // Record crash in SafeMode
+        safe_mode.record_crash();
+        
+        // Save session before entering safe mode
+        let _ = tab_manager.save_session(&db);
+        
+        tracing::warn!(target: "stability", "Safe mode: Tab {} crashed, entering safe mode (total crashes: {})", id, safe_mode.get_crash_count());
+    }
+    
+    Ok(should_safe_mode)
+}
+
+// Heartbeat answer from a tab's webview (reply to "watchdog:ping")
+// capability: browsing
+#[tauri::command]
+pub async fn watchdog_ack(
+    tab_id: String,
+    seq: u64,
+    watchdog: tauri::State<'_, stability::Watchdog>,
+    app: tauri::AppHandle,
+) -> Result<(), String> {
+    if let Some(recovered) = watchdog.acknowledge(&tab_id, seq) {
+        tracing::info!(target: "stability", "Watchdog: Tab {} recovered after {} ms", tab_id, recovered.downtime_ms);
+        let _ = app.emit("tab-recovered", &recovered);
+    }
+    Ok(())
+}
+
+// capability: browsing
+#[tauri::command]
+pub async fn safe_mode_status(
+    safe_mode: tauri::State<'_, stability::SafeMode>,
+) -> Result<stability::SafeModeStatus, String> {
+    Ok(safe_mode.status())
+}
+
+// Leave safe mode: clear the crash count, bring back the parked session, and restart normally
+// capability: settings
+#[tauri::command]
+pub async fn safe_mode_exit(
+    restore_session: Option<bool>,
+    safe_mode: tauri::State<'_, stability::SafeMode>,
+    tab_manager: tauri::State<'_, TabManager>,
+    db: tauri::State<'_, Database>,
+    app: tauri::AppHandle,
+) -> Result<(), String> {
+    safe_mode.reset_crash_count();
+    if restore_session.unwrap_or(true) {
+        if let Some(checkpoint) = session::parked_session(&db)? {
+            tab_manager.replace_tabs(checkpoint.active_tab_id, checkpoint.tabs);
+            tab_manager.save_session(&db)?;
+        }
+    }
+    safe_mode.end_session();
+    app.restart();
+}
+
+// Agent subsystems stay off in safe mode
+fn ensure_not_safe_mode(safe_mode: &stability::SafeMode) -> Result<(), String> {
+    if safe_mode.is_enabled() {
+        return Err("Agents are disabled in safe mode".to_string());
+    }
+    Ok(())
+}
+
+// capability: browsing
+#[tauri::command]
+pub async fn watchdog_stats(
+    watchdog: tauri::State<'_, stability::Watchdog>,
+) -> Result<stability::WatchdogStats, String> {
+    Ok(watchdog.stats())
+}
+
+// ============================================================================
+// SYSTEM COMMANDS
+// ============================================================================
+
+// capability: system
+#[tauri::command]
+pub async fn system_get_ram() -> Result<u64, String> {
+    crate::stability::get_system_ram()
+}
+
+// capability: system
+#[tauri::command]
+pub async fn system_get_max_tabs(
+    memory_guard: tauri::State<'_, stability::MemoryGuard>,
+) -> Result<u32, String> {
+    Ok(memory_guard.get_max_tabs())
+}
+
+// capability: system
+#[tauri::command]
+pub async fn get_system_info() -> Result<SystemInfo, String> {
+    let total_ram_bytes = crate::stability::get_system_ram()
+        .map_err(|e| format!("Failed to get RAM: {}", e))?;
+    let total_ram_gb = (total_ram_bytes as f64) / (1024.0 * 1024.0 * 1024.0);
+    
+    // Estimate available RAM (conservative: assume 50% used by system)
+    let available_ram_gb = total_ram_gb * 0.5;
+    
+    // Get CPU core count
+    let cpu_cores = num_cpus::get();
+    
+    Ok(SystemInfo {
+        total_ram_gb,
+        available_ram_gb,
+        cpu_cores,
+    })
+}
+
+// GPU/CPU/RAM detection and the model defaults derived from it
+// capability: system
+#[tauri::command]
+pub async fn system_capabilities() -> Result<SystemCapabilities, String> {
+    Ok(hardware::capabilities().clone())
+}
+
+// Approximate memory of models, services, and tabs against the global budget
+// capability: system
+#[tauri::command]
+pub async fn resource_report(
+    tab_manager: tauri::State<'_, TabManager>,
+    supervisor: tauri::State<'_, ServiceSupervisor>,
+    resources: tauri::State<'_, ResourceManager>,
+) -> Result<ResourceReport, String> {
+    Ok(resources.report(&tab_manager, &supervisor).await)
+}
+
+// Effective profile for every mode (built-in defaults overlaid by [modes] in config.toml)
+// capability: settings
+#[tauri::command]
+pub async fn mode_profiles_list() -> Result<Vec<ModeProfile>, String> {
+    Ok(modes::list())
+}
+
+// Profile in force for the active tab's mode
+// capability: browsing
+#[tauri::command]
+pub async fn mode_profile_active() -> Result<ModeProfile, String> {
+    Ok(modes::current())
+}
+
+// Lasts until the next mode switch or config change, which re-apply the mode's budget
+// capability: system
+#[tauri::command]
+pub async fn resource_set_budget(
+    bytes: u64,
+    resources: tauri::State<'_, ResourceManager>,
+) -> Result<(), String> {
+    const MIN_BUDGET: u64 = 512 * 1024 * 1024;
+    if bytes < MIN_BUDGET {
+        return Err(format!("Budget must be at least {} MB", MIN_BUDGET / (1024 * 1024)));
+    }
+    resources.set_budget(bytes);
+    Ok(())
+}
+
+// ============================================================================
+// LOGGING & DIAGNOSTICS COMMANDS
+// ============================================================================
+
+// Change the log level globally, or for one target (app, agent, ai, db, services, ws, trade, ...)
+// capability: system
+#[tauri::command]
+pub async fn logging_set_level(level: String, target: Option<String>) -> Result<LogLevels, String> {
+    logging::set_level(&level, target.as_deref()).map_err(|e| e.to_string())
+}
+
+// capability: settings
+#[tauri::command]
+pub async fn logging_get_levels() -> Result<LogLevels, String> {
+    Ok(logging::levels())
+}
+
+// Last lines of the log file for the diagnostics panel
+// capability: system
+#[tauri::command]
+pub async fn logs_tail(lines: Option<usize>) -> Result<Vec<String>, String> {
+    let lines = lines.unwrap_or(200);
+    tauri::async_runtime::spawn_blocking(move || logging::tail(lines))
+        .await
+        .map_err(|e| format!("Log read failed: {}", e))?
+        .map_err(|e| e.to_string())
+}
+
+// Zip version/OS info, recent logs, crash log, service statuses, DB integrity and anonymized
+// settings into the downloads folder, for attaching to bug reports
+// capability: files
+#[tauri::command]
+pub async fn diagnostics_export(
+    app: tauri::AppHandle,
+    app_state: tauri::State<'_, AppState>,
+    db: tauri::State<'_, Database>,
+    supervisor: tauri::State<'_, ServiceSupervisor>,
+    safe_mode: tauri::State<'_, stability::SafeMode>,
+) -> Result<DiagnosticsExport, String> {
+    let input = DiagnosticsInput {
+        app_version: app.package_info().version.to_string(),
+        settings: serde_json::to_value(app_state.get_settings()).map_err(|e| e.to_string())?,
+        services: supervisor.status(),
+        safe_mode: safe_mode.status(),
+        log_dir: logging::log_dir(),
+        crash_log: logging::crash_log_path(),
+    };
+    let out_dir = match (app.path().download_dir(), app.try_state::<ProfileManager>()) {
+        (Ok(dir), _) => dir,
+        (Err(_), Some(profiles)) => profiles.downloads_dir(),
+        (Err(e), None) => return Err(format!("No downloads folder: {}", e)),
+    };
+    let db = db.inner().clone();
+
+    // The integrity check reads the whole database file
+    tauri::async_runtime::spawn_blocking(move || {
+        let database = diagnostics::database_report(&db);
+        diagnostics::export(&out_dir, input, database)
+    })
+    .await
+    .map_err(|e| format!("Diagnostics task failed: {}", e))?
+    .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// CONFIG COMMANDS
+// ============================================================================
+
+// Whole config, or one dotted key ("ai.ollamaUrl")
+// capability: settings
+#[tauri::command]
+pub async fn config_get(key: Option<String>) -> Result<serde_json::Value, String> {
+    config::store().get(key.as_deref()).map_err(|e| e.to_string())
+}
+
+// Validate and save one key to config.toml; runtime-adjustable settings apply immediately
+// capability: system
+#[tauri::command]
+pub async fn config_set(
+    key: String,
+    value: serde_json::Value,
+    app: tauri::AppHandle,
+) -> Result<AppConfig, String> {
+    let updated = config::store().set(&key, value).map_err(|e| e.to_string())?;
+    config::apply(&app, &updated);
+    let _ = app.emit("config:changed", &updated);
+    Ok(updated)
+}
+
+// ============================================================================
+// API KEY COMMANDS
+// ============================================================================
+
+// Store a provider key (openai, anthropic, groq, mistral, huggingface, brave, finnhub) in secure storage
+// capability: secrets
+#[tauri::command]
+pub async fn apikey_set(
+    provider: String,
+    key: String,
+    api_keys: tauri::State<'_, ApiKeyStore>,
+) -> Result<ApiKeyInfo, String> {
+    let provider = Provider::parse(&provider).map_err(|e| e.to_string())?;
+    api_keys.set(provider, &key).map_err(|e| e.to_string())
+}
+
+// Validate the stored key with a cheap authenticated call
+// capability: network
+#[tauri::command]
+pub async fn apikey_test(
+    provider: String,
+    api_keys: tauri::State<'_, ApiKeyStore>,
+) -> Result<ApiKeyTest, String> {
+    let provider = Provider::parse(&provider).map_err(|e| e.to_string())?;
+    api_keys.test(provider).await.map_err(|e| e.to_string())
+}
+
+// Every known provider with whether a key is set (keys themselves are never returned)
+// capability: secrets
+#[tauri::command]
+pub async fn apikey_list(
+    api_keys: tauri::State<'_, ApiKeyStore>,
+) -> Result<Vec<ApiKeyInfo>, String> {
+    Ok(api_keys.list())
+}
+
+// capability: secrets
+#[tauri::command]
+pub async fn apikey_delete(
+    provider: String,
+    api_keys: tauri::State<'_, ApiKeyStore>,
+) -> Result<bool, String> {
+    let provider = Provider::parse(&provider).map_err(|e| e.to_string())?;
+    api_keys.remove(provider).map_err(|e| e.to_string())
+}
+
+// Whether the connectivity monitor currently considers the network down
+// capability: browsing
+#[tauri::command]
+pub async fn is_offline() -> Result<bool, String> {
+    Ok(connectivity::is_offline())
+}
+
+// Probe now (e.g. from a "Retry" button) instead of waiting for the next periodic check
+// capability: network
+#[tauri::command]
+pub async fn connectivity_check(app: tauri::AppHandle) -> Result<ConnectivityStatus, String> {
+    Ok(connectivity::check(Some(&app), "manual").await)
+}
+
+// Outbound HTTP metrics per host (requests, failures, retries, latency, circuit state)
+// capability: system
+#[tauri::command]
+pub async fn http_stats() -> Result<HttpStats, String> {
+    Ok(http::stats())
+}
+
+// Close a tripped circuit without waiting for it to cool down
+// capability: system
+#[tauri::command]
+pub async fn http_reset_circuit(host: String) -> Result<bool, String> {
+    Ok(http::reset_circuit(&host))
+}
+
+// Probe every LLM provider, search backend, broker endpoint, and local service for the diagnostics panel
+// capability: system
+#[tauri::command]
+pub async fn providers_health(app: tauri::AppHandle) -> Result<HealthReport, String> {
+    Ok(provider_health::check(&app).await)
+}
+
+// ============================================================================
+// AUTOFILL COMMANDS
+// ============================================================================
+
+// Saved address/contact/card profiles, newest first
+// capability: secrets
+#[tauri::command]
+pub async fn autofill_profiles_list(
+    autofill: tauri::State<'_, AutofillStore>,
+) -> Result<Vec<AutofillProfile>, String> {
+    Ok(autofill.list())
+}
+
+// Create (empty id) or update a profile; card numbers are reduced to their last four digits
+// capability: secrets
+#[tauri::command]
+pub async fn autofill_profiles_save(
+    profile: AutofillProfile,
+    autofill: tauri::State<'_, AutofillStore>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<AutofillProfile, String> {
+    privacy_enforcer.lock().unwrap().enforce_disk_write().map_err(|e| e.to_string())?;
+    autofill.save(profile).map_err(|e| e.to_string())
+}
+
+// capability: secrets
+#[tauri::command]
+pub async fn autofill_profiles_delete(
+    id: String,
+    autofill: tauri::State<'_, AutofillStore>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<bool, String> {
+    privacy_enforcer.lock().unwrap().enforce_disk_write().map_err(|e| e.to_string())?;
+    autofill.delete(&id).map_err(|e| e.to_string())
+}
+
+// Field -> value mappings for the frontend to inject (nothing in Ghost mode, no card details in Private)
+// capability: secrets
+#[tauri::command]
+pub async fn autofill_match(
+    form_schema: Vec<FormField>,
+    profile_id: Option<String>,
+    autofill: tauri::State<'_, AutofillStore>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<AutofillMatch, String> {
+    let mode = privacy_enforcer.lock().unwrap().get_policy().mode.clone();
+    autofill
+        .match_form(&form_schema, profile_id.as_deref(), mode)
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// PASSWORD COMMANDS
+// ============================================================================
+
+// Random password; every enabled character class appears at least once
+// capability: secrets
+#[tauri::command]
+pub async fn password_generate(policy: Option<PasswordPolicy>) -> Result<String, String> {
+    passwords::generate(&policy.unwrap_or_default()).map_err(|e| e.to_string())
+}
+
+// Replaces the password if the origin already has this username
+// capability: secrets
+#[tauri::command]
+pub async fn password_save(
+    origin: String,
+    user: String,
+    pass: String,
+    passwords: tauri::State<'_, PasswordVault>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<Credential, String> {
+    privacy_enforcer.lock().unwrap().enforce_disk_write().map_err(|e| e.to_string())?;
+    passwords.save(&origin, &user, &pass).map_err(|e| e.to_string())
+}
+
+// Credentials for a page URL or origin (errors while locked)
+// capability: secrets
+#[tauri::command]
+pub async fn password_find(
+    origin: String,
+    passwords: tauri::State<'_, PasswordVault>,
+) -> Result<Vec<Credential>, String> {
+    passwords.find(&origin).map_err(|e| e.to_string())
+}
+
+// capability: secrets
+#[tauri::command]
+pub async fn password_mark_used(
+    id: String,
+    passwords: tauri::State<'_, PasswordVault>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<(), String> {
+    // Ghost mode leaves no trace, not even a last-used time
+    if !privacy_enforcer.lock().unwrap().can_write_to_disk() {
+        return Ok(());
+    }
+    passwords.mark_used(&id).map_err(|e| e.to_string())
+}
+
+// capability: secrets
+#[tauri::command]
+pub async fn password_delete(
+    id: String,
+    passwords: tauri::State<'_, PasswordVault>,
+) -> Result<bool, String> {
+    passwords.delete(&id).map_err(|e| e.to_string())
+}
+
+// Have I Been Pwned range query; only a 5-character hash prefix is sent
+// capability: network
+#[tauri::command]
+pub async fn password_breach_check(
+    pass: String,
+    passwords: tauri::State<'_, PasswordVault>,
+) -> Result<BreachCheck, String> {
+    passwords.breach_check(&pass).await.map_err(|e| e.to_string())
+}
+
+// The first unlock sets the master password; it is never stored, so it can't be recovered
+// capability: secrets
+#[tauri::command]
+pub async fn password_unlock(
+    master_password: String,
+    passwords: tauri::State<'_, PasswordVault>,
+) -> Result<LockStatus, String> {
+    passwords.unlock(&master_password).map_err(|e| e.to_string())
+}
+
+// capability: secrets
+#[tauri::command]
+pub async fn password_lock(
+    app: tauri::AppHandle,
+    passwords: tauri::State<'_, PasswordVault>,
+) -> Result<LockStatus, String> {
+    let status = passwords.lock();
+    let _ = app.emit("passwords-locked", ());
+    Ok(status)
+}
+
+// capability: secrets
+#[tauri::command]
+pub async fn password_status(
+    passwords: tauri::State<'_, PasswordVault>,
+) -> Result<LockStatus, String> {
+    Ok(passwords.status())
+}
+
+// ============================================================================
+// WEB SEARCH COMMANDS
+// ============================================================================
+
+// Query several engines at once (default: config search.providers) and merge the results
+// capability: network
+#[tauri::command]
+pub async fn search_web(
+    query: String,
+    providers: Option<Vec<String>>,
+    limit: Option<usize>,
+    api_keys: tauri::State<'_, ApiKeyStore>,
+) -> Result<WebSearchResponse, String> {
+    web_search::search(&query, providers.as_deref(), limit, &api_keys)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// Typo-tolerant instant search over the local Meilisearch indexes (FTS5 stays available via db_search)
+// capability: history
+#[tauri::command]
+pub async fn search_local(
+    query: String,
+    filters: Option<LocalSearchFilters>,
+    local_index: tauri::State<'_, LocalIndex>,
+) -> Result<LocalSearchResponse, String> {
+    local_index
+        .search(&query, &filters.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// Rebuild the local indexes from the database
+// capability: history
+#[tauri::command]
+pub async fn search_local_reindex(local_index: tauri::State<'_, LocalIndex>) -> Result<(), String> {
+    local_index.resync();
+    Ok(())
+}
+
+// ============================================================================
+// OMNIBOX COMMANDS
+// ============================================================================
+
+// Suggestions from open tabs, bookmarks, and history within the local budget; remote search
+// suggestions (normal browsing only) arrive later as "omnibox:remote" with the same requestId
+// capability: browsing
+#[tauri::command]
+pub async fn omnibox_suggest(
+    input: String,
+    remote: Option<bool>,
+    app: tauri::AppHandle,
+    db: tauri::State<'_, Database>,
+    tab_manager: tauri::State<'_, TabManager>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<OmniboxResponse, String> {
+    // Private and Ghost keystrokes stay on the device
+    let remote_allowed = remote.unwrap_or(true) && privacy_enforcer.lock().unwrap().can_save_history();
+    let tabs = tab_manager.list_tabs();
+    Ok(omnibox::suggest(&input, tabs, db.inner().clone(), remote_allowed.then_some(app)).await)
+}
+
+// Calculator, unit/currency conversion, date, and time zone answers computed locally; None if the
+// query isn't one of those. Currency uses the last cached ECB rates and is skipped until they exist.
+// capability: browsing
+#[tauri::command]
+pub async fn instant_answer(query: String) -> Result<Option<InstantAnswer>, String> {
+    Ok(instant_answers::answer(&query))
+}
+
+// ============================================================================
+// EXCHANGE RATE COMMANDS
+// ============================================================================
+
+// Convert between ISO currency codes at the latest cached daily rate (used for trade P&L in the
+// account currency). Offline, the cache is used as-is and `stale` marks rates several days old.
+// capability: network
+#[tauri::command]
+pub async fn fx_convert(amount: f64, from: String, to: String) -> Result<Conversion, String> {
+    fx::convert(amount, &from, &to).await.map_err(|e| e.to_string())
+}
+
+// All cached rates against `base` (default EUR, the reference currency)
+// capability: network
+#[tauri::command]
+pub async fn fx_rates(base: Option<String>) -> Result<RateTable, String> {
+    fx::rates(base.as_deref().unwrap_or("EUR")).await.map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// TRADE NEWS COMMANDS
+// ============================================================================
+
+// Headlines for a ticker from the sources in config.trade.newsSources, newest first.
+// `sentiment` overrides config.trade.newsSentiment; scoring uses the local model and is skipped when it's unavailable.
+// capability: network
+#[tauri::command]
+pub async fn trade_news(
+    symbol: String,
+    limit: Option<usize>,
+    sentiment: Option<bool>,
+    app: tauri::AppHandle,
+) -> Result<NewsResponse, String> {
+    trade_news::news(&app, &symbol, limit.unwrap_or(trade_news::DEFAULT_LIMIT), sentiment)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// Strikes with call/put premiums, open interest, and IV for one expiry (YYYY-MM-DD, nearest when omitted).
+// NIFTY, BANKNIFTY, FINNIFTY, MIDCPNIFTY, and "NSE:" equities come from NSE; other symbols from Yahoo Finance.
+// capability: network
+#[tauri::command]
+pub async fn trade_option_chain(symbol: String, expiry: Option<String>) -> Result<OptionChain, String> {
+    options_chain::option_chain(&symbol, expiry.as_deref()).await.map_err(|e| e.to_string())
+}
+
+// Latest daily close for a ticker with the change on the previous session (delayed, from the bar cache or Yahoo)
+// capability: network
+#[tauri::command]
+pub async fn trade_quote(symbol: String, db: tauri::State<'_, Database>) -> Result<Quote, String> {
+    ohlc::quote(&db, &symbol).await.map_err(|e| e.to_string())
+}
+
+// Replay daily bars (cached locally, fetched when missing) against a rule spec. `range` is a preset
+// ("6mo", "1y", "2y", "5y", "10y"; default "1y") or {from, to} in seconds. Results are saved unless disk writes are off.
+// capability: network
+#[tauri::command]
+pub async fn backtest_run(
+    symbol: String,
+    strategy: StrategySpec,
+    range: Option<BacktestRange>,
+    app: tauri::AppHandle,
+) -> Result<BacktestResult, String> {
+    let range = range.unwrap_or(BacktestRange::Preset("1y".to_string()));
+    let result = backtest::run(&app, &symbol, strategy, &range).await.map_err(|e| e.to_string())?;
+    if !notifications::app_focused(&app) {
+        notifications::post(&app, NotificationRequest {
+            title: format!("Backtest finished: {}", result.symbol),
+            body: format!(
+                "{}: {} trades, {:+.2}% return",
+                result.strategy.name, result.stats.trades, result.stats.total_return_pct
+            ),
+            actions: vec![NotificationAction { id: "view".to_string(), title: "View".to_string() }],
+            kind: NotificationKind::Job,
+            payload: Some(serde_json::json!({ "backtestId": result.id })),
+            ..Default::default()
+        });
+    }
+    Ok(result)
+}
+
+// capability: content
+#[tauri::command]
+pub async fn backtest_list(
+    symbol: Option<String>,
+    limit: Option<usize>,
+    db: tauri::State<'_, Database>,
+) -> Result<Vec<BacktestResult>, String> {
+    let symbol = symbol.map(|s| s.trim().to_uppercase());
+    db.backtest_list(symbol.as_deref(), limit.unwrap_or(50)).map_err(|e| e.to_string())
+}
+
+// capability: content
+#[tauri::command]
+pub async fn backtest_get(id: String, db: tauri::State<'_, Database>) -> Result<Option<BacktestResult>, String> {
+    db.backtest_get(&id).map_err(|e| e.to_string())
+}
+
+// capability: content
+#[tauri::command]
+pub async fn backtest_delete(id: String, db: tauri::State<'_, Database>) -> Result<bool, String> {
+    db.backtest_delete(&id).map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// TRADE JOURNAL COMMANDS
+// ============================================================================
+
+// Record a fill; send the same id again with exitPrice when the position closes
+// capability: content
+#[tauri::command]
+pub async fn trade_log_record(trade: TradeLogInput, app: tauri::AppHandle) -> Result<TradeLog, String> {
+    journal::record_trade(&app, trade).map_err(|e| e.to_string())
+}
+
+// capability: content
+#[tauri::command]
+pub async fn trade_log_list(
+    symbol: Option<String>,
+    limit: Option<usize>,
+    db: tauri::State<'_, Database>,
+) -> Result<Vec<TradeLog>, String> {
+    let symbol = symbol.map(|s| s.trim().to_uppercase());
+    db.trade_log_list(symbol.as_deref(), limit.unwrap_or(100)).map_err(|e| e.to_string())
+}
+
+// Notes for a logged trade, enriched with a market snapshot at execution and tags from the local model
+// capability: content
+#[tauri::command]
+pub async fn journal_add_entry(trade_id: String, notes: String, app: tauri::AppHandle) -> Result<JournalItem, String> {
+    journal::add_entry(&app, &trade_id, &notes).await.map_err(|e| e.to_string())
+}
+
+// `range` is "week", "month" (default), "quarter", "year", "all", or {from, to} in seconds
+// capability: content
+#[tauri::command]
+pub async fn journal_report(range: Option<JournalRange>, app: tauri::AppHandle) -> Result<JournalReport, String> {
+    let range = range.unwrap_or(JournalRange::Preset("month".to_string()));
+    journal::report(&app, &range).await.map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// FOCUS MODE COMMANDS
+// ============================================================================
+
+// Duration in minutes; without a blocklist the default distracting sites are blocked.
+// Progress arrives as "focus:progress" every second and "focus:ended" when the session ends.
+// capability: content
+#[tauri::command]
+pub async fn focus_start(
+    duration: u32,
+    blocklist: Option<Vec<String>>,
+    app: tauri::AppHandle,
+    db: tauri::State<'_, Database>,
+) -> Result<FocusSession, String> {
+    focus::start(app, db.inner().clone(), duration, blocklist).map_err(|e| e.to_string())
+}
+
+// capability: content
+#[tauri::command]
+pub async fn focus_stop(
+    db: tauri::State<'_, Database>,
+) -> Result<FocusRecord, String> {
+    focus::stop(&db).map_err(|e| e.to_string())
+}
+
+// None when no session is running
+// capability: browsing
+#[tauri::command]
+pub async fn focus_status() -> Result<Option<FocusProgress>, String> {
+    Ok(focus::status())
+}
+
+// capability: content
+#[tauri::command]
+pub async fn focus_stats(
+    db: tauri::State<'_, Database>,
+) -> Result<FocusStats, String> {
+    focus::stats(&db).map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// USAGE ANALYTICS COMMANDS
+// ============================================================================
+
+// Local time-per-domain report: today, week, month, year, or a number of days
+// capability: history
+#[tauri::command]
+pub async fn usage_report(
+    range: String,
+    db: tauri::State<'_, Database>,
+) -> Result<UsageReport, String> {
+    usage::report(&db, &range).map_err(|e| e.to_string())
+}
+
+// Forget all recorded usage; returns the number of daily rows removed
+// capability: history
+#[tauri::command]
+pub async fn usage_clear(
+    db: tauri::State<'_, Database>,
+) -> Result<usize, String> {
+    usage::pause(&db);
+    db.usage_clear().map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// CLIPBOARD COMMANDS
+// ============================================================================
+
+// Opt-in: watch the clipboard and emit "clipboard-suggestion" for copied URLs and symbols
+// capability: capture
+#[tauri::command]
+pub async fn clipboard_monitor_enable(app: tauri::AppHandle) -> Result<(), String> {
+    clipboard::enable(app);
+    Ok(())
+}
+
+// capability: capture
+#[tauri::command]
+pub async fn clipboard_monitor_disable() -> Result<(), String> {
+    clipboard::disable();
+    Ok(())
+}
+
+// capability: capture
+#[tauri::command]
+pub async fn clipboard_monitor_status() -> Result<bool, String> {
+    Ok(clipboard::is_enabled())
+}
+
+// ============================================================================
+// CAPTURE & OCR COMMANDS
+// ============================================================================
+
+// PNG of a tab's window, or the whole app window, optionally cropped to a region
+// capability: capture
+#[tauri::command]
+pub async fn capture_screenshot(
+    tab_id: Option<String>,
+    full_window: Option<bool>,
+    region: Option<Region>,
+    app: tauri::AppHandle,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<Screenshot, String> {
+    if !privacy_enforcer.lock().unwrap().can_take_screenshot() {
+        return Err("Screenshots are disabled in Ghost mode".to_string());
+    }
+    let dir = app_data_path(&app, "screenshots")?;
+    let tab_id = tab_id.filter(|_| !full_window.unwrap_or(false));
+    tauri::async_runtime::spawn_blocking(move || capture::capture(&app, tab_id.as_deref(), region, &dir))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+// Extract text with the local tesseract ("eng" unless a language like "eng+deu" is given)
+// capability: files
+#[tauri::command]
+pub async fn ocr_image(
+    path: String,
+    language: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<OcrResult, String> {
+    let path = files::resolve(&path).map_err(|e| e.to_string())?;
+    tauri::async_runtime::spawn_blocking(move || capture::ocr(&app, std::path::Path::new(&path), language.as_deref()))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+// Hand captured text to notes ("note") or a research session ("research"); returns the new note's id
+// capability: content
+#[tauri::command]
+pub async fn capture_send_text(
+    text: String,
+    target: String,
+    session_id: Option<String>,
+    title: Option<String>,
+    url: Option<String>,
+    db: tauri::State<'_, Database>,
+    local_index: tauri::State<'_, LocalIndex>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<String, String> {
+    privacy_enforcer
+        .lock()
+        .unwrap()
+        .enforce_disk_write()
+        .map_err(|e| e.to_string())?;
+    let text = text.trim().to_string();
+    if text.is_empty() {
+        return Err("No text to send".to_string());
+    }
+
+    match target.as_str() {
+        "note" => {
+            let note = Note::new(text, title, url, vec!["ocr".to_string()]);
+            db.save_note(&note).map_err(|e| e.to_string())?;
+            local_index.note_saved(&note);
+            Ok(note.id)
+        }
+        "research" => {
+            let session_id = session_id.ok_or("A research session id is required")?;
+            let mut session = db
+                .get_research_session(&session_id)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Research session {} not found", session_id))?;
+            // Session timestamps are millis (frontend Date.now())
+            let now = chrono::Utc::now().timestamp_millis();
+            let note = research::SessionNote {
+                id: uuid::Uuid::new_v4().to_string(),
+                content: text,
+                url: url.filter(|u| !u.trim().is_empty()),
+                selection: None,
+                created_at: now,
+                tags: vec!["ocr".to_string()],
+            };
+            let id = note.id.clone();
+            session.notes.push(note);
+            session.updated_at = now;
+            db.save_research_session(&session).map_err(|e| e.to_string())?;
+            local_index.session_saved(&session);
+            Ok(id)
+        }
+        other => Err(format!("Unknown target '{}' (expected note or research)", other)),
+    }
+}
+
+// ============================================================================
+// GLOBAL SHORTCUT COMMANDS
+// ============================================================================
+
+// Bindings live in config.toml [shortcuts]; saving re-registers them through config::apply
+fn save_shortcut(app: &tauri::AppHandle, action: &str, value: serde_json::Value) -> Result<Vec<ShortcutBinding>, String> {
+    if !global_shortcut_service::ACTIONS.contains(&action) {
+        return Err(format!(
+            "Unknown shortcut action '{}' (expected one of {})",
+            action,
+            global_shortcut_service::ACTIONS.join(", ")
+        ));
+    }
+    let updated = config::store()
+        .set(&format!("shortcuts.{}", action), value)
+        .map_err(|e| e.to_string())?;
+    config::apply(app, &updated);
+    let _ = app.emit("config:changed", &updated);
+    Ok(global_shortcut_service::list())
+}
+
+// Bind an action to an accelerator ("CommandOrControl+Shift+K"); fails if another action already uses it
+// capability: settings
+#[tauri::command]
+pub async fn shortcut_register(
+    action: String,
+    accelerator: String,
+    app: tauri::AppHandle,
+) -> Result<Vec<ShortcutBinding>, String> {
+    let accelerator = accelerator.trim();
+    if accelerator.is_empty() {
+        return Err("Accelerator is empty".to_string());
+    }
+    save_shortcut(&app, &action, serde_json::json!(accelerator))
+}
+
+// capability: settings
+#[tauri::command]
+pub async fn shortcut_unregister(action: String, app: tauri::AppHandle) -> Result<Vec<ShortcutBinding>, String> {
+    save_shortcut(&app, &action, serde_json::json!(""))
+}
+
+// Back to the built-in binding (or unbound if the action has none)
+// capability: settings
+#[tauri::command]
+pub async fn shortcut_reset(action: String, app: tauri::AppHandle) -> Result<Vec<ShortcutBinding>, String> {
+    save_shortcut(&app, &action, serde_json::Value::Null)
+}
+
+// capability: settings
+#[tauri::command]
+pub async fn shortcut_list() -> Result<Vec<ShortcutBinding>, String> {
+    Ok(global_shortcut_service::list())
+}
+
+// ============================================================================
+// SERVICE COMMANDS
+// ============================================================================
+
+// capability: system
+#[tauri::command]
+pub async fn service_status(
+    supervisor: tauri::State<'_, ServiceSupervisor>,
+) -> Result<Vec<ServiceStatus>, String> {
+    Ok(supervisor.status())
+}
+
+// Per-OS binary discovery report (also emitted as "services:capabilities" at startup)
+// capability: system
+#[tauri::command]
+pub async fn service_capabilities(
+    supervisor: tauri::State<'_, ServiceSupervisor>,
+) -> Result<CapabilityReport, String> {
+    Ok(supervisor.capabilities())
+}
+
+// Restart a supervised service (ollama, meilisearch, n8n); resets its restart backoff
+// capability: system
+#[tauri::command]
+pub async fn service_restart(
+    name: String,
+    supervisor: tauri::State<'_, ServiceSupervisor>,
+) -> Result<ServiceStatus, String> {
+    let supervisor = supervisor.inner().clone();
+    // Stopping the old process can take a few seconds
+    tauri::async_runtime::spawn_blocking(move || supervisor.restart(&name))
+        .await
+        .map_err(|e| format!("Restart task failed: {}", e))?
+}
+
+// ============================================================================
+// LEGACY/COMPATIBILITY COMMANDS (for existing frontend code)
+// ============================================================================
+
+// capability: browsing
+#[tauri::command]
+pub async fn search(
+    query: String,
+    search_engine: tauri::State<'_, SearchEngine>,
+) -> Result<Vec<serde_json::Value>, String> {
+    // Use SearchEngine for actual search
+    let results = search_engine.search(&query, 20)
+        .map_err(|e| e.to_string())?;
+    Ok(results.into_iter().map(|r| serde_json::to_value(r).unwrap()).collect())
+}
+
+// ============================================================================
+// AGENT TOOL COMMANDS
+// ============================================================================
+
+// capability: agent
+#[tauri::command]
+pub async fn tools_list() -> Result<Vec<ToolInfo>, String> {
+    Ok(tools::registry().list())
+}
+
+// Run a tool the user invoked themselves: same validation and permission gates, no approval step
+// capability: agent
+#[tauri::command]
+pub async fn tools_call(
+    name: String,
+    arguments: Option<serde_json::Value>,
+    safe_mode: tauri::State<'_, stability::SafeMode>,
+    app: tauri::AppHandle,
+) -> Result<serde_json::Value, String> {
+    ensure_not_safe_mode(&safe_mode)?;
+    tools::registry()
+        .call(&app, &name, arguments.unwrap_or(serde_json::Value::Null))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// Answer a request with tool calls; each call and its result streams as "agent:event" ({requestId, event})
+// capability: agent
+#[tauri::command]
+pub async fn agent_ask(
+    query: String,
+    request_id: String,
+    safe_mode: tauri::State<'_, stability::SafeMode>,
+    app: tauri::AppHandle,
+) -> Result<AgentAnswer, String> {
+    ensure_not_safe_mode(&safe_mode)?;
+    let (emitter, tag) = (app.clone(), request_id.clone());
+    agent::run(&app, &request_id, &query, move |event| {
+        let _ = emitter.emit("agent:event", serde_json::json!({ "requestId": &tag, "event": event }));
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// Voice assistant entry point: audio (base64 WAV) or text -> transcript -> intent -> action -> spoken reply.
+// Stages stream as "wispr:event" ({requestId, event}: stage, transcript, intent, agent, reply, done/failed).
+// capability: ai
+#[tauri::command]
+pub async fn wispr_handle(
+    input: WisprInput,
+    request_id: String,
+    safe_mode: tauri::State<'_, stability::SafeMode>,
+    app: tauri::AppHandle,
+) -> Result<WisprResult, String> {
+    ensure_not_safe_mode(&safe_mode)?;
+    let (emitter, tag) = (app.clone(), request_id.clone());
+    wispr::handle(&app, &request_id, input, move |event| {
+        let _ = emitter.emit("wispr:event", serde_json::json!({ "requestId": &tag, "event": event }));
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// capability: ai
+#[tauri::command]
+pub async fn hotword_status() -> Result<HotwordStatus, String> {
+    Ok(hotword_service::status())
+}
+
+// Turn the "Hey Regen" detector on or off (persisted as voice.hotword); refused in Private/Ghost
+// capability: settings
+#[tauri::command]
+pub async fn hotword_set_enabled(enabled: bool, app: tauri::AppHandle) -> Result<HotwordStatus, String> {
+    hotword_service::set_enabled(&app, enabled).map_err(|e| e.to_string())
+}
+
+// capability: settings
+#[tauri::command]
+pub async fn hotword_set_sensitivity(sensitivity: f64) -> Result<HotwordStatus, String> {
+    hotword_service::set_sensitivity(sensitivity).map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// NOTIFICATION COMMANDS
+// ============================================================================
+
+// Show a native notification (held back inside notifications.dndStart..dndEnd unless urgent).
+// The in-app list calls notification_respond for clicks and buttons, which emits "notification:action".
+// capability: content
+#[tauri::command]
+pub async fn notify(
+    title: String,
+    body: String,
+    actions: Option<Vec<NotificationAction>>,
+    kind: Option<NotificationKind>,
+    urgent: Option<bool>,
+    payload: Option<serde_json::Value>,
+    app: tauri::AppHandle,
+) -> Result<Notification, String> {
+    let request = NotificationRequest {
+        title,
+        body,
+        actions: actions.unwrap_or_default(),
+        kind: kind.unwrap_or_default(),
+        urgent: urgent.unwrap_or(false),
+        payload,
+    };
+    notifications::notify(&app, request).map_err(|e| e.to_string())
+}
+
+// action None is a click on the notification itself
+// capability: content
+#[tauri::command]
+pub async fn notification_respond(
+    id: String,
+    action: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<NotificationResponse, String> {
+    notifications::respond(&app, &id, action.as_deref()).map_err(|e| e.to_string())
+}
+
+// capability: content
+#[tauri::command]
+pub async fn notification_list(limit: Option<usize>) -> Result<Vec<Notification>, String> {
+    Ok(notifications::list(limit.unwrap_or(50)))
+}
+
+// capability: content
+#[tauri::command]
+pub async fn notification_clear() -> Result<(), String> {
+    notifications::clear();
+    Ok(())
+}
+
+// ============================================================================
+// CRASH RECOVERY COMMANDS
+// ============================================================================
+
+// Latest in-memory research/agent UI state; written to disk every few seconds (not in Private/Ghost)
+// capability: content
+#[tauri::command]
+pub async fn recovery_checkpoint(state: serde_json::Value) -> Result<(), String> {
+    recovery::checkpoint(state).map_err(|e| e.to_string())
+}
+
+// State left behind by a crashed or killed run, if any
+// capability: content
+#[tauri::command]
+pub async fn recovery_available() -> Result<Option<RecoveryInfo>, String> {
+    Ok(recovery::available())
+}
+
+// capability: content
+#[tauri::command]
+pub async fn recovery_restore() -> Result<Option<RecoverySnapshot>, String> {
+    recovery::restore().map_err(|e| e.to_string())
+}
+
+// capability: content
+#[tauri::command]
+pub async fn recovery_discard() -> Result<bool, String> {
+    recovery::discard().map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// ONBOARDING COMMANDS
+// ============================================================================
+
+// Where first-run setup stands; steps survive restarts and "running" ones resume at startup
+// capability: settings
+#[tauri::command]
+pub async fn onboarding_status() -> Result<OnboardingStatus, String> {
+    Ok(onboarding::status())
+}
+
+// choice per step: ai-provider {mode: local|cloud, cloudProvider?, apiKey?}, model {model?},
+// search-provider {provider, apiKey?}, import {browser, bookmarks?, history?}; progress on "onboarding:progress"
+// capability: settings
+#[tauri::command]
+pub async fn onboarding_run_step(
+    step: OnboardingStep,
+    choice: Option<serde_json::Value>,
+    app: tauri::AppHandle,
+) -> Result<StepState, String> {
+    let choice = choice.unwrap_or_else(|| serde_json::json!({}));
+    onboarding::run_step(&app, step, choice).map_err(|e| e.to_string())
+}
+
+// capability: settings
+#[tauri::command]
+pub async fn onboarding_skip_step(step: OnboardingStep, app: tauri::AppHandle) -> Result<StepState, String> {
+    onboarding::skip(&app, step).map_err(|e| e.to_string())
+}
+
+// capability: settings
+#[tauri::command]
+pub async fn onboarding_reset(app: tauri::AppHandle) -> Result<OnboardingStatus, String> {
+    onboarding::reset(&app).map_err(|e| e.to_string())
+}
+
+// Browser profiles on this machine that bookmarks/history can be imported from
+// capability: files
+#[tauri::command]
+pub async fn onboarding_import_sources() -> Result<Vec<ImportSource>, String> {
+    Ok(onboarding::import_sources())
+}
+
+// ============================================================================
+// USER SCRIPT COMMANDS
+// ============================================================================
+
+// Called by the frontend at navigation: enabled scripts and styles whose patterns match `url`
+// capability: browsing
+#[tauri::command]
+pub async fn userscripts_for(url: String, db: tauri::State<'_, Database>) -> Result<Vec<Injection>, String> {
+    userscripts::for_url(&db, &url).map_err(|e| e.to_string())
+}
+
+// capability: content
+#[tauri::command]
+pub async fn userscript_list(db: tauri::State<'_, Database>) -> Result<Vec<UserScript>, String> {
+    db.userscript_list().map_err(|e| e.to_string())
+}
+
+// Create or edit a script/style written in the app (`origin` is shorthand for one site)
+// capability: settings
+#[tauri::command]
+pub async fn userscript_save(
+    script: UserScriptInput,
+    db: tauri::State<'_, Database>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<UserScript, String> {
+    privacy_enforcer.lock().unwrap().enforce_disk_write().map_err(|e| e.to_string())?;
+    userscripts::save(&db, script).map_err(|e| e.to_string())
+}
+
+// Import .user.js / .user.css source with a Greasemonkey-style metadata block
+// capability: settings
+#[tauri::command]
+pub async fn userscript_import(
+    source: String,
+    db: tauri::State<'_, Database>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<UserScript, String> {
+    privacy_enforcer.lock().unwrap().enforce_disk_write().map_err(|e| e.to_string())?;
+    userscripts::import(&db, &source, None).map_err(|e| e.to_string())
+}
+
+// Fetch and import from a URL, which also becomes the update URL unless the metadata names one
+// capability: network
+#[tauri::command]
+pub async fn userscript_install(
+    url: String,
+    db: tauri::State<'_, Database>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<UserScript, String> {
+    privacy_enforcer.lock().unwrap().enforce_disk_write().map_err(|e| e.to_string())?;
+    userscripts::install(&db, &url).await.map_err(|e| e.to_string())
+}
+
+// capability: settings
+#[tauri::command]
+pub async fn userscript_set_enabled(id: String, enabled: bool, db: tauri::State<'_, Database>) -> Result<UserScript, String> {
+    if !db.userscript_set_enabled(&id, enabled).map_err(|e| e.to_string())? {
+        return Err(format!("User script {} not found", id));
+    }
+    userscripts::get(&db, &id).map_err(|e| e.to_string())
+}
+
+// capability: settings
+#[tauri::command]
+pub async fn userscript_delete(id: String, db: tauri::State<'_, Database>) -> Result<bool, String> {
+    db.userscript_delete(&id).map_err(|e| e.to_string())
+}
+
+// Re-check update URLs (one script, or all that have one); returns the scripts that changed
+// capability: network
+#[tauri::command]
+pub async fn userscript_check_updates(id: Option<String>, db: tauri::State<'_, Database>) -> Result<Vec<UserScript>, String> {
+    let ids: Vec<String> = match id {
+        Some(id) => vec![id],
+        None => db
+            .userscript_list()
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .filter(|s| s.update_url.is_some())
+            .map(|s| s.id)
+            .collect(),
+    };
+    let single = ids.len() == 1;
+    let mut updated = Vec::new();
+    for id in ids {
+        match userscripts::check_update(&db, &id).await {
+            Ok(Some(script)) => updated.push(script),
+            Ok(None) => {}
+            Err(e) if single => return Err(e.to_string()),
+            Err(e) => tracing::warn!(target: "app", "User scripts: Update check for {} failed: {}", id, e),
+        }
+    }
+    Ok(updated)
+}
+
+// ============================================================================
+// FILTER RULE COMMANDS
+// ============================================================================
+
+// rule_type: block, allow (written with or without @@) or cosmetic (example.com##.ad, or a bare selector)
+// capability: settings
+#[tauri::command]
+pub async fn rules_add(
+    pattern: String,
+    rule_type: String,
+    app: tauri::AppHandle,
+    db: tauri::State<'_, Database>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<FilterRule, String> {
+    privacy_enforcer.lock().unwrap().enforce_disk_write().map_err(|e| e.to_string())?;
+    let rule_type = RuleType::parse(&rule_type).map_err(|e| e.to_string())?;
+    let rule = filter_rules::add(&db, &pattern, rule_type).map_err(|e| e.to_string())?;
+    emit_filter_rules(&app, &db);
+    Ok(rule)
+}
+
+// capability: content
+#[tauri::command]
+pub async fn rules_list(db: tauri::State<'_, Database>) -> Result<Vec<FilterRule>, String> {
+    filter_rules::list(&db).map_err(|e| e.to_string())
+}
+
+// Syntax check without saving; the canonical filter line when valid
+// capability: content
+#[tauri::command]
+pub async fn rules_validate(pattern: String, rule_type: String) -> Result<RuleCheck, String> {
+    let rule_type = RuleType::parse(&rule_type).map_err(|e| e.to_string())?;
+    Ok(filter_rules::check(&pattern, rule_type))
+}
+
+// Which enabled rules a request would hit, and whether it ends up blocked
+// capability: content
+#[tauri::command]
+pub async fn rules_test(
+    url: String,
+    source_url: Option<String>,
+    resource_type: Option<String>,
+    db: tauri::State<'_, Database>,
+) -> Result<RuleTestResult, String> {
+    filter_rules::test(&db, &TestRequest { url, source_url, resource_type }).map_err(|e| e.to_string())
+}
+
+// capability: settings
+#[tauri::command]
+pub async fn rules_set_enabled(id: String, enabled: bool, app: tauri::AppHandle, db: tauri::State<'_, Database>) -> Result<(), String> {
+    filter_rules::set_enabled(&db, &id, enabled).map_err(|e| e.to_string())?;
+    emit_filter_rules(&app, &db);
+    Ok(())
+}
+
+// capability: settings
+#[tauri::command]
+pub async fn rules_remove(id: String, app: tauri::AppHandle, db: tauri::State<'_, Database>) -> Result<(), String> {
+    filter_rules::remove(&db, &id).map_err(|e| e.to_string())?;
+    emit_filter_rules(&app, &db);
+    Ok(())
+}
+
+// The frontend engine reloads its custom filters from "filter-rules:changed"
+fn emit_filter_rules(app: &tauri::AppHandle, db: &Database) {
+    match filter_rules::export(db) {
+        Ok(filters) => {
+            let _ = app.emit("filter-rules:changed", filters);
+        }
+        Err(e) => tracing::warn!(target: "app", "Filter rules: {}", e),
+    }
 }
 
-#[tauri::command(name = "downloads:getQueue")]
-pub async fn downloads_get_queue(
+// ============================================================================
+// COMMAND PALETTE COMMANDS
+// ============================================================================
+
+// Every invokable backend action with its parameter schema and shortcut, optionally filtered by a query
+// capability: content
+#[tauri::command]
+pub async fn actions_registry(query: Option<String>) -> Result<Vec<PaletteAction>, String> {
+    Ok(palette::registry(query.as_deref()))
+}
+
+// Run a registry action by id; args are keyed the same way as when invoking the command directly
+// capability: system
+#[tauri::command]
+pub async fn action_invoke(id: String, args: Option<serde_json::Value>, app: tauri::AppHandle) -> Result<serde_json::Value, String> {
+    palette::invoke(&app, &id, args.unwrap_or_default()).await.map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// PERMISSION COMMANDS
+// ============================================================================
+
+// Commands refused by the capability check, newest first
+// capability: system
+#[tauri::command]
+pub async fn permissions_violations(limit: Option<usize>) -> Result<Vec<permissions::Violation>, String> {
+    Ok(permissions::violations(limit.unwrap_or(50)))
+}
+
+// Capabilities granted per window and origin, built-in grants first
+// capability: system
+#[tauri::command]
+pub async fn permissions_grants() -> Result<Vec<permissions::Grant>, String> {
+    Ok(permissions::grants())
+}
+
+// ============================================================================
+// FILE DIALOG COMMANDS
+// ============================================================================
+
+// Native open dialog; returns a handle to pass as `path` to the file commands, or null if cancelled
+// capability: files
+#[tauri::command]
+pub async fn dialog_open_file(
+    filters: Option<Vec<FileFilter>>,
+    app: tauri::AppHandle,
     db: tauri::State<'_, Database>,
-) -> Result<serde_json::Value, String> {
-    // Count active and queued downloads
-    let downloads = db.get_downloads(None).map_err(|e| e.to_string())?;
-    let active = downloads.iter().filter(|d| d.4 == "downloading" || d.4 == "verifying").count();
-    let queued = downloads.iter().filter(|d| d.4 == "pending").count();
-    
-    Ok(serde_json::json!({
-        "active": active,
-        "queued": queued,
-        "maxConcurrent": 3,
-    }))
+) -> Result<Option<FileHandle>, String> {
+    files::open(&app, &db, &filters.unwrap_or_default()).await.map_err(|e| e.to_string())
 }
 
-#[tauri::command(name = "downloads:save")]
-pub async fn downloads_save(
-    id: String,
-    url: String,
-    filename: Option<String>,
-    path: Option<String>,
-    status: String,
-    progress: f64,
-    received_bytes: i64,
-    total_bytes: Option<i64>,
-    checksum: Option<String>,
-    safety_status: Option<String>,
+// Native save dialog; returns a handle for session_export and similar, or null if cancelled
+// capability: files
+#[tauri::command]
+pub async fn dialog_save_file(
+    default_name: Option<String>,
+    app: tauri::AppHandle,
     db: tauri::State<'_, Database>,
-) -> Result<(), String> {
-    db.save_download(
-        &id,
-        &url,
-        filename.as_deref(),
-        path.as_deref(),
-        &status,
-        progress,
-        received_bytes,
-        total_bytes,
-        checksum.as_deref(),
-        safety_status.as_deref(),
-    ).map_err(|e| e.to_string())?;
-    Ok(())
+) -> Result<Option<FileHandle>, String> {
+    files::save(&app, &db, default_name.as_deref()).await.map_err(|e| e.to_string())
 }
 
-#[tauri::command(name = "downloads:delete")]
-pub async fn downloads_delete(
-    id: String,
+// Recently picked files for Docs mode, newest first; one document type or all
+// capability: files
+#[tauri::command]
+pub async fn files_recent(
+    doc_type: Option<DocType>,
+    limit: Option<usize>,
     db: tauri::State<'_, Database>,
-) -> Result<(), String> {
-    db.delete_download(&id).map_err(|e| e.to_string())?;
-    Ok(())
+) -> Result<Vec<RecentFile>, String> {
+    let limit = limit.unwrap_or(files::MAX_RECENT_PER_TYPE).min(200);
+    files::recent(&db, doc_type, limit).map_err(|e| e.to_string())
+}
+
+// Returns how many entries were removed
+// capability: files
+#[tauri::command]
+pub async fn files_recent_clear(doc_type: Option<DocType>, db: tauri::State<'_, Database>) -> Result<usize, String> {
+    files::clear_recent(&db, doc_type).map_err(|e| e.to_string())
 }
 
 // ============================================================================
-// AI COMMANDS
+// ARCHIVE FILE COMMANDS
 // ============================================================================
 
+// Entries of a zip, tar, or tar.gz (path or file handle); unsafe entries are ones extraction would refuse.
+// Named archive_inspect because archive_list already lists saved pages.
+// capability: files
 #[tauri::command]
-pub async fn ai_complete(
-    prompt: String,
-    ai_service: tauri::State<'_, AIService>,
-) -> Result<String, String> {
-    ai_service.complete(&prompt).map_err(|e| e.to_string())
+pub async fn archive_inspect(path: String) -> Result<ArchiveListing, String> {
+    let path = files::resolve(&path).map_err(|e| e.to_string())?;
+    tauri::async_runtime::spawn_blocking(move || archive_files::list(std::path::Path::new(&path)))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
 }
 
+// Extract the whole archive, or the named entries (a folder selects its contents), into dest.
+// Progress streams as "archive:progress".
+// capability: files
 #[tauri::command]
-pub async fn ai_detect_intent(
-    query: String,
-    ai_service: tauri::State<'_, AIService>,
-) -> Result<String, String> {
-    let intent = ai_service.detect_intent(&query).map_err(|e| e.to_string())?;
-    Ok(format!("{:?}", intent))
+pub async fn archive_extract(
+    path: String,
+    dest: String,
+    entries: Option<Vec<String>>,
+    app: tauri::AppHandle,
+) -> Result<ExtractReport, String> {
+    let path = files::resolve(&path).map_err(|e| e.to_string())?;
+    let dest = files::resolve(&dest).map_err(|e| e.to_string())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        archive_files::extract(std::path::Path::new(&path), std::path::Path::new(&dest), entries.as_deref(), |progress| {
+            let _ = app.emit("archive:progress", progress);
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
 }
 
 // ============================================================================
-// TAB CRASH RECOVERY COMMANDS
+// LOCAL API COMMANDS
 // ============================================================================
 
+// Whether the localhost REST API is on, where, and why not if it failed to start
+// capability: system
 #[tauri::command]
-pub async fn tabs_record_crash(
-    id: String,
-    tab_manager: tauri::State<'_, TabManager>,
-    db: tauri::State<'_, Database>,
-    safe_mode: tauri::State<'_, stability::SafeMode>,
-) -> Result<bool, String> {
-    // Returns true if crash threshold exceeded (should enter safe mode)
-    let should_safe_mode = tab_manager.record_tab_crash(&id)?;
-    
-    if should_safe_mode {
-        // Record crash in SafeMode
-        safe_mode.record_crash();
-        
-        // Save session before entering safe mode
-        let _ = tab_manager.save_session(&db);
-        
-        eprintln!("[SafeMode] Tab {} crashed, entering safe mode (total crashes: {})", id, safe_mode.get_crash_count());
-    }
-    
-    Ok(should_safe_mode)
+pub async fn api_server_status(app: tauri::AppHandle) -> Result<ApiServerStatus, String> {
+    Ok(api_server::status(&app))
+}
+
+// Bearer token for the local API; regenerate to revoke the current one
+// capability: system
+#[tauri::command]
+pub async fn api_server_token(regenerate: Option<bool>, app: tauri::AppHandle) -> Result<String, String> {
+    api_server::token(&app, regenerate.unwrap_or(false)).map_err(|e| e.to_string())
+}
+
+// OpenAPI description of the local API routes
+// capability: system
+#[tauri::command]
+pub async fn api_server_openapi() -> Result<serde_json::Value, String> {
+    Ok(api_server::openapi(crate::config::current().api.port))
 }
 
 // ============================================================================
-// SYSTEM COMMANDS
+// MCP COMMANDS
 // ============================================================================
 
+// MCP server state, with the client config to paste into Claude Desktop or an IDE
+// capability: system
 #[tauri::command]
-pub async fn system_get_ram() -> Result<u64, String> {
-    crate::stability::get_system_ram()
+pub async fn mcp_status(app: tauri::AppHandle) -> Result<McpStatus, String> {
+    mcp::status(&app).map_err(|e| e.to_string())
 }
 
+// ============================================================================
+// WORKFLOW COMMANDS
+// ============================================================================
+
+// n8n workflows with their webhook ids; needs the n8n API key
+// capability: agent
 #[tauri::command]
-pub async fn system_get_max_tabs(
-    memory_guard: tauri::State<'_, stability::MemoryGuard>,
-) -> Result<u32, String> {
-    Ok(memory_guard.get_max_tabs())
+pub async fn workflow_list(api_keys: tauri::State<'_, ApiKeyStore>) -> Result<Vec<Workflow>, String> {
+    workflows::list(&api_keys).await.map_err(|e| e.to_string())
 }
 
+// Start an n8n workflow by posting payload (JSON) to its webhook
+// capability: agent
 #[tauri::command]
-pub async fn get_system_info() -> Result<SystemInfo, String> {
-    let total_ram_bytes = crate::stability::get_system_ram()
-        .map_err(|e| format!("Failed to get RAM: {}", e))?;
-    let total_ram_gb = (total_ram_bytes as f64) / (1024.0 * 1024.0 * 1024.0);
-    
-    // Estimate available RAM (conservative: assume 50% used by system)
-    let available_ram_gb = total_ram_gb * 0.5;
-    
-    // Get CPU core count
-    let cpu_cores = num_cpus::get();
-    
-    Ok(SystemInfo {
-        total_ram_gb,
-        available_ram_gb,
-        cpu_cores,
-    })
+pub async fn workflow_trigger(webhook_id: String, payload: Option<serde_json::Value>) -> Result<WorkflowRun, String> {
+    workflows::trigger(&webhook_id, &payload.unwrap_or_else(|| serde_json::json!({}))).await.map_err(|e| e.to_string())
 }
 
 // ============================================================================
-// LEGACY/COMPATIBILITY COMMANDS (for existing frontend code)
+// CALENDAR COMMANDS
 // ============================================================================
 
+// Import events from an .ics file; events already imported (same UID) are updated
+// capability: files
 #[tauri::command]
-pub async fn search(
-    query: String,
-    search_engine: tauri::State<'_, SearchEngine>,
-) -> Result<Vec<serde_json::Value>, String> {
-    // Use SearchEngine for actual search
-    let results = search_engine.search(&query, 20)
+pub async fn calendar_import(
+    path: String,
+    db: tauri::State<'_, Database>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<ImportReport, String> {
+    privacy_enforcer.lock().unwrap().enforce_disk_write().map_err(|e| e.to_string())?;
+    let path = files::resolve(&path).map_err(|e| e.to_string())?;
+    let db = db.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || calendar::import_ics(&db, std::path::Path::new(&path)))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+// Occurrences from now through the next `days` days (default 7), recurring events expanded
+// capability: content
+#[tauri::command]
+pub async fn calendar_upcoming(days: Option<u32>, db: tauri::State<'_, Database>) -> Result<Vec<CalendarEvent>, String> {
+    calendar::upcoming(&db, days.unwrap_or(7)).map_err(|e| e.to_string())
+}
+
+// capability: content
+#[tauri::command]
+pub async fn calendar_add_event(
+    event: NewEvent,
+    db: tauri::State<'_, Database>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<CalendarEvent, String> {
+    privacy_enforcer.lock().unwrap().enforce_disk_write().map_err(|e| e.to_string())?;
+    calendar::add(&db, event, "manual").map_err(|e| e.to_string())
+}
+
+// capability: content
+#[tauri::command]
+pub async fn calendar_delete_event(id: String, db: tauri::State<'_, Database>) -> Result<bool, String> {
+    db.calendar_event_delete(&id).map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// EMAIL COMMANDS
+// ============================================================================
+
+// Draft an email (English or Hindi) from intent + research context and open it in the mail client
+// handoff: "mailto" (default; .eml when too long), "eml", or "none" to only return the draft
+// capability: open-external
+#[tauri::command]
+pub async fn compose_email(
+    recipient: Option<String>,
+    intent: String,
+    context: Option<String>,
+    language: Option<String>,
+    handoff: Option<EmailHandoff>,
+    ai_service: tauri::State<'_, AIService>,
+    privacy_enforcer: tauri::State<'_, Mutex<PrivacyEnforcer>>,
+) -> Result<EmailDraft, String> {
+    let mut draft = email::compose(&ai_service, recipient.as_deref(), &intent, context.as_deref(), language.as_deref())
+        .await
         .map_err(|e| e.to_string())?;
-    Ok(results.into_iter().map(|r| serde_json::to_value(r).unwrap()).collect())
+    let handoff = handoff.unwrap_or_default();
+    if handoff == EmailHandoff::Eml || (handoff == EmailHandoff::Mailto && draft.mailto_url.is_none()) {
+        privacy_enforcer.lock().unwrap().enforce_disk_write().map_err(|e| e.to_string())?;
+    }
+    email::handoff(&mut draft, handoff).map_err(|e| e.to_string())?;
+    Ok(draft)
+}
+
+// ============================================================================
+// SPELLCHECK COMMANDS
+// ============================================================================
+
+// Misspelled words (char ranges) with suggestions; `language` is detected when omitted
+// capability: ai
+#[tauri::command]
+pub async fn spellcheck(
+    text: String,
+    language: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<SpellReport, String> {
+    tauri::async_runtime::spawn_blocking(move || app.state::<SpellChecker>().check(&text, language.as_deref()))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+// Installed Hunspell dictionaries; more can be added as <lang>.aff/.dic in the app data "dictionaries" folder
+// capability: ai
+#[tauri::command]
+pub async fn spellcheck_dictionaries(
+    spellchecker: tauri::State<'_, SpellChecker>,
+) -> Result<Vec<DictionaryInfo>, String> {
+    Ok(spellchecker.dictionaries())
+}
+
+// ============================================================================
+// FLOATING WINDOW COMMANDS
+// ============================================================================
+
+// Pin any window (by label, e.g. "main") above other apps
+// capability: browsing
+#[tauri::command]
+pub async fn window_set_always_on_top(window_id: String, on_top: bool, app: tauri::AppHandle) -> Result<(), String> {
+    floating_windows::set_always_on_top(&app, &window_id, on_top).map_err(|e| e.to_string())
+}
+
+// Voice orb mini-window: "show" | "hide" | "toggle" | "status" | "reset-position"
+// capability: browsing
+#[tauri::command]
+pub async fn wispr_orb_window(action: FloatingAction, app: tauri::AppHandle) -> Result<FloatingWindowState, String> {
+    floating_windows::manage(&app, FloatingKind::WisprOrb, action).map_err(|e| e.to_string())
+}
+
+// capability: browsing
+#[tauri::command]
+pub async fn trade_ticker_window(action: FloatingAction, app: tauri::AppHandle) -> Result<FloatingWindowState, String> {
+    floating_windows::manage(&app, FloatingKind::TradeTicker, action).map_err(|e| e.to_string())
+}
+
+// Queue actions for approval; those the [approvals] rules allow run immediately.
+// Pending ones are announced with "agent-actions-proposed".
+// capability: agent
+#[tauri::command]
+pub async fn actions_propose(
+    actions: Vec<ActionRequest>,
+    run_id: Option<String>,
+    safe_mode: tauri::State<'_, stability::SafeMode>,
+    app: tauri::AppHandle,
+) -> Result<Vec<ProposedAction>, String> {
+    ensure_not_safe_mode(&safe_mode)?;
+    approvals::propose(&app, "frontend", run_id.as_deref(), actions).await.map_err(|e| e.to_string())
+}
+
+// capability: agent
+#[tauri::command]
+pub async fn actions_pending(app: tauri::AppHandle) -> Result<Vec<ProposedAction>, String> {
+    approvals::pending(&app).map_err(|e| e.to_string())
+}
+
+// Run the approved actions in order and return their outcomes
+// capability: agent
+#[tauri::command]
+pub async fn actions_approve(
+    ids: Vec<String>,
+    safe_mode: tauri::State<'_, stability::SafeMode>,
+    app: tauri::AppHandle,
+) -> Result<Vec<ProposedAction>, String> {
+    ensure_not_safe_mode(&safe_mode)?;
+    approvals::approve(&app, &ids).await.map_err(|e| e.to_string())
+}
+
+// capability: agent
+#[tauri::command]
+pub async fn actions_reject(ids: Vec<String>, app: tauri::AppHandle) -> Result<Vec<ProposedAction>, String> {
+    approvals::reject(&app, &ids).map_err(|e| e.to_string())
+}
+
+// capability: agent
+#[tauri::command]
+pub async fn agent_runs_list(
+    filter: Option<RunFilter>,
+    db: tauri::State<'_, Database>,
+) -> Result<Vec<AgentRunSummary>, String> {
+    agent_runs::list(&db, &filter.unwrap_or_default()).map_err(|e| e.to_string())
+}
+
+// Full transcript: every prompt (with provider, model, and token counts) and event, in order
+// capability: agent
+#[tauri::command]
+pub async fn agent_run_get(id: String, db: tauri::State<'_, Database>) -> Result<AgentRun, String> {
+    agent_runs::get(&db, &id).map_err(|e| e.to_string())
+}
+
+// Re-emit a past run's events on its original channel; returns how many were sent
+// capability: agent
+#[tauri::command]
+pub async fn agent_run_replay(
+    id: String,
+    request_id: Option<String>,
+    realtime: Option<bool>,
+    app: tauri::AppHandle,
+) -> Result<usize, String> {
+    agent_runs::replay(&app, &id, request_id, realtime.unwrap_or(false)).await.map_err(|e| e.to_string())
+}
+
+// capability: agent
+#[tauri::command]
+pub async fn agent_run_delete(id: String, db: tauri::State<'_, Database>) -> Result<(), String> {
+    agent_runs::delete(&db, &id).map_err(|e| e.to_string())
+}
+
+// Token usage and estimated cost per provider and model for the last `days` days (default 7)
+// capability: ai
+#[tauri::command]
+pub async fn llm_usage_report(days: Option<u32>) -> Result<llm_usage::UsageReport, String> {
+    llm_usage::report(days.unwrap_or(7)).map_err(|e| e.to_string())
+}
+
+// Usage of a call made outside this process (the backend's cloud router), ideally provider-reported.
+// Returns where the provider stands against its budgets afterwards.
+// capability: ai
+#[tauri::command]
+pub async fn llm_usage_record(
+    provider: String,
+    model: String,
+    prompt_tokens: u64,
+    completion_tokens: u64,
+    estimated: Option<bool>,
+) -> Result<BudgetCheck, String> {
+    let provider = provider.trim().to_lowercase();
+    if !llm_usage::PROVIDERS.contains(&provider.as_str()) {
+        return Err(format!("Unknown provider '{}'", provider));
+    }
+    llm_usage::record(&provider, &model, prompt_tokens, completion_tokens, !estimated.unwrap_or(false));
+    Ok(llm_usage::check(&provider, &model))
+}
+
+// Ask before a call: allow, downgrade to the local model, or block
+// capability: ai
+#[tauri::command]
+pub async fn llm_budget_check(provider: String, model: String) -> Result<BudgetCheck, String> {
+    Ok(llm_usage::check(&provider, &model))
+}
+
+// Whether the chat and embedding models are loaded yet (live updates arrive as "models:warmup")
+// capability: ai
+#[tauri::command]
+pub async fn models_warmup_status() -> Result<WarmupStatus, String> {
+    Ok(warmup::current().await)
+}
+
+// Replace personal data in a prompt bound for a cloud provider (per [redaction]); local providers pass through.
+// Names saved in autofill profiles are always treated as names.
+// capability: ai
+#[tauri::command]
+pub async fn pii_redact(
+    text: String,
+    provider: String,
+    app: tauri::AppHandle,
+) -> Result<RedactedText, String> {
+    let provider = provider.trim().to_lowercase();
+    if !llm_usage::PROVIDERS.contains(&provider.as_str()) {
+        return Err(format!("Unknown provider '{}'", provider));
+    }
+    let names: Vec<String> = app
+        .try_state::<AutofillStore>()
+        .map(|store| {
+            store
+                .list()
+                .into_iter()
+                .flat_map(|p| [p.full_name, p.given_name, p.family_name, p.cardholder])
+                .filter(|n| !n.trim().is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(redaction::redact(&provider, &text, &names))
+}
+
+// Put the redacted values back into a provider response (or a streamed chunk of one)
+// capability: ai
+#[tauri::command]
+pub async fn pii_restore(id: String, text: String) -> Result<String, String> {
+    redaction::restore(&id, &text).map_err(|e| e.to_string())
+}
+
+// What was redacted this session, newest first (kinds and placeholders only)
+// capability: ai
+#[tauri::command]
+pub async fn pii_redaction_log(limit: Option<usize>) -> Result<Vec<RedactionLogEntry>, String> {
+    Ok(redaction::log(limit))
 }
 
 // ============================================================================
@@ -678,14 +4426,20 @@ pub async fn search(
 // ============================================================================
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
 pub struct TaskResponse {
     pub ok: bool,
     pub id: Option<String>,
     pub error: Option<String>,
 }
 
+// capability: ai
 #[tauri::command]
-pub async fn run_demo_agent(intent: String) -> Result<TaskResponse, String> {
+pub async fn run_demo_agent(
+    intent: String,
+    safe_mode: tauri::State<'_, stability::SafeMode>,
+) -> Result<TaskResponse, String> {
+    ensure_not_safe_mode(&safe_mode)?;
     // This would normally call into the Node.js task system
     // For now, return a placeholder response
     // In a real implementation, this would trigger the Node.js demoAgentRunner
@@ -704,17 +4458,20 @@ pub async fn run_demo_agent(intent: String) -> Result<TaskResponse, String> {
 // ============================================================================
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
 pub struct TabResponse {
     pub tab_id: String,
 }
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
 pub struct AIRunPayload {
     pub task: String,
     pub context: Option<serde_json::Value>,
 }
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
 pub struct AIResponse {
     pub success: bool,
     pub result: Option<String>,
@@ -722,6 +4479,7 @@ pub struct AIResponse {
 }
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
 pub struct DownloadResponse {
     pub success: bool,
     pub download_id: Option<String>,
@@ -729,6 +4487,7 @@ pub struct DownloadResponse {
 }
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
 pub struct SystemStateResponse {
     pub tabs: Vec<serde_json::Value>,
     pub active_tab_id: Option<String>,
@@ -739,6 +4498,7 @@ pub struct SystemStateResponse {
 }
 
 // Tab management commands
+// capability: browsing
 #[tauri::command]
 pub async fn new_tab(url: Option<String>) -> Result<TabResponse, String> {
     // This would forward to the Node.js backend
@@ -747,12 +4507,14 @@ pub async fn new_tab(url: Option<String>) -> Result<TabResponse, String> {
     Ok(TabResponse { tab_id })
 }
 
+// capability: browsing
 #[tauri::command]
 pub async fn close_tab(tab_id: String) -> Result<(), String> {
     // Forward to Node.js backend
     Ok(())
 }
 
+// capability: browsing
 #[tauri::command]
 pub async fn switch_tab(tab_id: String) -> Result<(), String> {
     // Forward to Node.js backend
@@ -760,24 +4522,28 @@ pub async fn switch_tab(tab_id: String) -> Result<(), String> {
 }
 
 // Navigation commands
+// capability: browsing
 #[tauri::command]
 pub async fn navigate(tab_id: String, url: String) -> Result<(), String> {
     // Forward to Node.js backend NavigationController
     Ok(())
 }
 
+// capability: browsing
 #[tauri::command]
 pub async fn back(tab_id: String) -> Result<(), String> {
     // Forward to Node.js backend
     Ok(())
 }
 
+// capability: browsing
 #[tauri::command]
 pub async fn forward(tab_id: String) -> Result<(), String> {
     // Forward to Node.js backend
     Ok(())
 }
 
+// capability: browsing
 #[tauri::command]
 pub async fn reload(tab_id: String) -> Result<(), String> {
     // Forward to Node.js backend
@@ -785,8 +4551,13 @@ pub async fn reload(tab_id: String) -> Result<(), String> {
 }
 
 // AI commands
+// capability: ai
 #[tauri::command]
-pub async fn run_ai(payload: AIRunPayload) -> Result<AIResponse, String> {
+pub async fn run_ai(
+    payload: AIRunPayload,
+    safe_mode: tauri::State<'_, stability::SafeMode>,
+) -> Result<AIResponse, String> {
+    ensure_not_safe_mode(&safe_mode)?;
     // Forward to Node.js backend AIController
     // For now, return mock response
     Ok(AIResponse {
@@ -797,6 +4568,7 @@ pub async fn run_ai(payload: AIRunPayload) -> Result<AIResponse, String> {
 }
 
 // Download commands
+// capability: files
 #[tauri::command]
 pub async fn download(filename: String, url: String) -> Result<DownloadResponse, String> {
     // Forward to Node.js backend DownloadManager
@@ -809,6 +4581,7 @@ pub async fn download(filename: String, url: String) -> Result<DownloadResponse,
 }
 
 // System state
+// capability: browsing
 #[tauri::command]
 pub async fn get_state() -> Result<SystemStateResponse, String> {
     // Get current state from Node.js backend
@@ -823,6 +4596,7 @@ pub async fn get_state() -> Result<SystemStateResponse, String> {
     })
 }
 
+// capability: ai
 #[tauri::command]
 pub async fn cancel_task(task_id: String) -> Result<TaskResponse, String> {
     // Similar to run_demo_agent, this would trigger Node.js task cancellation
@@ -831,4 +4605,32 @@ pub async fn cancel_task(task_id: String) -> Result<TaskResponse, String> {
         id: Some(task_id),
         error: None,
     })
-}
\ No newline at end of file
+}
+
+// REGISTERED_COMMANDS and invoke_registered, generated by build.rs for the command palette
+include!(concat!(env!("OUT_DIR"), "/command_registry.rs"));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_switch_locks_password_vault() {
+        // No OS keychain on CI; the mock keeps vault keys in memory
+        keyring::set_default_credential_builder(keyring::mock::default_credential_builder());
+        let root = std::env::temp_dir().join(format!("regen-profile-switch-{}", std::process::id()));
+        let profiles = ProfileManager::load(root.clone());
+        let store = SecureStore::open(profiles.secure_storage_dir()).unwrap();
+        let passwords = PasswordVault::new(store.clone());
+        assert!(!passwords.unlock("default master").unwrap().locked);
+
+        profiles.create("Work").unwrap();
+        profiles.set_active("Work").unwrap();
+        reopen_secure_storage(&profiles, Some(&store), Some(&passwords)).unwrap();
+
+        let status = passwords.status();
+        assert!(status.locked);
+        assert!(!status.has_master_password);
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,906 @@
+// Config - Typed settings backed by config.toml in app data
+// Schema defaults, validation, env-var migration, and hot reload when the file is edited
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+pub const FILE_NAME: &str = "config.toml";
+// Editors often write a file in several steps; wait for them to settle before reloading
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(300);
+
+// Environment variables that used to configure the app, migrated into config.toml on first run
+// (variable, dotted config key)
+const ENV_MIGRATIONS: &[(&str, &str)] = &[
+    ("OLLAMA_MODEL", "ai.model"),
+    ("OLLAMA_EMBED_MODEL", "ai.embedModel"),
+    ("OLLAMA_BASE_URL", "ai.ollamaUrl"),
+    ("OLLAMA_URL", "ai.ollamaUrl"),
+    ("REGEN_SERVER_URL", "server.url"),
+    ("OPENAI_BASE_URL", "providers.openaiBaseUrl"),
+    ("ANTHROPIC_BASE_URL", "providers.anthropicBaseUrl"),
+    ("HUGGINGFACE_BASE_URL", "providers.huggingfaceBaseUrl"),
+    ("REGEN_LOG", "logging.level"),
+    ("SEARXNG_URL", "search.searxngUrl"),
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppConfig {
+    pub ai: AiConfig,
+    pub server: ServerConfig,
+    pub providers: ProviderConfig,
+    pub logging: LoggingConfig,
+    pub resources: ResourceConfig,
+    pub services: ServicesConfig,
+    pub search: SearchConfig,
+    pub passwords: PasswordConfig,
+    pub dns: DnsConfig,
+    pub proxy: ProxyConfig,
+    pub network_audit: NetworkAuditConfig,
+    pub tools: ToolsConfig,
+    pub approvals: ApprovalConfig,
+    pub llm_budget: LlmBudgetConfig,
+    pub redaction: RedactionConfig,
+    pub chunking: ChunkingConfig,
+    pub feeds: FeedsConfig,
+    pub digest: DigestConfig,
+    pub trade: TradeConfig,
+    pub voice: VoiceConfig,
+    pub notifications: NotificationsConfig,
+    pub api: ApiConfig,
+    pub mcp: McpConfig,
+    pub modes: BTreeMap<String, crate::modes::ModeOverride>,  // Per-mode profile overrides, keyed by lowercase mode
+    pub shortcuts: BTreeMap<String, String>,   // Action -> accelerator overrides; "" unbinds
+    pub permissions: Vec<crate::permissions::Grant>,  // Command capabilities granted beyond the app's own windows
+    pub local_only: bool,              // Refuse every backend request to a non-loopback host (see http.rs)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct AiConfig {
+    // Unset means the hardware-derived default (see hardware::model_defaults)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embed_model: Option<String>,
+    // Ollama model for image captions; unset picks an installed vision model (see images.rs)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vision_model: Option<String>,
+    pub ollama_url: String,
+    pub max_tokens: usize,
+    pub temperature: f32,
+    pub warmup: bool,                  // Load the chat and embedding models once Ollama is up (see warmup.rs)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct ServerConfig {
+    pub url: String,                   // Regen Node.js backend
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProviderConfig {
+    pub openai_base_url: String,
+    pub anthropic_base_url: String,
+    pub huggingface_base_url: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct LoggingConfig {
+    pub level: String,
+    pub targets: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct ResourceConfig {
+    // Unset means 60% of physical RAM
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub budget_mb: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct ServicesConfig {
+    pub autostart: bool,
+    pub disabled: Vec<String>,         // Supervised services never started (e.g. "n8n")
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct SearchConfig {
+    pub providers: Vec<String>,        // Used when search_web isn't given a list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub searxng_url: Option<String>,   // User-hosted instance; unset disables SearxNG
+    pub meilisearch_url: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct PasswordConfig {
+    pub idle_lock_minutes: u32,        // 0 keeps the vault unlocked until locked by hand
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct DnsConfig {
+    pub provider: String,              // DNS-over-HTTPS provider for backend requests: cloudflare or quad9
+    pub normal_mode: bool,             // Also use DoH in Normal mode (Private/Ghost always do); off uses system DNS
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProxyConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,           // http(s):// or socks5(h):// proxy for backend requests; unset connects directly
+    pub bypass: Vec<String>,           // Hosts reached directly (local services always are)
+    pub profiles: BTreeMap<String, String>,    // Profile name -> proxy URL overriding url; "" connects directly
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct NetworkAuditConfig {
+    pub persist: bool,                 // Also keep the audit log in SQLite (Normal mode only); off keeps it in memory
+    pub retention_days: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct ToolsConfig {
+    pub disabled: Vec<String>,         // Agent tools that may never be called (e.g. "open_tab")
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct ApprovalConfig {
+    pub enabled: bool,                 // Off runs every agent action immediately
+    pub auto_approve: Vec<crate::tools::Risk>, // Risk levels that never wait for the user
+    pub rules: BTreeMap<String, crate::approvals::ApprovalRule>, // Tool -> "auto" or "ask", overriding autoApprove
+    pub timeout_secs: u64,             // How long an agent waits for a decision
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct LlmBudgetConfig {
+    pub prices: BTreeMap<String, crate::llm_usage::ModelPrice>, // "provider/model" or "provider" -> USD per million tokens
+    pub budgets: BTreeMap<String, crate::llm_usage::ProviderBudget>, // Provider (or "total") -> daily limits
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChunkingConfig {
+    pub ollama_context: usize,         // num_ctx Ollama runs the app's requests with; caps every local model's window
+    pub context_tokens: BTreeMap<String, usize>, // Model (with or without tag) -> context window, overriding the built-in table
+    pub overlap_tokens: usize,         // Repeated from the end of one retrieval chunk at the start of the next
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct RedactionConfig {
+    pub enabled: bool,                 // Off sends prompts to cloud providers unchanged
+    pub kinds: Vec<crate::redaction::PiiKind>,
+    pub providers: BTreeMap<String, crate::redaction::ProviderRedaction>, // Per cloud provider overrides
+    pub names: Vec<String>,            // Always redacted as names, alongside autofill profile names
+    pub allow: Vec<String>,            // Values never redacted (e.g. your company's support address)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct FeedsConfig {
+    pub refresh_minutes: u32,          // 0 turns background refresh off
+    pub discover: bool,                // Look for feed links on sites visited with history on
+    pub keep_entries: usize,           // Read entries kept per feed, newest first
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct TradeConfig {
+    pub news_sources: Vec<String>,     // "finnhub" (needs its API key) or RSS/Atom URLs with a {symbol} placeholder
+    pub news_sentiment: bool,          // Score headlines with the local model unless trade_news says otherwise
+    pub news_cache_minutes: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct VoiceConfig {
+    // ggml model for whisper.cpp; unset uses the first ggml-*.bin in the app data models/ directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub whisper_model: Option<String>,
+    pub language: String,              // Spoken language for transcription ("auto" lets whisper detect it)
+    pub speak: bool,                   // Read replies aloud unless wispr_handle says otherwise
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tts_voice: Option<String>,     // Voice name for say/espeak/SAPI; unset uses the system default
+    pub tts_rate: u32,                 // Words per minute; 0 keeps the system default
+    pub hotword: bool,                 // Listen for "Hey Regen" (muted in Private/Ghost modes)
+    pub hotword_sensitivity: f64,      // 0 needs the exact phrase; 1 accepts loose matches (more false wakes)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotificationsConfig {
+    pub enabled: bool,                 // Native notifications; off still records them for the in-app list
+    // Do-not-disturb window in local time ("HH:MM"); may wrap midnight. Unset means never
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dnd_start: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dnd_end: Option<String>,
+}
+
+// Local REST API for scripts and launchers (see services/api_server.rs); the token lives in secure storage
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct ApiConfig {
+    pub enabled: bool,
+    pub port: u16,                     // Bound on 127.0.0.1 only
+}
+
+// MCP server for external AI clients (see mcp.rs), served through the local API
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct McpConfig {
+    pub enabled: bool,                 // Needs api.enabled
+    pub trusted_tools: Vec<String>,    // Run without asking; every other tool call waits for the user's approval
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct DigestConfig {
+    pub weekly: bool,                  // Compose a digest of the past week once a week
+    pub weekday: String,               // "mon".."sun"; the weekly digest waits for this day
+}
+
+impl Default for AiConfig {
+    fn default() -> Self {
+        Self {
+            model: None,
+            embed_model: None,
+            vision_model: None,
+            ollama_url: "http://127.0.0.1:11434".to_string(),
+            max_tokens: 2048,
+            temperature: 0.7,
+            warmup: true,
+        }
+    }
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self { url: "http://127.0.0.1:4000".to_string() }
+    }
+}
+
+impl Default for ProviderConfig {
+    fn default() -> Self {
+        Self {
+            openai_base_url: "https://api.openai.com/v1".to_string(),
+            anthropic_base_url: "https://api.anthropic.com/v1".to_string(),
+            huggingface_base_url: "https://api-inference.huggingface.co".to_string(),
+        }
+    }
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self { level: "info".to_string(), targets: BTreeMap::new() }
+    }
+}
+
+impl Default for ServicesConfig {
+    fn default() -> Self {
+        Self { autostart: true, disabled: Vec::new() }
+    }
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            providers: ["duckduckgo", "brave", "searxng", "meilisearch"].map(String::from).to_vec(),
+            searxng_url: None,
+            meilisearch_url: "http://127.0.0.1:7700".to_string(),
+        }
+    }
+}
+
+impl Default for PasswordConfig {
+    fn default() -> Self {
+        Self { idle_lock_minutes: 15 }
+    }
+}
+
+impl Default for NetworkAuditConfig {
+    fn default() -> Self {
+        Self { persist: false, retention_days: 30 }
+    }
+}
+
+impl Default for ApprovalConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            auto_approve: vec![crate::tools::Risk::Low],
+            rules: BTreeMap::new(),
+            timeout_secs: 300,
+        }
+    }
+}
+
+impl Default for ChunkingConfig {
+    fn default() -> Self {
+        Self { ollama_context: 4096, context_tokens: BTreeMap::new(), overlap_tokens: 48 }
+    }
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            kinds: crate::redaction::PiiKind::ALL.to_vec(),
+            providers: BTreeMap::new(),
+            names: Vec::new(),
+            allow: Vec::new(),
+        }
+    }
+}
+
+impl Default for FeedsConfig {
+    fn default() -> Self {
+        Self { refresh_minutes: 60, discover: true, keep_entries: 200 }
+    }
+}
+
+impl Default for TradeConfig {
+    fn default() -> Self {
+        Self {
+            news_sources: vec![
+                "finnhub".to_string(),
+                "https://feeds.finance.yahoo.com/rss/2.0/headline?s={symbol}&region=US&lang=en-US".to_string(),
+                "https://news.google.com/rss/search?q={symbol}+stock&hl=en-US&gl=US&ceid=US:en".to_string(),
+            ],
+            news_sentiment: false,
+            news_cache_minutes: 10,
+        }
+    }
+}
+
+impl Default for VoiceConfig {
+    fn default() -> Self {
+        Self {
+            whisper_model: None,
+            language: "auto".to_string(),
+            speak: true,
+            tts_voice: None,
+            tts_rate: 0,
+            hotword: false,
+            hotword_sensitivity: 0.5,
+        }
+    }
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self { enabled: true, dnd_start: None, dnd_end: None }
+    }
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self { enabled: false, port: 17_650 }
+    }
+}
+
+impl Default for DigestConfig {
+    fn default() -> Self {
+        Self { weekly: false, weekday: "mon".to_string() }
+    }
+}
+
+impl Default for DnsConfig {
+    fn default() -> Self {
+        Self { provider: "cloudflare".to_string(), normal_mode: false }
+    }
+}
+
+impl AiConfig {
+    pub fn chat_model(&self) -> String {
+        self.model
+            .clone()
+            .unwrap_or_else(|| crate::hardware::capabilities().models.chat_model.clone())
+    }
+
+    pub fn embed_model(&self) -> String {
+        self.embed_model
+            .clone()
+            .unwrap_or_else(|| crate::hardware::capabilities().models.embed_model.clone())
+    }
+}
+
+impl AppConfig {
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let urls = [
+            ("ai.ollamaUrl", &self.ai.ollama_url),
+            ("server.url", &self.server.url),
+            ("providers.openaiBaseUrl", &self.providers.openai_base_url),
+            ("providers.anthropicBaseUrl", &self.providers.anthropic_base_url),
+            ("providers.huggingfaceBaseUrl", &self.providers.huggingface_base_url),
+            ("search.meilisearchUrl", &self.search.meilisearch_url),
+        ];
+        let searxng = self.search.searxng_url.iter().map(|url| ("search.searxngUrl", url));
+        for (key, value) in urls.into_iter().chain(searxng) {
+            match url::Url::parse(value) {
+                Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+                _ => return Err(ConfigError::Invalid(key.to_string(), format!("'{}' is not an http(s) URL", value))),
+            }
+        }
+        for (key, model) in [("ai.model", &self.ai.model), ("ai.embedModel", &self.ai.embed_model), ("ai.visionModel", &self.ai.vision_model)] {
+            if model.as_deref().map(|m| m.trim().is_empty()).unwrap_or(false) {
+                return Err(ConfigError::Invalid(key.to_string(), "model name is empty".to_string()));
+            }
+        }
+        for provider in &self.search.providers {
+            if !crate::web_search::PROVIDERS.contains(&provider.as_str()) {
+                return Err(ConfigError::Invalid("search.providers".to_string(), format!("unknown provider '{}'", provider)));
+            }
+        }
+        if self.ai.max_tokens == 0 || self.ai.max_tokens > 131_072 {
+            return Err(ConfigError::Invalid("ai.maxTokens".to_string(), "must be between 1 and 131072".to_string()));
+        }
+        if !(0.0..=2.0).contains(&self.ai.temperature) {
+            return Err(ConfigError::Invalid("ai.temperature".to_string(), "must be between 0 and 2".to_string()));
+        }
+        for (key, level) in std::iter::once(("logging.level".to_string(), &self.logging.level))
+            .chain(self.logging.targets.iter().map(|(t, l)| (format!("logging.targets.{}", t), l)))
+        {
+            if !crate::logging::LEVELS.contains(&level.to_lowercase().as_str()) {
+                return Err(ConfigError::Invalid(key, format!("unknown level '{}'", level)));
+            }
+        }
+        if let Err((action, message)) = crate::services::global_shortcut_service::validate(&self.shortcuts) {
+            return Err(ConfigError::Invalid(format!("shortcuts.{}", action), message));
+        }
+        if let Err((key, message)) = crate::modes::validate(&self.modes) {
+            return Err(ConfigError::Invalid(format!("modes.{}", key), message));
+        }
+        if let Err((index, message)) = crate::permissions::validate(&self.permissions) {
+            return Err(ConfigError::Invalid(format!("permissions.{}", index), message));
+        }
+        if self.passwords.idle_lock_minutes > 24 * 60 {
+            return Err(ConfigError::Invalid("passwords.idleLockMinutes".to_string(), "must be at most 1440".to_string()));
+        }
+        let proxies = self.proxy.url.iter().map(|url| ("proxy.url".to_string(), url)).chain(
+            self.proxy.profiles.iter().filter(|(_, url)| !url.trim().is_empty()).map(|(p, url)| (format!("proxy.profiles.{}", p), url)),
+        );
+        for (key, url) in proxies {
+            if let Err(message) = crate::proxy::validate(url) {
+                return Err(ConfigError::Invalid(key, message));
+            }
+        }
+        if !(1..=365).contains(&self.network_audit.retention_days) {
+            return Err(ConfigError::Invalid("networkAudit.retentionDays".to_string(), "must be between 1 and 365".to_string()));
+        }
+        for tool in &self.tools.disabled {
+            if !crate::tools::NAMES.contains(&tool.as_str()) {
+                return Err(ConfigError::Invalid("tools.disabled".to_string(), format!("unknown tool '{}'", tool)));
+            }
+        }
+        for tool in self.approvals.rules.keys() {
+            if !crate::tools::NAMES.contains(&tool.as_str()) {
+                return Err(ConfigError::Invalid(format!("approvals.rules.{}", tool), "unknown tool".to_string()));
+            }
+        }
+        if !(10..=3600).contains(&self.approvals.timeout_secs) {
+            return Err(ConfigError::Invalid("approvals.timeoutSecs".to_string(), "must be between 10 and 3600".to_string()));
+        }
+        for (key, price) in &self.llm_budget.prices {
+            let provider = key.split('/').next().unwrap_or_default();
+            if !crate::llm_usage::PROVIDERS.contains(&provider) {
+                return Err(ConfigError::Invalid(format!("llmBudget.prices.{}", key), format!("unknown provider '{}'", provider)));
+            }
+            if !(price.input.is_finite() && price.input >= 0.0 && price.output.is_finite() && price.output >= 0.0) {
+                return Err(ConfigError::Invalid(format!("llmBudget.prices.{}", key), "prices must be non-negative".to_string()));
+            }
+        }
+        for (provider, budget) in &self.llm_budget.budgets {
+            let key = format!("llmBudget.budgets.{}", provider);
+            if provider != crate::llm_usage::TOTAL && !crate::llm_usage::PROVIDERS.contains(&provider.as_str()) {
+                return Err(ConfigError::Invalid(key, "unknown provider".to_string()));
+            }
+            if budget.daily_tokens == Some(0) || budget.daily_cost_usd.is_some_and(|c| !(c.is_finite() && c > 0.0)) {
+                return Err(ConfigError::Invalid(key, "limits must be positive".to_string()));
+            }
+        }
+        for provider in self.redaction.providers.keys() {
+            if !crate::llm_usage::PROVIDERS.contains(&provider.as_str()) {
+                return Err(ConfigError::Invalid(format!("redaction.providers.{}", provider), "unknown provider".to_string()));
+            }
+        }
+        if self.redaction.names.iter().any(|n| n.trim().chars().count() < 2) {
+            return Err(ConfigError::Invalid("redaction.names".to_string(), "names must be at least 2 characters".to_string()));
+        }
+        if !(512..=1_048_576).contains(&self.chunking.ollama_context) {
+            return Err(ConfigError::Invalid("chunking.ollamaContext".to_string(), "must be between 512 and 1048576".to_string()));
+        }
+        for (model, tokens) in &self.chunking.context_tokens {
+            if !(128..=1_048_576).contains(tokens) {
+                return Err(ConfigError::Invalid(format!("chunking.contextTokens.{}", model), "must be between 128 and 1048576".to_string()));
+            }
+        }
+        if self.chunking.overlap_tokens > 512 {
+            return Err(ConfigError::Invalid("chunking.overlapTokens".to_string(), "must be at most 512".to_string()));
+        }
+        if self.feeds.refresh_minutes != 0 && !(15..=7 * 24 * 60).contains(&self.feeds.refresh_minutes) {
+            return Err(ConfigError::Invalid("feeds.refreshMinutes".to_string(), "must be 0 (off) or between 15 and 10080".to_string()));
+        }
+        if self.feeds.keep_entries == 0 {
+            return Err(ConfigError::Invalid("feeds.keepEntries".to_string(), "must be at least 1".to_string()));
+        }
+        for source in &self.trade.news_sources {
+            if source == "finnhub" {
+                continue;
+            }
+            match url::Url::parse(&source.replace("{symbol}", "X")) {
+                Ok(url) if matches!(url.scheme(), "http" | "https") && source.contains("{symbol}") => {}
+                _ => {
+                    return Err(ConfigError::Invalid(
+                        "trade.newsSources".to_string(),
+                        format!("'{}' must be \"finnhub\" or an http(s) URL containing {{symbol}}", source),
+                    ))
+                }
+            }
+        }
+        if !(1..=24 * 60).contains(&self.trade.news_cache_minutes) {
+            return Err(ConfigError::Invalid("trade.newsCacheMinutes".to_string(), "must be between 1 and 1440".to_string()));
+        }
+        let language = self.voice.language.as_str();
+        if language != "auto" && !(language.len() == 2 && language.chars().all(|c| c.is_ascii_lowercase())) {
+            return Err(ConfigError::Invalid("voice.language".to_string(), "must be \"auto\" or a two-letter language code".to_string()));
+        }
+        if self.voice.tts_rate != 0 && !(80..=400).contains(&self.voice.tts_rate) {
+            return Err(ConfigError::Invalid("voice.ttsRate".to_string(), "must be 0 or between 80 and 400".to_string()));
+        }
+        if !(0.0..=1.0).contains(&self.voice.hotword_sensitivity) {
+            return Err(ConfigError::Invalid("voice.hotwordSensitivity".to_string(), "must be between 0 and 1".to_string()));
+        }
+        match (&self.notifications.dnd_start, &self.notifications.dnd_end) {
+            (None, None) => {}
+            (Some(start), Some(end)) => {
+                for (key, value) in [("notifications.dndStart", start), ("notifications.dndEnd", end)] {
+                    if chrono::NaiveTime::parse_from_str(value, "%H:%M").is_err() {
+                        return Err(ConfigError::Invalid(key.to_string(), "must be a time like \"22:00\"".to_string()));
+                    }
+                }
+            }
+            _ => {
+                return Err(ConfigError::Invalid(
+                    "notifications.dndStart".to_string(),
+                    "dndStart and dndEnd must be set together".to_string(),
+                ))
+            }
+        }
+        if self.digest.weekday.parse::<chrono::Weekday>().is_err() {
+            return Err(ConfigError::Invalid("digest.weekday".to_string(), "must be a day of the week (mon..sun)".to_string()));
+        }
+        if !crate::doh::PROVIDERS.contains(&self.dns.provider.as_str()) {
+            return Err(ConfigError::Invalid("dns.provider".to_string(), format!("unknown provider '{}'", self.dns.provider)));
+        }
+        if self.api.port < 1024 {
+            return Err(ConfigError::Invalid("api.port".to_string(), "must be between 1024 and 65535".to_string()));
+        }
+        if self.mcp.enabled && !self.api.enabled {
+            return Err(ConfigError::Invalid("mcp.enabled".to_string(), "needs the local API (api.enabled)".to_string()));
+        }
+        for tool in &self.mcp.trusted_tools {
+            if !crate::tools::NAMES.contains(&tool.as_str()) {
+                return Err(ConfigError::Invalid("mcp.trustedTools".to_string(), format!("unknown tool '{}'", tool)));
+            }
+        }
+        if let Some(budget) = self.resources.budget_mb {
+            if budget < 512 {
+                return Err(ConfigError::Invalid("resources.budgetMb".to_string(), "must be at least 512".to_string()));
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct ConfigStore {
+    config: RwLock<AppConfig>,
+    path: RwLock<Option<PathBuf>>,
+    // Last contents written or loaded, so our own writes don't trigger a reload
+    last_contents: RwLock<Option<String>>,
+}
+
+pub fn store() -> &'static ConfigStore {
+    static STORE: OnceLock<ConfigStore> = OnceLock::new();
+    STORE.get_or_init(|| ConfigStore {
+        config: RwLock::new(AppConfig::default()),
+        path: RwLock::new(None),
+        last_contents: RwLock::new(None),
+    })
+}
+
+// Shorthand for store().current()
+pub fn current() -> AppConfig {
+    store().current()
+}
+
+impl ConfigStore {
+    pub fn current(&self) -> AppConfig {
+        self.config.read().unwrap().clone()
+    }
+
+    pub fn path(&self) -> Option<PathBuf> {
+        self.path.read().unwrap().clone()
+    }
+
+    // Load config.toml; a missing file is created from defaults plus any legacy env vars
+    pub fn load(&self, path: PathBuf) -> Result<AppConfig, ConfigError> {
+        *self.path.write().unwrap() = Some(path.clone());
+        let config = match std::fs::read_to_string(&path) {
+            Ok(contents) => {
+                let config = parse(&contents)?;
+                *self.last_contents.write().unwrap() = Some(contents);
+                config
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let (config, migrated) = migrate_env(AppConfig::default());
+                if !migrated.is_empty() {
+                    tracing::info!(target: "app", "Config: Migrated {} from environment", migrated.join(", "));
+                }
+                self.write(&path, &config)?;
+                config
+            }
+            Err(e) => return Err(ConfigError::Io(e.to_string())),
+        };
+        *self.config.write().unwrap() = config.clone();
+        Ok(config)
+    }
+
+    // Re-read the file; Ok(None) when nothing changed
+    pub fn reload(&self) -> Result<Option<AppConfig>, ConfigError> {
+        let path = self.path().ok_or(ConfigError::NotLoaded)?;
+        let contents = std::fs::read_to_string(&path).map_err(|e| ConfigError::Io(e.to_string()))?;
+        if self.last_contents.read().unwrap().as_deref() == Some(contents.as_str()) {
+            return Ok(None);
+        }
+        let config = parse(&contents)?;
+        *self.last_contents.write().unwrap() = Some(contents);
+
+        let mut current = self.config.write().unwrap();
+        if *current == config {
+            return Ok(None);
+        }
+        *current = config.clone();
+        Ok(Some(config))
+    }
+
+    // Value at a dotted camelCase key ("ai.ollamaUrl"), or the whole config
+    pub fn get(&self, key: Option<&str>) -> Result<serde_json::Value, ConfigError> {
+        let value = serde_json::to_value(self.current()).map_err(|e| ConfigError::Parse(e.to_string()))?;
+        match key {
+            None => Ok(value),
+            Some(key) => lookup(&value, key).cloned().ok_or_else(|| ConfigError::UnknownKey(key.to_string())),
+        }
+    }
+
+    // Set one dotted key (null clears optional keys back to their default), validate, and save
+    pub fn set(&self, key: &str, value: serde_json::Value) -> Result<AppConfig, ConfigError> {
+        let path = self.path().ok_or(ConfigError::NotLoaded)?;
+        let mut tree = serde_json::to_value(self.current()).map_err(|e| ConfigError::Parse(e.to_string()))?;
+        if !set_path(&mut tree, key, value.clone()) {
+            return Err(ConfigError::UnknownKey(key.to_string()));
+        }
+
+        let config: AppConfig = serde_json::from_value(tree)
+            .map_err(|e| ConfigError::Invalid(key.to_string(), e.to_string()))?;
+        // Unknown keys are silently dropped by deserialization; catch them by reading the value back
+        let stored = serde_json::to_value(&config).map_err(|e| ConfigError::Parse(e.to_string()))?;
+        if !value.is_null() && lookup(&stored, key) != Some(&value) {
+            return Err(ConfigError::UnknownKey(key.to_string()));
+        }
+        config.validate()?;
+        self.write(&path, &config)?;
+        *self.config.write().unwrap() = config.clone();
+        Ok(config)
+    }
+
+    // Atomic write (temp file + rename) so a watcher never sees half a file
+    fn write(&self, path: &Path, config: &AppConfig) -> Result<(), ConfigError> {
+        let contents = format!(
+            "# Regen configuration. Edits are picked up while the app is running.\n\n{}",
+            toml::to_string_pretty(config).map_err(|e| ConfigError::Parse(e.to_string()))?
+        );
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| ConfigError::Io(e.to_string()))?;
+        }
+        let tmp = path.with_extension("toml.tmp");
+        std::fs::write(&tmp, &contents).map_err(|e| ConfigError::Io(e.to_string()))?;
+        std::fs::rename(&tmp, path).map_err(|e| ConfigError::Io(e.to_string()))?;
+        *self.last_contents.write().unwrap() = Some(contents);
+        Ok(())
+    }
+}
+
+fn parse(contents: &str) -> Result<AppConfig, ConfigError> {
+    let config: AppConfig = toml::from_str(contents).map_err(|e| ConfigError::Parse(e.to_string()))?;
+    config.validate()?;
+    Ok(config)
+}
+
+fn migrate_env(config: AppConfig) -> (AppConfig, Vec<String>) {
+    let Ok(mut tree) = serde_json::to_value(&config) else {
+        return (config, Vec::new());
+    };
+    let mut migrated = Vec::new();
+    for (var, key) in ENV_MIGRATIONS {
+        if let Ok(value) = std::env::var(var) {
+            let value = value.trim();
+            if !value.is_empty() && set_path(&mut tree, key, serde_json::Value::String(value.to_string())) {
+                migrated.push(var.to_string());
+            }
+        }
+    }
+    match serde_json::from_value::<AppConfig>(tree) {
+        Ok(candidate) if candidate.validate().is_ok() => (candidate, migrated),
+        _ => {
+            tracing::warn!(target: "app", "Config: Ignoring invalid environment overrides");
+            (config, Vec::new())
+        }
+    }
+}
+
+fn lookup<'a>(value: &'a serde_json::Value, key: &str) -> Option<&'a serde_json::Value> {
+    key.split('.').try_fold(value, |node, part| node.get(part))
+}
+
+// Only existing keys (or optional keys within a known section) can be set
+fn set_path(tree: &mut serde_json::Value, key: &str, value: serde_json::Value) -> bool {
+    let parts: Vec<&str> = key.split('.').collect();
+    let Some((last, sections)) = parts.split_last() else {
+        return false;
+    };
+    let mut node = tree;
+    for part in sections {
+        match node.get_mut(*part) {
+            Some(next) => node = next,
+            None => return false,
+        }
+    }
+    match node.as_object_mut() {
+        Some(map) => {
+            if value.is_null() {
+                map.remove(*last);
+            } else {
+                map.insert(last.to_string(), value);
+            }
+            true
+        }
+        None => false,
+    }
+}
+
+// Push settings that can change at runtime into the running subsystems
+pub fn apply(app: &tauri::AppHandle, config: &AppConfig) {
+    if let Err(e) = crate::logging::set_level(&config.logging.level, None) {
+        tracing::warn!(target: "app", "Config: {}", e);
+    }
+    for (target, level) in &config.logging.targets {
+        if let Err(e) = crate::logging::set_level(level, Some(target)) {
+            tracing::warn!(target: "app", "Config: {}", e);
+        }
+    }
+    // Re-applying the active mode picks up [modes] changes and the [resources] budget
+    if app.try_state::<crate::resources::ResourceManager>().is_some() {
+        crate::modes::apply(app, &crate::modes::current().mode);
+    }
+    crate::services::global_shortcut_service::apply(app, &config.shortcuts);
+    crate::permissions::apply(&config.permissions);
+    if let Err(e) = crate::doh::set_provider(&config.dns.provider) {
+        tracing::warn!(target: "app", "Config: {}", e);
+    }
+    crate::doh::set_normal_mode(config.dns.normal_mode);
+    crate::http::set_local_only(config.local_only);
+    crate::proxy::apply(app, &config.proxy);
+    crate::services::hotword_service::apply(app, &config.voice);
+    crate::services::api_server::apply(app, &config.api);
+}
+
+// Watch config.toml and apply edits; emits "config:changed" or "config:error"
+pub fn watch(app: tauri::AppHandle) -> Result<(), ConfigError> {
+    use notify::{RecursiveMode, Watcher};
+
+    let path = store().path().ok_or(ConfigError::NotLoaded)?;
+    let dir = path.parent().map(Path::to_path_buf).ok_or(ConfigError::NotLoaded)?;
+    let file_name = path.file_name().map(|n| n.to_os_string());
+    let (tx, rx) = std::sync::mpsc::channel::<()>();
+
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if let Ok(event) = event {
+            // Watch the directory, since editors and our own writes replace the file
+            if event.paths.iter().any(|p| p.file_name().map(|n| n.to_os_string()) == file_name) {
+                let _ = tx.send(());
+            }
+        }
+    })
+    .map_err(|e| ConfigError::Watch(e.to_string()))?;
+    watcher
+        .watch(&dir, RecursiveMode::NonRecursive)
+        .map_err(|e| ConfigError::Watch(e.to_string()))?;
+
+    std::thread::spawn(move || {
+        let _watcher = watcher;        // Dropping the watcher stops it
+        while rx.recv().is_ok() {
+            std::thread::sleep(RELOAD_DEBOUNCE);
+            while rx.try_recv().is_ok() {}
+
+            match store().reload() {
+                Ok(Some(config)) => {
+                    tracing::info!(target: "app", "Config: Reloaded {}", FILE_NAME);
+                    apply(&app, &config);
+                    let _ = app.emit("config:changed", &config);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    // Keep running with the last good config
+                    tracing::warn!(target: "app", "Config: Not reloaded: {}", e);
+                    let _ = app.emit("config:error", e.to_string());
+                }
+            }
+        }
+    });
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+pub enum ConfigError {
+    NotLoaded,
+    UnknownKey(String),
+    Invalid(String, String),
+    Parse(String),
+    Io(String),
+    Watch(String),
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::NotLoaded => write!(f, "Config not loaded"),
+            ConfigError::UnknownKey(key) => write!(f, "Unknown config key: {}", key),
+            ConfigError::Invalid(key, msg) => write!(f, "Invalid value for {}: {}", key, msg),
+            ConfigError::Parse(msg) => write!(f, "Invalid {}: {}", FILE_NAME, msg),
+            ConfigError::Io(msg) => write!(f, "Config file error: {}", msg),
+            ConfigError::Watch(msg) => write!(f, "Config watcher error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
@@ -0,0 +1,170 @@
+// Connectivity - Offline detection from periodic probes and OS network-change signals
+// While offline, remote HTTP fails fast and callers fall back to local models and cached data
+
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+use tokio::sync::Notify;
+
+// Anycast resolvers on well-known ports: no DNS lookup, tiny TCP handshake, rarely blocked together
+const PROBE_TARGETS: &[&str] = &["1.1.1.1:443", "8.8.8.8:443", "9.9.9.9:443"];
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+// Interfaces flap while a network comes up; let them settle before probing
+const CHANGE_SETTLE: Duration = Duration::from_secs(2);
+// Don't re-probe on every failed request
+const MIN_REPROBE: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectivityStatus {
+    pub offline: bool,
+    pub since: i64,                    // When the current state began
+    pub last_probe_at: Option<i64>,
+    pub last_probe_ms: Option<u64>,
+    pub reason: String,                // What triggered the last check
+}
+
+struct Monitor {
+    offline: AtomicBool,
+    status: Mutex<ConnectivityStatus>,
+    last_probe: Mutex<Option<Instant>>,
+    wake: Notify,
+}
+
+fn monitor() -> &'static Monitor {
+    static MONITOR: OnceLock<Monitor> = OnceLock::new();
+    MONITOR.get_or_init(|| Monitor {
+        // Assume online until a probe says otherwise
+        offline: AtomicBool::new(false),
+        status: Mutex::new(ConnectivityStatus {
+            offline: false,
+            since: chrono::Utc::now().timestamp(),
+            last_probe_at: None,
+            last_probe_ms: None,
+            reason: "startup".to_string(),
+        }),
+        last_probe: Mutex::new(None),
+        wake: Notify::new(),
+    })
+}
+
+pub fn is_offline() -> bool {
+    monitor().offline.load(Ordering::Relaxed)
+}
+
+pub fn status() -> ConnectivityStatus {
+    monitor().status.lock().unwrap().clone()
+}
+
+// Ask the monitor to re-check soon (e.g. after a remote request failed to connect)
+pub fn request_probe() {
+    let due = monitor()
+        .last_probe
+        .lock()
+        .unwrap()
+        .map(|t| t.elapsed() >= MIN_REPROBE)
+        .unwrap_or(true);
+    if due {
+        monitor().wake.notify_one();
+    }
+}
+
+// Online when any probe target accepts a TCP connection
+pub async fn probe() -> bool {
+    let attempts = PROBE_TARGETS.iter().filter_map(|t| t.parse::<SocketAddr>().ok()).map(|addr| {
+        Box::pin(async move {
+            matches!(
+                tokio::time::timeout(PROBE_TIMEOUT, tokio::net::TcpStream::connect(addr)).await,
+                Ok(Ok(_))
+            )
+        })
+    });
+    let mut pending: futures::stream::FuturesUnordered<_> = attempts.collect();
+    while let Some(reachable) = pending.next().await {
+        if reachable {
+            return true;
+        }
+    }
+    false
+}
+
+// Probe now and publish the result; returns the new status
+pub async fn check(app: Option<&tauri::AppHandle>, reason: &str) -> ConnectivityStatus {
+    // The probe targets are public hosts; local-only mode refuses remote requests anyway, so it
+    // reports a fixed online state instead of contacting them
+    if crate::http::local_only() {
+        return publish(app, true, None, "local-only mode");
+    }
+    let started = Instant::now();
+    let online = probe().await;
+    *monitor().last_probe.lock().unwrap() = Some(Instant::now());
+    publish(app, online, Some(started.elapsed().as_millis() as u64), reason)
+}
+
+fn publish(app: Option<&tauri::AppHandle>, online: bool, probe_ms: Option<u64>, reason: &str) -> ConnectivityStatus {
+    let now = chrono::Utc::now().timestamp();
+    let changed = monitor().offline.swap(!online, Ordering::Relaxed) == online;
+    let status = {
+        let mut status = monitor().status.lock().unwrap();
+        status.offline = !online;
+        if probe_ms.is_some() {
+            status.last_probe_at = Some(now);
+            status.last_probe_ms = probe_ms;
+        }
+        status.reason = reason.to_string();
+        if changed {
+            status.since = now;
+        }
+        status.clone()
+    };
+
+    if changed {
+        if online {
+            tracing::info!(target: "app", "Connectivity: Back online ({})", reason);
+        } else {
+            tracing::warn!(target: "app", "Connectivity: Offline ({}); using local providers and cached data", reason);
+        }
+        if let Some(app) = app {
+            let _ = app.emit("offline-changed", &status);
+        }
+    }
+    status
+}
+
+// Periodic probe plus immediate re-checks on interface changes and failed requests
+pub fn spawn_monitor(app: tauri::AppHandle, interval: Duration) {
+    let watcher_app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut watcher = match if_watch::tokio::IfWatcher::new() {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                tracing::warn!(target: "app", "Connectivity: Network change events unavailable: {}", e);
+                return;
+            }
+        };
+        while let Some(event) = watcher.next().await {
+            if event.is_err() {
+                continue;
+            }
+            // Coalesce a burst of up/down events (including the initial listing) into one check
+            while let Ok(Some(_)) = tokio::time::timeout(CHANGE_SETTLE, watcher.next()).await {}
+            check(Some(&watcher_app), "network change").await;
+        }
+    });
+
+    tauri::async_runtime::spawn(async move {
+        check(Some(&app), "startup").await;
+        loop {
+            let reason = tokio::select! {
+                _ = tokio::time::sleep(interval) => "periodic",
+                _ = monitor().wake.notified() => "request failure",
+            };
+            check(Some(&app), reason).await;
+        }
+    });
+}
@@ -0,0 +1,303 @@
+// Context Window - Packs a prompt template's variables into the model's context window by priority
+// The template itself, the user's query, and room for the answer come first; memory and retrieved text
+// fill what is left (cut or dropped per section) and the report says exactly what didn't make it
+
+use serde::{Deserialize, Serialize};
+
+use crate::chunker;
+use crate::text::estimate_tokens;
+
+// Room left for the answer: [ai] maxTokens, but never more than this share of the window
+const ANSWER_SHARE: usize = 4;
+// Separator between list items, and a marker where text was cut
+const ITEM_SEPARATOR: &str = "\n\n";
+const CUT_MARKER: &str = "…";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Required,                          // Always included; cut only if it alone overflows the window
+    High,
+    Normal,
+    Low,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "kebab-case")]
+pub enum Fit {
+    Whole,                             // All or nothing
+    KeepStart,                         // Cut from the end
+    KeepEnd,                           // Cut from the start (conversation memory: the oldest goes first)
+    DropLowest,                        // List: drop the lowest-ranked items
+    ShareEvenly,                       // List: cut every item to an equal share
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SectionReport {
+    pub name: String,                  // Template variable
+    pub priority: Priority,
+    pub tokens: usize,                 // Before packing
+    pub kept_tokens: usize,
+    pub items: usize,
+    pub dropped_items: usize,
+    pub truncated: bool,               // Cut short (or items cut) rather than kept whole
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ContextReport {
+    pub task: String,
+    pub model: String,
+    pub window_tokens: usize,
+    pub answer_tokens: usize,          // Reserved for the reply
+    pub template_tokens: usize,        // The template with every variable empty
+    pub used_tokens: usize,            // Whole prompt, estimated
+    pub overflow: bool,                // Required sections alone didn't fit
+    pub sections: Vec<SectionReport>,
+}
+
+impl ContextReport {
+    // Anything cut or left out
+    pub fn trimmed(&self) -> bool {
+        self.overflow || self.sections.iter().any(|s| s.truncated || s.dropped_items > 0)
+    }
+}
+
+pub struct PackedPrompt {
+    pub prompt: String,
+    pub report: ContextReport,
+    kept: Vec<(&'static str, Vec<usize>)>,
+}
+
+impl PackedPrompt {
+    // Indexes (in the order given) of the items of list `name` that made it into the prompt
+    pub fn kept(&self, name: &str) -> Vec<usize> {
+        self.kept.iter().find(|(n, _)| *n == name).map(|(_, kept)| kept.clone()).unwrap_or_default()
+    }
+}
+
+struct Section {
+    name: &'static str,
+    priority: Priority,
+    fit: Fit,
+    items: Vec<(usize, String)>,       // (rank, text) in display order; rank 0 matters most
+    list: bool,
+}
+
+// Builder: add each template variable with its priority and how it may be cut, then `render`
+pub struct ContextPacker {
+    model: String,
+    window_tokens: usize,
+    answer_tokens: usize,
+    sections: Vec<Section>,
+}
+
+impl ContextPacker {
+    pub fn new(model: &str) -> Self {
+        let window_tokens = chunker::context_tokens(model);
+        let answer_tokens = crate::config::current().ai.max_tokens.min(window_tokens / ANSWER_SHARE);
+        ContextPacker { model: model.to_string(), window_tokens, answer_tokens, sections: Vec::new() }
+    }
+
+    pub fn text(mut self, name: &'static str, text: &str, priority: Priority, fit: Fit) -> Self {
+        self.sections.push(Section { name, priority, fit, items: vec![(0, text.to_string())], list: false });
+        self
+    }
+
+    // Items ranked by position (first matters most)
+    pub fn list(self, name: &'static str, items: Vec<String>, priority: Priority, fit: Fit) -> Self {
+        let items = items.into_iter().enumerate().collect();
+        self.ranked_list(name, items, priority, fit)
+    }
+
+    // Items shown in the order given, dropped by rank (lowest rank number kept longest)
+    pub fn ranked_list(mut self, name: &'static str, items: Vec<(usize, String)>, priority: Priority, fit: Fit) -> Self {
+        self.sections.push(Section { name, priority, fit, items, list: true });
+        self
+    }
+
+    pub fn render(self, task: &str, language: Option<&str>, mode: Option<&str>) -> PackedPrompt {
+        let empty: Vec<(&str, &str)> = self.sections.iter().map(|s| (s.name, "")).collect();
+        let template_tokens = estimate_tokens(&crate::prompts::render(task, language, mode, &empty));
+        let mut remaining = self.window_tokens.saturating_sub(self.answer_tokens + template_tokens);
+        let mut overflow = false;
+
+        // Fill by priority; sections of equal priority in the order they were added
+        let mut order: Vec<usize> = (0..self.sections.len()).collect();
+        order.sort_by_key(|&i| self.sections[i].priority);
+        let mut packed: Vec<Option<(String, SectionReport, Vec<usize>)>> = vec![None; self.sections.len()];
+        for i in order {
+            let section = &self.sections[i];
+            let (text, mut report, kept) = fit_section(section, remaining);
+            if section.priority == Priority::Required && report.kept_tokens < report.tokens {
+                overflow = true;
+            }
+            remaining = remaining.saturating_sub(report.kept_tokens);
+            report.truncated |= !section.list && report.kept_tokens < report.tokens;
+            packed[i] = Some((text, report, kept));
+        }
+
+        let packed: Vec<(String, SectionReport, Vec<usize>)> = packed.into_iter().flatten().collect();
+        let vars: Vec<(&str, &str)> = self.sections.iter().zip(&packed).map(|(s, (text, _, _))| (s.name, text.as_str())).collect();
+        let prompt = crate::prompts::render(task, language, mode, &vars);
+        let kept = self.sections.iter().zip(&packed).filter(|(s, _)| s.list).map(|(s, (_, _, kept))| (s.name, kept.clone())).collect();
+        let report = ContextReport {
+            task: task.to_string(),
+            model: self.model,
+            window_tokens: self.window_tokens,
+            answer_tokens: self.answer_tokens,
+            template_tokens,
+            used_tokens: estimate_tokens(&prompt),
+            overflow,
+            sections: packed.into_iter().map(|(_, report, _)| report).collect(),
+        };
+        if report.overflow {
+            tracing::warn!(target: "ai", "Context: {} needs more than {}'s {} token window", task, report.model, report.window_tokens);
+        } else if report.trimmed() {
+            let cut: Vec<String> = report
+                .sections
+                .iter()
+                .filter(|s| s.truncated || s.dropped_items > 0)
+                .map(|s| format!("{} {}/{} tokens, {} of {} items dropped", s.name, s.kept_tokens, s.tokens, s.dropped_items, s.items))
+                .collect();
+            tracing::debug!(target: "ai", "Context: Packed {} into {} tokens ({})", task, report.used_tokens, cut.join("; "));
+        }
+        PackedPrompt { prompt, report, kept }
+    }
+}
+
+// The section cut to `budget` tokens per its Fit, with its report and the list items kept
+fn fit_section(section: &Section, budget: usize) -> (String, SectionReport, Vec<usize>) {
+    let tokens: Vec<usize> = section.items.iter().map(|(_, text)| estimate_tokens(text)).collect();
+    let separators = section.items.len().saturating_sub(1);
+    let total = tokens.iter().sum::<usize>() + separators;
+    let mut report = SectionReport {
+        name: section.name.to_string(),
+        priority: section.priority,
+        tokens: total,
+        kept_tokens: total,
+        items: section.items.len(),
+        dropped_items: 0,
+        truncated: false,
+    };
+    let all: Vec<usize> = (0..section.items.len()).collect();
+    if total <= budget {
+        let text = section.items.iter().map(|(_, t)| t.as_str()).collect::<Vec<_>>().join(ITEM_SEPARATOR);
+        return (text, report, all);
+    }
+
+    let (text, kept) = match section.fit {
+        Fit::Whole => (String::new(), Vec::new()),
+        Fit::KeepStart | Fit::KeepEnd if !section.list => {
+            let text = &section.items[0].1;
+            let cut = if section.fit == Fit::KeepStart { keep_start(text, budget) } else { keep_end(text, budget) };
+            (cut, if budget > 0 { all } else { Vec::new() })
+        }
+        // Lists cut from one end lose whole items from that end
+        Fit::KeepStart | Fit::KeepEnd => {
+            let mut kept: Vec<usize> = Vec::new();
+            let mut used = 0;
+            let indexes: Vec<usize> = if section.fit == Fit::KeepStart { all.clone() } else { all.iter().rev().copied().collect() };
+            for i in indexes {
+                if used + tokens[i] + 1 > budget {
+                    break;
+                }
+                used += tokens[i] + 1;
+                kept.push(i);
+            }
+            kept.sort_unstable();
+            (join_items(section, &kept), kept)
+        }
+        Fit::DropLowest => {
+            let mut by_rank = all.clone();
+            by_rank.sort_by_key(|&i| section.items[i].0);
+            let mut kept: Vec<usize> = Vec::new();
+            let mut used = 0;
+            for i in by_rank {
+                if used + tokens[i] + 1 <= budget {
+                    used += tokens[i] + 1;
+                    kept.push(i);
+                }
+            }
+            kept.sort_unstable();
+            (join_items(section, &kept), kept)
+        }
+        Fit::ShareEvenly => {
+            // Items under their share give the rest back to the others
+            let mut shares = vec![0; section.items.len()];
+            let mut open: Vec<usize> = all.clone();
+            let mut left = budget.saturating_sub(separators);
+            while !open.is_empty() {
+                let share = left / open.len();
+                let (small, large): (Vec<usize>, Vec<usize>) = open.iter().partition(|&&i| tokens[i] <= share);
+                if small.is_empty() {
+                    large.iter().for_each(|&i| shares[i] = share);
+                    break;
+                }
+                for i in small {
+                    shares[i] = tokens[i];
+                    left -= tokens[i];
+                }
+                open = large;
+            }
+            let kept: Vec<usize> = all.iter().copied().filter(|&i| shares[i] > 0).collect();
+            let text = kept
+                .iter()
+                .map(|&i| if shares[i] >= tokens[i] { section.items[i].1.clone() } else { keep_start(&section.items[i].1, shares[i]) })
+                .collect::<Vec<_>>()
+                .join(ITEM_SEPARATOR);
+            report.truncated = kept.iter().any(|&i| shares[i] < tokens[i]);
+            (text, kept)
+        }
+    };
+    report.kept_tokens = estimate_tokens(&text);
+    report.dropped_items = section.items.len() - kept.len();
+    (text, report, kept)
+}
+
+fn join_items(section: &Section, kept: &[usize]) -> String {
+    kept.iter().map(|&i| section.items[i].1.as_str()).collect::<Vec<_>>().join(ITEM_SEPARATOR)
+}
+
+fn keep_start(text: &str, budget: usize) -> String {
+    if budget <= 1 {
+        return String::new();
+    }
+    format!("{}{}", chunker::truncate_tokens(text, budget - 1).trim_end(), CUT_MARKER)
+}
+
+// The last `budget` tokens, starting at a line break where there is one
+fn keep_end(text: &str, budget: usize) -> String {
+    if budget <= 1 {
+        return String::new();
+    }
+    let chars: Vec<char> = text.chars().collect();
+    // Grow the tail a line at a time, then fall back to characters for the last partial line
+    let mut start = chars.len();
+    for (i, c) in chars.iter().enumerate().rev() {
+        if *c == '\n' {
+            let tail: String = chars[i + 1..].iter().collect();
+            if estimate_tokens(&tail) + 1 > budget {
+                break;
+            }
+            start = i + 1;
+        }
+    }
+    if start == chars.len() {
+        let mut tail = String::new();
+        for c in chars.iter().rev() {
+            tail.insert(0, *c);
+            if estimate_tokens(&tail) + 1 > budget {
+                tail.remove(0);
+                break;
+            }
+        }
+        return format!("{}{}", CUT_MARKER, tail.trim_start());
+    }
+    format!("{}{}", CUT_MARKER, chars[start..].iter().collect::<String>().trim_start())
+}
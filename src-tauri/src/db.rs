@@ -42,6 +42,17 @@ impl Database {
         Ok(db)
     }
 
+    // Point this handle (and every clone of it) at another database file
+    pub fn reopen(&self, db_path: PathBuf) -> SqliteResult<()> {
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent).ok();
+        }
+
+        let conn = Connection::open(db_path)?;
+        *self.conn.lock().unwrap() = conn;
+        self.init_schema()
+    }
+
     // Initialize database schema
     fn init_schema(&self) -> SqliteResult<()> {
         let conn = self.conn.lock().unwrap();
@@ -129,6 +140,174 @@ impl Database {
             [],
         )?;
 
+        // Notes full-text index (maintained by save_note/delete_note)
+        conn.execute(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS notes_fts USING fts5(
+                note_id UNINDEXED,
+                title,
+                content,
+                tags
+            )",
+            [],
+        )?;
+
+        // URLs referenced by each note (source page + links in the body), for backlinks
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS note_links (
+                note_id TEXT NOT NULL,
+                url TEXT NOT NULL,
+                PRIMARY KEY (note_id, url)
+            )",
+            [],
+        )?;
+
+        // Highlights (anchors stored as W3C-style selector JSON)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS highlights (
+                id TEXT PRIMARY KEY,
+                url TEXT NOT NULL,
+                text TEXT NOT NULL,
+                selectors_json TEXT NOT NULL,
+                color TEXT,
+                note TEXT,
+                created_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // Embedding cache (f32 little-endian blobs, keyed by content key + model)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS embeddings (
+                key TEXT NOT NULL,
+                model TEXT NOT NULL,
+                vector BLOB NOT NULL,
+                created_at INTEGER NOT NULL,
+                PRIMARY KEY (key, model)
+            )",
+            [],
+        )?;
+
+        // One vector per history URL for semantic history search (see semantic_history.rs)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS history_embeddings (
+                url TEXT PRIMARY KEY,
+                model TEXT NOT NULL,
+                key TEXT NOT NULL,
+                vector BLOB NOT NULL,
+                embedded_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // Opened files indexed for document Q&A, one row per content hash (see documents.rs)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS documents (
+                doc_id TEXT PRIMARY KEY,
+                path TEXT NOT NULL,
+                name TEXT NOT NULL,
+                title TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                chunks INTEGER NOT NULL,
+                model TEXT NOT NULL,
+                indexed_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS document_chunks (
+                doc_id TEXT NOT NULL,
+                seq INTEGER NOT NULL,
+                page INTEGER,
+                sheet TEXT,
+                chapter INTEGER,
+                paragraph INTEGER,
+                text TEXT NOT NULL,
+                vector BLOB NOT NULL,
+                PRIMARY KEY (doc_id, seq)
+            )",
+            [],
+        )?;
+
+        // Inspected, OCR'd, and captioned local images by path (see images.rs)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS images (
+                path TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                format TEXT,
+                width INTEGER,
+                height INTEGER,
+                taken_at TEXT,
+                camera TEXT,
+                latitude REAL,
+                longitude REAL,
+                ocr_text TEXT,
+                caption TEXT,
+                caption_model TEXT,
+                updated_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // RSS/Atom subscriptions, their entries, and feeds found on visited sites (see feeds.rs)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS feeds (
+                id TEXT PRIMARY KEY,
+                url TEXT NOT NULL UNIQUE,
+                site_url TEXT,
+                title TEXT NOT NULL,
+                description TEXT,
+                etag TEXT,
+                last_modified TEXT,
+                last_fetched_at INTEGER,
+                last_attempt_at INTEGER,
+                last_error TEXT,
+                created_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS feed_entries (
+                id TEXT PRIMARY KEY,
+                feed_id TEXT NOT NULL,
+                guid TEXT NOT NULL,
+                url TEXT,
+                title TEXT NOT NULL,
+                summary TEXT,
+                author TEXT,
+                published_at INTEGER NOT NULL,
+                fetched_at INTEGER NOT NULL,
+                read INTEGER NOT NULL DEFAULT 0,
+                UNIQUE (feed_id, guid)
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS feed_sites (
+                origin TEXT PRIMARY KEY,
+                feeds_json TEXT NOT NULL,
+                checked_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // LLM response cache (see llm_cache.rs)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS llm_cache (
+                key TEXT PRIMARY KEY,
+                task TEXT NOT NULL,
+                model TEXT NOT NULL,
+                prompt TEXT NOT NULL,
+                response TEXT NOT NULL,
+                embedding_model TEXT,
+                embedding BLOB,
+                created_at INTEGER NOT NULL,
+                last_hit_at INTEGER NOT NULL,
+                hit_count INTEGER DEFAULT 0
+            )",
+            [],
+        )?;
+
         // Session storage table (for tab persistence)
         conn.execute(
             "CREATE TABLE IF NOT EXISTS sessions (
@@ -140,7 +319,309 @@ impl Database {
             [],
         )?;
 
+        // Session checkpoints (versioned snapshots of the tab set)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS session_checkpoints (
+                id TEXT PRIMARY KEY,
+                name TEXT,
+                active_tab_id TEXT,
+                tabs_json TEXT NOT NULL,
+                tab_count INTEGER NOT NULL,
+                created_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // Research sessions (tabs, notes, summaries, highlights as one JSON document)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS research_sessions (
+                id TEXT PRIMARY KEY,
+                title TEXT NOT NULL,
+                session_json TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // Archives table (self-contained page snapshots on disk)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS archives (
+                id TEXT PRIMARY KEY,
+                url TEXT NOT NULL,
+                title TEXT NOT NULL,
+                tab_id TEXT,
+                file_path TEXT NOT NULL,
+                size_bytes INTEGER NOT NULL,
+                resource_count INTEGER DEFAULT 0,
+                created_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // Focus sessions (streaks and focus time)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS focus_sessions (
+                id TEXT PRIMARY KEY,
+                started_at INTEGER NOT NULL,
+                ended_at INTEGER NOT NULL,
+                planned_secs INTEGER NOT NULL,
+                focused_secs INTEGER NOT NULL,
+                completed INTEGER NOT NULL,
+                blocked_attempts INTEGER DEFAULT 0
+            )",
+            [],
+        )?;
+
+        // Usage analytics (seconds per domain and app mode per local day)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS usage_daily (
+                day TEXT NOT NULL,
+                domain TEXT NOT NULL,
+                mode TEXT NOT NULL,
+                seconds INTEGER NOT NULL DEFAULT 0,
+                visits INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (day, domain, mode)
+            )",
+            [],
+        )?;
+
+        // Network audit log (backend requests, when persistence is enabled)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS network_audit (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp INTEGER NOT NULL,
+                method TEXT NOT NULL,
+                host TEXT NOT NULL,
+                path TEXT NOT NULL,
+                purpose TEXT NOT NULL,
+                status INTEGER,
+                bytes_sent INTEGER NOT NULL DEFAULT 0,
+                bytes_received INTEGER,
+                duration_ms INTEGER NOT NULL,
+                privacy_mode TEXT NOT NULL,
+                local INTEGER NOT NULL DEFAULT 0,
+                error TEXT
+            )",
+            [],
+        )?;
+
+        // Agent actions awaiting (or past) user approval
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS agent_actions (
+                id TEXT PRIMARY KEY,
+                tool TEXT NOT NULL,
+                arguments TEXT NOT NULL,
+                risk TEXT NOT NULL,
+                source TEXT NOT NULL,
+                run_id TEXT,
+                status TEXT NOT NULL,
+                auto_approved INTEGER NOT NULL DEFAULT 0,
+                created_at INTEGER NOT NULL,
+                decided_at INTEGER,
+                result TEXT,
+                error TEXT
+            )",
+            [],
+        )?;
+
+        // Agent/research run transcripts (prompts, usage, events) for inspection and replay
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS agent_runs (
+                id TEXT PRIMARY KEY,
+                kind TEXT NOT NULL,
+                request_id TEXT,
+                query TEXT NOT NULL,
+                status TEXT NOT NULL,
+                provider TEXT,
+                model TEXT,
+                prompt_count INTEGER NOT NULL DEFAULT 0,
+                prompt_tokens INTEGER NOT NULL DEFAULT 0,
+                completion_tokens INTEGER NOT NULL DEFAULT 0,
+                tool_calls INTEGER NOT NULL DEFAULT 0,
+                started_at INTEGER NOT NULL,
+                finished_at INTEGER,
+                answer TEXT,
+                error TEXT
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS agent_run_entries (
+                run_id TEXT NOT NULL,
+                seq INTEGER NOT NULL,
+                timestamp INTEGER NOT NULL,
+                kind TEXT NOT NULL,
+                data TEXT NOT NULL,
+                PRIMARY KEY (run_id, seq)
+            )",
+            [],
+        )?;
+
+        // LLM token usage and estimated cost per local day, provider, and model
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS llm_usage_daily (
+                day TEXT NOT NULL,
+                provider TEXT NOT NULL,
+                model TEXT NOT NULL,
+                requests INTEGER NOT NULL DEFAULT 0,
+                prompt_tokens INTEGER NOT NULL DEFAULT 0,
+                completion_tokens INTEGER NOT NULL DEFAULT 0,
+                estimated_requests INTEGER NOT NULL DEFAULT 0,
+                cost_usd REAL NOT NULL DEFAULT 0,
+                PRIMARY KEY (day, provider, model)
+            )",
+            [],
+        )?;
+
+        // Daily exchange rates cached by fx.rs (units per euro, ECB reference)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS fx_rates (
+                currency TEXT PRIMARY KEY,
+                per_eur REAL NOT NULL,
+                as_of TEXT NOT NULL,
+                fetched_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // Price bars cached by ohlc.rs, keyed by the provider's symbol
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS ohlc_bars (
+                symbol TEXT NOT NULL,
+                interval TEXT NOT NULL,
+                time INTEGER NOT NULL,
+                open REAL NOT NULL,
+                high REAL NOT NULL,
+                low REAL NOT NULL,
+                close REAL NOT NULL,
+                volume REAL NOT NULL DEFAULT 0,
+                PRIMARY KEY (symbol, interval, time)
+            )",
+            [],
+        )?;
+
+        // Backtest results (strategy, trades, equity curve as one JSON document)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS backtests (
+                id TEXT PRIMARY KEY,
+                symbol TEXT NOT NULL,
+                result_json TEXT NOT NULL,
+                created_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // Trades recorded by the Trade mode (paper or broker fills)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS trade_logs (
+                id TEXT PRIMARY KEY,
+                symbol TEXT NOT NULL,
+                side TEXT NOT NULL,
+                quantity REAL NOT NULL,
+                entry_price REAL NOT NULL,
+                exit_price REAL,
+                fees REAL NOT NULL DEFAULT 0,
+                pnl REAL,
+                opened_at INTEGER NOT NULL,
+                closed_at INTEGER,
+                strategy TEXT,
+                source TEXT NOT NULL,
+                created_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // Journal entries: notes, tags, and the market snapshot for one trade
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS journal_entries (
+                id TEXT PRIMARY KEY,
+                trade_id TEXT NOT NULL UNIQUE,
+                notes TEXT NOT NULL,
+                tags_json TEXT NOT NULL DEFAULT '[]',
+                snapshot_json TEXT,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // User scripts and styles (see userscripts.rs); patterns are JSON arrays
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS userscripts (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                namespace TEXT,
+                version TEXT,
+                description TEXT,
+                kind TEXT NOT NULL,
+                matches_json TEXT NOT NULL DEFAULT '[]',
+                excludes_json TEXT NOT NULL DEFAULT '[]',
+                run_at TEXT NOT NULL,
+                grants_json TEXT NOT NULL DEFAULT '[]',
+                code TEXT NOT NULL,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                update_url TEXT,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // Custom adblock rules in filter-list syntax (see filter_rules.rs)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS filter_rules (
+                id TEXT PRIMARY KEY,
+                rule_type TEXT NOT NULL,
+                filter TEXT NOT NULL UNIQUE,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                created_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // Files picked in the open/save dialogs (see files.rs), capped per doc_type
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS recent_files (
+                path TEXT PRIMARY KEY,
+                doc_type TEXT NOT NULL,
+                opened_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // Events from .ics imports and the assistant (see calendar.rs); exdates is a JSON array
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS calendar_events (
+                id TEXT PRIMARY KEY,
+                uid TEXT NOT NULL UNIQUE,
+                title TEXT NOT NULL,
+                starts_at INTEGER NOT NULL,
+                ends_at INTEGER NOT NULL,
+                all_day INTEGER NOT NULL DEFAULT 0,
+                location TEXT,
+                description TEXT,
+                rrule TEXT,
+                exdates TEXT,
+                source TEXT NOT NULL,
+                created_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
         // Create indexes for performance
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_session_checkpoints_created_at ON session_checkpoints(created_at DESC)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_archives_created_at ON archives(created_at DESC)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_calendar_events_starts_at ON calendar_events(starts_at)",
+            [],
+        )?;
         conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_pages_url ON pages(url)",
             [],
@@ -153,11 +634,78 @@ impl Database {
             "CREATE INDEX IF NOT EXISTS idx_history_visited_at ON history(visited_at DESC)",
             [],
         )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_network_audit_timestamp ON network_audit(timestamp DESC)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_agent_actions_status ON agent_actions(status, created_at)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_agent_runs_started_at ON agent_runs(started_at DESC)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_backtests_symbol ON backtests(symbol, created_at DESC)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_trade_logs_closed_at ON trade_logs(closed_at)",
+            [],
+        )?;
         conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_notes_language ON notes(language)",
             [],
         )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_note_links_url ON note_links(url)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_highlights_url ON highlights(url)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_llm_cache_task ON llm_cache(task, model, created_at DESC)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_feed_entries_feed ON feed_entries(feed_id, published_at DESC)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_feed_entries_published_at ON feed_entries(published_at DESC)",
+            [],
+        )?;
+
+        // Columns added after the initial schema (existing databases need ALTER TABLE)
+        Self::ensure_column(&conn, "pages", "reader_view", "TEXT")?;
+        Self::ensure_column(&conn, "notes", "title", "TEXT")?;
+        Self::ensure_column(&conn, "notes", "url", "TEXT")?;
+        Self::ensure_column(&conn, "sessions", "groups_json", "TEXT")?;
+        Self::ensure_column(&conn, "sessions", "layout_json", "TEXT")?;
+        Self::ensure_column(&conn, "history", "frecency", "REAL")?;
+        Self::ensure_column(&conn, "history", "frecency_at", "INTEGER")?;
+        Self::ensure_column(&conn, "downloads", "verification_json", "TEXT")?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_history_frecency ON history(frecency DESC)",
+            [],
+        )?;
+
+        Ok(())
+    }
 
+    // Add a column to an existing table if it is missing (idempotent migration)
+    fn ensure_column(conn: &Connection, table: &str, column: &str, decl: &str) -> SqliteResult<()> {
+        let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+        let exists = stmt
+            .query_map([], |row| row.get::<_, String>(1))?
+            .filter_map(|name| name.ok())
+            .any(|name| name == column);
+        if !exists {
+            conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, decl), [])?;
+        }
         Ok(())
     }
 
@@ -245,23 +793,73 @@ impl Database {
         Ok(result)
     }
 
-    // Delete page from cache
-    pub fn delete_page(&self, url: &str) -> SqliteResult<()> {
+    // Every cached page without its HTML, newest first (for the local search index)
+    pub fn list_pages(&self, limit: usize) -> SqliteResult<Vec<PageCache>> {
         let conn = self.conn.lock().unwrap();
-        // Delete from FTS5 (automatic via content='pages' option, but explicit is safer)
-        conn.execute(
-            "DELETE FROM pages_fts WHERE rowid IN (SELECT rowid FROM pages WHERE url = ?1)",
-            params![url],
+        let mut stmt = conn.prepare(
+            "SELECT id, url, title, content, cached_at, language FROM pages ORDER BY cached_at DESC LIMIT ?1"
         )?;
 
-        // Delete from pages table
-        conn.execute("DELETE FROM pages WHERE url = ?1", params![url])?;
-
-        Ok(())
-    }
-
-    // Add history entry
-    pub fn add_history(&self, url: &str, title: &str) -> SqliteResult<()> {
+        let pages = stmt.query_map(params![limit as i64], |row| {
+            Ok(PageCache {
+                id: row.get(0)?,
+                url: row.get(1)?,
+                title: row.get(2)?,
+                content: row.get(3)?,
+                html: None,
+                cached_at: row.get(4)?,
+                language: row.get(5)?,
+            })
+        })?;
+
+        let mut result = Vec::new();
+        for page in pages {
+            result.push(page?);
+        }
+        Ok(result)
+    }
+
+    // Store the rendered reader view (JSON) alongside a cached page
+    pub fn save_reader_view(&self, url: &str, reader_json: &str) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE pages SET reader_view = ?1 WHERE url = ?2",
+            params![reader_json, url],
+        )?;
+        Ok(())
+    }
+
+    // Get the cached reader view (JSON) for a page
+    pub fn get_reader_view(&self, url: &str) -> SqliteResult<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        match conn.query_row(
+            "SELECT reader_view FROM pages WHERE url = ?1",
+            params![url],
+            |row| row.get::<_, Option<String>>(0),
+        ) {
+            Ok(view) => Ok(view),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    // Delete page from cache
+    pub fn delete_page(&self, url: &str) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        // Delete from FTS5 (automatic via content='pages' option, but explicit is safer)
+        conn.execute(
+            "DELETE FROM pages_fts WHERE rowid IN (SELECT rowid FROM pages WHERE url = ?1)",
+            params![url],
+        )?;
+
+        // Delete from pages table
+        conn.execute("DELETE FROM pages WHERE url = ?1", params![url])?;
+
+        Ok(())
+    }
+
+    // Add history entry
+    pub fn add_history(&self, url: &str, title: &str) -> SqliteResult<()> {
         let conn = self.conn.lock().unwrap();
         let id = uuid::Uuid::new_v4().to_string();
         let visited_at = std::time::SystemTime::now()
@@ -269,30 +867,54 @@ impl Database {
             .unwrap()
             .as_secs() as i64;
 
-        // Check if URL already exists in history
-        let exists: bool = conn.query_row(
-            "SELECT EXISTS(SELECT 1 FROM history WHERE url = ?1)",
+        // Existing entry: its frecency (decayed to now) plus this visit
+        let existing: Option<(Option<f64>, Option<i64>, i64, i64)> = match conn.query_row(
+            "SELECT frecency, frecency_at, visit_count, visited_at FROM history WHERE url = ?1",
             params![url],
-            |row| row.get(0),
-        )?;
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        ) {
+            Ok(row) => Some(row),
+            Err(rusqlite::Error::QueryReturnedNoRows) => None,
+            Err(e) => return Err(e),
+        };
 
-        if exists {
+        if let Some((frecency, frecency_at, visit_count, last_visit)) = existing {
+            let previous = match (frecency, frecency_at) {
+                (Some(score), Some(at)) => crate::frecency::decayed(score, at, visited_at),
+                _ => crate::frecency::estimate(visit_count, last_visit, visited_at),
+            };
             // Update existing entry
             conn.execute(
-                "UPDATE history SET title = ?1, visited_at = ?2, visit_count = visit_count + 1 WHERE url = ?3",
-                params![title, visited_at, url],
+                "UPDATE history SET title = ?1, visited_at = ?2, visit_count = visit_count + 1,
+                 frecency = ?3, frecency_at = ?2 WHERE url = ?4",
+                params![title, visited_at, previous + crate::frecency::VISIT_POINTS, url],
             )?;
         } else {
             // Insert new entry
             conn.execute(
-                "INSERT INTO history (id, url, title, visited_at, visit_count) VALUES (?1, ?2, ?3, ?4, 1)",
-                params![id, url, title, visited_at],
+                "INSERT INTO history (id, url, title, visited_at, visit_count, frecency, frecency_at)
+                 VALUES (?1, ?2, ?3, ?4, 1, ?5, ?4)",
+                params![id, url, title, visited_at, crate::frecency::VISIT_POINTS],
             )?;
         }
 
         Ok(())
     }
 
+    // Visit imported from another browser; a URL already in history is left alone. Returns whether it was added
+    pub fn import_history(&self, url: &str, title: &str, visited_at: i64, visit_count: i64) -> SqliteResult<bool> {
+        let conn = self.conn.lock().unwrap();
+        let now = chrono::Utc::now().timestamp();
+        let frecency = crate::frecency::estimate(visit_count, visited_at, now);
+        let added = conn.execute(
+            "INSERT INTO history (id, url, title, visited_at, visit_count, frecency, frecency_at)
+             SELECT ?1, ?2, ?3, ?4, ?5, ?6, ?7
+             WHERE NOT EXISTS (SELECT 1 FROM history WHERE url = ?2)",
+            params![uuid::Uuid::new_v4().to_string(), url, title, visited_at, visit_count.max(1), frecency, now],
+        )?;
+        Ok(added > 0)
+    }
+
     // Get history (most recent first)
     pub fn get_history(&self, limit: usize) -> SqliteResult<Vec<(String, String, i64)>> {
         let conn = self.conn.lock().unwrap();
@@ -315,6 +937,7 @@ impl Database {
     pub fn clear_history(&self) -> SqliteResult<()> {
         let conn = self.conn.lock().unwrap();
         conn.execute("DELETE FROM history", [])?;
+        conn.execute("DELETE FROM history_embeddings", [])?;
         Ok(())
     }
 
@@ -339,15 +962,135 @@ impl Database {
         Ok(result)
     }
 
+    // History rows whose URL or title contains every term, highest frecency first (omnibox candidates)
+    pub fn history_matches(&self, terms: &[String], limit: usize) -> SqliteResult<Vec<(String, String, i64, f64)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut sql = String::from("SELECT url, title, visited_at, COALESCE(frecency, 0) FROM history WHERE 1 = 1");
+        for i in 0..terms.len() {
+            sql.push_str(&format!(" AND (url LIKE ?{0} ESCAPE '\\' OR title LIKE ?{0} ESCAPE '\\')", i + 1));
+        }
+        sql.push_str(&format!(" ORDER BY frecency DESC, visited_at DESC LIMIT {}", limit));
+
+        let patterns: Vec<String> = terms.iter().map(|t| format!("%{}%", escape_like(t))).collect();
+        let mut stmt = conn.prepare(&sql)?;
+        let entries = stmt.query_map(rusqlite::params_from_iter(patterns.iter()), |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })?;
+
+        let mut result = Vec::new();
+        for entry in entries {
+            result.push(entry?);
+        }
+        Ok(result)
+    }
+
+    // (url, title, frecency, visit_count, visited_at), highest frecency first
+    pub fn history_by_frecency(&self, limit: usize) -> SqliteResult<Vec<(String, String, f64, i64, i64)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT url, title, frecency, visit_count, visited_at FROM history
+             WHERE frecency > 0
+             ORDER BY frecency DESC LIMIT ?1"
+        )?;
+
+        let entries = stmt.query_map(params![limit as i64], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+        })?;
+
+        let mut result = Vec::new();
+        for entry in entries {
+            result.push(entry?);
+        }
+        Ok(result)
+    }
+
+    // Decay every frecency score to `now` (rows from before frecency get an estimate); returns rows updated
+    pub fn recompute_frecency(&self, now: i64) -> SqliteResult<usize> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let rows: Vec<(String, Option<f64>, Option<i64>, i64, i64)> = {
+            let mut stmt = tx.prepare(
+                "SELECT id, frecency, frecency_at, visit_count, visited_at FROM history
+                 WHERE frecency IS NULL OR frecency > 0"
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+            })?;
+            rows.collect::<SqliteResult<Vec<_>>>()?
+        };
+
+        {
+            let mut update = tx.prepare("UPDATE history SET frecency = ?1, frecency_at = ?2 WHERE id = ?3")?;
+            for (id, frecency, frecency_at, visit_count, visited_at) in &rows {
+                let score = match (frecency, frecency_at) {
+                    (Some(score), Some(at)) => crate::frecency::decayed(*score, *at, now),
+                    _ => crate::frecency::estimate(*visit_count, *visited_at, now),
+                };
+                update.execute(params![score, now, id])?;
+            }
+        }
+        tx.commit()?;
+        Ok(rows.len())
+    }
+
     // Delete history entry by URL
     pub fn delete_history_url(&self, url: &str) -> SqliteResult<()> {
         let conn = self.conn.lock().unwrap();
         conn.execute("DELETE FROM history WHERE url = ?1", params![url])?;
+        conn.execute("DELETE FROM history_embeddings WHERE url = ?1", params![url])?;
         Ok(())
     }
 
+    // History entries visited since a timestamp (one row per URL, most recent first)
+    pub fn get_history_since(&self, since: i64, limit: usize) -> SqliteResult<Vec<(String, String, i64)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT url, title, MAX(visited_at) FROM history
+             WHERE visited_at >= ?1
+             GROUP BY url
+             ORDER BY MAX(visited_at) DESC LIMIT ?2"
+        )?;
+
+        let entries = stmt.query_map(params![since, limit as i64], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })?;
+
+        let mut result = Vec::new();
+        for entry in entries {
+            result.push(entry?);
+        }
+        Ok(result)
+    }
+
+    // Pages visited within [from, to] (unix seconds): url, title, visits in range; busiest first, frecency breaks ties
+    pub fn history_between(&self, from: i64, to: i64, limit: usize) -> SqliteResult<Vec<(String, String, i64)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT url, title, COUNT(*) FROM history
+             WHERE visited_at BETWEEN ?1 AND ?2
+             GROUP BY url
+             ORDER BY COUNT(*) DESC, MAX(frecency) DESC LIMIT ?3"
+        )?;
+
+        let entries = stmt.query_map(params![from, to, limit as i64], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })?;
+
+        let mut result = Vec::new();
+        for entry in entries {
+            result.push(entry?);
+        }
+        Ok(result)
+    }
+
     // Save session state
-    pub fn save_session(&self, active_tab_id: Option<&str>, tabs_json: &str) -> SqliteResult<()> {
+    pub fn save_session(
+        &self,
+        active_tab_id: Option<&str>,
+        tabs_json: &str,
+        groups_json: Option<&str>,
+        layout_json: Option<&str>,
+    ) -> SqliteResult<()> {
         let conn = self.conn.lock().unwrap();
         let saved_at = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -355,19 +1098,20 @@ impl Database {
             .as_secs() as i64;
 
         conn.execute(
-            "INSERT OR REPLACE INTO sessions (id, active_tab_id, tabs_json, saved_at) VALUES (?1, ?2, ?3, ?4)",
-            params!["current", active_tab_id, tabs_json, saved_at],
+            "INSERT OR REPLACE INTO sessions (id, active_tab_id, tabs_json, groups_json, layout_json, saved_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params!["current", active_tab_id, tabs_json, groups_json, layout_json, saved_at],
         )?;
         Ok(())
     }
 
     // Load session state
-    pub fn load_session(&self) -> SqliteResult<Option<(Option<String>, String)>> {
+    pub fn load_session(&self) -> SqliteResult<Option<(Option<String>, String, Option<String>)>> {
         let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare("SELECT active_tab_id, tabs_json FROM sessions WHERE id = 'current'")?;
+        let mut stmt = conn.prepare("SELECT active_tab_id, tabs_json, groups_json FROM sessions WHERE id = 'current'")?;
 
         match stmt.query_row([], |row| {
-            Ok((row.get::<_, Option<String>>(0)?, row.get::<_, String>(1)?))
+            Ok((row.get::<_, Option<String>>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<String>>(2)?))
         }) {
             Ok(result) => Ok(Some(result)),
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
@@ -375,122 +1119,2467 @@ impl Database {
         }
     }
 
+    // Split views saved with the session (None for sessions saved before them)
+    pub fn load_session_layout(&self) -> SqliteResult<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        match conn.query_row("SELECT layout_json FROM sessions WHERE id = 'current'", [], |row| {
+            row.get::<_, Option<String>>(0)
+        }) {
+            Ok(layout) => Ok(layout),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
     // ============================================================================
-    // DOWNLOADS METHODS
+    // NOTES METHODS
     // ============================================================================
 
-    // Add or update download
-    pub fn save_download(&self, id: &str, url: &str, filename: Option<&str>, path: Option<&str>, 
-                         status: &str, progress: f64, received_bytes: i64, total_bytes: Option<i64>,
-                         checksum: Option<&str>, safety_status: Option<&str>) -> SqliteResult<()> {
+    // Insert or update a note, keeping the FTS index and link table in sync
+    pub fn save_note(&self, note: &crate::notes::Note) -> SqliteResult<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tags_json = serde_json::to_string(&note.tags)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT OR REPLACE INTO notes (id, title, content, url, language, tags, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                note.id,
+                note.title,
+                note.content,
+                note.url,
+                note.language,
+                tags_json,
+                note.created_at,
+                note.updated_at
+            ],
+        )?;
+
+        tx.execute("DELETE FROM notes_fts WHERE note_id = ?1", params![note.id])?;
+        tx.execute(
+            "INSERT INTO notes_fts (note_id, title, content, tags) VALUES (?1, ?2, ?3, ?4)",
+            params![note.id, note.title.as_deref().unwrap_or(""), note.content, note.tags.join(" ")],
+        )?;
+
+        tx.execute("DELETE FROM note_links WHERE note_id = ?1", params![note.id])?;
+        for url in note.referenced_urls() {
+            tx.execute(
+                "INSERT OR IGNORE INTO note_links (note_id, url) VALUES (?1, ?2)",
+                params![note.id, url],
+            )?;
+        }
+        tx.commit()
+    }
+
+    // Get note by ID (with backlinks)
+    pub fn get_note(&self, id: &str) -> SqliteResult<Option<crate::notes::Note>> {
+        let mut note = {
+            let conn = self.conn.lock().unwrap();
+            match conn.query_row(
+                "SELECT id, title, content, url, language, tags, created_at, updated_at
+                 FROM notes WHERE id = ?1",
+                params![id],
+                Self::row_to_note,
+            ) {
+                Ok(note) => note,
+                Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
+                Err(e) => return Err(e),
+            }
+        };
+
+        note.backlinks = self.note_backlinks(id)?;
+        Ok(Some(note))
+    }
+
+    // List notes, optionally restricted to a tag (most recently updated first)
+    pub fn list_notes(&self, tag: Option<&str>, limit: usize) -> SqliteResult<Vec<crate::notes::Note>> {
         let conn = self.conn.lock().unwrap();
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
-        
-        conn.execute(
-            "INSERT OR REPLACE INTO downloads 
-             (id, url, filename, path, status, progress, received_bytes, total_bytes, created_at, checksum, safety_status)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, 
-                     COALESCE((SELECT created_at FROM downloads WHERE id = ?1), ?9), ?10, ?11)",
-            params![id, url, filename, path, status, progress, received_bytes, total_bytes, now, checksum, safety_status],
+        let mut stmt = conn.prepare(
+            "SELECT id, title, content, url, language, tags, created_at, updated_at
+             FROM notes
+             WHERE ?1 IS NULL OR EXISTS (SELECT 1 FROM json_each(notes.tags) WHERE value = ?1)
+             ORDER BY updated_at DESC
+             LIMIT ?2"
         )?;
-        Ok(())
+
+        let notes = stmt.query_map(params![tag, limit as i64], Self::row_to_note)?;
+
+        let mut result = Vec::new();
+        for note in notes {
+            result.push(note?);
+        }
+        Ok(result)
     }
 
-    // Get all downloads
-    pub fn get_downloads(&self, limit: Option<usize>) -> SqliteResult<Vec<(String, String, Option<String>, Option<String>, String, f64, i64, Option<i64>, i64, Option<i64>, Option<String>, Option<String>)>> {
+    // Notes created or edited within [from, to] (unix seconds), oldest first
+    pub fn notes_between(&self, from: i64, to: i64, limit: usize) -> SqliteResult<Vec<crate::notes::Note>> {
         let conn = self.conn.lock().unwrap();
-        let limit_val = limit.unwrap_or(100) as i64;
         let mut stmt = conn.prepare(
-            "SELECT id, url, filename, path, status, progress, received_bytes, total_bytes, created_at, completed_at, checksum, safety_status 
-             FROM downloads ORDER BY created_at DESC LIMIT ?1"
+            "SELECT id, title, content, url, language, tags, created_at, updated_at
+             FROM notes
+             WHERE updated_at BETWEEN ?1 AND ?2
+             ORDER BY updated_at ASC
+             LIMIT ?3"
         )?;
 
-        let entries = stmt.query_map(params![limit_val], |row| {
-            Ok((
-                row.get(0)?, // id
-                row.get(1)?, // url
-                row.get(2)?, // filename
-                row.get(3)?, // path
-                row.get(4)?, // status
-                row.get(5)?, // progress
-                row.get(6)?, // received_bytes
-                row.get(7)?, // total_bytes
-                row.get(8)?, // created_at
-                row.get(9)?, // completed_at
-                row.get(10)?, // checksum
-                row.get(11)?, // safety_status
-            ))
+        let notes = stmt.query_map(params![from, to, limit as i64], Self::row_to_note)?;
+
+        let mut result = Vec::new();
+        for note in notes {
+            result.push(note?);
+        }
+        Ok(result)
+    }
+
+    // Full-text search over note titles, bodies, and tags
+    pub fn search_notes(&self, fts_query: &str, limit: usize) -> SqliteResult<Vec<crate::notes::Note>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT n.id, n.title, n.content, n.url, n.language, n.tags, n.created_at, n.updated_at
+             FROM notes_fts fts
+             JOIN notes n ON n.id = fts.note_id
+             WHERE notes_fts MATCH ?1
+             ORDER BY rank
+             LIMIT ?2"
+        )?;
+
+        let notes = stmt.query_map(params![fts_query, limit as i64], Self::row_to_note)?;
+
+        let mut result = Vec::new();
+        for note in notes {
+            result.push(note?);
+        }
+        Ok(result)
+    }
+
+    // All tags in use, with note counts
+    pub fn list_note_tags(&self) -> SqliteResult<Vec<(String, i64)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT t.value, COUNT(*) FROM notes, json_each(notes.tags) t
+             GROUP BY t.value ORDER BY COUNT(*) DESC, t.value"
+        )?;
+
+        let tags = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+        let mut result = Vec::new();
+        for tag in tags {
+            result.push(tag?);
+        }
+        Ok(result)
+    }
+
+    // Other notes sharing a referenced URL with this one
+    pub fn note_backlinks(&self, id: &str) -> SqliteResult<Vec<crate::notes::NoteRef>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT n.id, n.title, b.url
+             FROM note_links a
+             JOIN note_links b ON b.url = a.url AND b.note_id != a.note_id
+             JOIN notes n ON n.id = b.note_id
+             WHERE a.note_id = ?1
+             ORDER BY n.updated_at DESC"
+        )?;
+
+        let links = stmt.query_map(params![id], |row| {
+            Ok(crate::notes::NoteRef {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                url: row.get(2)?,
+            })
         })?;
 
         let mut result = Vec::new();
-        for entry in entries {
-            result.push(entry?);
+        for link in links {
+            result.push(link?);
         }
         Ok(result)
     }
 
-    // Delete download
-    pub fn delete_download(&self, id: &str) -> SqliteResult<()> {
+    // Delete note (and its index/link rows)
+    pub fn delete_note(&self, id: &str) -> SqliteResult<()> {
         let conn = self.conn.lock().unwrap();
-        conn.execute("DELETE FROM downloads WHERE id = ?1", params![id])?;
+        conn.execute("DELETE FROM notes WHERE id = ?1", params![id])?;
+        conn.execute("DELETE FROM notes_fts WHERE note_id = ?1", params![id])?;
+        conn.execute("DELETE FROM note_links WHERE note_id = ?1", params![id])?;
         Ok(())
     }
 
+    fn row_to_note(row: &rusqlite::Row) -> SqliteResult<crate::notes::Note> {
+        let tags: Option<String> = row.get(5)?;
+        Ok(crate::notes::Note {
+            id: row.get(0)?,
+            title: row.get(1)?,
+            content: row.get(2)?,
+            url: row.get(3)?,
+            language: row.get(4)?,
+            tags: tags
+                .and_then(|t| serde_json::from_str(&t).ok())
+                .unwrap_or_default(),
+            created_at: row.get(6)?,
+            updated_at: row.get(7)?,
+            backlinks: Vec::new(),
+        })
+    }
+
     // ============================================================================
-    // BOOKMARKS METHODS (for backend sync if needed)
+    // HIGHLIGHT METHODS
     // ============================================================================
 
-    // Add or update bookmark
-    pub fn save_bookmark(&self, id: &str, url: &str, title: &str, folder: Option<&str>, 
-                         tags: Option<&str>, description: Option<&str>) -> SqliteResult<()> {
+    // Store a highlight
+    pub fn save_highlight(&self, highlight: &crate::highlights::Highlight) -> SqliteResult<()> {
         let conn = self.conn.lock().unwrap();
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
-        
+        let selectors_json = serde_json::to_string(&highlight.selectors)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+
         conn.execute(
-            "INSERT OR REPLACE INTO bookmarks 
-             (id, url, title, created_at, folder, tags, description)
-             VALUES (?1, ?2, ?3, COALESCE((SELECT created_at FROM bookmarks WHERE id = ?1), ?4), ?5, ?6, ?7)",
-            params![id, url, title, now, folder, tags, description],
+            "INSERT OR REPLACE INTO highlights (id, url, text, selectors_json, color, note, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                highlight.id,
+                highlight.url,
+                highlight.text,
+                selectors_json,
+                highlight.color,
+                highlight.note,
+                highlight.created_at
+            ],
         )?;
         Ok(())
     }
 
-    // Get all bookmarks
-    pub fn get_bookmarks(&self) -> SqliteResult<Vec<(String, String, String, i64, Option<String>, Option<String>, Option<String>)>> {
+    // Highlights on a page, in creation order
+    pub fn list_highlights_for_url(&self, url: &str) -> SqliteResult<Vec<crate::highlights::Highlight>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, url, title, created_at, folder, tags, description FROM bookmarks ORDER BY created_at DESC"
+            "SELECT id, url, text, selectors_json, color, note, created_at
+             FROM highlights WHERE url = ?1 ORDER BY created_at ASC"
         )?;
 
-        let entries = stmt.query_map([], |row| {
-            Ok((
-                row.get(0)?, // id
-                row.get(1)?, // url
-                row.get(2)?, // title
-                row.get(3)?, // created_at
-                row.get(4)?, // folder
-                row.get(5)?, // tags
-                row.get(6)?, // description
-            ))
-        })?;
+        let highlights = stmt.query_map(params![url], Self::row_to_highlight)?;
 
         let mut result = Vec::new();
-        for entry in entries {
-            result.push(entry?);
+        for highlight in highlights {
+            result.push(highlight?);
         }
         Ok(result)
     }
 
-    // Delete bookmark
-    pub fn delete_bookmark(&self, id: &str) -> SqliteResult<()> {
+    // Highlights made within [from, to] (unix seconds), in creation order
+    pub fn highlights_between(&self, from: i64, to: i64, limit: usize) -> SqliteResult<Vec<crate::highlights::Highlight>> {
         let conn = self.conn.lock().unwrap();
-        conn.execute("DELETE FROM bookmarks WHERE id = ?1", params![id])?;
-        Ok(())
+        let mut stmt = conn.prepare(
+            "SELECT id, url, text, selectors_json, color, note, created_at
+             FROM highlights WHERE created_at BETWEEN ?1 AND ?2
+             ORDER BY created_at ASC LIMIT ?3"
+        )?;
+
+        let highlights = stmt.query_map(params![from, to, limit as i64], Self::row_to_highlight)?;
+
+        let mut result = Vec::new();
+        for highlight in highlights {
+            result.push(highlight?);
+        }
+        Ok(result)
     }
+
+    fn row_to_highlight(row: &rusqlite::Row) -> SqliteResult<crate::highlights::Highlight> {
+        let selectors_json: String = row.get(3)?;
+        Ok(crate::highlights::Highlight {
+            id: row.get(0)?,
+            url: row.get(1)?,
+            text: row.get(2)?,
+            selectors: serde_json::from_str(&selectors_json).map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, Box::new(e))
+            })?,
+            color: row.get(4)?,
+            note: row.get(5)?,
+            created_at: row.get(6)?,
+        })
+    }
+
+    // Delete highlight
+    pub fn delete_highlight(&self, id: &str) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM highlights WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    // ============================================================================
+    // EMBEDDING METHODS
+    // ============================================================================
+
+    // Cached vectors for the given keys (missing keys are simply absent)
+    pub fn get_embeddings(&self, keys: &[String], model: &str) -> SqliteResult<std::collections::HashMap<String, Vec<f32>>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT vector FROM embeddings WHERE key = ?1 AND model = ?2")?;
+
+        let mut result = std::collections::HashMap::new();
+        for key in keys {
+            match stmt.query_row(params![key, model], |row| row.get::<_, Vec<u8>>(0)) {
+                Ok(blob) => {
+                    result.insert(key.clone(), crate::embeddings::from_blob(&blob));
+                }
+                Err(rusqlite::Error::QueryReturnedNoRows) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(result)
+    }
+
+    // Store vectors for keys
+    pub fn save_embeddings(&self, entries: &[(String, Vec<f32>)], model: &str) -> SqliteResult<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let now = chrono::Utc::now().timestamp();
+
+        let tx = conn.transaction()?;
+        for (key, vector) in entries {
+            tx.execute(
+                "INSERT OR REPLACE INTO embeddings (key, model, vector, created_at) VALUES (?1, ?2, ?3, ?4)",
+                params![key, model, crate::embeddings::to_blob(vector), now],
+            )?;
+        }
+        tx.commit()
+    }
+
+    // Title of a history entry, if the URL is in history
+    pub fn history_title(&self, url: &str) -> SqliteResult<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        match conn.query_row("SELECT title FROM history WHERE url = ?1 ORDER BY visited_at DESC LIMIT 1", params![url], |row| row.get(0)) {
+            Ok(title) => Ok(Some(title)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    // (content key, model) of a history URL's stored vector
+    pub fn history_embedding_key(&self, url: &str) -> SqliteResult<Option<(String, String)>> {
+        let conn = self.conn.lock().unwrap();
+        match conn.query_row("SELECT key, model FROM history_embeddings WHERE url = ?1", params![url], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        }) {
+            Ok(row) => Ok(Some(row)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    // Store (url, content key, vector) rows, replacing each URL's previous vector
+    pub fn save_history_embeddings(&self, entries: &[(String, String, Vec<f32>)], model: &str) -> SqliteResult<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let now = chrono::Utc::now().timestamp();
+
+        let tx = conn.transaction()?;
+        for (url, key, vector) in entries {
+            tx.execute(
+                "INSERT OR REPLACE INTO history_embeddings (url, model, key, vector, embedded_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![url, model, key, crate::embeddings::to_blob(vector), now],
+            )?;
+        }
+        tx.commit()
+    }
+
+    // History vectors from one model joined with their entries, most recently visited first
+    pub fn history_vectors(&self, model: &str, limit: usize) -> SqliteResult<Vec<crate::semantic_history::HistoryVector>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT h.url, h.title, MAX(h.visited_at), MAX(h.visit_count), MAX(COALESCE(h.frecency, 0)), e.vector
+             FROM history_embeddings e JOIN history h ON h.url = e.url
+             WHERE e.model = ?1
+             GROUP BY h.url
+             ORDER BY MAX(h.visited_at) DESC LIMIT ?2"
+        )?;
+        let rows = stmt
+            .query_map(params![model, limit as i64], |row| {
+                Ok(crate::semantic_history::HistoryVector {
+                    url: row.get(0)?,
+                    title: row.get(1)?,
+                    visited_at: row.get(2)?,
+                    visit_count: row.get(3)?,
+                    frecency: row.get(4)?,
+                    vector: crate::embeddings::from_blob(&row.get::<_, Vec<u8>>(5)?),
+                })
+            })?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    // History URLs with no vector from `model`, most recently visited first
+    pub fn history_missing_embeddings(&self, model: &str, limit: usize) -> SqliteResult<Vec<String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT h.url FROM history h LEFT JOIN history_embeddings e ON e.url = h.url
+             WHERE e.url IS NULL OR e.model != ?1
+             GROUP BY h.url
+             ORDER BY MAX(h.visited_at) DESC LIMIT ?2"
+        )?;
+        let urls = stmt
+            .query_map(params![model, limit as i64], |row| row.get(0))?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(urls)
+    }
+
+    // ============================================================================
+    // DOCUMENT METHODS
+    // ============================================================================
+
+    // Store a document and its chunks, replacing any earlier index of the same content
+    pub fn document_save(&self, document: &crate::documents::StoredDocument, chunks: &[crate::documents::DocumentChunk]) -> SqliteResult<()> {
+        let mut conn = self.conn.lock().unwrap();
+
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM document_chunks WHERE doc_id = ?1", params![document.doc_id])?;
+        tx.execute(
+            "INSERT OR REPLACE INTO documents (doc_id, path, name, title, kind, chunks, model, indexed_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                document.doc_id,
+                document.path,
+                document.name,
+                document.title,
+                document.kind.as_str(),
+                document.chunks as i64,
+                document.model,
+                document.indexed_at
+            ],
+        )?;
+        for chunk in chunks {
+            tx.execute(
+                "INSERT INTO document_chunks (doc_id, seq, page, sheet, chapter, paragraph, text, vector) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    document.doc_id,
+                    chunk.seq as i64,
+                    chunk.location.page,
+                    chunk.location.sheet,
+                    chunk.location.chapter,
+                    chunk.location.paragraph,
+                    chunk.text,
+                    crate::embeddings::to_blob(&chunk.vector)
+                ],
+            )?;
+        }
+        tx.commit()
+    }
+
+    pub fn document_get(&self, doc_id: &str) -> SqliteResult<Option<crate::documents::StoredDocument>> {
+        let conn = self.conn.lock().unwrap();
+        match conn.query_row(
+            "SELECT doc_id, path, name, title, kind, chunks, model, indexed_at FROM documents WHERE doc_id = ?1",
+            params![doc_id],
+            Self::row_to_stored_document,
+        ) {
+            Ok(document) => Ok(Some(document)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    // Indexed documents, most recent first
+    pub fn list_documents(&self, limit: usize) -> SqliteResult<Vec<crate::documents::StoredDocument>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT doc_id, path, name, title, kind, chunks, model, indexed_at FROM documents ORDER BY indexed_at DESC LIMIT ?1"
+        )?;
+        let documents = stmt
+            .query_map(params![limit as i64], Self::row_to_stored_document)?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(documents)
+    }
+
+    fn row_to_stored_document(row: &rusqlite::Row) -> SqliteResult<crate::documents::StoredDocument> {
+        let kind: String = row.get(4)?;
+        Ok(crate::documents::StoredDocument {
+            doc_id: row.get(0)?,
+            path: row.get(1)?,
+            name: row.get(2)?,
+            title: row.get(3)?,
+            kind: crate::documents::DocumentKind::parse(&kind).unwrap_or(crate::documents::DocumentKind::Text),
+            chunks: row.get::<_, i64>(5)? as usize,
+            model: row.get(6)?,
+            indexed_at: row.get(7)?,
+        })
+    }
+
+    // A document's chunk texts in reading order, without vectors
+    pub fn document_text(&self, doc_id: &str) -> SqliteResult<String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT text FROM document_chunks WHERE doc_id = ?1 ORDER BY seq")?;
+        let texts = stmt
+            .query_map(params![doc_id], |row| row.get::<_, String>(0))?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(texts.join("\n\n"))
+    }
+
+    // A document's chunks in reading order
+    pub fn document_chunks(&self, doc_id: &str) -> SqliteResult<Vec<crate::documents::DocumentChunk>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT seq, page, sheet, chapter, paragraph, text, vector FROM document_chunks WHERE doc_id = ?1 ORDER BY seq"
+        )?;
+        let chunks = stmt
+            .query_map(params![doc_id], |row| {
+                Ok(crate::documents::DocumentChunk {
+                    seq: row.get::<_, i64>(0)? as usize,
+                    location: crate::documents::Location {
+                        page: row.get(1)?,
+                        sheet: row.get(2)?,
+                        chapter: row.get(3)?,
+                        paragraph: row.get(4)?,
+                    },
+                    text: row.get(5)?,
+                    vector: crate::embeddings::from_blob(&row.get::<_, Vec<u8>>(6)?),
+                })
+            })?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(chunks)
+    }
+
+    // Returns true if the document existed
+    pub fn document_delete(&self, doc_id: &str) -> SqliteResult<bool> {
+        let mut conn = self.conn.lock().unwrap();
+
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM document_chunks WHERE doc_id = ?1", params![doc_id])?;
+        let deleted = tx.execute("DELETE FROM documents WHERE doc_id = ?1", params![doc_id])?;
+        tx.commit()?;
+        Ok(deleted > 0)
+    }
+
+    // ============================================================================
+    // IMAGE METHODS
+    // ============================================================================
+
+    pub fn image_save(&self, image: &crate::images::ImageRecord) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO images (path, name, format, width, height, taken_at, camera, latitude, longitude, ocr_text, caption, caption_model, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            params![
+                image.path,
+                image.name,
+                image.format,
+                image.width,
+                image.height,
+                image.taken_at,
+                image.camera,
+                image.latitude,
+                image.longitude,
+                image.ocr_text,
+                image.caption,
+                image.caption_model,
+                image.updated_at
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn image_get(&self, path: &str) -> SqliteResult<Option<crate::images::ImageRecord>> {
+        let conn = self.conn.lock().unwrap();
+        match conn.query_row(
+            "SELECT path, name, format, width, height, taken_at, camera, latitude, longitude, ocr_text, caption, caption_model, updated_at
+             FROM images WHERE path = ?1",
+            params![path],
+            Self::row_to_image,
+        ) {
+            Ok(image) => Ok(Some(image)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    // Stored images, most recently analyzed first
+    pub fn list_images(&self, limit: usize) -> SqliteResult<Vec<crate::images::ImageRecord>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT path, name, format, width, height, taken_at, camera, latitude, longitude, ocr_text, caption, caption_model, updated_at
+             FROM images ORDER BY updated_at DESC LIMIT ?1"
+        )?;
+        let images = stmt
+            .query_map(params![limit as i64], Self::row_to_image)?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(images)
+    }
+
+    fn row_to_image(row: &rusqlite::Row) -> SqliteResult<crate::images::ImageRecord> {
+        Ok(crate::images::ImageRecord {
+            path: row.get(0)?,
+            name: row.get(1)?,
+            format: row.get(2)?,
+            width: row.get(3)?,
+            height: row.get(4)?,
+            taken_at: row.get(5)?,
+            camera: row.get(6)?,
+            latitude: row.get(7)?,
+            longitude: row.get(8)?,
+            ocr_text: row.get(9)?,
+            caption: row.get(10)?,
+            caption_model: row.get(11)?,
+            updated_at: row.get(12)?,
+        })
+    }
+
+    // ============================================================================
+    // FEED METHODS
+    // ============================================================================
+
+    pub fn feed_save(&self, feed: &crate::feeds::Feed) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO feeds (id, url, site_url, title, description, etag, last_modified, last_fetched_at, last_attempt_at, last_error, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                feed.id,
+                feed.url,
+                feed.site_url,
+                feed.title,
+                feed.description,
+                feed.etag,
+                feed.last_modified,
+                feed.last_fetched_at,
+                feed.last_attempt_at,
+                feed.last_error,
+                feed.created_at
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn feed_get(&self, id: &str) -> SqliteResult<Option<crate::feeds::Feed>> {
+        self.feed_where("f.id = ?1", params![id]).map(|feeds| feeds.into_iter().next())
+    }
+
+    pub fn feed_by_url(&self, url: &str) -> SqliteResult<Option<crate::feeds::Feed>> {
+        self.feed_where("f.url = ?1", params![url]).map(|feeds| feeds.into_iter().next())
+    }
+
+    // All subscriptions with their unread counts, by title
+    pub fn feed_list(&self) -> SqliteResult<Vec<crate::feeds::Feed>> {
+        self.feed_where("1 = 1", params![])
+    }
+
+    // Feeds not attempted since `cutoff` (Unix seconds)
+    pub fn feeds_due(&self, cutoff: i64) -> SqliteResult<Vec<crate::feeds::Feed>> {
+        self.feed_where("COALESCE(f.last_attempt_at, 0) < ?1", params![cutoff])
+    }
+
+    fn feed_where(&self, condition: &str, values: &[&dyn rusqlite::ToSql]) -> SqliteResult<Vec<crate::feeds::Feed>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT f.id, f.url, f.site_url, f.title, f.description, f.etag, f.last_modified, f.last_fetched_at,
+                    f.last_attempt_at, f.last_error, f.created_at,
+                    (SELECT COUNT(*) FROM feed_entries e WHERE e.feed_id = f.id AND e.read = 0)
+             FROM feeds f WHERE {} ORDER BY f.title COLLATE NOCASE",
+            condition
+        ))?;
+        let feeds = stmt
+            .query_map(values, |row| {
+                Ok(crate::feeds::Feed {
+                    id: row.get(0)?,
+                    url: row.get(1)?,
+                    site_url: row.get(2)?,
+                    title: row.get(3)?,
+                    description: row.get(4)?,
+                    etag: row.get(5)?,
+                    last_modified: row.get(6)?,
+                    last_fetched_at: row.get(7)?,
+                    last_attempt_at: row.get(8)?,
+                    last_error: row.get(9)?,
+                    created_at: row.get(10)?,
+                    unread: row.get::<_, i64>(11)? as usize,
+                })
+            })?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(feeds)
+    }
+
+    // A fetch that succeeded (or came back 304); stores the new validators
+    pub fn feed_fetched(&self, id: &str, at: i64, etag: Option<&str>, last_modified: Option<&str>) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE feeds SET last_fetched_at = ?2, last_attempt_at = ?2, etag = ?3, last_modified = ?4, last_error = NULL WHERE id = ?1",
+            params![id, at, etag, last_modified],
+        )?;
+        Ok(())
+    }
+
+    pub fn feed_failed(&self, id: &str, at: i64, error: &str) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE feeds SET last_attempt_at = ?2, last_error = ?3 WHERE id = ?1",
+            params![id, at, error],
+        )?;
+        Ok(())
+    }
+
+    // Deletes the feed and its entries; false if there was no such feed
+    pub fn feed_delete(&self, id: &str) -> SqliteResult<bool> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM feed_entries WHERE feed_id = ?1", params![id])?;
+        let deleted = tx.execute("DELETE FROM feeds WHERE id = ?1", params![id])?;
+        tx.commit()?;
+        Ok(deleted > 0)
+    }
+
+    // Insert entries not seen before (by guid); returns how many were new
+    pub fn feed_add_entries(&self, feed_id: &str, entries: &[crate::feeds::ParsedEntry], fetched_at: i64) -> SqliteResult<usize> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let mut added = 0;
+        for entry in entries {
+            added += tx.execute(
+                "INSERT OR IGNORE INTO feed_entries (id, feed_id, guid, url, title, summary, author, published_at, fetched_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    uuid::Uuid::new_v4().to_string(),
+                    feed_id,
+                    entry.guid,
+                    entry.url,
+                    entry.title,
+                    entry.summary,
+                    entry.author,
+                    entry.published_at.unwrap_or(fetched_at),
+                    fetched_at
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(added)
+    }
+
+    // Drop read entries beyond the newest `keep` of a feed; returns how many were removed
+    pub fn feed_prune_entries(&self, feed_id: &str, keep: usize) -> SqliteResult<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM feed_entries WHERE feed_id = ?1 AND read = 1 AND id NOT IN (
+                SELECT id FROM feed_entries WHERE feed_id = ?1 ORDER BY published_at DESC LIMIT ?2
+             )",
+            params![feed_id, keep as i64],
+        )
+    }
+
+    pub fn feed_list_entries(&self, filter: &crate::feeds::EntryFilter, limit: usize) -> SqliteResult<Vec<crate::feeds::FeedEntry>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT e.id, e.feed_id, f.title, e.url, e.title, e.summary, e.author, e.published_at, e.read
+             FROM feed_entries e JOIN feeds f ON f.id = e.feed_id
+             WHERE (?1 IS NULL OR e.feed_id = ?1)
+               AND (?2 = 0 OR e.read = 0)
+               AND (?3 IS NULL OR instr(lower(e.title), lower(?3)) > 0 OR instr(lower(COALESCE(e.summary, '')), lower(?3)) > 0)
+               AND (?4 IS NULL OR e.published_at >= ?4)
+             ORDER BY e.published_at DESC LIMIT ?5 OFFSET ?6"
+        )?;
+        let entries = stmt
+            .query_map(
+                params![
+                    filter.feed_id,
+                    filter.unread_only,
+                    filter.query.as_deref().map(str::trim).filter(|q| !q.is_empty()),
+                    filter.since,
+                    limit as i64,
+                    filter.offset.unwrap_or(0) as i64
+                ],
+                Self::row_to_feed_entry,
+            )?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(entries)
+    }
+
+    // Entries published since `since`, newest first
+    pub fn feed_entries_since(&self, since: i64, limit: usize) -> SqliteResult<Vec<crate::feeds::FeedEntry>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT e.id, e.feed_id, f.title, e.url, e.title, e.summary, e.author, e.published_at, e.read
+             FROM feed_entries e JOIN feeds f ON f.id = e.feed_id
+             WHERE e.published_at >= ?1 ORDER BY e.published_at DESC LIMIT ?2"
+        )?;
+        let entries = stmt
+            .query_map(params![since, limit as i64], Self::row_to_feed_entry)?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(entries)
+    }
+
+    fn row_to_feed_entry(row: &rusqlite::Row) -> SqliteResult<crate::feeds::FeedEntry> {
+        Ok(crate::feeds::FeedEntry {
+            id: row.get(0)?,
+            feed_id: row.get(1)?,
+            feed_title: row.get(2)?,
+            url: row.get(3)?,
+            title: row.get(4)?,
+            summary: row.get(5)?,
+            author: row.get(6)?,
+            published_at: row.get(7)?,
+            read: row.get(8)?,
+        })
+    }
+
+    // Mark entries (or every entry of a feed) read or unread; returns how many changed
+    pub fn feed_mark_read(&self, entry_ids: &[String], feed_id: Option<&str>, read: bool) -> SqliteResult<usize> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let mut changed = 0;
+        if let Some(feed_id) = feed_id {
+            changed += tx.execute("UPDATE feed_entries SET read = ?2 WHERE feed_id = ?1 AND read != ?2", params![feed_id, read])?;
+        }
+        for id in entry_ids {
+            changed += tx.execute("UPDATE feed_entries SET read = ?2 WHERE id = ?1 AND read != ?2", params![id, read])?;
+        }
+        tx.commit()?;
+        Ok(changed)
+    }
+
+    pub fn feed_site_checked_at(&self, origin: &str) -> SqliteResult<Option<i64>> {
+        let conn = self.conn.lock().unwrap();
+        match conn.query_row("SELECT checked_at FROM feed_sites WHERE origin = ?1", params![origin], |row| row.get(0)) {
+            Ok(checked_at) => Ok(Some(checked_at)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn feed_site_save(&self, origin: &str, feeds: &[crate::feeds::DiscoveredFeed], checked_at: i64) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        let json = serde_json::to_string(feeds).unwrap_or_else(|_| "[]".to_string());
+        conn.execute(
+            "INSERT OR REPLACE INTO feed_sites (origin, feeds_json, checked_at) VALUES (?1, ?2, ?3)",
+            params![origin, json, checked_at],
+        )?;
+        Ok(())
+    }
+
+    pub fn feed_site_links(&self, origin: &str) -> SqliteResult<Vec<crate::feeds::DiscoveredFeed>> {
+        let conn = self.conn.lock().unwrap();
+        match conn.query_row("SELECT feeds_json FROM feed_sites WHERE origin = ?1", params![origin], |row| row.get::<_, String>(0)) {
+            Ok(json) => Ok(serde_json::from_str(&json).unwrap_or_default()),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(Vec::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    // ============================================================================
+    // LLM CACHE METHODS
+    // ============================================================================
+
+    // Cached response by key (entries older than min_created_at are ignored)
+    pub fn llm_cache_get(&self, key: &str, min_created_at: i64) -> SqliteResult<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        let response = match conn.query_row(
+            "SELECT response FROM llm_cache WHERE key = ?1 AND created_at >= ?2",
+            params![key, min_created_at],
+            |row| row.get::<_, String>(0),
+        ) {
+            Ok(response) => response,
+            Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        conn.execute(
+            "UPDATE llm_cache SET last_hit_at = ?2, hit_count = hit_count + 1 WHERE key = ?1",
+            params![key, chrono::Utc::now().timestamp()],
+        )?;
+        Ok(Some(response))
+    }
+
+    // Recent entries with embeddings from the given embedding model, for near-duplicate matching
+    pub fn llm_cache_candidates(&self, task: &str, model: &str, embedding_model: &str, min_created_at: i64) -> SqliteResult<Vec<(String, String, Vec<f32>)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT key, response, embedding FROM llm_cache
+             WHERE task = ?1 AND model = ?2 AND embedding_model = ?3 AND created_at >= ?4
+             ORDER BY last_hit_at DESC LIMIT 1000"
+        )?;
+
+        let rows = stmt.query_map(params![task, model, embedding_model, min_created_at], |row| {
+            let blob: Vec<u8> = row.get(2)?;
+            Ok((row.get(0)?, row.get(1)?, crate::embeddings::from_blob(&blob)))
+        })?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+
+    pub fn llm_cache_touch(&self, key: &str) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE llm_cache SET last_hit_at = ?2, hit_count = hit_count + 1 WHERE key = ?1",
+            params![key, chrono::Utc::now().timestamp()],
+        )?;
+        Ok(())
+    }
+
+    // Insert or replace a cached response
+    pub fn llm_cache_put(&self, key: &str, task: &str, model: &str, prompt: &str, response: &str, embedding: Option<(&str, &[f32])>) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        let now = chrono::Utc::now().timestamp();
+        conn.execute(
+            "INSERT OR REPLACE INTO llm_cache
+             (key, task, model, prompt, response, embedding_model, embedding, created_at, last_hit_at, hit_count)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8, 0)",
+            params![
+                key,
+                task,
+                model,
+                prompt,
+                response,
+                embedding.map(|(m, _)| m),
+                embedding.map(|(_, v)| crate::embeddings::to_blob(v)),
+                now
+            ],
+        )?;
+        Ok(())
+    }
+
+    // Drop expired entries, then least-recently-used entries beyond max_entries
+    pub fn llm_cache_prune(&self, min_created_at: i64, max_entries: usize) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM llm_cache WHERE created_at < ?1", params![min_created_at])?;
+        conn.execute(
+            "DELETE FROM llm_cache WHERE key NOT IN (
+                SELECT key FROM llm_cache ORDER BY last_hit_at DESC LIMIT ?1
+             )",
+            params![max_entries as i64],
+        )?;
+        Ok(())
+    }
+
+    // (entry count, approximate bytes, entries per task)
+    pub fn llm_cache_summary(&self) -> SqliteResult<(usize, i64, Vec<(String, usize)>)> {
+        let conn = self.conn.lock().unwrap();
+        let (entries, size_bytes): (i64, i64) = conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(LENGTH(prompt) + LENGTH(response) + COALESCE(LENGTH(embedding), 0)), 0)
+             FROM llm_cache",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+        let mut stmt = conn.prepare("SELECT task, COUNT(*) FROM llm_cache GROUP BY task ORDER BY COUNT(*) DESC")?;
+        let tasks = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize)))?;
+
+        let mut by_task = Vec::new();
+        for task in tasks {
+            by_task.push(task?);
+        }
+        Ok((entries as usize, size_bytes, by_task))
+    }
+
+    // Remove cached responses (all, or for one task); returns rows removed
+    pub fn llm_cache_clear(&self, task: Option<&str>) -> SqliteResult<usize> {
+        let conn = self.conn.lock().unwrap();
+        match task {
+            Some(task) => conn.execute("DELETE FROM llm_cache WHERE task = ?1", params![task]),
+            None => conn.execute("DELETE FROM llm_cache", []),
+        }
+    }
+
+    // ============================================================================
+    // SESSION CHECKPOINT METHODS
+    // ============================================================================
+
+    // Store a checkpoint, then prune past the retention limits
+    pub fn save_checkpoint(&self, checkpoint: &crate::session::SessionCheckpoint) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        let tabs_json = serde_json::to_string(&checkpoint.tabs)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+
+        conn.execute(
+            "INSERT INTO session_checkpoints (id, name, active_tab_id, tabs_json, tab_count, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                checkpoint.id,
+                checkpoint.name,
+                checkpoint.active_tab_id,
+                tabs_json,
+                checkpoint.tabs.len() as i64,
+                checkpoint.created_at
+            ],
+        )?;
+
+        conn.execute(
+            "DELETE FROM session_checkpoints WHERE name IS NULL AND id NOT IN (
+                SELECT id FROM session_checkpoints WHERE name IS NULL ORDER BY created_at DESC LIMIT ?1
+             )",
+            params![crate::session::MAX_CHECKPOINTS as i64],
+        )?;
+        conn.execute(
+            "DELETE FROM session_checkpoints WHERE name IS NOT NULL AND id NOT IN (
+                SELECT id FROM session_checkpoints WHERE name IS NOT NULL ORDER BY created_at DESC LIMIT ?1
+             )",
+            params![crate::session::MAX_NAMED_CHECKPOINTS as i64],
+        )?;
+        Ok(())
+    }
+
+    // List checkpoints (most recent first)
+    pub fn list_checkpoints(&self, limit: usize) -> SqliteResult<Vec<crate::session::SessionCheckpoint>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, name, active_tab_id, tabs_json, created_at
+             FROM session_checkpoints ORDER BY created_at DESC, rowid DESC LIMIT ?1"
+        )?;
+
+        let checkpoints = stmt.query_map(params![limit as i64], Self::row_to_checkpoint)?;
+
+        let mut result = Vec::new();
+        for checkpoint in checkpoints {
+            result.push(checkpoint?);
+        }
+        Ok(result)
+    }
+
+    // Get checkpoint by ID
+    pub fn get_checkpoint(&self, id: &str) -> SqliteResult<Option<crate::session::SessionCheckpoint>> {
+        let conn = self.conn.lock().unwrap();
+        match conn.query_row(
+            "SELECT id, name, active_tab_id, tabs_json, created_at
+             FROM session_checkpoints WHERE id = ?1",
+            params![id],
+            Self::row_to_checkpoint,
+        ) {
+            Ok(checkpoint) => Ok(Some(checkpoint)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn row_to_checkpoint(row: &rusqlite::Row) -> SqliteResult<crate::session::SessionCheckpoint> {
+        let tabs_json: String = row.get(3)?;
+        let tabs = serde_json::from_str(&tabs_json).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, Box::new(e))
+        })?;
+
+        Ok(crate::session::SessionCheckpoint {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            active_tab_id: row.get(2)?,
+            tabs,
+            created_at: row.get(4)?,
+        })
+    }
+
+    // ============================================================================
+    // RESEARCH SESSION METHODS
+    // ============================================================================
+
+    // Insert or update a research session
+    pub fn save_research_session(&self, session: &crate::research::ResearchSession) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        let session_json = serde_json::to_string(session)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+
+        conn.execute(
+            "INSERT OR REPLACE INTO research_sessions (id, title, session_json, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![session.id, session.title, session_json, session.created_at, session.updated_at],
+        )?;
+        Ok(())
+    }
+
+    // Get research session by ID
+    pub fn get_research_session(&self, id: &str) -> SqliteResult<Option<crate::research::ResearchSession>> {
+        let conn = self.conn.lock().unwrap();
+        match conn.query_row(
+            "SELECT session_json FROM research_sessions WHERE id = ?1",
+            params![id],
+            Self::row_to_research_session,
+        ) {
+            Ok(session) => Ok(Some(session)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    // List research sessions (most recently updated first)
+    pub fn list_research_sessions(&self, limit: usize) -> SqliteResult<Vec<crate::research::ResearchSession>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT session_json FROM research_sessions ORDER BY updated_at DESC LIMIT ?1"
+        )?;
+
+        let sessions = stmt.query_map(params![limit as i64], Self::row_to_research_session)?;
+
+        let mut result = Vec::new();
+        for session in sessions {
+            result.push(session?);
+        }
+        Ok(result)
+    }
+
+    // Delete research session
+    pub fn delete_research_session(&self, id: &str) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM research_sessions WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    fn row_to_research_session(row: &rusqlite::Row) -> SqliteResult<crate::research::ResearchSession> {
+        let json: String = row.get(0)?;
+        serde_json::from_str(&json).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+        })
+    }
+
+    // ============================================================================
+    // DOWNLOADS METHODS
+    // ============================================================================
+
+    // Add or update download
+    pub fn save_download(&self, id: &str, url: &str, filename: Option<&str>, path: Option<&str>, 
+                         status: &str, progress: f64, received_bytes: i64, total_bytes: Option<i64>,
+                         checksum: Option<&str>, safety_status: Option<&str>) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        
+        conn.execute(
+            "INSERT OR REPLACE INTO downloads 
+             (id, url, filename, path, status, progress, received_bytes, total_bytes, created_at, checksum, safety_status,
+              verification_json)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, 
+                     COALESCE((SELECT created_at FROM downloads WHERE id = ?1), ?9),
+                     COALESCE(?10, (SELECT checksum FROM downloads WHERE id = ?1)),
+                     COALESCE(?11, (SELECT safety_status FROM downloads WHERE id = ?1)),
+                     (SELECT verification_json FROM downloads WHERE id = ?1))",
+            params![id, url, filename, path, status, progress, received_bytes, total_bytes, now, checksum, safety_status],
+        )?;
+        Ok(())
+    }
+
+    pub fn download_status(&self, id: &str) -> SqliteResult<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        match conn.query_row("SELECT status FROM downloads WHERE id = ?1", params![id], |row| row.get(0)) {
+            Ok(status) => Ok(Some(status)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    // Where a download was saved; None when it doesn't exist or has no file yet
+    pub fn download_path(&self, id: &str) -> SqliteResult<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        match conn.query_row("SELECT path FROM downloads WHERE id = ?1", params![id], |row| row.get(0)) {
+            Ok(path) => Ok(path),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn download_set_status(&self, id: &str, status: &str) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("UPDATE downloads SET status = ?1 WHERE id = ?2", params![status, id])?;
+        Ok(())
+    }
+
+    // Store a verification result (see verify.rs) and mark the download completed again
+    pub fn download_set_verification(&self, id: &str, checksum: Option<&str>, safety_status: Option<&str>,
+                                     verification_json: &str) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE downloads SET status = 'completed', checksum = COALESCE(?1, checksum),
+             safety_status = COALESCE(?2, safety_status), verification_json = ?3 WHERE id = ?4",
+            params![checksum, safety_status, verification_json, id],
+        )?;
+        Ok(())
+    }
+
+    // Get all downloads
+    pub fn get_downloads(&self, limit: Option<usize>) -> SqliteResult<Vec<(String, String, Option<String>, Option<String>, String, f64, i64, Option<i64>, i64, Option<i64>, Option<String>, Option<String>, Option<String>)>> {
+        let conn = self.conn.lock().unwrap();
+        let limit_val = limit.unwrap_or(100) as i64;
+        let mut stmt = conn.prepare(
+            "SELECT id, url, filename, path, status, progress, received_bytes, total_bytes, created_at, completed_at, checksum, safety_status,
+                    verification_json
+             FROM downloads ORDER BY created_at DESC LIMIT ?1"
+        )?;
+
+        let entries = stmt.query_map(params![limit_val], |row| {
+            Ok((
+                row.get(0)?, // id
+                row.get(1)?, // url
+                row.get(2)?, // filename
+                row.get(3)?, // path
+                row.get(4)?, // status
+                row.get(5)?, // progress
+                row.get(6)?, // received_bytes
+                row.get(7)?, // total_bytes
+                row.get(8)?, // created_at
+                row.get(9)?, // completed_at
+                row.get(10)?, // checksum
+                row.get(11)?, // safety_status
+                row.get(12)?, // verification_json
+            ))
+        })?;
+
+        let mut result = Vec::new();
+        for entry in entries {
+            result.push(entry?);
+        }
+        Ok(result)
+    }
+
+    // Delete download
+    pub fn delete_download(&self, id: &str) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM downloads WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    // ============================================================================
+    // BOOKMARKS METHODS (for backend sync if needed)
+    // ============================================================================
+
+    // Add or update bookmark
+    pub fn save_bookmark(&self, id: &str, url: &str, title: &str, folder: Option<&str>, 
+                         tags: Option<&str>, description: Option<&str>) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        
+        conn.execute(
+            "INSERT OR REPLACE INTO bookmarks 
+             (id, url, title, created_at, folder, tags, description)
+             VALUES (?1, ?2, ?3, COALESCE((SELECT created_at FROM bookmarks WHERE id = ?1), ?4), ?5, ?6, ?7)",
+            params![id, url, title, now, folder, tags, description],
+        )?;
+        Ok(())
+    }
+
+    // Bookmark imported from another browser; skipped when the URL is already bookmarked
+    pub fn import_bookmark(&self, url: &str, title: &str, folder: Option<&str>) -> SqliteResult<bool> {
+        let conn = self.conn.lock().unwrap();
+        let added = conn.execute(
+            "INSERT INTO bookmarks (id, url, title, created_at, folder)
+             SELECT ?1, ?2, ?3, ?4, ?5
+             WHERE NOT EXISTS (SELECT 1 FROM bookmarks WHERE url = ?2)",
+            params![uuid::Uuid::new_v4().to_string(), url, title, chrono::Utc::now().timestamp(), folder],
+        )?;
+        Ok(added > 0)
+    }
+
+    // Get all bookmarks
+    pub fn get_bookmarks(&self) -> SqliteResult<Vec<(String, String, String, i64, Option<String>, Option<String>, Option<String>)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, url, title, created_at, folder, tags, description FROM bookmarks ORDER BY created_at DESC"
+        )?;
+
+        let entries = stmt.query_map([], |row| {
+            Ok((
+                row.get(0)?, // id
+                row.get(1)?, // url
+                row.get(2)?, // title
+                row.get(3)?, // created_at
+                row.get(4)?, // folder
+                row.get(5)?, // tags
+                row.get(6)?, // description
+            ))
+        })?;
+
+        let mut result = Vec::new();
+        for entry in entries {
+            result.push(entry?);
+        }
+        Ok(result)
+    }
+
+    // Bookmarks whose URL or title contains every term: (id, url, title)
+    pub fn bookmark_matches(&self, terms: &[String], limit: usize) -> SqliteResult<Vec<(String, String, String)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut sql = String::from("SELECT id, url, title FROM bookmarks WHERE 1 = 1");
+        for i in 0..terms.len() {
+            sql.push_str(&format!(" AND (url LIKE ?{0} ESCAPE '\\' OR title LIKE ?{0} ESCAPE '\\')", i + 1));
+        }
+        sql.push_str(&format!(" ORDER BY created_at DESC LIMIT {}", limit));
+
+        let patterns: Vec<String> = terms.iter().map(|t| format!("%{}%", escape_like(t))).collect();
+        let mut stmt = conn.prepare(&sql)?;
+        let entries = stmt.query_map(rusqlite::params_from_iter(patterns.iter()), |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })?;
+
+        let mut result = Vec::new();
+        for entry in entries {
+            result.push(entry?);
+        }
+        Ok(result)
+    }
+
+    // Delete bookmark
+    pub fn delete_bookmark(&self, id: &str) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM bookmarks WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    // ============================================================================
+    // ARCHIVE METHODS
+    // ============================================================================
+
+    // Record a saved page archive
+    pub fn save_archive(&self, entry: &crate::archive::ArchiveEntry) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO archives
+             (id, url, title, tab_id, file_path, size_bytes, resource_count, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                entry.id,
+                entry.url,
+                entry.title,
+                entry.tab_id,
+                entry.file_path,
+                entry.size_bytes,
+                entry.resource_count,
+                entry.created_at
+            ],
+        )?;
+        Ok(())
+    }
+
+    // List archives (most recent first)
+    pub fn list_archives(&self, limit: usize) -> SqliteResult<Vec<crate::archive::ArchiveEntry>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, url, title, tab_id, file_path, size_bytes, resource_count, created_at
+             FROM archives ORDER BY created_at DESC LIMIT ?1"
+        )?;
+
+        let entries = stmt.query_map(params![limit as i64], Self::row_to_archive)?;
+
+        let mut result = Vec::new();
+        for entry in entries {
+            result.push(entry?);
+        }
+        Ok(result)
+    }
+
+    // Get archive by ID
+    pub fn get_archive(&self, id: &str) -> SqliteResult<Option<crate::archive::ArchiveEntry>> {
+        let conn = self.conn.lock().unwrap();
+        match conn.query_row(
+            "SELECT id, url, title, tab_id, file_path, size_bytes, resource_count, created_at
+             FROM archives WHERE id = ?1",
+            params![id],
+            Self::row_to_archive,
+        ) {
+            Ok(entry) => Ok(Some(entry)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    // Delete archive record
+    pub fn delete_archive(&self, id: &str) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM archives WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    // ============================================================================
+    // FOCUS METHODS
+    // ============================================================================
+
+    pub fn save_focus_session(&self, record: &crate::focus::FocusRecord) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO focus_sessions
+             (id, started_at, ended_at, planned_secs, focused_secs, completed, blocked_attempts)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                record.id,
+                record.started_at,
+                record.ended_at,
+                record.planned_secs as i64,
+                record.focused_secs as i64,
+                record.completed,
+                record.blocked_attempts
+            ],
+        )?;
+        Ok(())
+    }
+
+    // Every recorded session, oldest first
+    pub fn list_focus_sessions(&self) -> SqliteResult<Vec<crate::focus::FocusRecord>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, started_at, ended_at, planned_secs, focused_secs, completed, blocked_attempts
+             FROM focus_sessions ORDER BY started_at"
+        )?;
+        let records = stmt
+            .query_map([], |row| {
+                Ok(crate::focus::FocusRecord {
+                    id: row.get(0)?,
+                    started_at: row.get(1)?,
+                    ended_at: row.get(2)?,
+                    planned_secs: row.get::<_, i64>(3)? as u64,
+                    focused_secs: row.get::<_, i64>(4)? as u64,
+                    completed: row.get(5)?,
+                    blocked_attempts: row.get(6)?,
+                })
+            })?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(records)
+    }
+
+    // ============================================================================
+    // USAGE METHODS
+    // ============================================================================
+
+    // Add to a day's totals for a domain and mode
+    pub fn usage_add(&self, row: &crate::usage::UsageRow) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO usage_daily (day, domain, mode, seconds, visits) VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(day, domain, mode) DO UPDATE SET
+                seconds = seconds + excluded.seconds,
+                visits = visits + excluded.visits",
+            params![row.day, row.domain, row.mode, row.seconds as i64, row.visits as i64],
+        )?;
+        Ok(())
+    }
+
+    // Rows for days in [from, to] (YYYY-MM-DD, inclusive)
+    pub fn usage_between(&self, from: &str, to: &str) -> SqliteResult<Vec<crate::usage::UsageRow>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT day, domain, mode, seconds, visits FROM usage_daily WHERE day >= ?1 AND day <= ?2 ORDER BY day"
+        )?;
+        let rows = stmt
+            .query_map(params![from, to], |row| {
+                Ok(crate::usage::UsageRow {
+                    day: row.get(0)?,
+                    domain: row.get(1)?,
+                    mode: row.get(2)?,
+                    seconds: row.get::<_, i64>(3)? as u64,
+                    visits: row.get::<_, i64>(4)? as u64,
+                })
+            })?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    pub fn usage_clear(&self) -> SqliteResult<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM usage_daily", [])
+    }
+
+    // ============================================================================
+    // LLM USAGE METHODS
+    // ============================================================================
+
+    // Add one sample to a day's totals for a provider and model
+    pub fn llm_usage_add(&self, row: &crate::llm_usage::UsageRow) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        let t = &row.totals;
+        conn.execute(
+            "INSERT INTO llm_usage_daily
+                (day, provider, model, requests, prompt_tokens, completion_tokens, estimated_requests, cost_usd)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+             ON CONFLICT(day, provider, model) DO UPDATE SET
+                requests = requests + excluded.requests,
+                prompt_tokens = prompt_tokens + excluded.prompt_tokens,
+                completion_tokens = completion_tokens + excluded.completion_tokens,
+                estimated_requests = estimated_requests + excluded.estimated_requests,
+                cost_usd = cost_usd + excluded.cost_usd",
+            params![
+                row.day,
+                row.provider,
+                row.model,
+                t.requests as i64,
+                t.prompt_tokens as i64,
+                t.completion_tokens as i64,
+                t.estimated_requests as i64,
+                t.cost_usd,
+            ],
+        )?;
+        Ok(())
+    }
+
+    // Rows for days in [from, to] (YYYY-MM-DD, inclusive)
+    pub fn llm_usage_between(&self, from: &str, to: &str) -> SqliteResult<Vec<crate::llm_usage::UsageRow>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT day, provider, model, requests, prompt_tokens, completion_tokens, estimated_requests, cost_usd
+             FROM llm_usage_daily WHERE day >= ?1 AND day <= ?2 ORDER BY day"
+        )?;
+        let rows = stmt
+            .query_map(params![from, to], |row| {
+                Ok(crate::llm_usage::UsageRow {
+                    day: row.get(0)?,
+                    provider: row.get(1)?,
+                    model: row.get(2)?,
+                    totals: crate::llm_usage::UsageTotals {
+                        requests: row.get::<_, i64>(3)? as u64,
+                        prompt_tokens: row.get::<_, i64>(4)? as u64,
+                        completion_tokens: row.get::<_, i64>(5)? as u64,
+                        estimated_requests: row.get::<_, i64>(6)? as u64,
+                        cost_usd: row.get(7)?,
+                    },
+                })
+            })?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    // ============================================================================
+    // FX RATE METHODS
+    // ============================================================================
+
+    // Replace the stored rates with a newer reference set
+    pub fn fx_rates_save(&self, rates: &crate::fx::FxRates) -> SqliteResult<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM fx_rates", [])?;
+        for (currency, per_eur) in &rates.per_eur {
+            tx.execute(
+                "INSERT INTO fx_rates (currency, per_eur, as_of, fetched_at) VALUES (?1, ?2, ?3, ?4)",
+                params![currency, per_eur, rates.as_of, rates.fetched_at],
+            )?;
+        }
+        tx.commit()
+    }
+
+    pub fn fx_rates_load(&self) -> SqliteResult<Option<crate::fx::FxRates>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT currency, per_eur, as_of, fetched_at FROM fx_rates")?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?, row.get::<_, String>(2)?, row.get::<_, i64>(3)?)))?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        let Some((_, _, as_of, fetched_at)) = rows.first().cloned() else {
+            return Ok(None);
+        };
+        Ok(Some(crate::fx::FxRates {
+            as_of,
+            fetched_at,
+            per_eur: rows.into_iter().map(|(currency, per_eur, _, _)| (currency, per_eur)).collect(),
+        }))
+    }
+
+    // ============================================================================
+    // MARKET DATA METHODS
+    // ============================================================================
+
+    pub fn ohlc_save(&self, symbol: &str, interval: &str, bars: &[crate::ohlc::Bar]) -> SqliteResult<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for bar in bars {
+            tx.execute(
+                "INSERT OR REPLACE INTO ohlc_bars (symbol, interval, time, open, high, low, close, volume)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![symbol, interval, bar.time, bar.open, bar.high, bar.low, bar.close, bar.volume],
+            )?;
+        }
+        tx.commit()
+    }
+
+    // Bars in [from, to], oldest first
+    pub fn ohlc_load(&self, symbol: &str, interval: &str, from: i64, to: i64) -> SqliteResult<Vec<crate::ohlc::Bar>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT time, open, high, low, close, volume FROM ohlc_bars
+             WHERE symbol = ?1 AND interval = ?2 AND time >= ?3 AND time <= ?4
+             ORDER BY time ASC",
+        )?;
+        let bars = stmt
+            .query_map(params![symbol, interval, from, to], |row| {
+                Ok(crate::ohlc::Bar {
+                    time: row.get(0)?,
+                    open: row.get(1)?,
+                    high: row.get(2)?,
+                    low: row.get(3)?,
+                    close: row.get(4)?,
+                    volume: row.get(5)?,
+                })
+            })?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(bars)
+    }
+
+    pub fn backtest_save(&self, result: &crate::backtest::BacktestResult) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        let json = serde_json::to_string(result).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        conn.execute(
+            "INSERT OR REPLACE INTO backtests (id, symbol, result_json, created_at) VALUES (?1, ?2, ?3, ?4)",
+            params![result.id, result.symbol, json, result.created_at],
+        )?;
+        Ok(())
+    }
+
+    pub fn backtest_get(&self, id: &str) -> SqliteResult<Option<crate::backtest::BacktestResult>> {
+        let conn = self.conn.lock().unwrap();
+        match conn.query_row("SELECT result_json FROM backtests WHERE id = ?1", params![id], Self::row_to_backtest) {
+            Ok(result) => Ok(Some(result)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    // Newest first, optionally for one symbol
+    pub fn backtest_list(&self, symbol: Option<&str>, limit: usize) -> SqliteResult<Vec<crate::backtest::BacktestResult>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT result_json FROM backtests WHERE ?1 IS NULL OR symbol = ?1 ORDER BY created_at DESC LIMIT ?2",
+        )?;
+        let results = stmt
+            .query_map(params![symbol, limit as i64], Self::row_to_backtest)?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(results)
+    }
+
+    pub fn backtest_delete(&self, id: &str) -> SqliteResult<bool> {
+        let conn = self.conn.lock().unwrap();
+        Ok(conn.execute("DELETE FROM backtests WHERE id = ?1", params![id])? > 0)
+    }
+
+    fn row_to_backtest(row: &rusqlite::Row) -> SqliteResult<crate::backtest::BacktestResult> {
+        let json: String = row.get(0)?;
+        serde_json::from_str(&json).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+        })
+    }
+
+    // ============================================================================
+    // TRADE JOURNAL METHODS
+    // ============================================================================
+
+    pub fn trade_log_save(&self, trade: &crate::journal::TradeLog) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO trade_logs
+             (id, symbol, side, quantity, entry_price, exit_price, fees, pnl, opened_at, closed_at, strategy, source, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            params![
+                trade.id,
+                trade.symbol,
+                if trade.side == crate::journal::TradeSide::Buy { "buy" } else { "sell" },
+                trade.quantity,
+                trade.entry_price,
+                trade.exit_price,
+                trade.fees,
+                trade.pnl,
+                trade.opened_at,
+                trade.closed_at,
+                trade.strategy,
+                trade.source,
+                trade.created_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn trade_log_get(&self, id: &str) -> SqliteResult<Option<crate::journal::TradeLog>> {
+        let conn = self.conn.lock().unwrap();
+        match conn.query_row(
+            "SELECT id, symbol, side, quantity, entry_price, exit_price, fees, pnl, opened_at, closed_at, strategy, source, created_at
+             FROM trade_logs WHERE id = ?1",
+            params![id],
+            Self::row_to_trade_log,
+        ) {
+            Ok(trade) => Ok(Some(trade)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    // Most recently opened first, optionally for one symbol
+    pub fn trade_log_list(&self, symbol: Option<&str>, limit: usize) -> SqliteResult<Vec<crate::journal::TradeLog>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, symbol, side, quantity, entry_price, exit_price, fees, pnl, opened_at, closed_at, strategy, source, created_at
+             FROM trade_logs WHERE ?1 IS NULL OR symbol = ?1 ORDER BY opened_at DESC LIMIT ?2",
+        )?;
+        let trades = stmt
+            .query_map(params![symbol, limit as i64], Self::row_to_trade_log)?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(trades)
+    }
+
+    pub fn trade_logs_closed_between(&self, from: i64, to: i64) -> SqliteResult<Vec<crate::journal::TradeLog>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, symbol, side, quantity, entry_price, exit_price, fees, pnl, opened_at, closed_at, strategy, source, created_at
+             FROM trade_logs WHERE closed_at >= ?1 AND closed_at < ?2 ORDER BY closed_at ASC",
+        )?;
+        let trades = stmt
+            .query_map(params![from, to], Self::row_to_trade_log)?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(trades)
+    }
+
+    fn row_to_trade_log(row: &rusqlite::Row) -> SqliteResult<crate::journal::TradeLog> {
+        let side: String = row.get(2)?;
+        Ok(crate::journal::TradeLog {
+            id: row.get(0)?,
+            symbol: row.get(1)?,
+            side: if side == "sell" { crate::journal::TradeSide::Sell } else { crate::journal::TradeSide::Buy },
+            quantity: row.get(3)?,
+            entry_price: row.get(4)?,
+            exit_price: row.get(5)?,
+            fees: row.get(6)?,
+            pnl: row.get(7)?,
+            opened_at: row.get(8)?,
+            closed_at: row.get(9)?,
+            strategy: row.get(10)?,
+            source: row.get(11)?,
+            created_at: row.get(12)?,
+        })
+    }
+
+    pub fn journal_entry_save(&self, entry: &crate::journal::JournalEntry) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        let tags_json = serde_json::to_string(&entry.tags).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        let snapshot_json = entry
+            .snapshot
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        conn.execute(
+            "INSERT OR REPLACE INTO journal_entries (id, trade_id, notes, tags_json, snapshot_json, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![entry.id, entry.trade_id, entry.notes, tags_json, snapshot_json, entry.created_at, entry.updated_at],
+        )?;
+        Ok(())
+    }
+
+    pub fn journal_entry_for_trade(&self, trade_id: &str) -> SqliteResult<Option<crate::journal::JournalEntry>> {
+        let conn = self.conn.lock().unwrap();
+        match conn.query_row(
+            "SELECT id, trade_id, notes, tags_json, snapshot_json, created_at, updated_at
+             FROM journal_entries WHERE trade_id = ?1",
+            params![trade_id],
+            Self::row_to_journal_entry,
+        ) {
+            Ok(entry) => Ok(Some(entry)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    // Entries whose trades closed in [from, to)
+    pub fn journal_entries_closed_between(&self, from: i64, to: i64) -> SqliteResult<Vec<crate::journal::JournalEntry>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT j.id, j.trade_id, j.notes, j.tags_json, j.snapshot_json, j.created_at, j.updated_at
+             FROM journal_entries j JOIN trade_logs t ON t.id = j.trade_id
+             WHERE t.closed_at >= ?1 AND t.closed_at < ?2",
+        )?;
+        let entries = stmt
+            .query_map(params![from, to], Self::row_to_journal_entry)?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(entries)
+    }
+
+    fn row_to_journal_entry(row: &rusqlite::Row) -> SqliteResult<crate::journal::JournalEntry> {
+        let tags_json: String = row.get(3)?;
+        let snapshot_json: Option<String> = row.get(4)?;
+        Ok(crate::journal::JournalEntry {
+            id: row.get(0)?,
+            trade_id: row.get(1)?,
+            notes: row.get(2)?,
+            tags: serde_json::from_str(&tags_json).unwrap_or_default(),
+            snapshot: snapshot_json.and_then(|json| serde_json::from_str(&json).ok()),
+            created_at: row.get(5)?,
+            updated_at: row.get(6)?,
+        })
+    }
+
+    // ============================================================================
+    // NETWORK AUDIT METHODS
+    // ============================================================================
+
+    pub fn network_audit_insert(&self, entry: &crate::network_audit::AuditEntry) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO network_audit
+                (timestamp, method, host, path, purpose, status, bytes_sent, bytes_received, duration_ms, privacy_mode, local, error)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                entry.timestamp,
+                entry.method,
+                entry.host,
+                entry.path,
+                entry.purpose,
+                entry.status,
+                entry.bytes_sent as i64,
+                entry.bytes_received.map(|b| b as i64),
+                entry.duration_ms as i64,
+                entry.privacy_mode,
+                entry.local,
+                entry.error,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn network_audit_since(&self, since: i64) -> SqliteResult<Vec<crate::network_audit::AuditEntry>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, timestamp, method, host, path, purpose, status, bytes_sent, bytes_received, duration_ms, privacy_mode, local, error
+             FROM network_audit WHERE timestamp >= ?1 ORDER BY timestamp DESC"
+        )?;
+        let rows = stmt
+            .query_map(params![since], |row| {
+                Ok(crate::network_audit::AuditEntry {
+                    id: row.get::<_, i64>(0)? as u64,
+                    timestamp: row.get(1)?,
+                    method: row.get(2)?,
+                    host: row.get(3)?,
+                    path: row.get(4)?,
+                    purpose: row.get(5)?,
+                    status: row.get(6)?,
+                    bytes_sent: row.get::<_, i64>(7)? as u64,
+                    bytes_received: row.get::<_, Option<i64>>(8)?.map(|b| b as u64),
+                    duration_ms: row.get::<_, i64>(9)? as u64,
+                    privacy_mode: row.get(10)?,
+                    local: row.get(11)?,
+                    error: row.get(12)?,
+                })
+            })?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    // Delete entries older than `before` (unix ms); i64::MAX clears everything
+    pub fn network_audit_prune(&self, before: i64) -> SqliteResult<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM network_audit WHERE timestamp < ?1", params![before])
+    }
+
+    // ============================================================================
+    // AGENT ACTION METHODS
+    // ============================================================================
+
+    pub fn agent_action_insert(&self, action: &crate::approvals::ProposedAction) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO agent_actions
+                (id, tool, arguments, risk, source, run_id, status, auto_approved, created_at, decided_at, result, error)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                action.id,
+                action.tool,
+                action.arguments.to_string(),
+                action.risk.as_str(),
+                action.source,
+                action.run_id,
+                action.status.as_str(),
+                action.auto_approved,
+                action.created_at,
+                action.decided_at,
+                action.result.as_ref().map(|r| r.to_string()),
+                action.error,
+            ],
+        )?;
+        Ok(())
+    }
+
+    // Store the outcome of a decided action
+    pub fn agent_action_update(&self, action: &crate::approvals::ProposedAction) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE agent_actions SET status = ?2, decided_at = ?3, result = ?4, error = ?5 WHERE id = ?1",
+            params![
+                action.id,
+                action.status.as_str(),
+                action.decided_at,
+                action.result.as_ref().map(|r| r.to_string()),
+                action.error,
+            ],
+        )?;
+        Ok(())
+    }
+
+    // Move a pending action to `status`; false if it was no longer pending
+    pub fn agent_action_decide(&self, id: &str, status: crate::approvals::ActionStatus, decided_at: i64) -> SqliteResult<bool> {
+        let conn = self.conn.lock().unwrap();
+        let changed = conn.execute(
+            "UPDATE agent_actions SET status = ?2, decided_at = ?3 WHERE id = ?1 AND status = 'pending'",
+            params![id, status.as_str(), decided_at],
+        )?;
+        Ok(changed == 1)
+    }
+
+    pub fn agent_action_get(&self, id: &str) -> SqliteResult<Option<crate::approvals::ProposedAction>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, tool, arguments, risk, source, run_id, status, auto_approved, created_at, decided_at, result, error
+             FROM agent_actions WHERE id = ?1"
+        )?;
+        let mut rows = stmt.query_map(params![id], Self::row_to_agent_action)?;
+        rows.next().transpose()
+    }
+
+    // Oldest first
+    pub fn agent_actions_by_status(&self, status: crate::approvals::ActionStatus) -> SqliteResult<Vec<crate::approvals::ProposedAction>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, tool, arguments, risk, source, run_id, status, auto_approved, created_at, decided_at, result, error
+             FROM agent_actions WHERE status = ?1 ORDER BY created_at ASC"
+        )?;
+        let rows = stmt
+            .query_map(params![status.as_str()], Self::row_to_agent_action)?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    pub fn agent_action_delete(&self, id: &str) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM agent_actions WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    // Pending (or mid-run) actions from a previous session; nothing is waiting on them any more
+    pub fn agent_actions_expire_pending(&self, now: i64) -> SqliteResult<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE agent_actions SET status = 'expired', decided_at = ?1 WHERE status IN ('pending', 'approved')",
+            params![now],
+        )
+    }
+
+    fn row_to_agent_action(row: &rusqlite::Row) -> SqliteResult<crate::approvals::ProposedAction> {
+        let json = |i: usize| -> SqliteResult<Option<serde_json::Value>> {
+            Ok(row.get::<_, Option<String>>(i)?.and_then(|s| serde_json::from_str(&s).ok()))
+        };
+        Ok(crate::approvals::ProposedAction {
+            id: row.get(0)?,
+            tool: row.get(1)?,
+            arguments: json(2)?.unwrap_or(serde_json::Value::Null),
+            risk: crate::tools::Risk::parse(&row.get::<_, String>(3)?).unwrap_or(crate::tools::Risk::High),
+            source: row.get(4)?,
+            run_id: row.get(5)?,
+            status: crate::approvals::ActionStatus::parse(&row.get::<_, String>(6)?)
+                .unwrap_or(crate::approvals::ActionStatus::Expired),
+            auto_approved: row.get(7)?,
+            created_at: row.get(8)?,
+            decided_at: row.get(9)?,
+            result: json(10)?,
+            error: row.get(11)?,
+        })
+    }
+
+    // ============================================================================
+    // AGENT RUN METHODS
+    // ============================================================================
+
+    pub fn agent_run_insert(&self, run: &crate::agent_runs::AgentRunSummary) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO agent_runs (id, kind, request_id, query, status, started_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![run.id, run.kind, run.request_id, run.query, run.status.as_str(), run.started_at],
+        )?;
+        Ok(())
+    }
+
+    pub fn agent_run_add_usage(&self, id: &str, prompt: &crate::agent_runs::PromptRecord) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE agent_runs SET
+                provider = ?2, model = ?3, prompt_count = prompt_count + 1,
+                prompt_tokens = prompt_tokens + ?4, completion_tokens = completion_tokens + ?5
+             WHERE id = ?1",
+            params![id, prompt.provider, prompt.model, prompt.prompt_tokens as i64, prompt.completion_tokens as i64],
+        )?;
+        Ok(())
+    }
+
+    pub fn agent_run_count_tool_call(&self, id: &str) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("UPDATE agent_runs SET tool_calls = tool_calls + 1 WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    pub fn agent_run_finish(
+        &self,
+        id: &str,
+        status: crate::agent_runs::RunStatus,
+        finished_at: i64,
+        answer: Option<&str>,
+        error: Option<&str>,
+    ) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE agent_runs SET status = ?2, finished_at = ?3, answer = ?4, error = ?5 WHERE id = ?1",
+            params![id, status.as_str(), finished_at, answer, error],
+        )?;
+        Ok(())
+    }
+
+    pub fn agent_run_entry_insert(&self, run_id: &str, entry: &crate::agent_runs::RunEntry) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        let kind = match entry.kind {
+            crate::agent_runs::EntryKind::Prompt => "prompt",
+            crate::agent_runs::EntryKind::Event => "event",
+        };
+        conn.execute(
+            "INSERT INTO agent_run_entries (run_id, seq, timestamp, kind, data) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![run_id, entry.seq as i64, entry.timestamp, kind, entry.data.to_string()],
+        )?;
+        Ok(())
+    }
+
+    pub fn agent_runs_interrupt_running(&self) -> SqliteResult<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("UPDATE agent_runs SET status = 'interrupted' WHERE status = 'running'", [])
+    }
+
+    // Keep the newest `keep` runs (and their entries)
+    pub fn agent_runs_prune(&self, keep: usize) -> SqliteResult<usize> {
+        let conn = self.conn.lock().unwrap();
+        let removed = conn.execute(
+            "DELETE FROM agent_runs WHERE id NOT IN (SELECT id FROM agent_runs ORDER BY started_at DESC LIMIT ?1)",
+            params![keep as i64],
+        )?;
+        if removed > 0 {
+            conn.execute("DELETE FROM agent_run_entries WHERE run_id NOT IN (SELECT id FROM agent_runs)", [])?;
+        }
+        Ok(removed)
+    }
+
+    pub fn agent_runs_list(
+        &self,
+        kind: Option<&str>,
+        query: Option<&str>,
+        status: Option<crate::agent_runs::RunStatus>,
+        limit: usize,
+        offset: usize,
+    ) -> SqliteResult<Vec<crate::agent_runs::AgentRunSummary>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, kind, request_id, query, status, provider, model, prompt_count, prompt_tokens,
+                    completion_tokens, tool_calls, started_at, finished_at, error
+             FROM agent_runs
+             WHERE (?1 IS NULL OR kind = ?1)
+               AND (?2 IS NULL OR instr(lower(query), lower(?2)) > 0)
+               AND (?3 IS NULL OR status = ?3)
+             ORDER BY started_at DESC LIMIT ?4 OFFSET ?5"
+        )?;
+        let rows = stmt
+            .query_map(
+                params![kind, query, status.map(|s| s.as_str()), limit as i64, offset as i64],
+                Self::row_to_agent_run_summary,
+            )?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    // Finished runs started within [from_ms, to_ms] that produced an answer: kind, query, answer
+    pub fn agent_runs_answered_between(&self, from_ms: i64, to_ms: i64, limit: usize) -> SqliteResult<Vec<(String, String, String)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT kind, query, answer FROM agent_runs
+             WHERE status = 'done' AND answer IS NOT NULL AND answer != ''
+               AND started_at BETWEEN ?1 AND ?2
+             ORDER BY started_at ASC LIMIT ?3"
+        )?;
+        let rows = stmt
+            .query_map(params![from_ms, to_ms, limit as i64], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    pub fn agent_run_get(&self, id: &str) -> SqliteResult<Option<crate::agent_runs::AgentRun>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, kind, request_id, query, status, provider, model, prompt_count, prompt_tokens,
+                    completion_tokens, tool_calls, started_at, finished_at, error, answer
+             FROM agent_runs WHERE id = ?1"
+        )?;
+        let mut rows = stmt.query_map(params![id], |row| Ok((Self::row_to_agent_run_summary(row)?, row.get::<_, Option<String>>(14)?)))?;
+        let Some((summary, answer)) = rows.next().transpose()? else {
+            return Ok(None);
+        };
+        let mut stmt = conn.prepare(
+            "SELECT seq, timestamp, kind, data FROM agent_run_entries WHERE run_id = ?1 ORDER BY seq ASC"
+        )?;
+        let entries = stmt
+            .query_map(params![id], |row| {
+                Ok(crate::agent_runs::RunEntry {
+                    seq: row.get::<_, i64>(0)? as u64,
+                    timestamp: row.get(1)?,
+                    kind: match row.get::<_, String>(2)?.as_str() {
+                        "prompt" => crate::agent_runs::EntryKind::Prompt,
+                        _ => crate::agent_runs::EntryKind::Event,
+                    },
+                    data: serde_json::from_str(&row.get::<_, String>(3)?).unwrap_or(serde_json::Value::Null),
+                })
+            })?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(Some(crate::agent_runs::AgentRun { summary, answer, entries }))
+    }
+
+    pub fn agent_run_delete(&self, id: &str) -> SqliteResult<bool> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM agent_run_entries WHERE run_id = ?1", params![id])?;
+        Ok(conn.execute("DELETE FROM agent_runs WHERE id = ?1", params![id])? == 1)
+    }
+
+    fn row_to_agent_run_summary(row: &rusqlite::Row) -> SqliteResult<crate::agent_runs::AgentRunSummary> {
+        Ok(crate::agent_runs::AgentRunSummary {
+            id: row.get(0)?,
+            kind: row.get(1)?,
+            request_id: row.get(2)?,
+            query: row.get(3)?,
+            status: crate::agent_runs::RunStatus::parse(&row.get::<_, String>(4)?)
+                .unwrap_or(crate::agent_runs::RunStatus::Interrupted),
+            provider: row.get(5)?,
+            model: row.get(6)?,
+            prompt_count: row.get(7)?,
+            prompt_tokens: row.get::<_, i64>(8)? as u64,
+            completion_tokens: row.get::<_, i64>(9)? as u64,
+            tool_calls: row.get(10)?,
+            started_at: row.get(11)?,
+            finished_at: row.get(12)?,
+            error: row.get(13)?,
+        })
+    }
+
+    // ============================================================================
+    // USER SCRIPT METHODS
+    // ============================================================================
+
+    // Insert or replace a user script
+    pub fn userscript_save(&self, script: &crate::userscripts::UserScript) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        let to_json = |values: &Vec<String>| {
+            serde_json::to_string(values).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+        };
+        conn.execute(
+            "INSERT OR REPLACE INTO userscripts
+             (id, name, namespace, version, description, kind, matches_json, excludes_json, run_at, grants_json,
+              code, enabled, update_url, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+            params![
+                script.id,
+                script.name,
+                script.namespace,
+                script.version,
+                script.description,
+                if script.kind == crate::userscripts::ScriptKind::Style { "style" } else { "script" },
+                to_json(&script.matches)?,
+                to_json(&script.excludes)?,
+                script.run_at.as_str(),
+                to_json(&script.grants)?,
+                script.code,
+                script.enabled,
+                script.update_url,
+                script.created_at,
+                script.updated_at
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn userscript_get(&self, id: &str) -> SqliteResult<Option<crate::userscripts::UserScript>> {
+        let conn = self.conn.lock().unwrap();
+        match conn.query_row(
+            "SELECT id, name, namespace, version, description, kind, matches_json, excludes_json, run_at, grants_json,
+                    code, enabled, update_url, created_at, updated_at
+             FROM userscripts WHERE id = ?1",
+            params![id],
+            Self::row_to_userscript,
+        ) {
+            Ok(script) => Ok(Some(script)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    // Same name + namespace: the script an import or update replaces
+    pub fn userscript_find(&self, name: &str, namespace: Option<&str>) -> SqliteResult<Option<crate::userscripts::UserScript>> {
+        let conn = self.conn.lock().unwrap();
+        match conn.query_row(
+            "SELECT id, name, namespace, version, description, kind, matches_json, excludes_json, run_at, grants_json,
+                    code, enabled, update_url, created_at, updated_at
+             FROM userscripts WHERE name = ?1 AND namespace IS ?2",
+            params![name, namespace],
+            Self::row_to_userscript,
+        ) {
+            Ok(script) => Ok(Some(script)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    // All user scripts, oldest first
+    pub fn userscript_list(&self) -> SqliteResult<Vec<crate::userscripts::UserScript>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, name, namespace, version, description, kind, matches_json, excludes_json, run_at, grants_json,
+                    code, enabled, update_url, created_at, updated_at
+             FROM userscripts ORDER BY created_at ASC",
+        )?;
+        let scripts = stmt
+            .query_map([], Self::row_to_userscript)?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(scripts)
+    }
+
+    // Returns false when there is no such script
+    pub fn userscript_set_enabled(&self, id: &str, enabled: bool) -> SqliteResult<bool> {
+        let conn = self.conn.lock().unwrap();
+        let changed = conn.execute(
+            "UPDATE userscripts SET enabled = ?1, updated_at = ?2 WHERE id = ?3",
+            params![enabled, chrono::Utc::now().timestamp(), id],
+        )?;
+        Ok(changed > 0)
+    }
+
+    pub fn userscript_delete(&self, id: &str) -> SqliteResult<bool> {
+        let conn = self.conn.lock().unwrap();
+        Ok(conn.execute("DELETE FROM userscripts WHERE id = ?1", params![id])? > 0)
+    }
+
+    fn row_to_userscript(row: &rusqlite::Row) -> SqliteResult<crate::userscripts::UserScript> {
+        let from_json = |index: usize| -> SqliteResult<Vec<String>> {
+            let json: String = row.get(index)?;
+            serde_json::from_str(&json).map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(e))
+            })
+        };
+        let kind: String = row.get(5)?;
+        let run_at: String = row.get(8)?;
+        Ok(crate::userscripts::UserScript {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            namespace: row.get(2)?,
+            version: row.get(3)?,
+            description: row.get(4)?,
+            kind: if kind == "style" { crate::userscripts::ScriptKind::Style } else { crate::userscripts::ScriptKind::Script },
+            matches: from_json(6)?,
+            excludes: from_json(7)?,
+            run_at: crate::userscripts::RunAt::parse(&run_at).unwrap_or_default(),
+            grants: from_json(9)?,
+            code: row.get(10)?,
+            enabled: row.get(11)?,
+            update_url: row.get(12)?,
+            created_at: row.get(13)?,
+            updated_at: row.get(14)?,
+        })
+    }
+
+    // ============================================================================
+    // FILTER RULE METHODS
+    // ============================================================================
+
+    pub fn filter_rule_save(&self, rule: &crate::filter_rules::FilterRule) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO filter_rules (id, rule_type, filter, enabled, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![rule.id, rule.rule_type.as_str(), rule.filter, rule.enabled, rule.created_at],
+        )?;
+        Ok(())
+    }
+
+    // All custom rules, oldest first
+    pub fn filter_rule_list(&self) -> SqliteResult<Vec<crate::filter_rules::FilterRule>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, rule_type, filter, enabled, created_at FROM filter_rules ORDER BY created_at ASC, rowid ASC",
+        )?;
+        let rules = stmt
+            .query_map([], |row| {
+                let rule_type: String = row.get(1)?;
+                Ok(crate::filter_rules::FilterRule {
+                    id: row.get(0)?,
+                    rule_type: crate::filter_rules::RuleType::parse(&rule_type).map_err(|e| {
+                        rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, Box::new(e))
+                    })?,
+                    filter: row.get(2)?,
+                    enabled: row.get(3)?,
+                    created_at: row.get(4)?,
+                })
+            })?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(rules)
+    }
+
+    // Returns false when there is no such rule
+    pub fn filter_rule_set_enabled(&self, id: &str, enabled: bool) -> SqliteResult<bool> {
+        let conn = self.conn.lock().unwrap();
+        Ok(conn.execute("UPDATE filter_rules SET enabled = ?1 WHERE id = ?2", params![enabled, id])? > 0)
+    }
+
+    pub fn filter_rule_delete(&self, id: &str) -> SqliteResult<bool> {
+        let conn = self.conn.lock().unwrap();
+        Ok(conn.execute("DELETE FROM filter_rules WHERE id = ?1", params![id])? > 0)
+    }
+
+    // ============================================================================
+    // RECENT FILE METHODS
+    // ============================================================================
+
+    // Record a picked file and drop the oldest of its type beyond `keep`
+    pub fn recent_files_touch(&self, path: &str, doc_type: &str, opened_at: i64, keep: usize) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO recent_files (path, doc_type, opened_at) VALUES (?1, ?2, ?3)",
+            params![path, doc_type, opened_at],
+        )?;
+        conn.execute(
+            "DELETE FROM recent_files WHERE doc_type = ?1 AND path NOT IN
+             (SELECT path FROM recent_files WHERE doc_type = ?1 ORDER BY opened_at DESC LIMIT ?2)",
+            params![doc_type, keep as i64],
+        )?;
+        Ok(())
+    }
+
+    // (path, opened_at), newest first; all types when doc_type is None
+    pub fn recent_files_list(&self, doc_type: Option<&str>, limit: usize) -> SqliteResult<Vec<(String, i64)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT path, opened_at FROM recent_files WHERE ?1 IS NULL OR doc_type = ?1
+             ORDER BY opened_at DESC LIMIT ?2",
+        )?;
+        let files = stmt
+            .query_map(params![doc_type, limit as i64], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(files)
+    }
+
+    pub fn recent_files_clear(&self, doc_type: Option<&str>) -> SqliteResult<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM recent_files WHERE ?1 IS NULL OR doc_type = ?1", params![doc_type])
+    }
+
+    // ============================================================================
+    // CALENDAR METHODS
+    // ============================================================================
+
+    // Insert or update by uid, keeping the stored id and created_at; true when the event is new
+    pub fn calendar_event_save(&self, event: &crate::calendar::CalendarEvent) -> SqliteResult<bool> {
+        let conn = self.conn.lock().unwrap();
+        let exists = conn
+            .query_row("SELECT 1 FROM calendar_events WHERE uid = ?1", params![event.uid], |_| Ok(()))
+            .map(|_| true)
+            .or_else(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => Ok(false),
+                e => Err(e),
+            })?;
+        let exdates = serde_json::to_string(&event.exdates).unwrap_or_else(|_| "[]".to_string());
+        conn.execute(
+            "INSERT INTO calendar_events (id, uid, title, starts_at, ends_at, all_day, location, description, rrule, exdates, source, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+             ON CONFLICT(uid) DO UPDATE SET
+                title = excluded.title, starts_at = excluded.starts_at, ends_at = excluded.ends_at,
+                all_day = excluded.all_day, location = excluded.location, description = excluded.description,
+                rrule = excluded.rrule, exdates = excluded.exdates, source = excluded.source",
+            params![
+                event.id,
+                event.uid,
+                event.title,
+                event.starts_at,
+                event.ends_at,
+                event.all_day as i32,
+                event.location,
+                event.description,
+                event.rrule,
+                exdates,
+                event.source,
+                event.created_at,
+            ],
+        )?;
+        Ok(!exists)
+    }
+
+    // Events overlapping [from, to), plus every recurring event that started before `to`
+    // (calendar.rs expands those into occurrences)
+    pub fn calendar_events_between(&self, from: i64, to: i64) -> SqliteResult<Vec<crate::calendar::CalendarEvent>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, uid, title, starts_at, ends_at, all_day, location, description, rrule, exdates, source, created_at
+             FROM calendar_events
+             WHERE (rrule IS NULL AND ends_at > ?1 AND starts_at < ?2) OR (rrule IS NOT NULL AND starts_at < ?2)
+             ORDER BY starts_at",
+        )?;
+        let events = stmt
+            .query_map(params![from, to], |row| {
+                Ok(crate::calendar::CalendarEvent {
+                    id: row.get(0)?,
+                    uid: row.get(1)?,
+                    title: row.get(2)?,
+                    starts_at: row.get(3)?,
+                    ends_at: row.get(4)?,
+                    all_day: row.get::<_, i32>(5)? != 0,
+                    location: row.get(6)?,
+                    description: row.get(7)?,
+                    rrule: row.get(8)?,
+                    exdates: row
+                        .get::<_, Option<String>>(9)?
+                        .and_then(|json| serde_json::from_str(&json).ok())
+                        .unwrap_or_default(),
+                    source: row.get(10)?,
+                    created_at: row.get(11)?,
+                })
+            })?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(events)
+    }
+
+    pub fn calendar_event_delete(&self, id: &str) -> SqliteResult<bool> {
+        let conn = self.conn.lock().unwrap();
+        Ok(conn.execute("DELETE FROM calendar_events WHERE id = ?1", params![id])? > 0)
+    }
+
+    // ============================================================================
+    // DIAGNOSTICS METHODS
+    // ============================================================================
+
+    // SQLite's integrity check ("ok" when healthy), plus page count and page size
+    pub fn integrity_check(&self) -> SqliteResult<(Vec<String>, i64, i64)> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("PRAGMA integrity_check(100)")?;
+        let results = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        let page_count: i64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+        let page_size: i64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+        Ok((results, page_count, page_size))
+    }
+
+    // Row counts per table (no contents) for bug reports
+    pub fn table_counts(&self) -> SqliteResult<Vec<(String, i64)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+        )?;
+        let tables = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        let mut counts = Vec::new();
+        for table in tables {
+            // Virtual/shadow tables can refuse a plain count; skip them
+            if let Ok(count) = conn.query_row(&format!("SELECT COUNT(*) FROM \"{}\"", table.replace('"', "\"\"")), [], |row| row.get(0)) {
+                counts.push((table, count));
+            }
+        }
+        Ok(counts)
+    }
+
+    fn row_to_archive(row: &rusqlite::Row) -> SqliteResult<crate::archive::ArchiveEntry> {
+        Ok(crate::archive::ArchiveEntry {
+            id: row.get(0)?,
+            url: row.get(1)?,
+            title: row.get(2)?,
+            tab_id: row.get(3)?,
+            file_path: row.get(4)?,
+            size_bytes: row.get(5)?,
+            resource_count: row.get(6)?,
+            created_at: row.get(7)?,
+        })
+    }
+}
+
+// Escape LIKE wildcards so user input matches literally (used with ESCAPE '\')
+fn escape_like(term: &str) -> String {
+    term.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
 }
@@ -0,0 +1,240 @@
+// Diagnostics - Single zip bundle for bug reports
+// Version/OS info, recent logs, crash log, service statuses, DB integrity, and anonymized settings
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+
+use crate::db::Database;
+use crate::hardware::SystemCapabilities;
+use crate::services::supervisor::ServiceStatus;
+use crate::stability::SafeModeStatus;
+
+// Only the newest few log files, and only their tails, go into a bundle
+const MAX_LOG_FILES: usize = 3;
+const MAX_LOG_BYTES: usize = 2 * 1024 * 1024;
+// Setting keys whose values are replaced outright
+const SECRET_KEYS: &[&str] = &["key", "token", "secret", "password", "email", "proxy", "cookie", "auth"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemReport {
+    pub app_version: String,
+    pub os: String,
+    pub os_version: Option<String>,
+    pub arch: String,
+    pub generated_at: i64,
+    pub capabilities: SystemCapabilities,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseReport {
+    pub ok: bool,
+    pub integrity: Vec<String>,
+    pub size_bytes: i64,
+    pub table_counts: BTreeMap<String, i64>,
+    pub error: Option<String>,
+}
+
+// Everything the bundle needs that lives in managed state
+pub struct DiagnosticsInput {
+    pub app_version: String,
+    pub settings: serde_json::Value,
+    pub services: Vec<ServiceStatus>,
+    pub safe_mode: SafeModeStatus,
+    pub log_dir: Option<PathBuf>,
+    pub crash_log: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsExport {
+    pub path: String,
+    pub size_bytes: u64,
+    pub files: Vec<String>,
+}
+
+pub fn database_report(db: &Database) -> DatabaseReport {
+    let counts: BTreeMap<String, i64> = db.table_counts().unwrap_or_default().into_iter().collect();
+    match db.integrity_check() {
+        Ok((integrity, page_count, page_size)) => DatabaseReport {
+            ok: integrity.len() == 1 && integrity[0] == "ok",
+            integrity,
+            size_bytes: page_count * page_size,
+            table_counts: counts,
+            error: None,
+        },
+        Err(e) => DatabaseReport {
+            ok: false,
+            integrity: Vec::new(),
+            size_bytes: 0,
+            table_counts: counts,
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+// Write regen-diagnostics-<timestamp>.zip into `out_dir`
+pub fn export(out_dir: &Path, input: DiagnosticsInput, database: DatabaseReport) -> Result<DiagnosticsExport, DiagnosticsError> {
+    std::fs::create_dir_all(out_dir).map_err(|e| DiagnosticsError::Io(e.to_string()))?;
+    let path = out_dir.join(format!(
+        "regen-diagnostics-{}.zip",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    let anonymizer = Anonymizer::new();
+
+    let system = SystemReport {
+        app_version: input.app_version,
+        os: std::env::consts::OS.to_string(),
+        os_version: os_version(),
+        arch: std::env::consts::ARCH.to_string(),
+        generated_at: chrono::Utc::now().timestamp(),
+        capabilities: crate::hardware::capabilities().clone(),
+    };
+
+    let mut entries: Vec<(String, Vec<u8>)> = vec![
+        ("system.json".to_string(), to_json(&system)?),
+        ("services.json".to_string(), anonymizer.apply(&String::from_utf8_lossy(&to_json(&input.services)?)).into_bytes()),
+        ("database.json".to_string(), to_json(&database)?),
+        ("safe_mode.json".to_string(), to_json(&input.safe_mode)?),
+        ("settings.json".to_string(), to_json(&redact_settings(input.settings))?),
+    ];
+
+    if let Some(log_dir) = &input.log_dir {
+        let files = crate::logging::log_files(log_dir).unwrap_or_default();
+        for file in files.iter().rev().take(MAX_LOG_FILES) {
+            if let Some(contents) = read_tail(file, MAX_LOG_BYTES) {
+                let name = file.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                entries.push((format!("logs/{}", name), anonymizer.apply(&contents).into_bytes()));
+            }
+        }
+    }
+    if let Some(contents) = input.crash_log.as_deref().and_then(|p| read_tail(p, MAX_LOG_BYTES)) {
+        entries.push(("crash.log".to_string(), anonymizer.apply(&contents).into_bytes()));
+    }
+
+    let file = std::fs::File::create(&path).map_err(|e| DiagnosticsError::Io(e.to_string()))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for (name, bytes) in &entries {
+        zip.start_file(name.as_str(), options).map_err(|e| DiagnosticsError::Zip(e.to_string()))?;
+        zip.write_all(bytes).map_err(|e| DiagnosticsError::Io(e.to_string()))?;
+    }
+    zip.finish().map_err(|e| DiagnosticsError::Zip(e.to_string()))?;
+
+    let size_bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    tracing::info!(target: "app", "Diagnostics bundle written ({} files, {} KB)", entries.len(), size_bytes / 1024);
+    Ok(DiagnosticsExport {
+        path: path.to_string_lossy().to_string(),
+        size_bytes,
+        files: entries.into_iter().map(|(name, _)| name).collect(),
+    })
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<Vec<u8>, DiagnosticsError> {
+    serde_json::to_vec_pretty(value).map_err(|e| DiagnosticsError::Serialize(e.to_string()))
+}
+
+// Last `max_bytes` of a text file, starting at a line boundary
+fn read_tail(path: &Path, max_bytes: usize) -> Option<String> {
+    let bytes = std::fs::read(path).ok()?;
+    if bytes.len() <= max_bytes {
+        return Some(String::from_utf8_lossy(&bytes).to_string());
+    }
+    let tail = &bytes[bytes.len() - max_bytes..];
+    let start = tail.iter().position(|b| *b == b'\n').map(|i| i + 1).unwrap_or(0);
+    Some(String::from_utf8_lossy(&tail[start..]).to_string())
+}
+
+// Drop values of secret-looking keys; other values are kept so settings stay useful for triage
+fn redact_settings(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    let lower = key.to_lowercase();
+                    if SECRET_KEYS.iter().any(|s| lower.contains(s)) && !value.is_boolean() {
+                        (key, serde_json::Value::String("<redacted>".to_string()))
+                    } else {
+                        (key, redact_settings(value))
+                    }
+                })
+                .collect(),
+        ),
+        serde_json::Value::Array(items) => serde_json::Value::Array(items.into_iter().map(redact_settings).collect()),
+        serde_json::Value::String(s) => serde_json::Value::String(Anonymizer::new().apply(&s)),
+        other => other,
+    }
+}
+
+// Replaces the home directory with ~ in free text (log lines and statuses carry file paths,
+// which include the user name)
+struct Anonymizer {
+    home: Option<String>,
+}
+
+impl Anonymizer {
+    fn new() -> Self {
+        let home = std::env::var("HOME")
+            .or_else(|_| std::env::var("USERPROFILE"))
+            .ok()
+            .filter(|h| h.len() > 1);
+        Self { home }
+    }
+
+    fn apply(&self, text: &str) -> String {
+        match &self.home {
+            // JSON escapes Windows separators, so replace that spelling too
+            Some(home) => text.replace(home.as_str(), "~").replace(&home.replace('\\', "\\\\"), "~"),
+            None => text.to_string(),
+        }
+    }
+}
+
+fn os_version() -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        let release = std::fs::read_to_string("/etc/os-release").ok()?;
+        release
+            .lines()
+            .find_map(|l| l.strip_prefix("PRETTY_NAME="))
+            .map(|v| v.trim_matches('"').to_string())
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let output = std::process::Command::new("sw_vers").arg("-productVersion").output().ok()?;
+        Some(format!("macOS {}", String::from_utf8_lossy(&output.stdout).trim()))
+    }
+    #[cfg(target_os = "windows")]
+    {
+        let output = std::process::Command::new("cmd").args(["/C", "ver"]).output().ok()?;
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    {
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum DiagnosticsError {
+    Io(String),
+    Zip(String),
+    Serialize(String),
+}
+
+impl std::fmt::Display for DiagnosticsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiagnosticsError::Io(msg) => write!(f, "Diagnostics I/O error: {}", msg),
+            DiagnosticsError::Zip(msg) => write!(f, "Diagnostics zip error: {}", msg),
+            DiagnosticsError::Serialize(msg) => write!(f, "Diagnostics serialization error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for DiagnosticsError {}
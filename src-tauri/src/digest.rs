@@ -0,0 +1,368 @@
+// Digest - Markdown digests of research activity over a period (notes, highlights, key pages, agent answers)
+// Composed by the model from local stores only, saved as a note and a research session; optionally weekly
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+
+use crate::ai::AIService;
+use crate::db::Database;
+use crate::local_index::LocalIndex;
+use crate::notes::Note;
+use crate::privacy::PrivacyEnforcer;
+use crate::research::{ResearchSession, SessionHighlight, SessionMetadata, SessionSummary, SessionTab};
+
+// Notes carrying this tag are digests themselves and are left out of later digests
+pub const DIGEST_TAG: &str = "digest";
+const MAX_NOTES: usize = 40;
+const MAX_HIGHLIGHTS: usize = 60;
+const MAX_PAGES: usize = 15;
+const MAX_RUNS: usize = 15;
+const NOTE_CHARS: usize = 600;
+const HIGHLIGHT_CHARS: usize = 300;
+const ANSWER_CHARS: usize = 800;
+// Whole activity block sent to the model; later sections are cut first
+const ACTIVITY_CHARS: usize = 24_000;
+const MAX_RANGE_SECS: i64 = 92 * 86_400;
+// Weekly schedule: checked hourly, and a digest from the last few days counts as this week's
+const SCHEDULE_TICK: Duration = Duration::from_secs(60 * 60);
+const STARTUP_DELAY: Duration = Duration::from_secs(120);
+const RECENT_DIGEST_SECS: i64 = 6 * 86_400;
+
+// Either a preset period ending now or an explicit span in unix seconds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(untagged)]
+pub enum DigestRange {
+    Preset(String),                    // "day" | "week" | "month"
+    Span { from: i64, to: i64 },
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct DigestCounts {
+    pub notes: usize,
+    pub highlights: usize,
+    pub pages: usize,
+    pub agent_runs: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct Digest {
+    pub title: String,
+    pub markdown: String,
+    pub from: i64,                     // Unix seconds
+    pub to: i64,
+    pub counts: DigestCounts,
+    pub note_id: Option<String>,       // None when privacy settings kept it off disk
+    pub session_id: Option<String>,
+    pub elapsed_ms: u64,
+}
+
+struct Period {
+    from: i64,
+    to: i64,
+    title: String,
+}
+
+struct Activity {
+    text: String,
+    counts: DigestCounts,
+    pages: Vec<(String, String)>,      // url, title
+    highlights: Vec<crate::highlights::Highlight>,
+}
+
+impl DigestRange {
+    fn resolve(&self, now: i64) -> Result<Period, DigestError> {
+        let (from, to, kind) = match self {
+            DigestRange::Preset(name) => {
+                let (days, kind) = match name.trim().to_lowercase().as_str() {
+                    "day" | "daily" => (1, "Daily digest"),
+                    "week" | "weekly" => (7, "Weekly digest"),
+                    "month" | "monthly" => (30, "Monthly digest"),
+                    other => return Err(DigestError::InvalidRange(format!("unknown period '{}'", other))),
+                };
+                (now - days * 86_400, now, kind)
+            }
+            DigestRange::Span { from, to } => {
+                if from >= to {
+                    return Err(DigestError::InvalidRange("start must be before end".to_string()));
+                }
+                if to - from > MAX_RANGE_SECS {
+                    return Err(DigestError::InvalidRange(format!("at most {} days", MAX_RANGE_SECS / 86_400)));
+                }
+                (*from, (*to).min(now), "Digest")
+            }
+        };
+        Ok(Period { from, to, title: format!("{}: {}", kind, label(from, to)) })
+    }
+}
+
+// "Oct 10 – Oct 17, 2026", or a single day when the span fits in one
+fn label(from: i64, to: i64) -> String {
+    use chrono::{Local, TimeZone};
+    let day = |ts: i64| Local.timestamp_opt(ts, 0).single().unwrap_or_default();
+    let (start, end) = (day(from), day(to));
+    if start.date_naive() == end.date_naive() || to - from <= 86_400 {
+        end.format("%b %-d, %Y").to_string()
+    } else if start.format("%Y").to_string() == end.format("%Y").to_string() {
+        format!("{} – {}", start.format("%b %-d"), end.format("%b %-d, %Y"))
+    } else {
+        format!("{} – {}", start.format("%b %-d, %Y"), end.format("%b %-d, %Y"))
+    }
+}
+
+// Compose a digest for the range and, unless privacy settings forbid disk writes, save it
+pub async fn generate(app: &tauri::AppHandle, range: &DigestRange) -> Result<Digest, DigestError> {
+    let started = Instant::now();
+    let period = range.resolve(chrono::Utc::now().timestamp())?;
+    let db = app.state::<Database>().inner().clone();
+
+    let (from, to) = (period.from, period.to);
+    let activity = tauri::async_runtime::spawn_blocking(move || gather(&db, from, to))
+        .await
+        .map_err(|e| DigestError::Storage(e.to_string()))??;
+    if activity.text.is_empty() {
+        return Err(DigestError::NoActivity);
+    }
+
+    let prompt = crate::prompts::render(
+        "digest",
+        None,
+        None,
+        &[("period", &period.title), ("activity", &activity.text)],
+    );
+    let ai = app.state::<AIService>();
+    let body = ai
+        .complete_task("digest", &prompt)
+        .await
+        .map_err(|e| DigestError::AIError(e.to_string()))?;
+    let markdown = format!("# {}\n\n{}", period.title, body.trim());
+
+    let mut digest = Digest {
+        title: period.title,
+        markdown,
+        from: period.from,
+        to: period.to,
+        counts: activity.counts.clone(),
+        note_id: None,
+        session_id: None,
+        elapsed_ms: 0,
+    };
+    if app.state::<Mutex<PrivacyEnforcer>>().lock().unwrap().can_write_to_disk() {
+        save(app, &mut digest, activity)?;
+    }
+    digest.elapsed_ms = started.elapsed().as_millis() as u64;
+    tracing::info!(
+        target: "ai",
+        "Digest: {} ({} notes, {} highlights, {} pages, {} runs) in {}ms",
+        digest.title,
+        digest.counts.notes,
+        digest.counts.highlights,
+        digest.counts.pages,
+        digest.counts.agent_runs,
+        digest.elapsed_ms
+    );
+    Ok(digest)
+}
+
+fn gather(db: &Database, from: i64, to: i64) -> Result<Activity, DigestError> {
+    let notes: Vec<Note> = db
+        .notes_between(from, to, MAX_NOTES * 2)
+        .map_err(storage)?
+        .into_iter()
+        .filter(|n| !n.tags.iter().any(|t| t == DIGEST_TAG))
+        .take(MAX_NOTES)
+        .collect();
+    let highlights = db.highlights_between(from, to, MAX_HIGHLIGHTS).map_err(storage)?;
+    let pages: Vec<(String, String, i64)> = db
+        .history_between(from, to, MAX_PAGES * 3)
+        .map_err(storage)?
+        .into_iter()
+        .filter(|(url, _, _)| url.starts_with("http://") || url.starts_with("https://"))
+        .take(MAX_PAGES)
+        .collect();
+    let runs = db.agent_runs_answered_between(from * 1000, to * 1000, MAX_RUNS).map_err(storage)?;
+
+    let mut sections = Vec::new();
+    if !notes.is_empty() {
+        let items = notes.iter().map(|n| {
+            let title = n.title.as_deref().unwrap_or("Untitled note");
+            let source = n.url.as_deref().map(|u| format!(" ({})", u)).unwrap_or_default();
+            format!("- {}{}: {}", title, source, one_line(&n.content, NOTE_CHARS))
+        });
+        sections.push(section("Notes", items));
+    }
+    if !highlights.is_empty() {
+        let items = highlights.iter().map(|h| {
+            let comment = h.note.as_deref().map(|c| format!(" (comment: {})", one_line(c, HIGHLIGHT_CHARS))).unwrap_or_default();
+            format!("- \"{}\" from {}{}", one_line(&h.text, HIGHLIGHT_CHARS), h.url, comment)
+        });
+        sections.push(section("Highlights", items));
+    }
+    if !pages.is_empty() {
+        let items = pages.iter().map(|(url, title, visits)| {
+            let title = if title.trim().is_empty() { url.as_str() } else { title.as_str() };
+            format!("- {} ({}), {} visit{}", title, url, visits, if *visits == 1 { "" } else { "s" })
+        });
+        sections.push(section("Most visited pages", items));
+    }
+    if !runs.is_empty() {
+        let items = runs.iter().map(|(kind, query, answer)| {
+            format!("- {} \"{}\": {}", if kind == "research" { "Research on" } else { "Asked" }, one_line(query, HIGHLIGHT_CHARS), one_line(answer, ANSWER_CHARS))
+        });
+        sections.push(section("Assistant answers", items));
+    }
+
+    let mut text = sections.join("\n\n");
+    if let Some((cut, _)) = text.char_indices().nth(ACTIVITY_CHARS) {
+        text.truncate(cut);
+    }
+
+    Ok(Activity {
+        text,
+        counts: DigestCounts { notes: notes.len(), highlights: highlights.len(), pages: pages.len(), agent_runs: runs.len() },
+        pages: pages.into_iter().map(|(url, title, _)| (url, title)).collect(),
+        highlights,
+    })
+}
+
+fn section(heading: &str, items: impl Iterator<Item = String>) -> String {
+    format!("{}:\n{}", heading, items.collect::<Vec<_>>().join("\n"))
+}
+
+// Whitespace collapsed onto one line and cut to `max` characters
+fn one_line(text: &str, max: usize) -> String {
+    let text = crate::extractor::collapse_whitespace(text);
+    match text.char_indices().nth(max) {
+        Some((i, _)) => format!("{}…", text[..i].trim_end()),
+        None => text,
+    }
+}
+
+fn storage(e: rusqlite::Error) -> DigestError {
+    DigestError::Storage(e.to_string())
+}
+
+// The digest becomes a tagged note and a research session holding its key pages and highlights
+fn save(app: &tauri::AppHandle, digest: &mut Digest, activity: Activity) -> Result<(), DigestError> {
+    let db = app.state::<Database>();
+    let local_index = app.state::<LocalIndex>();
+
+    let note = Note::new(digest.markdown.clone(), Some(digest.title.clone()), None, vec![DIGEST_TAG.to_string()]);
+    db.save_note(&note).map_err(storage)?;
+    local_index.note_saved(&note);
+
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let sources: Vec<String> = activity.pages.iter().map(|(url, _)| url.clone()).collect();
+    let session = ResearchSession {
+        id: uuid::Uuid::new_v4().to_string(),
+        title: digest.title.clone(),
+        created_at: now_ms,
+        updated_at: now_ms,
+        tabs: activity
+            .pages
+            .into_iter()
+            .map(|(url, title)| SessionTab { id: uuid::Uuid::new_v4().to_string(), url, title, favicon: None, snapshot: None })
+            .collect(),
+        notes: Vec::new(),
+        summaries: vec![SessionSummary {
+            id: uuid::Uuid::new_v4().to_string(),
+            url: String::new(),
+            summary: digest.markdown.clone(),
+            keywords: Vec::new(),
+            length: "long".to_string(),
+            timestamp: now_ms,
+        }],
+        highlights: activity
+            .highlights
+            .into_iter()
+            .map(|h| SessionHighlight { id: h.id, url: h.url, text: h.text, note: h.note, created_at: h.created_at * 1000 })
+            .collect(),
+        metadata: SessionMetadata { query: None, keywords: vec![DIGEST_TAG.to_string()], sources },
+    };
+    db.save_research_session(&session).map_err(storage)?;
+    local_index.session_saved(&session);
+
+    digest.note_id = Some(note.id);
+    digest.session_id = Some(session.id);
+    Ok(())
+}
+
+// Hourly check for the weekly digest (digest.weekly); runs once on the configured weekday
+pub fn spawn_weekly(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(STARTUP_DELAY).await;
+        loop {
+            if weekly_due(&app) {
+                match generate(&app, &DigestRange::Preset("week".to_string())).await {
+                    Ok(digest) => {
+                        let _ = app.emit("digest:ready", &digest);
+                        crate::notifications::post(&app, crate::notifications::NotificationRequest {
+                            title: "Weekly digest ready".to_string(),
+                            body: digest.title.clone(),
+                            actions: vec![crate::notifications::NotificationAction {
+                                id: "open".to_string(),
+                                title: "Open".to_string(),
+                            }],
+                            kind: crate::notifications::NotificationKind::Schedule,
+                            payload: Some(serde_json::json!({ "noteId": digest.note_id, "sessionId": digest.session_id })),
+                            ..Default::default()
+                        });
+                    }
+                    Err(DigestError::NoActivity) => tracing::debug!(target: "ai", "Digest: Nothing to digest this week"),
+                    Err(e) => tracing::warn!(target: "ai", "Digest: Weekly digest failed: {}", e),
+                }
+            }
+            tokio::time::sleep(SCHEDULE_TICK).await;
+        }
+    });
+}
+
+fn weekly_due(app: &tauri::AppHandle) -> bool {
+    use chrono::Datelike;
+    let config = crate::config::current().digest;
+    if !config.weekly || crate::modes::background_paused() {
+        return false;
+    }
+    if config.weekday.parse::<chrono::Weekday>().ok() != Some(chrono::Local::now().weekday()) {
+        return false;
+    }
+    // Nothing can be saved, so a scheduled digest would be lost
+    if !app.state::<Mutex<PrivacyEnforcer>>().lock().unwrap().can_write_to_disk() {
+        return false;
+    }
+    let now = chrono::Utc::now().timestamp();
+    match app.state::<Database>().list_notes(Some(DIGEST_TAG), 1) {
+        Ok(latest) => latest.first().is_none_or(|n| now - n.created_at > RECENT_DIGEST_SECS),
+        Err(e) => {
+            tracing::warn!(target: "ai", "Digest: Could not check for a recent digest: {}", e);
+            false
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum DigestError {
+    InvalidRange(String),
+    NoActivity,
+    Storage(String),
+    AIError(String),
+}
+
+impl std::fmt::Display for DigestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DigestError::InvalidRange(msg) => write!(f, "Invalid digest range: {}", msg),
+            DigestError::NoActivity => write!(f, "No notes, highlights, visits, or assistant answers in this period"),
+            DigestError::Storage(msg) => write!(f, "{}", msg),
+            DigestError::AIError(msg) => write!(f, "AI error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for DigestError {}
@@ -0,0 +1,726 @@
+// Documents - Text extraction for opened PDF/DOCX/XLSX/EPUB files and Q&A over them
+// Extracted text is chunked by page, sheet, or chapter, embedded into SQLite under a document id, and answered from with references
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{Cursor, Read};
+use std::path::Path;
+use tauri::Manager;
+
+use crate::ai::AIService;
+use crate::chunker::{self, ChunkOptions};
+use crate::context_window::{ContextPacker, ContextReport, Fit, Priority};
+use crate::db::Database;
+use crate::ebook::{self, Book, BookMetadata};
+use crate::embeddings;
+use crate::local_index::{IndexDocument, LocalIndex};
+use crate::markdown::MarkdownEvent;
+use crate::tables::{self, Table};
+
+const MAX_FILE_BYTES: u64 = 50 * 1024 * 1024;
+// Paragraphs per located section when a format has no pages, sheets, or chapters; retrieval chunks
+// (sized by chunker::ChunkOptions::retrieval) never span two sections
+const SECTION_CHARS: usize = 1200;
+const MAX_CHUNKS: usize = 2000;
+const TOP_K: usize = 6;
+const MAX_TOP_K: usize = 12;
+// Chunks below this similarity are left out unless nothing else matched
+const MIN_SIMILARITY: f32 = 0.2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum DocumentKind {
+    Pdf,
+    Docx,
+    Xlsx,
+    Epub,
+    Text,
+}
+
+impl DocumentKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DocumentKind::Pdf => "pdf",
+            DocumentKind::Docx => "docx",
+            DocumentKind::Xlsx => "xlsx",
+            DocumentKind::Epub => "epub",
+            DocumentKind::Text => "text",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        [DocumentKind::Pdf, DocumentKind::Docx, DocumentKind::Xlsx, DocumentKind::Epub, DocumentKind::Text]
+            .into_iter()
+            .find(|k| k.as_str() == value)
+    }
+
+    fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "pdf" => Some(DocumentKind::Pdf),
+            "docx" => Some(DocumentKind::Docx),
+            "xlsx" | "xlsm" | "xls" | "ods" => Some(DocumentKind::Xlsx),
+            "epub" => Some(DocumentKind::Epub),
+            "txt" | "md" | "csv" | "json" | "log" => Some(DocumentKind::Text),
+            _ => None,
+        }
+    }
+}
+
+// Where a piece of text came from; how answers cite it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,             // 1-based (PDF)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sheet: Option<String>,         // Spreadsheets
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chapter: Option<u32>,          // 1-based (books)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paragraph: Option<u32>,        // 1-based, first paragraph of the chunk (DOCX/text)
+}
+
+impl Location {
+    pub fn label(&self) -> String {
+        match (&self.page, &self.sheet, &self.chapter, &self.paragraph) {
+            (Some(page), ..) => format!("page {}", page),
+            (_, Some(sheet), ..) => format!("sheet \"{}\"", sheet),
+            (_, _, Some(chapter), _) => format!("chapter {}", chapter),
+            (.., Some(paragraph)) => format!("paragraph {}", paragraph),
+            _ => "document".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentMetadata {
+    pub path: String,
+    pub name: String,
+    pub title: String,                 // Book title for EPUBs, otherwise the file name without extension
+    pub kind: DocumentKind,
+    pub size_bytes: u64,
+    pub pages: Option<u32>,
+    pub sheets: Vec<String>,
+    pub word_count: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ProcessOptions {
+    pub extract_text: Option<bool>,    // Return the full text (default true)
+    pub extract_tables: Option<bool>,  // Detect tables: PDF layout, one per spreadsheet sheet (default true)
+    pub summarize: Option<bool>,       // Map-reduce summary of the whole document (default false)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessedDocument {
+    pub doc_id: String,                // Content hash; the same file always gets the same id
+    pub metadata: DocumentMetadata,
+    pub text: Option<String>,
+    pub tables: Vec<Table>,
+    pub summary: Option<String>,
+    pub indexed: bool,                 // Chunks stored for document_ask (not in Private/Ghost mode)
+    pub chunks: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ChapterInfo {
+    pub index: u32,                    // 1-based; matches the chapter in document_ask references
+    pub title: String,
+    pub word_count: usize,
+    pub excerpt: String,               // Opening of the chapter
+    pub summary: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessedBook {
+    pub doc_id: String,
+    pub metadata: DocumentMetadata,
+    pub book: BookMetadata,
+    pub chapters: Vec<ChapterInfo>,
+    pub indexed: bool,
+    pub chunks: usize,
+}
+
+// One retrievable piece of a document
+#[derive(Debug, Clone)]
+pub struct DocumentChunk {
+    pub seq: usize,
+    pub location: Location,
+    pub text: String,
+    pub vector: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredDocument {
+    pub doc_id: String,
+    pub path: String,
+    pub name: String,
+    pub title: String,
+    pub kind: DocumentKind,
+    pub chunks: usize,
+    pub model: String,                 // Embedding model the chunks were indexed with
+    pub indexed_at: i64,               // Unix seconds
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentReference {
+    pub id: usize,                     // How the answer cites it: [1], [2], ...
+    #[serde(flatten)]
+    pub location: Location,
+    pub label: String,                 // "page 3", "sheet \"Q2\"", ...
+    pub snippet: String,
+    pub similarity: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentAnswer {
+    pub doc_id: String,
+    pub question: String,
+    pub answer: String,
+    pub references: Vec<DocumentReference>,
+    pub elapsed_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case", rename_all_fields = "camelCase")]
+pub enum DocumentEvent {
+    ContextTrimmed { report: ContextReport },
+    References { references: Vec<DocumentReference> },
+    AnswerToken { token: String, events: Vec<MarkdownEvent> },
+    Done { answer: DocumentAnswer },
+    Failed { error: String },
+}
+
+// Text of one page, sheet, chapter, or paragraph run
+struct Section {
+    location: Location,
+    text: String,
+}
+
+// Extract, optionally summarize, and (when `index` is set) chunk and embed a local file
+pub async fn process(app: &tauri::AppHandle, path: &str, options: &ProcessOptions, index: bool) -> Result<ProcessedDocument, DocumentError> {
+    let (kind, size_bytes, bytes) = open(path).await?;
+    let doc_id = document_id(&bytes);
+
+    // Parsing is CPU-bound and can take a while for large PDFs
+    let with_tables = options.extract_tables.unwrap_or(true);
+    let extracted = tauri::async_runtime::spawn_blocking(move || extract(kind, &bytes, with_tables))
+        .await
+        .map_err(|e| DocumentError::Parse(e.to_string()))??;
+    let sections = extracted.sections;
+    let text = sections.iter().map(|s| s.text.as_str()).collect::<Vec<_>>().join("\n\n");
+    if text.trim().is_empty() {
+        return Err(DocumentError::NoText);
+    }
+
+    let mut metadata = metadata(path, kind, size_bytes, &text);
+    if let Some(title) = extracted.title.filter(|t| !t.is_empty()) {
+        metadata.title = title;
+    }
+    metadata.pages = (kind == DocumentKind::Pdf).then_some(sections.len() as u32);
+    metadata.sheets = sections.iter().filter_map(|s| s.location.sheet.clone()).collect();
+
+    let chunks = if index {
+        Some(index_document(app, &doc_id, &metadata, &sections).await?)
+    } else {
+        None
+    };
+
+    let summary = if options.summarize.unwrap_or(false) {
+        summarize_text(app, &text, &metadata.title, None).await
+    } else {
+        None
+    };
+
+    Ok(ProcessedDocument {
+        doc_id,
+        text: options.extract_text.unwrap_or(true).then_some(text),
+        tables: if with_tables { extracted.tables } else { Vec::new() },
+        summary,
+        indexed: chunks.is_some(),
+        chunks: chunks.unwrap_or(0),
+        metadata,
+    })
+}
+
+// Chapters and metadata of an EPUB or plain-text book, indexed chapter by chapter; `summarize` summarizes each chapter
+pub async fn process_book(app: &tauri::AppHandle, path: &str, summarize: bool, index: bool) -> Result<ProcessedBook, DocumentError> {
+    let (kind, size_bytes, bytes) = open(path).await?;
+    if !matches!(kind, DocumentKind::Epub | DocumentKind::Text) {
+        return Err(DocumentError::Unsupported(path.to_string()));
+    }
+    let doc_id = document_id(&bytes);
+    let fallback_title = file_title(path);
+    let book = tauri::async_runtime::spawn_blocking(move || read_book(kind, &bytes, &fallback_title))
+        .await
+        .map_err(|e| DocumentError::Parse(e.to_string()))??;
+
+    let sections = book_sections(&book);
+    let text = sections.iter().map(|s| s.text.as_str()).collect::<Vec<_>>().join("\n\n");
+    let mut metadata = metadata(path, kind, size_bytes, &text);
+    if !book.metadata.title.is_empty() {
+        metadata.title = book.metadata.title.clone();
+    }
+
+    let chunks = if index {
+        Some(index_document(app, &doc_id, &metadata, &sections).await?)
+    } else {
+        None
+    };
+
+    let mut chapters = Vec::with_capacity(book.chapters.len());
+    for (i, chapter) in book.chapters.iter().enumerate() {
+        // One chapter at a time; the summarizer already spreads a long chapter over parallel calls
+        let summary = if summarize {
+            let title = format!("{}: {}", metadata.title, chapter.title);
+            summarize_text(app, &chapter.text, &title, book.metadata.language.clone()).await
+        } else {
+            None
+        };
+        chapters.push(ChapterInfo {
+            index: i as u32 + 1,
+            title: chapter.title.clone(),
+            word_count: chapter.text.split_whitespace().count(),
+            excerpt: chapter.text.chars().take(300).collect(),
+            summary,
+        });
+    }
+
+    Ok(ProcessedBook {
+        doc_id,
+        metadata,
+        book: book.metadata,
+        chapters,
+        indexed: chunks.is_some(),
+        chunks: chunks.unwrap_or(0),
+    })
+}
+
+async fn open(path: &str) -> Result<(DocumentKind, u64, Vec<u8>), DocumentError> {
+    let file = Path::new(path);
+    let kind = DocumentKind::from_path(file).ok_or_else(|| DocumentError::Unsupported(path.to_string()))?;
+    let size_bytes = std::fs::metadata(file).map_err(|e| DocumentError::Io(e.to_string()))?.len();
+    if size_bytes > MAX_FILE_BYTES {
+        return Err(DocumentError::TooLarge(size_bytes));
+    }
+    let bytes = tokio::fs::read(file).await.map_err(|e| DocumentError::Io(e.to_string()))?;
+    Ok((kind, size_bytes, bytes))
+}
+
+fn metadata(path: &str, kind: DocumentKind, size_bytes: u64, text: &str) -> DocumentMetadata {
+    DocumentMetadata {
+        path: path.to_string(),
+        name: Path::new(path).file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default(),
+        title: file_title(path),
+        kind,
+        size_bytes,
+        pages: None,
+        sheets: Vec::new(),
+        word_count: text.split_whitespace().count(),
+    }
+}
+
+fn file_title(path: &str) -> String {
+    Path::new(path).file_stem().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default()
+}
+
+// Summary failures only lose the summary
+async fn summarize_text(app: &tauri::AppHandle, text: &str, title: &str, language: Option<String>) -> Option<String> {
+    let options = crate::summarizer::SummaryOptions { title: Some(title.to_string()), language, ..Default::default() };
+    match crate::summarizer::summarize(app.clone(), text.to_string(), options, |_| {}).await {
+        Ok(summary) => Some(summary.summary),
+        Err(e) => {
+            tracing::warn!(target: "ai", "Documents: Summary of {} failed: {}", title, e);
+            None
+        }
+    }
+}
+
+// Replace the document's chunks and mirror its text into local search; unchanged documents
+// already indexed with the current model are kept
+async fn index_document(app: &tauri::AppHandle, doc_id: &str, metadata: &DocumentMetadata, sections: &[Section]) -> Result<usize, DocumentError> {
+    let db = app.state::<Database>();
+    let err = |e: rusqlite::Error| DocumentError::Storage(e.to_string());
+    if let Some(stored) = db.document_get(doc_id).map_err(err)? {
+        if stored.model == embeddings::default_model() {
+            return Ok(stored.chunks);
+        }
+    }
+
+    let pieces = chunk_sections(sections, &ChunkOptions::retrieval(&embeddings::default_model()));
+    let texts: Vec<String> = pieces.iter().map(|(_, text)| text.clone()).collect();
+    let embedded = embeddings::embed(&texts).await;
+    let chunks: Vec<DocumentChunk> = pieces
+        .into_iter()
+        .zip(embedded.vectors)
+        .enumerate()
+        .map(|(seq, ((location, text), vector))| DocumentChunk { seq, location, text, vector })
+        .collect();
+    let stored = StoredDocument {
+        doc_id: doc_id.to_string(),
+        path: metadata.path.clone(),
+        name: metadata.name.clone(),
+        title: metadata.title.clone(),
+        kind: metadata.kind,
+        chunks: chunks.len(),
+        model: embedded.model,
+        indexed_at: chrono::Utc::now().timestamp(),
+    };
+    db.document_save(&stored, &chunks).map_err(err)?;
+    if let Some(local_index) = app.try_state::<LocalIndex>() {
+        local_index.document_saved(IndexDocument::document(&stored, &texts.join("\n\n")));
+    }
+    tracing::debug!(target: "ai", "Documents: Indexed {} ({} chunks)", metadata.name, chunks.len());
+    Ok(chunks.len())
+}
+
+// Retrieve the chunks closest to the question and stream an answer that cites them as [n].
+// `emit` sees ContextTrimmed (if excerpts had to be dropped) and the references first, then tokens, then Done/Failed.
+pub async fn ask(
+    app: &tauri::AppHandle,
+    doc_id: &str,
+    question: &str,
+    top_k: Option<usize>,
+    emit: impl Fn(DocumentEvent) + Send + Sync,
+) -> Result<DocumentAnswer, DocumentError> {
+    let result = ask_inner(app, doc_id, question, top_k, &emit).await;
+    match &result {
+        Ok(answer) => emit(DocumentEvent::Done { answer: answer.clone() }),
+        Err(e) => emit(DocumentEvent::Failed { error: e.to_string() }),
+    }
+    result
+}
+
+async fn ask_inner(
+    app: &tauri::AppHandle,
+    doc_id: &str,
+    question: &str,
+    top_k: Option<usize>,
+    emit: &(impl Fn(DocumentEvent) + Send + Sync),
+) -> Result<DocumentAnswer, DocumentError> {
+    let started = std::time::Instant::now();
+    let question = question.trim();
+    if question.is_empty() {
+        return Err(DocumentError::EmptyQuestion);
+    }
+    let db = app.state::<Database>();
+    let err = |e: rusqlite::Error| DocumentError::Storage(e.to_string());
+    let stored = db.document_get(doc_id).map_err(err)?.ok_or_else(|| DocumentError::NotIndexed(doc_id.to_string()))?;
+    let chunks = db.document_chunks(doc_id).map_err(err)?;
+
+    // The question has to be embedded with the model the chunks were indexed with
+    let embedded = embeddings::embed(&[question.to_string()]).await;
+    if embedded.model != stored.model {
+        return Err(DocumentError::ModelChanged(stored.model));
+    }
+    let query = embedded.vectors.first().cloned().unwrap_or_default();
+
+    let mut scored: Vec<(f32, &DocumentChunk)> = chunks.iter().map(|c| (embeddings::cosine(&query, &c.vector), c)).collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    let top_k = top_k.unwrap_or(TOP_K).clamp(1, MAX_TOP_K);
+    let mut selected: Vec<(f32, &DocumentChunk)> = scored.iter().take(top_k).filter(|(s, _)| *s >= MIN_SIMILARITY).cloned().collect();
+    if selected.is_empty() {
+        selected = scored.into_iter().take(top_k.min(3)).collect();
+    }
+    // Excerpts that don't fit the model's context window are dropped, least similar first
+    let language = crate::language::detect_language(question).language;
+    let model = app.state::<AIService>().model().to_string();
+    let pack = |selected: &[(f32, &DocumentChunk)]| {
+        let excerpts = selected
+            .iter()
+            .enumerate()
+            .map(|(i, (_, chunk))| format!("[{}] ({})\n{}", i + 1, chunk.location.label(), chunk.text))
+            .collect();
+        ContextPacker::new(&model)
+            .text("question", question, Priority::Required, Fit::KeepStart)
+            .text("name", &stored.name, Priority::Required, Fit::KeepStart)
+            .ranked_list("excerpts", ranked(selected, excerpts), Priority::High, Fit::DropLowest)
+            .render("document_qa", Some(&language), None)
+    };
+    // Reading order reads better than score order, for the model and for the reference list
+    selected.sort_by_key(|(_, c)| c.seq);
+    let mut packed = pack(&selected);
+    if packed.report.trimmed() {
+        emit(DocumentEvent::ContextTrimmed { report: packed.report.clone() });
+    }
+    let kept = packed.kept("excerpts");
+    if kept.len() < selected.len() {
+        // Number the remaining excerpts [1]..[n] again so citations match the reference list
+        selected = kept.iter().map(|&i| selected[i]).collect();
+        packed = pack(&selected);
+    }
+    let prompt = packed.prompt;
+
+    let references: Vec<DocumentReference> = selected
+        .iter()
+        .enumerate()
+        .map(|(i, (similarity, chunk))| DocumentReference {
+            id: i + 1,
+            location: chunk.location.clone(),
+            label: chunk.location.label(),
+            snippet: chunk.text.chars().take(240).collect(),
+            similarity: *similarity,
+        })
+        .collect();
+    emit(DocumentEvent::References { references: references.clone() });
+
+    let ai = app.state::<AIService>();
+    let answer = ai
+        .complete_stream_markdown(&prompt, |chunk| {
+            emit(DocumentEvent::AnswerToken { token: chunk.token, events: chunk.events });
+        })
+        .await
+        .map_err(|e| DocumentError::AIError(e.to_string()))?;
+
+    Ok(DocumentAnswer {
+        doc_id: doc_id.to_string(),
+        question: question.to_string(),
+        answer: answer.trim().to_string(),
+        references,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+// Items paired with their similarity rank (0 = most similar), in the order given
+fn ranked(selected: &[(f32, &DocumentChunk)], items: Vec<String>) -> Vec<(usize, String)> {
+    let mut by_score: Vec<usize> = (0..selected.len()).collect();
+    by_score.sort_by(|&a, &b| selected[b].0.total_cmp(&selected[a].0));
+    let mut ranks = vec![0; selected.len()];
+    for (rank, i) in by_score.into_iter().enumerate() {
+        ranks[i] = rank;
+    }
+    ranks.into_iter().zip(items).collect()
+}
+
+struct Extracted {
+    sections: Vec<Section>,
+    tables: Vec<Table>,
+    title: Option<String>,             // From the file's own metadata (EPUB)
+}
+
+fn extract(kind: DocumentKind, bytes: &[u8], with_tables: bool) -> Result<Extracted, DocumentError> {
+    let (sections, tables) = match kind {
+        DocumentKind::Pdf => {
+            let sections = extract_pdf(bytes)?;
+            // A second pass over the glyph positions; tables are a bonus, so failures only lose them
+            let tables = if with_tables {
+                tables::from_pdf(bytes).unwrap_or_else(|e| {
+                    tracing::debug!(target: "app", "Documents: {}", e);
+                    Vec::new()
+                })
+            } else {
+                Vec::new()
+            };
+            (sections, tables)
+        }
+        DocumentKind::Docx => (paragraph_sections(&extract_docx(bytes)?), Vec::new()),
+        DocumentKind::Xlsx => extract_sheets(bytes)?,
+        DocumentKind::Epub => {
+            let book = read_book(kind, bytes, "")?;
+            return Ok(Extracted { sections: book_sections(&book), tables: Vec::new(), title: Some(book.metadata.title) });
+        }
+        DocumentKind::Text => {
+            let text = String::from_utf8_lossy(bytes);
+            let paragraphs: Vec<String> = text.split("\n\n").map(|p| p.trim().to_string()).collect();
+            (paragraph_sections(&paragraphs), Vec::new())
+        }
+    };
+    Ok(Extracted { sections, tables, title: None })
+}
+
+fn read_book(kind: DocumentKind, bytes: &[u8], fallback_title: &str) -> Result<Book, DocumentError> {
+    match kind {
+        DocumentKind::Epub => ebook::read_epub(bytes).map_err(|e| DocumentError::Parse(e.to_string())),
+        _ => Ok(ebook::read_plain(&String::from_utf8_lossy(bytes), fallback_title)),
+    }
+}
+
+fn book_sections(book: &Book) -> Vec<Section> {
+    book.chapters
+        .iter()
+        .enumerate()
+        .map(|(i, chapter)| Section {
+            location: Location { chapter: Some(i as u32 + 1), ..Default::default() },
+            text: format!("{}\n\n{}", chapter.title, chapter.text),
+        })
+        .collect()
+}
+
+fn extract_pdf(bytes: &[u8]) -> Result<Vec<Section>, DocumentError> {
+    let pages = pdf_extract::extract_text_from_mem_by_pages(bytes).map_err(|e| DocumentError::Parse(e.to_string()))?;
+    Ok(pages
+        .into_iter()
+        .enumerate()
+        .map(|(i, text)| Section {
+            location: Location { page: Some(i as u32 + 1), ..Default::default() },
+            text: text.trim().to_string(),
+        })
+        .filter(|s| !s.text.is_empty())
+        .collect())
+}
+
+// Paragraph text from word/document.xml (w:p elements, w:t runs, tabs and breaks as whitespace)
+fn extract_docx(bytes: &[u8]) -> Result<Vec<String>, DocumentError> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).map_err(|e| DocumentError::Parse(e.to_string()))?;
+    let mut xml = String::new();
+    archive
+        .by_name("word/document.xml")
+        .map_err(|e| DocumentError::Parse(e.to_string()))?
+        .read_to_string(&mut xml)
+        .map_err(|e| DocumentError::Parse(e.to_string()))?;
+
+    let mut paragraphs = Vec::new();
+    let mut current = String::new();
+    let mut in_text = false;
+    let mut rest = xml.as_str();
+    while let Some(start) = rest.find('<') {
+        if in_text {
+            current.push_str(&unescape_xml(&rest[..start]));
+        }
+        let Some(end) = rest[start..].find('>') else { break };
+        let tag = &rest[start + 1..start + end];
+        let name = tag.trim_start_matches('/').split([' ', '/']).next().unwrap_or_default();
+        match name {
+            "w:t" => in_text = !tag.starts_with('/') && !tag.ends_with('/'),
+            "w:tab" => current.push('\t'),
+            "w:br" | "w:cr" => current.push('\n'),
+            "w:p" if tag.starts_with('/') || tag.ends_with('/') => {
+                paragraphs.push(std::mem::take(&mut current).trim().to_string());
+            }
+            _ => {}
+        }
+        rest = &rest[start + end + 1..];
+    }
+    if !current.trim().is_empty() {
+        paragraphs.push(current.trim().to_string());
+    }
+    Ok(paragraphs)
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+// One section per sheet (rows as tab-separated lines) plus the sheet as a table
+fn extract_sheets(bytes: &[u8]) -> Result<(Vec<Section>, Vec<Table>), DocumentError> {
+    use calamine::Reader;
+
+    let mut workbook = calamine::open_workbook_auto_from_rs(Cursor::new(bytes.to_vec())).map_err(|e| DocumentError::Parse(e.to_string()))?;
+    let mut sections = Vec::new();
+    let mut tables = Vec::new();
+    for name in workbook.sheet_names() {
+        let range = match workbook.worksheet_range(&name) {
+            Ok(range) => range,
+            Err(e) => {
+                tracing::debug!(target: "app", "Documents: Skipping sheet {}: {}", name, e);
+                continue;
+            }
+        };
+        let rows: Vec<Vec<String>> = range
+            .rows()
+            .map(|row| row.iter().map(|cell| cell.to_string().trim().to_string()).collect::<Vec<_>>())
+            .filter(|row| row.iter().any(|c| !c.is_empty()))
+            .collect();
+        let text = rows.iter().map(|row| row.join("\t")).collect::<Vec<_>>().join("\n");
+        if !text.is_empty() {
+            sections.push(Section { location: Location { sheet: Some(name.clone()), ..Default::default() }, text });
+        }
+        tables.push(tables::from_sheet(&name, rows));
+    }
+    Ok((sections, tables))
+}
+
+// Consecutive paragraphs grouped into sections of about SECTION_CHARS, located by their first paragraph
+fn paragraph_sections(paragraphs: &[String]) -> Vec<Section> {
+    let mut sections: Vec<Section> = Vec::new();
+    let mut current = String::new();
+    let mut first = 0;
+    for (i, paragraph) in paragraphs.iter().enumerate().filter(|(_, p)| !p.is_empty()) {
+        if !current.is_empty() && current.chars().count() + paragraph.chars().count() > SECTION_CHARS {
+            sections.push(Section { location: Location { paragraph: Some(first as u32 + 1), ..Default::default() }, text: std::mem::take(&mut current) });
+        }
+        if current.is_empty() {
+            first = i;
+        } else {
+            current.push_str("\n\n");
+        }
+        current.push_str(paragraph);
+    }
+    if !current.is_empty() {
+        sections.push(Section { location: Location { paragraph: Some(first as u32 + 1), ..Default::default() }, text: current });
+    }
+    sections
+}
+
+// Split sections into retrieval chunks; a chunk keeps the location of the section it came from
+fn chunk_sections(sections: &[Section], options: &ChunkOptions) -> Vec<(Location, String)> {
+    sections
+        .iter()
+        .flat_map(|section| {
+            chunker::split(&section.text, options)
+                .into_iter()
+                .map(|text| (section.location.clone(), text))
+        })
+        .take(MAX_CHUNKS)
+        .collect()
+}
+
+fn document_id(bytes: &[u8]) -> String {
+    let digest = Sha256::digest(bytes);
+    digest[..16].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[derive(Debug, Clone)]
+pub enum DocumentError {
+    Unsupported(String),
+    TooLarge(u64),
+    Io(String),
+    Parse(String),
+    NoText,
+    NotIndexed(String),
+    ModelChanged(String),
+    EmptyQuestion,
+    Storage(String),
+    AIError(String),
+}
+
+impl std::fmt::Display for DocumentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DocumentError::Unsupported(path) => write!(f, "Unsupported document type: {}", path),
+            DocumentError::TooLarge(bytes) => write!(f, "Document is too large ({} MB, limit {} MB)", bytes / 1_048_576, MAX_FILE_BYTES / 1_048_576),
+            DocumentError::Io(msg) => write!(f, "Could not read document: {}", msg),
+            DocumentError::Parse(msg) => write!(f, "Could not parse document: {}", msg),
+            DocumentError::NoText => write!(f, "No text found in the document (scanned PDFs need OCR)"),
+            DocumentError::NotIndexed(id) => write!(f, "Document '{}' has not been processed", id),
+            DocumentError::ModelChanged(model) => {
+                write!(f, "Document was indexed with {}, which is not available now; process it again", model)
+            }
+            DocumentError::EmptyQuestion => write!(f, "Question is empty"),
+            DocumentError::Storage(msg) => write!(f, "{}", msg),
+            DocumentError::AIError(msg) => write!(f, "AI error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for DocumentError {}
@@ -0,0 +1,407 @@
+// DNS over HTTPS - Resolver for the shared HTTP client so backend lookups skip the local resolver
+// Private/Ghost modes only ever use DoH; Normal mode uses system DNS unless [dns] normalMode opts in,
+// and then falls back to system DNS when the provider fails
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::state::PrivacyMode;
+
+pub const PROVIDERS: &[&str] = &["cloudflare", "quad9"];
+
+const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+// Answers are cached for their TTL, kept within these bounds
+const MIN_TTL: Duration = Duration::from_secs(30);
+const MAX_TTL: Duration = Duration::from_secs(3600);
+const MAX_CACHED: usize = 1024;
+const TYPE_A: u16 = 1;
+const TYPE_AAAA: u16 = 28;
+
+struct Provider {
+    name: &'static str,
+    host: &'static str,
+    url: &'static str,
+    bootstrap: &'static [IpAddr],      // The provider's own address, so reaching it needs no DNS lookup
+}
+
+const CLOUDFLARE: Provider = Provider {
+    name: "cloudflare",
+    host: "cloudflare-dns.com",
+    url: "https://cloudflare-dns.com/dns-query",
+    bootstrap: &[IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)), IpAddr::V4(Ipv4Addr::new(1, 0, 0, 1))],
+};
+
+const QUAD9: Provider = Provider {
+    name: "quad9",
+    host: "dns.quad9.net",
+    url: "https://dns.quad9.net/dns-query",
+    bootstrap: &[IpAddr::V4(Ipv4Addr::new(9, 9, 9, 9)), IpAddr::V4(Ipv4Addr::new(149, 112, 112, 112))],
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct DohStatus {
+    pub provider: String,
+    pub endpoint: String,
+    pub active: bool,                  // Lookups currently go over DoH
+    pub strict: bool,                  // Private/Ghost: no system DNS fallback
+    pub cached_names: usize,
+    pub lookups: u64,
+    pub cache_hits: u64,
+    pub failures: u64,
+    pub fallbacks: u64,                // Normal-mode lookups answered by system DNS instead
+}
+
+struct CacheEntry {
+    addrs: Vec<IpAddr>,
+    expires: Instant,
+}
+
+struct Resolver {
+    provider: Mutex<&'static Provider>,
+    client: Mutex<Option<(Option<String>, reqwest::Client)>>,   // Built for this proxy key
+    strict: AtomicBool,
+    normal_mode: AtomicBool,           // DoH opted into for Normal mode
+    cache: Mutex<HashMap<String, CacheEntry>>,
+    lookups: AtomicU64,
+    cache_hits: AtomicU64,
+    failures: AtomicU64,
+    fallbacks: AtomicU64,
+}
+
+fn resolver() -> &'static Resolver {
+    static RESOLVER: OnceLock<Resolver> = OnceLock::new();
+    RESOLVER.get_or_init(|| Resolver {
+        provider: Mutex::new(&CLOUDFLARE),
+        client: Mutex::new(None),
+        strict: AtomicBool::new(false),
+        normal_mode: AtomicBool::new(false),
+        cache: Mutex::new(HashMap::new()),
+        lookups: AtomicU64::new(0),
+        cache_hits: AtomicU64::new(0),
+        failures: AtomicU64::new(0),
+        fallbacks: AtomicU64::new(0),
+    })
+}
+
+// Installed on the shared client in http.rs
+pub struct DohResolver;
+
+impl reqwest::dns::Resolve for DohResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let name = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs = resolve(&name).await.map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?;
+            Ok(Box::new(addrs.into_iter().map(|ip| SocketAddr::new(ip, 0))) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+// Switch providers ("cloudflare" or "quad9"); cached answers from the old one are dropped
+pub fn set_provider(name: &str) -> Result<(), DohError> {
+    let provider = match name.trim().to_lowercase().as_str() {
+        "cloudflare" => &CLOUDFLARE,
+        "quad9" => &QUAD9,
+        _ => return Err(DohError::UnknownProvider(name.to_string())),
+    };
+    let state = resolver();
+    let mut current = state.provider.lock().unwrap();
+    if current.name != provider.name {
+        *current = provider;
+        *state.client.lock().unwrap() = None;
+        state.cache.lock().unwrap().clear();
+        tracing::info!(target: "privacy", "DoH: Provider set to {}", provider.name);
+    }
+    Ok(())
+}
+
+// Private/Ghost make DoH mandatory. The cache is cleared so nothing resolved by system DNS carries over.
+pub fn set_privacy_mode(mode: &PrivacyMode) {
+    let strict = !matches!(mode, PrivacyMode::Normal);
+    let state = resolver();
+    if state.strict.swap(strict, Ordering::SeqCst) != strict {
+        state.cache.lock().unwrap().clear();
+    }
+}
+
+// [dns] normalMode: use DoH in Normal mode too
+pub fn set_normal_mode(on: bool) {
+    let state = resolver();
+    if state.normal_mode.swap(on, Ordering::SeqCst) != on {
+        state.cache.lock().unwrap().clear();
+        tracing::info!(target: "privacy", "DoH: {} in Normal mode", if on { "Enabled" } else { "Disabled" });
+    }
+}
+
+fn active(state: &Resolver) -> bool {
+    state.strict.load(Ordering::SeqCst) || state.normal_mode.load(Ordering::SeqCst)
+}
+
+pub fn status() -> DohStatus {
+    let state = resolver();
+    let provider = *state.provider.lock().unwrap();
+    DohStatus {
+        provider: provider.name.to_string(),
+        endpoint: provider.url.to_string(),
+        active: active(state),
+        strict: state.strict.load(Ordering::SeqCst),
+        cached_names: state.cache.lock().unwrap().len(),
+        lookups: state.lookups.load(Ordering::Relaxed),
+        cache_hits: state.cache_hits.load(Ordering::Relaxed),
+        failures: state.failures.load(Ordering::Relaxed),
+        fallbacks: state.fallbacks.load(Ordering::Relaxed),
+    }
+}
+
+pub async fn resolve(name: &str) -> Result<Vec<IpAddr>, DohError> {
+    let name = name.trim_end_matches('.').to_lowercase();
+    // Loopback names never leave the machine
+    if name == "localhost" || name.ends_with(".localhost") {
+        return Ok(vec![IpAddr::V4(Ipv4Addr::LOCALHOST), IpAddr::V6(Ipv6Addr::LOCALHOST)]);
+    }
+
+    let state = resolver();
+    if !active(state) {
+        return system_lookup(&name).await;
+    }
+    state.lookups.fetch_add(1, Ordering::Relaxed);
+    if let Some(entry) = state.cache.lock().unwrap().get(&name).filter(|e| e.expires > Instant::now()) {
+        state.cache_hits.fetch_add(1, Ordering::Relaxed);
+        return Ok(entry.addrs.clone());
+    }
+
+    match query(&name).await {
+        Ok((addrs, ttl)) => {
+            cache(&name, addrs.clone(), ttl);
+            Ok(addrs)
+        }
+        Err(e) => {
+            state.failures.fetch_add(1, Ordering::Relaxed);
+            if state.strict.load(Ordering::SeqCst) {
+                tracing::warn!(target: "privacy", "DoH: Lookup for {} failed: {}", name, e);
+                return Err(e);
+            }
+            tracing::debug!(target: "privacy", "DoH: Lookup for {} failed ({}), using system DNS", name, e);
+            state.fallbacks.fetch_add(1, Ordering::Relaxed);
+            let addrs = system_lookup(&name).await?;
+            // System answers carry no TTL; cache them briefly
+            cache(&name, addrs.clone(), MIN_TTL);
+            Ok(addrs)
+        }
+    }
+}
+
+async fn system_lookup(name: &str) -> Result<Vec<IpAddr>, DohError> {
+    Ok(tokio::net::lookup_host((name, 0))
+        .await
+        .map_err(|e| DohError::Lookup(name.to_string(), e.to_string()))?
+        .map(|a| a.ip())
+        .collect())
+}
+
+pub fn clear_cache() {
+    resolver().cache.lock().unwrap().clear();
+}
+
+fn cache(name: &str, addrs: Vec<IpAddr>, ttl: Duration) {
+    let mut cache = resolver().cache.lock().unwrap();
+    if cache.len() >= MAX_CACHED {
+        let now = Instant::now();
+        cache.retain(|_, e| e.expires > now);
+        if cache.len() >= MAX_CACHED {
+            if let Some(oldest) = cache.iter().min_by_key(|(_, e)| e.expires).map(|(n, _)| n.clone()) {
+                cache.remove(&oldest);
+            }
+        }
+    }
+    let expires = Instant::now() + ttl.clamp(MIN_TTL, MAX_TTL);
+    cache.insert(name.to_string(), CacheEntry { addrs, expires });
+}
+
+// A and AAAA in parallel; either one answering is enough
+async fn query(name: &str) -> Result<(Vec<IpAddr>, Duration), DohError> {
+    let provider = *resolver().provider.lock().unwrap();
+    if crate::http::local_only() {
+        return Err(DohError::Provider(provider.name.to_string(), "local-only mode is on".to_string()));
+    }
+    let client = client(provider)?;
+    let (v4, v6) = tokio::join!(
+        query_type(&client, provider, name, TYPE_A),
+        query_type(&client, provider, name, TYPE_AAAA)
+    );
+    let mut addrs = Vec::new();
+    let mut ttl = MAX_TTL;
+    let mut error = None;
+    for result in [v4, v6] {
+        match result {
+            Ok((found, found_ttl)) => {
+                if !found.is_empty() {
+                    ttl = ttl.min(found_ttl);
+                }
+                addrs.extend(found);
+            }
+            Err(e) => error = Some(e),
+        }
+    }
+    if addrs.is_empty() {
+        return Err(error.unwrap_or_else(|| DohError::Lookup(name.to_string(), "no addresses".to_string())));
+    }
+    Ok((addrs, ttl))
+}
+
+async fn query_type(
+    client: &reqwest::Client,
+    provider: &Provider,
+    name: &str,
+    record_type: u16,
+) -> Result<(Vec<IpAddr>, Duration), DohError> {
+    let message = encode_query(name, record_type).ok_or_else(|| DohError::Lookup(name.to_string(), "invalid name".to_string()))?;
+    let encoded = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(message);
+    let sent_at = chrono::Utc::now().timestamp_millis();
+    let started = Instant::now();
+    let result = client
+        .get(provider.url)
+        .query(&[("dns", encoded)])
+        .header(reqwest::header::ACCEPT, "application/dns-message")
+        .send()
+        .await;
+    // Sent outside http::Client (which resolves through us), so it is audited here
+    crate::network_audit::record(crate::network_audit::AuditEntry {
+        id: 0,
+        timestamp: sent_at,
+        method: "GET".to_string(),
+        host: provider.host.to_string(),
+        path: url::Url::parse(provider.url).map(|u| u.path().to_string()).unwrap_or_default(),
+        purpose: "doh".to_string(),
+        status: result.as_ref().ok().map(|r| r.status().as_u16()),
+        bytes_sent: 0,
+        bytes_received: result.as_ref().ok().and_then(|r| r.content_length()),
+        duration_ms: started.elapsed().as_millis() as u64,
+        privacy_mode: String::new(),
+        local: false,
+        error: result.as_ref().err().map(|e| e.to_string()),
+    });
+    let response = result.map_err(|e| DohError::Provider(provider.name.to_string(), e.to_string()))?;
+    if !response.status().is_success() {
+        return Err(DohError::Provider(provider.name.to_string(), format!("HTTP {}", response.status().as_u16())));
+    }
+    let body = response.bytes().await.map_err(|e| DohError::Provider(provider.name.to_string(), e.to_string()))?;
+    parse_response(&body, record_type).ok_or_else(|| DohError::Lookup(name.to_string(), "malformed DNS response".to_string()))
+}
+
+// Separate from the shared client (which resolves through us); the provider is pinned to its bootstrap IPs.
+// Goes through the user's proxy like every other backend request, and is rebuilt when that changes.
+fn client(provider: &'static Provider) -> Result<reqwest::Client, DohError> {
+    let proxy = crate::http::current_proxy();
+    let key = proxy.as_ref().map(|(key, _)| key.clone());
+    let mut cached = resolver().client.lock().unwrap();
+    if let Some((_, client)) = cached.as_ref().filter(|(built_for, _)| *built_for == key) {
+        return Ok(client.clone());
+    }
+    let addrs: Vec<SocketAddr> = provider.bootstrap.iter().map(|ip| SocketAddr::new(*ip, 443)).collect();
+    let mut builder = reqwest::Client::builder().resolve_to_addrs(provider.host, &addrs).timeout(QUERY_TIMEOUT);
+    if let Some((_, proxy)) = proxy {
+        builder = builder.proxy(proxy);
+    }
+    let client = builder.build().map_err(|e| DohError::Provider(provider.name.to_string(), e.to_string()))?;
+    *cached = Some((key, client.clone()));
+    Ok(client)
+}
+
+// RFC 8484 GET: a wire-format query with ID 0 so responses are HTTP-cacheable
+fn encode_query(name: &str, record_type: u16) -> Option<Vec<u8>> {
+    if name.is_empty() || name.len() > 253 {
+        return None;
+    }
+    let mut message = vec![0, 0, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0];
+    for label in name.split('.') {
+        if label.is_empty() || label.len() > 63 || !label.is_ascii() {
+            return None;
+        }
+        message.push(label.len() as u8);
+        message.extend_from_slice(label.as_bytes());
+    }
+    message.push(0);
+    message.extend_from_slice(&record_type.to_be_bytes());
+    message.extend_from_slice(&1u16.to_be_bytes());
+    Some(message)
+}
+
+// Addresses of the requested type from the answer section, and the smallest TTL among them.
+// NXDOMAIN and empty answers come back as an empty list.
+fn parse_response(message: &[u8], record_type: u16) -> Option<(Vec<IpAddr>, Duration)> {
+    let header = message.get(..12)?;
+    let rcode = header[3] & 0x0F;
+    if rcode != 0 && rcode != 3 {
+        return None;
+    }
+    let questions = u16::from_be_bytes([header[4], header[5]]);
+    let answers = u16::from_be_bytes([header[6], header[7]]);
+
+    let mut offset = 12;
+    for _ in 0..questions {
+        offset = skip_name(message, offset)? + 4;
+    }
+    let mut addrs = Vec::new();
+    let mut ttl = u32::MAX;
+    for _ in 0..answers {
+        offset = skip_name(message, offset)?;
+        let fixed = message.get(offset..offset + 10)?;
+        let kind = u16::from_be_bytes([fixed[0], fixed[1]]);
+        let record_ttl = u32::from_be_bytes([fixed[4], fixed[5], fixed[6], fixed[7]]);
+        let length = u16::from_be_bytes([fixed[8], fixed[9]]) as usize;
+        let data = message.get(offset + 10..offset + 10 + length)?;
+        offset += 10 + length;
+
+        // CNAMEs in the chain are skipped; the resolver includes the final records
+        let ip = match (kind, length) {
+            (TYPE_A, 4) if record_type == TYPE_A => IpAddr::V4(Ipv4Addr::new(data[0], data[1], data[2], data[3])),
+            (TYPE_AAAA, 16) if record_type == TYPE_AAAA => IpAddr::V6(Ipv6Addr::from(<[u8; 16]>::try_from(data).ok()?)),
+            _ => continue,
+        };
+        addrs.push(ip);
+        ttl = ttl.min(record_ttl);
+    }
+    Some((addrs, Duration::from_secs(u64::from(ttl))))
+}
+
+// Offset just past a (possibly compressed) name
+fn skip_name(message: &[u8], mut offset: usize) -> Option<usize> {
+    loop {
+        let length = *message.get(offset)?;
+        if length == 0 {
+            return Some(offset + 1);
+        }
+        if length & 0xC0 == 0xC0 {
+            return Some(offset + 2);
+        }
+        offset += 1 + length as usize;
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum DohError {
+    UnknownProvider(String),
+    Provider(String, String),
+    Lookup(String, String),
+}
+
+impl std::fmt::Display for DohError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DohError::UnknownProvider(name) => {
+                write!(f, "Unknown DNS-over-HTTPS provider '{}' (expected {})", name, PROVIDERS.join(" or "))
+            }
+            DohError::Provider(name, msg) => write!(f, "DNS-over-HTTPS request to {} failed: {}", name, msg),
+            DohError::Lookup(name, msg) => write!(f, "Could not resolve {}: {}", name, msg),
+        }
+    }
+}
+
+impl std::error::Error for DohError {}
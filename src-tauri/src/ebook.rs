@@ -0,0 +1,387 @@
+// Ebook - EPUB container parsing and chapter detection for plain-text books
+// EPUB chapters follow the spine with titles from the nav document or NCX; plain text splits on "Chapter N" style headings
+
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+use std::sync::OnceLock;
+
+use crate::extractor::collapse_whitespace;
+
+// Spine items shorter than this (cover pages, blank separators) are not chapters
+const MIN_CHAPTER_WORDS: usize = 20;
+// Text before the first plain-text heading becomes a chapter only when it is this long
+const MIN_FRONT_MATTER_WORDS: usize = 100;
+const MAX_HEADING_CHARS: usize = 80;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct BookMetadata {
+    pub title: String,
+    pub authors: Vec<String>,
+    pub language: Option<String>,
+    pub publisher: Option<String>,
+    pub published: Option<String>,     // As written in the book, usually an ISO date or a year
+    pub identifier: Option<String>,    // ISBN, UUID, or Gutenberg URL
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BookChapter {
+    pub title: String,
+    pub text: String,                  // Paragraphs separated by blank lines
+}
+
+#[derive(Debug, Clone)]
+pub struct Book {
+    pub metadata: BookMetadata,
+    pub chapters: Vec<BookChapter>,
+}
+
+struct EbookRegexes {
+    tag: Regex,
+    attr: Regex,
+    anchor: Regex,
+    nav_point: Regex,
+    heading: Regex,
+}
+
+fn regexes() -> &'static EbookRegexes {
+    static RE: OnceLock<EbookRegexes> = OnceLock::new();
+    RE.get_or_init(|| EbookRegexes {
+        tag: Regex::new(r"(?s)<[^>]*>").unwrap(),
+        attr: Regex::new(r#"([\w:-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap(),
+        anchor: Regex::new(r#"(?is)<a\b[^>]*\bhref\s*=\s*(?:"([^"]*)"|'([^']*)')[^>]*>(.*?)</a\s*>"#).unwrap(),
+        nav_point: Regex::new(r#"(?is)<navLabel\b[^>]*>\s*<text\b[^>]*>(.*?)</text\s*>.*?<content\b[^>]*\bsrc\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap(),
+        heading: Regex::new(r"(?im)^[ \t]*(?:(?:chapter|book|part|letter)[ \t]+(?:[0-9]+|[ivxlcdm]+|[a-z]+teen|one|two|three|four|five|six|seven|eight|nine|ten|eleven|twelve)\b[^\n]*|(?-i:[IVXLC]+)\.?)[ \t]*$").unwrap(),
+    })
+}
+
+// ============================================================================
+// EPUB
+// ============================================================================
+
+pub fn read_epub(bytes: &[u8]) -> Result<Book, EbookError> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).map_err(|e| EbookError::Invalid(e.to_string()))?;
+    let container = read_entry(&mut archive, "META-INF/container.xml")?;
+    let package_path = elements(&container, "rootfile")
+        .into_iter()
+        .find_map(|attrs| attrs.get("full-path").cloned())
+        .ok_or(EbookError::MissingPackage)?;
+    let package = read_entry(&mut archive, &package_path)?;
+
+    let mut metadata = BookMetadata {
+        title: dc_values(&package, "title").into_iter().next().unwrap_or_default(),
+        authors: dc_values(&package, "creator"),
+        language: dc_values(&package, "language").into_iter().next(),
+        publisher: dc_values(&package, "publisher").into_iter().next(),
+        published: dc_values(&package, "date").into_iter().next(),
+        identifier: dc_values(&package, "identifier").into_iter().next(),
+        description: dc_values(&package, "description").into_iter().next(),
+    };
+    metadata.description = metadata.description.map(|d| strip_tags(&d)).filter(|d| !d.is_empty());
+
+    // id -> (archive path, media type, properties)
+    let manifest: HashMap<String, (String, String, String)> = elements(&package, "item")
+        .into_iter()
+        .filter_map(|attrs| {
+            let href = attrs.get("href")?;
+            Some((
+                attrs.get("id")?.clone(),
+                (
+                    resolve(&package_path, href),
+                    attrs.get("media-type").cloned().unwrap_or_default(),
+                    attrs.get("properties").cloned().unwrap_or_default(),
+                ),
+            ))
+        })
+        .collect();
+    let toc = toc_titles(&mut archive, &package, &manifest);
+
+    let mut chapters = Vec::new();
+    for attrs in elements(&package, "itemref") {
+        if attrs.get("linear").is_some_and(|l| l == "no") {
+            continue;
+        }
+        let Some((path, media_type, _)) = attrs.get("idref").and_then(|id| manifest.get(id)) else {
+            continue;
+        };
+        if !media_type.contains("html") {
+            continue;
+        }
+        let Ok(xhtml) = read_entry(&mut archive, path) else {
+            tracing::debug!(target: "app", "Ebook: Spine item {} is missing", path);
+            continue;
+        };
+        let (heading, text) = xhtml_text(&xhtml);
+        if text.split_whitespace().count() < MIN_CHAPTER_WORDS {
+            continue;
+        }
+        let title = toc
+            .get(path.as_str())
+            .cloned()
+            .or(heading)
+            .unwrap_or_else(|| format!("Chapter {}", chapters.len() + 1));
+        chapters.push(BookChapter { title, text });
+    }
+    if chapters.is_empty() {
+        return Err(EbookError::NoChapters);
+    }
+    Ok(Book { metadata, chapters })
+}
+
+fn read_entry(archive: &mut zip::ZipArchive<Cursor<&[u8]>>, path: &str) -> Result<String, EbookError> {
+    let mut entry = archive.by_name(path).map_err(|e| EbookError::Invalid(format!("{}: {}", path, e)))?;
+    let mut buffer = Vec::new();
+    entry.read_to_end(&mut buffer).map_err(|e| EbookError::Invalid(e.to_string()))?;
+    Ok(String::from_utf8_lossy(&buffer).into_owned())
+}
+
+// Attributes of every start tag with this local name (any namespace prefix)
+fn elements(xml: &str, name: &str) -> Vec<HashMap<String, String>> {
+    let re = regexes();
+    re.tag
+        .find_iter(xml)
+        .map(|m| m.as_str())
+        .filter(|tag| {
+            let tag_name = tag[1..].split(|c: char| c.is_whitespace() || c == '/' || c == '>').next().unwrap_or_default();
+            tag_name.rsplit(':').next() == Some(name)
+        })
+        .map(|tag| {
+            re.attr
+                .captures_iter(tag)
+                .map(|c| (c[1].to_string(), unescape(c.get(2).or(c.get(3)).map_or("", |m| m.as_str()))))
+                .collect()
+        })
+        .collect()
+}
+
+// Text of every <dc:name> element in document order
+fn dc_values(package: &str, name: &str) -> Vec<String> {
+    let Ok(re) = Regex::new(&format!(r"(?is)<dc:{0}\b[^>]*>(.*?)</dc:{0}\s*>", name)) else {
+        return Vec::new();
+    };
+    re.captures_iter(package)
+        .map(|c| collapse_whitespace(&unescape(&c[1])))
+        .filter(|v| !v.is_empty())
+        .collect()
+}
+
+// Chapter titles keyed by archive path, from the EPUB 3 nav document or the EPUB 2 NCX
+fn toc_titles(
+    archive: &mut zip::ZipArchive<Cursor<&[u8]>>,
+    package: &str,
+    manifest: &HashMap<String, (String, String, String)>,
+) -> HashMap<String, String> {
+    let re = regexes();
+    let mut titles = HashMap::new();
+    let nav = manifest.values().find(|(_, _, properties)| properties.split_whitespace().any(|p| p == "nav"));
+    if let Some((path, _, _)) = nav {
+        if let Ok(xhtml) = read_entry(archive, path) {
+            // Only the table of contents, not the landmarks or page list navs
+            let toc = xhtml
+                .find("epub:type=\"toc\"")
+                .map(|start| &xhtml[start..xhtml[start..].find("</nav").map_or(xhtml.len(), |end| start + end)])
+                .unwrap_or(&xhtml);
+            for c in re.anchor.captures_iter(toc) {
+                let href = c.get(1).or(c.get(2)).map_or("", |m| m.as_str());
+                let title = strip_tags(&c[3]);
+                if !title.is_empty() {
+                    titles.entry(resolve(path, href)).or_insert(title);
+                }
+            }
+        }
+    }
+    if titles.is_empty() {
+        let ncx_id = elements(package, "spine").into_iter().find_map(|attrs| attrs.get("toc").cloned());
+        let ncx = ncx_id
+            .and_then(|id| manifest.get(&id))
+            .or_else(|| manifest.values().find(|(_, media_type, _)| media_type == "application/x-dtbncx+xml"));
+        if let Some((path, _, _)) = ncx {
+            if let Ok(xml) = read_entry(archive, path) {
+                for c in re.nav_point.captures_iter(&xml) {
+                    let src = c.get(2).or(c.get(3)).map_or("", |m| m.as_str());
+                    let title = strip_tags(&c[1]);
+                    if !title.is_empty() {
+                        titles.entry(resolve(path, src)).or_insert(title);
+                    }
+                }
+            }
+        }
+    }
+    titles
+}
+
+// Archive path of `href` relative to the file at `from`; fragments dropped, %-escapes decoded
+fn resolve(from: &str, href: &str) -> String {
+    let href = href.split('#').next().unwrap_or_default();
+    let mut parts: Vec<&str> = from.split('/').collect();
+    parts.pop();
+    for segment in href.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            s => parts.push(s),
+        }
+    }
+    percent_decode(&parts.join("/"))
+}
+
+fn percent_decode(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = (bytes[i] == b'%' && i + 2 < bytes.len())
+            .then(|| std::str::from_utf8(&bytes[i + 1..i + 3]).ok())
+            .flatten()
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match hex {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+// First heading and block-level text of a chapter document
+fn xhtml_text(xhtml: &str) -> (Option<String>, String) {
+    let document = Html::parse_document(xhtml);
+    let heading_selector = Selector::parse("h1, h2, h3").unwrap();
+    let block_selector = Selector::parse("h1, h2, h3, h4, h5, h6, p, pre, li, dt, dd").unwrap();
+    let paragraph_selector = Selector::parse("p").unwrap();
+    let text_of = |e: ElementRef| collapse_whitespace(&e.text().collect::<Vec<_>>().join(" "));
+
+    let heading = document.select(&heading_selector).map(text_of).find(|t| !t.is_empty());
+    let blocks: Vec<String> = document
+        .select(&block_selector)
+        // List items holding paragraphs would repeat their text
+        .filter(|e| e.value().name() != "li" || e.select(&paragraph_selector).next().is_none())
+        .map(text_of)
+        .filter(|t| !t.is_empty())
+        .collect();
+    let text = if blocks.is_empty() {
+        let body = Selector::parse("body").unwrap();
+        document.select(&body).next().map(text_of).unwrap_or_default()
+    } else {
+        blocks.join("\n\n")
+    };
+    (heading, text)
+}
+
+fn strip_tags(html: &str) -> String {
+    collapse_whitespace(&unescape(&regexes().tag.replace_all(html, " ")))
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+// ============================================================================
+// PLAIN TEXT
+// ============================================================================
+
+// Chapters of a plain-text book; Project Gutenberg headers supply metadata and are cut with the license
+pub fn read_plain(text: &str, fallback_title: &str) -> Book {
+    let text = text.replace("\r\n", "\n");
+    let mut metadata = BookMetadata { title: fallback_title.to_string(), ..Default::default() };
+    let mut body = text.as_str();
+    if let Some(start) = body.find("*** START OF") {
+        for line in body[..start].lines() {
+            let Some((key, value)) = line.split_once(':') else { continue };
+            let value = value.trim().to_string();
+            match key.trim() {
+                "Title" if !value.is_empty() => metadata.title = value,
+                "Author" if !value.is_empty() => metadata.authors.push(value),
+                "Language" if !value.is_empty() => metadata.language = Some(value),
+                "Release date" | "Release Date" if !value.is_empty() => metadata.published = Some(value),
+                _ => {}
+            }
+        }
+        body = body[start..].split_once('\n').map_or("", |(_, rest)| rest);
+    }
+    if let Some(end) = body.find("*** END OF") {
+        body = &body[..end];
+    }
+
+    let headings: Vec<(usize, usize)> = regexes()
+        .heading
+        .find_iter(body)
+        // Headings stand after a blank line; hard-wrapped prose can start a line with "Part two" too
+        .filter(|m| m.as_str().trim().chars().count() <= MAX_HEADING_CHARS)
+        .filter(|m| m.start() == 0 || body[..m.start()].trim_end_matches([' ', '\t']).ends_with("\n\n"))
+        .map(|m| (m.start(), m.end()))
+        .collect();
+    let mut chapters = Vec::new();
+    if headings.len() >= 2 {
+        let front = paragraphs(&body[..headings[0].0]);
+        if front.split_whitespace().count() >= MIN_FRONT_MATTER_WORDS {
+            chapters.push(BookChapter { title: "Front matter".to_string(), text: front });
+        }
+        for (i, (start, end)) in headings.iter().enumerate() {
+            let next = headings.get(i + 1).map_or(body.len(), |h| h.0);
+            let mut title = collapse_whitespace(&body[*start..*end]);
+            let mut text = body[*end..next].trim_start_matches([' ', '\t', '\n']);
+            // "CHAPTER I." directly followed by a one-line name: "CHAPTER I. The Start"
+            if let Some((line, rest)) = text.split_once('\n') {
+                if !line.trim().is_empty() && line.trim().chars().count() <= MAX_HEADING_CHARS && rest.starts_with('\n') {
+                    title = format!("{} {}", title, line.trim());
+                    text = rest;
+                }
+            }
+            let text = paragraphs(text);
+            if text.split_whitespace().count() >= MIN_CHAPTER_WORDS {
+                chapters.push(BookChapter { title, text });
+            }
+        }
+    }
+    if chapters.is_empty() {
+        chapters.push(BookChapter { title: metadata.title.clone(), text: paragraphs(body) });
+    }
+    Book { metadata, chapters }
+}
+
+// Hard-wrapped lines joined back into paragraphs
+fn paragraphs(text: &str) -> String {
+    text.split("\n\n")
+        .map(collapse_whitespace)
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[derive(Debug, Clone)]
+pub enum EbookError {
+    Invalid(String),
+    MissingPackage,
+    NoChapters,
+}
+
+impl std::fmt::Display for EbookError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EbookError::Invalid(msg) => write!(f, "Invalid EPUB: {}", msg),
+            EbookError::MissingPackage => write!(f, "Invalid EPUB: no package document in META-INF/container.xml"),
+            EbookError::NoChapters => write!(f, "The book has no readable chapters"),
+        }
+    }
+}
+
+impl std::error::Error for EbookError {}
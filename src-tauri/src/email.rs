@@ -0,0 +1,229 @@
+// Email - Draft an email with the model from research context and hand it to the user's mail client
+// Nothing is sent from here: the draft opens as a mailto: link, or as an .eml file when it is too long for one
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::ai::AIService;
+
+const MAX_INTENT_CHARS: usize = 2_000;
+const MAX_CONTEXT_CHARS: usize = 12_000;
+// Mail clients (and Windows' URL handler) truncate long mailto: links
+const MAX_MAILTO_CHARS: usize = 1_800;
+const LANGUAGES: &[&str] = &["en", "hi"];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub enum EmailHandoff {
+    #[default]
+    Mailto,                            // Falls back to an .eml file when the link would be too long
+    Eml,
+    None,                              // Only return the draft
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct EmailDraft {
+    pub recipient: Option<String>,
+    pub subject: String,
+    pub body: String,
+    pub language: String,              // "en" or "hi"
+    pub mailto_url: Option<String>,    // None when the draft is too long for a link
+    pub eml_path: Option<String>,
+    pub handoff: EmailHandoff,         // How it was actually opened
+}
+
+// Draft the email; `language` defaults to the language of the intent (romanized Hindi counts as Hindi)
+pub async fn compose(
+    ai: &AIService,
+    recipient: Option<&str>,
+    intent: &str,
+    context: Option<&str>,
+    language: Option<&str>,
+) -> Result<EmailDraft, EmailError> {
+    let intent = intent.trim();
+    if intent.is_empty() {
+        return Err(EmailError::Invalid("say what the email should be about".to_string()));
+    }
+    let recipient = recipient.map(str::trim).filter(|r| !r.is_empty()).map(validate_recipient).transpose()?;
+    let language = match language.map(|l| l.trim().to_lowercase()).filter(|l| !l.is_empty()) {
+        Some(l) if LANGUAGES.contains(&l.as_str()) => l,
+        Some(l) => return Err(EmailError::Invalid(format!("unsupported language '{}' (use en or hi)", l))),
+        None => match crate::language::detect_language(intent).language.as_str() {
+            "hi" => "hi".to_string(),
+            _ => "en".to_string(),
+        },
+    };
+
+    let intent = truncate_chars(intent, MAX_INTENT_CHARS);
+    let context = truncate_chars(context.map(str::trim).unwrap_or_default(), MAX_CONTEXT_CHARS);
+    let prompt = crate::prompts::render(
+        "email_draft",
+        Some(&language),
+        None,
+        &[
+            ("recipient", recipient.as_deref().unwrap_or("(not given)")),
+            ("intent", intent),
+            ("context", if context.is_empty() { "(none)" } else { context }),
+        ],
+    );
+    let response = ai.complete_task("email_draft", &prompt).await.map_err(|e| EmailError::AIError(e.to_string()))?;
+    let (subject, body) = parse_draft(&response);
+    if body.is_empty() {
+        return Err(EmailError::AIError("the model returned an empty draft".to_string()));
+    }
+    let mailto_url = Some(mailto_url(recipient.as_deref(), &subject, &body)).filter(|url| url.len() <= MAX_MAILTO_CHARS);
+    Ok(EmailDraft { recipient, subject, body, language, mailto_url, eml_path: None, handoff: EmailHandoff::None })
+}
+
+// Open the draft in the default mail client; a mailto: handoff that doesn't fit becomes an .eml file
+pub fn handoff(draft: &mut EmailDraft, how: EmailHandoff) -> Result<(), EmailError> {
+    let how = match (how, &draft.mailto_url) {
+        (EmailHandoff::Mailto, None) => EmailHandoff::Eml,
+        (how, _) => how,
+    };
+    match how {
+        EmailHandoff::None => {}
+        EmailHandoff::Mailto => open_external(draft.mailto_url.as_deref().unwrap_or_default())?,
+        EmailHandoff::Eml => {
+            let path = write_eml(draft)?;
+            open_external(&path.to_string_lossy())?;
+            draft.eml_path = Some(path.to_string_lossy().to_string());
+        }
+    }
+    draft.handoff = how;
+    tracing::info!(target: "app", "Email: Drafted a {} email ({:?} handoff)", draft.language, how);
+    Ok(())
+}
+
+// "Subject: ..." on the first line, the body after it; without one, the whole reply is the body
+fn parse_draft(response: &str) -> (String, String) {
+    let text = response.trim().trim_start_matches("```").trim_end_matches("```").trim();
+    let (first, rest) = text.split_once('\n').unwrap_or((text, ""));
+    // Models like to bold the label: "**Subject:** ..."
+    let first = first.replace("**", "");
+    let first = first.trim();
+    let subject = ["subject:", "विषय:"].iter().find_map(|label| {
+        let head = first.get(..label.len()).filter(|head| head.eq_ignore_ascii_case(label))?;
+        Some(first[head.len()..].trim().trim_matches('"').to_string())
+    });
+    match subject {
+        Some(subject) => (subject, rest.trim().to_string()),
+        None => (String::new(), text.to_string()),
+    }
+}
+
+// A bare address or "Name <address>"; contacts are not looked up
+fn validate_recipient(recipient: &str) -> Result<String, EmailError> {
+    let address = match (recipient.find('<'), recipient.rfind('>')) {
+        (Some(start), Some(end)) if start < end => &recipient[start + 1..end],
+        _ => recipient,
+    };
+    let valid = address.split_once('@').is_some_and(|(local, domain)| {
+        !local.is_empty() && domain.contains('.') && !domain.starts_with('.') && !domain.ends_with('.')
+    }) && !address.chars().any(|c| c.is_whitespace() || c.is_control() || matches!(c, ',' | ';' | '?' | '&' | '"'));
+    if !valid {
+        return Err(EmailError::Invalid(format!("'{}' is not an email address", recipient)));
+    }
+    Ok(address.to_string())
+}
+
+// RFC 6068: spaces as %20 (not '+'), line breaks as %0D%0A
+fn mailto_url(recipient: Option<&str>, subject: &str, body: &str) -> String {
+    let encode = |text: &str| {
+        text.bytes()
+            .map(|b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+                _ => format!("%{:02X}", b),
+            })
+            .collect::<String>()
+    };
+    let body = body.replace("\r\n", "\n").replace('\n', "\r\n");
+    let mut params = Vec::new();
+    if !subject.is_empty() {
+        params.push(format!("subject={}", encode(subject)));
+    }
+    params.push(format!("body={}", encode(&body)));
+    format!("mailto:{}?{}", recipient.map(|r| encode(r).replace("%40", "@")).unwrap_or_default(), params.join("&"))
+}
+
+// Unsent message that Outlook, Thunderbird, and Apple Mail open as a draft
+fn write_eml(draft: &EmailDraft) -> Result<PathBuf, EmailError> {
+    use base64::Engine;
+    let header = |text: &str| {
+        if text.is_ascii() {
+            text.to_string()
+        } else {
+            format!("=?UTF-8?B?{}?=", base64::engine::general_purpose::STANDARD.encode(text))
+        }
+    };
+    let mut message = String::new();
+    if let Some(recipient) = &draft.recipient {
+        message.push_str(&format!("To: {}\r\n", recipient));
+    }
+    message.push_str(&format!("Subject: {}\r\n", header(&draft.subject)));
+    message.push_str("X-Unsent: 1\r\n");
+    message.push_str("MIME-Version: 1.0\r\n");
+    message.push_str("Content-Type: text/plain; charset=UTF-8\r\n");
+    message.push_str("Content-Transfer-Encoding: 8bit\r\n\r\n");
+    message.push_str(&draft.body.replace("\r\n", "\n").replace('\n', "\r\n"));
+    message.push_str("\r\n");
+
+    let path = std::env::temp_dir().join(format!("regen-draft-{}.eml", uuid::Uuid::new_v4()));
+    std::fs::write(&path, message).map_err(|e| EmailError::Io(e.to_string()))?;
+    Ok(path)
+}
+
+fn open_external(target: &str) -> Result<(), EmailError> {
+    use std::process::Command;
+    // `cmd /C start` would split a mailto: link at its '&'
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = Command::new("rundll32");
+        command.args(["url.dll,FileProtocolHandler", target]);
+        command
+    };
+    #[cfg(target_os = "macos")]
+    let mut command = {
+        let mut command = Command::new("open");
+        command.arg(target);
+        command
+    };
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let mut command = {
+        let mut command = Command::new("xdg-open");
+        command.arg(target);
+        command
+    };
+    command.spawn().map(|_| ()).map_err(|e| EmailError::NoMailClient(e.to_string()))
+}
+
+fn truncate_chars(text: &str, max: usize) -> &str {
+    match text.char_indices().nth(max) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum EmailError {
+    Invalid(String),
+    AIError(String),
+    Io(String),
+    NoMailClient(String),
+}
+
+impl std::fmt::Display for EmailError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EmailError::Invalid(msg) => write!(f, "Invalid email request: {}", msg),
+            EmailError::AIError(msg) => write!(f, "Drafting failed: {}", msg),
+            EmailError::Io(msg) => write!(f, "Failed to save the draft: {}", msg),
+            EmailError::NoMailClient(msg) => write!(f, "Could not open a mail client: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for EmailError {}
@@ -0,0 +1,264 @@
+// Embeddings - Text vectors via Ollama, with local fallbacks (MiniLM when built in, else hashed)
+// Vectors are L2-normalized so cosine similarity is a dot product
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+// Model name recorded for vectors from the hashed fallback (never mixed with real models)
+pub const HASHED_MODEL: &str = "hashed-bow-512";
+const HASHED_DIMS: usize = 512;
+const EMBED_TIMEOUT: Duration = Duration::from_secs(60);
+// Inputs are truncated; embedding models have small context windows
+pub const MAX_INPUT_CHARS: usize = 2000;
+// Largest batch accepted from the frontend
+pub const MAX_BATCH: usize = 1024;
+// Texts per /api/embed request, and requests in flight at once
+const OLLAMA_BATCH: usize = 64;
+const OLLAMA_PARALLEL: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingSource {
+    Ollama,
+    Local,                             // In-process MiniLM (feature "local-embeddings")
+    Hashed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct Embeddings {
+    pub model: String,                 // Vectors from different models must never be compared
+    pub source: EmbeddingSource,
+    pub dimensions: usize,
+    pub normalized: bool,              // Always true: unit length, so cosine is a dot product
+    pub vectors: Vec<Vec<f32>>,
+}
+
+impl Embeddings {
+    fn new(model: String, source: EmbeddingSource, vectors: Vec<Vec<f32>>) -> Self {
+        let dimensions = vectors.iter().map(Vec::len).max().unwrap_or(0);
+        Self { model, source, dimensions, normalized: true, vectors }
+    }
+}
+
+#[derive(Serialize)]
+struct OllamaEmbedRequest<'a> {
+    model: &'a str,
+    input: Vec<&'a str>,
+}
+
+#[derive(Deserialize)]
+struct OllamaEmbedResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+// Ollama base URL from config.toml (ai.ollamaUrl)
+pub fn ollama_url() -> String {
+    crate::config::current().ai.ollama_url
+}
+
+// Configured embedding model, else the one sized for this machine (see hardware::model_defaults)
+pub fn default_model() -> String {
+    crate::config::current().ai.embed_model()
+}
+
+// Embed with the default Ollama model, falling back to local vectors when Ollama is unreachable
+pub async fn embed(texts: &[String]) -> Embeddings {
+    embed_with(&default_model(), texts).await
+}
+
+pub async fn embed_with(model: &str, texts: &[String]) -> Embeddings {
+    match embed_ollama(model, texts).await {
+        Ok(vectors) => Embeddings::new(model.to_string(), EmbeddingSource::Ollama, vectors),
+        Err(e) => {
+            tracing::warn!(target: "ai", "Embeddings: Ollama unavailable ({}), using local fallback", e);
+            embed_local(texts).await
+        }
+    }
+}
+
+// The in-process model when built with "local-embeddings" and loadable, else hashed vectors
+pub async fn embed_local(texts: &[String]) -> Embeddings {
+    #[cfg(feature = "local-embeddings")]
+    match crate::local_embeddings::embed(texts).await {
+        Ok(vectors) => return Embeddings::new(crate::local_embeddings::MODEL.to_string(), EmbeddingSource::Local, vectors),
+        Err(e) => tracing::warn!(target: "ai", "Embeddings: Local model unavailable ({}), using hashed fallback", e),
+    }
+    Embeddings::new(HASHED_MODEL.to_string(), EmbeddingSource::Hashed, embed_hashed(texts))
+}
+
+// Embed (key, text) pairs, reusing vectors cached in SQLite; output order matches input
+pub async fn embed_cached(db: &crate::db::Database, items: &[(String, String)]) -> Embeddings {
+    let model = default_model();
+    let keys: Vec<String> = items.iter().map(|(k, _)| k.clone()).collect();
+    let mut cached = db.get_embeddings(&keys, &model).unwrap_or_default();
+
+    let missing: Vec<&(String, String)> = items.iter().filter(|(k, _)| !cached.contains_key(k)).collect();
+    if !missing.is_empty() {
+        let texts: Vec<String> = missing.iter().map(|(_, t)| t.clone()).collect();
+        match embed_ollama(&model, &texts).await {
+            Ok(vectors) => {
+                let fresh: Vec<(String, Vec<f32>)> = missing
+                    .iter()
+                    .map(|(k, _)| k.clone())
+                    .zip(vectors)
+                    .collect();
+                if let Err(e) = db.save_embeddings(&fresh, &model) {
+                    tracing::warn!(target: "ai", "Embeddings: Failed to cache vectors: {}", e);
+                }
+                cached.extend(fresh);
+            }
+            Err(e) => {
+                // Vectors from different models can't be compared, so fall back for the whole batch
+                tracing::warn!(target: "ai", "Embeddings: Ollama unavailable ({}), using local fallback", e);
+                let texts: Vec<String> = items.iter().map(|(_, t)| t.clone()).collect();
+                return embed_local(&texts).await;
+            }
+        }
+    }
+
+    let vectors = keys.iter().map(|k| cached.remove(k).unwrap_or_default()).collect();
+    Embeddings::new(model, EmbeddingSource::Ollama, vectors)
+}
+
+// Batch embedding through Ollama's /api/embed, OLLAMA_BATCH texts per request; output order matches input
+pub async fn embed_ollama(model: &str, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+    if texts.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let client = crate::http::Client::new(EMBED_TIMEOUT).with_retries(1).with_purpose("embeddings");
+    let mut vectors = Vec::with_capacity(texts.len());
+    for group in texts.chunks(OLLAMA_BATCH * OLLAMA_PARALLEL) {
+        let requests: Vec<_> = group.chunks(OLLAMA_BATCH).map(|batch| embed_ollama_batch(&client, model, batch)).collect();
+        for batch in futures::future::try_join_all(requests).await? {
+            vectors.extend(batch);
+        }
+    }
+    Ok(vectors)
+}
+
+async fn embed_ollama_batch(client: &crate::http::Client, model: &str, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+    let request = OllamaEmbedRequest {
+        model,
+        input: texts.iter().map(|t| truncate(t, MAX_INPUT_CHARS)).collect(),
+    };
+    let response = client
+        .send(client.post(format!("{}/api/embed", ollama_url())).json(&request))
+        .await
+        .map_err(|e| EmbeddingError::RequestFailed(e.to_string()))?;
+
+    if !response.status().is_success() {
+        return Err(EmbeddingError::RequestFailed(format!("HTTP {}", response.status().as_u16())));
+    }
+
+    let body: OllamaEmbedResponse = response
+        .json()
+        .await
+        .map_err(|e| EmbeddingError::InvalidResponse(e.to_string()))?;
+    if body.embeddings.len() != texts.len() {
+        return Err(EmbeddingError::InvalidResponse(format!(
+            "expected {} vectors, got {}",
+            texts.len(),
+            body.embeddings.len()
+        )));
+    }
+
+    Ok(body.embeddings.into_iter().map(normalize).collect())
+}
+
+// Feature-hashed bag of words (unigrams + bigrams); crude but deterministic and offline
+pub fn embed_hashed(texts: &[String]) -> Vec<Vec<f32>> {
+    texts
+        .iter()
+        .map(|text| {
+            let mut vector = vec![0f32; HASHED_DIMS];
+            let words: Vec<String> = truncate(text, MAX_INPUT_CHARS)
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|w| w.chars().count() > 1)
+                .map(|w| w.to_lowercase())
+                .collect();
+
+            for (i, word) in words.iter().enumerate() {
+                add_feature(&mut vector, word, 1.0);
+                if let Some(next) = words.get(i + 1) {
+                    add_feature(&mut vector, &format!("{} {}", word, next), 0.5);
+                }
+            }
+            normalize(vector)
+        })
+        .collect()
+}
+
+fn add_feature(vector: &mut [f32], feature: &str, weight: f32) {
+    let hash = fnv1a(feature);
+    let index = (hash % vector.len() as u64) as usize;
+    let sign = if (hash >> 63) == 0 { 1.0 } else { -1.0 };
+    vector[index] += sign * weight;
+}
+
+// FNV-1a: stable across runs and platforms (std's hasher is randomly seeded)
+fn fnv1a(text: &str) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in text.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+// Content-addressed cache key, so edited text never reuses a stale vector
+pub fn content_key(text: &str) -> String {
+    format!("{:016x}:{}", fnv1a(text), text.len())
+}
+
+pub fn normalize(mut vector: Vec<f32>) -> Vec<f32> {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+    vector
+}
+
+pub fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+// Little-endian f32 blob for SQLite storage
+pub fn to_blob(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+pub fn from_blob(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+fn truncate(text: &str, max_chars: usize) -> &str {
+    match text.char_indices().nth(max_chars) {
+        Some((i, _)) => &text[..i],
+        None => text,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum EmbeddingError {
+    RequestFailed(String),
+    InvalidResponse(String),
+    Unavailable(String),
+}
+
+impl std::fmt::Display for EmbeddingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EmbeddingError::RequestFailed(msg) => write!(f, "Embedding request failed: {}", msg),
+            EmbeddingError::InvalidResponse(msg) => write!(f, "Invalid embedding response: {}", msg),
+            EmbeddingError::Unavailable(msg) => write!(f, "Embedding model unavailable: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for EmbeddingError {}
@@ -0,0 +1,337 @@
+// Page Extractor - Fetch + main-content extraction
+// Readability-style scoring over paragraphs, plus page metadata (title, byline, dates)
+
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::tables::{Table, TableSource};
+
+const USER_AGENT: &str = "Mozilla/5.0 (compatible; RegenBrowser/0.3; +https://regen.app)";
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+const MAX_PAGE_BYTES: usize = 5 * 1024 * 1024;
+// colspan/rowspan beyond this are treated as typos or layout tricks
+const MAX_SPAN: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractedPage {
+    pub url: Option<String>,
+    pub title: String,
+    pub byline: Option<String>,
+    pub published_at: Option<String>,
+    pub description: Option<String>,
+    pub site_name: Option<String>,
+    pub language: Option<String>,     // <html lang> attribute, if any
+    pub content_html: String,         // Unsanitized main-content fragment
+    pub text: String,                 // Plain text of the main content
+    pub headings: Vec<String>,
+    pub images: Vec<String>,          // Absolute image URLs inside the main content
+    #[serde(default)]
+    pub tables: Vec<Table>,           // Data tables anywhere on the page, not just the main content
+    pub word_count: usize,
+}
+
+// Fetch a page's HTML
+pub async fn fetch_html(url: &str) -> Result<String, ExtractError> {
+    let client = crate::http::Client::new(FETCH_TIMEOUT).with_user_agent(USER_AGENT).with_purpose("page-fetch");
+    let response = client
+        .send(client.get(url))
+        .await
+        .map_err(|e| ExtractError::FetchFailed(e.to_string()))?;
+
+    if !response.status().is_success() {
+        return Err(ExtractError::HttpStatus(response.status().as_u16()));
+    }
+
+    let body = crate::http::read_body(response, MAX_PAGE_BYTES).await.map_err(|e| match e {
+        crate::http::HttpError::TooLarge(max) => ExtractError::TooLarge(max),
+        e => ExtractError::FetchFailed(e.to_string()),
+    })?;
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+// Fetch and extract in one step
+pub async fn extract_url(url: &str) -> Result<ExtractedPage, ExtractError> {
+    let html = fetch_html(url).await?;
+    Ok(extract(&html, Some(url)))
+}
+
+// Extract main content and metadata from raw HTML
+pub fn extract(html: &str, base_url: Option<&str>) -> ExtractedPage {
+    let document = Html::parse_document(html);
+    let base = base_url.and_then(|u| url::Url::parse(u).ok());
+
+    let title = meta_content(&document, "meta[property='og:title']")
+        .or_else(|| first_text(&document, "title"))
+        .or_else(|| first_text(&document, "h1"))
+        .unwrap_or_default();
+    let byline = meta_content(&document, "meta[name='author']")
+        .or_else(|| meta_content(&document, "meta[property='article:author']"))
+        .or_else(|| first_text(&document, "[rel='author'], .byline, .author"));
+    let published_at = meta_content(&document, "meta[property='article:published_time']")
+        .or_else(|| meta_content(&document, "meta[name='date']"))
+        .or_else(|| first_attr(&document, "time[datetime]", "datetime"));
+    let description = meta_content(&document, "meta[name='description']")
+        .or_else(|| meta_content(&document, "meta[property='og:description']"));
+    let site_name = meta_content(&document, "meta[property='og:site_name']");
+    let language = first_attr(&document, "html[lang]", "lang");
+
+    let content = find_main_content(&document);
+    let (content_html, text, headings, images) = match content {
+        Some(element) => (
+            element.html(),
+            collapse_whitespace(&element.text().collect::<Vec<_>>().join(" ")),
+            collect_texts(element, "h1, h2, h3"),
+            collect_images(element, base.as_ref()),
+        ),
+        None => (String::new(), String::new(), vec![], vec![]),
+    };
+
+    let word_count = text.split_whitespace().count();
+    let tables = collect_tables(&document, base_url);
+
+    ExtractedPage {
+        url: base_url.map(|u| u.to_string()),
+        title: collapse_whitespace(&title),
+        byline,
+        published_at,
+        description,
+        site_name,
+        language,
+        content_html,
+        text,
+        headings,
+        images,
+        tables,
+        word_count,
+    }
+}
+
+// Score paragraph containers (readability-lite) and return the best candidate
+fn find_main_content(document: &Html) -> Option<ElementRef<'_>> {
+    let paragraphs = Selector::parse("p, pre, blockquote, li").unwrap();
+    let mut scores: HashMap<ego_tree::NodeId, f64> = HashMap::new();
+
+    for p in document.select(&paragraphs) {
+        let length = p.text().map(|t| t.trim().len()).sum::<usize>();
+        if length < 25 {
+            continue;
+        }
+        // Commas are a cheap proxy for prose vs. navigation links
+        let commas = p.text().map(|t| t.matches(',').count()).sum::<usize>();
+        let score = 1.0 + commas as f64 + (length as f64 / 100.0).min(3.0);
+
+        let mut ancestor = p.parent().and_then(ElementRef::wrap);
+        for weight in [1.0, 0.5, 0.25] {
+            let Some(element) = ancestor else { break };
+            *scores.entry(element.id()).or_default() += score * weight;
+            ancestor = element.parent().and_then(ElementRef::wrap);
+        }
+    }
+
+    let best = scores
+        .into_iter()
+        .filter_map(|(id, score)| {
+            let element = ElementRef::wrap(document.tree.get(id)?)?;
+            Some((element, score * class_weight(&element)))
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(element, _)| element);
+
+    best.or_else(|| {
+        let fallback = Selector::parse("article, main, [role='main'], body").unwrap();
+        document.select(&fallback).next()
+    })
+}
+
+// Boost semantic containers, penalize page chrome
+fn class_weight(element: &ElementRef) -> f64 {
+    let value = element.value();
+    let hints = format!(
+        "{} {}",
+        value.attr("class").unwrap_or(""),
+        value.attr("id").unwrap_or("")
+    )
+    .to_lowercase();
+
+    let mut weight = match value.name() {
+        "article" | "main" => 1.5,
+        "body" => 0.5,
+        _ => 1.0,
+    };
+    const NEGATIVE: &[&str] = &[
+        "comment", "footer", "sidebar", "nav", "menu", "share", "related", "promo", "banner", "ad-",
+    ];
+    const POSITIVE: &[&str] = &["article", "content", "post", "entry", "story", "body-text"];
+    if NEGATIVE.iter().any(|h| hints.contains(h)) {
+        weight *= 0.2;
+    }
+    if POSITIVE.iter().any(|h| hints.contains(h)) {
+        weight *= 1.25;
+    }
+    weight
+}
+
+fn meta_content(document: &Html, selector: &str) -> Option<String> {
+    first_attr(document, selector, "content")
+}
+
+fn first_attr(document: &Html, selector: &str, attr: &str) -> Option<String> {
+    let selector = Selector::parse(selector).ok()?;
+    document
+        .select(&selector)
+        .filter_map(|e| e.value().attr(attr))
+        .map(|v| v.trim().to_string())
+        .find(|v| !v.is_empty())
+}
+
+fn first_text(document: &Html, selector: &str) -> Option<String> {
+    let selector = Selector::parse(selector).ok()?;
+    document
+        .select(&selector)
+        .map(|e| collapse_whitespace(&e.text().collect::<Vec<_>>().join(" ")))
+        .find(|t| !t.is_empty())
+}
+
+fn collect_texts(element: ElementRef, selector: &str) -> Vec<String> {
+    let selector = Selector::parse(selector).unwrap();
+    element
+        .select(&selector)
+        .map(|e| collapse_whitespace(&e.text().collect::<Vec<_>>().join(" ")))
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+fn collect_images(element: ElementRef, base: Option<&url::Url>) -> Vec<String> {
+    let selector = Selector::parse("img").unwrap();
+    let mut images: Vec<String> = element
+        .select(&selector)
+        .filter_map(|img| {
+            img.value()
+                .attr("src")
+                .or_else(|| img.value().attr("data-src"))
+        })
+        .filter_map(|src| resolve_url(base, src))
+        .collect();
+    images.dedup();
+    images
+}
+
+// Data tables with colspan/rowspan expanded; layout tables (nested, role=presentation, single row or column) are skipped
+fn collect_tables(document: &Html, base_url: Option<&str>) -> Vec<Table> {
+    let table_selector = Selector::parse("table").unwrap();
+    let row_selector = Selector::parse("tr").unwrap();
+    let caption_selector = Selector::parse("caption").unwrap();
+
+    let mut tables = Vec::new();
+    for (index, table) in document.select(&table_selector).enumerate() {
+        if table.value().attr("role") == Some("presentation") || table.select(&table_selector).next().is_some() {
+            continue;
+        }
+        let mut grid: Vec<Vec<String>> = Vec::new();
+        let mut header_rows = 0;
+        // Cells still covered by a rowspan from above: column -> (rows left, text)
+        let mut carried: HashMap<usize, (usize, String)> = HashMap::new();
+        for (i, row) in table.select(&row_selector).enumerate() {
+            let mut cells: Vec<String> = Vec::new();
+            let mut all_headers = true;
+            for cell in row.children().filter_map(ElementRef::wrap).filter(|c| matches!(c.value().name(), "td" | "th")) {
+                fill_carried(&mut cells, &mut carried);
+                all_headers &= cell.value().name() == "th";
+                let span = |attr| cell.value().attr(attr).and_then(|v| v.trim().parse::<usize>().ok()).unwrap_or(1).clamp(1, MAX_SPAN);
+                let text = collapse_whitespace(&cell.text().collect::<Vec<_>>().join(" "));
+                for _ in 0..span("colspan") {
+                    if span("rowspan") > 1 {
+                        carried.insert(cells.len(), (span("rowspan") - 1, text.clone()));
+                    }
+                    cells.push(text.clone());
+                }
+            }
+            // Trailing columns still covered from above
+            fill_carried(&mut cells, &mut carried);
+            let in_head = row.parent().and_then(ElementRef::wrap).is_some_and(|p| p.value().name() == "thead");
+            if i == header_rows && (in_head || all_headers) && !cells.is_empty() {
+                header_rows += 1;
+            }
+            if cells.iter().any(|c| !c.is_empty()) {
+                grid.push(cells);
+            }
+        }
+        if grid.len() < 2 || grid.iter().all(|r| r.len() < 2) {
+            continue;
+        }
+
+        // Stacked header rows collapse into one label per column
+        let header_rows = header_rows.min(grid.len() - 1);
+        let headers: Vec<String> = match header_rows {
+            0 => Vec::new(),
+            n => {
+                let width = grid[..n].iter().map(|r| r.len()).max().unwrap_or(0);
+                (0..width)
+                    .map(|c| {
+                        let mut parts: Vec<&str> = grid[..n].iter().filter_map(|r| r.get(c).map(String::as_str)).filter(|t| !t.is_empty()).collect();
+                        parts.dedup();
+                        parts.join(" ")
+                    })
+                    .collect()
+            }
+        };
+        let caption = table
+            .select(&caption_selector)
+            .next()
+            .map(|c| collapse_whitespace(&c.text().collect::<Vec<_>>().join(" ")))
+            .filter(|c| !c.is_empty());
+        let rows = grid.split_off(header_rows);
+        let source = TableSource::Html { url: base_url.map(|u| u.to_string()), index };
+        tables.push(Table::new(source, caption, headers, rows));
+    }
+    tables
+}
+
+// Copy rowspan cells from earlier rows into the columns they still cover at the end of `cells`
+fn fill_carried(cells: &mut Vec<String>, carried: &mut HashMap<usize, (usize, String)>) {
+    while let Some((left, text)) = carried.get_mut(&cells.len()) {
+        cells.push(text.clone());
+        *left -= 1;
+        if *left == 0 {
+            carried.remove(&(cells.len() - 1));
+        }
+    }
+}
+
+// Resolve a possibly-relative URL against the page URL
+pub fn resolve_url(base: Option<&url::Url>, href: &str) -> Option<String> {
+    let href = href.trim();
+    if href.is_empty() || href.starts_with("data:") || href.starts_with("javascript:") {
+        return None;
+    }
+    match base {
+        Some(base) => base.join(href).ok().map(|u| u.to_string()),
+        None => url::Url::parse(href).ok().map(|u| u.to_string()),
+    }
+}
+
+pub fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[derive(Debug, Clone)]
+pub enum ExtractError {
+    FetchFailed(String),
+    HttpStatus(u16),
+    TooLarge(usize),                   // The limit that was passed (bytes)
+}
+
+impl std::fmt::Display for ExtractError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExtractError::FetchFailed(msg) => write!(f, "Failed to fetch page: {}", msg),
+            ExtractError::HttpStatus(code) => write!(f, "Page returned HTTP {}", code),
+            ExtractError::TooLarge(max) => write!(f, "Page too large (over {} bytes)", max),
+        }
+    }
+}
+
+impl std::error::Error for ExtractError {}
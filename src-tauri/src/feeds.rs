@@ -0,0 +1,687 @@
+// Feeds - RSS/Atom subscriptions refreshed in the background, with discovery on visited sites
+// Refreshes are conditional (ETag/Last-Modified); recent entries are a local search provider for Research mode
+
+use futures::stream::{self, StreamExt};
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tauri::Emitter;
+use tokio::sync::mpsc;
+
+use crate::db::Database;
+use crate::extractor::collapse_whitespace;
+
+const USER_AGENT: &str = "Mozilla/5.0 (compatible; RegenBrowser/0.3; +https://regen.app)";
+const FETCH_TIMEOUT: Duration = Duration::from_secs(20);
+const MAX_FEED_BYTES: usize = 5 * 1024 * 1024;
+const CONCURRENT_REFRESHES: usize = 4;
+// First refresh waits for startup to settle; later ones follow feeds.refreshMinutes
+const STARTUP_DELAY: Duration = Duration::from_secs(45);
+const TICK: Duration = Duration::from_secs(60);
+// A site is checked for feed links at most this often
+const DISCOVERY_RECHECK_SECS: i64 = 7 * 86_400;
+const SUMMARY_CHARS: usize = 1000;
+const DEFAULT_ENTRIES: usize = 50;
+const MAX_ENTRIES: usize = 500;
+// Entries published within this window are offered as Research sources
+const FRESH_SECS: i64 = 14 * 86_400;
+const MAX_CANDIDATES: usize = 5000;
+// Query words shorter than this match too much to rank on
+const MIN_TERM_CHARS: usize = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct Feed {
+    pub id: String,
+    pub url: String,                   // The feed document itself
+    pub site_url: Option<String>,
+    pub title: String,
+    pub description: Option<String>,
+    #[serde(skip)]
+    pub etag: Option<String>,
+    #[serde(skip)]
+    pub last_modified: Option<String>,
+    pub last_fetched_at: Option<i64>,  // Unix seconds, last successful fetch (304 included)
+    #[serde(skip)]
+    pub last_attempt_at: Option<i64>,  // Failed fetches too; refreshes are spaced from this
+    pub last_error: Option<String>,
+    pub created_at: i64,
+    pub unread: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct FeedEntry {
+    pub id: String,
+    pub feed_id: String,
+    pub feed_title: String,
+    pub url: Option<String>,
+    pub title: String,
+    pub summary: Option<String>,       // Plain text, shortened
+    pub author: Option<String>,
+    pub published_at: Option<i64>,     // Unix seconds; fetch time when the feed gives none
+    pub read: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct EntryFilter {
+    pub feed_id: Option<String>,
+    pub unread_only: bool,
+    pub query: Option<String>,         // Substring of the title or summary
+    pub since: Option<i64>,            // Unix seconds
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveredFeed {
+    pub url: String,
+    pub title: Option<String>,
+    pub kind: FeedKind,
+    pub subscribed: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum FeedKind {
+    Rss,
+    Atom,
+}
+
+// A fetched feed document before it is stored
+#[derive(Debug, Clone, Default)]
+pub struct ParsedFeed {
+    pub title: String,
+    pub site_url: Option<String>,
+    pub description: Option<String>,
+    pub entries: Vec<ParsedEntry>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ParsedEntry {
+    pub guid: String,
+    pub url: Option<String>,
+    pub title: String,
+    pub summary: Option<String>,
+    pub author: Option<String>,
+    pub published_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshResult {
+    pub feed_id: String,
+    pub new_entries: usize,
+    pub not_modified: bool,
+    pub error: Option<String>,
+}
+
+static DB: OnceLock<Database> = OnceLock::new();
+
+#[derive(Clone)]
+pub struct Feeds {
+    visits: mpsc::UnboundedSender<String>,
+}
+
+impl Feeds {
+    // Starts the refresh loop and the discovery worker; emits "feeds:updated" after refreshes that found entries
+    pub fn start(app: tauri::AppHandle, db: Database) -> Self {
+        let _ = DB.set(db.clone());
+        let (visits, rx) = mpsc::unbounded_channel();
+        tauri::async_runtime::spawn(discovery_worker(app.clone(), db.clone(), rx));
+        tauri::async_runtime::spawn(refresh_loop(app, db));
+        Self { visits }
+    }
+
+    // A page visited with history on; its site is checked for feed links unless checked recently
+    pub fn visited(&self, url: &str) {
+        if crate::config::current().feeds.discover {
+            let _ = self.visits.send(url.to_string());
+        }
+    }
+}
+
+// Subscribe to a feed URL, or to the first feed a page links to; the first fetch is stored right away
+pub async fn subscribe(db: &Database, url: &str) -> Result<Feed, FeedError> {
+    let url = url.trim();
+    if !matches!(url::Url::parse(url).map(|u| u.scheme().to_string()).as_deref(), Ok("http" | "https")) {
+        return Err(FeedError::InvalidUrl(url.to_string()));
+    }
+    if let Some(feed) = db.feed_by_url(url).map_err(storage)? {
+        return Ok(feed);
+    }
+
+    let fetched = fetch(url, None, None).await?.ok_or_else(|| FeedError::Fetch("no content".to_string()))?;
+    let (feed_url, fetched, parsed) = match parse(&fetched.body, url) {
+        Ok(parsed) => (url.to_string(), fetched, parsed),
+        Err(FeedError::NotAFeed) => {
+            // A web page: follow its <link rel="alternate"> feed
+            let link = discover_links(&fetched.body, url).into_iter().next().ok_or(FeedError::NotAFeed)?;
+            if let Some(feed) = db.feed_by_url(&link.url).map_err(storage)? {
+                return Ok(feed);
+            }
+            let fetched = fetch(&link.url, None, None).await?.ok_or_else(|| FeedError::Fetch("no content".to_string()))?;
+            let parsed = parse(&fetched.body, &link.url)?;
+            (link.url, fetched, parsed)
+        }
+        Err(e) => return Err(e),
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    let feed = Feed {
+        id: uuid::Uuid::new_v4().to_string(),
+        url: feed_url.clone(),
+        site_url: parsed.site_url.clone(),
+        title: if parsed.title.is_empty() { host(&feed_url) } else { parsed.title.clone() },
+        description: parsed.description.clone(),
+        etag: fetched.etag,
+        last_modified: fetched.last_modified,
+        last_fetched_at: Some(now),
+        last_attempt_at: Some(now),
+        last_error: None,
+        created_at: now,
+        unread: 0,
+    };
+    db.feed_save(&feed).map_err(storage)?;
+    let added = db.feed_add_entries(&feed.id, &parsed.entries, now).map_err(storage)?;
+    prune(db, &feed.id);
+    tracing::info!(target: "app", "Feeds: Subscribed to {} ({} entries)", feed.url, added);
+    Ok(Feed { unread: added, ..feed })
+}
+
+// Fetch one feed with its stored validators and store any new entries
+pub async fn refresh(db: &Database, feed: &Feed) -> RefreshResult {
+    let mut result = RefreshResult { feed_id: feed.id.clone(), new_entries: 0, not_modified: false, error: None };
+    let now = chrono::Utc::now().timestamp();
+    let outcome = match fetch(&feed.url, feed.etag.as_deref(), feed.last_modified.as_deref()).await {
+        Ok(None) => {
+            result.not_modified = true;
+            db.feed_fetched(&feed.id, now, feed.etag.as_deref(), feed.last_modified.as_deref())
+        }
+        Ok(Some(fetched)) => match parse(&fetched.body, &feed.url) {
+            Ok(parsed) => db.feed_add_entries(&feed.id, &parsed.entries, now).and_then(|added| {
+                result.new_entries = added;
+                db.feed_fetched(&feed.id, now, fetched.etag.as_deref(), fetched.last_modified.as_deref())
+            }),
+            Err(e) => {
+                result.error = Some(e.to_string());
+                db.feed_failed(&feed.id, now, &e.to_string())
+            }
+        },
+        Err(e) => {
+            result.error = Some(e.to_string());
+            db.feed_failed(&feed.id, now, &e.to_string())
+        }
+    };
+    if let Err(e) = outcome {
+        result.error = Some(e.to_string());
+    }
+    if result.new_entries > 0 {
+        prune(db, &feed.id);
+    }
+    result
+}
+
+// Refresh several feeds a few at a time
+pub async fn refresh_all(db: &Database, feeds: Vec<Feed>) -> Vec<RefreshResult> {
+    stream::iter(feeds)
+        .map(|feed| async move { refresh(db, &feed).await })
+        .buffer_unordered(CONCURRENT_REFRESHES)
+        .collect()
+        .await
+}
+
+async fn refresh_loop(app: tauri::AppHandle, db: Database) {
+    tokio::time::sleep(STARTUP_DELAY).await;
+    loop {
+        let interval = i64::from(crate::config::current().feeds.refresh_minutes) * 60;
+        if interval > 0 && !crate::modes::background_paused() && !crate::connectivity::is_offline() {
+            let cutoff = chrono::Utc::now().timestamp() - interval;
+            match db.feeds_due(cutoff) {
+                Ok(due) if !due.is_empty() => {
+                    let results = refresh_all(&db, due).await;
+                    let added: usize = results.iter().map(|r| r.new_entries).sum();
+                    tracing::debug!(target: "app", "Feeds: Refreshed {} feeds, {} new entries", results.len(), added);
+                    if added > 0 {
+                        let _ = app.emit("feeds:updated", &results);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!(target: "db", "Feeds: Failed to list feeds due for refresh: {}", e),
+            }
+        }
+        tokio::time::sleep(TICK).await;
+    }
+}
+
+async fn discovery_worker(app: tauri::AppHandle, db: Database, mut rx: mpsc::UnboundedReceiver<String>) {
+    // Sites whose fetch failed aren't retried until the next start
+    let mut tried = HashSet::new();
+    while let Some(url) = rx.recv().await {
+        let Some(origin) = origin(&url) else { continue };
+        if tried.contains(&origin) {
+            continue;
+        }
+        let now = chrono::Utc::now().timestamp();
+        match db.feed_site_checked_at(&origin) {
+            Ok(Some(checked_at)) if now - checked_at < DISCOVERY_RECHECK_SECS => continue,
+            Ok(_) => {}
+            Err(e) => {
+                tracing::warn!(target: "db", "Feeds: Failed to look up {}: {}", origin, e);
+                continue;
+            }
+        }
+        if crate::modes::background_paused() || crate::connectivity::is_offline() {
+            continue;
+        }
+        tried.insert(origin.clone());
+        // Feed links are in the <head> of every page, so the visited page itself is enough
+        let links = match crate::extractor::fetch_html(&url).await {
+            Ok(html) => discover_links(&html, &url),
+            Err(e) => {
+                tracing::debug!(target: "app", "Feeds: Discovery fetch of {} failed: {}", url, e);
+                continue;
+            }
+        };
+        if let Err(e) = db.feed_site_save(&origin, &links, now) {
+            tracing::warn!(target: "db", "Feeds: Failed to save feeds found on {}: {}", origin, e);
+            continue;
+        }
+        if !links.is_empty() {
+            let _ = app.emit("feeds:discovered", serde_json::json!({ "site": origin, "feeds": links }));
+        }
+    }
+}
+
+// Feeds found on a site, marking the ones already subscribed
+pub fn discovered(db: &Database, url: &str) -> Result<Vec<DiscoveredFeed>, FeedError> {
+    let Some(origin) = origin(url) else { return Ok(Vec::new()) };
+    let mut links = db.feed_site_links(&origin).map_err(storage)?;
+    for link in &mut links {
+        link.subscribed = db.feed_by_url(&link.url).map_err(storage)?.is_some();
+    }
+    Ok(links)
+}
+
+// <link rel="alternate"> feeds declared by an HTML page
+pub fn discover_links(html: &str, base_url: &str) -> Vec<DiscoveredFeed> {
+    let document = Html::parse_document(html);
+    let base = url::Url::parse(base_url).ok();
+    let selector = Selector::parse("link[rel~='alternate'][href], link[rel~='feed'][href]").unwrap();
+    let mut seen = HashSet::new();
+    document
+        .select(&selector)
+        .filter_map(|link| {
+            let kind = match link.value().attr("type").unwrap_or_default().trim().to_lowercase().as_str() {
+                "application/rss+xml" | "application/rdf+xml" => FeedKind::Rss,
+                "application/atom+xml" => FeedKind::Atom,
+                _ => return None,
+            };
+            let url = crate::extractor::resolve_url(base.as_ref(), link.value().attr("href")?)?;
+            // Comment feeds ride along on most blog posts
+            let title = link.value().attr("title").map(collapse_whitespace).filter(|t| !t.is_empty());
+            if title.as_deref().is_some_and(|t| t.to_lowercase().contains("comments")) || !seen.insert(url.clone()) {
+                return None;
+            }
+            Some(DiscoveredFeed { url, title, kind, subscribed: false })
+        })
+        .collect()
+}
+
+// Recently published entries matching the query, best match first; backs the "feeds" search provider
+pub fn search_recent(query: &str, limit: usize) -> Result<Vec<FeedEntry>, FeedError> {
+    let Some(db) = DB.get() else { return Ok(Vec::new()) };
+    let terms: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| t.chars().count() >= MIN_TERM_CHARS)
+        .map(str::to_lowercase)
+        .collect();
+    if terms.is_empty() {
+        return Ok(Vec::new());
+    }
+    let since = chrono::Utc::now().timestamp() - FRESH_SECS;
+    let mut scored: Vec<(usize, FeedEntry)> = db
+        .feed_entries_since(since, MAX_CANDIDATES)
+        .map_err(storage)?
+        .into_iter()
+        .map(|entry| {
+            let text = format!("{} {}", entry.title, entry.summary.as_deref().unwrap_or_default()).to_lowercase();
+            (terms.iter().filter(|t| text.contains(t.as_str())).count(), entry)
+        })
+        .filter(|(matched, _)| *matched > 0)
+        .collect();
+    // Entries come newest first, and the sort is stable
+    scored.sort_by(|a, b| b.0.cmp(&a.0));
+    Ok(scored.into_iter().take(limit).map(|(_, entry)| entry).collect())
+}
+
+pub fn list_entries(db: &Database, filter: &EntryFilter) -> Result<Vec<FeedEntry>, FeedError> {
+    let limit = filter.limit.unwrap_or(DEFAULT_ENTRIES).clamp(1, MAX_ENTRIES);
+    db.feed_list_entries(filter, limit).map_err(storage)
+}
+
+// Keep the newest entries per feed (feeds.keepEntries); unread ones are kept regardless
+fn prune(db: &Database, feed_id: &str) {
+    let keep = crate::config::current().feeds.keep_entries;
+    match db.feed_prune_entries(feed_id, keep) {
+        Ok(0) => {}
+        Ok(n) => tracing::debug!(target: "db", "Feeds: Pruned {} old entries", n),
+        Err(e) => tracing::warn!(target: "db", "Feeds: Failed to prune entries: {}", e),
+    }
+}
+
+// ============================================================================
+// FETCH
+// ============================================================================
+
+struct Fetched {
+    body: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+// Ok(None) when the server answers 304 Not Modified
+async fn fetch(url: &str, etag: Option<&str>, last_modified: Option<&str>) -> Result<Option<Fetched>, FeedError> {
+    let client = crate::http::Client::new(FETCH_TIMEOUT).with_user_agent(USER_AGENT).with_purpose("feeds");
+    let mut request = client.get(url).header(
+        reqwest::header::ACCEPT,
+        "application/rss+xml, application/atom+xml, application/xml;q=0.9, text/xml;q=0.9, text/html;q=0.5, */*;q=0.1",
+    );
+    if let Some(etag) = etag {
+        request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = last_modified {
+        request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+    }
+    let response = client.send(request).await.map_err(|e| FeedError::Fetch(e.to_string()))?;
+    if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        return Err(FeedError::HttpStatus(response.status().as_u16()));
+    }
+    let header = |name| response.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+    let etag = header(reqwest::header::ETAG);
+    let last_modified = header(reqwest::header::LAST_MODIFIED);
+    let body = crate::http::read_body(response, MAX_FEED_BYTES).await.map_err(|e| match e {
+        crate::http::HttpError::TooLarge(max) => FeedError::TooLarge(max),
+        e => FeedError::Fetch(e.to_string()),
+    })?;
+    Ok(Some(Fetched { body: String::from_utf8_lossy(&body).into_owned(), etag, last_modified }))
+}
+
+fn origin(url: &str) -> Option<String> {
+    let url = url::Url::parse(url).ok()?;
+    matches!(url.scheme(), "http" | "https").then(|| url.origin().ascii_serialization())
+}
+
+fn host(url: &str) -> String {
+    url::Url::parse(url).ok().and_then(|u| u.host_str().map(str::to_string)).unwrap_or_else(|| url.to_string())
+}
+
+fn storage(e: rusqlite::Error) -> FeedError {
+    FeedError::Storage(e.to_string())
+}
+
+// ============================================================================
+// PARSING
+// ============================================================================
+
+// RSS 0.9x/1.0/2.0 or Atom 1.0; relative links resolve against the feed URL
+pub fn parse(xml: &str, feed_url: &str) -> Result<ParsedFeed, FeedError> {
+    let base = url::Url::parse(feed_url).ok();
+    let link = |href: &str| crate::extractor::resolve_url(base.as_ref(), href);
+
+    if find_tag(xml, "rss").or_else(|| find_tag(xml, "rdf:RDF")).is_some() {
+        let header = &xml[..find_tag(xml, "item").unwrap_or(xml.len())];
+        let entries = elements(xml, "item")
+            .into_iter()
+            .map(|(attrs, item)| {
+                let url = element(item, "link").and_then(|(_, href)| link(&text(href)));
+                let title = element(item, "title").map(|(_, t)| plain(t)).unwrap_or_default();
+                // RSS 1.0 identifies items by rdf:about
+                let guid = element(item, "guid")
+                    .map(|(_, g)| text(g))
+                    .or_else(|| attr(attrs, "rdf:about"))
+                    .filter(|g| !g.is_empty());
+                ParsedEntry {
+                    guid: guid.or_else(|| url.clone()).unwrap_or_else(|| title.clone()),
+                    url,
+                    summary: element(item, "description").or_else(|| element(item, "content:encoded")).map(|(_, s)| summary(s)),
+                    author: element(item, "dc:creator").or_else(|| element(item, "author")).map(|(_, a)| plain(a)),
+                    published_at: element(item, "pubDate").or_else(|| element(item, "dc:date")).and_then(|(_, d)| date(d)),
+                    title,
+                }
+            })
+            .collect();
+        return Ok(ParsedFeed {
+            title: element(header, "title").map(|(_, t)| plain(t)).unwrap_or_default(),
+            site_url: element(header, "link").and_then(|(_, href)| link(&text(href))),
+            description: element(header, "description").map(|(_, s)| plain(s)).filter(|s| !s.is_empty()),
+            entries,
+        });
+    }
+
+    if find_tag(xml, "feed").is_some() {
+        let header = &xml[..find_tag(xml, "entry").unwrap_or(xml.len())];
+        let entries = elements(xml, "entry")
+            .into_iter()
+            .map(|(_, entry)| {
+                let url = atom_link(entry).and_then(|href| link(&href));
+                let title = element(entry, "title").map(|(_, t)| plain(t)).unwrap_or_default();
+                let guid = element(entry, "id").map(|(_, id)| text(id)).filter(|id| !id.is_empty());
+                ParsedEntry {
+                    guid: guid.or_else(|| url.clone()).unwrap_or_else(|| title.clone()),
+                    url,
+                    summary: element(entry, "summary").or_else(|| element(entry, "content")).map(|(_, s)| summary(s)),
+                    author: element(entry, "author").and_then(|(_, a)| element(a, "name")).map(|(_, n)| plain(n)),
+                    published_at: element(entry, "published").or_else(|| element(entry, "updated")).and_then(|(_, d)| date(d)),
+                    title,
+                }
+            })
+            .collect();
+        return Ok(ParsedFeed {
+            title: element(header, "title").map(|(_, t)| plain(t)).unwrap_or_default(),
+            site_url: atom_link(header).and_then(|href| link(&href)),
+            description: element(header, "subtitle").map(|(_, s)| plain(s)).filter(|s| !s.is_empty()),
+            entries,
+        });
+    }
+
+    Err(FeedError::NotAFeed)
+}
+
+// First element named `name` in `xml`: (start tag attributes, inner content)
+fn element<'a>(xml: &'a str, name: &str) -> Option<(&'a str, &'a str)> {
+    elements_from(xml, name, true).into_iter().next()
+}
+
+fn elements<'a>(xml: &'a str, name: &str) -> Vec<(&'a str, &'a str)> {
+    elements_from(xml, name, false)
+}
+
+fn elements_from<'a>(xml: &'a str, name: &str, first_only: bool) -> Vec<(&'a str, &'a str)> {
+    let open = format!("<{}", name);
+    let close = format!("</{}", name);
+    let mut found = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        let after = &rest[start + open.len()..];
+        // "<link" must not match "<linkedin"
+        if !after.starts_with(|c: char| c.is_whitespace() || c == '>' || c == '/') {
+            rest = after;
+            continue;
+        }
+        let Some(tag_end) = after.find('>') else { break };
+        let attrs = &after[..tag_end];
+        if attrs.ends_with('/') {
+            found.push((attrs.trim_end_matches('/'), ""));
+            rest = &after[tag_end + 1..];
+        } else {
+            let body = &after[tag_end + 1..];
+            let Some(end) = body.find(&close) else { break };
+            found.push((attrs, &body[..end]));
+            rest = &body[end + close.len()..];
+        }
+        if first_only {
+            break;
+        }
+    }
+    found
+}
+
+// Offset of the first start tag named `name`; channel fields are read only before the first item
+fn find_tag(xml: &str, name: &str) -> Option<usize> {
+    let open = format!("<{}", name);
+    let mut at = 0;
+    while let Some(i) = xml[at..].find(&open) {
+        let start = at + i;
+        if xml[start + open.len()..].starts_with(|c: char| c.is_whitespace() || c == '>' || c == '/') {
+            return Some(start);
+        }
+        at = start + open.len();
+    }
+    None
+}
+
+// Atom <link> with rel="alternate" (the default) and an href
+fn atom_link(xml: &str) -> Option<String> {
+    elements(xml, "link")
+        .into_iter()
+        .filter(|(attrs, _)| attr(attrs, "rel").is_none_or(|rel| rel == "alternate"))
+        .find_map(|(attrs, _)| attr(attrs, "href"))
+}
+
+fn attr(attrs: &str, name: &str) -> Option<String> {
+    let mut rest = attrs;
+    while let Some(i) = rest.find(name) {
+        let preceded = rest[..i].ends_with(|c: char| c.is_whitespace());
+        let after = rest[i + name.len()..].trim_start();
+        if let (true, Some(value)) = (preceded, after.strip_prefix('=')) {
+            let value = value.trim_start();
+            let quote = value.chars().next()?;
+            if quote == '"' || quote == '\'' {
+                let value = &value[1..];
+                return value.find(quote).map(|end| text(&value[..end]));
+            }
+        }
+        rest = &rest[i + name.len()..];
+    }
+    None
+}
+
+// Character data with CDATA sections unwrapped and entities decoded
+fn text(inner: &str) -> String {
+    let mut out = String::with_capacity(inner.len());
+    let mut rest = inner;
+    while let Some(start) = rest.find("<![CDATA[") {
+        out.push_str(&unescape(&rest[..start]));
+        let after = &rest[start + 9..];
+        let end = after.find("]]>").unwrap_or(after.len());
+        out.push_str(&after[..end]);
+        rest = after.get(end + 3..).unwrap_or_default();
+    }
+    out.push_str(&unescape(rest));
+    out.trim().to_string()
+}
+
+// Titles and names; some feeds escape HTML inside them
+fn plain(inner: &str) -> String {
+    let decoded = text(inner);
+    if !decoded.contains(['<', '&']) {
+        return collapse_whitespace(&decoded);
+    }
+    collapse_whitespace(&Html::parse_fragment(&decoded).root_element().text().collect::<String>())
+}
+
+fn summary(inner: &str) -> String {
+    let text = plain(inner);
+    match text.char_indices().nth(SUMMARY_CHARS) {
+        Some((i, _)) => format!("{}…", text[..i].trim_end()),
+        None => text,
+    }
+}
+
+// RSS uses RFC 822 dates, Atom and Dublin Core RFC 3339
+fn date(inner: &str) -> Option<i64> {
+    let raw = text(inner);
+    chrono::DateTime::parse_from_rfc2822(&raw)
+        .or_else(|_| chrono::DateTime::parse_from_rfc3339(&raw))
+        .ok()
+        .map(|d| d.timestamp())
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let after = &rest[amp..];
+        let Some(semi) = after.find(';').filter(|&i| i <= 10) else {
+            out.push('&');
+            rest = &after[1..];
+            continue;
+        };
+        let decoded = match &after[1..semi] {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            entity => match entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok().and_then(char::from_u32),
+                None => entity.strip_prefix('#').and_then(|n| n.parse().ok()).and_then(char::from_u32),
+            },
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &after[semi + 1..];
+            }
+            // HTML entities (&nbsp;) stay for the HTML pass in plain()
+            None => {
+                out.push('&');
+                rest = &after[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[derive(Debug, Clone)]
+pub enum FeedError {
+    InvalidUrl(String),
+    Fetch(String),
+    HttpStatus(u16),
+    TooLarge(usize),
+    NotAFeed,
+    NotFound(String),
+    Storage(String),
+}
+
+impl std::fmt::Display for FeedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FeedError::InvalidUrl(url) => write!(f, "Not an http(s) URL: {}", url),
+            FeedError::Fetch(msg) => write!(f, "Failed to fetch feed: {}", msg),
+            FeedError::HttpStatus(code) => write!(f, "Feed returned HTTP {}", code),
+            FeedError::TooLarge(max) => write!(f, "Feed too large (over {} bytes)", max),
+            FeedError::NotAFeed => write!(f, "Not an RSS or Atom feed, and the page links to none"),
+            FeedError::NotFound(id) => write!(f, "No such feed: {}", id),
+            FeedError::Storage(msg) => write!(f, "Feed storage error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for FeedError {}
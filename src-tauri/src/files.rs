@@ -0,0 +1,201 @@
+// Files - Native open/save dialogs, path handles, and recent files per document type
+// Dialog results reach the webview as opaque handles ("file:<uuid>") that the file commands (process_pdf,
+// image_inspect, session_export, ...) resolve back to the path, so a page only touches files the user picked.
+// Picked files are remembered in SQLite per document type for Docs mode's recent list.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{OnceLock, RwLock};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::db::Database;
+
+const HANDLE_PREFIX: &str = "file:";
+pub const MAX_RECENT_PER_TYPE: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum DocType {
+    Pdf,
+    Document,                          // DOCX, spreadsheets, plain text
+    Ebook,
+    Image,
+    Other,
+}
+
+impl DocType {
+    pub fn from_path(path: &Path) -> Self {
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_ascii_lowercase();
+        match extension.as_str() {
+            "pdf" => DocType::Pdf,
+            "docx" | "doc" | "odt" | "rtf" | "txt" | "md" | "csv" | "tsv" | "xlsx" | "xls" | "ods" => DocType::Document,
+            "epub" => DocType::Ebook,
+            "png" | "jpg" | "jpeg" | "gif" | "webp" | "bmp" | "tif" | "tiff" | "heic" => DocType::Image,
+            _ => DocType::Other,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DocType::Pdf => "pdf",
+            DocType::Document => "document",
+            DocType::Ebook => "ebook",
+            DocType::Image => "image",
+            DocType::Other => "other",
+        }
+    }
+}
+
+// One entry in the dialog's file type list, e.g. { name: "PDF", extensions: ["pdf"] }
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct FileFilter {
+    pub name: String,
+    pub extensions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct FileHandle {
+    pub handle: String,                // Pass as `path` to the file commands
+    pub name: String,                  // File name only; the full path stays in the backend
+    pub doc_type: DocType,
+    pub size: Option<u64>,             // None for a save target that doesn't exist yet
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct RecentFile {
+    #[serde(flatten)]
+    pub file: FileHandle,
+    pub opened_at: i64,
+    pub exists: bool,                  // False once the file was moved or deleted
+}
+
+// Handles live for the session; a file keeps the same handle each time it is picked or listed
+#[derive(Default)]
+struct Handles {
+    paths: HashMap<String, String>,    // handle -> path
+    handles: HashMap<String, String>,  // path -> handle
+}
+
+fn handles() -> &'static RwLock<Handles> {
+    static HANDLES: OnceLock<RwLock<Handles>> = OnceLock::new();
+    HANDLES.get_or_init(Default::default)
+}
+
+// Native open dialog; None when the user cancels
+pub async fn open(app: &tauri::AppHandle, db: &Database, filters: &[FileFilter]) -> Result<Option<FileHandle>, FileError> {
+    let mut dialog = app.dialog().file();
+    for filter in filters {
+        let extensions: Vec<&str> = filter.extensions.iter().map(|e| e.trim_start_matches('.')).collect();
+        dialog = dialog.add_filter(&filter.name, &extensions);
+    }
+    let picked = tauri::async_runtime::spawn_blocking(move || dialog.blocking_pick_file())
+        .await
+        .map_err(|e| FileError::Dialog(e.to_string()))?;
+    match picked {
+        Some(path) => {
+            let path = path.into_path().map_err(|e| FileError::Dialog(e.to_string()))?;
+            Ok(Some(remember(db, &path.to_string_lossy())?))
+        }
+        None => Ok(None),
+    }
+}
+
+// Native save dialog; None when the user cancels
+pub async fn save(app: &tauri::AppHandle, db: &Database, default_name: Option<&str>) -> Result<Option<FileHandle>, FileError> {
+    let mut dialog = app.dialog().file();
+    if let Some(name) = default_name.filter(|name| !name.trim().is_empty()) {
+        dialog = dialog.set_file_name(name);
+    }
+    let picked = tauri::async_runtime::spawn_blocking(move || dialog.blocking_save_file())
+        .await
+        .map_err(|e| FileError::Dialog(e.to_string()))?;
+    match picked {
+        Some(path) => {
+            let path = path.into_path().map_err(|e| FileError::Dialog(e.to_string()))?;
+            Ok(Some(remember(db, &path.to_string_lossy())?))
+        }
+        None => Ok(None),
+    }
+}
+
+// Path behind a handle; anything that isn't a handle is taken as a plain path, as the file commands always have
+pub fn resolve(path: &str) -> Result<String, FileError> {
+    if !path.starts_with(HANDLE_PREFIX) {
+        return Ok(path.to_string());
+    }
+    handles().read().unwrap().paths.get(path).cloned().ok_or_else(|| FileError::UnknownHandle(path.to_string()))
+}
+
+pub fn recent(db: &Database, doc_type: Option<DocType>, limit: usize) -> Result<Vec<RecentFile>, FileError> {
+    let rows = db
+        .recent_files_list(doc_type.map(|t| t.as_str()), limit)
+        .map_err(|e| FileError::Storage(e.to_string()))?;
+    Ok(rows
+        .into_iter()
+        .map(|(path, opened_at)| {
+            let exists = Path::new(&path).exists();
+            RecentFile { file: handle_for(&path), opened_at, exists }
+        })
+        .collect())
+}
+
+// Forget recent files of one type, or all of them
+pub fn clear_recent(db: &Database, doc_type: Option<DocType>) -> Result<usize, FileError> {
+    db.recent_files_clear(doc_type.map(|t| t.as_str())).map_err(|e| FileError::Storage(e.to_string()))
+}
+
+fn remember(db: &Database, path: &str) -> Result<FileHandle, FileError> {
+    let handle = handle_for(path);
+    db.recent_files_touch(path, handle.doc_type.as_str(), chrono::Utc::now().timestamp(), MAX_RECENT_PER_TYPE)
+        .map_err(|e| FileError::Storage(e.to_string()))?;
+    Ok(handle)
+}
+
+fn handle_for(path: &str) -> FileHandle {
+    let handle = {
+        let mut handles = handles().write().unwrap();
+        match handles.handles.get(path) {
+            Some(handle) => handle.clone(),
+            None => {
+                let handle = format!("{}{}", HANDLE_PREFIX, uuid::Uuid::new_v4());
+                handles.handles.insert(path.to_string(), handle.clone());
+                handles.paths.insert(handle.clone(), path.to_string());
+                handle
+            }
+        }
+    };
+    let file = Path::new(path);
+    FileHandle {
+        handle,
+        name: file.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_else(|| path.to_string()),
+        doc_type: DocType::from_path(file),
+        size: std::fs::metadata(file).ok().filter(|m| m.is_file()).map(|m| m.len()),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum FileError {
+    UnknownHandle(String),
+    Dialog(String),
+    Storage(String),
+}
+
+impl std::fmt::Display for FileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileError::UnknownHandle(handle) => write!(f, "Unknown file handle {} (pick the file again)", handle),
+            FileError::Dialog(e) => write!(f, "File dialog failed: {}", e),
+            FileError::Storage(e) => write!(f, "Recent files storage error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for FileError {}
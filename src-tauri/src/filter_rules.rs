@@ -0,0 +1,473 @@
+// Filter Rules - User-written blocking rules in Adblock Plus / uBlock Origin syntax
+// Rules are validated and stored here; the frontend adblock engine loads their `filter` text as custom
+// filters, and `test` simulates a request so users can see exactly what a rule blocks.
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::db::Database;
+
+pub const MAX_PATTERN_CHARS: usize = 2_000;
+// Separator class for `^`: anything but a letter, digit, or one of _ - . %
+const SEPARATOR: &str = r"(?:[^\w\-.%]|$)";
+const RESOURCE_TYPES: &[&str] = &[
+    "document", "subdocument", "script", "stylesheet", "image", "media", "font", "object",
+    "xmlhttprequest", "websocket", "ping", "popup", "other",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum RuleType {
+    Block,                             // Network filter: matching requests are cancelled
+    Allow,                             // Exception (@@): matching requests are let through
+    Cosmetic,                          // Element hiding (##selector), optionally per domain
+}
+
+impl RuleType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RuleType::Block => "block",
+            RuleType::Allow => "allow",
+            RuleType::Cosmetic => "cosmetic",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, FilterRuleError> {
+        match value.trim().to_lowercase().as_str() {
+            "block" => Ok(RuleType::Block),
+            "allow" | "exception" => Ok(RuleType::Allow),
+            "cosmetic" | "hide" => Ok(RuleType::Cosmetic),
+            other => Err(FilterRuleError::Syntax(format!("unknown rule type '{}' (block, allow, cosmetic)", other))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct FilterRule {
+    pub id: String,
+    pub rule_type: RuleType,
+    pub filter: String,                // Full filter line as the engine reads it (with @@ for exceptions)
+    pub enabled: bool,
+    pub created_at: i64,
+}
+
+// Result of validating a rule without saving it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct RuleCheck {
+    pub valid: bool,
+    pub filter: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct RuleMatch {
+    pub id: String,
+    pub rule_type: RuleType,
+    pub filter: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct RuleTestResult {
+    pub url: String,
+    pub resource_type: String,
+    pub third_party: bool,
+    pub blocked: bool,
+    pub decided_by: Option<RuleMatch>, // The rule that settled it (an $important block beats exceptions)
+    pub matches: Vec<RuleMatch>,       // Every enabled network rule that matched
+    pub hidden_selectors: Vec<RuleMatch>, // Cosmetic rules that apply on the page (document requests)
+}
+
+// A request to simulate
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TestRequest {
+    pub url: String,
+    pub source_url: Option<String>,    // Page making the request; unset means `url` is a page load
+    pub resource_type: Option<String>, // Defaults to document for page loads, other for subresources
+}
+
+// ----------------------------------------------------------------------------
+// Parsed rules
+// ----------------------------------------------------------------------------
+
+enum Compiled {
+    Network(NetworkRule),
+    Cosmetic(CosmeticRule),
+}
+
+struct NetworkRule {
+    exception: bool,
+    regex: Regex,
+    types: Option<Vec<String>>,        // Only these resource types
+    excluded_types: Vec<String>,
+    third_party: Option<bool>,
+    domains: DomainList,
+    important: bool,
+}
+
+struct CosmeticRule {
+    exception: bool,                   // #@# un-hides a selector hidden elsewhere
+    domains: DomainList,
+    selector: String,
+}
+
+#[derive(Default)]
+struct DomainList {
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl DomainList {
+    // Empty include list = every domain not excluded
+    fn applies(&self, host: &str) -> bool {
+        let within = |d: &String| host == d || host.ends_with(&format!(".{}", d));
+        !self.exclude.iter().any(within) && (self.include.is_empty() || self.include.iter().any(within))
+    }
+
+    fn parse(list: &str, separator: char) -> Result<Self, FilterRuleError> {
+        let mut domains = DomainList::default();
+        for entry in list.split(separator).map(str::trim).filter(|d| !d.is_empty()) {
+            let (negated, domain) = match entry.strip_prefix('~') {
+                Some(domain) => (true, domain),
+                None => (false, entry),
+            };
+            let domain = domain.to_lowercase();
+            if domain.is_empty() || !domain.chars().all(|c| c.is_alphanumeric() || matches!(c, '.' | '-' | '*')) {
+                return Err(FilterRuleError::Syntax(format!("bad domain '{}'", entry)));
+            }
+            if negated {
+                domains.exclude.push(domain);
+            } else {
+                domains.include.push(domain);
+            }
+        }
+        Ok(domains)
+    }
+}
+
+// Check a rule and return its canonical filter line (exceptions get their @@ prefix)
+pub fn normalize(pattern: &str, rule_type: RuleType) -> Result<String, FilterRuleError> {
+    let pattern = pattern.trim();
+    if pattern.is_empty() || pattern.chars().count() > MAX_PATTERN_CHARS {
+        return Err(FilterRuleError::Syntax(format!("rule must be 1-{} characters", MAX_PATTERN_CHARS)));
+    }
+    if pattern.starts_with('!') || pattern.starts_with('[') {
+        return Err(FilterRuleError::Syntax("that's a comment, not a rule".to_string()));
+    }
+    let cosmetic = pattern.contains("##") || pattern.contains("#@#");
+    let filter = match rule_type {
+        RuleType::Cosmetic if !cosmetic => format!("##{}", pattern),
+        RuleType::Cosmetic => pattern.to_string(),
+        _ if cosmetic => {
+            return Err(FilterRuleError::Syntax("element hiding (##) rules use the cosmetic type".to_string()))
+        }
+        RuleType::Block if pattern.starts_with("@@") => {
+            return Err(FilterRuleError::Syntax("@@ rules are exceptions; use the allow type".to_string()))
+        }
+        RuleType::Block => pattern.to_string(),
+        RuleType::Allow if pattern.starts_with("@@") => pattern.to_string(),
+        RuleType::Allow => format!("@@{}", pattern),
+    };
+    compile(&filter)?;
+    Ok(filter)
+}
+
+pub fn check(pattern: &str, rule_type: RuleType) -> RuleCheck {
+    match normalize(pattern, rule_type) {
+        Ok(filter) => RuleCheck { valid: true, filter: Some(filter), error: None },
+        Err(e) => RuleCheck { valid: false, filter: None, error: Some(e.to_string()) },
+    }
+}
+
+fn compile(filter: &str) -> Result<Compiled, FilterRuleError> {
+    if let Some((domains, selector, exception)) = split_cosmetic(filter) {
+        let selector = selector.trim();
+        if selector.is_empty() {
+            return Err(FilterRuleError::Syntax("missing CSS selector after ##".to_string()));
+        }
+        // Procedural/scriptlet extensions (:has-text, +js(...)) are uBlock-only; the engine can't apply them
+        if selector.starts_with("+js(") || selector.contains(":has-text(") || selector.contains(":style(") {
+            return Err(FilterRuleError::Syntax("scriptlets and procedural cosmetic filters aren't supported".to_string()));
+        }
+        scraper::Selector::parse(selector).map_err(|e| FilterRuleError::Syntax(format!("bad CSS selector: {:?}", e)))?;
+        return Ok(Compiled::Cosmetic(CosmeticRule {
+            exception,
+            domains: DomainList::parse(domains, ',')?,
+            selector: selector.to_string(),
+        }));
+    }
+
+    let (exception, body) = match filter.strip_prefix("@@") {
+        Some(body) => (true, body),
+        None => (false, filter),
+    };
+    // A regex rule may itself contain '$'; options only follow the closing slash
+    let (pattern, options) = if body.starts_with('/') && body.len() > 1 {
+        match body.rfind("/$") {
+            Some(i) if i > 0 => (&body[..=i], Some(&body[i + 2..])),
+            _ => (body, None),
+        }
+    } else {
+        match body.rfind('$') {
+            Some(i) => (&body[..i], Some(&body[i + 1..])),
+            None => (body, None),
+        }
+    };
+
+    let mut rule = NetworkRule {
+        exception,
+        regex: Regex::new("").unwrap(),
+        types: None,
+        excluded_types: Vec::new(),
+        third_party: None,
+        domains: DomainList::default(),
+        important: false,
+    };
+    let mut match_case = false;
+    for option in options.into_iter().flat_map(|o| o.split(',')).map(str::trim).filter(|o| !o.is_empty()) {
+        let (negated, name) = match option.strip_prefix('~') {
+            Some(name) => (true, name),
+            None => (false, option),
+        };
+        let name = match name {
+            "xhr" => "xmlhttprequest",
+            "css" => "stylesheet",
+            "frame" => "subdocument",
+            "doc" => "document",
+            other => other,
+        };
+        match name {
+            "third-party" | "3p" => rule.third_party = Some(!negated),
+            "first-party" | "1p" => rule.third_party = Some(negated),
+            "match-case" => match_case = true,
+            "important" => rule.important = true,
+            _ if name.starts_with("domain=") => rule.domains = DomainList::parse(&name["domain=".len()..], '|')?,
+            _ if RESOURCE_TYPES.contains(&name) => {
+                if negated {
+                    rule.excluded_types.push(name.to_string());
+                } else {
+                    rule.types.get_or_insert_with(Vec::new).push(name.to_string());
+                }
+            }
+            _ => return Err(FilterRuleError::Syntax(format!("unsupported option '{}'", option))),
+        }
+    }
+
+    let source = pattern_regex(pattern)?;
+    rule.regex = RegexBuilder::new(&source)
+        .case_insensitive(!match_case)
+        .size_limit(1 << 20)
+        .build()
+        .map_err(|e| FilterRuleError::Syntax(format!("bad pattern: {}", e)))?;
+    Ok(Compiled::Network(rule))
+}
+
+// "example.com,~shop.example.com##.ad" -> (domains, selector, is_exception)
+fn split_cosmetic(filter: &str) -> Option<(&str, &str, bool)> {
+    if let Some(i) = filter.find("#@#") {
+        return Some((&filter[..i], &filter[i + 3..], true));
+    }
+    filter.find("##").map(|i| (&filter[..i], &filter[i + 2..], false))
+}
+
+// ABP pattern -> regex source: || domain anchor, | start/end anchor, * wildcard, ^ separator
+fn pattern_regex(pattern: &str) -> Result<String, FilterRuleError> {
+    if pattern.len() > 2 && pattern.starts_with('/') && pattern.ends_with('/') {
+        return Ok(pattern[1..pattern.len() - 1].to_string());
+    }
+    let mut rest = pattern;
+    let mut source = String::new();
+    if let Some(after) = rest.strip_prefix("||") {
+        source.push_str(r"^[a-z][a-z0-9+.\-]*://(?:[^/?#]*\.)?");
+        rest = after;
+    } else if let Some(after) = rest.strip_prefix('|') {
+        source.push('^');
+        rest = after;
+    }
+    let end_anchor = rest.ends_with('|') && !rest.is_empty();
+    if end_anchor {
+        rest = &rest[..rest.len() - 1];
+    }
+    if rest.is_empty() {
+        return Err(FilterRuleError::Syntax("empty pattern would match every request".to_string()));
+    }
+    for c in rest.chars() {
+        match c {
+            '*' => source.push_str(".*"),
+            '^' => source.push_str(SEPARATOR),
+            c => source.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    if end_anchor {
+        source.push('$');
+    }
+    Ok(source)
+}
+
+// ----------------------------------------------------------------------------
+// Storage and simulation
+// ----------------------------------------------------------------------------
+
+pub fn add(db: &Database, pattern: &str, rule_type: RuleType) -> Result<FilterRule, FilterRuleError> {
+    let filter = normalize(pattern, rule_type)?;
+    if let Some(existing) = list(db)?.into_iter().find(|r| r.filter == filter) {
+        return Err(FilterRuleError::Duplicate(existing.id));
+    }
+    let rule = FilterRule {
+        id: uuid::Uuid::new_v4().to_string(),
+        rule_type,
+        filter,
+        enabled: true,
+        created_at: chrono::Utc::now().timestamp(),
+    };
+    db.filter_rule_save(&rule).map_err(|e| FilterRuleError::Storage(e.to_string()))?;
+    Ok(rule)
+}
+
+pub fn list(db: &Database) -> Result<Vec<FilterRule>, FilterRuleError> {
+    db.filter_rule_list().map_err(|e| FilterRuleError::Storage(e.to_string()))
+}
+
+pub fn set_enabled(db: &Database, id: &str, enabled: bool) -> Result<(), FilterRuleError> {
+    match db.filter_rule_set_enabled(id, enabled) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(FilterRuleError::NotFound(id.to_string())),
+        Err(e) => Err(FilterRuleError::Storage(e.to_string())),
+    }
+}
+
+pub fn remove(db: &Database, id: &str) -> Result<(), FilterRuleError> {
+    match db.filter_rule_delete(id) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(FilterRuleError::NotFound(id.to_string())),
+        Err(e) => Err(FilterRuleError::Storage(e.to_string())),
+    }
+}
+
+// Enabled rules as filter-list text, for the frontend engine's custom filters
+pub fn export(db: &Database) -> Result<Vec<String>, FilterRuleError> {
+    Ok(list(db)?.into_iter().filter(|r| r.enabled).map(|r| r.filter).collect())
+}
+
+// Run a request past every enabled rule, the way the engine would decide it
+pub fn test(db: &Database, request: &TestRequest) -> Result<RuleTestResult, FilterRuleError> {
+    let url = url::Url::parse(request.url.trim()).map_err(|e| FilterRuleError::InvalidUrl(e.to_string()))?;
+    let source = match request.source_url.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        Some(source) => Some(url::Url::parse(source).map_err(|e| FilterRuleError::InvalidUrl(e.to_string()))?),
+        None => None,
+    };
+    let resource_type = match request.resource_type.as_deref().map(|t| t.trim().to_lowercase()) {
+        Some(t) if RESOURCE_TYPES.contains(&t.as_str()) => t,
+        Some(t) => return Err(FilterRuleError::Syntax(format!("unknown resource type '{}'", t))),
+        None if source.is_none() => "document".to_string(),
+        None => "other".to_string(),
+    };
+    let host = |u: &url::Url| u.host_str().unwrap_or_default().trim_end_matches('.').to_lowercase();
+    let request_host = host(&url);
+    let page_host = source.as_ref().map(host).unwrap_or_else(|| request_host.clone());
+    let third_party = site(&request_host) != site(&page_host);
+    let url_text = url.as_str();
+
+    let mut blocks = Vec::new();
+    let mut exceptions = Vec::new();
+    let mut hidden = Vec::new();
+    let mut unhidden = Vec::new();
+    for rule in list(db)?.into_iter().filter(|r| r.enabled) {
+        let compiled = match compile(&rule.filter) {
+            Ok(compiled) => compiled,
+            Err(e) => {
+                tracing::warn!(target: "app", "Filter rules: Skipping stored rule {}: {}", rule.id, e);
+                continue;
+            }
+        };
+        let found = RuleMatch { id: rule.id.clone(), rule_type: rule.rule_type, filter: rule.filter.clone() };
+        match compiled {
+            Compiled::Network(network) => {
+                let type_ok = network.types.as_ref().map_or(true, |types| types.contains(&resource_type))
+                    && !network.excluded_types.contains(&resource_type);
+                let party_ok = network.third_party.map_or(true, |wanted| wanted == third_party);
+                if type_ok && party_ok && network.domains.applies(&page_host) && network.regex.is_match(url_text) {
+                    if network.exception {
+                        exceptions.push(found);
+                    } else {
+                        blocks.push((network.important, found));
+                    }
+                }
+            }
+            Compiled::Cosmetic(cosmetic) => {
+                if resource_type == "document" && cosmetic.domains.applies(&request_host) {
+                    if cosmetic.exception {
+                        unhidden.push(cosmetic.selector);
+                    } else {
+                        hidden.push((cosmetic.selector, found));
+                    }
+                }
+            }
+        }
+    }
+
+    let decided_by = blocks
+        .iter()
+        .find(|(important, _)| *important)
+        .map(|(_, m)| m.clone())
+        .or_else(|| exceptions.first().cloned())
+        .or_else(|| blocks.first().map(|(_, m)| m.clone()));
+    let blocked = decided_by.as_ref().is_some_and(|m| m.rule_type == RuleType::Block);
+    let mut matches: Vec<RuleMatch> = blocks.into_iter().map(|(_, m)| m).collect();
+    matches.extend(exceptions);
+    Ok(RuleTestResult {
+        url: url.to_string(),
+        resource_type,
+        third_party,
+        blocked,
+        decided_by,
+        matches,
+        hidden_selectors: hidden
+            .into_iter()
+            .filter(|(selector, _)| !unhidden.contains(selector))
+            .map(|(_, m)| m)
+            .collect(),
+    })
+}
+
+// Rough registrable domain (last two labels, three for co.uk-style suffixes) for third-party checks
+fn site(host: &str) -> String {
+    let labels: Vec<&str> = host.split('.').collect();
+    let keep = match labels.as_slice() {
+        [.., second, last] if last.len() == 2 && matches!(*second, "co" | "com" | "org" | "net" | "ac" | "gov" | "edu") => 3,
+        _ => 2,
+    };
+    labels[labels.len().saturating_sub(keep)..].join(".")
+}
+
+#[derive(Debug, Clone)]
+pub enum FilterRuleError {
+    Syntax(String),
+    Duplicate(String),
+    InvalidUrl(String),
+    NotFound(String),
+    Storage(String),
+}
+
+impl std::fmt::Display for FilterRuleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FilterRuleError::Syntax(e) => write!(f, "Invalid filter rule: {}", e),
+            FilterRuleError::Duplicate(id) => write!(f, "The same rule already exists ({})", id),
+            FilterRuleError::InvalidUrl(e) => write!(f, "Invalid URL: {}", e),
+            FilterRuleError::NotFound(id) => write!(f, "Filter rule {} not found", id),
+            FilterRuleError::Storage(e) => write!(f, "Filter rule storage error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for FilterRuleError {}
@@ -0,0 +1,262 @@
+// Floating Windows - Small frameless always-on-top native windows (voice orb, trade ticker)
+// They stay up when the main window is minimized or hidden; positions are remembered across runs.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{Manager, PhysicalPosition, Position, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+
+use crate::privacy::PrivacyEnforcer;
+
+const POSITIONS_FILE: &str = "window_positions.json";
+// Gap from the screen edge when placing a window for the first time
+const EDGE_MARGIN: f64 = 24.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "kebab-case")]
+pub enum FloatingKind {
+    WisprOrb,
+    TradeTicker,
+}
+
+impl FloatingKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            FloatingKind::WisprOrb => "wispr-orb",
+            FloatingKind::TradeTicker => "trade-ticker",
+        }
+    }
+
+    // Frontend route rendered in the window
+    fn route(self) -> &'static str {
+        match self {
+            FloatingKind::WisprOrb => "wispr-orb",
+            FloatingKind::TradeTicker => "trade-ticker",
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            FloatingKind::WisprOrb => "Regen Voice",
+            FloatingKind::TradeTicker => "Regen Ticker",
+        }
+    }
+
+    // Logical size
+    fn size(self) -> (f64, f64) {
+        match self {
+            FloatingKind::WisprOrb => (96.0, 96.0),
+            FloatingKind::TradeTicker => (420.0, 56.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "kebab-case")]
+pub enum FloatingAction {
+    Show,
+    Hide,
+    Toggle,
+    Status,
+    ResetPosition,                     // Forget the remembered spot and move back to the default corner
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct FloatingWindowState {
+    pub label: String,
+    pub kind: FloatingKind,
+    pub open: bool,                    // The native window exists
+    pub visible: bool,
+    pub always_on_top: bool,
+    pub x: Option<i32>,                // Physical pixels
+    pub y: Option<i32>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct SavedPosition {
+    x: i32,
+    y: i32,
+}
+
+// label -> last position; loaded from disk on first use
+static POSITIONS: Mutex<Option<HashMap<String, SavedPosition>>> = Mutex::new(None);
+
+pub fn set_always_on_top(app: &tauri::AppHandle, label: &str, on_top: bool) -> Result<(), WindowError> {
+    let window = app.get_webview_window(label).ok_or_else(|| WindowError::NotFound(label.to_string()))?;
+    window.set_always_on_top(on_top).map_err(|e| WindowError::Native(e.to_string()))
+}
+
+pub fn manage(app: &tauri::AppHandle, kind: FloatingKind, action: FloatingAction) -> Result<FloatingWindowState, WindowError> {
+    match action {
+        FloatingAction::Show => show(app, kind)?,
+        FloatingAction::Hide => hide(app, kind)?,
+        FloatingAction::Toggle => {
+            let visible = app
+                .get_webview_window(kind.label())
+                .and_then(|w| w.is_visible().ok())
+                .unwrap_or(false);
+            if visible {
+                hide(app, kind)?
+            } else {
+                show(app, kind)?
+            }
+        }
+        FloatingAction::Status => {}
+        FloatingAction::ResetPosition => {
+            with_positions(app, |positions| positions.remove(kind.label()));
+            persist_positions(app);
+            if let Some(window) = app.get_webview_window(kind.label()) {
+                if let Some(position) = default_position(app, kind) {
+                    window.set_position(Position::Physical(position)).map_err(|e| WindowError::Native(e.to_string()))?;
+                }
+            }
+        }
+    }
+    Ok(state(app, kind))
+}
+
+pub fn state(app: &tauri::AppHandle, kind: FloatingKind) -> FloatingWindowState {
+    let window = app.get_webview_window(kind.label());
+    let position = window.as_ref().and_then(|w| w.outer_position().ok());
+    FloatingWindowState {
+        label: kind.label().to_string(),
+        kind,
+        open: window.is_some(),
+        visible: window.as_ref().and_then(|w| w.is_visible().ok()).unwrap_or(false),
+        always_on_top: window.as_ref().and_then(|w| w.is_always_on_top().ok()).unwrap_or(false),
+        x: position.map(|p| p.x),
+        y: position.map(|p| p.y),
+    }
+}
+
+fn show(app: &tauri::AppHandle, kind: FloatingKind) -> Result<(), WindowError> {
+    if let Some(window) = app.get_webview_window(kind.label()) {
+        return window.show().map_err(|e| WindowError::Native(e.to_string()));
+    }
+    let (width, height) = kind.size();
+    let window = WebviewWindowBuilder::new(app, kind.label(), WebviewUrl::App(kind.route().into()))
+        .title(kind.title())
+        .inner_size(width, height)
+        .decorations(false)
+        .resizable(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .shadow(false)
+        // Appearing must not steal focus from whatever the user is typing in
+        .focused(false)
+        .visible(false)
+        .build()
+        .map_err(|e| WindowError::Native(e.to_string()))?;
+
+    let remembered = with_positions(app, |positions| positions.get(kind.label()).copied())
+        .map(|p| PhysicalPosition::new(p.x, p.y))
+        .filter(|p| on_screen(app, *p));
+    if let Some(position) = remembered.or_else(|| default_position(app, kind)) {
+        let _ = window.set_position(Position::Physical(position));
+    }
+
+    let (handle, label) = (app.clone(), kind.label().to_string());
+    window.on_window_event(move |event| match event {
+        WindowEvent::Moved(position) => {
+            with_positions(&handle, |positions| {
+                positions.insert(label.clone(), SavedPosition { x: position.x, y: position.y })
+            });
+        }
+        // Drags fire many Moved events; disk writes wait for hide, close, or exit
+        WindowEvent::Destroyed => persist_positions(&handle),
+        _ => {}
+    });
+    window.show().map_err(|e| WindowError::Native(e.to_string()))
+}
+
+fn hide(app: &tauri::AppHandle, kind: FloatingKind) -> Result<(), WindowError> {
+    let Some(window) = app.get_webview_window(kind.label()) else {
+        return Ok(());
+    };
+    persist_positions(app);
+    window.hide().map_err(|e| WindowError::Native(e.to_string()))
+}
+
+// Bottom-right corner of the primary monitor, with extra room at the bottom for a taskbar or dock;
+// the ticker sits above the orb
+fn default_position(app: &tauri::AppHandle, kind: FloatingKind) -> Option<PhysicalPosition<i32>> {
+    let monitor = app.primary_monitor().ok().flatten()?;
+    let scale = monitor.scale_factor();
+    let (origin, size) = (monitor.position(), monitor.size());
+    let (width, height) = kind.size();
+    let stack = match kind {
+        FloatingKind::WisprOrb => 0.0,
+        FloatingKind::TradeTicker => FloatingKind::WisprOrb.size().1 + EDGE_MARGIN,
+    };
+    let x = origin.x as f64 + size.width as f64 - (width + EDGE_MARGIN) * scale;
+    let y = origin.y as f64 + size.height as f64 - (height + stack + EDGE_MARGIN * 3.0) * scale;
+    Some(PhysicalPosition::new(x.round() as i32, y.round() as i32))
+}
+
+// A remembered spot on a monitor that has since been unplugged would put the window out of reach
+fn on_screen(app: &tauri::AppHandle, position: PhysicalPosition<i32>) -> bool {
+    app.available_monitors().map(|monitors| {
+        monitors.iter().any(|m| {
+            let (origin, size) = (m.position(), m.size());
+            position.x >= origin.x
+                && position.y >= origin.y
+                && position.x < origin.x + size.width as i32
+                && position.y < origin.y + size.height as i32
+        })
+    })
+    .unwrap_or(false)
+}
+
+fn positions_path(app: &tauri::AppHandle) -> Option<PathBuf> {
+    app.path().app_data_dir().ok().map(|dir| dir.join(POSITIONS_FILE))
+}
+
+fn with_positions<T>(app: &tauri::AppHandle, f: impl FnOnce(&mut HashMap<String, SavedPosition>) -> T) -> T {
+    let mut guard = POSITIONS.lock().unwrap();
+    let positions = guard.get_or_insert_with(|| {
+        positions_path(app)
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    });
+    f(positions)
+}
+
+pub fn persist_positions(app: &tauri::AppHandle) {
+    let allowed = app
+        .try_state::<Mutex<PrivacyEnforcer>>()
+        .map(|enforcer| enforcer.lock().unwrap().can_write_to_disk())
+        .unwrap_or(true);
+    let Some(path) = positions_path(app).filter(|_| allowed) else {
+        return;
+    };
+    let json = with_positions(app, |positions| serde_json::to_string_pretty(positions));
+    let result = json
+        .map_err(|e| e.to_string())
+        .and_then(|json| std::fs::write(&path, json).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        tracing::warn!(target: "app", "Floating windows: Failed to save positions: {}", e);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum WindowError {
+    NotFound(String),
+    Native(String),
+}
+
+impl std::fmt::Display for WindowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WindowError::NotFound(label) => write!(f, "No window '{}'", label),
+            WindowError::Native(e) => write!(f, "Window error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for WindowError {}
@@ -0,0 +1,315 @@
+// Focus Mode - Timed sessions that block distracting sites and quiet background work
+// Blocking is checked by TabManager on every navigation, so a frontend bug can't let a blocked page through
+
+use chrono::{Duration as ChronoDuration, Local, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+use crate::db::Database;
+use crate::privacy::PrivacyEnforcer;
+
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_DURATION_MINUTES: u32 = 8 * 60;
+
+// Used when focus_start isn't given a blocklist
+pub const DEFAULT_BLOCKLIST: &[&str] = &[
+    "youtube.com",
+    "twitter.com",
+    "x.com",
+    "facebook.com",
+    "instagram.com",
+    "reddit.com",
+    "tiktok.com",
+    "netflix.com",
+    "twitch.tv",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct FocusSession {
+    pub id: String,
+    pub started_at: i64,
+    pub ends_at: i64,
+    pub duration_secs: u64,
+    pub blocklist: Vec<String>,        // Domains; subdomains are blocked too
+    pub blocked_attempts: u32,
+}
+
+// Emitted every second as "focus:progress"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct FocusProgress {
+    pub session_id: String,
+    pub elapsed_secs: u64,
+    pub remaining_secs: u64,
+    pub duration_secs: u64,
+    pub blocked_attempts: u32,
+}
+
+// A finished session as stored in focus_sessions; emitted as "focus:ended"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct FocusRecord {
+    pub id: String,
+    pub started_at: i64,
+    pub ended_at: i64,
+    pub planned_secs: u64,
+    pub focused_secs: u64,
+    pub completed: bool,               // Ran to the end rather than being stopped
+    pub blocked_attempts: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct FocusStats {
+    pub current_streak_days: u32,      // Consecutive days with a completed session, through today or yesterday
+    pub longest_streak_days: u32,
+    pub sessions: u32,
+    pub completed_sessions: u32,
+    pub total_focus_secs: u64,
+    pub today_focus_secs: u64,
+}
+
+// Emitted as "focus:blocked" when a navigation is refused
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct FocusBlocked {
+    pub url: String,
+    pub domain: String,
+}
+
+struct Active {
+    session: FocusSession,
+    app: tauri::AppHandle,
+}
+
+struct Focus {
+    active: Mutex<Option<Active>>,
+    generation: AtomicU64,             // Bumped per session so a stopped session's timer exits
+}
+
+fn focus() -> &'static Focus {
+    static FOCUS: OnceLock<Focus> = OnceLock::new();
+    FOCUS.get_or_init(|| Focus { active: Mutex::new(None), generation: AtomicU64::new(0) })
+}
+
+pub fn start(
+    app: tauri::AppHandle,
+    db: Database,
+    duration_minutes: u32,
+    blocklist: Option<Vec<String>>,
+) -> Result<FocusSession, FocusError> {
+    if duration_minutes == 0 || duration_minutes > MAX_DURATION_MINUTES {
+        return Err(FocusError::InvalidDuration(duration_minutes));
+    }
+    let blocklist = match blocklist {
+        Some(domains) => normalize_blocklist(&domains),
+        None => DEFAULT_BLOCKLIST.iter().map(|d| d.to_string()).collect(),
+    };
+
+    let state = focus();
+    let mut active = state.active.lock().unwrap();
+    if active.is_some() {
+        return Err(FocusError::AlreadyActive);
+    }
+    let started_at = chrono::Utc::now().timestamp();
+    let duration_secs = u64::from(duration_minutes) * 60;
+    let session = FocusSession {
+        id: uuid::Uuid::new_v4().to_string(),
+        started_at,
+        ends_at: started_at + duration_secs as i64,
+        duration_secs,
+        blocklist,
+        blocked_attempts: 0,
+    };
+    *active = Some(Active { session: session.clone(), app: app.clone() });
+    let generation = state.generation.fetch_add(1, Ordering::SeqCst) + 1;
+    tracing::info!(target: "app", "Focus: Started {} min session blocking {} domains", duration_minutes, session.blocklist.len());
+
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(PROGRESS_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if state.generation.load(Ordering::SeqCst) != generation {
+                break;
+            }
+            let Some(progress) = status() else { break };
+            let _ = app.emit("focus:progress", &progress);
+            if progress.remaining_secs == 0 {
+                if let Some(record) = finish(&db, true) {
+                    tracing::info!(target: "app", "Focus: Session completed ({} blocked attempts)", record.blocked_attempts);
+                }
+                break;
+            }
+        }
+    });
+
+    Ok(session)
+}
+
+// End early; the partial session still counts toward focus time but not streaks
+pub fn stop(db: &Database) -> Result<FocusRecord, FocusError> {
+    finish(db, false).ok_or(FocusError::NotActive)
+}
+
+pub fn status() -> Option<FocusProgress> {
+    let active = focus().active.lock().unwrap();
+    let session = &active.as_ref()?.session;
+    let now = chrono::Utc::now().timestamp();
+    let elapsed_secs = (now - session.started_at).max(0) as u64;
+    Some(FocusProgress {
+        session_id: session.id.clone(),
+        elapsed_secs: elapsed_secs.min(session.duration_secs),
+        remaining_secs: session.duration_secs.saturating_sub(elapsed_secs),
+        duration_secs: session.duration_secs,
+        blocked_attempts: session.blocked_attempts,
+    })
+}
+
+// Non-essential pollers (clipboard, frecency, index resync) skip their work while this is true (see modes::background_paused)
+pub fn is_active() -> bool {
+    focus().active.lock().unwrap().is_some()
+}
+
+// Refuse navigation to a blocklisted domain; called by TabManager before a tab's URL changes
+pub fn check_navigation(url: &str) -> Result<(), String> {
+    let mut active = focus().active.lock().unwrap();
+    let Some(active) = active.as_mut() else {
+        return Ok(());
+    };
+    let Some(host) = url::Url::parse(url)
+        .ok()
+        .filter(|u| matches!(u.scheme(), "http" | "https"))
+        .and_then(|u| u.host_str().map(|h| h.trim_end_matches('.').to_lowercase()))
+    else {
+        return Ok(());
+    };
+    let Some(domain) = active
+        .session
+        .blocklist
+        .iter()
+        .find(|d| host == **d || host.ends_with(&format!(".{}", d)))
+        .cloned()
+    else {
+        return Ok(());
+    };
+
+    active.session.blocked_attempts += 1;
+    tracing::debug!(target: "app", "Focus: Blocked navigation to {}", domain);
+    let _ = active.app.emit("focus:blocked", FocusBlocked { url: url.to_string(), domain: domain.clone() });
+    Err(format!("{} is blocked during focus mode", domain))
+}
+
+pub fn stats(db: &Database) -> Result<FocusStats, FocusError> {
+    let records = db.list_focus_sessions().map_err(|e| FocusError::Storage(e.to_string()))?;
+    let today = Local::now().date_naive();
+    let day_of = |ts: i64| Local.timestamp_opt(ts, 0).single().map(|t| t.date_naive());
+
+    let mut stats = FocusStats::default();
+    let mut completed_days = BTreeSet::new();
+    for record in &records {
+        stats.sessions += 1;
+        stats.total_focus_secs += record.focused_secs;
+        if day_of(record.ended_at) == Some(today) {
+            stats.today_focus_secs += record.focused_secs;
+        }
+        if record.completed {
+            stats.completed_sessions += 1;
+            completed_days.extend(day_of(record.ended_at));
+        }
+    }
+
+    let mut run = 0u32;
+    let mut previous: Option<NaiveDate> = None;
+    for day in &completed_days {
+        run = match previous {
+            Some(p) if *day - p == ChronoDuration::days(1) => run + 1,
+            _ => 1,
+        };
+        stats.longest_streak_days = stats.longest_streak_days.max(run);
+        previous = Some(*day);
+    }
+    // A streak stays alive until a whole day passes without a completed session
+    if previous.is_some_and(|last| today - last <= ChronoDuration::days(1)) {
+        stats.current_streak_days = run;
+    }
+    Ok(stats)
+}
+
+// Take the active session, store it (unless Ghost mode forbids disk writes), and announce the end
+fn finish(db: &Database, completed: bool) -> Option<FocusRecord> {
+    let state = focus();
+    let Active { session, app } = state.active.lock().unwrap().take()?;
+    state.generation.fetch_add(1, Ordering::SeqCst);
+
+    let ended_at = chrono::Utc::now().timestamp();
+    let record = FocusRecord {
+        id: session.id,
+        started_at: session.started_at,
+        ended_at,
+        planned_secs: session.duration_secs,
+        focused_secs: ((ended_at - session.started_at).max(0) as u64).min(session.duration_secs),
+        completed,
+        blocked_attempts: session.blocked_attempts,
+    };
+    let can_write = app
+        .try_state::<Mutex<PrivacyEnforcer>>()
+        .map(|p| p.lock().unwrap().can_write_to_disk())
+        .unwrap_or(true);
+    if can_write {
+        if let Err(e) = db.save_focus_session(&record) {
+            tracing::warn!(target: "db", "Focus: Failed to record session: {}", e);
+        }
+    }
+    let _ = app.emit("focus:ended", &record);
+    Some(record)
+}
+
+// "https://www.YouTube.com/feed" -> "youtube.com"; invalid entries are dropped
+fn normalize_blocklist(domains: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = domains
+        .iter()
+        .filter_map(|entry| {
+            let entry = entry.trim().to_lowercase();
+            let with_scheme = if entry.contains("://") { entry } else { format!("https://{}", entry) };
+            let host = url::Url::parse(&with_scheme).ok()?.host_str()?.trim_end_matches('.').to_string();
+            let host = host.strip_prefix("www.").map(str::to_string).unwrap_or(host);
+            host.contains('.').then_some(host)
+        })
+        .collect();
+    normalized.sort();
+    normalized.dedup();
+    normalized
+}
+
+#[derive(Debug, Clone)]
+pub enum FocusError {
+    AlreadyActive,
+    NotActive,
+    InvalidDuration(u32),
+    Storage(String),
+}
+
+impl std::fmt::Display for FocusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FocusError::AlreadyActive => write!(f, "A focus session is already running"),
+            FocusError::NotActive => write!(f, "No focus session is running"),
+            FocusError::InvalidDuration(minutes) => {
+                write!(f, "Focus duration must be 1-{} minutes (got {})", MAX_DURATION_MINUTES, minutes)
+            }
+            FocusError::Storage(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for FocusError {}
@@ -0,0 +1,82 @@
+// Frecency - Recency-decayed visit scores for history ranking and new-tab top sites
+// Each visit adds one point; scores halve every HALF_LIFE_DAYS, kept current by a periodic job
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::db::Database;
+
+pub const HALF_LIFE_DAYS: f64 = 14.0;
+pub const VISIT_POINTS: f64 = 1.0;
+const RECOMPUTE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+// Fully decayed entries stop contributing and are zeroed rather than kept as tiny floats
+const MIN_SCORE: f64 = 0.01;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct TopSite {
+    pub url: String,
+    pub title: String,
+    pub host: String,
+    pub frecency: f64,
+    pub visit_count: i64,
+    pub last_visited_at: i64,
+}
+
+// A score computed at `from` as seen at `to` (both unix seconds)
+pub fn decayed(score: f64, from: i64, to: i64) -> f64 {
+    let age_days = (to - from).max(0) as f64 / 86_400.0;
+    let value = score * 0.5f64.powf(age_days / HALF_LIFE_DAYS);
+    if value < MIN_SCORE { 0.0 } else { value }
+}
+
+// Score for rows that predate frecency: every visit counted at the last visit time
+pub fn estimate(visit_count: i64, visited_at: i64, now: i64) -> f64 {
+    decayed(visit_count.max(1) as f64 * VISIT_POINTS, visited_at, now)
+}
+
+// Best page per host, highest frecency first; internal pages are skipped
+pub fn top_sites(db: &Database, limit: usize) -> rusqlite::Result<Vec<TopSite>> {
+    let mut seen = std::collections::HashSet::new();
+    let mut sites = Vec::new();
+    // Over-fetch so one busy host can't crowd out the rest
+    for (url, title, frecency, visit_count, visited_at) in db.history_by_frecency(limit * 5)? {
+        let Some(host) = url::Url::parse(&url)
+            .ok()
+            .filter(|u| matches!(u.scheme(), "http" | "https"))
+            .and_then(|u| u.host_str().map(|h| h.trim_start_matches("www.").to_string()))
+        else {
+            continue;
+        };
+        if !seen.insert(host.clone()) {
+            continue;
+        }
+        sites.push(TopSite { url, title, host, frecency, visit_count, last_visited_at: visited_at });
+        if sites.len() == limit {
+            break;
+        }
+    }
+    Ok(sites)
+}
+
+// Hourly: bring every score up to date so stored values stay comparable
+pub fn spawn_recompute(db: Database) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            // Scores can wait while focus mode or the mode profile keeps background work off
+            if crate::modes::background_paused() {
+                tokio::time::sleep(RECOMPUTE_INTERVAL).await;
+                continue;
+            }
+            let job_db = db.clone();
+            let result = tauri::async_runtime::spawn_blocking(move || job_db.recompute_frecency(chrono::Utc::now().timestamp())).await;
+            match result {
+                Ok(Ok(updated)) => tracing::debug!(target: "db", "Frecency: Recomputed {} history entries", updated),
+                Ok(Err(e)) => tracing::warn!(target: "db", "Frecency: Recompute failed: {}", e),
+                Err(e) => tracing::warn!(target: "db", "Frecency: Recompute task failed: {}", e),
+            }
+            tokio::time::sleep(RECOMPUTE_INTERVAL).await;
+        }
+    });
+}
@@ -0,0 +1,255 @@
+// FX - Daily exchange rates (ECB reference, via Frankfurter with the ECB feed as fallback) cached in SQLite
+// Conversions always answer from the cache; a stale cache is refreshed first when online, and used as-is when not
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
+
+use crate::db::Database;
+
+const FRANKFURTER_URL: &str = "https://api.frankfurter.app/latest?from=EUR";
+const ECB_URL: &str = "https://www.ecb.europa.eu/stats/eurofxref/eurofxref-daily.xml";
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+// Rates are published once per working day; a cache older than this is refetched
+const MAX_AGE_SECS: i64 = 12 * 3600;
+// Older than this (weekends and holidays included), answers are flagged as stale
+const STALE_SECS: i64 = 4 * 86_400;
+// After a failed fetch, on-demand refreshes wait this long before trying again
+const RETRY_SECS: i64 = 5 * 60;
+const STARTUP_DELAY: Duration = Duration::from_secs(30);
+const TICK: Duration = Duration::from_secs(30 * 60);
+
+static DB: OnceLock<Database> = OnceLock::new();
+static LAST_ATTEMPT: AtomicI64 = AtomicI64::new(0);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FxRates {
+    pub as_of: String,                 // Reference date (YYYY-MM-DD)
+    pub fetched_at: i64,
+    pub per_eur: BTreeMap<String, f64>, // Units of each currency per euro; EUR itself is implied
+}
+
+impl FxRates {
+    pub fn per_eur(&self, code: &str) -> Option<f64> {
+        if code == "EUR" { Some(1.0) } else { self.per_eur.get(code).copied() }
+    }
+
+    // Units of `to` per one unit of `from`
+    pub fn rate(&self, from: &str, to: &str) -> Option<f64> {
+        Some(self.per_eur(to)? / self.per_eur(from)?)
+    }
+
+    fn is_stale(&self, now: i64) -> bool {
+        now - self.fetched_at > STALE_SECS
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct Conversion {
+    pub amount: f64,
+    pub from: String,
+    pub to: String,
+    pub rate: f64,
+    pub value: f64,
+    pub as_of: String,
+    pub stale: bool,                   // The cache couldn't be refreshed and is several days old
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct RateTable {
+    pub base: String,
+    pub as_of: String,
+    pub fetched_at: i64,
+    pub stale: bool,
+    pub rates: BTreeMap<String, f64>,  // Units of each currency per one unit of `base`
+}
+
+fn cache() -> &'static RwLock<Option<FxRates>> {
+    static RATES: OnceLock<RwLock<Option<FxRates>>> = OnceLock::new();
+    RATES.get_or_init(|| RwLock::new(None))
+}
+
+// Whatever is cached right now, without touching the network
+pub fn cached() -> Option<FxRates> {
+    cache().read().unwrap().clone()
+}
+
+// Load the saved rates and keep them fresh in the background
+pub fn init(db: Database) {
+    match db.fx_rates_load() {
+        Ok(rates) => *cache().write().unwrap() = rates,
+        Err(e) => tracing::warn!(target: "db", "FX: Failed to load cached rates: {}", e),
+    }
+    let _ = DB.set(db);
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(STARTUP_DELAY).await;
+        loop {
+            if !crate::modes::background_paused() {
+                refresh_if_old().await;
+            }
+            tokio::time::sleep(TICK).await;
+        }
+    });
+}
+
+// Refetch when the cache is past MAX_AGE_SECS and we're online; failures leave the cache as it was
+async fn refresh_if_old() {
+    let now = chrono::Utc::now().timestamp();
+    let fetched_at = cache().read().unwrap().as_ref().map(|r| r.fetched_at).unwrap_or(0);
+    if now - fetched_at <= MAX_AGE_SECS || crate::connectivity::is_offline() {
+        return;
+    }
+    if now - LAST_ATTEMPT.load(Ordering::SeqCst) < RETRY_SECS {
+        return;
+    }
+    LAST_ATTEMPT.store(now, Ordering::SeqCst);
+    match fetch().await {
+        Ok(rates) => {
+            if let Some(db) = DB.get() {
+                if let Err(e) = db.fx_rates_save(&rates) {
+                    tracing::warn!(target: "db", "FX: Failed to save rates: {}", e);
+                }
+            }
+            tracing::debug!(target: "app", "FX: {} rates as of {}", rates.per_eur.len(), rates.as_of);
+            *cache().write().unwrap() = Some(rates);
+        }
+        Err(e) => tracing::debug!(target: "app", "FX: Refresh failed: {}", e),
+    }
+}
+
+// Cached rates, refreshed first if they're old and the network allows
+async fn current() -> Result<FxRates, FxError> {
+    refresh_if_old().await;
+    cached().ok_or(if crate::connectivity::is_offline() { FxError::Offline } else { FxError::Unavailable })
+}
+
+pub fn normalize_code(code: &str) -> Result<String, FxError> {
+    let code = code.trim().to_uppercase();
+    if code.len() == 3 && code.chars().all(|c| c.is_ascii_alphabetic()) {
+        Ok(code)
+    } else {
+        Err(FxError::UnknownCurrency(code))
+    }
+}
+
+pub async fn convert(amount: f64, from: &str, to: &str) -> Result<Conversion, FxError> {
+    if !amount.is_finite() {
+        return Err(FxError::InvalidAmount);
+    }
+    let (from, to) = (normalize_code(from)?, normalize_code(to)?);
+    let rates = current().await?;
+    let rate = rates.rate(&from, &to).ok_or_else(|| {
+        let unknown = if rates.per_eur(&from).is_none() { &from } else { &to };
+        FxError::UnknownCurrency(unknown.clone())
+    })?;
+    Ok(Conversion {
+        amount,
+        value: amount * rate,
+        rate,
+        stale: rates.is_stale(chrono::Utc::now().timestamp()),
+        as_of: rates.as_of,
+        from,
+        to,
+    })
+}
+
+// Every cached currency against `base`
+pub async fn rates(base: &str) -> Result<RateTable, FxError> {
+    let base = normalize_code(base)?;
+    let cached = current().await?;
+    let base_per_eur = cached.per_eur(&base).ok_or_else(|| FxError::UnknownCurrency(base.clone()))?;
+    let rates = std::iter::once(("EUR".to_string(), 1.0))
+        .chain(cached.per_eur.iter().map(|(code, rate)| (code.clone(), *rate)))
+        .filter(|(code, _)| *code != base)
+        .map(|(code, per_eur)| (code, per_eur / base_per_eur))
+        .collect();
+    Ok(RateTable {
+        base,
+        stale: cached.is_stale(chrono::Utc::now().timestamp()),
+        as_of: cached.as_of,
+        fetched_at: cached.fetched_at,
+        rates,
+    })
+}
+
+async fn fetch() -> Result<FxRates, String> {
+    let client = crate::http::Client::new(FETCH_TIMEOUT).with_purpose("exchange-rates");
+    let now = chrono::Utc::now().timestamp();
+    let frankfurter = async {
+        let response = client.send(client.get(FRANKFURTER_URL)).await.map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("Frankfurter: HTTP {}", response.status().as_u16()));
+        }
+        let body: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+        parse_frankfurter(&body, now).ok_or_else(|| "Frankfurter: No rates in the response".to_string())
+    };
+    match frankfurter.await {
+        Ok(rates) => Ok(rates),
+        Err(e) => {
+            tracing::debug!(target: "app", "FX: {}; trying the ECB feed", e);
+            let response = client.send(client.get(ECB_URL)).await.map_err(|e| e.to_string())?;
+            if !response.status().is_success() {
+                return Err(format!("ECB: HTTP {}", response.status().as_u16()));
+            }
+            let body = response.text().await.map_err(|e| e.to_string())?;
+            parse_ecb(&body, now).ok_or_else(|| "ECB: No rates in the response".to_string())
+        }
+    }
+}
+
+// {"amount":1.0,"base":"EUR","date":"2026-10-16","rates":{"USD":1.0823,...}}
+fn parse_frankfurter(body: &serde_json::Value, fetched_at: i64) -> Option<FxRates> {
+    if body.get("base").and_then(|b| b.as_str()) != Some("EUR") {
+        return None;
+    }
+    let as_of = body.get("date")?.as_str()?.to_string();
+    let per_eur: BTreeMap<String, f64> = body
+        .get("rates")?
+        .as_object()?
+        .iter()
+        .filter_map(|(code, rate)| Some((code.clone(), rate.as_f64().filter(|r| *r > 0.0)?)))
+        .collect();
+    (!per_eur.is_empty()).then_some(FxRates { as_of, fetched_at, per_eur })
+}
+
+// <Cube time='2026-10-16'><Cube currency='USD' rate='1.0823'/>...
+fn parse_ecb(xml: &str, fetched_at: i64) -> Option<FxRates> {
+    static TIME: OnceLock<regex::Regex> = OnceLock::new();
+    static RATE: OnceLock<regex::Regex> = OnceLock::new();
+    let time = TIME.get_or_init(|| regex::Regex::new(r#"time=['"](\d{4}-\d{2}-\d{2})['"]"#).unwrap());
+    let rate = RATE.get_or_init(|| regex::Regex::new(r#"currency=['"]([A-Z]{3})['"]\s+rate=['"]([\d.]+)['"]"#).unwrap());
+    let as_of = time.captures(xml)?[1].to_string();
+    let per_eur: BTreeMap<String, f64> = rate
+        .captures_iter(xml)
+        .filter_map(|c| Some((c[1].to_string(), c[2].parse::<f64>().ok().filter(|r| *r > 0.0)?)))
+        .collect();
+    (!per_eur.is_empty()).then_some(FxRates { as_of, fetched_at, per_eur })
+}
+
+#[derive(Debug, Clone)]
+pub enum FxError {
+    UnknownCurrency(String),
+    InvalidAmount,
+    Offline,                           // No rates cached yet and no network to fetch them
+    Unavailable,                       // No rates cached yet and the fetch failed
+}
+
+impl std::fmt::Display for FxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FxError::UnknownCurrency(code) => write!(f, "Unknown currency: {}", code),
+            FxError::InvalidAmount => write!(f, "Amount must be a finite number"),
+            FxError::Offline => write!(f, "Exchange rates haven't been downloaded yet and the network is offline"),
+            FxError::Unavailable => write!(f, "Exchange rates are unavailable right now"),
+        }
+    }
+}
+
+impl std::error::Error for FxError {}
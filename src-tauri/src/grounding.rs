@@ -0,0 +1,184 @@
+// Grounding - Per-claim support scores for generated answers
+// Each answer sentence is compared against retrieved source chunks (embedding similarity + lexical recall)
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::embeddings::{self, cosine};
+use crate::text;
+
+// Claims shorter than this (in content words) are connective tissue, not factual claims
+const MIN_CLAIM_WORDS: usize = 4;
+// Source text is compared in windows of this many sentences
+const CHUNK_SENTENCES: usize = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SourceChunk {
+    pub id: String,
+    pub url: Option<String>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum Support {
+    Supported,
+    Partial,
+    Unsupported,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ClaimSupport {
+    pub claim: String,
+    pub score: f32,                    // 0..1
+    pub support: Support,
+    pub source_id: Option<String>,
+    pub source_url: Option<String>,
+    pub evidence: Option<String>,      // Best-matching source window
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct GroundingReport {
+    pub claims: Vec<ClaimSupport>,
+    pub grounded_fraction: f32,        // Supported = 1, partial = 0.5
+    pub hallucination_risk: String,    // "low" | "medium" | "high", for existing consumers
+    pub model: String,
+}
+
+struct Window<'a> {
+    source: &'a SourceChunk,
+    text: String,
+    words: HashSet<String>,
+}
+
+// Score every claim in `answer` against `sources`
+pub async fn check(answer: &str, sources: &[SourceChunk]) -> GroundingReport {
+    let claims: Vec<String> = text::split_sentences(answer)
+        .into_iter()
+        .filter(|s| text::content_words(s).len() >= MIN_CLAIM_WORDS)
+        .collect();
+
+    let windows: Vec<Window> = sources
+        .iter()
+        .flat_map(|source| {
+            let sentences = text::split_sentences(&source.text);
+            sentences
+                .chunks(CHUNK_SENTENCES)
+                .map(|chunk| chunk.join(" "))
+                .collect::<Vec<_>>()
+                .into_iter()
+                .map(move |text| Window {
+                    source,
+                    words: text::content_words(&text).into_iter().collect(),
+                    text,
+                })
+        })
+        .collect();
+
+    if claims.is_empty() || windows.is_empty() {
+        let claims: Vec<ClaimSupport> = claims.into_iter().map(unsupported).collect();
+        return report(claims, String::new());
+    }
+
+    let mut texts: Vec<String> = claims.clone();
+    texts.extend(windows.iter().map(|w| w.text.clone()));
+    let embedded = embeddings::embed(&texts).await;
+    let (claim_vectors, window_vectors) = embedded.vectors.split_at(claims.len());
+    let semantic = embedded.source != embeddings::EmbeddingSource::Hashed;
+
+    let scored = claims
+        .into_iter()
+        .zip(claim_vectors)
+        .map(|(claim, claim_vector)| {
+            let claim_words: HashSet<String> = text::content_words(&claim).into_iter().collect();
+            let best = windows
+                .iter()
+                .zip(window_vectors)
+                .map(|(window, window_vector)| {
+                    let recall = claim_words.intersection(&window.words).count() as f32
+                        / claim_words.len().max(1) as f32;
+                    let score = if semantic {
+                        // Rescale cosine: unrelated text from the same model still sits around 0.4
+                        let similarity = ((cosine(claim_vector, window_vector) - 0.4) / 0.45).clamp(0.0, 1.0);
+                        0.7 * similarity + 0.3 * recall
+                    } else {
+                        recall
+                    };
+                    (window, score)
+                })
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+
+            match best {
+                Some((window, score)) => ClaimSupport {
+                    support: classify(score),
+                    score,
+                    source_id: Some(window.source.id.clone()),
+                    source_url: window.source.url.clone(),
+                    evidence: Some(window.text.clone()),
+                    claim,
+                },
+                None => unsupported(claim),
+            }
+        })
+        .collect();
+
+    report(scored, embedded.model)
+}
+
+fn classify(score: f32) -> Support {
+    if score >= 0.7 {
+        Support::Supported
+    } else if score >= 0.45 {
+        Support::Partial
+    } else {
+        Support::Unsupported
+    }
+}
+
+fn unsupported(claim: String) -> ClaimSupport {
+    ClaimSupport {
+        claim,
+        score: 0.0,
+        support: Support::Unsupported,
+        source_id: None,
+        source_url: None,
+        evidence: None,
+    }
+}
+
+fn report(claims: Vec<ClaimSupport>, model: String) -> GroundingReport {
+    let grounded_fraction = if claims.is_empty() {
+        1.0
+    } else {
+        claims
+            .iter()
+            .map(|c| match c.support {
+                Support::Supported => 1.0,
+                Support::Partial => 0.5,
+                Support::Unsupported => 0.0,
+            })
+            .sum::<f32>()
+            / claims.len() as f32
+    };
+    let hallucination_risk = if grounded_fraction >= 0.8 {
+        "low"
+    } else if grounded_fraction >= 0.5 {
+        "medium"
+    } else {
+        "high"
+    };
+
+    GroundingReport {
+        claims,
+        grounded_fraction,
+        hallucination_risk: hallucination_risk.to_string(),
+        model,
+    }
+}
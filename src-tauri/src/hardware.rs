@@ -0,0 +1,250 @@
+// Hardware Capabilities - GPU acceleration, CPU features, cores and RAM
+// Detected once per run and used to pick local model defaults (size, quantization, threads)
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+use std::sync::OnceLock;
+
+const GB: u64 = 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SystemCapabilities {
+    pub os: String,
+    pub arch: String,
+    pub cpu: CpuInfo,
+    pub total_ram_bytes: u64,
+    pub acceleration: Acceleration,
+    pub gpus: Vec<GpuInfo>,
+    pub tier: HardwareTier,
+    pub models: ModelDefaults,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct CpuInfo {
+    pub logical_cores: usize,
+    pub physical_cores: usize,
+    pub features: Vec<String>,         // e.g. "avx2", "fma", "neon"
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct Acceleration {
+    pub cuda: bool,
+    pub metal: bool,
+    pub vulkan: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct GpuInfo {
+    pub name: String,
+    pub vendor: String,
+    pub vram_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum HardwareTier {
+    Low,
+    Standard,
+    High,
+}
+
+// Defaults for local inference; callers may still override per request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ModelDefaults {
+    pub chat_model: String,
+    pub embed_model: String,
+    pub quantization: String,          // Of the chat model: "q4" | "q8"
+    pub threads: usize,
+    pub max_loaded_models: usize,
+    pub num_parallel: usize,
+}
+
+// Cached detection (probing GPUs spawns processes, so do it once)
+pub fn capabilities() -> &'static SystemCapabilities {
+    static CAPABILITIES: OnceLock<SystemCapabilities> = OnceLock::new();
+    CAPABILITIES.get_or_init(detect)
+}
+
+pub fn detect() -> SystemCapabilities {
+    let cpu = CpuInfo {
+        logical_cores: num_cpus::get(),
+        physical_cores: num_cpus::get_physical(),
+        features: cpu_features(),
+    };
+    let total_ram_bytes = crate::stability::get_system_ram().unwrap_or(0);
+    let gpus = nvidia_gpus();
+    let acceleration = Acceleration {
+        cuda: !gpus.is_empty() || cuda_library_present(),
+        metal: cfg!(target_os = "macos"),
+        vulkan: vulkan_library_present(),
+    };
+    let tier = tier(&cpu, total_ram_bytes, &acceleration, &gpus);
+    let models = model_defaults(tier, &cpu);
+
+    SystemCapabilities {
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        cpu,
+        total_ram_bytes,
+        acceleration,
+        gpus,
+        tier,
+        models,
+    }
+}
+
+fn tier(cpu: &CpuInfo, ram: u64, acceleration: &Acceleration, gpus: &[GpuInfo]) -> HardwareTier {
+    // Without AVX2 (or NEON) llama.cpp falls back to slow scalar kernels
+    let fast_cpu = cpu.features.iter().any(|f| f == "avx2" || f == "neon");
+    let big_gpu = gpus.iter().any(|g| g.vram_bytes.unwrap_or(0) >= 8 * GB);
+    // Apple Silicon shares RAM with the GPU
+    let unified = acceleration.metal && cfg!(target_arch = "aarch64");
+
+    if (ram > 0 && ram < 6 * GB) || (!fast_cpu && !acceleration.cuda && !unified) {
+        HardwareTier::Low
+    } else if big_gpu || (ram >= 16 * GB && (unified || cpu.physical_cores >= 8)) {
+        HardwareTier::High
+    } else {
+        HardwareTier::Standard
+    }
+}
+
+fn model_defaults(tier: HardwareTier, cpu: &CpuInfo) -> ModelDefaults {
+    // Physical cores are what matter for llama.cpp; keep one free for the UI on larger CPUs
+    let cores = cpu.physical_cores.max(1);
+    let threads = if cores > 4 { cores - 1 } else { cores }.max(2);
+
+    // Ollama's embedding tags are f16 only, so the low tier trades nomic-embed-text (274MB)
+    // for all-minilm (46MB) rather than picking a lower-bit build
+    match tier {
+        HardwareTier::Low => ModelDefaults {
+            chat_model: "phi3:mini".to_string(),
+            embed_model: "all-minilm".to_string(),
+            quantization: "q4".to_string(),
+            threads,
+            max_loaded_models: 1,
+            num_parallel: 1,
+        },
+        HardwareTier::Standard => ModelDefaults {
+            chat_model: "phi3:mini".to_string(),
+            embed_model: "nomic-embed-text".to_string(),
+            quantization: "q4".to_string(),
+            threads,
+            max_loaded_models: 2,
+            num_parallel: 2,
+        },
+        HardwareTier::High => ModelDefaults {
+            chat_model: "phi3:3.8b-mini-4k-instruct-q8_0".to_string(),
+            embed_model: "nomic-embed-text".to_string(),
+            quantization: "q8".to_string(),
+            threads,
+            max_loaded_models: 2,
+            num_parallel: 4,
+        },
+    }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn cpu_features() -> Vec<String> {
+    let mut features = Vec::new();
+    macro_rules! probe {
+        ($($name:tt),*) => {
+            $(if std::arch::is_x86_feature_detected!($name) {
+                features.push($name.to_string());
+            })*
+        };
+    }
+    probe!("sse4.2", "avx", "avx2", "fma", "f16c", "avx512f");
+    features
+}
+
+#[cfg(target_arch = "aarch64")]
+fn cpu_features() -> Vec<String> {
+    let mut features = Vec::new();
+    if std::arch::is_aarch64_feature_detected!("neon") {
+        features.push("neon".to_string());
+    }
+    if std::arch::is_aarch64_feature_detected!("dotprod") {
+        features.push("dotprod".to_string());
+    }
+    features
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+fn cpu_features() -> Vec<String> {
+    Vec::new()
+}
+
+// NVIDIA GPUs via nvidia-smi (installed with the driver on Windows and Linux)
+fn nvidia_gpus() -> Vec<GpuInfo> {
+    let Ok(output) = Command::new("nvidia-smi")
+        .args(["--query-gpu=name,memory.total", "--format=csv,noheader,nounits"])
+        .output()
+    else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (name, memory) = line.rsplit_once(',')?;
+            Some(GpuInfo {
+                name: name.trim().to_string(),
+                vendor: "NVIDIA".to_string(),
+                // Reported in MiB
+                vram_bytes: memory.trim().parse::<u64>().ok().map(|mib| mib * 1024 * 1024),
+            })
+        })
+        .collect()
+}
+
+fn any_exists(paths: &[&str]) -> bool {
+    paths.iter().any(|p| Path::new(p).exists())
+}
+
+fn cuda_library_present() -> bool {
+    if cfg!(windows) {
+        any_exists(&["C:\\Windows\\System32\\nvcuda.dll"])
+    } else if cfg!(target_os = "linux") {
+        any_exists(&[
+            "/usr/lib/x86_64-linux-gnu/libcuda.so.1",
+            "/usr/lib/aarch64-linux-gnu/libcuda.so.1",
+            "/usr/lib64/libcuda.so.1",
+            "/usr/lib/libcuda.so.1",
+            "/usr/lib/wsl/lib/libcuda.so.1",
+        ])
+    } else {
+        false
+    }
+}
+
+fn vulkan_library_present() -> bool {
+    if cfg!(windows) {
+        any_exists(&["C:\\Windows\\System32\\vulkan-1.dll"])
+    } else if cfg!(target_os = "macos") {
+        // Only via MoltenVK
+        any_exists(&["/usr/local/lib/libvulkan.1.dylib", "/opt/homebrew/lib/libvulkan.1.dylib"])
+    } else {
+        any_exists(&[
+            "/usr/lib/x86_64-linux-gnu/libvulkan.so.1",
+            "/usr/lib/aarch64-linux-gnu/libvulkan.so.1",
+            "/usr/lib64/libvulkan.so.1",
+            "/usr/lib/libvulkan.so.1",
+        ])
+    }
+}
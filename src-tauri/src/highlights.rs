@@ -0,0 +1,201 @@
+// Highlights - Persisted page highlights with W3C Web Annotation style anchors
+// CSS + text-quote + text-position selectors so the frontend can re-anchor on revisit
+
+use serde::{Deserialize, Serialize};
+
+// Characters of context compared when a quote occurs more than once
+const CONTEXT_CHARS: usize = 32;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(tag = "type")]
+pub enum Selector {
+    #[serde(rename = "CssSelector")]
+    Css { value: String },
+    #[serde(rename = "TextQuoteSelector")]
+    TextQuote {
+        exact: String,
+        #[serde(default)]
+        prefix: String,
+        #[serde(default)]
+        suffix: String,
+    },
+    #[serde(rename = "TextPositionSelector")]
+    TextPosition { start: usize, end: usize },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct Highlight {
+    pub id: String,
+    pub url: String,
+    pub text: String,                  // The exact quote (duplicated for listing/search)
+    pub selectors: Vec<Selector>,
+    pub color: Option<String>,
+    pub note: Option<String>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedHighlight {
+    #[serde(flatten)]
+    pub highlight: Highlight,
+    pub status: AnchorStatus,
+    pub position: Option<(usize, usize)>, // Char offsets in the cached page text, when verified
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum AnchorStatus {
+    Exact,                             // Quote found with matching context
+    Fuzzy,                             // Found after whitespace/context relaxation
+    Orphaned,                          // Quote no longer present in the page
+    Unverified,                        // No cached copy of the page to check against
+}
+
+impl Highlight {
+    pub fn new(url: String, selectors: Vec<Selector>, color: Option<String>, note: Option<String>) -> Result<Self, HighlightError> {
+        let text = selectors
+            .iter()
+            .find_map(|s| match s {
+                Selector::TextQuote { exact, .. } => Some(exact.clone()),
+                _ => None,
+            })
+            .filter(|t| !t.trim().is_empty())
+            .ok_or(HighlightError::MissingQuote)?;
+
+        Ok(Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            url: crate::notes::normalize_url(&url).unwrap_or(url),
+            text,
+            selectors,
+            color,
+            note: note.filter(|n| !n.trim().is_empty()),
+            created_at: chrono::Utc::now().timestamp(),
+        })
+    }
+
+    fn quote(&self) -> (&str, &str, &str) {
+        self.selectors
+            .iter()
+            .find_map(|s| match s {
+                Selector::TextQuote { exact, prefix, suffix } => Some((exact.as_str(), prefix.as_str(), suffix.as_str())),
+                _ => None,
+            })
+            .unwrap_or((self.text.as_str(), "", ""))
+    }
+
+    fn stored_position(&self) -> Option<(usize, usize)> {
+        self.selectors.iter().find_map(|s| match s {
+            Selector::TextPosition { start, end } => Some((*start, *end)),
+            _ => None,
+        })
+    }
+}
+
+// Check a highlight against the page's current text (e.g. the cached page content)
+pub fn resolve(highlight: Highlight, page_text: Option<&str>) -> ResolvedHighlight {
+    let Some(page_text) = page_text else {
+        return ResolvedHighlight { highlight, status: AnchorStatus::Unverified, position: None };
+    };
+
+    let chars: Vec<char> = page_text.chars().collect();
+    let (exact, prefix, suffix) = highlight.quote();
+    let exact_chars: Vec<char> = exact.chars().collect();
+
+    let candidates = find_all(&chars, &exact_chars);
+    let (status, position) = if candidates.is_empty() {
+        match find_normalized(&chars, exact) {
+            Some(position) => (AnchorStatus::Fuzzy, Some(position)),
+            None => (AnchorStatus::Orphaned, None),
+        }
+    } else {
+        // Prefer the occurrence whose surrounding text matches; break ties by stored position
+        let hint = highlight.stored_position().map(|(start, _)| start);
+        let best = candidates
+            .iter()
+            .map(|&start| {
+                let end = start + exact_chars.len();
+                let score = context_score(&chars, start, end, prefix, suffix);
+                let distance = hint.map(|h| h.abs_diff(start)).unwrap_or(0);
+                (start, end, score, distance)
+            })
+            .max_by(|a, b| a.2.cmp(&b.2).then(b.3.cmp(&a.3)))
+            .unwrap();
+
+        let context_given = !prefix.is_empty() || !suffix.is_empty();
+        let full_context = (prefix.chars().count().min(CONTEXT_CHARS) + suffix.chars().count().min(CONTEXT_CHARS)) as i64;
+        let status = if !context_given || best.2 >= full_context || candidates.len() == 1 {
+            AnchorStatus::Exact
+        } else {
+            AnchorStatus::Fuzzy
+        };
+        (status, Some((best.0, best.1)))
+    };
+
+    ResolvedHighlight { highlight, status, position }
+}
+
+fn find_all(haystack: &[char], needle: &[char]) -> Vec<usize> {
+    if needle.is_empty() || needle.len() > haystack.len() {
+        return Vec::new();
+    }
+    (0..=haystack.len() - needle.len())
+        .filter(|&i| haystack[i..i + needle.len()] == *needle)
+        .collect()
+}
+
+// Number of matching context characters adjacent to the quote
+fn context_score(chars: &[char], start: usize, end: usize, prefix: &str, suffix: &str) -> i64 {
+    let before = prefix
+        .chars()
+        .rev()
+        .take(CONTEXT_CHARS)
+        .zip(chars[..start].iter().rev())
+        .take_while(|(a, b)| a == *b)
+        .count();
+    let after = suffix
+        .chars()
+        .take(CONTEXT_CHARS)
+        .zip(chars[end..].iter())
+        .take_while(|(a, b)| a == *b)
+        .count();
+    (before + after) as i64
+}
+
+// Match ignoring whitespace differences; returns char offsets in the original text
+fn find_normalized(chars: &[char], exact: &str) -> Option<(usize, usize)> {
+    let needle: Vec<char> = exact.chars().filter(|c| !c.is_whitespace()).collect();
+    if needle.is_empty() {
+        return None;
+    }
+    let (stripped, offsets): (Vec<char>, Vec<usize>) = chars
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| !c.is_whitespace())
+        .map(|(i, c)| (*c, i))
+        .unzip();
+
+    let start = *find_all(&stripped, &needle).first()?;
+    let end = offsets[start + needle.len() - 1] + 1;
+    Some((offsets[start], end))
+}
+
+#[derive(Debug, Clone)]
+pub enum HighlightError {
+    MissingQuote,
+}
+
+impl std::fmt::Display for HighlightError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HighlightError::MissingQuote => write!(f, "Highlight needs a non-empty TextQuoteSelector"),
+        }
+    }
+}
+
+impl std::error::Error for HighlightError {}
@@ -0,0 +1,583 @@
+// HTTP - Shared outbound client with per-host rate limiting, retries, and circuit breaking
+// Every request goes through one connection pool; per-host metrics are kept for http_stats
+// In local-only mode (the localOnly setting) requests to anything but loopback are refused before DNS or connect
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_RETRIES: u32 = 2;
+const BACKOFF_BASE: Duration = Duration::from_millis(500);
+const BACKOFF_MAX: Duration = Duration::from_secs(10);
+// Same as reqwest's default policy
+const MAX_REDIRECTS: usize = 10;
+// Token bucket per host: sustained requests per second and burst size
+const DEFAULT_RATE: f64 = 10.0;
+const DEFAULT_BURST: f64 = 20.0;
+// Consecutive failures that open a host's circuit, and how long it stays open
+const FAILURE_THRESHOLD: u32 = 5;
+const OPEN_FOR: Duration = Duration::from_secs(30);
+// Local services (Ollama, Meilisearch, the Regen server) are never rate limited
+const LOCAL_HOSTS: &[&str] = &["127.0.0.1", "localhost", "::1", "[::1]"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,                          // One trial request allowed through
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct HostStats {
+    pub host: String,
+    pub requests: u64,
+    pub failures: u64,
+    pub retries: u64,
+    pub rejected: u64,                 // Refused while the circuit was open
+    pub avg_latency_ms: f64,
+    pub max_latency_ms: u64,
+    pub circuit: CircuitState,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct HttpStats {
+    pub requests: u64,
+    pub failures: u64,
+    pub hosts: Vec<HostStats>,
+}
+
+struct HostState {
+    tokens: f64,
+    rate: f64,
+    burst: f64,
+    refilled_at: Instant,
+    blocked_until: Option<Instant>,    // From a 429 Retry-After
+    consecutive_failures: u32,
+    circuit: CircuitState,
+    opened_at: Option<Instant>,
+    trial_in_flight: bool,
+    requests: u64,
+    failures: u64,
+    retries: u64,
+    rejected: u64,
+    total_latency_ms: u64,
+    max_latency_ms: u64,
+    last_error: Option<String>,
+}
+
+impl HostState {
+    fn new(rate: f64, burst: f64) -> Self {
+        Self {
+            tokens: burst,
+            rate,
+            burst,
+            refilled_at: Instant::now(),
+            blocked_until: None,
+            consecutive_failures: 0,
+            circuit: CircuitState::Closed,
+            opened_at: None,
+            trial_in_flight: false,
+            requests: 0,
+            failures: 0,
+            retries: 0,
+            rejected: 0,
+            total_latency_ms: 0,
+            max_latency_ms: 0,
+            last_error: None,
+        }
+    }
+
+    // Time to wait before a token is available (taking it when there is none to wait for)
+    fn take_token(&mut self, now: Instant) -> Option<Duration> {
+        if let Some(until) = self.blocked_until {
+            if until > now {
+                return Some(until - now);
+            }
+            self.blocked_until = None;
+        }
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.burst);
+        self.refilled_at = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - self.tokens) / self.rate))
+        }
+    }
+}
+
+struct Registry {
+    hosts: Mutex<HashMap<String, HostState>>,
+    limits: Mutex<HashMap<String, (f64, f64)>>,
+}
+
+fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(|| Registry {
+        hosts: Mutex::new(HashMap::new()),
+        limits: Mutex::new(HashMap::new()),
+    })
+}
+
+// One pool for the whole app; per-request timeouts come from each Client.
+// Names resolve over DNS-over-HTTPS (see doh.rs). Rebuilt only when the proxy changes.
+struct Shared {
+    client: reqwest::Client,
+    proxy: Option<(String, reqwest::Proxy)>,   // Settings the pool was built with, to skip needless rebuilds
+}
+
+fn pool() -> &'static RwLock<Shared> {
+    static CLIENT: OnceLock<RwLock<Shared>> = OnceLock::new();
+    CLIENT.get_or_init(|| RwLock::new(Shared { client: build(None), proxy: None }))
+}
+
+// The proxy backend requests go through, and the key describing it; the DoH client uses it too
+pub fn current_proxy() -> Option<(String, reqwest::Proxy)> {
+    pool().read().unwrap().proxy.clone()
+}
+
+fn shared() -> reqwest::Client {
+    pool().read().unwrap().client.clone()
+}
+
+fn build(proxy: Option<reqwest::Proxy>) -> reqwest::Client {
+    let mut builder = reqwest::Client::builder()
+        .dns_resolver(std::sync::Arc::new(crate::doh::DohResolver))
+        .connect_timeout(Duration::from_secs(10))
+        .pool_idle_timeout(Duration::from_secs(90))
+        .redirect(reqwest::redirect::Policy::custom(redirect));
+    if let Some(proxy) = proxy {
+        builder = builder.proxy(proxy);
+    }
+    builder.build().unwrap_or_default()
+}
+
+// send() only checks the first host, so in local-only mode each redirect hop is checked again here
+fn redirect(attempt: reqwest::redirect::Attempt) -> reqwest::redirect::Action {
+    if attempt.previous().len() >= MAX_REDIRECTS {
+        return attempt.error("too many redirects");
+    }
+    let host = attempt.url().host_str().unwrap_or("").to_string();
+    if local_only() && !is_local(&host) {
+        return attempt.error(RemoteRedirect(host));
+    }
+    attempt.follow()
+}
+
+// Redirect to a remote host refused by `redirect`; send() reports it as HttpError::LocalOnly
+#[derive(Debug)]
+struct RemoteRedirect(String);
+
+impl std::fmt::Display for RemoteRedirect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "redirect to {} blocked: local-only mode is on", self.0)
+    }
+}
+
+impl std::error::Error for RemoteRedirect {}
+
+// Route requests through a proxy (see proxy.rs); `key` describes its settings. Returns whether anything changed.
+// Requests already in flight finish on the old pool.
+pub fn set_proxy(key: Option<String>, proxy: Option<reqwest::Proxy>) -> bool {
+    let mut shared = pool().write().unwrap();
+    let key = key.filter(|_| proxy.is_some());
+    if shared.proxy.as_ref().map(|(k, _)| k) == key.as_ref() {
+        return false;
+    }
+    let settings = key.zip(proxy.clone());
+    *shared = Shared { client: build(proxy), proxy: settings };
+    true
+}
+
+static LOCAL_ONLY: AtomicBool = AtomicBool::new(false);
+
+// Applied from config (see config::apply); takes effect for the next request
+pub fn set_local_only(on: bool) {
+    if LOCAL_ONLY.swap(on, Ordering::SeqCst) != on {
+        match on {
+            true => tracing::info!(target: "privacy", "HTTP: Local-only mode on; requests to remote hosts are refused"),
+            false => tracing::info!(target: "privacy", "HTTP: Local-only mode off"),
+        }
+    }
+}
+
+pub fn local_only() -> bool {
+    LOCAL_ONLY.load(Ordering::SeqCst)
+}
+
+// Loopback names and addresses (127.0.0.0/8, ::1)
+pub fn is_local(host: &str) -> bool {
+    LOCAL_HOSTS.contains(&host)
+        || host.trim_start_matches('[').trim_end_matches(']').parse::<std::net::IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+// Override the rate limit for a host ("api.example.com", or "host:port" for non-default ports)
+pub fn set_host_limit(host: &str, rate: f64, burst: f64) {
+    let (rate, burst) = (rate.max(0.01), burst.max(1.0));
+    registry().limits.lock().unwrap().insert(host.to_string(), (rate, burst));
+    if let Some(state) = registry().hosts.lock().unwrap().get_mut(host) {
+        state.rate = rate;
+        state.burst = burst;
+        state.tokens = state.tokens.min(burst);
+    }
+}
+
+// Read a body of at most `max` bytes. Content-Length is checked up front, and the cap is also
+// enforced while streaming since the header can be missing or wrong.
+pub async fn read_body(mut response: reqwest::Response, max: usize) -> Result<Vec<u8>, HttpError> {
+    if response.content_length().is_some_and(|len| len > max as u64) {
+        return Err(HttpError::TooLarge(max));
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| HttpError::Request(e.to_string()))? {
+        if body.len() + chunk.len() > max {
+            return Err(HttpError::TooLarge(max));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+pub fn stats() -> HttpStats {
+    let hosts = registry().hosts.lock().unwrap();
+    let mut list: Vec<HostStats> = hosts
+        .iter()
+        .map(|(host, s)| HostStats {
+            host: host.clone(),
+            requests: s.requests,
+            failures: s.failures,
+            retries: s.retries,
+            rejected: s.rejected,
+            avg_latency_ms: if s.requests > 0 { s.total_latency_ms as f64 / s.requests as f64 } else { 0.0 },
+            max_latency_ms: s.max_latency_ms,
+            circuit: s.circuit,
+            last_error: s.last_error.clone(),
+        })
+        .collect();
+    list.sort_by(|a, b| b.requests.cmp(&a.requests));
+    HttpStats {
+        requests: list.iter().map(|h| h.requests).sum(),
+        failures: list.iter().map(|h| h.failures).sum(),
+        hosts: list,
+    }
+}
+
+// Close a host's circuit by hand (e.g. after the user fixes an API key)
+pub fn reset_circuit(host: &str) -> bool {
+    match registry().hosts.lock().unwrap().get_mut(host) {
+        Some(state) => {
+            state.circuit = CircuitState::Closed;
+            state.consecutive_failures = 0;
+            state.opened_at = None;
+            state.trial_in_flight = false;
+            true
+        }
+        None => false,
+    }
+}
+
+#[derive(Clone)]
+pub struct Client {
+    timeout: Duration,
+    max_retries: u32,
+    user_agent: Option<String>,
+    purpose: &'static str,             // Shown in the network audit log
+}
+
+impl Client {
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout, max_retries: DEFAULT_RETRIES, user_agent: None, purpose: "unspecified" }
+    }
+
+    // Tag for the network audit log ("web-search", "embeddings", ...)
+    pub fn with_purpose(mut self, purpose: &'static str) -> Self {
+        self.purpose = purpose;
+        self
+    }
+
+    // Retries after the first attempt (0 disables retrying)
+    pub fn with_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn with_user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    pub fn get(&self, url: impl reqwest::IntoUrl) -> reqwest::RequestBuilder {
+        self.request(reqwest::Method::GET, url)
+    }
+
+    pub fn post(&self, url: impl reqwest::IntoUrl) -> reqwest::RequestBuilder {
+        self.request(reqwest::Method::POST, url)
+    }
+
+    pub fn request(&self, method: reqwest::Method, url: impl reqwest::IntoUrl) -> reqwest::RequestBuilder {
+        let builder = shared().request(method, url).timeout(self.timeout);
+        match &self.user_agent {
+            Some(ua) => builder.header(reqwest::header::USER_AGENT, ua),
+            None => builder,
+        }
+    }
+
+    // Send with rate limiting, retries on transient failures, and the host's circuit breaker.
+    // Non-2xx responses that aren't retried are returned as Ok for the caller to inspect.
+    pub async fn send(&self, builder: reqwest::RequestBuilder) -> Result<reqwest::Response, HttpError> {
+        let request = builder.build().map_err(|e| HttpError::InvalidRequest(e.to_string()))?;
+        let local = is_local(request.url().host_str().unwrap_or(""));
+        // Keyed by host and non-default port, so local services on one address are tracked apart
+        let host = match request.url().port() {
+            Some(port) => format!("{}:{}", request.url().host_str().unwrap_or(""), port),
+            None => request.url().host_str().unwrap_or("").to_string(),
+        };
+        // Streaming bodies can't be replayed
+        let retries = if request.try_clone().is_some() { self.max_retries } else { 0 };
+
+        // Recorded in the network audit so the refusal itself is visible
+        if !local && local_only() {
+            let error = HttpError::LocalOnly(host.clone());
+            crate::network_audit::record(crate::network_audit::AuditEntry {
+                id: 0,
+                timestamp: chrono::Utc::now().timestamp_millis(),
+                method: request.method().to_string(),
+                host,
+                path: request.url().path().to_string(),
+                purpose: self.purpose.to_string(),
+                status: None,
+                bytes_sent: 0,
+                bytes_received: None,
+                duration_ms: 0,
+                privacy_mode: String::new(),
+                local,
+                error: Some(error.to_string()),
+            });
+            return Err(error);
+        }
+
+        // Offline: fail now instead of waiting out connect timeouts
+        if !local && crate::connectivity::is_offline() {
+            return Err(HttpError::Offline);
+        }
+
+        let mut pending = Some(request);
+        let mut attempt = 0;
+        loop {
+            admit(&host)?;
+            if !local {
+                throttle(&host).await;
+            }
+
+            let current = match pending.take() {
+                Some(request) => {
+                    if attempt < retries {
+                        pending = request.try_clone();
+                    }
+                    request
+                }
+                None => return Err(HttpError::InvalidRequest("request could not be retried".to_string())),
+            };
+
+            let method = current.method().to_string();
+            let path = current.url().path().to_string();
+            let bytes_sent = current.body().and_then(|b| b.as_bytes()).map_or(0, |b| b.len() as u64);
+            let sent_at = chrono::Utc::now().timestamp_millis();
+            let started = Instant::now();
+            let result = shared().execute(current).await;
+            let latency = started.elapsed();
+            crate::network_audit::record(crate::network_audit::AuditEntry {
+                id: 0,
+                timestamp: sent_at,
+                method,
+                host: host.clone(),
+                path,
+                purpose: self.purpose.to_string(),
+                status: result.as_ref().ok().map(|r| r.status().as_u16()),
+                bytes_sent,
+                bytes_received: result.as_ref().ok().and_then(|r| r.content_length()),
+                duration_ms: latency.as_millis() as u64,
+                privacy_mode: String::new(),
+                local,
+                error: result.as_ref().err().map(|e| e.to_string()),
+            });
+
+            let (transient, error, retry_after) = match &result {
+                Ok(response) => {
+                    let status = response.status();
+                    let transient = matches!(status.as_u16(), 429 | 502 | 503 | 504);
+                    let failed = status.is_server_error();
+                    (transient, failed.then(|| format!("HTTP {}", status.as_u16())), retry_after(response))
+                }
+                Err(e) => {
+                    if !local && (e.is_connect() || e.is_timeout()) {
+                        crate::connectivity::request_probe();
+                    }
+                    (e.is_timeout() || e.is_connect() || e.is_request(), Some(e.to_string()), None)
+                }
+            };
+            record(&host, latency, error.clone(), attempt > 0);
+            if let Some(wait) = retry_after {
+                block_host(&host, wait);
+            }
+
+            if transient && attempt < retries && pending.is_some() {
+                attempt += 1;
+                let backoff = retry_after.unwrap_or_else(|| backoff(attempt)).min(BACKOFF_MAX);
+                tracing::debug!(target: "app", "HTTP: {} failed ({}), retry {} in {:?}", host, error.as_deref().unwrap_or("transient"), attempt, backoff);
+                tokio::time::sleep(backoff).await;
+                continue;
+            }
+            return result.map_err(|e| {
+                let source = std::error::Error::source(&e);
+                match source.and_then(|source| source.downcast_ref::<RemoteRedirect>()) {
+                    Some(RemoteRedirect(host)) => HttpError::LocalOnly(host.clone()),
+                    None => HttpError::Request(e.to_string()),
+                }
+            });
+        }
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Self::new(DEFAULT_TIMEOUT)
+    }
+}
+
+// Exponential backoff with jitter: 0.5s, 1s, 2s, ... (±25%)
+fn backoff(attempt: u32) -> Duration {
+    let base = BACKOFF_BASE.as_millis() as u64 * 2u64.saturating_pow(attempt.saturating_sub(1));
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    let jitter = u64::from(nanos) % (base / 2 + 1);
+    Duration::from_millis(base * 3 / 4 + jitter)
+}
+
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    if response.status().as_u16() != 429 && response.status().as_u16() != 503 {
+        return None;
+    }
+    let value = response.headers().get(reqwest::header::RETRY_AFTER)?.to_str().ok()?;
+    value.trim().parse::<u64>().ok().map(Duration::from_secs)
+}
+
+fn with_host<T>(host: &str, f: impl FnOnce(&mut HostState) -> T) -> T {
+    let (rate, burst) = registry()
+        .limits
+        .lock()
+        .unwrap()
+        .get(host)
+        .copied()
+        .unwrap_or((DEFAULT_RATE, DEFAULT_BURST));
+    let mut hosts = registry().hosts.lock().unwrap();
+    f(hosts.entry(host.to_string()).or_insert_with(|| HostState::new(rate, burst)))
+}
+
+// Circuit breaker gate
+fn admit(host: &str) -> Result<(), HttpError> {
+    with_host(host, |state| match state.circuit {
+        CircuitState::Closed => Ok(()),
+        CircuitState::Open => {
+            if state.opened_at.map(|t| t.elapsed() >= OPEN_FOR).unwrap_or(true) {
+                state.circuit = CircuitState::HalfOpen;
+                state.trial_in_flight = true;
+                Ok(())
+            } else {
+                state.rejected += 1;
+                Err(HttpError::CircuitOpen(host.to_string()))
+            }
+        }
+        CircuitState::HalfOpen => {
+            if state.trial_in_flight {
+                state.rejected += 1;
+                Err(HttpError::CircuitOpen(host.to_string()))
+            } else {
+                state.trial_in_flight = true;
+                Ok(())
+            }
+        }
+    })
+}
+
+async fn throttle(host: &str) {
+    while let Some(wait) = with_host(host, |state| state.take_token(Instant::now())) {
+        tokio::time::sleep(wait).await;
+    }
+}
+
+fn block_host(host: &str, wait: Duration) {
+    with_host(host, |state| state.blocked_until = Some(Instant::now() + wait.min(Duration::from_secs(300))));
+}
+
+fn record(host: &str, latency: Duration, error: Option<String>, retry: bool) {
+    with_host(host, |state| {
+        let latency_ms = latency.as_millis() as u64;
+        state.requests += 1;
+        state.total_latency_ms += latency_ms;
+        state.max_latency_ms = state.max_latency_ms.max(latency_ms);
+        state.trial_in_flight = false;
+        if retry {
+            state.retries += 1;
+        }
+        match error {
+            Some(error) => {
+                state.failures += 1;
+                state.consecutive_failures += 1;
+                state.last_error = Some(error);
+                let trip = state.circuit == CircuitState::HalfOpen || state.consecutive_failures >= FAILURE_THRESHOLD;
+                if trip && state.circuit != CircuitState::Open {
+                    tracing::warn!(target: "app", "HTTP: Circuit open for {} after {} failures", host, state.consecutive_failures);
+                    state.circuit = CircuitState::Open;
+                    state.opened_at = Some(Instant::now());
+                }
+            }
+            None => {
+                state.consecutive_failures = 0;
+                if state.circuit != CircuitState::Closed {
+                    tracing::info!(target: "app", "HTTP: Circuit closed for {}", host);
+                }
+                state.circuit = CircuitState::Closed;
+                state.opened_at = None;
+            }
+        }
+    })
+}
+
+#[derive(Debug, Clone)]
+pub enum HttpError {
+    InvalidRequest(String),
+    CircuitOpen(String),
+    Offline,
+    LocalOnly(String),                 // Remote host refused in local-only mode
+    Request(String),
+    TooLarge(usize),                   // Body passed the caller's limit (bytes)
+}
+
+impl std::fmt::Display for HttpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HttpError::InvalidRequest(msg) => write!(f, "Invalid request: {}", msg),
+            HttpError::CircuitOpen(host) => write!(f, "{} is failing; requests paused briefly", host),
+            HttpError::Offline => write!(f, "No network connection"),
+            HttpError::LocalOnly(host) => write!(f, "Request to {} blocked: local-only mode is on", host),
+            HttpError::Request(msg) => write!(f, "{}", msg),
+            HttpError::TooLarge(max) => write!(f, "Response is larger than {} bytes", max),
+        }
+    }
+}
+
+impl std::error::Error for HttpError {}
@@ -0,0 +1,931 @@
+// Images - Dimensions, EXIF/GPS, OCR, and captions for local images, bulk download from pages (Images mode)
+// Metadata is read straight from the file headers; captions come from a local Ollama vision model when one is installed
+
+use base64::Engine;
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+use crate::db::Database;
+use crate::local_index::{IndexDocument, LocalIndex};
+use crate::privacy::PrivacyEnforcer;
+
+const MAX_IMAGE_BYTES: u64 = 50 * 1024 * 1024;
+// Vision models get the whole file base64-encoded in one request
+const MAX_CAPTION_BYTES: u64 = 20 * 1024 * 1024;
+const CAPTION_TIMEOUT: Duration = Duration::from_secs(180);
+const TAGS_TIMEOUT: Duration = Duration::from_secs(3);
+const IMAGE_TIMEOUT: Duration = Duration::from_secs(30);
+const CONCURRENT_DOWNLOADS: usize = 6;
+// Galleries and infinite-scroll pages can reference thousands of thumbnails
+const MAX_COLLECT_IMAGES: usize = 500;
+// Installed Ollama models that accept images, in order of preference (name prefixes)
+const VISION_MODELS: &[&str] = &["llava", "llama3.2-vision", "bakllava", "minicpm-v", "qwen2.5vl", "moondream", "gemma3"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFormat {
+    Jpeg,
+    Png,
+    Gif,
+    Webp,
+    Bmp,
+    Tiff,
+}
+
+impl ImageFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "jpeg",
+            ImageFormat::Png => "png",
+            ImageFormat::Gif => "gif",
+            ImageFormat::Webp => "webp",
+            ImageFormat::Bmp => "bmp",
+            ImageFormat::Tiff => "tiff",
+        }
+    }
+
+    // Names accepted in filters; "jpg" and "tif" are the usual spellings
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().trim_start_matches('.').to_ascii_lowercase().as_str() {
+            "jpeg" | "jpg" => Some(ImageFormat::Jpeg),
+            "png" => Some(ImageFormat::Png),
+            "gif" => Some(ImageFormat::Gif),
+            "webp" => Some(ImageFormat::Webp),
+            "bmp" => Some(ImageFormat::Bmp),
+            "tiff" | "tif" => Some(ImageFormat::Tiff),
+            _ => None,
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "jpg",
+            ImageFormat::Tiff => "tif",
+            other => other.as_str(),
+        }
+    }
+
+    fn sniff(data: &[u8]) -> Option<Self> {
+        match data {
+            [0xFF, 0xD8, 0xFF, ..] => Some(ImageFormat::Jpeg),
+            [0x89, b'P', b'N', b'G', ..] => Some(ImageFormat::Png),
+            [b'G', b'I', b'F', b'8', ..] => Some(ImageFormat::Gif),
+            [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some(ImageFormat::Webp),
+            [b'B', b'M', ..] => Some(ImageFormat::Bmp),
+            [b'I', b'I', 42, 0, ..] | [b'M', b'M', 0, 42, ..] => Some(ImageFormat::Tiff),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ExifData {
+    pub make: Option<String>,
+    pub model: Option<String>,
+    pub lens: Option<String>,
+    pub software: Option<String>,
+    pub taken_at: Option<String>,      // "YYYY-MM-DD HH:MM:SS", camera local time
+    pub orientation: Option<u16>,      // 1-8; 5-8 mean width and height display swapped
+    pub exposure_time: Option<String>, // "1/250"
+    pub f_number: Option<f64>,
+    pub iso: Option<u32>,
+    pub focal_length_mm: Option<f64>,
+    pub artist: Option<String>,
+    pub copyright: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct GpsPosition {
+    pub latitude: f64,                 // Decimal degrees, south negative
+    pub longitude: f64,                // Decimal degrees, west negative
+    pub altitude_m: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ImageInfo {
+    pub path: String,
+    pub name: String,
+    pub format: ImageFormat,
+    pub width: u32,
+    pub height: u32,
+    pub size_bytes: u64,
+    pub exif: Option<ExifData>,
+    pub gps: Option<GpsPosition>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ImageCaption {
+    pub path: String,
+    pub caption: String,
+    pub model: String,
+    pub elapsed_ms: u64,
+}
+
+// What is stored per image path; every analysis fills in its own part
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageRecord {
+    pub path: String,
+    pub name: String,
+    pub format: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub taken_at: Option<String>,
+    pub camera: Option<String>,        // "Make Model"
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub ocr_text: Option<String>,
+    pub caption: Option<String>,
+    pub caption_model: Option<String>,
+    pub updated_at: i64,               // Unix seconds
+}
+
+impl ImageRecord {
+    fn new(path: &str) -> Self {
+        Self { path: path.to_string(), name: file_name(path), ..Default::default() }
+    }
+
+    // Caption and OCR text, the parts worth searching
+    pub fn searchable_text(&self) -> String {
+        [self.caption.as_deref(), self.ocr_text.as_deref()].into_iter().flatten().collect::<Vec<_>>().join("\n\n")
+    }
+}
+
+// Dimensions, EXIF, and GPS from the file; nothing is decoded beyond the headers
+pub fn inspect(path: &str) -> Result<ImageInfo, ImageError> {
+    let data = read(path, MAX_IMAGE_BYTES)?;
+    let format = ImageFormat::sniff(&data).ok_or_else(|| ImageError::Unsupported(path.to_string()))?;
+    let (dimensions, parsed) = headers(&data, format);
+    let (width, height) = dimensions.ok_or_else(|| ImageError::Corrupt(path.to_string()))?;
+
+    Ok(ImageInfo {
+        path: path.to_string(),
+        name: file_name(path),
+        format,
+        width,
+        height,
+        size_bytes: data.len() as u64,
+        exif: parsed.as_ref().map(Tiff::exif),
+        gps: parsed.as_ref().and_then(Tiff::gps),
+    })
+}
+
+// Pixel size plus the EXIF block (a TIFF structure), whichever the container carries
+fn headers(data: &[u8], format: ImageFormat) -> (Option<(u32, u32)>, Option<Tiff<'_>>) {
+    let (dimensions, tiff) = match format {
+        ImageFormat::Jpeg => jpeg(data),
+        ImageFormat::Png => png(data),
+        ImageFormat::Gif => (gif(data), None),
+        ImageFormat::Webp => webp(data),
+        ImageFormat::Bmp => (bmp(data), None),
+        ImageFormat::Tiff => (None, Some(data)),
+    };
+    let parsed = tiff.and_then(Tiff::new);
+    let dimensions = dimensions.or_else(|| parsed.as_ref().and_then(|t| t.dimensions()));
+    (dimensions, parsed)
+}
+
+// Describe the image with a local vision model; the model comes from ai.visionModel or the installed models
+pub async fn caption(path: &str) -> Result<ImageCaption, ImageError> {
+    let data = read(path, MAX_CAPTION_BYTES)?;
+    if ImageFormat::sniff(&data).is_none() {
+        return Err(ImageError::Unsupported(path.to_string()));
+    }
+    let model = vision_model().await?;
+    let budget = crate::llm_usage::check("ollama", &model);
+    if budget.decision == crate::llm_usage::BudgetDecision::Block {
+        return Err(ImageError::Caption(budget.reason.unwrap_or_default()));
+    }
+
+    let started = Instant::now();
+    let prompt = crate::prompts::render("image_caption", None, None, &[]);
+    let body = serde_json::json!({
+        "model": model,
+        "prompt": prompt,
+        "images": [base64::engine::general_purpose::STANDARD.encode(&data)],
+        "stream": false,
+        "options": { "temperature": 0.2 },
+    });
+    let client = crate::http::Client::new(CAPTION_TIMEOUT).with_retries(0).with_purpose("image-caption");
+    let response = client
+        .send(client.post(format!("{}/api/generate", crate::embeddings::ollama_url())).json(&body))
+        .await
+        .map_err(|e| ImageError::Caption(e.to_string()))?;
+    if !response.status().is_success() {
+        let status = response.status().as_u16();
+        let body = response.text().await.unwrap_or_default();
+        return Err(ImageError::Caption(format!("HTTP {}: {}", status, body)));
+    }
+    let generated: OllamaGenerateResponse = response.json().await.map_err(|e| ImageError::Caption(e.to_string()))?;
+    crate::llm_usage::record("ollama", &model, generated.prompt_eval_count, generated.eval_count, true);
+
+    Ok(ImageCaption {
+        path: path.to_string(),
+        caption: generated.response.trim().to_string(),
+        model,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+#[derive(Deserialize)]
+struct OllamaGenerateResponse {
+    response: String,
+    #[serde(default)]
+    prompt_eval_count: u64,
+    #[serde(default)]
+    eval_count: u64,
+}
+
+#[derive(Deserialize)]
+struct OllamaTags {
+    models: Vec<OllamaModel>,
+}
+
+#[derive(Deserialize)]
+struct OllamaModel {
+    name: String,
+}
+
+async fn vision_model() -> Result<String, ImageError> {
+    if let Some(model) = crate::config::current().ai.vision_model {
+        return Ok(model);
+    }
+    let client = crate::http::Client::new(TAGS_TIMEOUT).with_retries(0).with_purpose("image-caption");
+    let tags: OllamaTags = match client.send(client.get(format!("{}/api/tags", crate::embeddings::ollama_url()))).await {
+        Ok(response) if response.status().is_success() => response.json().await.map_err(|e| ImageError::Caption(e.to_string()))?,
+        _ => return Err(ImageError::NoVisionModel),
+    };
+    VISION_MODELS
+        .iter()
+        .find_map(|prefix| tags.models.iter().find(|m| m.name.starts_with(prefix)))
+        .map(|m| m.name.clone())
+        .ok_or(ImageError::NoVisionModel)
+}
+
+// Merge a new result into the stored record and re-index it; nothing is kept in Private/Ghost mode
+pub fn remember(app: &tauri::AppHandle, path: &str, update: impl FnOnce(&mut ImageRecord)) {
+    if !app.state::<Mutex<PrivacyEnforcer>>().lock().unwrap().can_write_to_disk() {
+        return;
+    }
+    let db = app.state::<Database>();
+    let mut record = match db.image_get(path) {
+        Ok(record) => record.unwrap_or_else(|| ImageRecord::new(path)),
+        Err(e) => {
+            tracing::warn!(target: "db", "Images: Failed to load {}: {}", path, e);
+            return;
+        }
+    };
+    update(&mut record);
+    record.updated_at = chrono::Utc::now().timestamp();
+    if let Err(e) = db.image_save(&record) {
+        tracing::warn!(target: "db", "Images: Failed to save {}: {}", path, e);
+        return;
+    }
+    if let Some(local_index) = app.try_state::<LocalIndex>() {
+        local_index.image_saved(IndexDocument::image(&record));
+    }
+}
+
+impl ImageInfo {
+    // The stored fields this inspection provides
+    pub fn apply(&self, record: &mut ImageRecord) {
+        record.format = Some(self.format.as_str().to_string());
+        record.width = Some(self.width);
+        record.height = Some(self.height);
+        let exif = self.exif.clone().unwrap_or_default();
+        record.taken_at = exif.taken_at;
+        record.camera = match (exif.make, exif.model) {
+            // Models usually repeat the make ("Canon" + "Canon EOS R6")
+            (Some(make), Some(model)) if model.starts_with(&make) => Some(model),
+            (Some(make), Some(model)) => Some(format!("{} {}", make, model)),
+            (make, model) => make.or(model),
+        };
+        record.latitude = self.gps.map(|g| g.latitude);
+        record.longitude = self.gps.map(|g| g.longitude);
+    }
+}
+
+fn read(path: &str, limit: u64) -> Result<Vec<u8>, ImageError> {
+    let size = std::fs::metadata(path).map_err(|e| ImageError::Io(e.to_string()))?.len();
+    if size > limit {
+        return Err(ImageError::TooLarge(size, limit));
+    }
+    std::fs::read(path).map_err(|e| ImageError::Io(e.to_string()))
+}
+
+fn file_name(path: &str) -> String {
+    Path::new(path).file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default()
+}
+
+// ============================================================================
+// PAGE COLLECTION
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct CollectedImage {
+    pub url: String,
+    pub path: String,
+    pub format: ImageFormat,
+    pub width: u32,
+    pub height: u32,
+    pub size_bytes: u64,
+    pub sha256: String,
+    pub download_id: String,           // Row in the downloads list
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedImage {
+    pub url: String,
+    pub reason: String,
+}
+
+// What images_collect returns; also written next to the images as manifest.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ImageManifest {
+    pub page_url: String,
+    pub folder: String,
+    pub found: usize,
+    pub images: Vec<CollectedImage>,
+    pub skipped: Vec<SkippedImage>,    // Filtered out by size or format
+    pub failed: Vec<SkippedImage>,     // Download or write errors
+    pub collected_at: i64,             // Unix seconds
+}
+
+// Every image URL on a page, in document order: <img>/<picture> sources (largest srcset
+// candidate, lazy-load attributes), og:image, and links straight to image files
+pub fn page_images(html: &str, base_url: &str) -> Vec<String> {
+    let document = scraper::Html::parse_document(html);
+    let base = url::Url::parse(base_url).ok();
+    let selector = scraper::Selector::parse(
+        "img, picture source, meta[property='og:image'], meta[name='twitter:image'], link[rel='image_src'], a[href]",
+    )
+    .unwrap();
+
+    let mut seen = std::collections::HashSet::new();
+    let mut urls = Vec::new();
+    for element in document.select(&selector) {
+        let el = element.value();
+        let candidates: Vec<&str> = match el.name() {
+            "meta" => el.attr("content").into_iter().collect(),
+            "link" => el.attr("href").into_iter().collect(),
+            "a" => el.attr("href").filter(|href| url_format(href).is_some()).into_iter().collect(),
+            _ => {
+                let srcset = ["srcset", "data-srcset"].iter().find_map(|a| el.attr(a)).and_then(largest_candidate);
+                // Lazy loaders keep the real URL in a data attribute and a placeholder in src
+                let src = ["data-src", "data-original", "data-lazy-src", "src"].iter().find_map(|a| el.attr(a));
+                srcset.into_iter().chain(src).take(1).collect()
+            }
+        };
+        for href in candidates {
+            if let Some(absolute) = crate::extractor::resolve_url(base.as_ref(), href) {
+                if absolute.starts_with("http") && seen.insert(absolute.clone()) {
+                    urls.push(absolute);
+                }
+            }
+        }
+    }
+    urls
+}
+
+// The widest (or highest-density) entry of a srcset: "a.jpg 480w, b.jpg 1080w"
+fn largest_candidate(srcset: &str) -> Option<&str> {
+    srcset
+        .split(',')
+        .filter_map(|candidate| {
+            let mut parts = candidate.split_whitespace();
+            let url = parts.next()?;
+            let descriptor = parts.next().unwrap_or("1x");
+            let size = descriptor.trim_end_matches(['w', 'x']).parse::<f64>().unwrap_or(1.0);
+            // Width descriptors outrank density ones when a srcset mixes both
+            let rank = if descriptor.ends_with('w') { size } else { size * 1e-6 };
+            Some((url, rank))
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(url, _)| url)
+}
+
+// Format implied by the URL's file extension, if it has a recognizable one
+fn url_format(url: &str) -> Option<ImageFormat> {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let extension = path.rsplit('/').next()?.rsplit_once('.')?.1;
+    ImageFormat::parse(extension)
+}
+
+// Download every image on `page_url` into `folder`, keeping those at least `min_width` wide and
+// in one of `formats` (all supported formats when empty); each file is listed in the downloads
+pub async fn collect(
+    app: &tauri::AppHandle,
+    page_url: &str,
+    folder: &Path,
+    min_width: u32,
+    formats: &[ImageFormat],
+) -> Result<ImageManifest, ImageError> {
+    let html = crate::extractor::fetch_html(page_url).await.map_err(|e| ImageError::Fetch(e.to_string()))?;
+    let found = page_images(&html, page_url);
+    std::fs::create_dir_all(folder).map_err(|e| ImageError::Io(e.to_string()))?;
+
+    let allowed = |format: ImageFormat| formats.is_empty() || formats.contains(&format);
+    let mut skipped = Vec::new();
+    let mut pending = Vec::new();
+    for url in found.iter().take(MAX_COLLECT_IMAGES) {
+        match url_format(url) {
+            // Trust an explicit extension to avoid downloading what would be thrown away
+            Some(format) if !allowed(format) => skipped.push(SkippedImage { url: url.clone(), reason: format!("format {}", format.as_str()) }),
+            _ => pending.push(url.clone()),
+        }
+    }
+    for url in found.iter().skip(MAX_COLLECT_IMAGES) {
+        skipped.push(SkippedImage { url: url.clone(), reason: format!("over the {} image limit", MAX_COLLECT_IMAGES) });
+    }
+
+    // Downloads stay out of the history in Private/Ghost mode; the files are still written
+    let record = app.state::<Mutex<PrivacyEnforcer>>().lock().unwrap().can_write_to_disk();
+    let db = app.state::<Database>().inner().clone();
+    let client = crate::http::Client::new(IMAGE_TIMEOUT).with_purpose("image-collect");
+    let mut downloads = stream::iter(pending)
+        .map(|url| {
+            let client = client.clone();
+            async move {
+                let result = fetch_image(&client, &url).await;
+                (url, result)
+            }
+        })
+        .buffer_unordered(CONCURRENT_DOWNLOADS);
+
+    let (mut images, mut failed) = (Vec::new(), Vec::new());
+    let mut names = std::collections::HashSet::new();
+    while let Some((url, result)) = downloads.next().await {
+        let data = match result {
+            Ok(data) => data,
+            Err(reason) => {
+                failed.push(SkippedImage { url, reason });
+                continue;
+            }
+        };
+        // SVG and other non-raster responses have no pixel size to filter on
+        let Some(format) = ImageFormat::sniff(&data) else {
+            skipped.push(SkippedImage { url, reason: "not a supported image format".to_string() });
+            continue;
+        };
+        let Some((width, height)) = headers(&data, format).0 else {
+            failed.push(SkippedImage { url, reason: "could not read the image size".to_string() });
+            continue;
+        };
+        if !allowed(format) {
+            skipped.push(SkippedImage { url, reason: format!("format {}", format.as_str()) });
+            continue;
+        }
+        if width < min_width {
+            skipped.push(SkippedImage { url, reason: format!("{}px wide", width) });
+            continue;
+        }
+
+        let (name, path) = match save_new(folder, &mut names, &url, format, &data) {
+            Ok(saved) => saved,
+            Err(e) => {
+                failed.push(SkippedImage { url, reason: e.to_string() });
+                continue;
+            }
+        };
+        let path = path.to_string_lossy().into_owned();
+        let sha256 = format!("{:x}", Sha256::digest(&data));
+        let download_id = uuid::Uuid::new_v4().to_string();
+        if record {
+            let size = data.len() as i64;
+            let saved = db.save_download(
+                &download_id,
+                &url,
+                Some(name.as_str()),
+                Some(path.as_str()),
+                "completed",
+                1.0,
+                size,
+                Some(size),
+                Some(sha256.as_str()),
+                None,
+            );
+            if let Err(e) = saved {
+                tracing::warn!(target: "db", "Images: Failed to record download of {}: {}", url, e);
+            }
+        }
+        images.push(CollectedImage { url, path, format, width, height, size_bytes: data.len() as u64, sha256, download_id });
+    }
+
+    // Completion order is arbitrary; keep the page's order
+    images.sort_by_key(|image| found.iter().position(|u| *u == image.url));
+    let manifest = ImageManifest {
+        page_url: page_url.to_string(),
+        folder: folder.to_string_lossy().into_owned(),
+        found: found.len(),
+        images,
+        skipped,
+        failed,
+        collected_at: chrono::Utc::now().timestamp(),
+    };
+    let json = serde_json::to_vec_pretty(&manifest).map_err(|e| ImageError::Io(e.to_string()))?;
+    std::fs::write(folder.join("manifest.json"), json).map_err(|e| ImageError::Io(e.to_string()))?;
+    Ok(manifest)
+}
+
+async fn fetch_image(client: &crate::http::Client, url: &str) -> Result<Vec<u8>, String> {
+    let response = client.send(client.get(url)).await.map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status().as_u16()));
+    }
+    if response.content_length().is_some_and(|length| length > MAX_IMAGE_BYTES) {
+        return Err(ImageError::TooLarge(response.content_length().unwrap_or_default(), MAX_IMAGE_BYTES).to_string());
+    }
+    // Chunked responses have no Content-Length; the cap also applies while streaming
+    crate::http::read_body(response, MAX_IMAGE_BYTES as usize).await.map_err(|e| e.to_string())
+}
+
+// Write without replacing anything already in the folder; a taken name moves on to the next suffix
+fn save_new(
+    folder: &Path,
+    taken: &mut std::collections::HashSet<String>,
+    url: &str,
+    format: ImageFormat,
+    data: &[u8],
+) -> std::io::Result<(String, std::path::PathBuf)> {
+    use std::io::Write;
+
+    loop {
+        let name = unique_name(taken, url, format);
+        let path = folder.join(&name);
+        match std::fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                file.write_all(data)?;
+                return Ok((name, path));
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+// File name from the URL's last path segment, with the sniffed format's extension
+fn unique_name(taken: &mut std::collections::HashSet<String>, url: &str, format: ImageFormat) -> String {
+    let segment = url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.path_segments().and_then(|mut s| s.next_back().map(str::to_string)))
+        .unwrap_or_default();
+    let stem = segment.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(&segment);
+    let mut stem: String = stem
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '-' | '_') { c } else { '_' })
+        .take(80)
+        .collect();
+    if stem.trim_matches('_').is_empty() {
+        stem = "image".to_string();
+    }
+    let mut name = format!("{}.{}", stem, format.extension());
+    let mut n = 2;
+    while !taken.insert(name.to_lowercase()) {
+        name = format!("{}-{}.{}", stem, n, format.extension());
+        n += 1;
+    }
+    name
+}
+
+// ============================================================================
+// CONTAINER FORMATS
+// ============================================================================
+
+// Frame size from the first SOF marker, EXIF from the APP1 segment
+fn jpeg(data: &[u8]) -> (Option<(u32, u32)>, Option<&[u8]>) {
+    let (mut dimensions, mut exif) = (None, None);
+    let mut i = 2;
+    while i + 4 <= data.len() && (dimensions.is_none() || exif.is_none()) {
+        if data[i] != 0xFF {
+            break;
+        }
+        let marker = data[i + 1];
+        if marker == 0xFF {
+            i += 1;                        // Fill byte
+            continue;
+        }
+        if marker == 0xD9 || marker == 0xDA {
+            break;                         // End of image / start of scan
+        }
+        let length = u16::from_be_bytes([data[i + 2], data[i + 3]]) as usize;
+        let segment = data.get(i + 4..i + 2 + length).unwrap_or_default();
+        match marker {
+            0xE1 if segment.starts_with(b"Exif\0\0") => exif = Some(&segment[6..]),
+            0xC0..=0xCF if !matches!(marker, 0xC4 | 0xC8 | 0xCC) && segment.len() >= 5 => {
+                let height = u16::from_be_bytes([segment[1], segment[2]]) as u32;
+                let width = u16::from_be_bytes([segment[3], segment[4]]) as u32;
+                dimensions = Some((width, height));
+            }
+            _ => {}
+        }
+        i += 2 + length;
+    }
+    (dimensions, exif)
+}
+
+// IHDR size; EXIF from the eXIf chunk
+fn png(data: &[u8]) -> (Option<(u32, u32)>, Option<&[u8]>) {
+    let dimensions = data.get(16..24).map(|d| (be32(&d[..4]), be32(&d[4..])));
+    let mut exif = None;
+    let mut i = 8;
+    while i + 8 <= data.len() {
+        let length = be32(&data[i..i + 4]) as usize;
+        let kind = &data[i + 4..i + 8];
+        if kind == b"eXIf" {
+            exif = data.get(i + 8..i + 8 + length);
+            break;
+        }
+        if kind == b"IDAT" || kind == b"IEND" {
+            break;
+        }
+        i += 12 + length;
+    }
+    (dimensions, exif)
+}
+
+fn gif(data: &[u8]) -> Option<(u32, u32)> {
+    let d = data.get(6..10)?;
+    Some((u16::from_le_bytes([d[0], d[1]]) as u32, u16::from_le_bytes([d[2], d[3]]) as u32))
+}
+
+fn bmp(data: &[u8]) -> Option<(u32, u32)> {
+    let d = data.get(18..26)?;
+    let width = i32::from_le_bytes([d[0], d[1], d[2], d[3]]);
+    // Negative height means top-down rows
+    let height = i32::from_le_bytes([d[4], d[5], d[6], d[7]]);
+    Some((width.unsigned_abs(), height.unsigned_abs()))
+}
+
+// Size from the VP8/VP8L/VP8X chunk, EXIF from the EXIF chunk
+fn webp(data: &[u8]) -> (Option<(u32, u32)>, Option<&[u8]>) {
+    let (mut dimensions, mut exif) = (None, None);
+    let mut i = 12;
+    while i + 8 <= data.len() {
+        let kind = &data[i..i + 4];
+        let length = u32::from_le_bytes([data[i + 4], data[i + 5], data[i + 6], data[i + 7]]) as usize;
+        let chunk = data.get(i + 8..i + 8 + length).unwrap_or_default();
+        match kind {
+            b"VP8X" if chunk.len() >= 10 => {
+                let width = 1 + u32::from_le_bytes([chunk[4], chunk[5], chunk[6], 0]);
+                let height = 1 + u32::from_le_bytes([chunk[7], chunk[8], chunk[9], 0]);
+                dimensions = Some((width, height));
+            }
+            b"VP8 " if chunk.len() >= 10 && dimensions.is_none() => {
+                let width = u16::from_le_bytes([chunk[6], chunk[7]]) as u32 & 0x3FFF;
+                let height = u16::from_le_bytes([chunk[8], chunk[9]]) as u32 & 0x3FFF;
+                dimensions = Some((width, height));
+            }
+            b"VP8L" if chunk.len() >= 5 && dimensions.is_none() => {
+                let bits = u32::from_le_bytes([chunk[1], chunk[2], chunk[3], chunk[4]]);
+                dimensions = Some(((bits & 0x3FFF) + 1, ((bits >> 14) & 0x3FFF) + 1));
+            }
+            // Some writers keep the JPEG-style prefix
+            b"EXIF" => exif = Some(chunk.strip_prefix(b"Exif\0\0").unwrap_or(chunk)),
+            _ => {}
+        }
+        // Chunks are padded to an even length
+        i += 8 + length + (length & 1);
+    }
+    (dimensions, exif)
+}
+
+fn be32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+// ============================================================================
+// TIFF / EXIF
+// ============================================================================
+
+// IFD tags read here
+const TAG_WIDTH: u16 = 0x0100;
+const TAG_HEIGHT: u16 = 0x0101;
+const TAG_MAKE: u16 = 0x010F;
+const TAG_MODEL: u16 = 0x0110;
+const TAG_ORIENTATION: u16 = 0x0112;
+const TAG_SOFTWARE: u16 = 0x0131;
+const TAG_DATETIME: u16 = 0x0132;
+const TAG_ARTIST: u16 = 0x013B;
+const TAG_COPYRIGHT: u16 = 0x8298;
+const TAG_EXIF_IFD: u16 = 0x8769;
+const TAG_GPS_IFD: u16 = 0x8825;
+const TAG_EXPOSURE_TIME: u16 = 0x829A;
+const TAG_F_NUMBER: u16 = 0x829D;
+const TAG_ISO: u16 = 0x8827;
+const TAG_DATETIME_ORIGINAL: u16 = 0x9003;
+const TAG_FOCAL_LENGTH: u16 = 0x920A;
+const TAG_LENS_MODEL: u16 = 0xA434;
+const GPS_LATITUDE_REF: u16 = 0x0001;
+const GPS_LATITUDE: u16 = 0x0002;
+const GPS_LONGITUDE_REF: u16 = 0x0003;
+const GPS_LONGITUDE: u16 = 0x0004;
+const GPS_ALTITUDE_REF: u16 = 0x0005;
+const GPS_ALTITUDE: u16 = 0x0006;
+// Corrupt offsets can point IFDs at each other; real files have a handful of entries
+const MAX_IFD_ENTRIES: usize = 512;
+
+#[derive(Clone, Copy)]
+struct Entry {
+    tag: u16,
+    kind: u16,
+    count: u32,
+    value_at: usize,                   // Offset of the value (inline or pointed to)
+}
+
+struct Tiff<'a> {
+    data: &'a [u8],
+    little_endian: bool,
+    ifd0: Vec<Entry>,
+}
+
+impl<'a> Tiff<'a> {
+    fn new(data: &'a [u8]) -> Option<Self> {
+        let little_endian = match data.get(..2)? {
+            b"II" => true,
+            b"MM" => false,
+            _ => return None,
+        };
+        let mut tiff = Self { data, little_endian, ifd0: Vec::new() };
+        let offset = tiff.u32_at(4)? as usize;
+        tiff.ifd0 = tiff.ifd(offset);
+        Some(tiff)
+    }
+
+    fn u16_at(&self, at: usize) -> Option<u16> {
+        let b = self.data.get(at..at + 2)?;
+        Some(if self.little_endian { u16::from_le_bytes([b[0], b[1]]) } else { u16::from_be_bytes([b[0], b[1]]) })
+    }
+
+    fn u32_at(&self, at: usize) -> Option<u32> {
+        let b = self.data.get(at..at + 4)?;
+        let b = [b[0], b[1], b[2], b[3]];
+        Some(if self.little_endian { u32::from_le_bytes(b) } else { u32::from_be_bytes(b) })
+    }
+
+    fn ifd(&self, offset: usize) -> Vec<Entry> {
+        let count = (self.u16_at(offset).unwrap_or(0) as usize).min(MAX_IFD_ENTRIES);
+        (0..count)
+            .filter_map(|i| {
+                let at = offset + 2 + i * 12;
+                let (tag, kind, count) = (self.u16_at(at)?, self.u16_at(at + 2)?, self.u32_at(at + 4)?);
+                let size = match kind {
+                    1 | 2 | 6 | 7 => 1,
+                    3 | 8 => 2,
+                    4 | 9 | 11 => 4,
+                    5 | 10 | 12 => 8,
+                    _ => return None,
+                } * count as usize;
+                // Values of four bytes or less sit in the entry itself
+                let value_at = if size <= 4 { at + 8 } else { self.u32_at(at + 8)? as usize };
+                Some(Entry { tag, kind, count, value_at })
+            })
+            .collect()
+    }
+
+    fn sub_ifd(&self, entries: &[Entry], tag: u16) -> Vec<Entry> {
+        match find(entries, tag).and_then(|e| self.uint(&e)) {
+            Some(offset) => self.ifd(offset as usize),
+            None => Vec::new(),
+        }
+    }
+
+    fn ascii(&self, entry: &Entry) -> Option<String> {
+        let bytes = self.data.get(entry.value_at..entry.value_at + entry.count as usize)?;
+        let text = String::from_utf8_lossy(bytes).trim_matches(|c: char| c == '\0' || c.is_whitespace()).to_string();
+        (!text.is_empty()).then_some(text)
+    }
+
+    fn uint(&self, entry: &Entry) -> Option<u32> {
+        match entry.kind {
+            1 | 7 => self.data.get(entry.value_at).map(|b| *b as u32),
+            3 => self.u16_at(entry.value_at).map(u32::from),
+            4 => self.u32_at(entry.value_at),
+            _ => None,
+        }
+    }
+
+    fn rational(&self, entry: &Entry, index: usize) -> Option<(u32, u32)> {
+        if !matches!(entry.kind, 5 | 10) || index >= entry.count as usize {
+            return None;
+        }
+        let at = entry.value_at + index * 8;
+        Some((self.u32_at(at)?, self.u32_at(at + 4)?))
+    }
+
+    fn float(&self, entry: &Entry, index: usize) -> Option<f64> {
+        let (numerator, denominator) = self.rational(entry, index)?;
+        (denominator != 0).then(|| numerator as f64 / denominator as f64)
+    }
+
+    fn dimensions(&self) -> Option<(u32, u32)> {
+        Some((self.uint(&find(&self.ifd0, TAG_WIDTH)?)?, self.uint(&find(&self.ifd0, TAG_HEIGHT)?)?))
+    }
+
+    fn exif(&self) -> ExifData {
+        let sub = self.sub_ifd(&self.ifd0, TAG_EXIF_IFD);
+        let text = |entries: &[Entry], tag| find(entries, tag).and_then(|e| self.ascii(&e));
+        let taken_at = text(&sub, TAG_DATETIME_ORIGINAL).or_else(|| text(&self.ifd0, TAG_DATETIME)).map(|t| {
+            // EXIF writes dates as "2024:05:01 12:00:00"
+            match t.split_once(' ') {
+                Some((date, time)) => format!("{} {}", date.replace(':', "-"), time),
+                None => t.replace(':', "-"),
+            }
+        });
+        ExifData {
+            make: text(&self.ifd0, TAG_MAKE),
+            model: text(&self.ifd0, TAG_MODEL),
+            lens: text(&sub, TAG_LENS_MODEL),
+            software: text(&self.ifd0, TAG_SOFTWARE),
+            taken_at,
+            orientation: find(&self.ifd0, TAG_ORIENTATION).and_then(|e| self.uint(&e)).map(|o| o as u16),
+            exposure_time: find(&sub, TAG_EXPOSURE_TIME).and_then(|e| self.rational(&e, 0)).map(|(n, d)| match (n, d) {
+                (n, d) if n != 0 && d % n == 0 => format!("1/{}", d / n),
+                (n, d) => format!("{}", n as f64 / d.max(1) as f64),
+            }),
+            f_number: find(&sub, TAG_F_NUMBER).and_then(|e| self.float(&e, 0)),
+            iso: find(&sub, TAG_ISO).and_then(|e| self.uint(&e)),
+            focal_length_mm: find(&sub, TAG_FOCAL_LENGTH).and_then(|e| self.float(&e, 0)),
+            artist: text(&self.ifd0, TAG_ARTIST),
+            copyright: text(&self.ifd0, TAG_COPYRIGHT),
+        }
+    }
+
+    fn gps(&self) -> Option<GpsPosition> {
+        let gps = self.sub_ifd(&self.ifd0, TAG_GPS_IFD);
+        let degrees = |tag| {
+            let entry = find(&gps, tag)?;
+            Some(self.float(&entry, 0)? + self.float(&entry, 1).unwrap_or(0.0) / 60.0 + self.float(&entry, 2).unwrap_or(0.0) / 3600.0)
+        };
+        let reference = |tag| find(&gps, tag).and_then(|e| self.ascii(&e)).unwrap_or_default();
+        let mut latitude = degrees(GPS_LATITUDE)?;
+        let mut longitude = degrees(GPS_LONGITUDE)?;
+        if reference(GPS_LATITUDE_REF) == "S" {
+            latitude = -latitude;
+        }
+        if reference(GPS_LONGITUDE_REF) == "W" {
+            longitude = -longitude;
+        }
+        // Cameras without a fix often write zeros
+        if (latitude == 0.0 && longitude == 0.0) || latitude.abs() > 90.0 || longitude.abs() > 180.0 {
+            return None;
+        }
+        let below_sea_level = find(&gps, GPS_ALTITUDE_REF).and_then(|e| self.uint(&e)) == Some(1);
+        let altitude_m = find(&gps, GPS_ALTITUDE).and_then(|e| self.float(&e, 0)).map(|a| if below_sea_level { -a } else { a });
+        Some(GpsPosition { latitude, longitude, altitude_m })
+    }
+}
+
+fn find(entries: &[Entry], tag: u16) -> Option<Entry> {
+    entries.iter().find(|e| e.tag == tag).copied()
+}
+
+#[derive(Debug, Clone)]
+pub enum ImageError {
+    Io(String),
+    TooLarge(u64, u64),
+    Unsupported(String),
+    Corrupt(String),
+    NoVisionModel,
+    Caption(String),
+    Fetch(String),
+}
+
+impl std::fmt::Display for ImageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImageError::Io(msg) => write!(f, "Could not read image: {}", msg),
+            ImageError::TooLarge(size, limit) => {
+                write!(f, "Image is too large ({} MB, limit {} MB)", size / 1_048_576, limit / 1_048_576)
+            }
+            ImageError::Unsupported(path) => write!(f, "Not a supported image (JPEG, PNG, GIF, WebP, BMP, TIFF): {}", path),
+            ImageError::Corrupt(path) => write!(f, "Could not read the image size: {}", path),
+            ImageError::NoVisionModel => {
+                write!(f, "No local vision model; install one with `ollama pull llava` or set ai.visionModel")
+            }
+            ImageError::Caption(msg) => write!(f, "Captioning failed: {}", msg),
+            ImageError::Fetch(msg) => write!(f, "Could not load the page: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ImageError {}
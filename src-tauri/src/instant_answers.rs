@@ -0,0 +1,1058 @@
+// Instant answers - Calculator, unit and currency conversion, date arithmetic, and time zones for the omnibox
+// Answered in-process with no model or network call; currency uses whatever rates fx.rs has cached
+
+use chrono::{DateTime, Datelike, Days, FixedOffset, Months, NaiveDate, NaiveTime, Offset, TimeZone};
+use chrono_tz::Tz;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+use crate::fx::FxRates;
+
+const MAX_QUERY_CHARS: usize = 200;
+// Significant digits shown for calculator results and for conversions
+const MATH_DIGITS: i32 = 12;
+const CONVERSION_DIGITS: i32 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum AnswerKind {
+    Math,
+    Unit,
+    Currency,
+    Date,
+    Time,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct InstantAnswer {
+    pub kind: AnswerKind,
+    pub expression: String,            // How the query was read, e.g. "5 km in mi"
+    pub result: String,                // Display text, e.g. "3.1068560 mi"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<f64>,            // The plain number, when the answer is one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,          // Extra context, e.g. which day's exchange rates were used
+}
+
+impl InstantAnswer {
+    fn new(kind: AnswerKind, expression: String, result: String) -> Self {
+        Self { kind, expression, result, value: None, note: None }
+    }
+
+    fn with_value(mut self, value: f64) -> Self {
+        self.value = Some(value);
+        self
+    }
+
+    fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.note = Some(note.into());
+        self
+    }
+}
+
+// Answer against the local clock and the cached rates; None when the query is not one we answer
+pub fn answer(query: &str) -> Option<InstantAnswer> {
+    answer_at(query, chrono::Local::now().fixed_offset(), crate::fx::cached().as_ref())
+}
+
+// The same query, clock, and rates always give the same answer
+pub fn answer_at(query: &str, now: DateTime<FixedOffset>, rates: Option<&FxRates>) -> Option<InstantAnswer> {
+    let query = normalize(query)?;
+    time_zone(&query, now)
+        .or_else(|| date(&query, now.date_naive()))
+        .or_else(|| currency(&query, rates))
+        .or_else(|| unit(&query))
+        .or_else(|| math(&query))
+}
+
+// Trimmed, with question phrasing around the actual expression removed
+fn normalize(query: &str) -> Option<String> {
+    static PREFIX: OnceLock<Regex> = OnceLock::new();
+    let prefix = PREFIX.get_or_init(|| Regex::new(r"(?i)^(?:what(?:'s|\s+is)\s+|calculate\s+|calc\s+|convert\s+|=\s*)").unwrap());
+    let query = query.trim();
+    if query.is_empty() || query.chars().count() > MAX_QUERY_CHARS {
+        return None;
+    }
+    let query = prefix.replace(query, "");
+    let query = query.trim().trim_end_matches(['?', '=']).trim();
+    let query = query.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!query.is_empty()).then_some(query)
+}
+
+// ============================================================================
+// NUMBERS
+// ============================================================================
+
+// Rounded to `digits` significant digits, thousands grouped; very large or small values in e-notation
+pub fn format_number(value: f64, digits: i32) -> String {
+    if value == 0.0 || !value.is_finite() {
+        return if value.is_finite() { "0".to_string() } else { value.to_string() };
+    }
+    let magnitude = value.abs().log10().floor() as i32;
+    if !(-6..15).contains(&magnitude) {
+        let text = format!("{:.*e}", (digits - 1).clamp(0, 16) as usize, value);
+        let (mantissa, exponent) = text.split_once('e').unwrap_or((&text, "0"));
+        return format!("{}e{}", trim_fraction(mantissa), exponent);
+    }
+    let decimals = (digits - 1 - magnitude).clamp(0, 15) as usize;
+    let text = trim_fraction(&format!("{:.*}", decimals, value));
+    if text == "-0" {
+        return "0".to_string();
+    }
+    group_thousands(&text)
+}
+
+fn trim_fraction(text: &str) -> String {
+    if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+fn group_thousands(text: &str) -> String {
+    let (sign, rest) = text.strip_prefix('-').map(|r| ("-", r)).unwrap_or(("", text));
+    let (int, fraction) = rest.split_once('.').map(|(i, f)| (i, Some(f))).unwrap_or((rest, None));
+    let mut grouped = String::new();
+    for (i, c) in int.chars().enumerate() {
+        if i > 0 && (int.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    match fraction {
+        Some(f) => format!("{}{}.{}", sign, grouped, f),
+        None => format!("{}{}", sign, grouped),
+    }
+}
+
+// "1,234.5" and "2.5e3"; thousands commas are only accepted in groups of three
+fn parse_amount(text: &str) -> Option<f64> {
+    static AMOUNT: OnceLock<Regex> = OnceLock::new();
+    let re = AMOUNT.get_or_init(|| Regex::new(r"^[-+]?(?:\d{1,3}(?:,\d{3})+|\d+)?(?:\.\d+)?(?:[eE][-+]?\d+)?$").unwrap());
+    let text = text.trim();
+    if !re.is_match(text) || !text.chars().any(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    text.replace(',', "").parse::<f64>().ok().filter(|v| v.is_finite())
+}
+
+// ============================================================================
+// CALCULATOR
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Num(f64),
+    Op(char),                          // + - * / ^ % ! ( ) ,
+    Ident(String),
+}
+
+const CONSTANTS: &[(&str, f64)] = &[
+    ("pi", std::f64::consts::PI),
+    ("tau", std::f64::consts::TAU),
+    ("e", std::f64::consts::E),
+    ("phi", 1.618_033_988_749_895),
+    ("deg", std::f64::consts::PI / 180.0),
+];
+
+const FUNCTIONS: &[&str] = &[
+    "sqrt", "cbrt", "abs", "ln", "log", "log2", "log10", "exp", "sin", "cos", "tan", "asin", "acos", "atan",
+    "sinh", "cosh", "tanh", "floor", "ceil", "round", "min", "max",
+];
+
+fn math(query: &str) -> Option<InstantAnswer> {
+    static NOT_MATH: OnceLock<Regex> = OnceLock::new();
+    // Dates, phone numbers, and version strings parse as arithmetic but aren't meant as it
+    let not_math = NOT_MATH.get_or_init(|| Regex::new(r"^\+?\d+(?:[-/.]\d+){2,}$").unwrap());
+    if not_math.is_match(query) {
+        return None;
+    }
+    let tokens = tokenize(query)?;
+    let has_operation = tokens.iter().any(|t| match t {
+        Token::Op(c) => !matches!(c, '(' | ')'),
+        Token::Ident(name) => name != "e" && name != "deg",
+        Token::Num(_) => false,
+    });
+    if !has_operation {
+        return None;
+    }
+    let mut parser = Parser { tokens, pos: 0 };
+    let value = parser.expression()?;
+    if parser.pos != parser.tokens.len() || !value.is_finite() {
+        return None;
+    }
+    Some(InstantAnswer::new(AnswerKind::Math, query.to_string(), format_number(value, MATH_DIGITS)).with_value(value))
+}
+
+fn tokenize(input: &str) -> Option<Vec<Token>> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit())) {
+            let start = i;
+            while i < chars.len() {
+                let c = chars[i];
+                let next_digit = chars.get(i + 1).is_some_and(|n| n.is_ascii_digit());
+                let thousands = c == ',' && chars[i + 1..].iter().take_while(|n| n.is_ascii_digit()).count() == 3;
+                let exponent = matches!(c, 'e' | 'E')
+                    && (next_digit || (matches!(chars.get(i + 1), Some('+' | '-')) && chars.get(i + 2).is_some_and(|n| n.is_ascii_digit())));
+                if c.is_ascii_digit() || c == '.' || thousands {
+                    i += 1;
+                } else if exponent {
+                    i += 2;
+                } else {
+                    break;
+                }
+            }
+            let text: String = chars[start..i].iter().filter(|c| **c != ',').collect();
+            tokens.push(Token::Num(text.parse().ok()?));
+        } else if c.is_alphabetic() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect::<String>().to_lowercase();
+            match word.as_str() {
+                "x" | "times" => tokens.push(Token::Op('*')),
+                "plus" => tokens.push(Token::Op('+')),
+                "minus" => tokens.push(Token::Op('-')),
+                "mod" | "of" => tokens.push(Token::Ident(word)),
+                "π" => tokens.push(Token::Ident("pi".to_string())),
+                _ if FUNCTIONS.contains(&word.as_str()) || CONSTANTS.iter().any(|(n, _)| *n == word) => {
+                    tokens.push(Token::Ident(word))
+                }
+                _ => return None,
+            }
+        } else {
+            let op = match c {
+                '*' if chars.get(i + 1) == Some(&'*') => {
+                    i += 1;
+                    '^'
+                }
+                '×' | '·' | '*' => '*',
+                '÷' | '/' => '/',
+                '−' | '-' => '-',
+                '+' | '^' | '%' | '!' | '(' | ')' | ',' => c,
+                _ => return None,
+            };
+            tokens.push(Token::Op(op));
+            i += 1;
+        }
+    }
+    Some(tokens)
+}
+
+// Precedence climbing: expression > term > unary > power > postfix > primary
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn eat(&mut self, op: char) -> bool {
+        if self.peek() == Some(&Token::Op(op)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expression(&mut self) -> Option<f64> {
+        let mut value = self.term()?;
+        loop {
+            if self.eat('+') {
+                value += self.term()?;
+            } else if self.eat('-') {
+                value -= self.term()?;
+            } else {
+                return Some(value);
+            }
+        }
+    }
+
+    fn term(&mut self) -> Option<f64> {
+        let mut value = self.unary()?;
+        loop {
+            match self.peek() {
+                Some(Token::Op('*')) => {
+                    self.pos += 1;
+                    value *= self.unary()?;
+                }
+                Some(Token::Op('/')) => {
+                    self.pos += 1;
+                    let divisor = self.unary()?;
+                    if divisor == 0.0 {
+                        return None;
+                    }
+                    value /= divisor;
+                }
+                Some(Token::Op('%')) => {
+                    self.pos += 1;
+                    value = value.rem_euclid(self.unary()?);
+                }
+                Some(Token::Ident(word)) if word == "mod" => {
+                    self.pos += 1;
+                    value = value.rem_euclid(self.unary()?);
+                }
+                Some(Token::Ident(word)) if word == "of" => {
+                    self.pos += 1;
+                    value *= self.unary()?;
+                }
+                // Implicit multiplication: "2pi", "3(4 + 1)", "90deg"
+                Some(Token::Op('(')) | Some(Token::Ident(_)) => value *= self.power()?,
+                _ => return Some(value),
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Option<f64> {
+        if self.eat('-') {
+            Some(-self.unary()?)
+        } else if self.eat('+') {
+            self.unary()
+        } else {
+            self.power()
+        }
+    }
+
+    // Right-associative, and binds tighter than unary minus: -2^2 = -4
+    fn power(&mut self) -> Option<f64> {
+        let base = self.postfix()?;
+        if self.eat('^') {
+            let exponent = self.unary()?;
+            return Some(base.powf(exponent));
+        }
+        Some(base)
+    }
+
+    fn postfix(&mut self) -> Option<f64> {
+        let mut value = self.primary()?;
+        loop {
+            if self.eat('!') {
+                value = factorial(value)?;
+            } else if self.peek() == Some(&Token::Op('%')) && self.percent_follows() {
+                // "15%" is a percentage unless another operand follows ("10 % 3" is modulo)
+                self.pos += 1;
+                value /= 100.0;
+            } else {
+                return Some(value);
+            }
+        }
+    }
+
+    fn percent_follows(&self) -> bool {
+        match self.tokens.get(self.pos + 1) {
+            None => true,
+            Some(Token::Op(c)) => *c != '(',
+            Some(Token::Ident(word)) => word == "of",
+            Some(Token::Num(_)) => false,
+        }
+    }
+
+    fn primary(&mut self) -> Option<f64> {
+        let token = self.tokens.get(self.pos)?.clone();
+        self.pos += 1;
+        match token {
+            Token::Num(value) => Some(value),
+            Token::Op('(') => {
+                let value = self.expression()?;
+                // A missing closing parenthesis at the very end is forgiven
+                if !self.eat(')') && self.pos != self.tokens.len() {
+                    return None;
+                }
+                Some(value)
+            }
+            Token::Ident(name) => {
+                if let Some((_, value)) = CONSTANTS.iter().find(|(n, _)| *n == name) {
+                    return Some(*value);
+                }
+                let args = if self.eat('(') {
+                    let mut args = vec![self.expression()?];
+                    while self.eat(',') {
+                        args.push(self.expression()?);
+                    }
+                    if !self.eat(')') && self.pos != self.tokens.len() {
+                        return None;
+                    }
+                    args
+                } else {
+                    // "sqrt 16", "sin pi"
+                    vec![self.power()?]
+                };
+                apply(&name, &args)
+            }
+            Token::Op(_) => None,
+        }
+    }
+}
+
+fn apply(name: &str, args: &[f64]) -> Option<f64> {
+    let one = |f: fn(f64) -> f64| (args.len() == 1).then(|| f(args[0]));
+    let value = match name {
+        "sqrt" if args.len() == 1 && args[0] < 0.0 => return None,
+        "sqrt" => one(f64::sqrt)?,
+        "cbrt" => one(f64::cbrt)?,
+        "abs" => one(f64::abs)?,
+        "ln" => one(f64::ln)?,
+        "log" if args.len() == 2 => args[0].log(args[1]),
+        "log" | "log10" => one(f64::log10)?,
+        "log2" => one(f64::log2)?,
+        "exp" => one(f64::exp)?,
+        "sin" => one(f64::sin)?,
+        "cos" => one(f64::cos)?,
+        "tan" => one(f64::tan)?,
+        "asin" => one(f64::asin)?,
+        "acos" => one(f64::acos)?,
+        "atan" => one(f64::atan)?,
+        "sinh" => one(f64::sinh)?,
+        "cosh" => one(f64::cosh)?,
+        "tanh" => one(f64::tanh)?,
+        "floor" => one(f64::floor)?,
+        "ceil" => one(f64::ceil)?,
+        "round" => one(f64::round)?,
+        "min" if !args.is_empty() => args.iter().copied().fold(f64::INFINITY, f64::min),
+        "max" if !args.is_empty() => args.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        _ => return None,
+    };
+    // Floating-point noise like sin(pi) = 1.2e-16 reads better as 0
+    Some(if value.abs() < 1e-15 { 0.0 } else { value }).filter(|v| v.is_finite())
+}
+
+fn factorial(n: f64) -> Option<f64> {
+    if n < 0.0 || n.fract() != 0.0 || n > 170.0 {
+        return None;
+    }
+    Some((1..=n as u64).fold(1.0, |acc, k| acc * k as f64))
+}
+
+// ============================================================================
+// UNITS
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dimension {
+    Length,
+    Mass,
+    Volume,
+    Area,
+    Speed,
+    Time,
+    Data,
+    Energy,
+    Pressure,
+    Temperature,
+}
+
+struct Unit {
+    symbol: &'static str,              // Matched case-sensitively ("Mb" is megabits, "MB" megabytes)
+    names: &'static [&'static str],    // Matched case-insensitively
+    dimension: Dimension,
+    factor: f64,                       // Size in the dimension's base unit (temperatures use `to_kelvin`)
+}
+
+const fn u(symbol: &'static str, names: &'static [&'static str], dimension: Dimension, factor: f64) -> Unit {
+    Unit { symbol, names, dimension, factor }
+}
+
+use Dimension::*;
+
+// Base units: m, kg, L, m², m/s, s, byte, J, Pa, K
+const UNITS: &[Unit] = &[
+    u("mm", &["millimeter", "millimeters", "millimetre", "millimetres"], Length, 0.001),
+    u("cm", &["centimeter", "centimeters", "centimetre", "centimetres"], Length, 0.01),
+    u("m", &["meter", "meters", "metre", "metres"], Length, 1.0),
+    u("km", &["kilometer", "kilometers", "kilometre", "kilometres", "kms"], Length, 1000.0),
+    u("in", &["inch", "inches", "\""], Length, 0.0254),
+    u("ft", &["foot", "feet", "'"], Length, 0.3048),
+    u("yd", &["yard", "yards", "yds"], Length, 0.9144),
+    u("mi", &["mile", "miles"], Length, 1609.344),
+    u("nmi", &["nautical mile", "nautical miles"], Length, 1852.0),
+    u("mg", &["milligram", "milligrams"], Mass, 1e-6),
+    u("g", &["gram", "grams", "gramme", "grammes", "gr"], Mass, 0.001),
+    u("kg", &["kilogram", "kilograms", "kilo", "kilos", "kgs"], Mass, 1.0),
+    u("t", &["tonne", "tonnes", "metric ton", "metric tons"], Mass, 1000.0),
+    u("oz", &["ounce", "ounces"], Mass, 0.028_349_523_125),
+    u("lb", &["pound", "pounds", "lbs"], Mass, 0.453_592_37),
+    u("st", &["stone", "stones"], Mass, 6.350_293_18),
+    u("mL", &["ml", "milliliter", "milliliters", "millilitre", "millilitres"], Volume, 0.001),
+    u("cL", &["cl", "centiliter", "centiliters", "centilitre", "centilitres"], Volume, 0.01),
+    u("dL", &["dl", "deciliter", "deciliters", "decilitre", "decilitres"], Volume, 0.1),
+    u("L", &["l", "liter", "liters", "litre", "litres"], Volume, 1.0),
+    u("m³", &["m3", "cubic meter", "cubic meters", "cubic metre", "cubic metres"], Volume, 1000.0),
+    u("tsp", &["teaspoon", "teaspoons"], Volume, 0.004_928_921_593_75),
+    u("tbsp", &["tablespoon", "tablespoons"], Volume, 0.014_786_764_781_25),
+    u("fl oz", &["floz", "fluid ounce", "fluid ounces"], Volume, 0.029_573_529_562_5),
+    u("cup", &["cups"], Volume, 0.236_588_236_5),
+    u("pt", &["pint", "pints"], Volume, 0.473_176_473),
+    u("qt", &["quart", "quarts"], Volume, 0.946_352_946),
+    u("gal", &["gallon", "gallons", "us gallon", "us gallons"], Volume, 3.785_411_784),
+    u("imp gal", &["imperial gallon", "imperial gallons", "uk gallon", "uk gallons"], Volume, 4.546_09),
+    u("cm²", &["cm2", "sq cm", "square centimeter", "square centimeters", "square centimetre", "square centimetres"], Area, 1e-4),
+    u("m²", &["m2", "sq m", "sqm", "square meter", "square meters", "square metre", "square metres"], Area, 1.0),
+    u("km²", &["km2", "sq km", "square kilometer", "square kilometers", "square kilometre", "square kilometres"], Area, 1e6),
+    u("ha", &["hectare", "hectares"], Area, 1e4),
+    u("acre", &["acres", "ac"], Area, 4_046.856_422_4),
+    u("ft²", &["ft2", "sq ft", "sqft", "square foot", "square feet"], Area, 0.092_903_04),
+    u("mi²", &["mi2", "sq mi", "square mile", "square miles"], Area, 2_589_988.110_336),
+    u("m/s", &["mps", "meters per second", "metres per second"], Speed, 1.0),
+    u("km/h", &["kph", "kmh", "kmph", "km/hr", "kilometers per hour", "kilometres per hour"], Speed, 1.0 / 3.6),
+    u("mph", &["mi/h", "miles per hour"], Speed, 0.447_04),
+    u("kn", &["knot", "knots", "kt"], Speed, 1852.0 / 3600.0),
+    u("ft/s", &["feet per second"], Speed, 0.3048),
+    u("ms", &["millisecond", "milliseconds"], Time, 0.001),
+    u("s", &["sec", "secs", "second", "seconds"], Time, 1.0),
+    u("min", &["mins", "minute", "minutes"], Time, 60.0),
+    u("h", &["hr", "hrs", "hour", "hours"], Time, 3600.0),
+    u("d", &["day", "days"], Time, 86_400.0),
+    u("wk", &["week", "weeks", "wks"], Time, 604_800.0),
+    u("month", &["months"], Time, 2_629_800.0),       // 1/12 of a Julian year
+    u("yr", &["year", "years", "yrs"], Time, 31_557_600.0), // Julian year, 365.25 days
+    u("bit", &["bits"], Data, 0.125),
+    u("B", &["byte", "bytes"], Data, 1.0),
+    u("KB", &["kb", "kilobyte", "kilobytes"], Data, 1e3),
+    u("MB", &["mb", "megabyte", "megabytes"], Data, 1e6),
+    u("GB", &["gb", "gigabyte", "gigabytes"], Data, 1e9),
+    u("TB", &["tb", "terabyte", "terabytes"], Data, 1e12),
+    u("PB", &["pb", "petabyte", "petabytes"], Data, 1e15),
+    u("KiB", &["kib", "kibibyte", "kibibytes"], Data, 1024.0),
+    u("MiB", &["mib", "mebibyte", "mebibytes"], Data, 1_048_576.0),
+    u("GiB", &["gib", "gibibyte", "gibibytes"], Data, 1_073_741_824.0),
+    u("TiB", &["tib", "tebibyte", "tebibytes"], Data, 1_099_511_627_776.0),
+    u("kb", &["kbit", "kilobit", "kilobits"], Data, 125.0),
+    u("Mb", &["mbit", "megabit", "megabits"], Data, 125_000.0),
+    u("Gb", &["gbit", "gigabit", "gigabits"], Data, 1.25e8),
+    u("J", &["j", "joule", "joules"], Energy, 1.0),
+    u("kJ", &["kj", "kilojoule", "kilojoules"], Energy, 1000.0),
+    u("cal", &["calorie", "calories"], Energy, 4.184),
+    u("kcal", &["kilocalorie", "kilocalories", "Cal"], Energy, 4184.0),
+    u("Wh", &["wh", "watt hour", "watt hours"], Energy, 3600.0),
+    u("kWh", &["kwh", "kilowatt hour", "kilowatt hours"], Energy, 3.6e6),
+    u("BTU", &["btu", "btus"], Energy, 1_055.055_852_62),
+    u("eV", &["ev", "electronvolt", "electronvolts"], Energy, 1.602_176_634e-19),
+    u("Pa", &["pa", "pascal", "pascals"], Pressure, 1.0),
+    u("kPa", &["kpa", "kilopascal", "kilopascals"], Pressure, 1000.0),
+    u("bar", &["bars"], Pressure, 1e5),
+    u("atm", &["atmosphere", "atmospheres"], Pressure, 101_325.0),
+    u("psi", &["pounds per square inch"], Pressure, 6_894.757_293_168),
+    u("mmHg", &["mmhg", "torr"], Pressure, 133.322_387_415),
+    u("°C", &["c", "celsius", "centigrade"], Temperature, 1.0),
+    u("°F", &["f", "fahrenheit"], Temperature, 1.0),
+    u("K", &["k", "kelvin", "kelvins"], Temperature, 1.0),
+];
+
+fn find_unit(raw: &str) -> Option<&'static Unit> {
+    let raw = raw.trim().trim_end_matches('.');
+    let raw = raw.strip_prefix("a ").or_else(|| raw.strip_prefix("an ")).unwrap_or(raw);
+    let lower = raw.to_lowercase();
+    let lower = ["degrees ", "degree ", "deg ", "°"]
+        .iter()
+        .find_map(|p| lower.strip_prefix(p))
+        .map(str::to_string)
+        .unwrap_or(lower);
+    UNITS
+        .iter()
+        .find(|unit| unit.symbol == raw)
+        .or_else(|| UNITS.iter().find(|unit| unit.names.iter().any(|n| n.to_lowercase() == lower)))
+        .or_else(|| UNITS.iter().find(|unit| unit.symbol.to_lowercase() == lower))
+}
+
+fn to_base(unit: &Unit, value: f64) -> f64 {
+    match unit.symbol {
+        "°C" => value + 273.15,
+        "°F" => (value - 32.0) * 5.0 / 9.0 + 273.15,
+        _ => value * unit.factor,
+    }
+}
+
+fn from_base(unit: &Unit, value: f64) -> f64 {
+    match unit.symbol {
+        "°C" => value - 273.15,
+        "°F" => (value - 273.15) * 9.0 / 5.0 + 32.0,
+        _ => value / unit.factor,
+    }
+}
+
+// "<amount> <from> to <to>" or "how many <to> in <amount?> <from>"; both sides still raw text
+fn conversion_parts(query: &str) -> Option<(String, String, String)> {
+    static CONVERT: OnceLock<Regex> = OnceLock::new();
+    static HOW_MANY: OnceLock<Regex> = OnceLock::new();
+    let convert = CONVERT.get_or_init(|| {
+        Regex::new(r"(?i)^(?P<amount>[-+]?[\d.,]*\d(?:e[-+]?\d+)?)\s*(?P<from>\S.*?)\s+(?:to|in|into|as|=|->|→)\s+(?P<to>\S.*)$").unwrap()
+    });
+    let how_many = HOW_MANY.get_or_init(|| {
+        Regex::new(r"(?i)^how (?:many|much) (?P<to>\S.*?) (?:are )?in (?:an? |one )?(?P<amount>[-+]?[\d.,]*\d(?:e[-+]?\d+)?)?\s*(?P<from>\S.*)$").unwrap()
+    });
+    let caps = convert.captures(query).or_else(|| how_many.captures(query))?;
+    let amount = caps.name("amount").map(|m| m.as_str().to_string()).unwrap_or_else(|| "1".to_string());
+    Some((amount, caps["from"].to_string(), caps["to"].to_string()))
+}
+
+fn unit(query: &str) -> Option<InstantAnswer> {
+    let (amount, from, to) = conversion_parts(query)?;
+    let amount = parse_amount(&amount)?;
+    let (from, to) = (find_unit(&from)?, find_unit(&to)?);
+    if from.dimension != to.dimension {
+        return None;
+    }
+    let value = from_base(to, to_base(from, amount));
+    if !value.is_finite() {
+        return None;
+    }
+    Some(
+        InstantAnswer::new(
+            AnswerKind::Unit,
+            format!("{} {} in {}", format_number(amount, CONVERSION_DIGITS), from.symbol, to.symbol),
+            format!("{} {}", format_number(value, CONVERSION_DIGITS), to.symbol),
+        )
+        .with_value(value),
+    )
+}
+
+// ============================================================================
+// CURRENCY
+// ============================================================================
+
+// Symbols and names people type instead of ISO codes; "¥" is read as yen
+const CURRENCY_NAMES: &[(&str, &[&str])] = &[
+    ("USD", &["$", "us$", "dollar", "dollars", "us dollar", "us dollars", "bucks"]),
+    ("EUR", &["€", "euro", "euros"]),
+    ("GBP", &["£", "pound sterling", "pounds sterling", "quid", "british pound", "british pounds"]),
+    ("JPY", &["¥", "yen", "japanese yen"]),
+    ("INR", &["₹", "rs", "rupee", "rupees", "indian rupee", "indian rupees"]),
+    ("CNY", &["yuan", "rmb", "renminbi"]),
+    ("CHF", &["swiss franc", "swiss francs"]),
+    ("CAD", &["c$", "canadian dollar", "canadian dollars"]),
+    ("AUD", &["a$", "australian dollar", "australian dollars"]),
+    ("KRW", &["₩", "won", "korean won"]),
+    ("BRL", &["r$", "real", "reais", "brazilian real"]),
+    ("MXN", &["mexican peso", "mexican pesos"]),
+];
+
+fn currency_code(raw: &str, rates: &FxRates) -> Option<String> {
+    let lower = raw.trim().to_lowercase();
+    let code = CURRENCY_NAMES
+        .iter()
+        .find(|(code, names)| code.eq_ignore_ascii_case(&lower) || names.contains(&lower.as_str()))
+        .map(|(code, _)| code.to_string())
+        .or_else(|| (lower.len() == 3 && lower.chars().all(|c| c.is_ascii_alphabetic())).then(|| lower.to_uppercase()))?;
+    rates.per_eur(&code).map(|_| code)
+}
+
+fn currency(query: &str, rates: Option<&FxRates>) -> Option<InstantAnswer> {
+    let rates = rates?;
+    // "$100 to eur": move a leading symbol behind the amount
+    let query = match query.chars().next() {
+        Some(c) if "$€£¥₹₩".contains(c) => {
+            let rest = query[c.len_utf8()..].trim_start();
+            let end = rest.find(|ch: char| !(ch.is_ascii_digit() || ch == '.' || ch == ',')).unwrap_or(rest.len());
+            format!("{} {}{}", &rest[..end], c, &rest[end..])
+        }
+        _ => query.to_string(),
+    };
+    let (amount, from, to) = conversion_parts(&query)?;
+    let amount = parse_amount(&amount)?;
+    let (from, to) = (currency_code(&from, rates)?, currency_code(&to, rates)?);
+    let value = amount / rates.per_eur(&from)? * rates.per_eur(&to)?;
+    let shown = if value.abs() >= 0.01 || value == 0.0 {
+        group_thousands(&format!("{:.2}", value))
+    } else {
+        format_number(value, 4)
+    };
+    Some(
+        InstantAnswer::new(
+            AnswerKind::Currency,
+            format!("{} {} in {}", group_thousands(&trim_fraction(&format!("{:.2}", amount))), from, to),
+            format!("{} {}", shown, to),
+        )
+        .with_value(value)
+        .with_note(format!("ECB reference rates from {}", rates.as_of)),
+    )
+}
+
+// ============================================================================
+// DATES
+// ============================================================================
+
+#[derive(Debug, Clone, Copy)]
+enum Span {
+    Days(u64),
+    Months(u32),
+}
+
+fn span(count: &str, unit: &str) -> Option<Span> {
+    let n: u32 = count.parse().ok()?;
+    Some(match unit.trim_end_matches('s') {
+        "day" | "d" => Span::Days(n.into()),
+        "week" | "wk" | "w" => Span::Days(u64::from(n) * 7),
+        "month" | "mo" => Span::Months(n),
+        "year" | "yr" | "y" => Span::Months(n.checked_mul(12)?),
+        _ => return None,
+    })
+}
+
+fn shift(date: NaiveDate, span: Span, forward: bool) -> Option<NaiveDate> {
+    match (span, forward) {
+        (Span::Days(n), true) => date.checked_add_days(Days::new(n)),
+        (Span::Days(n), false) => date.checked_sub_days(Days::new(n)),
+        (Span::Months(n), true) => date.checked_add_months(Months::new(n)),
+        (Span::Months(n), false) => date.checked_sub_months(Months::new(n)),
+    }
+}
+
+// "today", "2026-12-25", "Dec 25 2026", "25 December", "christmas"; dates without a year mean the next one
+fn parse_day(text: &str, today: NaiveDate) -> Option<NaiveDate> {
+    static ORDINAL: OnceLock<Regex> = OnceLock::new();
+    let ordinal = ORDINAL.get_or_init(|| Regex::new(r"(\d)(?:st|nd|rd|th)\b").unwrap());
+    let text = text.trim().trim_start_matches("the ").to_lowercase();
+    let text = ordinal.replace_all(&text, "$1").replace(',', " ");
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match text.as_str() {
+        "today" | "now" => return Some(today),
+        "tomorrow" => return today.succ_opt(),
+        "yesterday" => return today.pred_opt(),
+        "christmas" | "xmas" => return next_occurrence(today, 12, 25),
+        "new year" | "new years" | "new year's" | "new year's day" => return next_occurrence(today, 1, 1),
+        _ => {}
+    }
+    for format in ["%Y-%m-%d", "%B %d %Y", "%b %d %Y", "%d %B %Y", "%d %b %Y"] {
+        if let Ok(date) = NaiveDate::parse_from_str(&text, format) {
+            return Some(date);
+        }
+    }
+    // No year: borrow one just to read month and day
+    for format in ["%B %d %Y", "%b %d %Y", "%d %B %Y", "%d %b %Y"] {
+        if let Ok(date) = NaiveDate::parse_from_str(&format!("{} 2000", text), format) {
+            return next_occurrence(today, date.month(), date.day());
+        }
+    }
+    None
+}
+
+fn next_occurrence(today: NaiveDate, month: u32, day: u32) -> Option<NaiveDate> {
+    let this_year = NaiveDate::from_ymd_opt(today.year(), month, day);
+    match this_year {
+        Some(date) if date >= today => Some(date),
+        _ => (1..=4).find_map(|n| NaiveDate::from_ymd_opt(today.year() + n, month, day)),
+    }
+}
+
+fn long_date(date: NaiveDate) -> String {
+    date.format("%A, %B %-d, %Y").to_string()
+}
+
+fn day_count(days: i64) -> String {
+    let plural = |n: i64, word: &str| format!("{} {}{}", group_thousands(&n.to_string()), word, if n.abs() == 1 { "" } else { "s" });
+    let (weeks, rest) = (days.abs() / 7, days.abs() % 7);
+    if weeks == 0 {
+        plural(days, "day")
+    } else if rest == 0 {
+        format!("{} ({})", plural(days, "day"), plural(weeks, "week"))
+    } else {
+        format!("{} ({}, {})", plural(days, "day"), plural(weeks, "week"), plural(rest, "day"))
+    }
+}
+
+fn date(query: &str, today: NaiveDate) -> Option<InstantAnswer> {
+    static WEEKDAY: OnceLock<Regex> = OnceLock::new();
+    static UNTIL: OnceLock<Regex> = OnceLock::new();
+    static RELATIVE: OnceLock<Regex> = OnceLock::new();
+    static AGO: OnceLock<Regex> = OnceLock::new();
+    static OFFSET: OnceLock<Regex> = OnceLock::new();
+    static BETWEEN: OnceLock<Regex> = OnceLock::new();
+    const UNIT: &str = r"(days?|d|weeks?|wks?|w|months?|mo|years?|yrs?|y)";
+    let weekday = WEEKDAY.get_or_init(|| Regex::new(r"^(?:what )?day (?:of the week )?(?:is|was|will be|is it) (.+)$").unwrap());
+    let until = UNTIL.get_or_init(|| Regex::new(r"^(?:how many )?(days|weeks) (until|till|to|before|since|after) (.+)$").unwrap());
+    let relative = RELATIVE.get_or_init(|| Regex::new(&format!(r"^(\d+) {}\s*(from|after|before) (.+)$", UNIT)).unwrap());
+    let ago = AGO.get_or_init(|| Regex::new(&format!(r"^(\d+) {}\s*ago$", UNIT)).unwrap());
+    let offset = OFFSET.get_or_init(|| Regex::new(&format!(r"^(.+?)\s*([+-])\s*(\d+)\s*{}$", UNIT)).unwrap());
+    let between = BETWEEN.get_or_init(|| Regex::new(r"^(?:(?:how many )?days )?between (.+) and (.+)$|^(.+?) - (.+)$").unwrap());
+    let lower = query.to_lowercase();
+
+    if matches!(lower.as_str(), "today" | "tomorrow" | "yesterday") {
+        return Some(InstantAnswer::new(AnswerKind::Date, lower.clone(), long_date(parse_day(&lower, today)?)));
+    }
+    if let Some(c) = weekday.captures(&lower) {
+        let day = parse_day(&c[1], today)?;
+        return Some(InstantAnswer::new(AnswerKind::Date, format!("Day of the week for {}", day), long_date(day)));
+    }
+    if let Some(c) = until.captures(&lower) {
+        let target = parse_day(&c[3], today)?;
+        let days = match &c[2] {
+            "since" | "after" => (today - target).num_days(),
+            _ => (target - today).num_days(),
+        };
+        let expression = format!("{} {} {}", &c[1], &c[2], long_date(target));
+        if &c[1] == "weeks" {
+            let weeks = days as f64 / 7.0;
+            let result = format!("{} weeks", format_number(weeks, 3));
+            return Some(InstantAnswer::new(AnswerKind::Date, expression, result).with_value(weeks));
+        }
+        return Some(InstantAnswer::new(AnswerKind::Date, expression, day_count(days)).with_value(days as f64));
+    }
+    let shifted = if let Some(c) = relative.captures(&lower) {
+        let from = if c[4].trim() == "now" { today } else { parse_day(&c[4], today)? };
+        Some((shift(from, span(&c[1], &c[2])?, &c[3] != "before")?, format!("{} {} {} {}", &c[1], &c[2], &c[3], from)))
+    } else if let Some(c) = ago.captures(&lower) {
+        Some((shift(today, span(&c[1], &c[2])?, false)?, format!("{} {} ago", &c[1], &c[2])))
+    } else if let Some(c) = offset.captures(&lower) {
+        let from = parse_day(&c[1], today)?;
+        Some((shift(from, span(&c[3], &c[4])?, &c[2] == "+")?, format!("{} {} {} {}", from, &c[2], &c[3], &c[4])))
+    } else {
+        None
+    };
+    if let Some((day, expression)) = shifted {
+        return Some(InstantAnswer::new(AnswerKind::Date, expression, long_date(day)));
+    }
+    if let Some(c) = between.captures(&lower) {
+        let (a, b) = match (c.get(1), c.get(2)) {
+            (Some(a), Some(b)) => (a.as_str(), b.as_str()),
+            _ => (c.get(3)?.as_str(), c.get(4)?.as_str()),
+        };
+        let (a, b) = (parse_day(a, today)?, parse_day(b, today)?);
+        let days = (a - b).num_days();
+        let days = if c.get(1).is_some() { days.abs() } else { days };
+        return Some(
+            InstantAnswer::new(AnswerKind::Date, format!("{} to {}", b.min(a), b.max(a)), day_count(days)).with_value(days as f64),
+        );
+    }
+    None
+}
+
+// ============================================================================
+// TIME ZONES
+// ============================================================================
+
+// Abbreviations name the region, so "est" in July is answered in EDT
+const ZONE_ALIASES: &[(&str, Tz)] = &[
+    ("utc", Tz::UTC),
+    ("gmt", Tz::GMT),
+    ("z", Tz::UTC),
+    ("est", Tz::America__New_York),
+    ("edt", Tz::America__New_York),
+    ("et", Tz::America__New_York),
+    ("eastern", Tz::America__New_York),
+    ("cst", Tz::America__Chicago),
+    ("cdt", Tz::America__Chicago),
+    ("ct", Tz::America__Chicago),
+    ("central", Tz::America__Chicago),
+    ("mst", Tz::America__Denver),
+    ("mdt", Tz::America__Denver),
+    ("mt", Tz::America__Denver),
+    ("mountain", Tz::America__Denver),
+    ("pst", Tz::America__Los_Angeles),
+    ("pdt", Tz::America__Los_Angeles),
+    ("pt", Tz::America__Los_Angeles),
+    ("pacific", Tz::America__Los_Angeles),
+    ("akst", Tz::America__Anchorage),
+    ("hst", Tz::Pacific__Honolulu),
+    ("bst", Tz::Europe__London),
+    ("wet", Tz::Europe__Lisbon),
+    ("cet", Tz::Europe__Paris),
+    ("cest", Tz::Europe__Paris),
+    ("eet", Tz::Europe__Athens),
+    ("eest", Tz::Europe__Athens),
+    ("msk", Tz::Europe__Moscow),
+    ("gst", Tz::Asia__Dubai),
+    ("pkt", Tz::Asia__Karachi),
+    ("ist", Tz::Asia__Kolkata),
+    ("sgt", Tz::Asia__Singapore),
+    ("hkt", Tz::Asia__Hong_Kong),
+    ("jst", Tz::Asia__Tokyo),
+    ("kst", Tz::Asia__Seoul),
+    ("awst", Tz::Australia__Perth),
+    ("aest", Tz::Australia__Sydney),
+    ("aedt", Tz::Australia__Sydney),
+    ("nzst", Tz::Pacific__Auckland),
+    ("nzdt", Tz::Pacific__Auckland),
+    ("brt", Tz::America__Sao_Paulo),
+    // Places that aren't zone names themselves
+    ("nyc", Tz::America__New_York),
+    ("boston", Tz::America__New_York),
+    ("washington", Tz::America__New_York),
+    ("washington dc", Tz::America__New_York),
+    ("miami", Tz::America__New_York),
+    ("atlanta", Tz::America__New_York),
+    ("dallas", Tz::America__Chicago),
+    ("houston", Tz::America__Chicago),
+    ("austin", Tz::America__Chicago),
+    ("san francisco", Tz::America__Los_Angeles),
+    ("sf", Tz::America__Los_Angeles),
+    ("seattle", Tz::America__Los_Angeles),
+    ("la", Tz::America__Los_Angeles),
+    ("uk", Tz::Europe__London),
+    ("england", Tz::Europe__London),
+    ("france", Tz::Europe__Paris),
+    ("germany", Tz::Europe__Berlin),
+    ("spain", Tz::Europe__Madrid),
+    ("italy", Tz::Europe__Rome),
+    ("netherlands", Tz::Europe__Amsterdam),
+    ("russia", Tz::Europe__Moscow),
+    ("uae", Tz::Asia__Dubai),
+    ("india", Tz::Asia__Kolkata),
+    ("mumbai", Tz::Asia__Kolkata),
+    ("delhi", Tz::Asia__Kolkata),
+    ("new delhi", Tz::Asia__Kolkata),
+    ("bangalore", Tz::Asia__Kolkata),
+    ("bengaluru", Tz::Asia__Kolkata),
+    ("chennai", Tz::Asia__Kolkata),
+    ("hyderabad", Tz::Asia__Kolkata),
+    ("china", Tz::Asia__Shanghai),
+    ("beijing", Tz::Asia__Shanghai),
+    ("japan", Tz::Asia__Tokyo),
+    ("korea", Tz::Asia__Seoul),
+    ("south korea", Tz::Asia__Seoul),
+    ("australia", Tz::Australia__Sydney),
+    ("new zealand", Tz::Pacific__Auckland),
+    ("nz", Tz::Pacific__Auckland),
+    ("brazil", Tz::America__Sao_Paulo),
+    ("são paulo", Tz::America__Sao_Paulo),
+    ("rio", Tz::America__Sao_Paulo),
+];
+
+// Alias, IANA name ("Asia/Tokyo"), or the city part of one ("tokyo", "new york")
+fn find_zone(raw: &str) -> Option<Tz> {
+    let name = raw.trim().trim_start_matches("the ").to_lowercase();
+    if name.is_empty() {
+        return None;
+    }
+    if let Some((_, tz)) = ZONE_ALIASES.iter().find(|(alias, _)| *alias == name) {
+        return Some(*tz);
+    }
+    let city = name.replace(' ', "_");
+    chrono_tz::TZ_VARIANTS
+        .iter()
+        .find(|tz| tz.name().eq_ignore_ascii_case(&name))
+        .or_else(|| {
+            chrono_tz::TZ_VARIANTS
+                .iter()
+                .filter(|tz| tz.name().contains('/') && !tz.name().starts_with("Etc/"))
+                .find(|tz| tz.name().rsplit('/').next().is_some_and(|last| last.eq_ignore_ascii_case(&city)))
+        })
+        .copied()
+}
+
+fn parse_clock(text: &str) -> Option<NaiveTime> {
+    static CLOCK: OnceLock<Regex> = OnceLock::new();
+    let clock = CLOCK.get_or_init(|| Regex::new(r"^(\d{1,2})(?::(\d{2}))?\s*(am|pm|a\.m\.|p\.m\.)?$").unwrap());
+    match text {
+        "noon" | "midday" => return NaiveTime::from_hms_opt(12, 0, 0),
+        "midnight" => return NaiveTime::from_hms_opt(0, 0, 0),
+        _ => {}
+    }
+    let c = clock.captures(text)?;
+    let hour: u32 = c[1].parse().ok()?;
+    let minute: u32 = c.get(2).map(|m| m.as_str().parse().ok()).unwrap_or(Some(0))?;
+    let hour = match c.get(3).map(|m| m.as_str().starts_with('p')) {
+        // A bare "5" is too ambiguous to be a time
+        None if c.get(2).is_none() => return None,
+        None => hour,
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some(pm) => hour % 12 + if pm { 12 } else { 0 },
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+fn utc_offset(seconds: i32) -> String {
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let (hours, minutes) = (seconds.abs() / 3600, seconds.abs() % 3600 / 60);
+    match minutes {
+        0 => format!("UTC{}{}", sign, hours),
+        _ => format!("UTC{}{}:{:02}", sign, hours, minutes),
+    }
+}
+
+fn clock_text<T: TimeZone>(time: &DateTime<T>, zone: &str) -> String
+where
+    T::Offset: std::fmt::Display,
+{
+    format!("{} {}", time.format("%-I:%M %p"), zone)
+}
+
+fn zone_label(time: &DateTime<Tz>) -> String {
+    let abbreviation = time.format("%Z").to_string();
+    // Zones without a letter abbreviation print their offset ("+04"); show it as UTC+4 instead
+    if abbreviation.starts_with(['+', '-']) {
+        utc_offset(time.offset().fix().local_minus_utc())
+    } else {
+        abbreviation
+    }
+}
+
+fn time_zone(query: &str, now: DateTime<FixedOffset>) -> Option<InstantAnswer> {
+    static CURRENT: OnceLock<Regex> = OnceLock::new();
+    static PLACE_TIME: OnceLock<Regex> = OnceLock::new();
+    static CONVERT: OnceLock<Regex> = OnceLock::new();
+    let current = CURRENT.get_or_init(|| {
+        Regex::new(r"^(?:what(?:'s| is)? )?(?:the )?(?:current |local )?time (?:is it |now )?in (.+)$").unwrap()
+    });
+    let place_time = PLACE_TIME.get_or_init(|| Regex::new(r"^(.+?) (?:local )?time(?: now)?$").unwrap());
+    let convert = CONVERT.get_or_init(|| {
+        Regex::new(r"^(\d{1,2}(?::\d{2})?\s*(?:am|pm|a\.m\.|p\.m\.)?|noon|midday|midnight)(?: (.+?))??(?: (?:to|in|into) (.+))?$").unwrap()
+    });
+    let lower = query.to_lowercase();
+
+    if let Some(place) = current.captures(&lower).or_else(|| place_time.captures(&lower)).map(|c| c[1].to_string()) {
+        let tz = find_zone(&place)?;
+        let there = now.with_timezone(&tz);
+        let label = zone_label(&there);
+        return Some(
+            InstantAnswer::new(AnswerKind::Time, format!("Time in {}", tz.name()), clock_text(&there, &label))
+                .with_note(format!("{} · {}", there.format("%A, %B %-d"), utc_offset(there.offset().fix().local_minus_utc()))),
+        );
+    }
+
+    // Either side may be left out and means local time: "3pm est", "9am in tokyo"
+    let c = convert.captures(&lower)?;
+    let clock = parse_clock(c[1].trim())?;
+    let local = format!("local time ({})", utc_offset(now.offset().local_minus_utc()));
+    let (date, start, start_label) = match c.get(2) {
+        Some(source) => {
+            let source = find_zone(source.as_str())?;
+            let date = now.with_timezone(&source).date_naive();
+            let start = source.from_local_datetime(&date.and_time(clock)).earliest()?;
+            let label = zone_label(&start);
+            (date, start.fixed_offset(), label)
+        }
+        None => {
+            let date = now.date_naive();
+            let start = now.offset().from_local_datetime(&date.and_time(clock)).earliest()?;
+            (date, start, local.clone())
+        }
+    };
+    let (result, day) = match c.get(3) {
+        Some(target) => {
+            let tz = find_zone(target.as_str())?;
+            let converted = start.with_timezone(&tz);
+            (clock_text(&converted, &zone_label(&converted)), converted.date_naive())
+        }
+        None if c.get(2).is_some() => {
+            let converted = start.with_timezone(now.offset());
+            (clock_text(&converted, &local), converted.date_naive())
+        }
+        None => return None,
+    };
+    let shift = match (day - date).num_days() {
+        0 => String::new(),
+        1 => " (next day)".to_string(),
+        -1 => " (previous day)".to_string(),
+        n => format!(" ({:+} days)", n),
+    };
+    Some(
+        InstantAnswer::new(AnswerKind::Time, clock_text(&start, &start_label), result)
+            .with_note(format!("{}{}", day.format("%A, %B %-d"), shift)),
+    )
+}
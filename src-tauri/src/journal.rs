@@ -0,0 +1,537 @@
+// Trade Journal - Logged trades with notes, a market snapshot at execution, and model-suggested tags
+// The Trade mode records fills in trade_logs; journal entries enrich them and journal_report looks for patterns.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::ai::AIService;
+use crate::db::Database;
+use crate::ohlc::Bar;
+use crate::privacy::PrivacyEnforcer;
+
+const DAY_SECS: i64 = 86_400;
+// Enough daily bars before the execution for SMA(50) and a 20-day range
+const SNAPSHOT_LOOKBACK_DAYS: i64 = 120;
+const MAX_NOTES_CHARS: usize = 8_000;
+const MAX_TAGS: usize = 5;
+
+// The vocabulary the model picks tags from; anything else it answers is dropped
+pub const TAGS: &[&str] = &[
+    "breakout",
+    "breakdown",
+    "reversal",
+    "trend-following",
+    "mean-reversion",
+    "momentum",
+    "news-driven",
+    "earnings",
+    "gap",
+    "scalp",
+    "swing",
+    "hedge",
+    "planned",
+    "impulsive",
+    "fomo",
+    "revenge",
+    "stopped-out",
+    "target-hit",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum TradeSide {
+    Buy,
+    Sell,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct TradeLog {
+    pub id: String,
+    pub symbol: String,
+    pub side: TradeSide,
+    pub quantity: f64,
+    pub entry_price: f64,
+    pub exit_price: Option<f64>,       // None while the position is open
+    pub fees: f64,
+    pub pnl: Option<f64>,              // After fees, once closed
+    pub opened_at: i64,
+    pub closed_at: Option<i64>,
+    pub strategy: Option<String>,
+    pub source: String,                // "paper", "binance", ...
+    pub created_at: i64,
+}
+
+// What the Trade mode sends when an order fills or a position closes; the same id updates the log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct TradeLogInput {
+    pub id: Option<String>,
+    pub symbol: String,
+    pub side: TradeSide,
+    pub quantity: f64,
+    pub entry_price: f64,
+    pub exit_price: Option<f64>,
+    pub fees: Option<f64>,
+    pub opened_at: Option<i64>,
+    pub closed_at: Option<i64>,
+    pub strategy: Option<String>,
+    pub source: Option<String>,
+}
+
+// Daily-bar conditions on the day of execution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct MarketSnapshot {
+    pub as_of: i64,                    // Session of the bar used
+    pub close: f64,
+    pub day_change_pct: f64,
+    pub sma20: Option<f64>,
+    pub sma50: Option<f64>,
+    pub rsi14: Option<f64>,
+    pub atr14_pct: Option<f64>,        // Average true range as a percent of the close
+    pub volume_ratio: Option<f64>,     // Volume over its 20-day average
+    pub range_position_pct: Option<f64>, // Close within the 20-day high/low range (0 = low, 100 = high)
+    pub trend: String,                 // "up", "down", or "sideways"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    pub id: String,
+    pub trade_id: String,
+    pub notes: String,
+    pub tags: Vec<String>,
+    pub snapshot: Option<MarketSnapshot>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct JournalItem {
+    pub trade: TradeLog,
+    pub entry: JournalEntry,
+}
+
+// "week", "month", "quarter", "year", "all", or an explicit span in unix seconds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(untagged)]
+pub enum JournalRange {
+    Preset(String),
+    Span { from: i64, to: i64 },
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct GroupStats {
+    pub key: String,
+    pub trades: usize,
+    pub wins: usize,
+    pub win_rate: f64,
+    pub net_pnl: f64,
+    pub avg_pnl: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct JournalReport {
+    pub from: i64,
+    pub to: i64,
+    pub overall: GroupStats,
+    pub avg_win: f64,
+    pub avg_loss: f64,
+    pub profit_factor: Option<f64>,
+    pub by_tag: Vec<GroupStats>,       // Best net P&L first
+    pub by_symbol: Vec<GroupStats>,
+    pub by_side: Vec<GroupStats>,
+    pub by_trend: Vec<GroupStats>,     // Market trend at execution
+    pub untagged: usize,               // Closed trades without a journal entry
+    pub best: Option<TradeLog>,
+    pub worst: Option<TradeLog>,
+    pub summary: Option<String>,       // Local model's read of the patterns; None when unavailable
+}
+
+impl TradeLog {
+    fn from_input(input: TradeLogInput, existing: Option<&TradeLog>) -> Result<Self, JournalError> {
+        let symbol = input.symbol.trim().to_uppercase();
+        if symbol.is_empty() || symbol.len() > 30 {
+            return Err(JournalError::InvalidTrade("symbol is required".to_string()));
+        }
+        if !(input.quantity.is_finite() && input.quantity > 0.0) {
+            return Err(JournalError::InvalidTrade("quantity must be positive".to_string()));
+        }
+        let prices = [Some(input.entry_price), input.exit_price];
+        if prices.into_iter().flatten().any(|p| !(p.is_finite() && p > 0.0)) {
+            return Err(JournalError::InvalidTrade("prices must be positive".to_string()));
+        }
+        let fees = input.fees.unwrap_or(0.0);
+        if !(fees.is_finite() && fees >= 0.0) {
+            return Err(JournalError::InvalidTrade("fees can't be negative".to_string()));
+        }
+        let now = chrono::Utc::now().timestamp();
+        let opened_at = input.opened_at.or(existing.map(|t| t.opened_at)).unwrap_or(now);
+        let closed_at = input.exit_price.map(|_| input.closed_at.unwrap_or(now));
+        if closed_at.is_some_and(|closed| closed < opened_at) {
+            return Err(JournalError::InvalidTrade("a trade can't close before it opens".to_string()));
+        }
+        let direction = if input.side == TradeSide::Buy { 1.0 } else { -1.0 };
+        Ok(TradeLog {
+            id: input.id.filter(|id| !id.trim().is_empty()).unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            pnl: input.exit_price.map(|exit| direction * (exit - input.entry_price) * input.quantity - fees),
+            symbol,
+            side: input.side,
+            quantity: input.quantity,
+            entry_price: input.entry_price,
+            exit_price: input.exit_price,
+            fees,
+            opened_at,
+            closed_at,
+            strategy: input.strategy.filter(|s| !s.trim().is_empty()),
+            source: input.source.filter(|s| !s.trim().is_empty()).unwrap_or_else(|| "paper".to_string()),
+            created_at: existing.map(|t| t.created_at).unwrap_or(now),
+        })
+    }
+
+    fn describe(&self) -> String {
+        let side = if self.side == TradeSide::Buy { "Bought" } else { "Sold short" };
+        let opened = chrono::DateTime::from_timestamp(self.opened_at, 0).map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string()).unwrap_or_default();
+        let mut text = format!("{} {} {} at {} on {}", side, self.quantity, self.symbol, self.entry_price, opened);
+        if let (Some(exit), Some(pnl)) = (self.exit_price, self.pnl) {
+            text.push_str(&format!(", closed at {} for a P&L of {:.2}", exit, pnl));
+        }
+        if let Some(strategy) = &self.strategy {
+            text.push_str(&format!(" (strategy: {})", strategy));
+        }
+        text
+    }
+}
+
+pub fn record_trade(app: &tauri::AppHandle, input: TradeLogInput) -> Result<TradeLog, JournalError> {
+    enforce_disk_write(app)?;
+    let db = app.state::<Database>();
+    let existing = match input.id.as_deref() {
+        Some(id) => db.trade_log_get(id).map_err(storage)?,
+        None => None,
+    };
+    let trade = TradeLog::from_input(input, existing.as_ref())?;
+    db.trade_log_save(&trade).map_err(storage)?;
+    Ok(trade)
+}
+
+// Attach (or replace) notes for a trade; the market snapshot is taken once and tags are refreshed from the notes
+pub async fn add_entry(app: &tauri::AppHandle, trade_id: &str, notes: &str) -> Result<JournalItem, JournalError> {
+    enforce_disk_write(app)?;
+    let db = app.state::<Database>().inner().clone();
+    let trade = db.trade_log_get(trade_id).map_err(storage)?.ok_or_else(|| JournalError::TradeNotFound(trade_id.to_string()))?;
+    let notes: String = notes.trim().chars().take(MAX_NOTES_CHARS).collect();
+    let existing = db.journal_entry_for_trade(trade_id).map_err(storage)?;
+    let now = chrono::Utc::now().timestamp();
+
+    let snapshot = match existing.as_ref().and_then(|e| e.snapshot.clone()) {
+        Some(snapshot) => Some(snapshot),
+        None => market_snapshot(&db, &trade.symbol, trade.opened_at).await,
+    };
+    let tags = suggest_tags(app, &trade, snapshot.as_ref(), &notes).await;
+
+    let entry = JournalEntry {
+        id: existing.as_ref().map(|e| e.id.clone()).unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        trade_id: trade.id.clone(),
+        notes,
+        tags,
+        snapshot,
+        created_at: existing.as_ref().map(|e| e.created_at).unwrap_or(now),
+        updated_at: now,
+    };
+    db.journal_entry_save(&entry).map_err(storage)?;
+    Ok(JournalItem { trade, entry })
+}
+
+// Best effort: without price history (offline, unknown symbol) the entry simply has no snapshot
+async fn market_snapshot(db: &Database, symbol: &str, at: i64) -> Option<MarketSnapshot> {
+    let from = at - SNAPSHOT_LOOKBACK_DAYS * DAY_SECS;
+    let bars = match crate::ohlc::daily_bars(db, symbol, from, at + DAY_SECS, false).await {
+        Ok(bars) => bars,
+        Err(e) => {
+            tracing::debug!(target: "app", "Journal: No snapshot for {}: {}", symbol, e);
+            return None;
+        }
+    };
+    snapshot_from_bars(&bars.into_iter().filter(|b| b.time <= at).collect::<Vec<_>>())
+}
+
+fn snapshot_from_bars(bars: &[Bar]) -> Option<MarketSnapshot> {
+    if bars.len() < 2 {
+        return None;
+    }
+    let last = bars[bars.len() - 1];
+    let previous = bars[bars.len() - 2];
+    let closes: Vec<f64> = bars.iter().map(|b| b.close).collect();
+    let latest = |series: Vec<Option<f64>>| series.last().copied().flatten();
+    let sma20 = latest(crate::backtest::sma(&closes, 20));
+    let sma50 = latest(crate::backtest::sma(&closes, 50));
+
+    let true_ranges: Vec<f64> = bars
+        .windows(2)
+        .map(|w| (w[1].high - w[1].low).max((w[1].high - w[0].close).abs()).max((w[1].low - w[0].close).abs()))
+        .collect();
+    let atr14_pct = (true_ranges.len() >= 14 && last.close > 0.0)
+        .then(|| true_ranges[true_ranges.len() - 14..].iter().sum::<f64>() / 14.0 / last.close * 100.0);
+
+    let prior = &bars[bars.len().saturating_sub(21)..bars.len() - 1];
+    let avg_volume = prior.iter().map(|b| b.volume).sum::<f64>() / prior.len().max(1) as f64;
+    let window = &bars[bars.len().saturating_sub(20)..];
+    let high = window.iter().map(|b| b.high).fold(f64::MIN, f64::max);
+    let low = window.iter().map(|b| b.low).fold(f64::MAX, f64::min);
+
+    let trend = match (sma20, sma50) {
+        (Some(fast), Some(slow)) if last.close > fast && fast > slow => "up",
+        (Some(fast), Some(slow)) if last.close < fast && fast < slow => "down",
+        _ => "sideways",
+    };
+    Some(MarketSnapshot {
+        as_of: last.time,
+        close: last.close,
+        day_change_pct: if previous.close > 0.0 { (last.close / previous.close - 1.0) * 100.0 } else { 0.0 },
+        sma20,
+        sma50,
+        rsi14: latest(crate::backtest::rsi(&closes, 14)),
+        atr14_pct,
+        volume_ratio: (avg_volume > 0.0).then(|| last.volume / avg_volume),
+        range_position_pct: (high > low).then(|| (last.close - low) / (high - low) * 100.0),
+        trend: trend.to_string(),
+    })
+}
+
+fn describe_snapshot(snapshot: &MarketSnapshot) -> String {
+    let mut parts = vec![format!("trend {}", snapshot.trend), format!("day change {:+.2}%", snapshot.day_change_pct)];
+    if let Some(rsi) = snapshot.rsi14 {
+        parts.push(format!("RSI(14) {:.0}", rsi));
+    }
+    if let Some(atr) = snapshot.atr14_pct {
+        parts.push(format!("ATR(14) {:.2}% of price", atr));
+    }
+    if let Some(ratio) = snapshot.volume_ratio {
+        parts.push(format!("volume {:.1}x its 20-day average", ratio));
+    }
+    if let Some(position) = snapshot.range_position_pct {
+        parts.push(format!("close at {:.0}% of the 20-day range", position));
+    }
+    parts.join(", ")
+}
+
+async fn suggest_tags(app: &tauri::AppHandle, trade: &TradeLog, snapshot: Option<&MarketSnapshot>, notes: &str) -> Vec<String> {
+    let ai = app.state::<AIService>();
+    if !ai.is_available() {
+        return Vec::new();
+    }
+    let market = snapshot.map(describe_snapshot).unwrap_or_else(|| "unknown".to_string());
+    let prompt = crate::prompts::render(
+        "journal_tags",
+        None,
+        None,
+        &[
+            ("tags", &TAGS.join(", ")),
+            ("trade", &trade.describe()),
+            ("market", &market),
+            ("notes", if notes.is_empty() { "(none)" } else { notes }),
+        ],
+    );
+    match ai.complete_task("journal_tags", &prompt).await {
+        Ok(answer) => parse_tags(&answer),
+        Err(e) => {
+            tracing::debug!(target: "ai", "Journal: Tagging failed: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+fn parse_tags(answer: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for word in answer.split([',', '\n', ';']) {
+        let tag = word.trim().trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase().replace([' ', '_'], "-");
+        if TAGS.contains(&tag.as_str()) && !tags.contains(&tag) && tags.len() < MAX_TAGS {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+impl JournalRange {
+    fn resolve(&self, now: i64) -> Result<(i64, i64), JournalError> {
+        let (from, to) = match self {
+            JournalRange::Preset(preset) => {
+                let days = match preset.trim().to_lowercase().as_str() {
+                    "week" => 7,
+                    "month" => 30,
+                    "quarter" => 91,
+                    "year" => 365,
+                    "all" => return Ok((0, now)),
+                    other => return Err(JournalError::InvalidRange(format!("unknown range '{}'", other))),
+                };
+                (now - days * DAY_SECS, now)
+            }
+            JournalRange::Span { from, to } => (*from, *to),
+        };
+        if from >= to {
+            return Err(JournalError::InvalidRange("'from' must be before 'to'".to_string()));
+        }
+        Ok((from, to))
+    }
+}
+
+fn group<'a>(key: String, trades: impl Iterator<Item = &'a TradeLog>) -> GroupStats {
+    let pnls: Vec<f64> = trades.filter_map(|t| t.pnl).collect();
+    let wins = pnls.iter().filter(|p| **p > 0.0).count();
+    let net_pnl: f64 = pnls.iter().sum();
+    GroupStats {
+        key,
+        trades: pnls.len(),
+        wins,
+        win_rate: if pnls.is_empty() { 0.0 } else { wins as f64 / pnls.len() as f64 * 100.0 },
+        net_pnl,
+        avg_pnl: if pnls.is_empty() { 0.0 } else { net_pnl / pnls.len() as f64 },
+    }
+}
+
+fn group_by<'a>(trades: &'a [TradeLog], keys: impl Fn(&'a TradeLog) -> Vec<String>) -> Vec<GroupStats> {
+    let mut groups: BTreeMap<String, Vec<&TradeLog>> = BTreeMap::new();
+    for trade in trades {
+        for key in keys(trade) {
+            groups.entry(key).or_default().push(trade);
+        }
+    }
+    let mut stats: Vec<GroupStats> = groups.into_iter().map(|(key, trades)| group(key, trades.into_iter())).collect();
+    stats.sort_by(|a, b| b.net_pnl.total_cmp(&a.net_pnl));
+    stats
+}
+
+// Closed trades in the range, grouped by tag, symbol, side, and market trend at execution
+pub async fn report(app: &tauri::AppHandle, range: &JournalRange) -> Result<JournalReport, JournalError> {
+    let (from, to) = range.resolve(chrono::Utc::now().timestamp())?;
+    let db = app.state::<Database>();
+    let trades = db.trade_logs_closed_between(from, to).map_err(storage)?;
+    let entries: BTreeMap<String, JournalEntry> = db
+        .journal_entries_closed_between(from, to)
+        .map_err(storage)?
+        .into_iter()
+        .map(|e| (e.trade_id.clone(), e))
+        .collect();
+
+    let pnl = |t: &TradeLog| t.pnl.unwrap_or(0.0);
+    let gross_profit: f64 = trades.iter().map(pnl).filter(|p| *p > 0.0).sum();
+    let gross_loss: f64 = -trades.iter().map(pnl).filter(|p| *p < 0.0).sum::<f64>();
+    let overall = group("all".to_string(), trades.iter());
+    let losses = overall.trades - overall.wins;
+    let mut report = JournalReport {
+        from,
+        to,
+        avg_win: if overall.wins > 0 { gross_profit / overall.wins as f64 } else { 0.0 },
+        avg_loss: if losses > 0 { -gross_loss / losses as f64 } else { 0.0 },
+        profit_factor: (gross_loss > 0.0).then(|| gross_profit / gross_loss),
+        by_tag: group_by(&trades, |t| entries.get(&t.id).map(|e| e.tags.clone()).unwrap_or_default()),
+        by_symbol: group_by(&trades, |t| vec![t.symbol.clone()]),
+        by_side: group_by(&trades, |t| vec![if t.side == TradeSide::Buy { "long" } else { "short" }.to_string()]),
+        by_trend: group_by(&trades, |t| {
+            entries.get(&t.id).and_then(|e| e.snapshot.as_ref()).map(|s| vec![s.trend.clone()]).unwrap_or_default()
+        }),
+        untagged: trades.iter().filter(|t| !entries.contains_key(&t.id)).count(),
+        best: trades.iter().max_by(|a, b| pnl(a).total_cmp(&pnl(b))).cloned(),
+        worst: trades.iter().min_by(|a, b| pnl(a).total_cmp(&pnl(b))).cloned(),
+        overall,
+        summary: None,
+    };
+    if report.overall.trades > 0 {
+        report.summary = summarize(app, &report, &trades, &entries).await;
+    }
+    Ok(report)
+}
+
+async fn summarize(
+    app: &tauri::AppHandle,
+    report: &JournalReport,
+    trades: &[TradeLog],
+    entries: &BTreeMap<String, JournalEntry>,
+) -> Option<String> {
+    let ai = app.state::<AIService>();
+    if !ai.is_available() {
+        return None;
+    }
+    let line = |g: &GroupStats| format!("- {}: {} trades, {:.0}% wins, net {:.2}", g.key, g.trades, g.win_rate, g.net_pnl);
+    let section = |title: &str, groups: &[GroupStats]| {
+        format!("{}:\n{}", title, groups.iter().map(line).collect::<Vec<_>>().join("\n"))
+    };
+    let mut stats = vec![
+        line(&report.overall),
+        format!("Average win {:.2}, average loss {:.2}", report.avg_win, report.avg_loss),
+        section("By tag", &report.by_tag),
+        section("By symbol", &report.by_symbol),
+        section("By side", &report.by_side),
+        section("By market trend at entry", &report.by_trend),
+    ];
+    let noted: Vec<String> = trades
+        .iter()
+        .filter_map(|t| {
+            let entry = entries.get(&t.id).filter(|e| !e.notes.is_empty())?;
+            Some(format!("- {}: {}", t.describe(), entry.notes.chars().take(300).collect::<String>()))
+        })
+        .take(20)
+        .collect();
+    if !noted.is_empty() {
+        stats.push(format!("Journal notes:\n{}", noted.join("\n")));
+    }
+    let prompt = crate::prompts::render("journal_report", None, None, &[("stats", &stats.join("\n\n"))]);
+    match ai.complete_task("journal_report", &prompt).await {
+        Ok(summary) => Some(summary.trim().to_string()),
+        Err(e) => {
+            tracing::debug!(target: "ai", "Journal: Report summary failed: {}", e);
+            None
+        }
+    }
+}
+
+fn enforce_disk_write(app: &tauri::AppHandle) -> Result<(), JournalError> {
+    app.state::<Mutex<PrivacyEnforcer>>()
+        .lock()
+        .unwrap()
+        .enforce_disk_write()
+        .map_err(|e| JournalError::Privacy(e.to_string()))
+}
+
+fn storage(e: rusqlite::Error) -> JournalError {
+    JournalError::Storage(e.to_string())
+}
+
+#[derive(Debug, Clone)]
+pub enum JournalError {
+    InvalidTrade(String),
+    InvalidRange(String),
+    TradeNotFound(String),
+    Privacy(String),
+    Storage(String),
+}
+
+impl std::fmt::Display for JournalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JournalError::InvalidTrade(msg) => write!(f, "Invalid trade: {}", msg),
+            JournalError::InvalidRange(msg) => write!(f, "Invalid range: {}", msg),
+            JournalError::TradeNotFound(id) => write!(f, "Trade {} not found", id),
+            JournalError::Privacy(e) => write!(f, "{}", e),
+            JournalError::Storage(e) => write!(f, "Storage error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for JournalError {}
@@ -0,0 +1,354 @@
+// Language Detection - Script regex + whatlang n-gram model
+// Hybrid detector: script ranges pick the family, whatlang + marker words rank languages;
+// scripts without a range (Cyrillic, CJK, Greek, ...) are left to whatlang alone
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use whatlang::{Detector, Lang};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageCandidate {
+    pub language: String, // ISO 639-1 code where one exists (e.g. "hi", "mr")
+    pub confidence: f64,  // 0.0 - 1.0
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageDetection {
+    pub language: String,
+    pub confidence: f64,
+    pub script: String,
+    pub romanized: bool,                    // Indic language typed in Latin script
+    pub candidates: Vec<LanguageCandidate>, // Ranked, highest confidence first
+}
+
+impl LanguageDetection {
+    fn undetermined() -> Self {
+        Self {
+            language: "und".to_string(),
+            confidence: 0.0,
+            script: "Unknown".to_string(),
+            romanized: false,
+            candidates: vec![],
+        }
+    }
+}
+
+// Minimum letters before the n-gram model is trusted at full weight
+const NGRAM_FULL_TRUST_CHARS: usize = 40;
+// Share of romanized Hindi tokens needed before "hi" is offered for Latin text
+const ROMAN_HINDI_MIN_SHARE: f64 = 0.25;
+
+const HINDI_MARKERS: &[&str] = &[
+    "है", "हैं", "और", "नहीं", "का", "की", "के", "में", "था", "थी", "थे", "क्या",
+    "मैं", "आप", "यह", "वह", "हम", "से", "को", "पर", "रहा", "रही", "गया", "कैसा", "होगा",
+];
+
+const MARATHI_MARKERS: &[&str] = &[
+    "आहे", "आहेत", "आणि", "नाही", "मध्ये", "होते", "होता", "काय", "मी", "तुम्ही",
+    "आम्ही", "करा", "कसे", "कसा", "झाले", "येथे", "त्या", "साठी", "पण", "आपण",
+];
+
+const NEPALI_MARKERS: &[&str] = &[
+    "छ", "छन्", "हो", "गर्न", "भएको", "पनि", "लागि", "थियो", "गरेको", "हुन्छ", "तपाईं",
+];
+
+// Marathi genitive suffixes rarely end Hindi words
+const MARATHI_SUFFIXES: &[&str] = &["च्या", "ांना", "ाचा", "ाची", "ाचे"];
+
+const ROMAN_HINDI_WORDS: &[&str] = &[
+    "hai", "hain", "kya", "kaise", "kaisa", "kaisi", "nahi", "nahin", "mera", "meri", "mere",
+    "tum", "tera", "aap", "apna", "hum", "hoga", "hogi", "kal", "aaj", "mausam", "karo",
+    "karna", "kar", "raha", "rahi", "tha", "thi", "bhai", "yaar", "acha", "accha", "theek",
+    "thik", "kuch", "kyun", "kyu", "kab", "kahan", "kaun", "batao", "bata", "chahiye", "abhi",
+    "bahut", "sab", "wala", "wali", "mein", "aur", "jao", "chalo", "dekho", "baje", "paisa",
+    "khana", "pani", "ghar", "dost", "din", "raat", "subah", "shaam", "kitna", "kitne", "koi",
+];
+
+fn script_patterns() -> &'static [(&'static str, Regex)] {
+    static PATTERNS: OnceLock<Vec<(&'static str, Regex)>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        [
+            ("Devanagari", r"[\x{0900}-\x{097F}]"),
+            ("Bengali", r"[\x{0980}-\x{09FF}]"),
+            ("Gurmukhi", r"[\x{0A00}-\x{0A7F}]"),
+            ("Gujarati", r"[\x{0A80}-\x{0AFF}]"),
+            ("Oriya", r"[\x{0B00}-\x{0B7F}]"),
+            ("Tamil", r"[\x{0B80}-\x{0BFF}]"),
+            ("Telugu", r"[\x{0C00}-\x{0C7F}]"),
+            ("Kannada", r"[\x{0C80}-\x{0CFF}]"),
+            ("Malayalam", r"[\x{0D00}-\x{0D7F}]"),
+            ("Arabic", r"[\x{0600}-\x{06FF}]"),
+            ("Latin", r"[A-Za-z\x{00C0}-\x{024F}]"),
+        ]
+        .into_iter()
+        .map(|(name, pattern)| (name, Regex::new(pattern).expect("valid script regex")))
+        .collect()
+    })
+}
+
+// Detect the language of a text with ranked candidates
+pub fn detect_language(text: &str) -> LanguageDetection {
+    // Scripts without a range above (Cyrillic, CJK, Greek, Hebrew, Thai, ...) go straight to whatlang
+    if let Some(script) = whatlang::detect_script(text).filter(|s| !has_pattern(*s)) {
+        return detect_other_script(text, script);
+    }
+
+    let counts: Vec<(&'static str, usize)> = script_patterns()
+        .iter()
+        .map(|(name, re)| (*name, re.find_iter(text).count()))
+        .filter(|(_, count)| *count > 0)
+        .collect();
+
+    let total: usize = counts.iter().map(|(_, c)| c).sum();
+    let Some(&(script, script_chars)) = counts.iter().max_by_key(|(_, c)| *c) else {
+        return LanguageDetection::undetermined();
+    };
+
+    // Mixed-script text lowers every candidate's confidence proportionally
+    let script_share = script_chars as f64 / total as f64;
+    let length_trust = (script_chars as f64 / NGRAM_FULL_TRUST_CHARS as f64).min(1.0);
+
+    let mut romanized = false;
+    let scored: Vec<(String, f64)> = match script {
+        "Devanagari" => score_devanagari(text, length_trust),
+        "Latin" => {
+            let (scores, is_roman_hindi) = score_latin(text, length_trust);
+            romanized = is_roman_hindi;
+            scores
+        }
+        "Arabic" => score_with_model(text, vec![Lang::Urd, Lang::Ara, Lang::Pes], length_trust),
+        "Bengali" => vec![("bn".to_string(), 1.0)],
+        "Gurmukhi" => vec![("pa".to_string(), 1.0)],
+        "Gujarati" => vec![("gu".to_string(), 1.0)],
+        "Oriya" => vec![("or".to_string(), 1.0)],
+        "Tamil" => vec![("ta".to_string(), 1.0)],
+        "Telugu" => vec![("te".to_string(), 1.0)],
+        "Kannada" => vec![("kn".to_string(), 1.0)],
+        "Malayalam" => vec![("ml".to_string(), 1.0)],
+        _ => vec![],
+    };
+
+    let candidates: Vec<LanguageCandidate> = normalize(scored)
+        .into_iter()
+        .map(|(language, score)| LanguageCandidate {
+            language,
+            confidence: round3(score * script_share),
+        })
+        .filter(|c| c.confidence > 0.0)
+        .collect();
+    ranked(candidates, script, romanized)
+}
+
+fn has_pattern(script: whatlang::Script) -> bool {
+    use whatlang::Script;
+    matches!(
+        script,
+        Script::Devanagari
+            | Script::Bengali
+            | Script::Gurmukhi
+            | Script::Gujarati
+            | Script::Oriya
+            | Script::Tamil
+            | Script::Telugu
+            | Script::Kannada
+            | Script::Malayalam
+            | Script::Arabic
+            | Script::Latin
+    )
+}
+
+// The n-gram model over every language it knows, trusted by the number of letters
+fn detect_other_script(text: &str, script: whatlang::Script) -> LanguageDetection {
+    let letters = text.chars().filter(|c| c.is_alphabetic()).count();
+    let length_trust = (letters as f64 / NGRAM_FULL_TRUST_CHARS as f64).min(1.0);
+    let candidates = normalize(score_with_model_all(text, length_trust))
+        .into_iter()
+        .map(|(language, score)| LanguageCandidate { language, confidence: round3(score) })
+        .filter(|c| c.confidence > 0.0)
+        .collect();
+    ranked(candidates, script.name(), false)
+}
+
+fn ranked(mut candidates: Vec<LanguageCandidate>, script: &str, romanized: bool) -> LanguageDetection {
+    candidates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    match candidates.first() {
+        Some(top) => LanguageDetection {
+            language: top.language.clone(),
+            confidence: top.confidence,
+            script: script.to_string(),
+            romanized,
+            candidates,
+        },
+        None => LanguageDetection::undetermined(),
+    }
+}
+
+// Hindi / Marathi / Nepali share Devanagari; blend the n-gram model with marker words
+fn score_devanagari(text: &str, length_trust: f64) -> Vec<(String, f64)> {
+    let model = score_with_model(text, vec![Lang::Hin, Lang::Mar, Lang::Nep], length_trust);
+
+    let mut markers: HashMap<&str, f64> = HashMap::new();
+    for token in tokens(text) {
+        if HINDI_MARKERS.contains(&token.as_str()) {
+            *markers.entry("hi").or_default() += 1.0;
+        }
+        if MARATHI_MARKERS.contains(&token.as_str())
+            || MARATHI_SUFFIXES.iter().any(|s| token.ends_with(s) && token != *s)
+        {
+            *markers.entry("mr").or_default() += 1.0;
+        }
+        if NEPALI_MARKERS.contains(&token.as_str()) {
+            *markers.entry("ne").or_default() += 1.0;
+        }
+    }
+    let marker_total: f64 = markers.values().sum();
+
+    if marker_total == 0.0 {
+        return model;
+    }
+
+    // Marker words are strong evidence on short text where the n-gram model is weak
+    let marker_weight = 1.0 - 0.5 * length_trust;
+    ["hi", "mr", "ne"]
+        .iter()
+        .map(|code| {
+            let model_score = model
+                .iter()
+                .find(|(c, _)| c == code)
+                .map(|(_, s)| *s)
+                .unwrap_or(0.0);
+            let marker_score = markers.get(code).copied().unwrap_or(0.0) / marker_total;
+            (
+                code.to_string(),
+                marker_weight * marker_score + (1.0 - marker_weight) * model_score,
+            )
+        })
+        .collect()
+}
+
+// Latin script: check for romanized Hindi before handing over to the n-gram model
+fn score_latin(text: &str, length_trust: f64) -> (Vec<(String, f64)>, bool) {
+    let tokens = tokens(text);
+    let roman_hits = tokens
+        .iter()
+        .filter(|t| ROMAN_HINDI_WORDS.contains(&t.as_str()))
+        .count();
+    let roman_share = if tokens.is_empty() {
+        0.0
+    } else {
+        roman_hits as f64 / tokens.len() as f64
+    };
+
+    let model = score_with_model_all(text, length_trust);
+
+    if roman_share < ROMAN_HINDI_MIN_SHARE {
+        return (model, false);
+    }
+
+    let mut scores: Vec<(String, f64)> = model
+        .into_iter()
+        .filter(|(code, _)| code != "hi")
+        .map(|(code, score)| (code, score * (1.0 - roman_share)))
+        .collect();
+    scores.push(("hi".to_string(), roman_share));
+    (scores, true)
+}
+
+// Run whatlang restricted to `langs`, spreading the residual confidence over the rest
+fn score_with_model(text: &str, langs: Vec<Lang>, length_trust: f64) -> Vec<(String, f64)> {
+    let others = langs.len().saturating_sub(1).max(1) as f64;
+    let detector = Detector::with_allowlist(langs.clone());
+    match detector.detect(text) {
+        Some(info) => {
+            let top = info.confidence() * length_trust + (1.0 - length_trust) / langs.len() as f64;
+            langs
+                .iter()
+                .map(|lang| {
+                    let score = if *lang == info.lang() { top } else { (1.0 - top) / others };
+                    (iso_code(*lang), score)
+                })
+                .collect()
+        }
+        None => langs
+            .iter()
+            .map(|lang| (iso_code(*lang), 1.0 / langs.len() as f64))
+            .collect(),
+    }
+}
+
+// Run whatlang over all languages, returning the winner and the runner-up
+fn score_with_model_all(text: &str, length_trust: f64) -> Vec<(String, f64)> {
+    let Some(first) = whatlang::detect(text) else {
+        return vec![];
+    };
+    let top = first.confidence() * length_trust;
+    let mut scores = vec![(iso_code(first.lang()), top.max(0.01))];
+
+    if let Some(second) = Detector::with_denylist(vec![first.lang()]).detect(text) {
+        scores.push((iso_code(second.lang()), (1.0 - top) * second.confidence().max(0.1)));
+    }
+    scores
+}
+
+fn tokens(text: &str) -> Vec<String> {
+    text.split(|c: char| c.is_whitespace() || (c.is_ascii_punctuation() && c != '\'') || c == '।')
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_lowercase())
+        .collect()
+}
+
+fn normalize(scores: Vec<(String, f64)>) -> Vec<(String, f64)> {
+    let total: f64 = scores.iter().map(|(_, s)| s.max(0.0)).sum();
+    if total <= 0.0 {
+        return scores;
+    }
+    scores
+        .into_iter()
+        .map(|(code, score)| (code, score.max(0.0) / total))
+        .collect()
+}
+
+fn round3(value: f64) -> f64 {
+    (value * 1000.0).round() / 1000.0
+}
+
+// Map whatlang ISO 639-3 codes to the 639-1 codes used across the app
+fn iso_code(lang: Lang) -> String {
+    let code = match lang {
+        Lang::Eng => "en",
+        Lang::Hin => "hi",
+        Lang::Mar => "mr",
+        Lang::Nep => "ne",
+        Lang::Ben => "bn",
+        Lang::Tam => "ta",
+        Lang::Tel => "te",
+        Lang::Guj => "gu",
+        Lang::Pan => "pa",
+        Lang::Kan => "kn",
+        Lang::Mal => "ml",
+        Lang::Ori => "or",
+        Lang::Urd => "ur",
+        Lang::Ara => "ar",
+        Lang::Pes => "fa",
+        Lang::Spa => "es",
+        Lang::Fra => "fr",
+        Lang::Deu => "de",
+        Lang::Por => "pt",
+        Lang::Ita => "it",
+        Lang::Nld => "nl",
+        Lang::Rus => "ru",
+        Lang::Tur => "tr",
+        Lang::Ind => "id",
+        Lang::Jpn => "ja",
+        Lang::Kor => "ko",
+        Lang::Cmn => "zh",
+        other => other.code(),
+    };
+    code.to_string()
+}
@@ -11,9 +11,107 @@ pub mod agent;
 pub mod tor;
 pub mod commands;
 pub mod stability;
+pub mod language;
+pub mod extractor;
+pub mod reader;
+pub mod archive;
+pub mod session;
+pub mod profiles;
+pub mod research;
+pub mod notes;
+pub mod highlights;
+pub mod embeddings;
+#[cfg(feature = "local-embeddings")]
+pub mod local_embeddings;
+pub mod clustering;
+pub mod text;
+pub mod grounding;
+pub mod prompts;
+pub mod llm_cache;
+pub mod hardware;
+pub mod resources;
+pub mod logging;
+pub mod config;
+pub mod secure_store;
+pub mod apikeys;
+pub mod http;
+pub mod connectivity;
+pub mod web_search;
+pub mod local_index;
+pub mod omnibox;
+pub mod frecency;
+pub mod tab_discard;
+pub mod tab_metrics;
+pub mod clipboard;
+pub mod capture;
+pub mod selection;
+pub mod diagnostics;
+pub mod autofill;
+pub mod passwords;
+pub mod focus;
+pub mod usage;
+pub mod modes;
+pub mod webrtc_guard;
+pub mod doh;
+pub mod proxy;
+pub mod network_audit;
+pub mod markdown;
+pub mod research_agent;
+pub mod tools;
+pub mod trade_news;
+pub mod options_chain;
+pub mod ohlc;
+pub mod backtest;
+pub mod journal;
+pub mod wispr;
+pub mod floating_windows;
+pub mod tray;
+pub mod notifications;
+pub mod recovery;
+pub mod onboarding;
+pub mod userscripts;
+pub mod filter_rules;
+pub mod palette;
+pub mod permissions;
+pub mod files;
+pub mod archive_files;
+pub mod verify;
+pub mod mcp;
+pub mod workflows;
+pub mod calendar;
+pub mod email;
+pub mod citations;
+pub mod similarity;
+pub mod research_multilingual;
+pub mod spellcheck;
+pub mod redaction;
+pub mod provider_health;
+pub mod warmup;
+pub mod coalesce;
+pub mod chunker;
+pub mod context_window;
+#[cfg(feature = "bindings")]
+pub mod bindings;
+pub mod approvals;
+pub mod agent_runs;
+pub mod llm_usage;
+pub mod summarizer;
+pub mod semantic_history;
+pub mod documents;
+pub mod ebook;
+pub mod tables;
+pub mod images;
+pub mod feeds;
+pub mod digest;
+pub mod fx;
+pub mod instant_answers;
 
 // Service modules
 pub mod services {
     pub mod ollama_service;
     pub mod global_shortcut_service;
+    pub mod hotword_service;
+    pub mod api_server;
+    pub mod supervisor;
+    pub mod binaries;
 }
@@ -0,0 +1,230 @@
+// LLM Response Cache - Exact-hash and near-duplicate lookup for (task, prompt) pairs
+// Backed by the llm_cache table; TTL + LRU size cap; disabled while privacy mode forbids caching
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use crate::db::Database;
+use crate::embeddings::{self, EmbeddingSource};
+
+// Tasks whose answer depends only on the gist of a short prompt, so a near-identical prompt may
+// reuse it. Anything that transforms the user's content (translate, summarize, tags, drafts,
+// sentiment) must match exactly: prompts differing in a number or past the embedded prefix
+// would otherwise get each other's output.
+const SEMANTIC_TASKS: &[&str] = &["research_plan"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct CacheConfig {
+    pub ttl_secs: i64,
+    pub max_entries: usize,
+    pub semantic: bool,                // Near-duplicate matching via embeddings (only for SEMANTIC_TASKS)
+    pub similarity_threshold: f32,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl_secs: 7 * 86_400,
+            max_entries: 5_000,
+            semantic: true,
+            similarity_threshold: 0.97,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+    pub enabled: bool,
+    pub entries: usize,
+    pub size_bytes: i64,
+    pub hits: u64,
+    pub semantic_hits: u64,
+    pub misses: u64,
+    pub hit_rate: f64,
+    pub by_task: Vec<(String, usize)>,
+    pub config: CacheConfig,
+}
+
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    pub response: String,
+    pub semantic: bool,                // Matched a similar (not identical) prompt
+}
+
+pub struct LlmCache {
+    db: Database,
+    config: CacheConfig,
+    enabled: AtomicBool,
+    hits: AtomicU64,
+    semantic_hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl LlmCache {
+    pub fn new(db: Database, config: CacheConfig) -> Self {
+        Self {
+            db,
+            config,
+            enabled: AtomicBool::new(true),
+            hits: AtomicU64::new(0),
+            semantic_hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    // Exact match only (cheap, synchronous)
+    pub fn get(&self, task: &str, model: &str, prompt: &str) -> Option<String> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let min_created_at = chrono::Utc::now().timestamp() - self.config.ttl_secs;
+        match self.db.llm_cache_get(&cache_key(task, model, prompt), min_created_at) {
+            Ok(Some(response)) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                Some(response)
+            }
+            Ok(None) => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+            Err(e) => {
+                tracing::warn!(target: "ai", "LLM cache: Lookup failed: {}", e);
+                None
+            }
+        }
+    }
+
+    // Exact match, then (if enabled) the most similar cached prompt for the same task/model
+    pub async fn lookup(&self, task: &str, model: &str, prompt: &str) -> Option<CachedResponse> {
+        if let Some(response) = self.get(task, model, prompt) {
+            return Some(CachedResponse { response, semantic: false });
+        }
+        if !self.is_enabled() || !self.semantic_for(task, prompt) {
+            return None;
+        }
+
+        let embedded = embeddings::embed(&[prompt.to_string()]).await;
+        // Hashed bag-of-words vectors ignore word order and short tokens; too coarse to trust
+        if embedded.source == EmbeddingSource::Hashed {
+            return None;
+        }
+        let vector = embedded.vectors.into_iter().next()?;
+        let min_created_at = chrono::Utc::now().timestamp() - self.config.ttl_secs;
+        let candidates = self
+            .db
+            .llm_cache_candidates(task, model, &embedded.model, min_created_at)
+            .ok()?;
+
+        let (key, response, similarity) = candidates
+            .into_iter()
+            .map(|(key, response, candidate)| {
+                let similarity = embeddings::cosine(&vector, &candidate);
+                (key, response, similarity)
+            })
+            .max_by(|a, b| a.2.total_cmp(&b.2))?;
+
+        if similarity < self.config.similarity_threshold {
+            return None;
+        }
+        let _ = self.db.llm_cache_touch(&key);
+        // A semantic hit was counted as an exact miss above; move it over
+        self.misses.fetch_sub(1, Ordering::Relaxed);
+        self.semantic_hits.fetch_add(1, Ordering::Relaxed);
+        Some(CachedResponse { response, semantic: true })
+    }
+
+    // Store a response; embeds the prompt when the task may be matched semantically
+    pub async fn store(&self, task: &str, model: &str, prompt: &str, response: &str) {
+        if !self.is_enabled() || response.trim().is_empty() {
+            return;
+        }
+        let embedding = if self.semantic_for(task, prompt) {
+            let embedded = embeddings::embed(&[prompt.to_string()]).await;
+            let vector = embedded.vectors.into_iter().next().filter(|_| embedded.source != EmbeddingSource::Hashed);
+            vector.map(|v| (embedded.model, v))
+        } else {
+            None
+        };
+        self.store_with_embedding(task, model, prompt, response, embedding);
+    }
+
+    // Synchronous store (no embedding; exact-match only)
+    pub fn store_exact(&self, task: &str, model: &str, prompt: &str, response: &str) {
+        if !self.is_enabled() || response.trim().is_empty() {
+            return;
+        }
+        self.store_with_embedding(task, model, prompt, response, None);
+    }
+
+    fn store_with_embedding(&self, task: &str, model: &str, prompt: &str, response: &str, embedding: Option<(String, Vec<f32>)>) {
+        let key = cache_key(task, model, prompt);
+        let result = self
+            .db
+            .llm_cache_put(&key, task, model, prompt, response, embedding.as_ref().map(|(m, v)| (m.as_str(), v.as_slice())))
+            .and_then(|_| {
+                let min_created_at = chrono::Utc::now().timestamp() - self.config.ttl_secs;
+                self.db.llm_cache_prune(min_created_at, self.config.max_entries)
+            });
+        if let Err(e) = result {
+            tracing::warn!(target: "ai", "LLM cache: Failed to store response: {}", e);
+        }
+    }
+
+    // Semantic matching is on, the task is known to tolerate it, and the whole prompt gets embedded
+    fn semantic_for(&self, task: &str, prompt: &str) -> bool {
+        self.config.semantic && SEMANTIC_TASKS.contains(&task) && prompt.trim().chars().count() <= embeddings::MAX_INPUT_CHARS
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let (entries, size_bytes, by_task) = self.db.llm_cache_summary().unwrap_or_default();
+        let hits = self.hits.load(Ordering::Relaxed);
+        let semantic_hits = self.semantic_hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let total = hits + semantic_hits + misses;
+
+        CacheStats {
+            enabled: self.is_enabled(),
+            entries,
+            size_bytes,
+            hits,
+            semantic_hits,
+            misses,
+            hit_rate: if total == 0 { 0.0 } else { (hits + semantic_hits) as f64 / total as f64 },
+            by_task,
+            config: self.config.clone(),
+        }
+    }
+
+    // Remove all entries (or one task's) and reset counters
+    pub fn clear(&self, task: Option<&str>) -> Result<usize, String> {
+        let removed = self.db.llm_cache_clear(task).map_err(|e| e.to_string())?;
+        if task.is_none() {
+            self.hits.store(0, Ordering::Relaxed);
+            self.semantic_hits.store(0, Ordering::Relaxed);
+            self.misses.store(0, Ordering::Relaxed);
+        }
+        Ok(removed)
+    }
+}
+
+fn cache_key(task: &str, model: &str, prompt: &str) -> String {
+    let mut hasher = Sha256::new();
+    for part in [task, model, prompt.trim()] {
+        hasher.update(part.as_bytes());
+        hasher.update([0u8]);
+    }
+    format!("{:x}", hasher.finalize())
+}
@@ -0,0 +1,436 @@
+// LLM Usage - Daily token and cost accounting per provider/model, with budgets
+// Local completions are counted here; the backend's cloud router reports its provider-reported usage and asks before each call
+
+use chrono::{Duration as ChronoDuration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Mutex, OnceLock};
+
+use crate::config::LlmBudgetConfig;
+use crate::db::Database;
+
+// Providers usage can be recorded and budgeted for
+pub const PROVIDERS: &[&str] = &["openai", "anthropic", "groq", "mistral", "huggingface", "ollama", "llamacpp"];
+// Providers that run on this machine: no cost, and nothing cheaper to downgrade to
+pub const LOCAL_PROVIDERS: &[&str] = &["ollama", "llamacpp"];
+// Budget key that caps spend across every provider
+pub const TOTAL: &str = "total";
+const MAX_REPORT_DAYS: u32 = 366;
+
+// List prices in USD per million tokens (input, output), matched by longest model-name prefix.
+// [llmBudget.prices] overrides these per "provider/model" or for a whole provider.
+const LIST_PRICES: &[(&str, &str, f64, f64)] = &[
+    ("openai", "gpt-4o-mini", 0.15, 0.60),
+    ("openai", "gpt-4o", 2.50, 10.00),
+    ("openai", "gpt-4.1-nano", 0.10, 0.40),
+    ("openai", "gpt-4.1-mini", 0.40, 1.60),
+    ("openai", "gpt-4.1", 2.00, 8.00),
+    ("openai", "o3-mini", 1.10, 4.40),
+    ("anthropic", "claude-3-haiku", 0.25, 1.25),
+    ("anthropic", "claude-3-5-haiku", 0.80, 4.00),
+    ("anthropic", "claude-3-5-sonnet", 3.00, 15.00),
+    ("anthropic", "claude-3-7-sonnet", 3.00, 15.00),
+    ("anthropic", "claude-sonnet-4", 3.00, 15.00),
+    ("anthropic", "claude-opus-4", 15.00, 75.00),
+    ("groq", "llama-3.1-8b-instant", 0.05, 0.08),
+    ("groq", "llama-3.3-70b-versatile", 0.59, 0.79),
+    ("mistral", "mistral-small", 0.20, 0.60),
+    ("mistral", "mistral-large", 2.00, 6.00),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ModelPrice {
+    pub input: f64,                    // USD per million prompt tokens
+    pub output: f64,                   // USD per million completion tokens
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetAction {
+    Block,
+    #[default]
+    Downgrade,                         // Route to the local model instead (cloud providers only)
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProviderBudget {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub daily_tokens: Option<u64>,     // Prompt + completion tokens per local day
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub daily_cost_usd: Option<f64>,
+    pub on_exceed: BudgetAction,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct UsageTotals {
+    pub requests: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub estimated_requests: u64,       // Requests whose token counts were estimated, not provider-reported
+    pub cost_usd: f64,
+}
+
+impl UsageTotals {
+    fn add(&mut self, other: &UsageTotals) {
+        self.requests += other.requests;
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.estimated_requests += other.estimated_requests;
+        self.cost_usd += other.cost_usd;
+    }
+
+    fn tokens(&self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+}
+
+// One stored day/provider/model row
+#[derive(Debug, Clone)]
+pub struct UsageRow {
+    pub day: String,                   // YYYY-MM-DD, local time
+    pub provider: String,
+    pub model: String,
+    pub totals: UsageTotals,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetDecision {
+    Allow,
+    Downgrade,
+    Block,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetCheck {
+    pub provider: String,
+    pub model: String,
+    pub decision: BudgetDecision,
+    pub reason: Option<String>,        // Which budget was exceeded
+    pub fallback_provider: Option<String>, // Set when downgrading
+    pub fallback_model: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ModelUsage {
+    pub model: String,
+    #[serde(flatten)]
+    pub totals: UsageTotals,
+    pub priced: bool,                  // False when no price is known; cost_usd is then 0
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderUsage {
+    pub provider: String,
+    #[serde(flatten)]
+    pub totals: UsageTotals,
+    pub models: Vec<ModelUsage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS), ts(rename = "LlmDayUsage"))]
+#[serde(rename_all = "camelCase")]
+pub struct DayUsage {
+    pub day: String,
+    #[serde(flatten)]
+    pub totals: UsageTotals,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetStatus {
+    pub provider: String,              // Or "total"
+    pub used_tokens: u64,
+    pub used_cost_usd: f64,
+    #[serde(flatten)]
+    pub budget: ProviderBudget,
+    pub exceeded: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS), ts(rename = "LlmUsageReport"))]
+#[serde(rename_all = "camelCase")]
+pub struct UsageReport {
+    pub from: String,
+    pub to: String,
+    pub totals: UsageTotals,
+    pub providers: Vec<ProviderUsage>, // Most expensive first, then most tokens
+    pub days: Vec<DayUsage>,           // Oldest first; days without usage are included as zeroes
+    pub budgets: Vec<BudgetStatus>,    // Today's standing against each configured budget
+}
+
+// Today's totals by (provider, model)
+type DayTotals = BTreeMap<(String, String), UsageTotals>;
+
+struct Ledger {
+    db: OnceLock<Database>,
+    today: Mutex<(String, DayTotals)>,
+}
+
+fn ledger() -> &'static Ledger {
+    static LEDGER: OnceLock<Ledger> = OnceLock::new();
+    LEDGER.get_or_init(|| Ledger { db: OnceLock::new(), today: Mutex::new((day_key(today()), BTreeMap::new())) })
+}
+
+// Load today's totals so budgets survive a restart
+pub fn init(db: Database) {
+    let day = day_key(today());
+    match db.llm_usage_between(&day, &day) {
+        Ok(rows) => {
+            let mut current = ledger().today.lock().unwrap();
+            *current = (day, rows.into_iter().map(|r| ((r.provider, r.model), r.totals)).collect());
+        }
+        Err(e) => tracing::warn!(target: "db", "LLM usage: Failed to load today's totals: {}", e),
+    }
+    let _ = ledger().db.set(db);
+}
+
+// Add one completion's tokens. `reported` is false when the counts were estimated from the text.
+// Returns the estimated cost in USD (0 when the model has no known price).
+pub fn record(provider: &str, model: &str, prompt_tokens: u64, completion_tokens: u64, reported: bool) -> f64 {
+    let provider = provider.trim().to_lowercase();
+    let model = model.trim().to_string();
+    let config = crate::config::current().llm_budget;
+    let cost = price(&config, &provider, &model)
+        .map(|p| (prompt_tokens as f64 * p.input + completion_tokens as f64 * p.output) / 1_000_000.0)
+        .unwrap_or(0.0);
+    let sample = UsageTotals {
+        requests: 1,
+        prompt_tokens,
+        completion_tokens,
+        estimated_requests: u64::from(!reported),
+        cost_usd: cost,
+    };
+
+    let day = day_key(today());
+    {
+        let mut current = ledger().today.lock().unwrap();
+        if current.0 != day {
+            *current = (day.clone(), BTreeMap::new());
+        }
+        current.1.entry((provider.clone(), model.clone())).or_default().add(&sample);
+    }
+    if let Some(db) = ledger().db.get() {
+        let row = UsageRow { day, provider, model, totals: sample };
+        if let Err(e) = db.llm_usage_add(&row) {
+            tracing::warn!(target: "db", "LLM usage: Failed to store usage: {}", e);
+        }
+    }
+    cost
+}
+
+// Whether a call to this provider fits today's budgets (the provider's own, then "total")
+pub fn check(provider: &str, model: &str) -> BudgetCheck {
+    let provider = provider.trim().to_lowercase();
+    let config = crate::config::current().llm_budget;
+    let mut result = BudgetCheck {
+        provider: provider.clone(),
+        model: model.to_string(),
+        decision: BudgetDecision::Allow,
+        reason: None,
+        fallback_provider: None,
+        fallback_model: None,
+    };
+    let local = LOCAL_PROVIDERS.contains(&provider.as_str());
+    let today = today_by_provider();
+
+    let mut exceeded = None;
+    if let Some(budget) = config.budgets.get(&provider) {
+        let used = today.get(&provider).cloned().unwrap_or_default();
+        if let Some(reason) = over_budget(&provider, budget, &used) {
+            exceeded = Some((budget.on_exceed, reason));
+        }
+    }
+    // The total cap only constrains providers that cost money
+    if exceeded.is_none() && !local {
+        if let Some(budget) = config.budgets.get(TOTAL) {
+            let mut used = UsageTotals::default();
+            today.values().for_each(|t| used.add(t));
+            if let Some(reason) = over_budget(TOTAL, budget, &used) {
+                exceeded = Some((budget.on_exceed, reason));
+            }
+        }
+    }
+
+    let Some((action, reason)) = exceeded else { return result };
+    result.reason = Some(reason);
+    let (fallback_provider, fallback_model) = local_fallback();
+    let fallback_ok = config.budgets.get(&fallback_provider).is_none_or(|budget| {
+        let used = today.get(&fallback_provider).cloned().unwrap_or_default();
+        over_budget(&fallback_provider, budget, &used).is_none()
+    });
+    if action == BudgetAction::Downgrade && !local && fallback_ok {
+        result.decision = BudgetDecision::Downgrade;
+        result.fallback_provider = Some(fallback_provider);
+        result.fallback_model = Some(fallback_model);
+    } else {
+        result.decision = BudgetDecision::Block;
+    }
+    result
+}
+
+// Per-day, per-provider, and per-model totals for the last `days` days (including today)
+pub fn report(days: u32) -> Result<UsageReport, UsageError> {
+    let days = days.clamp(1, MAX_REPORT_DAYS);
+    let to = today();
+    let from = to - ChronoDuration::days(i64::from(days) - 1);
+    let (from_key, to_key) = (day_key(from), day_key(to));
+    let rows = match ledger().db.get() {
+        Some(db) => db.llm_usage_between(&from_key, &to_key).map_err(|e| UsageError::Storage(e.to_string()))?,
+        // No database yet: today's in-memory totals are all there is
+        None => {
+            let current = ledger().today.lock().unwrap();
+            current
+                .1
+                .iter()
+                .map(|((provider, model), totals)| UsageRow {
+                    day: current.0.clone(),
+                    provider: provider.clone(),
+                    model: model.clone(),
+                    totals: totals.clone(),
+                })
+                .collect()
+        }
+    };
+
+    let config = crate::config::current().llm_budget;
+    let mut totals = UsageTotals::default();
+    let mut by_day: BTreeMap<String, UsageTotals> = BTreeMap::new();
+    let mut by_provider: BTreeMap<String, BTreeMap<String, UsageTotals>> = BTreeMap::new();
+    for row in &rows {
+        totals.add(&row.totals);
+        by_day.entry(row.day.clone()).or_default().add(&row.totals);
+        by_provider.entry(row.provider.clone()).or_default().entry(row.model.clone()).or_default().add(&row.totals);
+    }
+
+    let mut providers: Vec<ProviderUsage> = by_provider
+        .into_iter()
+        .map(|(provider, models)| {
+            let mut provider_totals = UsageTotals::default();
+            let mut models: Vec<ModelUsage> = models
+                .into_iter()
+                .map(|(model, totals)| {
+                    provider_totals.add(&totals);
+                    let priced = price(&config, &provider, &model).is_some();
+                    ModelUsage { model, totals, priced }
+                })
+                .collect();
+            models.sort_by(|a, b| b.totals.cost_usd.total_cmp(&a.totals.cost_usd).then(b.totals.tokens().cmp(&a.totals.tokens())));
+            ProviderUsage { provider, totals: provider_totals, models }
+        })
+        .collect();
+    providers.sort_by(|a, b| b.totals.cost_usd.total_cmp(&a.totals.cost_usd).then(b.totals.tokens().cmp(&a.totals.tokens())));
+
+    let days = (0..days)
+        .map(|offset| {
+            let day = day_key(from + ChronoDuration::days(i64::from(offset)));
+            let totals = by_day.remove(&day).unwrap_or_default();
+            DayUsage { day, totals }
+        })
+        .collect();
+
+    let today = today_by_provider();
+    let budgets = config
+        .budgets
+        .iter()
+        .map(|(provider, budget)| {
+            let used = if provider == TOTAL {
+                let mut all = UsageTotals::default();
+                today.values().for_each(|t| all.add(t));
+                all
+            } else {
+                today.get(provider).cloned().unwrap_or_default()
+            };
+            BudgetStatus {
+                provider: provider.clone(),
+                used_tokens: used.tokens(),
+                used_cost_usd: used.cost_usd,
+                budget: budget.clone(),
+                exceeded: over_budget(provider, budget, &used).is_some(),
+            }
+        })
+        .collect();
+
+    Ok(UsageReport { from: from_key, to: to_key, totals, providers, days, budgets })
+}
+
+// Configured price first ("provider/model", then "provider"), then the list price by longest model prefix
+pub fn price(config: &LlmBudgetConfig, provider: &str, model: &str) -> Option<ModelPrice> {
+    if LOCAL_PROVIDERS.contains(&provider) {
+        return Some(ModelPrice { input: 0.0, output: 0.0 });
+    }
+    if let Some(price) = config.prices.get(&format!("{}/{}", provider, model)).or_else(|| config.prices.get(provider)) {
+        return Some(*price);
+    }
+    let model = model.to_lowercase();
+    LIST_PRICES
+        .iter()
+        .filter(|(p, prefix, _, _)| *p == provider && model.starts_with(prefix))
+        .max_by_key(|(_, prefix, _, _)| prefix.len())
+        .map(|(_, _, input, output)| ModelPrice { input: *input, output: *output })
+}
+
+fn over_budget(provider: &str, budget: &ProviderBudget, used: &UsageTotals) -> Option<String> {
+    if let Some(limit) = budget.daily_tokens.filter(|limit| used.tokens() >= *limit) {
+        return Some(format!("{} used {} of {} tokens today", provider, used.tokens(), limit));
+    }
+    if let Some(limit) = budget.daily_cost_usd.filter(|limit| used.cost_usd >= *limit) {
+        return Some(format!("{} spent ${:.2} of ${:.2} today", provider, used.cost_usd, limit));
+    }
+    None
+}
+
+fn today_by_provider() -> BTreeMap<String, UsageTotals> {
+    let day = day_key(today());
+    let current = ledger().today.lock().unwrap();
+    let mut by_provider: BTreeMap<String, UsageTotals> = BTreeMap::new();
+    if current.0 == day {
+        for ((provider, _), totals) in &current.1 {
+            by_provider.entry(provider.clone()).or_default().add(totals);
+        }
+    }
+    by_provider
+}
+
+// The local model budgets fall back to
+fn local_fallback() -> (String, String) {
+    ("ollama".to_string(), crate::config::current().ai.chat_model())
+}
+
+fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+fn day_key(day: NaiveDate) -> String {
+    day.format("%Y-%m-%d").to_string()
+}
+
+#[derive(Debug, Clone)]
+pub enum UsageError {
+    Storage(String),
+}
+
+impl std::fmt::Display for UsageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UsageError::Storage(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for UsageError {}
@@ -0,0 +1,129 @@
+// Local Embeddings - all-MiniLM-L6-v2 run in-process with candle (feature "local-embeddings")
+// Used when Ollama is unreachable; the model (~90 MB) is fetched into the Hugging Face cache on first use,
+// through http::Client so local-only mode, the proxy and the network audit apply
+
+use candle_core::{Device, Tensor};
+use candle_nn::VarBuilder;
+use candle_transformers::models::bert::{BertModel, Config, DTYPE};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokenizers::{PaddingParams, Tokenizer, TruncationParams};
+
+use crate::embeddings::EmbeddingError;
+
+pub const MODEL: &str = "all-MiniLM-L6-v2";
+const REPO: &str = "sentence-transformers/all-MiniLM-L6-v2";
+const FILES: [&str; 3] = ["config.json", "tokenizer.json", "model.safetensors"];
+// Snapshot name used when the cache has no ref for the repo yet
+const SNAPSHOT: &str = "omnibrowser";
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(600);
+// The model was trained on sequences up to this many tokens
+const MAX_TOKENS: usize = 256;
+const BATCH_SIZE: usize = 32;
+
+struct Loaded {
+    model: BertModel,
+    tokenizer: Tokenizer,
+}
+
+// Loaded once; a failed load is remembered so every call doesn't retry the download
+fn loaded() -> &'static tokio::sync::OnceCell<Result<Loaded, String>> {
+    static LOADED: OnceLock<tokio::sync::OnceCell<Result<Loaded, String>>> = OnceLock::new();
+    LOADED.get_or_init(tokio::sync::OnceCell::new)
+}
+
+// Mean-pooled, L2-normalized sentence vectors in input order
+pub async fn embed(texts: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+    let loaded = loaded().get_or_init(load).await.as_ref().map_err(|e| EmbeddingError::Unavailable(e.clone()))?;
+    let mut vectors = Vec::with_capacity(texts.len());
+    for batch in texts.chunks(BATCH_SIZE) {
+        vectors.extend(embed_batch(loaded, batch).map_err(|e| EmbeddingError::InvalidResponse(e.to_string()))?);
+    }
+    Ok(vectors)
+}
+
+async fn load() -> Result<Loaded, String> {
+    let [config, tokenizer, weights] = fetch_model().await?;
+    let config: Config = serde_json::from_str(&std::fs::read_to_string(config).map_err(|e| e.to_string())?)
+        .map_err(|e| e.to_string())?;
+    let mut tokenizer = Tokenizer::from_file(tokenizer).map_err(|e| e.to_string())?;
+    tokenizer.with_padding(Some(PaddingParams::default()));
+    tokenizer
+        .with_truncation(Some(TruncationParams { max_length: MAX_TOKENS, ..Default::default() }))
+        .map_err(|e| e.to_string())?;
+    // Safety: the weights file is memory-mapped read-only and not modified while loaded
+    let vb = unsafe { VarBuilder::from_mmaped_safetensors(&[weights], DTYPE, &Device::Cpu) }.map_err(|e| e.to_string())?;
+    let model = BertModel::load(vb, &config).map_err(|e| e.to_string())?;
+    tracing::info!(target: "ai", "Embeddings: Loaded local model {}", MODEL);
+    Ok(Loaded { model, tokenizer })
+}
+
+// Cached files, downloading the missing ones into the Hugging Face cache layout
+// (models--<org>--<name>/refs/main + snapshots/<ref>/<file>) so other tools share them
+async fn fetch_model() -> Result<[PathBuf; 3], String> {
+    let cache = hf_hub::Cache::default();
+    let cached = cache.model(REPO.to_string());
+    if let [Some(config), Some(tokenizer), Some(weights)] = FILES.map(|file| cached.get(file)) {
+        return Ok([config, tokenizer, weights]);
+    }
+
+    let repo_dir = cache.path().join(format!("models--{}", REPO.replace('/', "--")));
+    let ref_path = repo_dir.join("refs").join("main");
+    let snapshot = match std::fs::read_to_string(&ref_path) {
+        Ok(commit) if !commit.trim().is_empty() => commit.trim().to_string(),
+        _ => {
+            std::fs::create_dir_all(repo_dir.join("refs")).map_err(|e| e.to_string())?;
+            std::fs::write(&ref_path, SNAPSHOT).map_err(|e| e.to_string())?;
+            SNAPSHOT.to_string()
+        }
+    };
+    let dir = repo_dir.join("snapshots").join(snapshot);
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let client = crate::http::Client::new(DOWNLOAD_TIMEOUT).with_retries(1).with_purpose("model-download");
+    for file in FILES {
+        let path = dir.join(file);
+        if path.exists() {
+            continue;
+        }
+        tracing::info!(target: "ai", "Embeddings: Downloading {} for {}", file, MODEL);
+        download(&client, &format!("https://huggingface.co/{}/resolve/main/{}", REPO, file), &path).await?;
+    }
+    Ok(FILES.map(|file| dir.join(file)))
+}
+
+// Stream to a temporary file and rename, so an interrupted download isn't mistaken for a cached one
+async fn download(client: &crate::http::Client, url: &str, path: &std::path::Path) -> Result<(), String> {
+    use std::io::Write;
+
+    let mut response = client.send(client.get(url)).await.map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("{}: HTTP {}", url, response.status().as_u16()));
+    }
+    let partial = path.with_extension("part");
+    let mut out = std::fs::File::create(&partial).map_err(|e| e.to_string())?;
+    while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+        out.write_all(&chunk).map_err(|e| e.to_string())?;
+    }
+    out.sync_all().map_err(|e| e.to_string())?;
+    std::fs::rename(&partial, path).map_err(|e| e.to_string())
+}
+
+fn embed_batch(loaded: &Loaded, texts: &[String]) -> Result<Vec<Vec<f32>>, Box<dyn std::error::Error + Send + Sync>> {
+    let encodings = loaded.tokenizer.encode_batch(texts.to_vec(), true)?;
+    let device = &loaded.model.device;
+    let ids = encodings.iter().map(|e| Tensor::new(e.get_ids(), device)).collect::<Result<Vec<_>, _>>()?;
+    let mask = encodings.iter().map(|e| Tensor::new(e.get_attention_mask(), device)).collect::<Result<Vec<_>, _>>()?;
+    let ids = Tensor::stack(&ids, 0)?;
+    let mask = Tensor::stack(&mask, 0)?;
+    let token_types = ids.zeros_like()?;
+
+    let hidden = loaded.model.forward(&ids, &token_types, Some(&mask))?;
+    // Mean over real tokens only (padding masked out)
+    let mask = mask.to_dtype(DTYPE)?.unsqueeze(2)?;
+    let summed = hidden.broadcast_mul(&mask)?.sum(1)?;
+    let counts = mask.sum(1)?;
+    let pooled = summed.broadcast_div(&counts)?;
+    Ok(pooled.to_vec2::<f32>()?.into_iter().map(crate::embeddings::normalize).collect())
+}
@@ -0,0 +1,588 @@
+// Local Index - Cached pages, notes, bookmarks, research sessions, documents, and images mirrored into Meilisearch
+// Writes are queued and pushed in the background; a full resync runs whenever Meilisearch (re)appears
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+use crate::db::{Database, PageCache};
+use crate::documents::StoredDocument;
+use crate::http;
+use crate::images::ImageRecord;
+use crate::notes::Note;
+use crate::research::ResearchSession;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+// How often to retry a full sync while Meilisearch is down or behind
+const RESYNC_INTERVAL: Duration = Duration::from_secs(30);
+const BATCH_SIZE: usize = 200;
+// Keeps the index small; the head of a page is what instant search needs
+const MAX_CONTENT_CHARS: usize = 20_000;
+const MAX_SYNC_ROWS: usize = 100_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum IndexKind {
+    Pages,
+    Notes,
+    Bookmarks,
+    Sessions,
+    Documents,
+    Images,
+}
+
+impl IndexKind {
+    pub const ALL: [IndexKind; 6] = [
+        IndexKind::Pages,
+        IndexKind::Notes,
+        IndexKind::Bookmarks,
+        IndexKind::Sessions,
+        IndexKind::Documents,
+        IndexKind::Images,
+    ];
+
+    pub fn uid(&self) -> &'static str {
+        match self {
+            IndexKind::Pages => "pages",
+            IndexKind::Notes => "notes",
+            IndexKind::Bookmarks => "bookmarks",
+            IndexKind::Sessions => "sessions",
+            IndexKind::Documents => "documents",
+            IndexKind::Images => "images",
+        }
+    }
+
+    pub fn parse(uid: &str) -> Result<Self, LocalIndexError> {
+        Self::ALL
+            .into_iter()
+            .find(|k| k.uid() == uid.trim().to_lowercase())
+            .ok_or_else(|| LocalIndexError::UnknownIndex(uid.to_string()))
+    }
+}
+
+// Same shape in every index so one query can span them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexDocument {
+    pub id: String,                    // Hash of ref_id; Meilisearch ids allow only [a-zA-Z0-9_-]
+    pub ref_id: String,                // Page URL, image path, or note/bookmark/session/document id
+    pub kind: IndexKind,
+    pub url: Option<String>,
+    pub title: String,
+    pub content: String,
+    pub language: Option<String>,
+    pub tags: Vec<String>,
+    pub updated_at: i64,               // Unix seconds
+}
+
+impl IndexDocument {
+    pub fn page(page: &PageCache) -> Self {
+        Self {
+            id: document_id(&page.url),
+            ref_id: page.url.clone(),
+            kind: IndexKind::Pages,
+            url: Some(page.url.clone()),
+            title: page.title.clone(),
+            content: truncate(&page.content),
+            language: page.language.clone(),
+            tags: Vec::new(),
+            updated_at: page.cached_at,
+        }
+    }
+
+    pub fn note(note: &Note) -> Self {
+        Self {
+            id: document_id(&note.id),
+            ref_id: note.id.clone(),
+            kind: IndexKind::Notes,
+            url: note.url.clone(),
+            title: note.title.clone().unwrap_or_default(),
+            content: truncate(&note.content),
+            language: Some(note.language.clone()),
+            tags: note.tags.clone(),
+            updated_at: note.updated_at,
+        }
+    }
+
+    pub fn bookmark(id: &str, url: &str, title: &str, created_at: i64, folder: Option<&str>, tags: Option<&str>, description: Option<&str>) -> Self {
+        let mut tags: Vec<String> = tags
+            .unwrap_or_default()
+            .split(',')
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect();
+        if let Some(folder) = folder.filter(|f| !f.trim().is_empty()) {
+            tags.push(format!("folder:{}", folder.trim()));
+        }
+        Self {
+            id: document_id(id),
+            ref_id: id.to_string(),
+            kind: IndexKind::Bookmarks,
+            url: Some(url.to_string()),
+            title: title.to_string(),
+            content: description.unwrap_or_default().to_string(),
+            language: None,
+            tags,
+            updated_at: created_at,
+        }
+    }
+
+    // Title, query, keywords, summaries, and notes; tabs only contribute their titles
+    pub fn session(session: &ResearchSession) -> Self {
+        let mut parts: Vec<&str> = Vec::new();
+        parts.extend(session.metadata.query.as_deref());
+        parts.extend(session.summaries.iter().map(|s| s.summary.as_str()));
+        parts.extend(session.notes.iter().map(|n| n.content.as_str()));
+        parts.extend(session.highlights.iter().map(|h| h.text.as_str()));
+        parts.extend(session.tabs.iter().map(|t| t.title.as_str()));
+        Self {
+            id: document_id(&session.id),
+            ref_id: session.id.clone(),
+            kind: IndexKind::Sessions,
+            url: None,
+            title: session.title.clone(),
+            content: truncate(&parts.join("\n")),
+            language: None,
+            tags: session.metadata.keywords.clone(),
+            // Sessions carry frontend millis
+            updated_at: session.updated_at / 1000,
+        }
+    }
+
+    // Opened files processed for document Q&A; `text` is their chunk text in order
+    pub fn document(document: &StoredDocument, text: &str) -> Self {
+        Self {
+            id: document_id(&document.doc_id),
+            ref_id: document.doc_id.clone(),
+            kind: IndexKind::Documents,
+            url: Some(document.path.clone()),
+            title: document.title.clone(),
+            content: truncate(text),
+            language: None,
+            tags: vec![document.kind.as_str().to_string()],
+            updated_at: document.indexed_at,
+        }
+    }
+
+    // Inspected/captioned local images; searchable by caption and recognized text
+    pub fn image(image: &ImageRecord) -> Self {
+        Self {
+            id: document_id(&image.path),
+            ref_id: image.path.clone(),
+            kind: IndexKind::Images,
+            url: Some(image.path.clone()),
+            title: image.name.clone(),
+            content: truncate(&image.searchable_text()),
+            language: None,
+            tags: image.format.iter().chain(image.camera.iter()).cloned().collect(),
+            updated_at: image.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct LocalSearchFilters {
+    pub kinds: Option<Vec<String>>,    // Index uids; all when unset
+    pub language: Option<String>,
+    pub tags: Option<Vec<String>>,     // Any of
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct LocalHit {
+    pub kind: IndexKind,
+    pub ref_id: String,
+    pub url: Option<String>,
+    pub title: String,
+    pub snippet: String,               // Matches wrapped in <mark>
+    pub tags: Vec<String>,
+    pub updated_at: i64,
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct LocalSearchResponse {
+    pub query: String,
+    pub hits: Vec<LocalHit>,
+    pub took_ms: u64,
+}
+
+enum IndexOp {
+    Upsert(IndexKind, Vec<IndexDocument>),
+    Delete(IndexKind, Vec<String>),
+    Resync,
+}
+
+#[derive(Clone)]
+pub struct LocalIndex {
+    http: http::Client,
+    queue: mpsc::UnboundedSender<IndexOp>,
+}
+
+impl LocalIndex {
+    // Starts the background sync worker; the first full sync runs once Meilisearch answers
+    pub fn start(db: Database) -> Self {
+        let (queue, rx) = mpsc::unbounded_channel();
+        let index = Self { http: client(), queue };
+        tauri::async_runtime::spawn(worker(db, rx));
+        index
+    }
+
+    pub fn page_saved(&self, page: &PageCache) {
+        self.send(IndexOp::Upsert(IndexKind::Pages, vec![IndexDocument::page(page)]));
+    }
+
+    pub fn note_saved(&self, note: &Note) {
+        self.send(IndexOp::Upsert(IndexKind::Notes, vec![IndexDocument::note(note)]));
+    }
+
+    pub fn bookmark_saved(&self, document: IndexDocument) {
+        self.send(IndexOp::Upsert(IndexKind::Bookmarks, vec![document]));
+    }
+
+    pub fn session_saved(&self, session: &ResearchSession) {
+        self.send(IndexOp::Upsert(IndexKind::Sessions, vec![IndexDocument::session(session)]));
+    }
+
+    pub fn document_saved(&self, document: IndexDocument) {
+        self.send(IndexOp::Upsert(IndexKind::Documents, vec![document]));
+    }
+
+    pub fn image_saved(&self, document: IndexDocument) {
+        self.send(IndexOp::Upsert(IndexKind::Images, vec![document]));
+    }
+
+    // ref_id: page URL, image path, or note/bookmark/session/document id
+    pub fn removed(&self, kind: IndexKind, ref_id: &str) {
+        self.send(IndexOp::Delete(kind, vec![document_id(ref_id)]));
+    }
+
+    // Rebuild every index from the database (e.g. after a profile switch)
+    pub fn resync(&self) {
+        self.send(IndexOp::Resync);
+    }
+
+    fn send(&self, op: IndexOp) {
+        // Only fails once the worker is gone during shutdown
+        let _ = self.queue.send(op);
+    }
+
+    // Typo-tolerant search across the selected indexes, best matches first
+    pub async fn search(&self, query: &str, filters: &LocalSearchFilters) -> Result<LocalSearchResponse, LocalIndexError> {
+        let started = Instant::now();
+        let kinds = match &filters.kinds {
+            Some(uids) if !uids.is_empty() => uids.iter().map(|u| IndexKind::parse(u)).collect::<Result<Vec<_>, _>>()?,
+            _ => IndexKind::ALL.to_vec(),
+        };
+        let limit = filters.limit.unwrap_or(20).clamp(1, 100);
+        let filter = filter_expression(filters);
+
+        let queries: Vec<serde_json::Value> = kinds
+            .iter()
+            .map(|kind| {
+                serde_json::json!({
+                    "indexUid": kind.uid(),
+                    "q": query,
+                    "limit": limit,
+                    "filter": filter,
+                    "attributesToCrop": ["content"],
+                    "cropLength": 24,
+                    "attributesToHighlight": ["title", "content"],
+                    "highlightPreTag": "<mark>",
+                    "highlightPostTag": "</mark>",
+                    "showRankingScore": true,
+                })
+            })
+            .collect();
+        let request = self.http.post(endpoint("/multi-search")).json(&serde_json::json!({ "queries": queries }));
+        let response = self.http.send(request).await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(LocalIndexError::Request(format!("HTTP {}: {}", status.as_u16(), body)));
+        }
+        let results: MultiSearchResponse = response.json().await.map_err(|e| LocalIndexError::Request(e.to_string()))?;
+
+        let mut hits: Vec<LocalHit> = results
+            .results
+            .into_iter()
+            .flat_map(|r| r.hits)
+            .map(|hit| {
+                let formatted = hit.formatted.unwrap_or_default();
+                LocalHit {
+                    kind: hit.document.kind,
+                    ref_id: hit.document.ref_id,
+                    url: hit.document.url,
+                    title: formatted.title.unwrap_or(hit.document.title),
+                    snippet: formatted.content.unwrap_or_default(),
+                    tags: hit.document.tags,
+                    updated_at: hit.document.updated_at,
+                    score: hit.ranking_score.unwrap_or(0.0),
+                }
+            })
+            .collect();
+        hits.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        hits.truncate(limit);
+
+        Ok(LocalSearchResponse {
+            query: query.to_string(),
+            hits,
+            took_ms: started.elapsed().as_millis() as u64,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct MultiSearchResponse {
+    results: Vec<IndexResults>,
+}
+
+#[derive(Deserialize)]
+struct IndexResults {
+    hits: Vec<SearchHit>,
+}
+
+#[derive(Deserialize)]
+struct SearchHit {
+    #[serde(flatten)]
+    document: IndexDocument,
+    #[serde(rename = "_formatted")]
+    formatted: Option<FormattedHit>,
+    #[serde(rename = "_rankingScore")]
+    ranking_score: Option<f64>,
+}
+
+#[derive(Deserialize, Default)]
+struct FormattedHit {
+    title: Option<String>,
+    content: Option<String>,
+}
+
+// ============================================================================
+// SYNC WORKER
+// ============================================================================
+
+async fn worker(db: Database, mut rx: mpsc::UnboundedReceiver<IndexOp>) {
+    let http = client();
+    // Anything written while Meilisearch was unreachable is only recovered by a full sync
+    let mut dirty = true;
+    loop {
+        let op = if dirty {
+            tokio::select! {
+                op = rx.recv() => op,
+                _ = tokio::time::sleep(RESYNC_INTERVAL) => {
+                    // Background retries wait out focus mode and quiet modes; new writes still trigger a sync
+                    if crate::modes::background_paused() {
+                        continue;
+                    }
+                    Some(IndexOp::Resync)
+                }
+            }
+        } else {
+            rx.recv().await
+        };
+        let Some(op) = op else { break };
+
+        if dirty || matches!(op, IndexOp::Resync) {
+            // A full sync covers whatever else is already queued
+            while rx.try_recv().is_ok() {}
+            if !healthy(&http).await {
+                dirty = true;
+                continue;
+            }
+            match full_sync(&http, &db).await {
+                Ok(count) => {
+                    tracing::info!(target: "services", "Local index: Synced {} documents to Meilisearch", count);
+                    dirty = false;
+                }
+                Err(e) => {
+                    tracing::warn!(target: "services", "Local index: Full sync failed: {}", e);
+                    dirty = true;
+                }
+            }
+            continue;
+        }
+
+        let result = match op {
+            IndexOp::Upsert(kind, documents) => add_documents(&http, kind, &documents).await,
+            IndexOp::Delete(kind, ids) => delete_documents(&http, kind, &ids).await,
+            IndexOp::Resync => Ok(()),
+        };
+        if let Err(e) = result {
+            tracing::debug!(target: "services", "Local index: Update failed, will resync: {}", e);
+            dirty = true;
+        }
+    }
+}
+
+async fn healthy(http: &http::Client) -> bool {
+    match http.send(http.get(endpoint("/health"))).await {
+        Ok(response) => response.status().is_success(),
+        Err(_) => false,
+    }
+}
+
+// Recreate every index from the database; returns the number of documents pushed
+async fn full_sync(http: &http::Client, db: &Database) -> Result<usize, LocalIndexError> {
+    let db = db.clone();
+    let documents = tauri::async_runtime::spawn_blocking(move || collect_documents(&db))
+        .await
+        .map_err(|e| LocalIndexError::Database(e.to_string()))??;
+
+    let mut total = 0;
+    for (kind, docs) in documents {
+        configure(http, kind).await?;
+        // Drop documents deleted while we weren't listening; Meilisearch applies tasks in order
+        check(http.send(http.request(reqwest::Method::DELETE, endpoint(&format!("/indexes/{}/documents", kind.uid())))).await?).await?;
+        for batch in docs.chunks(BATCH_SIZE) {
+            add_documents(http, kind, batch).await?;
+        }
+        total += docs.len();
+    }
+    Ok(total)
+}
+
+fn collect_documents(db: &Database) -> Result<Vec<(IndexKind, Vec<IndexDocument>)>, LocalIndexError> {
+    let err = |e: rusqlite::Error| LocalIndexError::Database(e.to_string());
+    let pages = db.list_pages(MAX_SYNC_ROWS).map_err(err)?.iter().map(IndexDocument::page).collect();
+    let notes = db.list_notes(None, MAX_SYNC_ROWS).map_err(err)?.iter().map(IndexDocument::note).collect();
+    let bookmarks = db
+        .get_bookmarks()
+        .map_err(err)?
+        .into_iter()
+        .map(|(id, url, title, created_at, folder, tags, description)| {
+            IndexDocument::bookmark(&id, &url, &title, created_at, folder.as_deref(), tags.as_deref(), description.as_deref())
+        })
+        .collect();
+    let sessions = db.list_research_sessions(MAX_SYNC_ROWS).map_err(err)?.iter().map(IndexDocument::session).collect();
+    let documents = db
+        .list_documents(MAX_SYNC_ROWS)
+        .map_err(err)?
+        .iter()
+        .map(|d| Ok(IndexDocument::document(d, &db.document_text(&d.doc_id).map_err(err)?)))
+        .collect::<Result<_, LocalIndexError>>()?;
+    let images = db.list_images(MAX_SYNC_ROWS).map_err(err)?.iter().map(IndexDocument::image).collect();
+    Ok(vec![
+        (IndexKind::Pages, pages),
+        (IndexKind::Notes, notes),
+        (IndexKind::Bookmarks, bookmarks),
+        (IndexKind::Sessions, sessions),
+        (IndexKind::Documents, documents),
+        (IndexKind::Images, images),
+    ])
+}
+
+// Creates the index on first use
+async fn configure(http: &http::Client, kind: IndexKind) -> Result<(), LocalIndexError> {
+    let settings = serde_json::json!({
+        "searchableAttributes": ["title", "content", "tags", "url"],
+        "filterableAttributes": ["kind", "language", "tags", "updatedAt"],
+        "sortableAttributes": ["updatedAt"],
+    });
+    let request = http.request(reqwest::Method::PATCH, endpoint(&format!("/indexes/{}/settings", kind.uid()))).json(&settings);
+    check(http.send(request).await?).await
+}
+
+async fn add_documents(http: &http::Client, kind: IndexKind, documents: &[IndexDocument]) -> Result<(), LocalIndexError> {
+    let request = http
+        .post(endpoint(&format!("/indexes/{}/documents?primaryKey=id", kind.uid())))
+        .json(documents);
+    check(http.send(request).await?).await
+}
+
+async fn delete_documents(http: &http::Client, kind: IndexKind, ids: &[String]) -> Result<(), LocalIndexError> {
+    let request = http
+        .post(endpoint(&format!("/indexes/{}/documents/delete-batch", kind.uid())))
+        .json(ids);
+    check(http.send(request).await?).await
+}
+
+// Writes are accepted as tasks (202); anything else is an error
+async fn check(response: reqwest::Response) -> Result<(), LocalIndexError> {
+    if response.status().is_success() {
+        return Ok(());
+    }
+    let status = response.status().as_u16();
+    let body = response.text().await.unwrap_or_default();
+    Err(LocalIndexError::Request(format!("HTTP {}: {}", status, body)))
+}
+
+// Meilisearch filter syntax; values are quoted so user input can't change the expression
+fn filter_expression(filters: &LocalSearchFilters) -> Option<String> {
+    let quote = |v: &str| format!("\"{}\"", v.replace('\\', "\\\\").replace('"', "\\\""));
+    let mut clauses = Vec::new();
+    if let Some(language) = filters.language.as_deref().filter(|l| !l.is_empty()) {
+        clauses.push(format!("language = {}", quote(language)));
+    }
+    if let Some(tags) = filters.tags.as_ref().filter(|t| !t.is_empty()) {
+        let tags: Vec<String> = tags.iter().map(|t| quote(t)).collect();
+        clauses.push(format!("tags IN [{}]", tags.join(", ")));
+    }
+    if let Some(since) = filters.since {
+        clauses.push(format!("updatedAt >= {}", since));
+    }
+    if let Some(until) = filters.until {
+        clauses.push(format!("updatedAt <= {}", until));
+    }
+    (!clauses.is_empty()).then(|| clauses.join(" AND "))
+}
+
+fn client() -> http::Client {
+    http::Client::new(REQUEST_TIMEOUT).with_retries(0).with_purpose("local-index")
+}
+
+fn endpoint(path: &str) -> String {
+    format!("{}{}", crate::config::current().search.meilisearch_url.trim_end_matches('/'), path)
+}
+
+fn document_id(ref_id: &str) -> String {
+    let digest = Sha256::digest(ref_id.as_bytes());
+    digest[..16].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn truncate(text: &str) -> String {
+    match text.char_indices().nth(MAX_CONTENT_CHARS) {
+        Some((end, _)) => text[..end].to_string(),
+        None => text.to_string(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum LocalIndexError {
+    UnknownIndex(String),
+    Unavailable,
+    Database(String),
+    Request(String),
+}
+
+impl From<http::HttpError> for LocalIndexError {
+    fn from(e: http::HttpError) -> Self {
+        match e {
+            http::HttpError::Request(_) | http::HttpError::CircuitOpen(_) => LocalIndexError::Unavailable,
+            e => LocalIndexError::Request(e.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for LocalIndexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LocalIndexError::UnknownIndex(uid) => write!(f, "Unknown index: {}", uid),
+            LocalIndexError::Unavailable => write!(f, "Meilisearch is not running"),
+            LocalIndexError::Database(msg) => write!(f, "Database error: {}", msg),
+            LocalIndexError::Request(msg) => write!(f, "Meilisearch request failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for LocalIndexError {}
@@ -0,0 +1,225 @@
+// Logging - tracing subscriber writing a daily rolling file under <app data>/logs
+// Log lines carry a subsystem target (agent, ws, db, trade, ...) whose level can be changed at runtime
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+
+// Subsystem targets used with `tracing::info!(target: "db", ...)`
+pub const TARGETS: &[&str] = &["app", "agent", "ai", "db", "services", "stability", "privacy", "ws", "trade"];
+pub const LEVELS: &[&str] = &["off", "error", "warn", "info", "debug", "trace"];
+const DEFAULT_LEVEL: &str = "info";
+const LOG_PREFIX: &str = "regen";
+const LOG_SUFFIX: &str = "log";
+const MAX_LOG_FILES: usize = 7;
+const CRASH_LOG: &str = "crash.log";
+// Dependency crates are chatty at debug level; keep them quiet unless asked for explicitly
+const QUIET_CRATES: &[&str] = &["hyper", "hyper_util", "reqwest", "rustls", "h2", "tao", "wry"];
+pub const MAX_TAIL_LINES: usize = 5_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct LogLevels {
+    pub default: String,
+    pub targets: BTreeMap<String, String>,
+    pub log_dir: Option<String>,
+}
+
+struct Logging {
+    dir: PathBuf,
+    filter: reload::Handle<EnvFilter, Registry>,
+    levels: Mutex<(String, BTreeMap<String, String>)>,
+    _guard: WorkerGuard,               // Flushes the background writer on drop
+}
+
+static LOGGING: OnceLock<Logging> = OnceLock::new();
+
+// Install the global subscriber; RUST_LOG-style directives in REGEN_LOG override the default level
+pub fn init(dir: PathBuf) -> Result<(), LogError> {
+    std::fs::create_dir_all(&dir).map_err(|e| LogError::Io(e.to_string()))?;
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_PREFIX)
+        .filename_suffix(LOG_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(&dir)
+        .map_err(|e| LogError::Io(e.to_string()))?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+
+    let default = std::env::var("REGEN_LOG").unwrap_or_else(|_| DEFAULT_LEVEL.to_string());
+    let targets = BTreeMap::new();
+    let filter = EnvFilter::try_new(directives(&default, &targets))
+        .or_else(|_| EnvFilter::try_new(DEFAULT_LEVEL))
+        .map_err(|e| LogError::InvalidLevel(e.to_string()))?;
+    let (filter_layer, filter_handle) = reload::Layer::new(filter);
+
+    tracing_subscriber::registry()
+        .with(filter_layer)
+        .with(fmt::layer().with_writer(writer).with_ansi(false).with_target(true))
+        .with(fmt::layer().with_writer(std::io::stderr).with_target(true))
+        .try_init()
+        .map_err(|e| LogError::AlreadyInitialized(e.to_string()))?;
+
+    let _ = LOGGING.set(Logging {
+        dir,
+        filter: filter_handle,
+        levels: Mutex::new((default, targets)),
+        _guard: guard,
+    });
+    install_panic_hook();
+    Ok(())
+}
+
+// Panics are appended to crash.log (the non-blocking writer may not flush before the process dies)
+fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let thread = std::thread::current().name().unwrap_or("unnamed").to_string();
+        let backtrace = std::backtrace::Backtrace::force_capture();
+        tracing::error!(target: "app", "Panic on thread '{}': {}", thread, info);
+        if let Some(path) = crash_log_path() {
+            use std::io::Write;
+            if let Ok(mut file) = std::fs::OpenOptions::new().create(true).append(true).open(path) {
+                let _ = writeln!(
+                    file,
+                    "=== {} panic on thread '{}' ===\n{}\n{}\n",
+                    chrono::Utc::now().to_rfc3339(),
+                    thread,
+                    info,
+                    backtrace
+                );
+            }
+        }
+        previous(info);
+    }));
+}
+
+pub fn crash_log_path() -> Option<PathBuf> {
+    log_dir().map(|dir| dir.join(CRASH_LOG))
+}
+
+pub fn log_dir() -> Option<PathBuf> {
+    LOGGING.get().map(|l| l.dir.clone())
+}
+
+pub fn levels() -> LogLevels {
+    match LOGGING.get() {
+        Some(logging) => {
+            let (default, targets) = logging.levels.lock().unwrap().clone();
+            LogLevels { default, targets, log_dir: Some(logging.dir.to_string_lossy().to_string()) }
+        }
+        None => LogLevels { default: DEFAULT_LEVEL.to_string(), targets: BTreeMap::new(), log_dir: None },
+    }
+}
+
+// Set the default level, or one target's level; "inherit" drops a target override
+pub fn set_level(level: &str, target: Option<&str>) -> Result<LogLevels, LogError> {
+    let logging = LOGGING.get().ok_or(LogError::NotInitialized)?;
+    let level = level.trim().to_lowercase();
+    if !LEVELS.contains(&level.as_str()) && !(target.is_some() && level == "inherit") {
+        return Err(LogError::InvalidLevel(level));
+    }
+
+    {
+        let mut levels = logging.levels.lock().unwrap();
+        match target {
+            Some(target) => {
+                let target = target.trim();
+                if target.is_empty() || !target.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':') {
+                    return Err(LogError::InvalidTarget(target.to_string()));
+                }
+                if level == "inherit" {
+                    levels.1.remove(target);
+                } else {
+                    levels.1.insert(target.to_string(), level);
+                }
+            }
+            None => levels.0 = level,
+        }
+
+        let filter = EnvFilter::try_new(directives(&levels.0, &levels.1))
+            .map_err(|e| LogError::InvalidLevel(e.to_string()))?;
+        logging
+            .filter
+            .reload(filter)
+            .map_err(|e| LogError::Io(e.to_string()))?;
+    }
+    Ok(levels())
+}
+
+fn directives(default: &str, targets: &BTreeMap<String, String>) -> String {
+    let mut parts = vec![default.to_string()];
+    parts.extend(
+        QUIET_CRATES
+            .iter()
+            .filter(|c| !targets.contains_key(**c))
+            .map(|c| format!("{}=warn", c)),
+    );
+    parts.extend(targets.iter().map(|(target, level)| format!("{}={}", target, level)));
+    parts.join(",")
+}
+
+// Last `lines` lines across the newest log files (oldest first)
+pub fn tail(lines: usize) -> Result<Vec<String>, LogError> {
+    let dir = log_dir().ok_or(LogError::NotInitialized)?;
+    let lines = lines.clamp(1, MAX_TAIL_LINES);
+
+    let mut collected: Vec<String> = Vec::new();
+    for file in log_files(&dir)?.into_iter().rev() {
+        let contents = std::fs::read_to_string(&file).map_err(|e| LogError::Io(e.to_string()))?;
+        let mut chunk: Vec<String> = contents.lines().map(str::to_string).collect();
+        chunk.append(&mut collected);
+        collected = chunk;
+        if collected.len() >= lines {
+            break;
+        }
+    }
+    let skip = collected.len().saturating_sub(lines);
+    Ok(collected.split_off(skip))
+}
+
+// Log files, oldest first (names sort by date)
+pub fn log_files(dir: &Path) -> Result<Vec<PathBuf>, LogError> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| LogError::Io(e.to_string()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .map(|n| n.starts_with(LOG_PREFIX) && n.ends_with(LOG_SUFFIX))
+                .unwrap_or(false)
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+#[derive(Debug, Clone)]
+pub enum LogError {
+    NotInitialized,
+    AlreadyInitialized(String),
+    InvalidLevel(String),
+    InvalidTarget(String),
+    Io(String),
+}
+
+impl std::fmt::Display for LogError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LogError::NotInitialized => write!(f, "Logging is not initialized"),
+            LogError::AlreadyInitialized(msg) => write!(f, "Logging already initialized: {}", msg),
+            LogError::InvalidLevel(level) => write!(f, "Invalid log level '{}' (expected one of {})", level, LEVELS.join(", ")),
+            LogError::InvalidTarget(target) => write!(f, "Invalid log target '{}'", target),
+            LogError::Io(msg) => write!(f, "Log file error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for LogError {}
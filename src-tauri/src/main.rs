@@ -4,13 +4,18 @@
 use omnibrowser_tauri::*;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use tauri::Manager;
+use tauri::{Emitter, Manager};
 
 fn main() {
+    // Launched by an MCP client: relay stdio to the running app instead of opening a window
+    if std::env::args().any(|arg| arg == mcp::STDIO_FLAG) {
+        std::process::exit(mcp::run_stdio_bridge());
+    }
+
     // Initialize stability features (before database, as they don't depend on it)
     let safe_mode = stability::SafeMode::new(3);
     let memory_guard = stability::MemoryGuard::new(
-        Duration::from_secs(30), // Freeze tabs after 30s idle
+        Duration::from_secs(10 * 60), // Discard background tabs after 10min idle
         2_147_483_648,            // 2GB RAM threshold
     );
     
@@ -25,16 +30,9 @@ fn main() {
     }
 
     // Initialize privacy enforcer (default: Normal mode)
-    let privacy_enforcer = Mutex::new(privacy::PrivacyEnforcer::new(state::PrivacyMode::Normal));
-
-    // Initialize AI service (default: Ollama)
-    let ai_config = ai::AIConfig {
-        provider: ai::AIProvider::Ollama,
-        model: "phi3:mini".to_string(), // Default model
-        max_tokens: 2048,
-        temperature: 0.7,
-    };
-    let ai_service = ai::AIService::new(ai_config);
+    let initial_privacy = state::PrivacyMode::Normal;
+    webrtc_guard::configure_engine(&initial_privacy);
+    let privacy_enforcer = Mutex::new(privacy::PrivacyEnforcer::new(initial_privacy));
 
     // Initialize tab manager (before database, will restore session in setup)
     let tab_manager = Arc::new(browser::TabManager::new(3)); // Max 3 crashes before safe mode
@@ -42,36 +40,140 @@ fn main() {
     let tab_manager_clone = Arc::clone(&tab_manager);
     tauri::Builder::default()
         .setup(move |app| {
+            // Logs go to <app data>/logs before anything else runs
+            if let Ok(dir) = app.path().app_data_dir() {
+                if let Err(e) = logging::init(dir.join("logs")) {
+                    eprintln!("Failed to initialize logging: {}", e);
+                }
+            }
+
+            // config.toml (created on first run from defaults and legacy env vars)
+            if let Ok(dir) = app.path().app_data_dir() {
+                match config::store().load(dir.join(config::FILE_NAME)) {
+                    Ok(loaded) => config::apply(app.handle(), &loaded),
+                    Err(e) => tracing::error!(target: "app", "Config: {}; using defaults", e),
+                }
+                if let Err(e) = config::watch(app.handle().clone()) {
+                    tracing::warn!(target: "app", "Config: Hot reload unavailable: {}", e);
+                }
+            }
+            let app_config = config::current();
+
+            // Global shortcuts from config (no-op if config::apply already registered them)
+            if let Err(e) = services::global_shortcut_service::initialize_global_shortcuts(app.handle()) {
+                tracing::warn!(target: "app", "Global shortcut: {}", e);
+            }
+
+            // Tray icon with quick actions
+            if let Err(e) = tray::init(app.handle()) {
+                tracing::warn!(target: "app", "Tray: {}", e);
+            }
+
+            // AI service (default: Ollama; model from config, else sized to this machine)
+            app.manage(ai::AIService::new(ai::AIConfig {
+                provider: ai::AIProvider::Ollama,
+                model: app_config.ai.chat_model(),
+                max_tokens: app_config.ai.max_tokens,
+                temperature: app_config.ai.temperature,
+            }));
+
+            // Crash-loop detection: a previous run that never exited cleanly counts as a crash
+            let safe_mode_active = match app.path().app_data_dir() {
+                Ok(dir) => {
+                    std::fs::create_dir_all(&dir).ok();
+                    app.state::<stability::SafeMode>().begin_session(dir.join("safe_mode.json"))
+                }
+                Err(_) => false,
+            };
+            if safe_mode_active {
+                tracing::warn!(target: "stability", "Safe mode: Starting with session restore, service autostart and agents disabled");
+            }
+
             // Get app data directory and initialize database there
             let db = if let Ok(app_data_dir) = app.path().app_data_dir() {
                 std::fs::create_dir_all(&app_data_dir).ok();
-                let db_path = app_data_dir.join("regen.db");
+                // Each profile has its own database; Default lives in the app data root
+                // User prompt overrides are install-wide, not per profile
+                if let Err(e) = prompts::registry().load_overrides(app_data_dir.join("prompts.toml")) {
+                    tracing::warn!(target: "ai", "Failed to load prompt overrides: {}", e);
+                }
+                let profile_manager = profiles::ProfileManager::load(app_data_dir);
+                let db_path = profile_manager.database_path();
+                app.manage(profile_manager);
+                // config::apply ran before profiles were known; pick up a per-profile proxy now
+                proxy::apply(app.handle(), &config::current().proxy);
                 match db::Database::new(Some(db_path)) {
                     Ok(database) => database,
                     Err(e) => {
-                        eprintln!("Failed to initialize database in app data dir: {}. Using fallback.", e);
+                        tracing::error!(target: "db", "Failed to initialize database in app data dir: {}. Using fallback.", e);
                         // Fallback to current directory
                         db::Database::new(None).unwrap_or_else(|e| {
-                            eprintln!("Failed to initialize database (fallback): {}", e);
+                            tracing::error!(target: "db", "Failed to initialize database (fallback): {}", e);
                             std::process::exit(1);
                         })
                     }
                 }
             } else {
                 // Fallback to current directory if app data dir unavailable
-                eprintln!("App data directory unavailable. Using current directory for database.");
+                tracing::warn!(target: "db", "App data directory unavailable. Using current directory for database.");
                 db::Database::new(None).unwrap_or_else(|e| {
-                    eprintln!("Failed to initialize database: {}", e);
+                    tracing::error!(target: "db", "Failed to initialize database: {}", e);
                     std::process::exit(1);
                 })
             };
 
+            // Unsaved UI state from a crashed run is held for recovery_restore
+            let recovery_dir = app
+                .try_state::<profiles::ProfileManager>()
+                .map(|p| p.data_dir())
+                .or_else(|| app.path().app_data_dir().ok());
+            if let Some(dir) = recovery_dir {
+                let unclean = app.state::<stability::SafeMode>().previous_exit_unclean();
+                recovery::init(app.handle(), dir, unclean);
+            }
+
+            // Encrypted per-profile vault; API keys from legacy env vars move into it once
+            let secure_dir = app
+                .try_state::<profiles::ProfileManager>()
+                .map(|p| p.secure_storage_dir())
+                .unwrap_or_else(|| std::path::PathBuf::from("secure"));
+            match secure_store::SecureStore::open(secure_dir) {
+                Ok(store) => {
+                    let api_keys = apikeys::ApiKeyStore::new(store.clone());
+                    let migrated = api_keys.migrate_env();
+                    if !migrated.is_empty() {
+                        tracing::info!(target: "app", "API keys: Migrated {:?} from environment", migrated);
+                    }
+                    app.manage(autofill::AutofillStore::new(store.clone()));
+                    let passwords = passwords::PasswordVault::new(store.clone());
+                    passwords.spawn_idle_lock(app.handle().clone());
+                    app.manage(passwords);
+                    app.manage(store);
+                    app.manage(api_keys);
+                }
+                Err(e) => tracing::error!(target: "app", "Secure storage unavailable: {}", e),
+            }
+
+            // LLM responses are cached in the same database
+            app.state::<ai::AIService>()
+                .attach_cache(llm_cache::LlmCache::new(db.clone(), llm_cache::CacheConfig::default()));
+
             // Initialize search engine with database
             let search_engine = search::SearchEngine::new(db.clone());
 
-            // Restore session from database (if exists)
-            if let Err(e) = tab_manager_clone.restore_session(&db) {
-                eprintln!("Warning: Failed to restore session: {}. Starting with new tab.", e);
+            if safe_mode_active {
+                // Keep the skipped session recoverable (safe_mode_exit brings it back)
+                if let Err(e) = session::park_for_safe_mode(&db) {
+                    tracing::warn!(target: "stability", "Safe mode: Failed to park previous session: {}", e);
+                }
+                let _ = tab_manager_clone.create_tab(
+                    "about:blank".to_string(),
+                    state::PrivacyMode::Normal,
+                    state::AppMode::Browse,
+                );
+            } else if let Err(e) = tab_manager_clone.restore_session(&db) {
+                // Restore session from database (if exists)
+                tracing::warn!(target: "app", "Failed to restore session: {}. Starting with new tab.", e);
                 // Create default tab if restore failed and no tabs exist
                 let tabs = tab_manager_clone.list_tabs();
                 if tabs.is_empty() {
@@ -83,26 +185,125 @@ fn main() {
                 }
             }
 
+            // Mirror pages, notes, bookmarks, and sessions into Meilisearch for search_local
+            app.manage(local_index::LocalIndex::start(db.clone()));
+            // Embed history entries as they're visited for history_semantic_search
+            app.manage(semantic_history::SemanticHistory::start(db.clone()));
+            // Refresh feed subscriptions and look for feeds on visited sites
+            app.manage(feeds::Feeds::start(app.handle().clone(), db.clone()));
+            // Keep history frecency scores decayed to the present
+            frecency::spawn_recompute(db.clone());
+            // Weekly research digest, if [digest] weekly is on
+            digest::spawn_weekly(app.handle().clone());
+            // Local-only time per domain, written out every minute
+            usage::spawn_flush(app.handle().clone(), db.clone());
+            // Backend request log for network_audit_list (SQLite copy only if [networkAudit] persist is on)
+            network_audit::init(app.handle().clone(), db.clone());
+            // Agent actions still pending from the last session can't be approved any more
+            approvals::init(&db);
+            // Close transcripts of runs cut off by the last exit and cap how many are kept
+            agent_runs::init(&db);
+            // Today's LLM token and cost totals, so budgets carry over a restart
+            llm_usage::init(db.clone());
+            // Cached daily exchange rates (currency answers, fx_convert), refreshed in the background
+            fx::init(db.clone());
+            // Hunspell dictionaries are install-wide (app data "dictionaries") plus the system's; loaded on first use
+            app.manage(spellcheck::SpellChecker::new(app.path().app_data_dir().ok().map(|dir| dir.join("dictionaries"))));
+
             // Manage all state (db and search_engine managed here)
             app.manage(db);
             app.manage(search_engine);
+
+            // First-run setup progress; a model download or import cut off by the last exit continues
+            if let Ok(dir) = app.path().app_data_dir() {
+                onboarding::init(app.handle(), dir);
+            }
             
+            // Supervise local services (Ollama, Meilisearch, n8n); stopped again on exit
+            let services_dir = app
+                .path()
+                .app_data_dir()
+                .map(|dir| dir.join("services"))
+                .unwrap_or_else(|_| std::path::PathBuf::from("services"));
+            let bin_dirs = services::binaries::search_dirs(app.path().resource_dir().ok().as_deref());
+            let mut specs = services::supervisor::default_specs(&services_dir);
+            for spec in &mut specs {
+                spec.autostart &= app_config.services.autostart && !app_config.services.disabled.contains(&spec.name);
+            }
+            let supervisor = services::supervisor::ServiceSupervisor::new(specs, bin_dirs);
+            // Tell the frontend which local services this platform can run
+            let _ = app.emit("services:capabilities", supervisor.capabilities());
+            if !safe_mode_active {
+                let startup = supervisor.clone();
+                tauri::async_runtime::spawn_blocking(move || startup.start_all());
+            }
+            supervisor.spawn_monitor(Duration::from_secs(15));
+            // Load the configured models once Ollama is up, if [ai] warmup is on
+            warmup::spawn(app.handle().clone(), !safe_mode_active);
+
+            // Offline detection; emits "offline-changed"
+            connectivity::spawn_monitor(app.handle().clone(), Duration::from_secs(60));
+
+            // Global memory budget across models, services, and tabs
+            let resource_manager = resources::ResourceManager::new();
+            resource_manager.spawn_enforcer(
+                app.handle().clone(),
+                Arc::clone(&tab_manager_clone),
+                supervisor.clone(),
+                Duration::from_secs(20),
+            );
+            app.manage(supervisor);
+            app.manage(resource_manager);
+
+            // Idle tab discard; emits "tab-discard-request", "tab-discarded", "tab-restored"
+            let discarder = tab_discard::TabDiscarder::new();
+            discarder.spawn(app.handle().clone(), Arc::clone(&tab_manager_clone), Duration::from_secs(30));
+            app.manage(discarder);
+
             // Start watchdog task now that Tauri runtime is ready
-            stability::start_watchdog_task_async(
+            let watchdog = stability::Watchdog::new(
                 Duration::from_secs(5),  // Check every 5 seconds
                 Duration::from_secs(10), // 10 second timeout
             );
-            
+            stability::start_watchdog_task_async(
+                app.handle().clone(),
+                Arc::clone(&tab_manager_clone),
+                watchdog.clone(),
+            );
+            app.manage(watchdog);
+
+            // Staying up this long counts as a successful session and clears the crash count
+            if !safe_mode_active {
+                let safe_mode = app.state::<stability::SafeMode>().inner().clone();
+                tauri::async_runtime::spawn(async move {
+                    tokio::time::sleep(Duration::from_secs(120)).await;
+                    safe_mode.reset_crash_count();
+                });
+            }
+
             Ok(())
         })
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::Focused(focused) = event {
+                usage::on_window_focus(window.app_handle(), *focused);
+            }
+        })
         .plugin(tauri_plugin_shell::init())
-        .manage(tab_manager)
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(services::global_shortcut_service::on_shortcut_event)
+                .build(),
+        )
+        // Commands take State<TabManager>; clones share the same tabs
+        .manage(tab_manager.as_ref().clone())
         .manage(app_state)
         .manage(privacy_enforcer)
-        .manage(ai_service)
         .manage(safe_mode)
         .manage(memory_guard)
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(permissions::guard(tauri::generate_handler![
             // Tab commands
             commands::tabs_create,
             commands::tabs_delete,
@@ -111,6 +312,39 @@ fn main() {
             commands::tabs_set_active,
             commands::tabs_update,
             commands::tabs_record_crash,
+            commands::watchdog_ack,
+            commands::watchdog_stats,
+            commands::safe_mode_status,
+            commands::safe_mode_exit,
+            // Tab navigation commands
+            commands::tabs_navigate,
+            commands::tabs_back,
+            commands::tabs_forward,
+            commands::tabs_history,
+            // Tab discard commands
+            commands::tabs_discard_ack,
+            commands::tabs_discard,
+            commands::tabs_discard_stats,
+            // Tab metrics commands
+            commands::tabs_metrics,
+            commands::tabs_report_usage,
+            // Tab group commands
+            commands::tabs_group_create,
+            commands::tabs_group_update,
+            commands::tabs_assign_group,
+            commands::tabs_list_groups,
+            commands::tabs_group_sleep,
+            commands::tabs_group_close,
+            commands::tabs_group_restore,
+            commands::tabs_group_delete,
+            // Split view commands
+            commands::layout_set_split,
+            commands::layout_reset,
+            commands::layout_get,
+            // Session checkpoint commands
+            commands::session_checkpoint,
+            commands::session_list_checkpoints,
+            commands::session_restore_checkpoint,
             // Settings commands
             commands::settings_get_language,
             commands::settings_set_language,
@@ -120,6 +354,16 @@ fn main() {
             // Privacy commands
             commands::privacy_get_mode,
             commands::privacy_set_mode,
+            commands::privacy_leak_test,
+            commands::privacy_webrtc_guard_script,
+            commands::privacy_webrtc_support,
+            commands::doh_set_provider,
+            commands::doh_status,
+            commands::doh_clear_cache,
+            commands::proxy_set,
+            commands::proxy_test,
+            commands::network_audit_list,
+            commands::network_audit_clear,
             // Database commands
             commands::db_search,
             commands::db_save_page,
@@ -129,11 +373,50 @@ fn main() {
             commands::db_clear_history,
             commands::db_search_history,
             commands::db_delete_history_url,
+            // Notes commands
+            commands::note_create,
+            commands::note_update,
+            commands::note_delete,
+            commands::note_get,
+            commands::note_list,
+            commands::note_search,
+            commands::note_tags,
+            commands::note_spellcheck,
+            commands::note_apply_spelling,
+            // Highlight commands
+            commands::highlight_add,
+            commands::highlight_list_for_url,
+            commands::highlight_delete,
+            commands::highlight_resolve,
+            // Selection action commands
+            commands::selection_action,
+            // Reader mode commands
+            commands::reader_mode,
+            // Profile commands
+            commands::profile_create,
+            commands::profile_list,
+            commands::profile_switch,
+            // Archive commands
+            commands::archive_page,
+            commands::archive_list,
+            commands::archive_open,
+            commands::archive_delete,
+            // Research session commands
+            commands::research_session_save,
+            commands::research_session_get,
+            commands::research_session_list,
+            commands::research_session_delete,
+            commands::session_export,
+            commands::citations_export,
+            commands::session_import,
             // History commands (Frontend API - using name attribute)
             commands::history_list,
             commands::history_clear,
             commands::history_search,
             commands::history_delete_url,
+            commands::history_clusters,
+            commands::history_semantic_search,
+            commands::history_top_sites,
             // Downloads commands (Frontend API - using name attribute)
             commands::downloads_list,
             commands::downloads_open_file,
@@ -141,13 +424,210 @@ fn main() {
             commands::downloads_get_queue,
             commands::downloads_save,
             commands::downloads_delete,
+            commands::downloads_verify,
+            commands::verify_file,
+            commands::verify_signature,
             // AI commands
             commands::ai_complete,
             commands::ai_detect_intent,
+            commands::ai_complete_stream,
+            commands::markdown_segment,
+            commands::language_detect,
+            commands::embed_text,
+            commands::embed_batch,
+            commands::research_check_grounding,
+            commands::similarity_check,
+            commands::research_run,
+            commands::research_multilingual,
+            commands::summarize_document,
+            commands::summarize_v2,
+            commands::process_pdf,
+            commands::process_doc,
+            commands::process_epub,
+            commands::document_ask,
+            commands::document_delete,
+            commands::extract_tables,
+            commands::table_export_csv,
+            commands::image_inspect,
+            commands::image_ocr,
+            commands::image_caption,
+            commands::images_collect,
+            commands::feed_subscribe,
+            commands::feed_unsubscribe,
+            commands::feed_list,
+            commands::feed_list_entries,
+            commands::feed_mark_read,
+            commands::feed_refresh,
+            commands::feed_discovered,
+            commands::digest_generate,
+            commands::prompt_list,
+            commands::prompt_override,
+            commands::llm_cache_stats,
+            commands::llm_cache_clear,
             // System commands
             commands::system_get_ram,
             commands::system_get_max_tabs,
             commands::get_system_info,
+            commands::system_capabilities,
+            commands::resource_report,
+            commands::resource_set_budget,
+            commands::mode_profiles_list,
+            commands::mode_profile_active,
+            // Logging & diagnostics commands
+            commands::logging_set_level,
+            commands::logging_get_levels,
+            commands::logs_tail,
+            commands::diagnostics_export,
+            // Config commands
+            commands::config_get,
+            commands::config_set,
+            // API key commands
+            commands::apikey_set,
+            commands::apikey_test,
+            commands::apikey_list,
+            commands::apikey_delete,
+            // Autofill commands
+            commands::autofill_profiles_list,
+            commands::autofill_profiles_save,
+            commands::autofill_profiles_delete,
+            commands::autofill_match,
+            // Password commands
+            commands::password_generate,
+            commands::password_save,
+            commands::password_find,
+            commands::password_mark_used,
+            commands::password_delete,
+            commands::password_breach_check,
+            commands::password_unlock,
+            commands::password_lock,
+            commands::password_status,
+            // Network commands
+            commands::http_stats,
+            commands::http_reset_circuit,
+            commands::providers_health,
+            commands::is_offline,
+            commands::connectivity_check,
+            // Web search commands
+            commands::search_web,
+            commands::search_local,
+            commands::search_local_reindex,
+            // Omnibox commands
+            commands::omnibox_suggest,
+            commands::instant_answer,
+            commands::fx_convert,
+            commands::fx_rates,
+            commands::trade_news,
+            commands::trade_option_chain,
+            commands::trade_quote,
+            commands::backtest_run,
+            commands::backtest_list,
+            commands::backtest_get,
+            commands::backtest_delete,
+            commands::trade_log_record,
+            commands::trade_log_list,
+            commands::journal_add_entry,
+            commands::journal_report,
+            // Focus mode commands
+            commands::focus_start,
+            commands::focus_stop,
+            commands::focus_status,
+            commands::focus_stats,
+            // Usage analytics commands
+            commands::usage_report,
+            commands::usage_clear,
+            // Clipboard commands
+            commands::clipboard_monitor_enable,
+            commands::clipboard_monitor_disable,
+            commands::clipboard_monitor_status,
+            // Capture & OCR commands
+            commands::capture_screenshot,
+            commands::ocr_image,
+            commands::capture_send_text,
+            // Global shortcut commands
+            commands::shortcut_register,
+            commands::shortcut_unregister,
+            commands::shortcut_reset,
+            commands::shortcut_list,
+            // Service supervisor commands
+            commands::service_status,
+            commands::service_restart,
+            commands::service_capabilities,
+            // Agent tool commands
+            commands::tools_list,
+            commands::tools_call,
+            commands::agent_ask,
+            commands::wispr_handle,
+            commands::hotword_status,
+            commands::hotword_set_enabled,
+            commands::hotword_set_sensitivity,
+            commands::window_set_always_on_top,
+            commands::wispr_orb_window,
+            commands::trade_ticker_window,
+            commands::notify,
+            commands::notification_respond,
+            commands::notification_list,
+            commands::notification_clear,
+            commands::recovery_checkpoint,
+            commands::recovery_available,
+            commands::recovery_restore,
+            commands::recovery_discard,
+            commands::onboarding_status,
+            commands::onboarding_run_step,
+            commands::onboarding_skip_step,
+            commands::onboarding_reset,
+            commands::onboarding_import_sources,
+            commands::userscripts_for,
+            commands::userscript_list,
+            commands::userscript_save,
+            commands::userscript_import,
+            commands::userscript_install,
+            commands::userscript_set_enabled,
+            commands::userscript_delete,
+            commands::userscript_check_updates,
+            commands::rules_add,
+            commands::rules_list,
+            commands::rules_validate,
+            commands::rules_test,
+            commands::rules_set_enabled,
+            commands::rules_remove,
+            commands::actions_registry,
+            commands::action_invoke,
+            commands::permissions_violations,
+            commands::permissions_grants,
+            commands::dialog_open_file,
+            commands::dialog_save_file,
+            commands::files_recent,
+            commands::files_recent_clear,
+            commands::archive_inspect,
+            commands::archive_extract,
+            commands::api_server_status,
+            commands::api_server_token,
+            commands::api_server_openapi,
+            commands::mcp_status,
+            commands::workflow_list,
+            commands::workflow_trigger,
+            commands::calendar_import,
+            commands::calendar_upcoming,
+            commands::calendar_add_event,
+            commands::calendar_delete_event,
+            commands::compose_email,
+            commands::spellcheck,
+            commands::spellcheck_dictionaries,
+            commands::actions_propose,
+            commands::actions_pending,
+            commands::actions_approve,
+            commands::actions_reject,
+            commands::agent_runs_list,
+            commands::agent_run_get,
+            commands::agent_run_replay,
+            commands::agent_run_delete,
+            commands::llm_usage_report,
+            commands::llm_usage_record,
+            commands::llm_budget_check,
+            commands::models_warmup_status,
+            commands::pii_redact,
+            commands::pii_restore,
+            commands::pii_redaction_log,
             // Task system commands
             commands::run_demo_agent,
             commands::cancel_task,
@@ -164,46 +644,21 @@ fn main() {
             commands::run_ai,
             commands::download,
             commands::get_state,
-        ])
-        .setup(move |app| {
-            // IPC Event Emitters - Forward backend events to UI
-            let app_handle = app.handle().clone();
-
-            // Task events
-            app.listen_global("task:created", move |event| {
-                if let Some(payload) = event.payload() {
-                    let _ = app_handle.emit("task:created", payload);
-                }
-            });
-
-            app.listen_global("task:updated", move |event| {
-                if let Some(payload) = event.payload() {
-                    let _ = app_handle.emit("task:updated", payload);
-                }
-            });
-
-            app.listen_global("task:log", move |event| {
-                if let Some(payload) = event.payload() {
-                    let _ = app_handle.emit("task:log", payload);
+        ]))
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            // Don't leave supervised services running after the app quits
+            if let tauri::RunEvent::Exit = event {
+                if let Some(supervisor) = app_handle.try_state::<services::supervisor::ServiceSupervisor>() {
+                    supervisor.shutdown();
                 }
-            });
-
-            // Thought stream events
-            app.listen_global("thought:step", move |event| {
-                if let Some(payload) = event.payload() {
-                    let _ = app_handle.emit("thought:step", payload);
-                }
-            });
-
-            // System metrics events
-            app.listen_global("system:metrics", move |event| {
-                if let Some(payload) = event.payload() {
-                    let _ = app_handle.emit("system:metrics", payload);
-                }
-            });
-
-            Ok(())
-        })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+                services::hotword_service::shutdown();
+                services::api_server::shutdown();
+                floating_windows::persist_positions(app_handle);
+                recovery::end_session();
+                // Clean exit; the next launch isn't counted as a crash
+                app_handle.state::<stability::SafeMode>().end_session();
+            }
+        });
 }
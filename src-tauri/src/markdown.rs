@@ -0,0 +1,349 @@
+// Markdown Segmenter - Incremental block parser for streamed model output
+// Each token is scanned once; finished lines become block events the UI can render without re-parsing the answer
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub enum ColumnAlign {
+    None,
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(tag = "type", rename_all = "kebab-case", rename_all_fields = "camelCase")]
+pub enum Block {
+    HeadingStart { level: u8, text: String, section: usize },
+    Paragraph { text: String },
+    ListItem { ordered: bool, number: Option<u32>, depth: usize, checked: Option<bool>, text: String },
+    TableRow { row: usize, header: bool, cells: Vec<String>, align: Option<Vec<ColumnAlign>> },
+    CodeBlockStart { language: Option<String> },
+    CodeLine { text: String },
+    CodeBlockEnd { language: Option<String>, lines: usize },
+    Quote { text: String },
+    Rule,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct MarkdownEvent {
+    pub offset: usize,                 // Byte offset of the block's first line in the full text
+    #[serde(flatten)]
+    pub block: Block,
+}
+
+// One streamed token and the blocks it completed; emitted as-is to the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkdownChunk {
+    pub token: String,
+    pub events: Vec<MarkdownEvent>,
+}
+
+struct Fence {
+    marker: char,
+    width: usize,
+    language: Option<String>,
+    lines: usize,
+}
+
+#[derive(Default)]
+pub struct MarkdownSegmenter {
+    line: String,                      // Current unfinished line
+    line_offset: usize,
+    consumed: usize,
+    fence: Option<Fence>,
+    paragraph: Option<(usize, String)>,
+    pending_row: Option<(usize, String)>,  // Pipe row that may turn out to be a table header
+    table_row: Option<usize>,          // Next row index while inside a table
+    columns: usize,
+    sections: usize,
+}
+
+impl MarkdownSegmenter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Feed a token; returns blocks completed by it
+    pub fn push(&mut self, token: &str) -> Vec<MarkdownEvent> {
+        let mut events = Vec::new();
+        let mut rest = token;
+        while let Some(newline) = rest.find('\n') {
+            self.line.push_str(&rest[..newline]);
+            self.consumed += newline + 1;
+            let line = std::mem::take(&mut self.line);
+            let offset = self.line_offset;
+            self.line_offset = self.consumed;
+            self.process_line(line.trim_end_matches('\r'), offset, &mut events);
+            rest = &rest[newline + 1..];
+        }
+        self.line.push_str(rest);
+        self.consumed += rest.len();
+        events
+    }
+
+    // End of stream: close whatever is still open
+    pub fn finish(&mut self) -> Vec<MarkdownEvent> {
+        let mut events = Vec::new();
+        if !self.line.is_empty() {
+            let line = std::mem::take(&mut self.line);
+            self.process_line(&line, self.line_offset, &mut events);
+        }
+        self.flush(&mut events);
+        if let Some(fence) = self.fence.take() {
+            events.push(MarkdownEvent {
+                offset: self.consumed,
+                block: Block::CodeBlockEnd { language: fence.language, lines: fence.lines },
+            });
+        }
+        events
+    }
+
+    fn process_line(&mut self, line: &str, offset: usize, events: &mut Vec<MarkdownEvent>) {
+        if let Some(fence) = self.fence.as_mut() {
+            let trimmed = line.trim();
+            let closing = trimmed.len() >= fence.width && trimmed.chars().all(|c| c == fence.marker);
+            if closing {
+                let fence = self.fence.take().unwrap();
+                events.push(MarkdownEvent { offset, block: Block::CodeBlockEnd { language: fence.language, lines: fence.lines } });
+            } else {
+                fence.lines += 1;
+                events.push(MarkdownEvent { offset, block: Block::CodeLine { text: line.to_string() } });
+            }
+            return;
+        }
+
+        // A pipe row followed by a delimiter row is a table header; otherwise it was plain text
+        if let Some((row_offset, row)) = self.pending_row.take() {
+            let cells = split_row(&row);
+            if let Some(align) = parse_delimiter(line).filter(|a| a.len() == cells.len()) {
+                self.flush_paragraph(events);
+                self.columns = cells.len();
+                self.table_row = Some(1);
+                events.push(MarkdownEvent {
+                    offset: row_offset,
+                    block: Block::TableRow { row: 0, header: true, cells, align: Some(align) },
+                });
+                return;
+            }
+            self.append_paragraph(row_offset, &row);
+        }
+
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+        if trimmed.is_empty() {
+            self.flush(events);
+            return;
+        }
+
+        if let Some(row) = self.table_row {
+            if trimmed.contains('|') {
+                let mut cells = split_row(trimmed);
+                cells.resize(self.columns, String::new());
+                self.table_row = Some(row + 1);
+                events.push(MarkdownEvent { offset, block: Block::TableRow { row, header: false, cells, align: None } });
+                return;
+            }
+            self.table_row = None;
+        }
+
+        // Code blocks indented 4+ spaces aren't recognised; models use fences
+        if indent < 4 {
+            if let Some((marker, width, language)) = parse_fence(trimmed) {
+                self.flush(events);
+                self.fence = Some(Fence { marker, width, language: language.clone(), lines: 0 });
+                events.push(MarkdownEvent { offset, block: Block::CodeBlockStart { language } });
+                return;
+            }
+            if let Some((level, text)) = parse_heading(trimmed) {
+                self.flush(events);
+                self.heading(offset, level, text, events);
+                return;
+            }
+            // Setext heading: a paragraph underlined with === or ---
+            if let Some(level) = parse_underline(trimmed).filter(|_| self.paragraph.is_some()) {
+                let (start, text) = self.paragraph.take().unwrap();
+                self.heading(start, level, text, events);
+                return;
+            }
+            if is_rule(trimmed) {
+                self.flush(events);
+                events.push(MarkdownEvent { offset, block: Block::Rule });
+                return;
+            }
+        }
+        if let Some(item) = parse_list_item(trimmed, indent) {
+            self.flush(events);
+            events.push(MarkdownEvent { offset, block: item });
+            return;
+        }
+        if let Some(text) = trimmed.strip_prefix('>') {
+            self.flush(events);
+            events.push(MarkdownEvent { offset, block: Block::Quote { text: text.trim().to_string() } });
+            return;
+        }
+        if trimmed.contains('|') {
+            self.pending_row = Some((offset, trimmed.to_string()));
+            return;
+        }
+        self.append_paragraph(offset, trimmed);
+    }
+
+    fn heading(&mut self, offset: usize, level: u8, text: String, events: &mut Vec<MarkdownEvent>) {
+        self.sections += 1;
+        events.push(MarkdownEvent { offset, block: Block::HeadingStart { level, text, section: self.sections } });
+    }
+
+    fn append_paragraph(&mut self, offset: usize, text: &str) {
+        match self.paragraph.as_mut() {
+            Some((_, paragraph)) => {
+                paragraph.push(' ');
+                paragraph.push_str(text.trim());
+            }
+            None => self.paragraph = Some((offset, text.trim().to_string())),
+        }
+    }
+
+    fn flush_paragraph(&mut self, events: &mut Vec<MarkdownEvent>) {
+        if let Some((offset, text)) = self.paragraph.take() {
+            events.push(MarkdownEvent { offset, block: Block::Paragraph { text } });
+        }
+    }
+
+    // Close the open paragraph (including a held pipe row) and table
+    fn flush(&mut self, events: &mut Vec<MarkdownEvent>) {
+        if let Some((offset, row)) = self.pending_row.take() {
+            self.append_paragraph(offset, &row);
+        }
+        self.flush_paragraph(events);
+        self.table_row = None;
+    }
+}
+
+// Segment a complete document in one go
+pub fn segment(text: &str) -> Vec<MarkdownEvent> {
+    let mut segmenter = MarkdownSegmenter::new();
+    let mut events = segmenter.push(text);
+    events.extend(segmenter.finish());
+    events
+}
+
+fn parse_fence(line: &str) -> Option<(char, usize, Option<String>)> {
+    let marker = line.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let width = line.chars().take_while(|c| *c == marker).count();
+    if width < 3 {
+        return None;
+    }
+    let info = line[width..].trim();
+    if marker == '`' && info.contains('`') {
+        return None;
+    }
+    let language = info.split_whitespace().next().map(str::to_string);
+    Some((marker, width, language))
+}
+
+fn parse_heading(line: &str) -> Option<(u8, String)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    // Optional closing hashes: "## Title ##"
+    let text = rest.trim().trim_end_matches('#').trim_end();
+    Some((level as u8, text.to_string()))
+}
+
+fn parse_underline(line: &str) -> Option<u8> {
+    let line = line.trim_end();
+    if line.chars().all(|c| c == '=') {
+        Some(1)
+    } else if line.chars().all(|c| c == '-') {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+fn is_rule(line: &str) -> bool {
+    let compact: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    compact.len() >= 3
+        && ['-', '*', '_'].iter().any(|m| compact.chars().all(|c| c == *m))
+}
+
+fn parse_list_item(line: &str, indent: usize) -> Option<Block> {
+    let (ordered, number, rest) = if let Some(rest) = line.strip_prefix(['-', '*', '+']) {
+        (false, None, rest)
+    } else {
+        let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+        if digits == 0 || digits > 9 {
+            return None;
+        }
+        let rest = line[digits..].strip_prefix(['.', ')'])?;
+        (true, line[..digits].parse().ok(), rest)
+    };
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    let text = rest.trim();
+    let (checked, text) = match text.get(..3) {
+        Some("[ ]") => (Some(false), text[3..].trim()),
+        Some("[x]") | Some("[X]") => (Some(true), text[3..].trim()),
+        _ => (None, text),
+    };
+    Some(Block::ListItem { ordered, number, depth: indent / 2, checked, text: text.to_string() })
+}
+
+// "| a | b |" -> ["a", "b"]; escaped pipes stay in the cell
+fn split_row(line: &str) -> Vec<String> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = if line.ends_with('|') && !line.ends_with("\\|") { &line[..line.len() - 1] } else { line };
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                cell.push('|');
+                chars.next();
+            }
+            '|' => cells.push(std::mem::take(&mut cell).trim().to_string()),
+            _ => cell.push(c),
+        }
+    }
+    cells.push(cell.trim().to_string());
+    cells
+}
+
+// "|:---|:-:|--:|" -> [Left, Center, Right]
+fn parse_delimiter(line: &str) -> Option<Vec<ColumnAlign>> {
+    if !line.contains('-') {
+        return None;
+    }
+    split_row(line)
+        .iter()
+        .map(|cell| {
+            let left = cell.starts_with(':');
+            let right = cell.ends_with(':');
+            let dashes = cell.trim_matches(':');
+            if dashes.is_empty() || !dashes.chars().all(|c| c == '-') {
+                return None;
+            }
+            Some(match (left, right) {
+                (true, true) => ColumnAlign::Center,
+                (true, false) => ColumnAlign::Left,
+                (false, true) => ColumnAlign::Right,
+                (false, false) => ColumnAlign::None,
+            })
+        })
+        .collect()
+}
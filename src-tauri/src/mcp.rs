@@ -0,0 +1,308 @@
+// MCP - Model Context Protocol server so external AI clients (Claude Desktop, IDEs) can use Regen as a context provider
+// Served by the local API (services/api_server.rs): POST /mcp answers JSON-RPC directly, GET /mcp/sse with
+// POST /mcp/messages is the SSE transport, and `--mcp-stdio` relays stdio clients to the running app.
+// Tools are the agent's tool registry; each call is an approval from source "mcp", so the user is asked
+// unless the tool is in [mcp] trustedTools.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use tokio::sync::mpsc;
+
+use crate::config::ApiConfig;
+use crate::services::api_server;
+use crate::tools;
+
+pub const STDIO_FLAG: &str = "--mcp-stdio";
+pub const TOKEN_ENV: &str = "REGEN_API_TOKEN";
+pub const PORT_ENV: &str = "REGEN_API_PORT";
+
+const PROTOCOL_VERSIONS: &[&str] = &["2025-03-26", "2024-11-05"];
+const MAX_SESSIONS: usize = 16;
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct McpStatus {
+    pub enabled: bool,                 // config.mcp.enabled
+    pub sse_url: Option<String>,       // While the local API is running
+    pub sessions: usize,               // Open SSE connections
+    pub client_config: Value,          // "mcpServers" entry for stdio clients such as Claude Desktop
+}
+
+// SSE sessions: replies are pushed down the stream that announced the session id
+fn sessions() -> &'static Mutex<HashMap<String, mpsc::UnboundedSender<Value>>> {
+    static SESSIONS: OnceLock<Mutex<HashMap<String, mpsc::UnboundedSender<Value>>>> = OnceLock::new();
+    SESSIONS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+pub fn status(app: &tauri::AppHandle) -> Result<McpStatus, McpError> {
+    let config = crate::config::current();
+    let api = api_server::status(app);
+    let token = api_server::token(app, false).map_err(|e| McpError::Token(e.to_string()))?;
+    let command = std::env::current_exe().map(|path| path.display().to_string()).unwrap_or_default();
+    let mut sessions = sessions().lock().unwrap();
+    sessions.retain(|_, sender| !sender.is_closed());
+    Ok(McpStatus {
+        enabled: config.mcp.enabled,
+        sse_url: api.url.filter(|_| config.mcp.enabled).map(|url| format!("{}/mcp/sse", url)),
+        sessions: sessions.len(),
+        client_config: json!({
+            "mcpServers": {
+                "regen": {
+                    "command": command,
+                    "args": [STDIO_FLAG],
+                    "env": { TOKEN_ENV: token, PORT_ENV: config.api.port.to_string() },
+                }
+            }
+        }),
+    })
+}
+
+// Register an SSE stream; None once MAX_SESSIONS are open
+pub fn open_session() -> Option<(String, mpsc::UnboundedReceiver<Value>)> {
+    let mut sessions = sessions().lock().unwrap();
+    sessions.retain(|_, sender| !sender.is_closed());
+    if sessions.len() >= MAX_SESSIONS {
+        return None;
+    }
+    let id = uuid::Uuid::new_v4().simple().to_string();
+    let (sender, receiver) = mpsc::unbounded_channel();
+    sessions.insert(id.clone(), sender);
+    tracing::info!(target: "ai", "MCP: SSE session {} opened", id);
+    Some((id, receiver))
+}
+
+// A message posted for an SSE session; the reply (if any) goes down that session's stream
+pub fn post(app: &tauri::AppHandle, session: &str, message: Value) -> Result<(), McpError> {
+    let sender = {
+        let mut sessions = sessions().lock().unwrap();
+        match sessions.get(session) {
+            Some(sender) if !sender.is_closed() => sender.clone(),
+            Some(_) => {
+                sessions.remove(session);
+                return Err(McpError::UnknownSession(session.to_string()));
+            }
+            None => return Err(McpError::UnknownSession(session.to_string())),
+        }
+    };
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Some(reply) = handle(&app, message).await {
+            let _ = sender.send(reply);
+        }
+    });
+    Ok(())
+}
+
+// One JSON-RPC message or batch; None when nothing is owed back (notifications, responses)
+pub async fn handle(app: &tauri::AppHandle, message: Value) -> Option<Value> {
+    match message {
+        Value::Array(batch) if batch.is_empty() => Some(error(Value::Null, INVALID_REQUEST, "Empty batch")),
+        Value::Array(batch) => {
+            let mut replies = Vec::new();
+            for message in batch {
+                if let Some(reply) = handle_one(app, message).await {
+                    replies.push(reply);
+                }
+            }
+            (!replies.is_empty()).then_some(Value::Array(replies))
+        }
+        message => handle_one(app, message).await,
+    }
+}
+
+pub fn parse_error(message: &str) -> Value {
+    error(Value::Null, PARSE_ERROR, message)
+}
+
+async fn handle_one(app: &tauri::AppHandle, message: Value) -> Option<Value> {
+    let id = message.get("id").cloned();
+    let Some(method) = message.get("method").and_then(Value::as_str) else {
+        // A response to a request we never send, or not JSON-RPC at all
+        let is_response = message.get("result").is_some() || message.get("error").is_some();
+        return (!is_response).then(|| error(id.unwrap_or(Value::Null), INVALID_REQUEST, "Expected a JSON-RPC request"));
+    };
+    let params = message.get("params").cloned().unwrap_or_else(|| json!({}));
+    // Notifications (initialized, cancelled) need no answer and change nothing here
+    let id = id?;
+
+    let result = match method {
+        "initialize" => Ok(initialize(&params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": list_tools() })),
+        "tools/call" => call_tool(app, &params).await,
+        _ => Err((METHOD_NOT_FOUND, format!("Method '{}' is not supported", method))),
+    };
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => error(id, code, &message),
+    })
+}
+
+fn initialize(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str).unwrap_or_default();
+    let version = PROTOCOL_VERSIONS.iter().find(|v| **v == requested).unwrap_or(&PROTOCOL_VERSIONS[0]);
+    let client = params.pointer("/clientInfo/name").and_then(Value::as_str).unwrap_or("unknown client");
+    tracing::info!(target: "ai", "MCP: {} connected (protocol {})", client, version);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": { "listChanged": false } },
+        "serverInfo": { "name": "regen", "version": env!("CARGO_PKG_VERSION") },
+        "instructions": "Context from the user's Regen browser: the open page, browsing history, notes, and quotes. Tool calls may wait for the user to approve them.",
+    })
+}
+
+fn list_tools() -> Vec<Value> {
+    tools::registry()
+        .list()
+        .into_iter()
+        .filter(|info| info.enabled)
+        .map(|info| {
+            json!({
+                "name": info.spec.name,
+                "description": info.spec.description,
+                "inputSchema": info.spec.parameters,
+            })
+        })
+        .collect()
+}
+
+async fn call_tool(app: &tauri::AppHandle, params: &Value) -> Result<Value, (i64, String)> {
+    let name = params
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or_else(|| (INVALID_PARAMS, "tools/call needs a tool name".to_string()))?;
+    if !tools::registry().list().iter().any(|info| info.enabled && info.spec.name == name) {
+        return Err((INVALID_PARAMS, format!("Unknown tool '{}'", name)));
+    }
+    let arguments = params.get("arguments").cloned().unwrap_or(Value::Null);
+    let trusted = crate::config::current().mcp.trusted_tools.iter().any(|t| t == name);
+    let record = crate::approvals::submit_from(app, "mcp", None, name, arguments, Some(!trusted), |action| {
+        tracing::debug!(target: "ai", "MCP: {} waiting for approval ({})", action.tool, action.id);
+    })
+    .await;
+
+    // Tool failures are results the model can read, not protocol errors
+    let text = match (&record.output, &record.error) {
+        (Some(output), None) => serde_json::to_string_pretty(output).unwrap_or_default(),
+        (_, Some(error)) => error.clone(),
+        (None, None) => String::new(),
+    };
+    Ok(json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": !record.ok,
+    }))
+}
+
+fn error(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+// ============================================================================
+// STDIO BRIDGE
+// ============================================================================
+
+// `regen --mcp-stdio`: relay newline-delimited JSON-RPC between stdin/stdout and the running app's POST /mcp.
+// Runs instead of the app; the port and token come from the environment the client config sets.
+pub fn run_stdio_bridge() -> i32 {
+    let port = std::env::var(PORT_ENV).ok().and_then(|p| p.parse::<u16>().ok()).unwrap_or_else(|| ApiConfig::default().port);
+    let Ok(token) = std::env::var(TOKEN_ENV) else {
+        eprintln!("{} is not set; copy the client config from Regen's MCP settings", TOKEN_ENV);
+        return 2;
+    };
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("Failed to start: {}", e);
+            return 1;
+        }
+    };
+    // Localhost only, so never through the user's proxy
+    let client = match reqwest::Client::builder().no_proxy().build() {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("Failed to start: {}", e);
+            return 1;
+        }
+    };
+    let url = format!("http://127.0.0.1:{}/mcp", port);
+
+    runtime.block_on(async move {
+        let (lines, mut incoming) = mpsc::unbounded_channel::<String>();
+        std::thread::spawn(move || {
+            for line in std::io::stdin().lines().map_while(Result::ok) {
+                if lines.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        // Calls run side by side so a ping isn't stuck behind a tool call waiting for approval
+        let mut calls = tokio::task::JoinSet::new();
+        while let Some(line) = incoming.recv().await {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (client, url, token) = (client.clone(), url.clone(), token.clone());
+            calls.spawn(async move {
+                if let Some(reply) = forward(&client, &url, &token, &line).await {
+                    use std::io::Write;
+                    let mut stdout = std::io::stdout().lock();
+                    let _ = writeln!(stdout, "{}", reply);
+                    let _ = stdout.flush();
+                }
+            });
+        }
+        while calls.join_next().await.is_some() {}
+    });
+    0
+}
+
+async fn forward(client: &reqwest::Client, url: &str, token: &str, line: &str) -> Option<Value> {
+    let message: Value = match serde_json::from_str(line) {
+        Ok(message) => message,
+        Err(e) => return Some(parse_error(&e.to_string())),
+    };
+    let failed = |reason: String| {
+        let id = message.get("id").cloned()?;
+        Some(error(id, INVALID_REQUEST, &reason))
+    };
+    let response = match client.post(url).bearer_auth(token).json(&message).send().await {
+        Ok(response) => response,
+        Err(e) => return failed(format!("Regen is not reachable (is it running with the local API and MCP enabled?): {}", e)),
+    };
+    let status = response.status();
+    if status == reqwest::StatusCode::ACCEPTED {
+        return None;
+    }
+    let body: Value = response.json().await.unwrap_or(Value::Null);
+    if status.is_success() {
+        return Some(body);
+    }
+    let reason = body.get("error").and_then(Value::as_str).unwrap_or(status.as_str()).to_string();
+    failed(reason)
+}
+
+#[derive(Debug, Clone)]
+pub enum McpError {
+    UnknownSession(String),
+    Token(String),
+}
+
+impl std::fmt::Display for McpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            McpError::UnknownSession(id) => write!(f, "No open MCP session '{}'", id),
+            McpError::Token(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for McpError {}
@@ -0,0 +1,234 @@
+// Mode Profiles - Resource and background-work settings per app mode (Browse, Research, Trade, ...)
+// The active tab's mode picks the profile; [modes.<name>] tables in config.toml override the defaults
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use tauri::{Emitter, Manager};
+
+use crate::state::{AppMode, AppState};
+
+pub const MODES: &[&str] = &["browse", "research", "trade", "games", "docs", "images", "threats", "graphmind"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ModeProfile {
+    pub mode: String,
+    pub budget_mb: Option<u64>,        // Memory budget while active; None keeps [resources] budgetMb
+    pub llm_concurrency: usize,        // Model calls allowed to run at once
+    pub background_agents: bool,       // Index resync, frecency recompute, clipboard suggestions
+    pub keep_alive: bool,              // Tabs in this mode are never discarded (live quotes, sockets)
+    pub freeze_after_minutes: Option<u32>, // Idle time before a tab in this mode is discarded; None uses the default
+}
+
+// A [modes.<name>] table; unset fields keep the built-in profile
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct ModeOverride {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub budget_mb: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub llm_concurrency: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background_agents: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_alive: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub freeze_after_minutes: Option<u32>,
+}
+
+// Emitted as "mode-profile-changed" when the active profile switches
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveProfile {
+    pub profile: ModeProfile,
+    pub budget_bytes: u64,
+}
+
+fn builtin(mode: &str) -> ModeProfile {
+    let mut profile = ModeProfile {
+        mode: mode.to_string(),
+        budget_mb: None,
+        llm_concurrency: 1,
+        background_agents: true,
+        keep_alive: false,
+        freeze_after_minutes: None,
+    };
+    match mode {
+        // Parallel summaries and searches across many open sources
+        "research" => {
+            profile.llm_concurrency = 3;
+            profile.freeze_after_minutes = Some(30);
+        }
+        // Charts and order books must keep streaming in background tabs
+        "trade" => profile.keep_alive = true,
+        // Nothing competes with the game for CPU
+        "games" => {
+            profile.background_agents = false;
+            profile.freeze_after_minutes = Some(5);
+        }
+        "docs" | "graphmind" => profile.llm_concurrency = 2,
+        _ => {}
+    }
+    profile
+}
+
+// Effective profile for a mode name ("Research" or "research"); unknown modes get Browse's
+pub fn profile_for(mode: &str) -> ModeProfile {
+    let key = mode.trim().to_lowercase();
+    let key = if MODES.contains(&key.as_str()) { key } else { "browse".to_string() };
+    let mut profile = builtin(&key);
+    if let Some(o) = crate::config::current().modes.get(&key) {
+        profile.budget_mb = o.budget_mb.or(profile.budget_mb);
+        profile.llm_concurrency = o.llm_concurrency.unwrap_or(profile.llm_concurrency);
+        profile.background_agents = o.background_agents.unwrap_or(profile.background_agents);
+        profile.keep_alive = o.keep_alive.unwrap_or(profile.keep_alive);
+        profile.freeze_after_minutes = o.freeze_after_minutes.or(profile.freeze_after_minutes);
+    }
+    profile
+}
+
+pub fn list() -> Vec<ModeProfile> {
+    MODES.iter().map(|m| profile_for(m)).collect()
+}
+
+// Check [modes] tables: known mode names and sane limits. Errors carry the offending key.
+pub fn validate(overrides: &BTreeMap<String, ModeOverride>) -> Result<(), (String, String)> {
+    for (mode, o) in overrides {
+        if !MODES.contains(&mode.as_str()) {
+            return Err((mode.clone(), format!("unknown mode (expected one of {})", MODES.join(", "))));
+        }
+        if o.llm_concurrency.is_some_and(|n| !(1..=8).contains(&n)) {
+            return Err((format!("{}.llmConcurrency", mode), "must be between 1 and 8".to_string()));
+        }
+        if o.budget_mb.is_some_and(|mb| mb < 512) {
+            return Err((format!("{}.budgetMb", mode), "must be at least 512".to_string()));
+        }
+        if o.freeze_after_minutes == Some(0) {
+            return Err((format!("{}.freezeAfterMinutes", mode), "must be at least 1".to_string()));
+        }
+    }
+    Ok(())
+}
+
+struct Active {
+    profile: Mutex<ModeProfile>,
+    llm_limit: AtomicUsize,
+    llm_in_flight: Mutex<usize>,
+    llm_released: tokio::sync::Notify,
+}
+
+fn active() -> &'static Active {
+    static ACTIVE: OnceLock<Active> = OnceLock::new();
+    ACTIVE.get_or_init(|| {
+        let profile = builtin("browse");
+        Active {
+            llm_limit: AtomicUsize::new(profile.llm_concurrency),
+            profile: Mutex::new(profile),
+            llm_in_flight: Mutex::new(0),
+            llm_released: tokio::sync::Notify::new(),
+        }
+    })
+}
+
+pub fn current() -> ModeProfile {
+    active().profile.lock().unwrap().clone()
+}
+
+// The active tab changed; switch profiles if its mode differs from the current one
+pub fn on_tab_activated(app: &tauri::AppHandle, tab_mode: &str) {
+    if current().mode != tab_mode.trim().to_lowercase() {
+        apply(app, tab_mode);
+    }
+}
+
+// Make a mode's profile the active one (also re-run after config changes)
+pub fn apply(app: &tauri::AppHandle, mode: &str) {
+    let profile = profile_for(mode);
+    if let Some(state) = app.try_state::<AppState>() {
+        state.set_active_mode(parse_mode(&profile.mode));
+    }
+
+    let budget_bytes = match profile.budget_mb {
+        Some(mb) => mb * 1024 * 1024,
+        None => crate::resources::configured_budget(),
+    };
+    if let Some(resources) = app.try_state::<crate::resources::ResourceManager>() {
+        resources.set_budget(budget_bytes);
+    }
+
+    let state = active();
+    state.llm_limit.store(profile.llm_concurrency.max(1), Ordering::SeqCst);
+    state.llm_released.notify_waiters();
+    let changed = {
+        let mut current = state.profile.lock().unwrap();
+        let changed = *current != profile;
+        *current = profile.clone();
+        changed
+    };
+    if changed {
+        tracing::info!(target: "app", "Mode profile: {} (llm {}, background {})", profile.mode, profile.llm_concurrency, profile.background_agents);
+        let _ = app.emit("mode-profile-changed", ActiveProfile { profile, budget_bytes });
+    }
+}
+
+// Set from the tray's "Pause background services"; holds until cleared, whatever the mode
+static USER_PAUSED: AtomicBool = AtomicBool::new(false);
+
+pub fn set_user_paused(paused: bool) {
+    USER_PAUSED.store(paused, Ordering::SeqCst);
+}
+
+pub fn user_paused() -> bool {
+    USER_PAUSED.load(Ordering::SeqCst)
+}
+
+// Pollers that aren't essential skip their work during focus sessions, in modes that turn them off, and while paused by the user
+pub fn background_paused() -> bool {
+    user_paused() || crate::focus::is_active() || !current().background_agents
+}
+
+// Held for the duration of a model call
+pub struct LlmSlot;
+
+impl Drop for LlmSlot {
+    fn drop(&mut self) {
+        let state = active();
+        *state.llm_in_flight.lock().unwrap() -= 1;
+        state.llm_released.notify_waiters();
+    }
+}
+
+// Wait until the active profile's llm_concurrency allows another call
+pub async fn llm_slot() -> LlmSlot {
+    let state = active();
+    loop {
+        // Registered before checking, so a release in between isn't missed
+        let released = state.llm_released.notified();
+        {
+            let mut in_flight = state.llm_in_flight.lock().unwrap();
+            if *in_flight < state.llm_limit.load(Ordering::SeqCst) {
+                *in_flight += 1;
+                return LlmSlot;
+            }
+        }
+        released.await;
+    }
+}
+
+fn parse_mode(mode: &str) -> AppMode {
+    match mode {
+        "research" => AppMode::Research,
+        "trade" => AppMode::Trade,
+        "games" => AppMode::Games,
+        "docs" => AppMode::Docs,
+        "images" => AppMode::Images,
+        "threats" => AppMode::Threats,
+        "graphmind" => AppMode::GraphMind,
+        _ => AppMode::Browse,
+    }
+}
@@ -0,0 +1,214 @@
+// Network Audit - Record of every request the backend sends, for "what did the app contact?"
+// Kept in memory for the session; also written to SQLite when [networkAudit] persist is on and history is allowed
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use tauri::Manager;
+
+use crate::db::Database;
+use crate::privacy::PrivacyEnforcer;
+
+const RING_CAPACITY: usize = 2000;
+const DEFAULT_LIMIT: usize = 200;
+const MAX_LIMIT: usize = 2000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub id: u64,                       // Session-local sequence (row id for entries read back from SQLite)
+    pub timestamp: i64,                // Unix ms when the request was sent
+    pub method: String,
+    pub host: String,                  // Host plus non-default port
+    pub path: String,                  // Query strings are never recorded (they can carry keys and search terms)
+    pub purpose: String,               // Tag given by the calling feature ("web-search", "embeddings", ...)
+    pub status: Option<u16>,
+    pub bytes_sent: u64,
+    pub bytes_received: Option<u64>,   // From Content-Length; None for streamed/chunked responses
+    pub duration_ms: u64,
+    pub privacy_mode: String,
+    pub local: bool,                   // Loopback service (Ollama, Meilisearch, the Regen server)
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct AuditFilter {
+    pub host: Option<String>,          // Substring match
+    pub purpose: Option<String>,
+    pub method: Option<String>,
+    pub privacy_mode: Option<String>,
+    pub since: Option<i64>,            // Unix ms
+    pub errors_only: bool,
+    pub include_local: bool,
+    pub history: bool,                 // Read persisted entries from SQLite instead of this session's buffer
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct HostSummary {
+    pub host: String,
+    pub requests: u64,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub purposes: Vec<String>,
+    pub last_seen: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct AuditList {
+    pub entries: Vec<AuditEntry>,      // Newest first, up to the limit
+    pub matched: usize,
+    pub hosts: Vec<HostSummary>,       // Every distinct host among the matches, busiest first
+    pub persisting: bool,
+    pub local_only: bool,              // Remote requests are being refused (they appear with an error and no status)
+}
+
+struct Audit {
+    ring: Mutex<VecDeque<AuditEntry>>,
+    next_id: AtomicU64,
+    context: OnceLock<(tauri::AppHandle, Database)>,
+}
+
+fn audit() -> &'static Audit {
+    static AUDIT: OnceLock<Audit> = OnceLock::new();
+    AUDIT.get_or_init(|| Audit {
+        ring: Mutex::new(VecDeque::with_capacity(RING_CAPACITY)),
+        next_id: AtomicU64::new(1),
+        context: OnceLock::new(),
+    })
+}
+
+// Hook up privacy mode lookups and persistence; old rows past the retention window are dropped
+pub fn init(app: tauri::AppHandle, db: Database) {
+    let retention_days = crate::config::current().network_audit.retention_days;
+    let cutoff = chrono::Utc::now().timestamp_millis() - i64::from(retention_days) * 86_400_000;
+    match db.network_audit_prune(cutoff) {
+        Ok(0) => {}
+        Ok(n) => tracing::debug!(target: "db", "Network audit: Pruned {} old entries", n),
+        Err(e) => tracing::warn!(target: "db", "Network audit: Failed to prune: {}", e),
+    }
+    let _ = audit().context.set((app, db));
+}
+
+// Called by http::Client for every attempt that reaches the network
+pub fn record(mut entry: AuditEntry) {
+    let state = audit();
+    entry.id = state.next_id.fetch_add(1, Ordering::Relaxed);
+    let (mode, persist) = match state.context.get() {
+        Some((app, db)) => {
+            let (mode, history) = app
+                .try_state::<Mutex<PrivacyEnforcer>>()
+                .map(|p| {
+                    let enforcer = p.lock().unwrap();
+                    (format!("{:?}", enforcer.get_policy().mode).to_lowercase(), enforcer.can_save_history())
+                })
+                .unwrap_or_else(|| ("normal".to_string(), false));
+            (mode, (history && crate::config::current().network_audit.persist).then_some(db))
+        }
+        None => ("normal".to_string(), None),
+    };
+    entry.privacy_mode = mode;
+
+    if let Some(db) = persist {
+        if let Err(e) = db.network_audit_insert(&entry) {
+            tracing::warn!(target: "db", "Network audit: Failed to store entry: {}", e);
+        }
+    }
+    let mut ring = state.ring.lock().unwrap();
+    if ring.len() == RING_CAPACITY {
+        ring.pop_front();
+    }
+    ring.push_back(entry);
+}
+
+pub fn list(filter: &AuditFilter) -> Result<AuditList, AuditError> {
+    let source: Vec<AuditEntry> = if filter.history {
+        let (_, db) = audit().context.get().ok_or(AuditError::NotReady)?;
+        db.network_audit_since(filter.since.unwrap_or(0)).map_err(|e| AuditError::Storage(e.to_string()))?
+    } else {
+        audit().ring.lock().unwrap().iter().cloned().collect()
+    };
+
+    let host = filter.host.as_deref().map(str::to_lowercase);
+    let mut matched: Vec<AuditEntry> = source
+        .into_iter()
+        .filter(|e| filter.include_local || !e.local)
+        .filter(|e| !filter.errors_only || e.error.is_some() || e.status.is_some_and(|s| s >= 400))
+        .filter(|e| host.as_deref().is_none_or(|h| e.host.contains(h)))
+        .filter(|e| filter.purpose.as_deref().is_none_or(|p| e.purpose.eq_ignore_ascii_case(p)))
+        .filter(|e| filter.method.as_deref().is_none_or(|m| e.method.eq_ignore_ascii_case(m)))
+        .filter(|e| filter.privacy_mode.as_deref().is_none_or(|m| e.privacy_mode.eq_ignore_ascii_case(m)))
+        .filter(|e| filter.since.is_none_or(|since| e.timestamp >= since))
+        .collect();
+    matched.sort_by(|a, b| b.timestamp.cmp(&a.timestamp).then(b.id.cmp(&a.id)));
+
+    let mut hosts: BTreeMap<&str, HostSummary> = BTreeMap::new();
+    for entry in &matched {
+        let summary = hosts.entry(&entry.host).or_insert_with(|| HostSummary {
+            host: entry.host.clone(),
+            requests: 0,
+            bytes_sent: 0,
+            bytes_received: 0,
+            purposes: Vec::new(),
+            last_seen: entry.timestamp,
+        });
+        summary.requests += 1;
+        summary.bytes_sent += entry.bytes_sent;
+        summary.bytes_received += entry.bytes_received.unwrap_or(0);
+        summary.last_seen = summary.last_seen.max(entry.timestamp);
+        if !summary.purposes.contains(&entry.purpose) {
+            summary.purposes.push(entry.purpose.clone());
+        }
+    }
+    let mut hosts: Vec<HostSummary> = hosts.into_values().collect();
+    hosts.sort_by(|a, b| b.requests.cmp(&a.requests).then_with(|| a.host.cmp(&b.host)));
+
+    let total = matched.len();
+    matched.truncate(filter.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT));
+    Ok(AuditList {
+        entries: matched,
+        matched: total,
+        hosts,
+        persisting: crate::config::current().network_audit.persist,
+        local_only: crate::http::local_only(),
+    })
+}
+
+// Empty the session buffer and the stored history
+pub fn clear() -> Result<usize, AuditError> {
+    let mut cleared = {
+        let mut ring = audit().ring.lock().unwrap();
+        let n = ring.len();
+        ring.clear();
+        n
+    };
+    if let Some((_, db)) = audit().context.get() {
+        cleared += db.network_audit_prune(i64::MAX).map_err(|e| AuditError::Storage(e.to_string()))?;
+    }
+    Ok(cleared)
+}
+
+#[derive(Debug, Clone)]
+pub enum AuditError {
+    NotReady,
+    Storage(String),
+}
+
+impl std::fmt::Display for AuditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuditError::NotReady => write!(f, "Network audit history is not available yet"),
+            AuditError::Storage(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AuditError {}
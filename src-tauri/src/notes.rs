@@ -0,0 +1,107 @@
+// Notes - Tagged notes with URL backlinks
+// Storage lives in db.rs (notes, notes_fts, note_links); this module owns the shape and link extraction
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct Note {
+    pub id: String,
+    pub title: Option<String>,
+    pub content: String,
+    pub url: Option<String>,           // Page the note was taken on
+    pub language: String,
+    pub tags: Vec<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub backlinks: Vec<NoteRef>,       // Other notes referencing the same URLs
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct NoteRef {
+    pub id: String,
+    pub title: Option<String>,
+    pub url: String,                   // The shared URL
+}
+
+impl Note {
+    pub fn new(content: String, title: Option<String>, url: Option<String>, tags: Vec<String>) -> Self {
+        let now = chrono::Utc::now().timestamp();
+        let language = crate::language::detect_language(&content).language;
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            title: title.filter(|t| !t.trim().is_empty()),
+            content,
+            url: url.filter(|u| !u.trim().is_empty()),
+            language,
+            tags: normalize_tags(tags),
+            created_at: now,
+            updated_at: now,
+            backlinks: Vec::new(),
+        }
+    }
+
+    // Every URL the note points at: its source page plus links in the body
+    pub fn referenced_urls(&self) -> Vec<String> {
+        static URL_RE: OnceLock<Regex> = OnceLock::new();
+        let re = URL_RE.get_or_init(|| Regex::new(r#"https?://[^\s<>"')\]]+"#).unwrap());
+
+        let mut urls: Vec<String> = self
+            .url
+            .iter()
+            .map(|u| u.as_str())
+            .chain(re.find_iter(&self.content).map(|m| m.as_str()))
+            .filter_map(normalize_url)
+            .collect();
+        urls.sort();
+        urls.dedup();
+        urls
+    }
+}
+
+// Lowercased, trimmed, deduplicated; a leading '#' is dropped
+pub fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut tags: Vec<String> = tags
+        .into_iter()
+        .map(|t| t.trim().trim_start_matches('#').to_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+// Canonical form for link matching: no fragment, no trailing slash, no trailing punctuation
+pub fn normalize_url(raw: &str) -> Option<String> {
+    let raw = raw.trim().trim_end_matches(['.', ',', ';', ':']);
+    let mut url = url::Url::parse(raw).ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    url.set_fragment(None);
+    let mut normalized = url.to_string();
+    if normalized.ends_with('/') && url.path() == "/" && url.query().is_none() {
+        normalized.pop();
+    }
+    Some(normalized)
+}
+
+// Turn free text into a safe FTS5 query (each word quoted, last word prefix-matched)
+pub fn fts_query(query: &str) -> Option<String> {
+    let words: Vec<String> = query
+        .split_whitespace()
+        .map(|w| w.replace('"', ""))
+        .filter(|w| !w.is_empty())
+        .map(|w| format!("\"{}\"", w))
+        .collect();
+    if words.is_empty() {
+        return None;
+    }
+    Some(format!("{}*", words.join(" ")))
+}
@@ -0,0 +1,215 @@
+// Notifications - Native OS notifications for downloads, price alerts, finished jobs and scheduled work
+// Desktop toasts can't carry buttons or report clicks, so actions are offered in the in-app list and
+// routed back through `respond` as "notification:action". Inside the do-not-disturb window nothing pops up.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::config::NotificationsConfig;
+use crate::privacy::PrivacyEnforcer;
+use crate::state::PrivacyMode;
+
+// Recent notifications kept for the in-app list and for routing responses
+const MAX_RECENT: usize = 100;
+const MAX_ACTIONS: usize = 3;
+const MAX_TITLE_CHARS: usize = 200;
+const MAX_BODY_CHARS: usize = 1_000;
+// Reported as the action when the notification itself (not a button) is clicked
+pub const CLICK: &str = "click";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationAction {
+    pub id: String,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "kebab-case")]
+pub enum NotificationKind {
+    #[default]
+    General,
+    Download,
+    PriceAlert,
+    Job,                               // A long-running task (backtest, research run) finished
+    Schedule,                          // Scheduled work ran (weekly digest, ...)
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotificationRequest {
+    pub title: String,
+    pub body: String,
+    pub actions: Vec<NotificationAction>,
+    pub kind: NotificationKind,
+    pub urgent: bool,                  // Shown even inside the do-not-disturb window
+    pub payload: Option<serde_json::Value>, // Handed back with the response (download id, run id, ...)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct Notification {
+    pub id: String,
+    pub title: String,
+    pub body: String,
+    pub actions: Vec<NotificationAction>,
+    pub kind: NotificationKind,
+    pub payload: Option<serde_json::Value>,
+    pub delivered: bool,               // A native toast was shown
+    pub silenced: bool,                // Held back by do-not-disturb or notifications.enabled = false
+    pub response: Option<String>,      // Action id, or "click"
+    pub created_at: i64,
+}
+
+// Emitted as "notification:action"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationResponse {
+    pub id: String,
+    pub action: String,
+    pub kind: NotificationKind,
+    pub payload: Option<serde_json::Value>,
+}
+
+static RECENT: Mutex<VecDeque<Notification>> = Mutex::new(VecDeque::new());
+
+// Show a notification (unless silenced) and announce it as "notification:posted"
+pub fn notify(app: &tauri::AppHandle, request: NotificationRequest) -> Result<Notification, NotifyError> {
+    let title = request.title.trim().to_string();
+    if title.is_empty() || title.chars().count() > MAX_TITLE_CHARS {
+        return Err(NotifyError::Invalid(format!("title must be 1-{} characters", MAX_TITLE_CHARS)));
+    }
+    let body: String = request.body.trim().chars().take(MAX_BODY_CHARS).collect();
+    if request.actions.len() > MAX_ACTIONS {
+        return Err(NotifyError::Invalid(format!("at most {} actions", MAX_ACTIONS)));
+    }
+    for (i, action) in request.actions.iter().enumerate() {
+        if action.id.trim().is_empty() || action.title.trim().is_empty() || action.id == CLICK {
+            return Err(NotifyError::Invalid(format!("action '{}' needs an id (not \"{}\") and a title", action.id, CLICK)));
+        }
+        if request.actions[..i].iter().any(|a| a.id == action.id) {
+            return Err(NotifyError::Invalid(format!("duplicate action '{}'", action.id)));
+        }
+    }
+
+    let config = crate::config::current().notifications;
+    let silenced = !config.enabled || (!request.urgent && in_dnd(&config, chrono::Local::now().time()));
+    let mut notification = Notification {
+        id: uuid::Uuid::new_v4().to_string(),
+        title,
+        body,
+        actions: request.actions,
+        kind: request.kind,
+        payload: request.payload,
+        delivered: false,
+        silenced,
+        response: None,
+        created_at: chrono::Utc::now().timestamp(),
+    };
+    if !silenced {
+        // The OS keeps its own history; in Ghost mode it only learns that something happened
+        let ghost = app
+            .try_state::<Mutex<PrivacyEnforcer>>()
+            .is_some_and(|enforcer| matches!(enforcer.lock().unwrap().get_policy().mode, PrivacyMode::Ghost));
+        let mut toast = app.notification().builder().title(if ghost { "Regen" } else { notification.title.as_str() });
+        if ghost {
+            toast = toast.body("Open Regen to view");
+        } else if !notification.body.is_empty() {
+            toast = toast.body(&notification.body);
+        }
+        match toast.show() {
+            Ok(()) => notification.delivered = true,
+            Err(e) => tracing::warn!(target: "app", "Notifications: Failed to show '{}': {}", notification.title, e),
+        }
+    }
+
+    {
+        let mut recent = RECENT.lock().unwrap();
+        recent.push_front(notification.clone());
+        recent.truncate(MAX_RECENT);
+    }
+    let _ = app.emit("notification:posted", &notification);
+    Ok(notification)
+}
+
+// For backend events (downloads, jobs, schedules): failures are logged rather than returned
+pub fn post(app: &tauri::AppHandle, request: NotificationRequest) {
+    if let Err(e) = notify(app, request) {
+        tracing::warn!(target: "app", "Notifications: {}", e);
+    }
+}
+
+// Route a click (action None) or action button back to whoever posted the notification
+pub fn respond(app: &tauri::AppHandle, id: &str, action: Option<&str>) -> Result<NotificationResponse, NotifyError> {
+    let action = action.unwrap_or(CLICK);
+    let response = {
+        let mut recent = RECENT.lock().unwrap();
+        let notification = recent
+            .iter_mut()
+            .find(|n| n.id == id)
+            .ok_or_else(|| NotifyError::NotFound(id.to_string()))?;
+        if action != CLICK && !notification.actions.iter().any(|a| a.id == action) {
+            return Err(NotifyError::Invalid(format!("notification has no action '{}'", action)));
+        }
+        notification.response = Some(action.to_string());
+        NotificationResponse {
+            id: notification.id.clone(),
+            action: action.to_string(),
+            kind: notification.kind,
+            payload: notification.payload.clone(),
+        }
+    };
+    let _ = app.emit("notification:action", &response);
+    Ok(response)
+}
+
+// Newest first
+pub fn list(limit: usize) -> Vec<Notification> {
+    RECENT.lock().unwrap().iter().take(limit).cloned().collect()
+}
+
+pub fn clear() {
+    RECENT.lock().unwrap().clear();
+}
+
+pub fn in_dnd(config: &NotificationsConfig, now: chrono::NaiveTime) -> bool {
+    let parse = |value: &Option<String>| value.as_deref().and_then(|v| chrono::NaiveTime::parse_from_str(v, "%H:%M").ok());
+    let (Some(start), Some(end)) = (parse(&config.dnd_start), parse(&config.dnd_end)) else {
+        return false;
+    };
+    if start <= end {
+        now >= start && now < end
+    } else {
+        // Wraps midnight, e.g. 22:00-07:00
+        now >= start || now < end
+    }
+}
+
+// Finished background work is only worth a notification when the user is looking elsewhere
+pub fn app_focused(app: &tauri::AppHandle) -> bool {
+    app.webview_windows().values().any(|w| w.is_focused().unwrap_or(false))
+}
+
+#[derive(Debug, Clone)]
+pub enum NotifyError {
+    Invalid(String),
+    NotFound(String),
+}
+
+impl std::fmt::Display for NotifyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotifyError::Invalid(e) => write!(f, "Invalid notification: {}", e),
+            NotifyError::NotFound(id) => write!(f, "Notification {} not found (it may have expired)", id),
+        }
+    }
+}
+
+impl std::error::Error for NotifyError {}
@@ -0,0 +1,183 @@
+// OHLC - Daily price bars from Yahoo Finance, cached in SQLite so replays (backtests, charts) work offline
+// Index names traders use (NIFTY, BANKNIFTY) and "NSE:" tickers are mapped to Yahoo's symbols
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::db::Database;
+
+const CHART_URL: &str = "https://query1.finance.yahoo.com/v8/finance/chart/";
+const FETCH_TIMEOUT: Duration = Duration::from_secs(20);
+pub const DAILY: &str = "1d";
+// Weekends and holidays: cached bars this close to the requested edges count as covering it
+const EDGE_SLACK_SECS: i64 = 5 * 86_400;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bar {
+    pub time: i64,                     // Session start (unix seconds)
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct Quote {
+    pub symbol: String,                // As requested, uppercased
+    pub price: f64,                    // Latest daily close
+    pub previous_close: Option<f64>,
+    pub change: Option<f64>,
+    pub change_percent: Option<f64>,
+    pub time: i64,                     // Session of `price`
+}
+
+pub fn yahoo_symbol(symbol: &str) -> String {
+    let symbol = symbol.trim().to_uppercase();
+    match symbol.as_str() {
+        "NIFTY" | "NIFTY50" | "NSE:NIFTY" => "^NSEI".to_string(),
+        "BANKNIFTY" | "NIFTYBANK" | "NSE:BANKNIFTY" | "NSE:NIFTYBANK" => "^NSEBANK".to_string(),
+        "FINNIFTY" => "NIFTY_FIN_SERVICE.NS".to_string(),
+        "SENSEX" => "^BSESN".to_string(),
+        s => match s.strip_prefix("NSE:") {
+            Some(ticker) => format!("{}.NS", ticker),
+            None => s.to_string(),
+        },
+    }
+}
+
+// Latest daily close and the change from the session before; as delayed as the bars behind it
+pub async fn quote(db: &Database, symbol: &str) -> Result<Quote, OhlcError> {
+    let now = chrono::Utc::now().timestamp();
+    let bars = daily_bars(db, symbol, now - 2 * EDGE_SLACK_SECS, now, false).await?;
+    let last = bars.last().ok_or_else(|| OhlcError::NoData(symbol.to_string()))?;
+    let previous_close = bars.len().checked_sub(2).map(|i| bars[i].close);
+    let change = previous_close.map(|previous| last.close - previous);
+    Ok(Quote {
+        symbol: symbol.trim().to_uppercase(),
+        price: last.close,
+        previous_close,
+        change,
+        change_percent: previous_close.zip(change).filter(|(previous, _)| *previous != 0.0).map(|(previous, change)| change / previous * 100.0),
+        time: last.time,
+    })
+}
+
+// Daily bars in [from, to], from the cache when it covers the range, otherwise fetched (and cached when `store`)
+pub async fn daily_bars(db: &Database, symbol: &str, from: i64, to: i64, store: bool) -> Result<Vec<Bar>, OhlcError> {
+    let symbol = yahoo_symbol(symbol);
+    if symbol.is_empty() || symbol.len() > 30 {
+        return Err(OhlcError::InvalidSymbol(symbol));
+    }
+    let cached = {
+        let (db, symbol) = (db.clone(), symbol.clone());
+        tauri::async_runtime::spawn_blocking(move || db.ohlc_load(&symbol, DAILY, from, to))
+            .await
+            .map_err(|e| OhlcError::Storage(e.to_string()))?
+            .map_err(|e| OhlcError::Storage(e.to_string()))?
+    };
+    let until = to.min(chrono::Utc::now().timestamp());
+    let covered = matches!(
+        (cached.first(), cached.last()),
+        (Some(first), Some(last)) if first.time <= from + EDGE_SLACK_SECS && last.time >= until - EDGE_SLACK_SECS
+    );
+    if covered {
+        return Ok(cached);
+    }
+    if crate::connectivity::is_offline() {
+        return if cached.is_empty() { Err(OhlcError::Offline) } else { Ok(cached) };
+    }
+
+    let bars = match fetch(&symbol, from, to).await {
+        Ok(bars) => bars,
+        Err(e) if !cached.is_empty() => {
+            tracing::debug!(target: "app", "OHLC: {} refresh failed, using {} cached bars: {}", symbol, cached.len(), e);
+            return Ok(cached);
+        }
+        Err(e) => return Err(e),
+    };
+    if store {
+        let (db, symbol, saved) = (db.clone(), symbol.clone(), bars.clone());
+        tauri::async_runtime::spawn_blocking(move || {
+            if let Err(e) = db.ohlc_save(&symbol, DAILY, &saved) {
+                tracing::warn!(target: "db", "OHLC: Failed to cache {} bars: {}", symbol, e);
+            }
+        });
+    }
+    Ok(bars)
+}
+
+// {"chart":{"result":[{"timestamp":[..],"indicators":{"quote":[{"open":[..],"high":[..],"low":[..],"close":[..],"volume":[..]}]}}],"error":null}}
+async fn fetch(symbol: &str, from: i64, to: i64) -> Result<Vec<Bar>, OhlcError> {
+    let client = crate::http::Client::new(FETCH_TIMEOUT).with_purpose("market-data");
+    let url = format!("{}{}", CHART_URL, url::form_urlencoded::byte_serialize(symbol.as_bytes()).collect::<String>());
+    let request = client.get(url).query(&[
+        ("period1", from.to_string()),
+        ("period2", to.to_string()),
+        ("interval", DAILY.to_string()),
+        ("events", "history".to_string()),
+    ]);
+    let response = client.send(request).await.map_err(|e| OhlcError::Provider(e.to_string()))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(OhlcError::NoData(symbol.to_string()));
+    }
+    if !response.status().is_success() {
+        return Err(OhlcError::Provider(format!("HTTP {}", response.status().as_u16())));
+    }
+    let body: serde_json::Value = response.json().await.map_err(|e| OhlcError::Provider(e.to_string()))?;
+    let bars = parse_chart(&body);
+    if bars.is_empty() {
+        return Err(OhlcError::NoData(symbol.to_string()));
+    }
+    Ok(bars)
+}
+
+fn parse_chart(body: &serde_json::Value) -> Vec<Bar> {
+    let Some(result) = body.pointer("/chart/result/0") else { return Vec::new() };
+    let times = result.get("timestamp").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+    let quote = result.pointer("/indicators/quote/0");
+    let column = |name: &str| quote.and_then(|q| q.get(name)).and_then(|v| v.as_array()).cloned().unwrap_or_default();
+    let (open, high, low, close, volume) = (column("open"), column("high"), column("low"), column("close"), column("volume"));
+    let at = |values: &[serde_json::Value], i: usize| values.get(i).and_then(|v| v.as_f64());
+    times
+        .iter()
+        .enumerate()
+        // Halted sessions come back as nulls
+        .filter_map(|(i, time)| {
+            Some(Bar {
+                time: time.as_i64()?,
+                open: at(&open, i)?,
+                high: at(&high, i)?,
+                low: at(&low, i)?,
+                close: at(&close, i)?,
+                volume: at(&volume, i).unwrap_or(0.0),
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub enum OhlcError {
+    InvalidSymbol(String),
+    NoData(String),
+    Offline,                           // Nothing cached for the range and no network to fetch it
+    Provider(String),
+    Storage(String),
+}
+
+impl std::fmt::Display for OhlcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OhlcError::InvalidSymbol(symbol) => write!(f, "'{}' is not a ticker symbol", symbol),
+            OhlcError::NoData(symbol) => write!(f, "No price history for {}", symbol),
+            OhlcError::Offline => write!(f, "No cached price history for this range and the network is offline"),
+            OhlcError::Provider(e) => write!(f, "Price history provider failed: {}", e),
+            OhlcError::Storage(e) => write!(f, "Storage error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for OhlcError {}
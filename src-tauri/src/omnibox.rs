@@ -0,0 +1,306 @@
+// Omnibox - Address bar suggestions from open tabs, bookmarks, history, and remote search
+// Local sources answer within a strict budget; remote suggestions follow as an "omnibox:remote" event
+// Calculator-style queries also get an instant answer (see instant_answers.rs)
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+use crate::browser::Tab;
+use crate::db::Database;
+use crate::http;
+use crate::instant_answers::{self, InstantAnswer};
+
+// Local sources must answer within this; whatever misses it is dropped for this keystroke
+pub const LOCAL_BUDGET: Duration = Duration::from_millis(30);
+const REMOTE_TIMEOUT: Duration = Duration::from_secs(2);
+const MAX_SUGGESTIONS: usize = 8;
+const CANDIDATES_PER_SOURCE: usize = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub enum SuggestionSource {
+    OpenTab,
+    Bookmark,
+    History,
+    Remote,
+}
+
+impl SuggestionSource {
+    // Per-source weight applied on top of match quality
+    fn weight(&self) -> f64 {
+        match self {
+            SuggestionSource::OpenTab => 1.5,
+            SuggestionSource::Bookmark => 1.25,
+            SuggestionSource::History => 1.0,
+            SuggestionSource::Remote => 0.6,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct Suggestion {
+    pub source: SuggestionSource,
+    pub title: String,
+    pub url: Option<String>,           // None for remote query suggestions
+    pub query: Option<String>,         // Search text for remote suggestions
+    pub tab_id: Option<String>,        // "Switch to tab" target
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct OmniboxResponse {
+    pub request_id: u64,               // Matches the later "omnibox:remote" event
+    pub input: String,
+    pub suggestions: Vec<Suggestion>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub answer: Option<InstantAnswer>, // Calculator/conversion/date/time result shown above the list
+    pub remote_pending: bool,
+    pub elapsed_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteSuggestions {
+    pub request_id: u64,
+    pub input: String,
+    pub suggestions: Vec<Suggestion>,
+}
+
+// Latest request; remote answers for older keystrokes are dropped
+static LATEST_REQUEST: AtomicU64 = AtomicU64::new(0);
+
+pub fn next_request_id() -> u64 {
+    LATEST_REQUEST.fetch_add(1, Ordering::SeqCst) + 1
+}
+
+fn is_latest(request_id: u64) -> bool {
+    LATEST_REQUEST.load(Ordering::SeqCst) == request_id
+}
+
+// Lowercased whitespace-separated terms
+pub fn terms(input: &str) -> Vec<String> {
+    input.split_whitespace().map(|t| t.to_lowercase()).collect()
+}
+
+// Typed text that is already an address shouldn't leak to a suggestion service
+pub fn looks_like_url(input: &str) -> bool {
+    let input = input.trim();
+    input.contains("://")
+        || (!input.contains(' ') && input.contains('.') && !input.ends_with('.'))
+        || input.starts_with("localhost")
+}
+
+pub fn tab_candidates(tabs: &[Tab], terms: &[String]) -> Vec<Suggestion> {
+    tabs.iter()
+        .filter(|tab| !tab.is_active && tab.url != "about:blank")
+        .filter_map(|tab| {
+            let quality = match_quality(&tab.url, &tab.title, terms)?;
+            Some(Suggestion {
+                source: SuggestionSource::OpenTab,
+                title: tab.title.clone(),
+                url: Some(tab.url.clone()),
+                query: None,
+                tab_id: Some(tab.id.clone()),
+                score: quality * SuggestionSource::OpenTab.weight(),
+            })
+        })
+        .collect()
+}
+
+// Bookmarks and history in one pass over the database
+pub fn database_candidates(db: &Database, terms: &[String]) -> Vec<Suggestion> {
+    let mut out = Vec::new();
+    if let Ok(bookmarks) = db.bookmark_matches(terms, CANDIDATES_PER_SOURCE) {
+        out.extend(bookmarks.into_iter().filter_map(|(_, url, title)| {
+            let quality = match_quality(&url, &title, terms)?;
+            Some(Suggestion {
+                source: SuggestionSource::Bookmark,
+                title,
+                url: Some(url),
+                query: None,
+                tab_id: None,
+                score: quality * SuggestionSource::Bookmark.weight(),
+            })
+        }));
+    }
+
+    if let Ok(history) = db.history_matches(terms, CANDIDATES_PER_SOURCE) {
+        out.extend(history.into_iter().filter_map(|(url, title, _, frecency)| {
+            let quality = match_quality(&url, &title, terms)?;
+            Some(Suggestion {
+                source: SuggestionSource::History,
+                title,
+                url: Some(url),
+                query: None,
+                tab_id: None,
+                score: quality * frecency_boost(frecency) * SuggestionSource::History.weight(),
+            })
+        }));
+    }
+    out
+}
+
+// 1.0 for a long-forgotten page, growing slowly with frecency
+fn frecency_boost(frecency: f64) -> f64 {
+    1.0 + frecency.max(0.0).ln_1p() * 0.3
+}
+
+// How well every term matches: host prefix > title word prefix > anywhere; None if a term is missing
+fn match_quality(url: &str, title: &str, terms: &[String]) -> Option<f64> {
+    if terms.is_empty() {
+        return None;
+    }
+    let url_lower = url.to_lowercase();
+    let title_lower = title.to_lowercase();
+    let host = url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.trim_start_matches("www.").to_lowercase()))
+        .unwrap_or_default();
+
+    let mut total = 0.0;
+    for term in terms {
+        let score = if host.starts_with(term.as_str()) {
+            1.0
+        } else if title_lower.split(|c: char| !c.is_alphanumeric()).any(|w| w.starts_with(term.as_str())) {
+            0.8
+        } else if host.contains(term.as_str()) {
+            0.6
+        } else if url_lower.contains(term.as_str()) || title_lower.contains(term.as_str()) {
+            0.4
+        } else {
+            return None;
+        };
+        total += score;
+    }
+    Some(total / terms.len() as f64)
+}
+
+// Best score per URL; an open tab wins over the same page in bookmarks or history
+pub fn rank(candidates: Vec<Suggestion>, limit: usize) -> Vec<Suggestion> {
+    let mut best: HashMap<String, Suggestion> = HashMap::new();
+    let mut queries = Vec::new();
+    for candidate in candidates {
+        let Some(key) = candidate.url.as_deref().map(dedupe_key) else {
+            queries.push(candidate);
+            continue;
+        };
+        match best.get_mut(&key) {
+            Some(existing) => {
+                if candidate.source == SuggestionSource::OpenTab && existing.source != SuggestionSource::OpenTab {
+                    let score = existing.score.max(candidate.score);
+                    *existing = Suggestion { score, ..candidate };
+                } else if candidate.score > existing.score && existing.source != SuggestionSource::OpenTab {
+                    *existing = candidate;
+                } else {
+                    existing.score = existing.score.max(candidate.score);
+                }
+            }
+            None => {
+                best.insert(key, candidate);
+            }
+        }
+    }
+    let mut ranked: Vec<Suggestion> = best.into_values().chain(queries).collect();
+    ranked.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    ranked.truncate(limit);
+    ranked
+}
+
+fn dedupe_key(url: &str) -> String {
+    url.trim_end_matches('/')
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_start_matches("www.")
+        .to_lowercase()
+}
+
+pub async fn suggest(
+    input: &str,
+    tabs: Vec<Tab>,
+    db: Database,
+    remote: Option<tauri::AppHandle>,
+) -> OmniboxResponse {
+    let started = Instant::now();
+    let request_id = next_request_id();
+    let terms = terms(input);
+
+    let mut candidates = tab_candidates(&tabs, &terms);
+    if !terms.is_empty() {
+        let db_terms = terms.clone();
+        let lookup = tauri::async_runtime::spawn_blocking(move || database_candidates(&db, &db_terms));
+        match tokio::time::timeout(LOCAL_BUDGET.saturating_sub(started.elapsed()), lookup).await {
+            Ok(Ok(found)) => candidates.extend(found),
+            Ok(Err(e)) => tracing::warn!(target: "app", "Omnibox: Lookup failed: {}", e),
+            Err(_) => tracing::debug!(target: "app", "Omnibox: Database lookup missed the {}ms budget", LOCAL_BUDGET.as_millis()),
+        }
+    }
+
+    let remote_pending = match remote {
+        Some(app) if !terms.is_empty() && !looks_like_url(input) && !crate::connectivity::is_offline() => {
+            let input = input.trim().to_string();
+            tauri::async_runtime::spawn(async move {
+                let suggestions = remote_suggestions(&input).await;
+                if is_latest(request_id) && !suggestions.is_empty() {
+                    let _ = app.emit("omnibox:remote", RemoteSuggestions { request_id, input, suggestions });
+                }
+            });
+            true
+        }
+        _ => false,
+    };
+
+    OmniboxResponse {
+        request_id,
+        input: input.to_string(),
+        suggestions: rank(candidates, MAX_SUGGESTIONS),
+        answer: instant_answers::answer(input),
+        remote_pending,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+// DuckDuckGo autocomplete: ["input", ["suggestion", ...]]
+async fn remote_suggestions(input: &str) -> Vec<Suggestion> {
+    let client = http::Client::new(REMOTE_TIMEOUT).with_retries(0).with_purpose("suggestions");
+    let request = client
+        .get("https://duckduckgo.com/ac/")
+        .query(&[("q", input), ("type", "list")]);
+    let body: serde_json::Value = match client.send(request).await {
+        Ok(response) => response.json().await.unwrap_or_default(),
+        Err(e) => {
+            tracing::debug!(target: "app", "Omnibox: Remote suggestions failed: {}", e);
+            return Vec::new();
+        }
+    };
+    let count = body.get(1).and_then(|v| v.as_array()).map(|a| a.len()).unwrap_or(0).max(1);
+    body.get(1)
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|v| v.as_str())
+                .filter(|s| !s.eq_ignore_ascii_case(input))
+                .enumerate()
+                .map(|(i, s)| Suggestion {
+                    source: SuggestionSource::Remote,
+                    title: s.to_string(),
+                    url: None,
+                    query: Some(s.to_string()),
+                    tab_id: None,
+                    // Keep the service's order
+                    score: (1.0 - i as f64 / count as f64) * SuggestionSource::Remote.weight(),
+                })
+                .take(MAX_SUGGESTIONS)
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -0,0 +1,746 @@
+// Onboarding - First-run setup: local vs cloud AI, model download, search provider, browser import
+// Each step is a backend task whose status is saved to onboarding.json, so a restart (or crash) in the
+// middle of setup picks up where it left off; steps still running at startup are resumed.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+use crate::apikeys::{ApiKeyStore, Provider};
+use crate::db::Database;
+use crate::privacy::PrivacyEnforcer;
+
+const STATE_FILE: &str = "onboarding.json";
+const TAGS_TIMEOUT: Duration = Duration::from_secs(5);
+// Whole-request limit for a model pull; Ollama keeps partial layers, so a retry continues the download
+const PULL_TIMEOUT: Duration = Duration::from_secs(6 * 60 * 60);
+// Rows between "onboarding:progress" events during an import
+const IMPORT_PROGRESS_EVERY: usize = 250;
+// Providers that can answer chat requests (Brave and Finnhub keys are for search and market data)
+const CLOUD_AI_PROVIDERS: [Provider; 5] =
+    [Provider::OpenAI, Provider::Anthropic, Provider::Groq, Provider::Mistral, Provider::HuggingFace];
+// Chromium stores times as microseconds since 1601-01-01
+const CHROMIUM_EPOCH_OFFSET: i64 = 11_644_473_600;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "kebab-case")]
+pub enum OnboardingStep {
+    AiProvider,
+    Model,
+    SearchProvider,
+    Import,
+}
+
+impl OnboardingStep {
+    pub const ALL: [OnboardingStep; 4] =
+        [OnboardingStep::AiProvider, OnboardingStep::Model, OnboardingStep::SearchProvider, OnboardingStep::Import];
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum StepStatus {
+    #[default]
+    Pending,
+    Running,
+    Done,
+    Skipped,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct StepState {
+    pub step: OnboardingStep,
+    pub status: StepStatus,
+    pub choice: Option<serde_json::Value>, // What the user picked (no secrets); replayed when resuming
+    pub progress: Option<f64>,             // 0.0-1.0 for the model download and import
+    pub detail: Option<String>,            // Current phase, or a summary once done
+    pub error: Option<String>,
+    pub updated_at: i64,
+}
+
+impl StepState {
+    fn new(step: OnboardingStep) -> Self {
+        Self { step, status: StepStatus::Pending, choice: None, progress: None, detail: None, error: None, updated_at: 0 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingStatus {
+    pub complete: bool,                // Every step is done or skipped
+    pub next_step: Option<OnboardingStep>,
+    pub steps: Vec<StepState>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AiMode {
+    Local,
+    Cloud,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiChoice {
+    pub mode: AiMode,
+    #[serde(default)]
+    pub cloud_provider: Option<String>, // openai, anthropic, groq, mistral, huggingface
+    #[serde(default, skip_serializing)]
+    pub api_key: Option<String>,       // Goes to secure storage, never into onboarding.json
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ModelChoice {
+    pub model: Option<String>,         // Unset pulls the chat model sized for this machine
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchChoice {
+    pub provider: String,              // Moved to the front of search.providers
+    #[serde(default, skip_serializing)]
+    pub api_key: Option<String>,       // Brave only
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum Browser {
+    Chrome,
+    Chromium,
+    Edge,
+    Brave,
+    Firefox,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportChoice {
+    pub browser: Browser,
+    #[serde(default = "default_true")]
+    pub bookmarks: bool,
+    #[serde(default = "default_true")]
+    pub history: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+// A browser profile found on this machine, for the import step's picker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSource {
+    pub browser: Browser,
+    pub profile: String,
+    pub bookmarks: bool,
+    pub history: bool,
+}
+
+struct Onboarding {
+    dir: Option<PathBuf>,
+    steps: Vec<StepState>,
+}
+
+static ONBOARDING: Mutex<Onboarding> = Mutex::new(Onboarding { dir: None, steps: Vec::new() });
+
+// Load saved progress and restart steps that were still running when the app last exited
+pub fn init(app: &tauri::AppHandle, dir: PathBuf) {
+    let saved: Vec<StepState> = std::fs::read_to_string(dir.join(STATE_FILE))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    let steps: Vec<StepState> = OnboardingStep::ALL
+        .into_iter()
+        .map(|step| saved.iter().find(|s| s.step == step).cloned().unwrap_or_else(|| StepState::new(step)))
+        .collect();
+    let interrupted: Vec<StepState> = steps.iter().filter(|s| s.status == StepStatus::Running).cloned().collect();
+    {
+        let mut onboarding = ONBOARDING.lock().unwrap();
+        onboarding.dir = Some(dir);
+        onboarding.steps = steps;
+    }
+
+    for state in interrupted {
+        let Some(choice) = state.choice else {
+            continue;
+        };
+        tracing::info!(target: "app", "Onboarding: Resuming {:?}", state.step);
+        if let Err(e) = launch(app, state.step, choice) {
+            finish(app, state.step, Err(e));
+        }
+    }
+}
+
+pub fn status() -> OnboardingStatus {
+    let steps = ONBOARDING.lock().unwrap().steps.clone();
+    let finished = |s: &StepState| matches!(s.status, StepStatus::Done | StepStatus::Skipped);
+    OnboardingStatus {
+        complete: !steps.is_empty() && steps.iter().all(finished),
+        next_step: steps.iter().find(|s| !finished(s)).map(|s| s.step),
+        steps,
+    }
+}
+
+// Start (or retry) a step with the user's choice. Quick steps finish before returning; the model
+// download and import keep running in the background and report on "onboarding:progress".
+pub fn run_step(app: &tauri::AppHandle, step: OnboardingStep, choice: serde_json::Value) -> Result<StepState, OnboardingError> {
+    if step_state(step).status == StepStatus::Running {
+        return Err(OnboardingError::Busy(step));
+    }
+    launch(app, step, choice)
+}
+
+fn launch(app: &tauri::AppHandle, step: OnboardingStep, choice: serde_json::Value) -> Result<StepState, OnboardingError> {
+    let invalid = |e: serde_json::Error| OnboardingError::InvalidChoice(e.to_string());
+    match step {
+        OnboardingStep::AiProvider => {
+            let choice: AiChoice = serde_json::from_value(choice).map_err(invalid)?;
+            start(app, step, &choice, "Checking AI provider")?;
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let result = choose_ai(&app, &choice).await;
+                finish(&app, step, result);
+            });
+        }
+        OnboardingStep::Model => {
+            let choice: ModelChoice = serde_json::from_value(choice).map_err(invalid)?;
+            let cloud = step_state(OnboardingStep::AiProvider)
+                .choice
+                .and_then(|c| serde_json::from_value::<AiChoice>(c).ok())
+                .is_some_and(|c| c.mode == AiMode::Cloud);
+            if cloud {
+                return Ok(update(app, step, |s| {
+                    s.status = StepStatus::Skipped;
+                    s.detail = Some("Cloud AI chosen; no local model needed".to_string());
+                }));
+            }
+            let model = choice.model.clone().unwrap_or_else(|| crate::config::current().ai.chat_model());
+            start(app, step, &choice, &format!("Downloading {}", model))?;
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let result = pull_model(&app, &model).await;
+                finish(&app, step, result);
+            });
+        }
+        OnboardingStep::SearchProvider => {
+            let choice: SearchChoice = serde_json::from_value(choice).map_err(invalid)?;
+            start(app, step, &choice, "Saving search provider")?;
+            let result = choose_search(app, &choice);
+            finish(app, step, result);
+        }
+        OnboardingStep::Import => {
+            let choice: ImportChoice = serde_json::from_value(choice).map_err(invalid)?;
+            if !disk_allowed(app) {
+                return Err(OnboardingError::PrivateMode);
+            }
+            start(app, step, &choice, "Importing")?;
+            let app = app.clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                let result = import(&app, &choice);
+                finish(&app, step, result);
+            });
+        }
+    }
+    Ok(step_state(step))
+}
+
+pub fn skip(app: &tauri::AppHandle, step: OnboardingStep) -> Result<StepState, OnboardingError> {
+    if step_state(step).status == StepStatus::Running {
+        return Err(OnboardingError::Busy(step));
+    }
+    Ok(update(app, step, |s| {
+        s.status = StepStatus::Skipped;
+        s.progress = None;
+        s.error = None;
+    }))
+}
+
+// Start setup over (settings already applied stay as they are)
+pub fn reset(app: &tauri::AppHandle) -> Result<OnboardingStatus, OnboardingError> {
+    if let Some(running) = status().steps.iter().find(|s| s.status == StepStatus::Running) {
+        return Err(OnboardingError::Busy(running.step));
+    }
+    for step in OnboardingStep::ALL {
+        update(app, step, |s| *s = StepState::new(step));
+    }
+    Ok(status())
+}
+
+// Browser profiles with bookmarks or history to import
+pub fn import_sources() -> Vec<ImportSource> {
+    [Browser::Chrome, Browser::Chromium, Browser::Edge, Browser::Brave, Browser::Firefox]
+        .into_iter()
+        .filter_map(|browser| {
+            let profile = profile_dir(browser)?;
+            let (bookmarks, history) = match browser {
+                Browser::Firefox => {
+                    let places = profile.join("places.sqlite").exists();
+                    (places, places)
+                }
+                _ => (profile.join("Bookmarks").exists(), profile.join("History").exists()),
+            };
+            (bookmarks || history).then(|| ImportSource { browser, profile: profile.display().to_string(), bookmarks, history })
+        })
+        .collect()
+}
+
+// ----------------------------------------------------------------------------
+// Steps
+// ----------------------------------------------------------------------------
+
+async fn choose_ai(app: &tauri::AppHandle, choice: &AiChoice) -> Result<String, OnboardingError> {
+    match choice.mode {
+        AiMode::Local => {
+            let url = crate::embeddings::ollama_url();
+            let client = crate::http::Client::new(TAGS_TIMEOUT).with_retries(1).with_purpose("onboarding");
+            match client.send(client.get(format!("{}/api/tags", url))).await {
+                Ok(response) if response.status().is_success() => Ok(format!("Local AI via Ollama at {}", url)),
+                _ => Err(OnboardingError::OllamaUnavailable(url)),
+            }
+        }
+        AiMode::Cloud => {
+            let id = choice.cloud_provider.as_deref().unwrap_or_default();
+            let provider = Provider::parse(id)
+                .ok()
+                .filter(|p| CLOUD_AI_PROVIDERS.contains(p))
+                .ok_or_else(|| OnboardingError::InvalidChoice(format!("'{}' is not a cloud AI provider", id)))?;
+            let keys = app.try_state::<ApiKeyStore>().ok_or(OnboardingError::Unavailable("secure storage"))?;
+            if let Some(key) = &choice.api_key {
+                keys.set(provider, key).map_err(|e| OnboardingError::InvalidChoice(e.to_string()))?;
+            }
+            if keys.get(provider).is_none() {
+                return Err(OnboardingError::MissingKey(provider.id().to_string()));
+            }
+            Ok(format!("Cloud AI via {}", provider.id()))
+        }
+    }
+}
+
+// Stream Ollama's pull progress (one JSON object per line), then make the model the chat default
+async fn pull_model(app: &tauri::AppHandle, model: &str) -> Result<String, OnboardingError> {
+    let client = crate::http::Client::new(PULL_TIMEOUT).with_retries(0).with_purpose("onboarding");
+    let request = client
+        .post(format!("{}/api/pull", crate::embeddings::ollama_url()))
+        .json(&serde_json::json!({ "model": model, "stream": true }));
+    let mut response = match client.send(request).await {
+        Ok(response) if response.status().is_success() => response,
+        Ok(response) => return Err(OnboardingError::Download(format!("Ollama returned {}", response.status()))),
+        Err(e) => return Err(OnboardingError::Download(e.to_string())),
+    };
+
+    let mut buffer = Vec::new();
+    let mut last_percent = None;
+    while let Some(chunk) = response.chunk().await.map_err(|e| OnboardingError::Download(e.to_string()))? {
+        buffer.extend_from_slice(&chunk);
+        while let Some(end) = buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=end).collect();
+            let Ok(event) = serde_json::from_slice::<PullEvent>(&line) else {
+                continue;
+            };
+            if let Some(error) = event.error {
+                return Err(OnboardingError::Download(error));
+            }
+            let progress = match (event.completed, event.total) {
+                (Some(done), Some(total)) if total > 0 => Some(done as f64 / total as f64),
+                _ => None,
+            };
+            // One event per percent (or phase change) is plenty for a progress bar
+            let percent = progress.map(|p| (p * 100.0) as u32);
+            if percent != last_percent || progress.is_none() {
+                last_percent = percent;
+                progress_update(app, OnboardingStep::Model, progress, event.status);
+            }
+        }
+    }
+
+    let updated = crate::config::store()
+        .set("ai.model", serde_json::Value::String(model.to_string()))
+        .map_err(|e| OnboardingError::Config(e.to_string()))?;
+    crate::config::apply(app, &updated);
+    let _ = app.emit("config:changed", &updated);
+    Ok(format!("{} installed", model))
+}
+
+#[derive(Debug, Deserialize)]
+struct PullEvent {
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    total: Option<u64>,
+    #[serde(default)]
+    completed: Option<u64>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+fn choose_search(app: &tauri::AppHandle, choice: &SearchChoice) -> Result<String, OnboardingError> {
+    let provider = choice.provider.trim().to_lowercase();
+    if provider == "brave" {
+        let keys = app.try_state::<ApiKeyStore>().ok_or(OnboardingError::Unavailable("secure storage"))?;
+        if let Some(key) = &choice.api_key {
+            keys.set(Provider::Brave, key).map_err(|e| OnboardingError::InvalidChoice(e.to_string()))?;
+        }
+        if keys.get(Provider::Brave).is_none() {
+            return Err(OnboardingError::MissingKey("brave".to_string()));
+        }
+    }
+    if provider == "searxng" && crate::config::current().search.searxng_url.is_none() {
+        return Err(OnboardingError::InvalidChoice("set search.searxngUrl before choosing SearxNG".to_string()));
+    }
+
+    let mut providers = crate::config::current().search.providers;
+    providers.retain(|p| *p != provider);
+    providers.insert(0, provider.clone());
+    let updated = crate::config::store()
+        .set("search.providers", serde_json::json!(providers))
+        .map_err(|e| OnboardingError::Config(e.to_string()))?;
+    crate::config::apply(app, &updated);
+    let _ = app.emit("config:changed", &updated);
+    Ok(format!("Searching with {} first", provider))
+}
+
+// Blocking: reads the other browser's files and writes into our database. URLs already present are
+// skipped, so re-running after an interruption carries on without duplicates.
+fn import(app: &tauri::AppHandle, choice: &ImportChoice) -> Result<String, OnboardingError> {
+    let profile = profile_dir(choice.browser).ok_or(OnboardingError::BrowserNotFound(choice.browser))?;
+    let db = app.try_state::<Database>().ok_or(OnboardingError::Unavailable("database"))?.inner().clone();
+    let io = |e: rusqlite::Error| OnboardingError::Import(e.to_string());
+
+    let mut bookmarks = Vec::new();
+    let mut history = Vec::new();
+    match choice.browser {
+        Browser::Firefox => {
+            let places = open_copy(&profile.join("places.sqlite"))?;
+            if choice.bookmarks {
+                bookmarks = firefox_bookmarks(&places.conn).map_err(io)?;
+            }
+            if choice.history {
+                history = firefox_history(&places.conn).map_err(io)?;
+            }
+        }
+        _ => {
+            if choice.bookmarks {
+                bookmarks = chromium_bookmarks(&profile.join("Bookmarks"))?;
+            }
+            if choice.history {
+                let copy = open_copy(&profile.join("History"))?;
+                history = chromium_history(&copy.conn).map_err(io)?;
+            }
+        }
+    }
+
+    let total = bookmarks.len() + history.len();
+    let (mut added_bookmarks, mut added_history) = (0, 0);
+    for (i, (url, title, folder)) in bookmarks.iter().enumerate() {
+        if db.import_bookmark(url, title, folder.as_deref()).map_err(io)? {
+            added_bookmarks += 1;
+        }
+        if i % IMPORT_PROGRESS_EVERY == 0 {
+            progress_update(app, OnboardingStep::Import, Some(i as f64 / total as f64), Some("Importing bookmarks".to_string()));
+        }
+    }
+    for (i, (url, title, visited_at, visit_count)) in history.iter().enumerate() {
+        if db.import_history(url, title, *visited_at, *visit_count).map_err(io)? {
+            added_history += 1;
+        }
+        if i % IMPORT_PROGRESS_EVERY == 0 {
+            let done = bookmarks.len() + i;
+            progress_update(app, OnboardingStep::Import, Some(done as f64 / total as f64), Some("Importing history".to_string()));
+        }
+    }
+    Ok(format!("Imported {} bookmarks and {} history entries", added_bookmarks, added_history))
+}
+
+// ----------------------------------------------------------------------------
+// Browser data
+// ----------------------------------------------------------------------------
+
+// (url, title, folder)
+type ImportedBookmark = (String, String, Option<String>);
+// (url, title, last visit unix seconds, visit count)
+type ImportedVisit = (String, String, i64, i64);
+
+fn chromium_bookmarks(path: &Path) -> Result<Vec<ImportedBookmark>, OnboardingError> {
+    let json = std::fs::read_to_string(path).map_err(|e| OnboardingError::Import(e.to_string()))?;
+    let root: serde_json::Value = serde_json::from_str(&json).map_err(|e| OnboardingError::Import(e.to_string()))?;
+    let mut bookmarks = Vec::new();
+    if let Some(roots) = root.get("roots").and_then(|r| r.as_object()) {
+        // The roots themselves ("Bookmarks bar", "Other bookmarks", ...) don't become folders
+        for root in roots.values() {
+            for child in root.get("children").and_then(|c| c.as_array()).into_iter().flatten() {
+                collect_chromium(child, None, &mut bookmarks);
+            }
+        }
+    }
+    Ok(bookmarks)
+}
+
+fn collect_chromium(node: &serde_json::Value, folder: Option<&str>, out: &mut Vec<ImportedBookmark>) {
+    let name = node.get("name").and_then(|n| n.as_str()).unwrap_or_default();
+    match node.get("type").and_then(|t| t.as_str()) {
+        Some("url") => {
+            if let Some(url) = node.get("url").and_then(|u| u.as_str()).filter(|u| importable(u)) {
+                out.push((url.to_string(), name.to_string(), folder.map(str::to_string)));
+            }
+        }
+        Some("folder") => {
+            let folder = Some(name).filter(|n| !n.is_empty()).or(folder);
+            for child in node.get("children").and_then(|c| c.as_array()).into_iter().flatten() {
+                collect_chromium(child, folder, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn chromium_history(conn: &rusqlite::Connection) -> rusqlite::Result<Vec<ImportedVisit>> {
+    let mut stmt = conn.prepare(
+        "SELECT url, title, last_visit_time, visit_count FROM urls WHERE hidden = 0 ORDER BY last_visit_time DESC",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        let visited: i64 = row.get(2)?;
+        Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?.unwrap_or_default(), visited / 1_000_000 - CHROMIUM_EPOCH_OFFSET, row.get(3)?))
+    })?;
+    Ok(rows.filter_map(Result::ok).filter(|(url, ..)| importable(url)).collect())
+}
+
+fn firefox_bookmarks(conn: &rusqlite::Connection) -> rusqlite::Result<Vec<ImportedBookmark>> {
+    // type 1 = bookmark; bookmarks directly in Firefox's own roots (toolbar, menu, ...) get no folder
+    let mut stmt = conn.prepare(
+        "SELECT p.url, COALESCE(b.title, p.title, ''),
+                CASE WHEN f.guid IN ('toolbar_____', 'menu________', 'unfiled_____', 'mobile______') THEN NULL ELSE f.title END
+         FROM moz_bookmarks b
+         JOIN moz_places p ON p.id = b.fk
+         LEFT JOIN moz_bookmarks f ON f.id = b.parent
+         WHERE b.type = 1",
+    )?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<String>>(2)?)))?;
+    Ok(rows.filter_map(Result::ok).filter(|(url, ..)| importable(url)).collect())
+}
+
+fn firefox_history(conn: &rusqlite::Connection) -> rusqlite::Result<Vec<ImportedVisit>> {
+    let mut stmt = conn.prepare(
+        "SELECT url, COALESCE(title, ''), last_visit_date, visit_count FROM moz_places
+         WHERE last_visit_date IS NOT NULL AND hidden = 0 ORDER BY last_visit_date DESC",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        let visited: i64 = row.get(2)?;
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, visited / 1_000_000, row.get(3)?))
+    })?;
+    Ok(rows.filter_map(Result::ok).filter(|(url, ..)| importable(url)).collect())
+}
+
+fn importable(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+// The running browser keeps its databases locked; read from a temporary copy (with its WAL) instead
+struct DbCopy {
+    conn: rusqlite::Connection,
+    dir: PathBuf,
+}
+
+impl Drop for DbCopy {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn open_copy(path: &Path) -> Result<DbCopy, OnboardingError> {
+    let io = |e: std::io::Error| OnboardingError::Import(format!("{}: {}", path.display(), e));
+    let dir = std::env::temp_dir().join(format!("regen-import-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).map_err(io)?;
+    let copy = dir.join("import.sqlite");
+    std::fs::copy(path, &copy).map_err(io)?;
+    for suffix in ["-wal", "-shm"] {
+        let side = PathBuf::from(format!("{}{}", path.display(), suffix));
+        if side.exists() {
+            let _ = std::fs::copy(&side, dir.join(format!("import.sqlite{}", suffix)));
+        }
+    }
+    let conn = rusqlite::Connection::open(&copy).map_err(|e| OnboardingError::Import(e.to_string()))?;
+    Ok(DbCopy { conn, dir })
+}
+
+// Default profile of each browser for the current platform
+fn profile_dir(browser: Browser) -> Option<PathBuf> {
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")).map(PathBuf::from)?;
+    let chromium = |linux: &str, mac: &str, windows: &str| -> PathBuf {
+        if cfg!(target_os = "windows") {
+            let local = std::env::var_os("LOCALAPPDATA").map(PathBuf::from).unwrap_or_else(|| home.join("AppData/Local"));
+            local.join(windows).join("User Data/Default")
+        } else if cfg!(target_os = "macos") {
+            home.join("Library/Application Support").join(mac).join("Default")
+        } else {
+            home.join(".config").join(linux).join("Default")
+        }
+    };
+    let dir = match browser {
+        Browser::Chrome => chromium("google-chrome", "Google/Chrome", "Google/Chrome"),
+        Browser::Chromium => chromium("chromium", "Chromium", "Chromium"),
+        Browser::Edge => chromium("microsoft-edge", "Microsoft Edge", "Microsoft/Edge"),
+        Browser::Brave => chromium("BraveSoftware/Brave-Browser", "BraveSoftware/Brave-Browser", "BraveSoftware/Brave-Browser"),
+        Browser::Firefox => return firefox_profile(&home),
+    };
+    dir.is_dir().then_some(dir)
+}
+
+// The profile with the most recently used places.sqlite
+fn firefox_profile(home: &Path) -> Option<PathBuf> {
+    let root = if cfg!(target_os = "windows") {
+        std::env::var_os("APPDATA").map(PathBuf::from).unwrap_or_else(|| home.join("AppData/Roaming")).join("Mozilla/Firefox/Profiles")
+    } else if cfg!(target_os = "macos") {
+        home.join("Library/Application Support/Firefox/Profiles")
+    } else {
+        home.join(".mozilla/firefox")
+    };
+    std::fs::read_dir(root)
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter_map(|dir| {
+            let modified = std::fs::metadata(dir.join("places.sqlite")).and_then(|m| m.modified()).ok()?;
+            Some((modified, dir))
+        })
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, dir)| dir)
+}
+
+// ----------------------------------------------------------------------------
+// State
+// ----------------------------------------------------------------------------
+
+fn step_state(step: OnboardingStep) -> StepState {
+    ONBOARDING
+        .lock()
+        .unwrap()
+        .steps
+        .iter()
+        .find(|s| s.step == step)
+        .cloned()
+        .unwrap_or_else(|| StepState::new(step))
+}
+
+fn start<T: Serialize>(app: &tauri::AppHandle, step: OnboardingStep, choice: &T, detail: &str) -> Result<(), OnboardingError> {
+    let choice = serde_json::to_value(choice).map_err(|e| OnboardingError::InvalidChoice(e.to_string()))?;
+    update(app, step, |s| {
+        s.status = StepStatus::Running;
+        s.choice = Some(choice);
+        s.progress = None;
+        s.detail = Some(detail.to_string());
+        s.error = None;
+    });
+    Ok(())
+}
+
+fn finish(app: &tauri::AppHandle, step: OnboardingStep, result: Result<String, OnboardingError>) {
+    update(app, step, |s| match result {
+        Ok(summary) => {
+            s.status = StepStatus::Done;
+            s.progress = Some(1.0);
+            s.detail = Some(summary);
+        }
+        Err(e) => {
+            tracing::warn!(target: "app", "Onboarding: {:?} failed: {}", step, e);
+            s.status = StepStatus::Failed;
+            s.error = Some(e.to_string());
+        }
+    });
+}
+
+// Progress is only announced; the step's status on disk changes at start and finish
+fn progress_update(app: &tauri::AppHandle, step: OnboardingStep, progress: Option<f64>, detail: Option<String>) {
+    let state = {
+        let mut onboarding = ONBOARDING.lock().unwrap();
+        let Some(state) = onboarding.steps.iter_mut().find(|s| s.step == step) else {
+            return;
+        };
+        if progress.is_some() {
+            state.progress = progress;
+        }
+        if detail.is_some() {
+            state.detail = detail;
+        }
+        state.clone()
+    };
+    let _ = app.emit("onboarding:progress", &state);
+}
+
+fn update(app: &tauri::AppHandle, step: OnboardingStep, f: impl FnOnce(&mut StepState)) -> StepState {
+    let (state, dir, steps) = {
+        let mut onboarding = ONBOARDING.lock().unwrap();
+        if !onboarding.steps.iter().any(|s| s.step == step) {
+            onboarding.steps.push(StepState::new(step));
+        }
+        let state = onboarding.steps.iter_mut().find(|s| s.step == step).unwrap();
+        f(state);
+        state.updated_at = chrono::Utc::now().timestamp();
+        let state = state.clone();
+        (state, onboarding.dir.clone(), onboarding.steps.clone())
+    };
+    if let Some(dir) = dir {
+        let result = serde_json::to_string_pretty(&steps)
+            .map_err(|e| e.to_string())
+            .and_then(|json| write_atomic(&dir.join(STATE_FILE), &json).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            tracing::warn!(target: "app", "Onboarding: Failed to save progress: {}", e);
+        }
+    }
+    let _ = app.emit("onboarding:progress", &state);
+    state
+}
+
+fn write_atomic(path: &Path, contents: &str) -> std::io::Result<()> {
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, contents)?;
+    std::fs::rename(&tmp, path)
+}
+
+fn disk_allowed(app: &tauri::AppHandle) -> bool {
+    app.try_state::<Mutex<PrivacyEnforcer>>()
+        .map(|enforcer| enforcer.lock().unwrap().can_write_to_disk())
+        .unwrap_or(true)
+}
+
+#[derive(Debug, Clone)]
+pub enum OnboardingError {
+    InvalidChoice(String),
+    Busy(OnboardingStep),
+    OllamaUnavailable(String),
+    MissingKey(String),
+    Download(String),
+    BrowserNotFound(Browser),
+    Import(String),
+    PrivateMode,
+    Config(String),
+    Unavailable(&'static str),
+}
+
+impl std::fmt::Display for OnboardingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OnboardingError::InvalidChoice(e) => write!(f, "Invalid choice: {}", e),
+            OnboardingError::Busy(step) => write!(f, "Onboarding step {:?} is already running", step),
+            OnboardingError::OllamaUnavailable(url) => write!(f, "Ollama isn't reachable at {}; start it or choose cloud AI", url),
+            OnboardingError::MissingKey(provider) => write!(f, "No API key for {}", provider),
+            OnboardingError::Download(e) => write!(f, "Model download failed: {}", e),
+            OnboardingError::BrowserNotFound(browser) => write!(f, "No {:?} profile found on this machine", browser),
+            OnboardingError::Import(e) => write!(f, "Import failed: {}", e),
+            OnboardingError::PrivateMode => write!(f, "Importing is disabled in Private/Ghost mode"),
+            OnboardingError::Config(e) => write!(f, "Failed to save setting: {}", e),
+            OnboardingError::Unavailable(what) => write!(f, "Onboarding: {} is not ready", what),
+        }
+    }
+}
+
+impl std::error::Error for OnboardingError {}
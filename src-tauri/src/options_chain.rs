@@ -0,0 +1,361 @@
+// Options Chain - Strikes, open interest, and implied volatility per expiry for the Trade mode
+// NSE serves the Indian indices (NIFTY, BANKNIFTY, ...) and "NSE:" equities; Yahoo Finance serves everything else.
+// Chains are normalized into one shape and cached briefly per symbol and expiry.
+
+use chrono::{NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+const USER_AGENT: &str = "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0 Safari/537.36";
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+const NSE_HOME: &str = "https://www.nseindia.com/option-chain";
+const NSE_INDICES_URL: &str = "https://www.nseindia.com/api/option-chain-indices";
+const NSE_EQUITIES_URL: &str = "https://www.nseindia.com/api/option-chain-equities";
+const YAHOO_URL: &str = "https://query2.finance.yahoo.com/v7/finance/options/";
+const NSE_INDICES: &[&str] = &["NIFTY", "BANKNIFTY", "FINNIFTY", "MIDCPNIFTY", "NIFTYNXT50"];
+// Premiums move every tick; a chain is reused for this long
+const CACHE_TTL: Duration = Duration::from_secs(60);
+const MAX_CACHED: usize = 64;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct OptionQuote {
+    pub last_price: Option<f64>,
+    pub change: Option<f64>,
+    pub bid: Option<f64>,
+    pub ask: Option<f64>,
+    pub volume: Option<u64>,
+    pub open_interest: Option<u64>,
+    pub change_in_oi: Option<i64>,     // NSE only
+    pub implied_volatility: Option<f64>, // Percent (14.2 means 14.2%)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct StrikeRow {
+    pub strike: f64,
+    pub call: Option<OptionQuote>,
+    pub put: Option<OptionQuote>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct OptionChain {
+    pub symbol: String,
+    pub provider: String,              // "nse" or "yahoo"
+    pub underlying_price: Option<f64>,
+    pub expiry: String,                // YYYY-MM-DD of the strikes below
+    pub expiries: Vec<String>,         // Every listed expiry, nearest first
+    pub strikes: Vec<StrikeRow>,       // Ascending by strike
+    pub total_call_oi: u64,
+    pub total_put_oi: u64,
+    pub put_call_ratio: Option<f64>,   // Put OI over call OI
+    pub fetched_at: i64,
+    pub cached: bool,
+    pub stale: bool,                   // Offline or the provider failed: an expired cache entry was returned
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Source {
+    NseIndex,
+    NseEquity,
+    Yahoo,
+}
+
+struct CacheEntry {
+    chain: OptionChain,
+    expires: Instant,
+}
+
+fn cache() -> &'static Mutex<HashMap<String, CacheEntry>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, CacheEntry>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+// Map what users type (NIFTY, ^NSEBANK, NSE:RELIANCE, AAPL) to the provider's symbol
+fn resolve(symbol: &str) -> Result<(Source, String), OptionsError> {
+    let symbol = symbol.trim().to_uppercase().replace([' ', '_'], "");
+    let valid = !symbol.is_empty()
+        && symbol.len() <= 20
+        && symbol.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':' | '^' | '&'));
+    if !valid {
+        return Err(OptionsError::InvalidSymbol(symbol));
+    }
+    let resolved = match symbol.as_str() {
+        "^NSEI" | "NIFTY50" | "NSE:NIFTY" => (Source::NseIndex, "NIFTY".to_string()),
+        "^NSEBANK" | "NIFTYBANK" | "NSE:NIFTYBANK" | "NSE:BANKNIFTY" => (Source::NseIndex, "BANKNIFTY".to_string()),
+        s if NSE_INDICES.contains(&s) => (Source::NseIndex, s.to_string()),
+        s => match s.strip_prefix("NSE:").or_else(|| s.strip_suffix(".NS")) {
+            Some(equity) if !equity.is_empty() => (Source::NseEquity, equity.to_string()),
+            _ => (Source::Yahoo, s.to_string()),
+        },
+    };
+    Ok(resolved)
+}
+
+// `expiry` is YYYY-MM-DD; without one the nearest listed expiry is used
+pub async fn option_chain(symbol: &str, expiry: Option<&str>) -> Result<OptionChain, OptionsError> {
+    let (source, symbol) = resolve(symbol)?;
+    let expiry = match expiry.map(str::trim).filter(|e| !e.is_empty()) {
+        Some(e) => Some(NaiveDate::parse_from_str(e, "%Y-%m-%d").map_err(|_| OptionsError::InvalidExpiry(e.to_string()))?),
+        None => None,
+    };
+    let key = format!("{}:{}", symbol, expiry.map(|e| e.to_string()).unwrap_or_default());
+
+    let expired = {
+        let cache = cache().lock().unwrap();
+        match cache.get(&key) {
+            Some(entry) if entry.expires > Instant::now() => return Ok(OptionChain { cached: true, ..entry.chain.clone() }),
+            Some(entry) => Some(OptionChain { cached: true, stale: true, ..entry.chain.clone() }),
+            None => None,
+        }
+    };
+    if crate::connectivity::is_offline() {
+        return expired.ok_or(OptionsError::Offline);
+    }
+
+    let client = crate::http::Client::new(FETCH_TIMEOUT).with_user_agent(USER_AGENT).with_purpose("option-chain");
+    let result = match source {
+        Source::NseIndex | Source::NseEquity => fetch_nse(&client, source, &symbol, expiry).await,
+        Source::Yahoo => fetch_yahoo(&client, &symbol, expiry).await,
+    };
+    match result {
+        Ok(chain) => {
+            store(key, &chain);
+            Ok(chain)
+        }
+        // Unknown symbols and expiries aren't worth papering over with an old chain
+        Err(e @ (OptionsError::NoOptions(_) | OptionsError::UnknownExpiry(..))) => Err(e),
+        Err(e) => {
+            tracing::debug!(target: "app", "Options: {} failed: {}", symbol, e);
+            expired.ok_or(e)
+        }
+    }
+}
+
+fn store(key: String, chain: &OptionChain) {
+    let mut cache = cache().lock().unwrap();
+    if cache.len() >= MAX_CACHED {
+        let now = Instant::now();
+        cache.retain(|_, e| e.expires > now);
+        if cache.len() >= MAX_CACHED {
+            cache.clear();
+        }
+    }
+    cache.insert(key, CacheEntry { chain: chain.clone(), expires: Instant::now() + CACHE_TTL });
+}
+
+// NSE's API only answers requests carrying the cookies its own page sets
+async fn fetch_nse(
+    client: &crate::http::Client,
+    source: Source,
+    symbol: &str,
+    expiry: Option<NaiveDate>,
+) -> Result<OptionChain, OptionsError> {
+    let provider = |e: String| OptionsError::Provider(format!("NSE: {}", e));
+    let home = client.send(client.get(NSE_HOME)).await.map_err(|e| provider(e.to_string()))?;
+    let cookies = home
+        .headers()
+        .get_all(reqwest::header::SET_COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok()?.split(';').next().map(str::to_string))
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    let url = if source == Source::NseIndex { NSE_INDICES_URL } else { NSE_EQUITIES_URL };
+    let request = client
+        .get(url)
+        .query(&[("symbol", symbol)])
+        .header(reqwest::header::COOKIE, cookies)
+        .header(reqwest::header::REFERER, NSE_HOME)
+        .header(reqwest::header::ACCEPT, "application/json");
+    let response = client.send(request).await.map_err(|e| provider(e.to_string()))?;
+    if !response.status().is_success() {
+        return Err(provider(format!("HTTP {}", response.status().as_u16())));
+    }
+    let body: serde_json::Value = response.json().await.map_err(|e| provider(e.to_string()))?;
+    parse_nse(&body, symbol, expiry)
+}
+
+// {"records":{"expiryDates":["28-Oct-2026",...],"underlyingValue":25012.3,
+//   "data":[{"strikePrice":25000,"expiryDate":"28-Oct-2026","CE":{"openInterest":..,"impliedVolatility":..},"PE":{..}}]}}
+fn parse_nse(body: &serde_json::Value, symbol: &str, expiry: Option<NaiveDate>) -> Result<OptionChain, OptionsError> {
+    let records = body.get("records").ok_or_else(|| OptionsError::NoOptions(symbol.to_string()))?;
+    let nse_date = |s: &str| NaiveDate::parse_from_str(s, "%d-%b-%Y").ok();
+    let mut expiries: Vec<NaiveDate> = records
+        .get("expiryDates")
+        .and_then(|v| v.as_array())
+        .map(|dates| dates.iter().filter_map(|d| nse_date(d.as_str()?)).collect())
+        .unwrap_or_default();
+    expiries.sort();
+    let selected = pick_expiry(symbol, &expiries, expiry)?;
+
+    let mut rows: BTreeMap<i64, StrikeRow> = BTreeMap::new();
+    for item in records.get("data").and_then(|v| v.as_array()).into_iter().flatten() {
+        if item.get("expiryDate").and_then(|v| v.as_str()).and_then(nse_date) != Some(selected) {
+            continue;
+        }
+        let Some(strike) = item.get("strikePrice").and_then(|v| v.as_f64()) else { continue };
+        let quote = |side: &str| {
+            let leg = item.get(side)?;
+            let num = |key: &str| leg.get(key).and_then(|v| v.as_f64());
+            Some(OptionQuote {
+                last_price: num("lastPrice"),
+                change: num("change"),
+                bid: num("bidprice"),
+                ask: num("askPrice"),
+                volume: num("totalTradedVolume").map(|v| v as u64),
+                open_interest: num("openInterest").map(|v| v as u64),
+                change_in_oi: num("changeinOpenInterest").map(|v| v as i64),
+                implied_volatility: num("impliedVolatility").filter(|v| *v > 0.0),
+            })
+        };
+        let row = rows.entry(strike_key(strike)).or_insert(StrikeRow { strike, call: None, put: None });
+        row.call = row.call.take().or_else(|| quote("CE"));
+        row.put = row.put.take().or_else(|| quote("PE"));
+    }
+
+    let underlying = records.get("underlyingValue").and_then(|v| v.as_f64());
+    Ok(chain(symbol, "nse", underlying, selected, &expiries, rows.into_values().collect()))
+}
+
+async fn fetch_yahoo(client: &crate::http::Client, symbol: &str, expiry: Option<NaiveDate>) -> Result<OptionChain, OptionsError> {
+    let url = format!("{}{}", YAHOO_URL, url::form_urlencoded::byte_serialize(symbol.as_bytes()).collect::<String>());
+
+    // The first answer lists the expiries; another request fetches a specific one
+    let result = fetch_yahoo_page(client, &url, symbol, None).await?;
+    let mut expiries: Vec<NaiveDate> = result
+        .get("expirationDates")
+        .and_then(|v| v.as_array())
+        .map(|dates| dates.iter().filter_map(|d| Some(chrono::DateTime::from_timestamp(d.as_i64()?, 0)?.date_naive())).collect())
+        .unwrap_or_default();
+    expiries.sort();
+    let selected = pick_expiry(symbol, &expiries, expiry)?;
+    let listed = result.pointer("/options/0/expirationDate").and_then(|v| v.as_i64());
+    let wanted = chrono::Utc.from_utc_datetime(&selected.and_hms_opt(0, 0, 0).unwrap_or_default()).timestamp();
+    let result = if listed.and_then(|t| chrono::DateTime::from_timestamp(t, 0)).map(|t| t.date_naive()) == Some(selected) {
+        result
+    } else {
+        fetch_yahoo_page(client, &url, symbol, Some(wanted)).await?
+    };
+
+    let mut rows: BTreeMap<i64, StrikeRow> = BTreeMap::new();
+    for (side, is_call) in [("calls", true), ("puts", false)] {
+        for leg in result.pointer(&format!("/options/0/{}", side)).and_then(|v| v.as_array()).into_iter().flatten() {
+            let num = |key: &str| leg.get(key).and_then(|v| v.as_f64());
+            let Some(strike) = num("strike") else { continue };
+            let quote = OptionQuote {
+                last_price: num("lastPrice"),
+                change: num("change"),
+                bid: num("bid"),
+                ask: num("ask"),
+                volume: num("volume").map(|v| v as u64),
+                open_interest: num("openInterest").map(|v| v as u64),
+                change_in_oi: None,
+                // Yahoo reports a fraction
+                implied_volatility: num("impliedVolatility").filter(|v| *v > 0.0).map(|v| v * 100.0),
+            };
+            let row = rows.entry(strike_key(strike)).or_insert(StrikeRow { strike, call: None, put: None });
+            if is_call { row.call = Some(quote) } else { row.put = Some(quote) }
+        }
+    }
+
+    let underlying = result.pointer("/quote/regularMarketPrice").and_then(|v| v.as_f64());
+    Ok(chain(symbol, "yahoo", underlying, selected, &expiries, rows.into_values().collect()))
+}
+
+async fn fetch_yahoo_page(
+    client: &crate::http::Client,
+    url: &str,
+    symbol: &str,
+    date: Option<i64>,
+) -> Result<serde_json::Value, OptionsError> {
+    let provider = |e: String| OptionsError::Provider(format!("Yahoo: {}", e));
+    let mut request = client.get(url);
+    if let Some(date) = date {
+        request = request.query(&[("date", date)]);
+    }
+    let response = client.send(request).await.map_err(|e| provider(e.to_string()))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(OptionsError::NoOptions(symbol.to_string()));
+    }
+    if !response.status().is_success() {
+        return Err(provider(format!("HTTP {}", response.status().as_u16())));
+    }
+    let mut body: serde_json::Value = response.json().await.map_err(|e| provider(e.to_string()))?;
+    body.pointer_mut("/optionChain/result/0")
+        .map(serde_json::Value::take)
+        .ok_or_else(|| OptionsError::NoOptions(symbol.to_string()))
+}
+
+fn pick_expiry(symbol: &str, expiries: &[NaiveDate], wanted: Option<NaiveDate>) -> Result<NaiveDate, OptionsError> {
+    match wanted {
+        Some(date) if expiries.contains(&date) => Ok(date),
+        Some(date) => Err(OptionsError::UnknownExpiry(date.to_string(), expiries.iter().map(|d| d.to_string()).collect())),
+        None => expiries.first().copied().ok_or_else(|| OptionsError::NoOptions(symbol.to_string())),
+    }
+}
+
+// Strikes as map keys, in paise/cents so 22.5 and 22.50 land on one row
+fn strike_key(strike: f64) -> i64 {
+    (strike * 100.0).round() as i64
+}
+
+fn chain(
+    symbol: &str,
+    provider: &str,
+    underlying_price: Option<f64>,
+    expiry: NaiveDate,
+    expiries: &[NaiveDate],
+    strikes: Vec<StrikeRow>,
+) -> OptionChain {
+    let open_interest = |quote: &Option<OptionQuote>| quote.as_ref().and_then(|q| q.open_interest).unwrap_or(0);
+    let total_call_oi: u64 = strikes.iter().map(|r| open_interest(&r.call)).sum();
+    let total_put_oi: u64 = strikes.iter().map(|r| open_interest(&r.put)).sum();
+    OptionChain {
+        symbol: symbol.to_string(),
+        provider: provider.to_string(),
+        underlying_price,
+        expiry: expiry.to_string(),
+        expiries: expiries.iter().map(|d| d.to_string()).collect(),
+        strikes,
+        total_call_oi,
+        total_put_oi,
+        put_call_ratio: (total_call_oi > 0).then(|| total_put_oi as f64 / total_call_oi as f64),
+        fetched_at: chrono::Utc::now().timestamp(),
+        cached: false,
+        stale: false,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum OptionsError {
+    InvalidSymbol(String),
+    InvalidExpiry(String),
+    UnknownExpiry(String, Vec<String>), // Requested expiry, listed expiries
+    NoOptions(String),                 // The provider lists no options for the symbol
+    Offline,                           // Nothing cached and no network to fetch it
+    Provider(String),
+}
+
+impl std::fmt::Display for OptionsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OptionsError::InvalidSymbol(symbol) => write!(f, "'{}' is not a ticker symbol", symbol),
+            OptionsError::InvalidExpiry(expiry) => write!(f, "Invalid expiry '{}' (expected YYYY-MM-DD)", expiry),
+            OptionsError::UnknownExpiry(expiry, listed) => {
+                write!(f, "No options expire on {}; listed expiries: {}", expiry, listed.join(", "))
+            }
+            OptionsError::NoOptions(symbol) => write!(f, "No options are listed for {}", symbol),
+            OptionsError::Offline => write!(f, "No cached option chain for this symbol and the network is offline"),
+            OptionsError::Provider(e) => write!(f, "Option chain provider failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for OptionsError {}
@@ -0,0 +1,244 @@
+// Command Palette - Searchable registry of every invokable backend action
+// The command list is generated at build time from main.rs's invoke handler and the signatures and
+// comments in commands.rs (see build.rs), so new commands show up without touching this file.
+// Global shortcut actions are listed alongside them with their current key bindings.
+
+use serde::{Deserialize, Serialize};
+
+use crate::services::global_shortcut_service;
+
+// Reported as the accelerator when the palette runs a shortcut action
+const PALETTE_SOURCE: &str = "palette";
+const SHORTCUT_PREFIX: &str = "shortcut:";
+// Invoking these from the palette would recurse into action_invoke
+const NOT_INVOKABLE: &[&str] = &["action_invoke"];
+
+// One registered command, as extracted by build.rs
+pub struct CommandSpec {
+    pub id: &'static str,              // Invoke name (the fn name, or its `name = "..."` override)
+    pub category: &'static str,        // commands.rs section header
+    pub description: &'static str,     // Comment above the command, if any
+    pub params: &'static [ParamSpec],
+}
+
+pub struct ParamSpec {
+    pub name: &'static str,            // As the frontend passes it (camelCase)
+    pub rust_type: &'static str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS), ts(rename = "PaletteActionKind"))]
+#[serde(rename_all = "lowercase")]
+pub enum ActionKind {
+    Command,                           // A Tauri command; args are its parameters
+    Shortcut,                          // A global shortcut action; takes no args
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct PaletteAction {
+    pub id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub category: String,
+    pub kind: ActionKind,
+    pub params: serde_json::Value,     // JSON Schema object for the args
+    pub shortcut: Option<String>,      // Current accelerator
+    pub invokable: bool,
+}
+
+// Every action, or those matching all words of `query` (best matches first)
+pub fn registry(query: Option<&str>) -> Vec<PaletteAction> {
+    let bindings = global_shortcut_service::list();
+    let mut actions: Vec<PaletteAction> = global_shortcut_service::ACTIONS
+        .iter()
+        .map(|action| PaletteAction {
+            id: format!("{}{}", SHORTCUT_PREFIX, action),
+            title: humanize(action),
+            description: None,
+            category: "Shortcuts".to_string(),
+            kind: ActionKind::Shortcut,
+            params: serde_json::json!({ "type": "object", "properties": {} }),
+            shortcut: bindings.iter().find(|b| b.action == *action).and_then(|b| b.accelerator.clone()),
+            invokable: true,
+        })
+        .collect();
+    actions.extend(crate::commands::REGISTERED_COMMANDS.iter().map(|spec| PaletteAction {
+        id: spec.id.to_string(),
+        title: humanize(spec.id),
+        description: Some(spec.description.to_string()).filter(|d| !d.is_empty()),
+        category: spec.category.to_string(),
+        kind: ActionKind::Command,
+        params: params_schema(spec.params),
+        shortcut: None,
+        invokable: !NOT_INVOKABLE.contains(&spec.id),
+    }));
+
+    let terms: Vec<String> = query
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect();
+    if terms.is_empty() {
+        return actions;
+    }
+    let mut scored: Vec<(u32, PaletteAction)> = actions
+        .into_iter()
+        .filter_map(|action| score(&action, &terms).map(|s| (s, action)))
+        .collect();
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.title.cmp(&b.1.title)));
+    scored.into_iter().map(|(_, action)| action).collect()
+}
+
+// Run an action by id; commands get `args` (an object keyed by parameter name) and return their result
+pub async fn invoke(app: &tauri::AppHandle, id: &str, args: serde_json::Value) -> Result<serde_json::Value, PaletteError> {
+    if let Some(action) = id.strip_prefix(SHORTCUT_PREFIX) {
+        if !global_shortcut_service::ACTIONS.contains(&action) {
+            return Err(PaletteError::UnknownAction(id.to_string()));
+        }
+        global_shortcut_service::trigger(app, action, PALETTE_SOURCE);
+        return Ok(serde_json::Value::Null);
+    }
+    if NOT_INVOKABLE.contains(&id) {
+        return Err(PaletteError::NotInvokable(id.to_string()));
+    }
+    let args = match args {
+        serde_json::Value::Null => serde_json::Value::Object(Default::default()),
+        serde_json::Value::Object(map) => serde_json::Value::Object(map),
+        _ => return Err(PaletteError::InvalidArgs("args must be an object".to_string())),
+    };
+    tracing::debug!(target: "app", "Palette: Invoking {}", id);
+    match crate::commands::invoke_registered(app, id, &args).await {
+        Ok(Some(value)) => Ok(value),
+        Ok(None) => Err(PaletteError::UnknownAction(id.to_string())),
+        Err(e) => Err(PaletteError::Failed(e)),
+    }
+}
+
+// Used by the generated dispatcher: one argument, looked up the way Tauri does (camelCase, missing = null)
+pub fn arg<T: serde::de::DeserializeOwned>(args: &serde_json::Value, name: &str) -> Result<T, String> {
+    let value = args.get(name).cloned().unwrap_or(serde_json::Value::Null);
+    let missing = value.is_null();
+    serde_json::from_value(value).map_err(|e| {
+        if missing {
+            format!("Missing argument '{}'", name)
+        } else {
+            format!("Invalid argument '{}': {}", name, e)
+        }
+    })
+}
+
+pub fn state<'a, T: Send + Sync + 'static>(app: &'a tauri::AppHandle) -> Result<tauri::State<'a, T>, String> {
+    use tauri::Manager;
+    app.try_state::<T>().ok_or_else(|| format!("{} is not ready", std::any::type_name::<T>()))
+}
+
+pub fn to_value<T: Serialize>(result: Result<T, String>) -> Result<serde_json::Value, String> {
+    result.and_then(|value| serde_json::to_value(value).map_err(|e| e.to_string()))
+}
+
+// Substring matches on the id and title weigh most, then category, then description; None = no match
+fn score(action: &PaletteAction, terms: &[String]) -> Option<u32> {
+    let id = action.id.to_lowercase();
+    let title = action.title.to_lowercase();
+    let category = action.category.to_lowercase();
+    let description = action.description.as_deref().unwrap_or_default().to_lowercase();
+    let mut total = 0;
+    for term in terms {
+        total += if title.starts_with(term.as_str()) || id.starts_with(term.as_str()) {
+            8
+        } else if title.contains(term.as_str()) || id.contains(term.as_str()) {
+            5
+        } else if category.contains(term.as_str()) {
+            3
+        } else if description.contains(term.as_str()) {
+            1
+        } else {
+            return None;
+        };
+    }
+    Some(total)
+}
+
+// "layout_set_split" / "history:deleteUrl" / "toggle-privacy-mode" -> "Layout set split" / "History delete url" / ...
+fn humanize(id: &str) -> String {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    for c in id.chars() {
+        if matches!(c, '_' | '-' | ':' | '.') || (c.is_uppercase() && !current.is_empty()) {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            if !c.is_uppercase() {
+                continue;
+            }
+        }
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    let mut title = words.join(" ");
+    if let Some(first) = title.get(..1) {
+        title = first.to_uppercase() + &title[1..];
+    }
+    title
+}
+
+pub fn params_schema(params: &[ParamSpec]) -> serde_json::Value {
+    let mut properties = serde_json::Map::new();
+    let mut required = Vec::new();
+    for param in params {
+        let (schema, optional) = type_schema(param.rust_type);
+        properties.insert(param.name.to_string(), schema);
+        if !optional {
+            required.push(serde_json::Value::String(param.name.to_string()));
+        }
+    }
+    serde_json::json!({ "type": "object", "properties": properties, "required": required })
+}
+
+// Rust parameter type -> (JSON Schema, optional); named structs are described by their Rust type
+pub fn type_schema(rust_type: &str) -> (serde_json::Value, bool) {
+    let ty = rust_type.replace(' ', "");
+    if let Some(inner) = ty.strip_prefix("Option<").and_then(|t| t.strip_suffix('>')) {
+        return (type_schema(inner).0, true);
+    }
+    if let Some(inner) = ty.strip_prefix("Vec<").and_then(|t| t.strip_suffix('>')) {
+        return (serde_json::json!({ "type": "array", "items": type_schema(inner).0 }), false);
+    }
+    let schema = match ty.as_str() {
+        "String" | "&str" => serde_json::json!({ "type": "string" }),
+        "bool" => serde_json::json!({ "type": "boolean" }),
+        "u8" | "u16" | "u32" | "u64" | "usize" | "i8" | "i16" | "i32" | "i64" | "isize" => serde_json::json!({ "type": "integer" }),
+        "f32" | "f64" => serde_json::json!({ "type": "number" }),
+        "serde_json::Value" | "Value" => serde_json::json!({}),
+        t if t.starts_with("HashMap<") || t.starts_with("BTreeMap<") || t.contains("Map<String,") => {
+            serde_json::json!({ "type": "object" })
+        }
+        t => serde_json::json!({ "x-rust-type": t }),
+    };
+    (schema, false)
+}
+
+#[derive(Debug, Clone)]
+pub enum PaletteError {
+    UnknownAction(String),
+    NotInvokable(String),
+    InvalidArgs(String),
+    Failed(String),
+}
+
+impl std::fmt::Display for PaletteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PaletteError::UnknownAction(id) => write!(f, "Unknown action '{}'", id),
+            PaletteError::NotInvokable(id) => write!(f, "'{}' can't be run from the palette", id),
+            PaletteError::InvalidArgs(e) => write!(f, "Invalid action arguments: {}", e),
+            PaletteError::Failed(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for PaletteError {}
@@ -0,0 +1,437 @@
+// Passwords - Per-origin credentials in secure storage, a password generator, and breach checks
+// Each credential is encrypted again with a key derived from the master password (Argon2id), which
+// only lives in memory while unlocked; the vault locks itself after [passwords] idleLockMinutes
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::Argon2;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+use crate::secure_store::{SecureStore, SecureStoreError};
+
+const ENTRY_PREFIX: &str = "password.";
+// Salt and a known value encrypted with the master key; the password itself is never stored
+const MASTER_ENTRY: &str = "password-master";
+const MASTER_CHECK: &[u8] = b"omnibrowser-passwords";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+// k-anonymity range API: only the first 5 hex chars of the SHA-1 leave the machine
+const HIBP_RANGE_URL: &str = "https://api.pwnedpasswords.com/range/";
+const BREACH_TIMEOUT: Duration = Duration::from_secs(10);
+const LOCK_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
+const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGITS: &str = "0123456789";
+const SYMBOLS: &str = "!@#$%^&*()-_=+[]{};:,.?/~";
+const AMBIGUOUS: &str = "Il1O0o";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct PasswordPolicy {
+    pub length: usize,
+    pub lowercase: bool,
+    pub uppercase: bool,
+    pub digits: bool,
+    pub symbols: bool,
+    pub exclude_ambiguous: bool,       // Drop look-alikes (I, l, 1, O, 0, o) for passwords typed by hand
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self { length: 20, lowercase: true, uppercase: true, digits: true, symbols: true, exclude_ambiguous: false }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct Credential {
+    pub id: String,
+    pub origin: String,                // scheme://host[:port]
+    pub username: String,
+    pub password: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub last_used_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct BreachCheck {
+    pub breached: bool,
+    pub count: u64,                    // Times the password appears in known breaches
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct LockStatus {
+    pub locked: bool,
+    pub has_master_password: bool,     // False until the first unlock sets one
+    pub idle_lock_minutes: u32,        // 0 never locks on idle
+    pub locks_at: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct MasterRecord {
+    salt: String,                      // base64
+    check: String,                     // base64 nonce + MASTER_CHECK encrypted with the derived key
+}
+
+struct Unlocked {
+    cipher: Aes256Gcm,
+    last_activity: Instant,
+}
+
+#[derive(Clone)]
+pub struct PasswordVault {
+    store: SecureStore,
+    http: crate::http::Client,
+    unlocked: Arc<Mutex<Option<Unlocked>>>,  // None while locked
+}
+
+impl PasswordVault {
+    // Starts locked
+    pub fn new(store: SecureStore) -> Self {
+        Self {
+            store,
+            http: crate::http::Client::new(BREACH_TIMEOUT).with_retries(1).with_purpose("breach-check"),
+            unlocked: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    // Derive the vault key from the master password; the first unlock sets the master password
+    pub fn unlock(&self, master_password: &str) -> Result<LockStatus, PasswordError> {
+        if master_password.is_empty() {
+            return Err(PasswordError::Invalid("master password is empty".to_string()));
+        }
+        let cipher = match self.master_record() {
+            Some(record) => {
+                let salt = decode(&record.salt)?;
+                let cipher = derive_cipher(master_password, &salt)?;
+                match open_sealed(&cipher, &record.check) {
+                    Ok(check) if check == MASTER_CHECK => cipher,
+                    _ => {
+                        tracing::warn!(target: "privacy", "Passwords: Unlock with a wrong master password");
+                        return Err(PasswordError::WrongPassword);
+                    }
+                }
+            }
+            None => self.set_master(master_password)?,
+        };
+        *self.unlocked.lock().unwrap() = Some(Unlocked { cipher, last_activity: Instant::now() });
+        Ok(self.status())
+    }
+
+    pub fn lock(&self) -> LockStatus {
+        *self.unlocked.lock().unwrap() = None;
+        self.status()
+    }
+
+    pub fn status(&self) -> LockStatus {
+        let idle_lock_minutes = crate::config::current().passwords.idle_lock_minutes;
+        let last_activity = self.expire(idle_lock_minutes);
+        LockStatus {
+            locked: last_activity.is_none(),
+            has_master_password: self.master_record().is_some(),
+            idle_lock_minutes,
+            locks_at: last_activity.filter(|_| idle_lock_minutes > 0).map(|at| {
+                let remaining = idle_timeout(idle_lock_minutes).saturating_sub(at.elapsed());
+                chrono::Utc::now().timestamp() + remaining.as_secs() as i64
+            }),
+        }
+    }
+
+    // Insert or update the credential for (origin, username)
+    pub fn save(&self, origin: &str, username: &str, password: &str) -> Result<Credential, PasswordError> {
+        let cipher = self.touch()?;
+        let origin = normalize_origin(origin)?;
+        if password.is_empty() {
+            return Err(PasswordError::Invalid("password is empty".to_string()));
+        }
+        let username = username.trim();
+        let now = chrono::Utc::now().timestamp();
+        let credential = match self.entries(&cipher).into_iter().find(|c| c.origin == origin && c.username == username) {
+            Some(existing) => Credential { password: password.to_string(), updated_at: now, ..existing },
+            None => Credential {
+                id: uuid::Uuid::new_v4().to_string(),
+                origin,
+                username: username.to_string(),
+                password: password.to_string(),
+                created_at: now,
+                updated_at: now,
+                last_used_at: None,
+            },
+        };
+        self.write(&cipher, &credential)?;
+        Ok(credential)
+    }
+
+    // Credentials for the page's origin, most recently used first
+    pub fn find(&self, origin: &str) -> Result<Vec<Credential>, PasswordError> {
+        let cipher = self.touch()?;
+        let origin = normalize_origin(origin)?;
+        let mut found: Vec<Credential> = self.entries(&cipher).into_iter().filter(|c| c.origin == origin).collect();
+        found.sort_by(|a, b| {
+            b.last_used_at
+                .unwrap_or(b.updated_at)
+                .cmp(&a.last_used_at.unwrap_or(a.updated_at))
+                .then_with(|| a.username.cmp(&b.username))
+        });
+        Ok(found)
+    }
+
+    // Called when the frontend fills a credential, so find() ranks it first next time
+    pub fn mark_used(&self, id: &str) -> Result<(), PasswordError> {
+        let cipher = self.touch()?;
+        let mut credential = self.entry(&cipher, id).ok_or_else(|| PasswordError::NotFound(id.to_string()))?;
+        credential.last_used_at = Some(chrono::Utc::now().timestamp());
+        self.write(&cipher, &credential)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<bool, PasswordError> {
+        self.touch()?;
+        Ok(self.store.remove(&entry_name(id))?)
+    }
+
+    pub async fn breach_check(&self, password: &str) -> Result<BreachCheck, PasswordError> {
+        if password.is_empty() {
+            return Err(PasswordError::Invalid("password is empty".to_string()));
+        }
+        let hash: String = Sha1::digest(password.as_bytes()).iter().map(|b| format!("{:02X}", b)).collect();
+        let (prefix, suffix) = hash.split_at(5);
+
+        // Padding hides the real response size from anyone watching the connection
+        let response = self
+            .http
+            .send(self.http.get(format!("{}{}", HIBP_RANGE_URL, prefix)).header("Add-Padding", "true"))
+            .await
+            .map_err(|e| PasswordError::BreachCheck(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(PasswordError::BreachCheck(format!("HTTP {}", response.status().as_u16())));
+        }
+        let body = response.text().await.map_err(|e| PasswordError::BreachCheck(e.to_string()))?;
+
+        let count = body
+            .lines()
+            .filter_map(|line| line.trim().split_once(':'))
+            .find(|(candidate, _)| candidate.eq_ignore_ascii_case(suffix))
+            .and_then(|(_, count)| count.trim().parse::<u64>().ok())
+            .unwrap_or(0);
+        Ok(BreachCheck { breached: count > 0, count })
+    }
+
+    // Emit "passwords-locked" once the idle timeout passes, so open fill prompts close
+    pub fn spawn_idle_lock(&self, app: tauri::AppHandle) {
+        let vault = self.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut ticker = tokio::time::interval(LOCK_CHECK_INTERVAL);
+            loop {
+                ticker.tick().await;
+                let was_unlocked = vault.unlocked.lock().unwrap().is_some();
+                if was_unlocked && vault.status().locked {
+                    tracing::info!(target: "privacy", "Passwords: Locked after idle timeout");
+                    let _ = app.emit("passwords-locked", ());
+                }
+            }
+        });
+    }
+
+    // Lock (dropping the key) if idle too long; returns the last activity while still unlocked
+    fn expire(&self, idle_lock_minutes: u32) -> Option<Instant> {
+        let mut unlocked = self.unlocked.lock().unwrap();
+        if idle_lock_minutes > 0 && unlocked.as_ref().is_some_and(|u| u.last_activity.elapsed() >= idle_timeout(idle_lock_minutes)) {
+            *unlocked = None;
+        }
+        unlocked.as_ref().map(|u| u.last_activity)
+    }
+
+    // Every read or write counts as activity; returns the key for it
+    fn touch(&self) -> Result<Aes256Gcm, PasswordError> {
+        self.expire(crate::config::current().passwords.idle_lock_minutes);
+        let mut unlocked = self.unlocked.lock().unwrap();
+        let unlocked = unlocked.as_mut().ok_or(PasswordError::Locked)?;
+        unlocked.last_activity = Instant::now();
+        Ok(unlocked.cipher.clone())
+    }
+
+    fn master_record(&self) -> Option<MasterRecord> {
+        serde_json::from_str(&self.store.get(MASTER_ENTRY)?).ok()
+    }
+
+    fn set_master(&self, master_password: &str) -> Result<Aes256Gcm, PasswordError> {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let cipher = derive_cipher(master_password, &salt)?;
+        let record = MasterRecord { salt: encode(&salt), check: seal(&cipher, MASTER_CHECK)? };
+        let json = serde_json::to_string(&record).map_err(|e| PasswordError::Storage(e.to_string()))?;
+        self.store.set(MASTER_ENTRY, &json)?;
+        tracing::info!(target: "privacy", "Passwords: Master password set");
+        Ok(cipher)
+    }
+
+    fn entries(&self, cipher: &Aes256Gcm) -> Vec<Credential> {
+        self.store
+            .names(ENTRY_PREFIX)
+            .iter()
+            .filter_map(|name| self.store.get(name))
+            .filter_map(|sealed| open_credential(cipher, &sealed))
+            .collect()
+    }
+
+    fn entry(&self, cipher: &Aes256Gcm, id: &str) -> Option<Credential> {
+        open_credential(cipher, &self.store.get(&entry_name(id))?)
+    }
+
+    fn write(&self, cipher: &Aes256Gcm, credential: &Credential) -> Result<(), PasswordError> {
+        let json = serde_json::to_string(credential).map_err(|e| PasswordError::Invalid(e.to_string()))?;
+        Ok(self.store.set(&entry_name(&credential.id), &seal(cipher, json.as_bytes())?)?)
+    }
+}
+
+// Argon2id with the crate's default cost (19 MiB, 2 passes)
+fn derive_cipher(master_password: &str, salt: &[u8]) -> Result<Aes256Gcm, PasswordError> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(master_password.as_bytes(), salt, &mut key)
+        .map_err(|e| PasswordError::Storage(format!("key derivation failed: {}", e)))?;
+    Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)))
+}
+
+// base64(nonce + ciphertext)
+fn seal(cipher: &Aes256Gcm, plaintext: &[u8]) -> Result<String, PasswordError> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| PasswordError::Storage("encryption failed".to_string()))?;
+    let mut bytes = nonce.to_vec();
+    bytes.extend_from_slice(&ciphertext);
+    Ok(encode(&bytes))
+}
+
+fn open_sealed(cipher: &Aes256Gcm, sealed: &str) -> Result<Vec<u8>, PasswordError> {
+    let bytes = decode(sealed)?;
+    if bytes.len() <= NONCE_LEN {
+        return Err(PasswordError::Storage("sealed entry is truncated".to_string()));
+    }
+    let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| PasswordError::WrongPassword)
+}
+
+fn open_credential(cipher: &Aes256Gcm, sealed: &str) -> Option<Credential> {
+    serde_json::from_slice(&open_sealed(cipher, sealed).ok()?).ok()
+}
+
+fn encode(bytes: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+fn decode(text: &str) -> Result<Vec<u8>, PasswordError> {
+    base64::engine::general_purpose::STANDARD
+        .decode(text)
+        .map_err(|e| PasswordError::Storage(format!("corrupt vault entry: {}", e)))
+}
+
+pub fn generate(policy: &PasswordPolicy) -> Result<String, PasswordError> {
+    if !(8..=128).contains(&policy.length) {
+        return Err(PasswordError::Invalid("length must be between 8 and 128".to_string()));
+    }
+    let classes: Vec<Vec<char>> = [
+        (policy.lowercase, LOWERCASE),
+        (policy.uppercase, UPPERCASE),
+        (policy.digits, DIGITS),
+        (policy.symbols, SYMBOLS),
+    ]
+    .into_iter()
+    .filter(|(enabled, _)| *enabled)
+    .map(|(_, chars)| chars.chars().filter(|c| !policy.exclude_ambiguous || !AMBIGUOUS.contains(*c)).collect())
+    .collect();
+    if classes.is_empty() {
+        return Err(PasswordError::Invalid("enable at least one character class".to_string()));
+    }
+
+    // One from each enabled class, the rest from all of them, then shuffle
+    let all: Vec<char> = classes.iter().flatten().copied().collect();
+    let mut chars: Vec<char> = classes.iter().map(|class| class[random_below(class.len())]).collect();
+    while chars.len() < policy.length {
+        chars.push(all[random_below(all.len())]);
+    }
+    for i in (1..chars.len()).rev() {
+        chars.swap(i, random_below(i + 1));
+    }
+    Ok(chars.into_iter().collect())
+}
+
+// Uniform in 0..bound (rejection sampling avoids modulo bias)
+fn random_below(bound: usize) -> usize {
+    let bound = bound as u32;
+    let zone = u32::MAX - (u32::MAX % bound);
+    loop {
+        let value = OsRng.next_u32();
+        if value < zone {
+            return (value % bound) as usize;
+        }
+    }
+}
+
+// Page URL or bare host -> origin; paths and queries don't matter for matching
+pub fn normalize_origin(input: &str) -> Result<String, PasswordError> {
+    let input = input.trim();
+    let url = url::Url::parse(input)
+        .or_else(|_| url::Url::parse(&format!("https://{}", input)))
+        .map_err(|_| PasswordError::Invalid(format!("'{}' is not a URL or host", input)))?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return Err(PasswordError::Invalid(format!("'{}' has no web origin", input)));
+    }
+    Ok(url.origin().ascii_serialization())
+}
+
+fn idle_timeout(minutes: u32) -> Duration {
+    Duration::from_secs(u64::from(minutes) * 60)
+}
+
+fn entry_name(id: &str) -> String {
+    format!("{}{}", ENTRY_PREFIX, id)
+}
+
+#[derive(Debug, Clone)]
+pub enum PasswordError {
+    Locked,
+    WrongPassword,
+    NotFound(String),
+    Invalid(String),
+    BreachCheck(String),
+    Storage(String),
+}
+
+impl From<SecureStoreError> for PasswordError {
+    fn from(e: SecureStoreError) -> Self {
+        PasswordError::Storage(e.to_string())
+    }
+}
+
+impl std::fmt::Display for PasswordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PasswordError::Locked => write!(f, "Passwords are locked"),
+            PasswordError::WrongPassword => write!(f, "Wrong master password"),
+            PasswordError::NotFound(id) => write!(f, "Credential not found: {}", id),
+            PasswordError::Invalid(msg) => write!(f, "Invalid credential: {}", msg),
+            PasswordError::BreachCheck(msg) => write!(f, "Breach check failed: {}", msg),
+            PasswordError::Storage(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for PasswordError {}
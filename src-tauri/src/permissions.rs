@@ -0,0 +1,240 @@
+// Permissions - Capability checks on every Tauri command before it runs
+// Each command declares the capability it requires (see `required`); the calling webview's label and the origin of the
+// frame that sent the invoke must be granted it. The app's own windows get built-in grants, [permissions]
+// grants add more, and anything else (remote pages, third-party iframes) is refused and logged.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Mutex, RwLock};
+use tauri::ipc::{Invoke, InvokeMessage};
+use tauri::Runtime;
+
+use crate::browser::MAIN_WINDOW;
+
+const MAX_VIOLATIONS: usize = 200;
+// Matches the app's own frontend (tauri://localhost, http(s)://tauri.localhost, the dev server)
+pub const APP_ORIGIN: &str = "app";
+const ANY: &str = "*";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "kebab-case")]
+pub enum Capability {
+    Browsing,                          // Tabs, windows, navigation, layout, page-level helpers
+    History,                           // History, saved pages, archives, downloads list, usage
+    Content,                           // Notes, highlights, research sessions, feeds, journal, notifications
+    Ai,                                // Local/cloud model calls, documents already processed, prompts
+    Agent,                             // Agent tools and approving their actions
+    Network,                           // Outbound fetches to arbitrary URLs
+    Files,                             // Reading or writing local paths
+    OpenExternal,                      // Launching other apps on a file
+    Capture,                           // Screenshots and clipboard monitoring
+    Secrets,                           // API keys, passwords, autofill profiles
+    Settings,                          // Preferences, privacy mode, profiles, shortcuts, filters, scripts
+    System,                            // Config, services, logs, diagnostics, and anything not declared
+}
+
+impl Capability {
+    pub const ALL: [Capability; 12] = [
+        Capability::Browsing,
+        Capability::History,
+        Capability::Content,
+        Capability::Ai,
+        Capability::Agent,
+        Capability::Network,
+        Capability::Files,
+        Capability::OpenExternal,
+        Capability::Capture,
+        Capability::Secrets,
+        Capability::Settings,
+        Capability::System,
+    ];
+}
+
+// Windows matching `window` whose caller matches `origin` may run commands needing `capabilities`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct Grant {
+    pub window: String,                // Webview label, or "*"
+    pub origin: String,                // "app", "https://example.com", "https://*.example.com", or "*"
+    pub capabilities: Vec<Capability>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct Violation {
+    pub command: String,
+    pub capability: Capability,
+    pub window: String,
+    pub origin: String,
+    pub at: i64,                       // Unix seconds
+}
+
+// [permissions] grants, on top of the built-in ones
+static EXTRA_GRANTS: RwLock<Vec<Grant>> = RwLock::new(Vec::new());
+static VIOLATIONS: Mutex<VecDeque<Violation>> = Mutex::new(VecDeque::new());
+
+// Capability a command needs, from the `// capability:` line above it in commands.rs (build.rs refuses
+// to build a registered command without one). Anything else needs System.
+pub fn required(command: &str) -> Capability {
+    crate::commands::command_capability(command).unwrap_or(Capability::System)
+}
+
+// The app's own windows; the main window runs the whole UI
+fn builtin_grants() -> Vec<Grant> {
+    let grant = |window: &str, capabilities: &[Capability]| Grant {
+        window: window.to_string(),
+        origin: APP_ORIGIN.to_string(),
+        capabilities: capabilities.to_vec(),
+    };
+    vec![
+        grant(MAIN_WINDOW, &Capability::ALL),
+        grant("wispr-orb", &[Capability::Browsing, Capability::Ai, Capability::Agent]),
+        grant("trade-ticker", &[Capability::Browsing, Capability::Content, Capability::Network]),
+    ]
+}
+
+// Called from config::apply
+pub fn apply(grants: &[Grant]) {
+    *EXTRA_GRANTS.write().unwrap() = grants.to_vec();
+}
+
+pub fn grants() -> Vec<Grant> {
+    let mut grants = builtin_grants();
+    grants.extend(EXTRA_GRANTS.read().unwrap().iter().cloned());
+    grants
+}
+
+// Check an incoming invoke against the grant table
+pub fn authorize<R: Runtime>(message: &InvokeMessage<R>) -> Result<(), PermissionError> {
+    let command = message.command();
+    let capability = required(command);
+    let webview = message.webview_ref();
+    let window = webview.label().to_string();
+    // The Origin header is the frame that called invoke, which differs from the webview's URL for iframes
+    let origin = message
+        .headers()
+        .get("Origin")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| url::Url::parse(value).ok())
+        .or_else(|| webview.url().ok())
+        .map(|url| url.origin().ascii_serialization())
+        .unwrap_or_else(|| "null".to_string());
+    let app_origin = is_app_origin(webview, &origin);
+
+    let granted = grants().iter().any(|grant| {
+        (grant.window == ANY || grant.window == window)
+            && origin_matches(&grant.origin, &origin, app_origin)
+            && grant.capabilities.contains(&capability)
+    });
+    if granted {
+        return Ok(());
+    }
+
+    tracing::warn!(
+        target: "privacy",
+        "Permissions: Refused {} from window {} ({}): needs {:?}",
+        command, window, origin, capability
+    );
+    let violation = Violation {
+        command: command.to_string(),
+        capability,
+        window: window.clone(),
+        origin: origin.clone(),
+        at: chrono::Utc::now().timestamp(),
+    };
+    {
+        let mut violations = VIOLATIONS.lock().unwrap();
+        violations.push_front(violation);
+        violations.truncate(MAX_VIOLATIONS);
+    }
+    Err(PermissionError::Denied { command: command.to_string(), capability, window, origin })
+}
+
+// Wraps tauri::generate_handler! so every command passes `authorize` first; refusals reject the invoke
+// with the serialized PermissionError
+pub fn guard<R: Runtime>(
+    handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke: Invoke<R>| match authorize(&invoke.message) {
+        Ok(()) => handler(invoke),
+        Err(e) => {
+            invoke.resolver.reject(e);
+            true
+        }
+    }
+}
+
+// Most recent refusals first
+pub fn violations(limit: usize) -> Vec<Violation> {
+    VIOLATIONS.lock().unwrap().iter().take(limit).cloned().collect()
+}
+
+pub fn validate(grants: &[Grant]) -> Result<(), (usize, String)> {
+    for (index, grant) in grants.iter().enumerate() {
+        if grant.window.trim().is_empty() {
+            return Err((index, "window is empty (use \"*\" for every window)".to_string()));
+        }
+        let origin = grant.origin.as_str();
+        if origin != APP_ORIGIN && origin != ANY {
+            let url = url::Url::parse(&origin.replacen("://*.", "://wildcard.", 1))
+                .map_err(|_| (index, format!("origin '{}' is not \"app\", \"*\", or a URL origin", origin)))?;
+            if url.origin().ascii_serialization() != origin.replacen("://*.", "://wildcard.", 1) {
+                return Err((index, format!("origin '{}' must be scheme://host[:port] with no path", origin)));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_app_origin<R: Runtime>(webview: &tauri::Webview<R>, origin: &str) -> bool {
+    if matches!(origin, "tauri://localhost" | "http://tauri.localhost" | "https://tauri.localhost") {
+        return true;
+    }
+    cfg!(debug_assertions)
+        && webview
+            .config()
+            .build
+            .dev_url
+            .as_ref()
+            .is_some_and(|dev| dev.origin().ascii_serialization() == origin)
+}
+
+fn origin_matches(pattern: &str, origin: &str, app_origin: bool) -> bool {
+    match pattern {
+        ANY => true,
+        APP_ORIGIN => app_origin,
+        _ => match pattern.split_once("://*.") {
+            // "https://*.example.com" covers example.com and its subdomains
+            Some((scheme, domain)) => origin
+                .strip_prefix(scheme)
+                .and_then(|rest| rest.strip_prefix("://"))
+                .is_some_and(|host| host == domain || host.ends_with(&format!(".{}", domain))),
+            None => pattern == origin,
+        },
+    }
+}
+
+// Sent to the frontend as the invoke's rejection: { code: "denied", command, capability, window, origin }
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(tag = "code", rename_all = "kebab-case")]
+pub enum PermissionError {
+    Denied { command: String, capability: Capability, window: String, origin: String },
+}
+
+impl std::fmt::Display for PermissionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PermissionError::Denied { command, capability, window, origin } => write!(
+                f,
+                "'{}' needs the {:?} capability, which window '{}' ({}) has not been granted",
+                command, capability, window, origin
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PermissionError {}
@@ -3,6 +3,8 @@
 // UI cannot override these rules
 
 use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
 use crate::state::PrivacyMode as StatePrivacyMode;
 
 // Re-export for commands.rs
@@ -167,7 +169,7 @@ impl PrivacyEnforcer {
 
     // Violation handler - called when privacy rule is violated
     pub fn handle_violation(&mut self, violation: PrivacyViolation) -> PrivacyAction {
-        eprintln!("[Privacy] Violation detected: {:?}", violation);
+        tracing::warn!(target: "privacy", "Violation detected: {:?}", violation);
         
         // In Ghost mode, violations are critical
         if matches!(self.current_policy.mode, PrivacyMode::Ghost) {
@@ -228,3 +230,20 @@ impl std::fmt::Display for PrivacyError {
 }
 
 impl std::error::Error for PrivacyError {}
+
+// Switch modes and bring every mode-dependent subsystem along; emits "privacy-mode-changed".
+// The command, the shortcut toggle, and anything else that changes modes go through here.
+pub fn apply_privacy_mode(app: &tauri::AppHandle, mode: PrivacyMode) -> Result<PrivacyPolicy, String> {
+    let enforcer = app.try_state::<Mutex<PrivacyEnforcer>>().ok_or("Privacy enforcer not ready")?;
+    let policy = enforcer.lock().unwrap().set_mode(mode);
+    crate::webrtc_guard::apply(app, &policy.mode);
+    crate::doh::set_privacy_mode(&policy.mode);
+    crate::services::hotword_service::set_privacy_mode(app, &policy.mode);
+    crate::tray::refresh(app);
+    // Private/Ghost modes must not read or write cached AI responses
+    if let Some(cache) = app.try_state::<crate::ai::AIService>().as_ref().and_then(|ai| ai.cache()) {
+        cache.set_enabled(policy.allow_cache);
+    }
+    let _ = app.emit("privacy-mode-changed", format!("{:?}", policy.mode).to_lowercase());
+    Ok(policy)
+}
@@ -0,0 +1,215 @@
+// Profiles - Isolated workspaces ("Work", "Personal", "Trading") within one install
+// Each profile owns its own database, session, downloads, and secure storage directories
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const DEFAULT_PROFILE: &str = "Default";
+const REGISTRY_FILE: &str = "profiles.json";
+const MAX_NAME_LEN: usize = 64;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    pub name: String,
+    pub slug: String,                  // Directory name under profiles/
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileInfo {
+    pub name: String,
+    pub created_at: i64,
+    pub active: bool,
+    pub data_dir: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Registry {
+    active: String,
+    profiles: Vec<Profile>,
+}
+
+pub struct ProfileManager {
+    root: PathBuf,                     // App data directory
+    registry: Mutex<Registry>,
+}
+
+impl ProfileManager {
+    // Load (or create) the profile registry under the app data directory
+    pub fn load(root: PathBuf) -> Self {
+        let registry_path = root.join("profiles").join(REGISTRY_FILE);
+        let mut registry: Registry = std::fs::read_to_string(&registry_path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+
+        if !registry.profiles.iter().any(|p| p.name == DEFAULT_PROFILE) {
+            registry.profiles.insert(0, Profile {
+                name: DEFAULT_PROFILE.to_string(),
+                slug: String::new(),
+                created_at: chrono::Utc::now().timestamp(),
+            });
+        }
+        if !registry.profiles.iter().any(|p| p.name == registry.active) {
+            registry.active = DEFAULT_PROFILE.to_string();
+        }
+
+        Self { root, registry: Mutex::new(registry) }
+    }
+
+    pub fn active(&self) -> String {
+        self.registry.lock().unwrap().active.clone()
+    }
+
+    pub fn list(&self) -> Vec<ProfileInfo> {
+        let registry = self.registry.lock().unwrap();
+        registry
+            .profiles
+            .iter()
+            .map(|p| ProfileInfo {
+                name: p.name.clone(),
+                created_at: p.created_at,
+                active: p.name == registry.active,
+                data_dir: self.dir_for(p).to_string_lossy().to_string(),
+            })
+            .collect()
+    }
+
+    pub fn create(&self, name: &str) -> Result<ProfileInfo, ProfileError> {
+        let name = name.trim();
+        if name.is_empty() || name.len() > MAX_NAME_LEN {
+            return Err(ProfileError::InvalidName(name.to_string()));
+        }
+        let slug = slugify(name);
+        if slug.is_empty() {
+            return Err(ProfileError::InvalidName(name.to_string()));
+        }
+
+        let profile = {
+            let mut registry = self.registry.lock().unwrap();
+            if registry
+                .profiles
+                .iter()
+                .any(|p| p.name.eq_ignore_ascii_case(name) || p.slug == slug)
+            {
+                return Err(ProfileError::AlreadyExists(name.to_string()));
+            }
+            let profile = Profile {
+                name: name.to_string(),
+                slug,
+                created_at: chrono::Utc::now().timestamp(),
+            };
+            registry.profiles.push(profile.clone());
+            profile
+        };
+
+        let dir = self.dir_for(&profile);
+        std::fs::create_dir_all(&dir).map_err(|e| ProfileError::Io(e.to_string()))?;
+        self.persist()?;
+
+        Ok(ProfileInfo {
+            name: profile.name,
+            created_at: profile.created_at,
+            active: false,
+            data_dir: dir.to_string_lossy().to_string(),
+        })
+    }
+
+    // Mark a profile active; callers are responsible for reopening per-profile state
+    pub fn set_active(&self, name: &str) -> Result<(), ProfileError> {
+        {
+            let mut registry = self.registry.lock().unwrap();
+            let profile = registry
+                .profiles
+                .iter()
+                .find(|p| p.name == name)
+                .ok_or_else(|| ProfileError::NotFound(name.to_string()))?;
+            registry.active = profile.name.clone();
+        }
+        self.persist()
+    }
+
+    // Root data directory of the active profile
+    pub fn data_dir(&self) -> PathBuf {
+        let registry = self.registry.lock().unwrap();
+        registry
+            .profiles
+            .iter()
+            .find(|p| p.name == registry.active)
+            .map(|p| self.dir_for(p))
+            .unwrap_or_else(|| self.root.clone())
+    }
+
+    pub fn database_path(&self) -> PathBuf {
+        self.data_dir().join("regen.db")
+    }
+
+    pub fn downloads_dir(&self) -> PathBuf {
+        self.data_dir().join("downloads")
+    }
+
+    pub fn secure_storage_dir(&self) -> PathBuf {
+        self.data_dir().join("secure")
+    }
+
+    // The Default profile keeps using the app data root so existing installs keep their data
+    fn dir_for(&self, profile: &Profile) -> PathBuf {
+        if profile.slug.is_empty() {
+            self.root.clone()
+        } else {
+            self.root.join("profiles").join(&profile.slug)
+        }
+    }
+
+    fn persist(&self) -> Result<(), ProfileError> {
+        let dir = self.root.join("profiles");
+        std::fs::create_dir_all(&dir).map_err(|e| ProfileError::Io(e.to_string()))?;
+        let json = serde_json::to_string_pretty(&*self.registry.lock().unwrap())
+            .map_err(|e| ProfileError::Io(e.to_string()))?;
+        write_atomic(&dir.join(REGISTRY_FILE), json.as_bytes())
+    }
+}
+
+fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_matches('-').to_string()
+}
+
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), ProfileError> {
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, bytes).map_err(|e| ProfileError::Io(e.to_string()))?;
+    std::fs::rename(&tmp, path).map_err(|e| ProfileError::Io(e.to_string()))
+}
+
+#[derive(Debug, Clone)]
+pub enum ProfileError {
+    InvalidName(String),
+    AlreadyExists(String),
+    NotFound(String),
+    Io(String),
+}
+
+impl std::fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProfileError::InvalidName(name) => write!(f, "Invalid profile name: {:?}", name),
+            ProfileError::AlreadyExists(name) => write!(f, "Profile {} already exists", name),
+            ProfileError::NotFound(name) => write!(f, "Profile {} not found", name),
+            ProfileError::Io(msg) => write!(f, "Profile storage error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ProfileError {}
@@ -0,0 +1,236 @@
+// Prompt Registry - Templated prompts keyed by task, language, and app mode
+// Defaults are embedded from prompts/defaults.toml; user overrides live in <app data>/prompts.toml
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+
+const DEFAULTS: &str = include_str!("prompts/defaults.toml");
+const FALLBACK_LANGUAGE: &str = "en";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptTemplate {
+    pub task: String,
+    pub language: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub template: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct PromptInfo {
+    pub task: String,
+    pub language: String,
+    pub mode: Option<String>,
+    pub description: Option<String>,
+    pub template: String,
+    pub variables: Vec<String>,
+    pub overridden: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PromptFile {
+    #[serde(default)]
+    prompt: Vec<PromptTemplate>,
+}
+
+type PromptKey = (String, String, Option<String>); // (task, language, mode)
+
+pub struct PromptRegistry {
+    defaults: BTreeMap<PromptKey, PromptTemplate>,
+    overrides: RwLock<BTreeMap<PromptKey, PromptTemplate>>,
+    overrides_path: RwLock<Option<PathBuf>>,
+}
+
+// Process-wide registry (prompts are needed in modules that don't see Tauri state)
+pub fn registry() -> &'static PromptRegistry {
+    static REGISTRY: OnceLock<PromptRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let file: PromptFile = toml::from_str(DEFAULTS).expect("embedded prompts/defaults.toml is invalid");
+        PromptRegistry {
+            defaults: file.prompt.into_iter().map(|p| (key_of(&p), p)).collect(),
+            overrides: RwLock::new(BTreeMap::new()),
+            overrides_path: RwLock::new(None),
+        }
+    })
+}
+
+// Shorthand for registry().render(...)
+pub fn render(task: &str, language: Option<&str>, mode: Option<&str>, vars: &[(&str, &str)]) -> String {
+    registry().render(task, language, mode, vars)
+}
+
+impl PromptRegistry {
+    // Load user overrides (called once the app data directory is known)
+    pub fn load_overrides(&self, path: PathBuf) -> Result<usize, PromptError> {
+        let overrides: BTreeMap<PromptKey, PromptTemplate> = match std::fs::read_to_string(&path) {
+            Ok(contents) => {
+                let file: PromptFile = toml::from_str(&contents).map_err(|e| PromptError::InvalidFile(e.to_string()))?;
+                file.prompt.into_iter().map(|p| (key_of(&p), p)).collect()
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(PromptError::Io(e.to_string())),
+        };
+
+        let count = overrides.len();
+        *self.overrides.write().unwrap() = overrides;
+        *self.overrides_path.write().unwrap() = Some(path);
+        Ok(count)
+    }
+
+    // Most specific template: exact mode, then any mode; requested language, then English
+    pub fn get(&self, task: &str, language: Option<&str>, mode: Option<&str>) -> Option<PromptTemplate> {
+        let language = language.unwrap_or(FALLBACK_LANGUAGE);
+        let overrides = self.overrides.read().unwrap();
+
+        let mut candidates: Vec<PromptKey> = Vec::new();
+        for lang in [language, FALLBACK_LANGUAGE] {
+            if let Some(mode) = mode {
+                candidates.push((task.to_string(), lang.to_string(), Some(mode.to_string())));
+            }
+            candidates.push((task.to_string(), lang.to_string(), None));
+        }
+
+        candidates
+            .iter()
+            .find_map(|key| overrides.get(key).or_else(|| self.defaults.get(key)))
+            .cloned()
+    }
+
+    // Fill {{variables}}; unknown tasks fall back to the raw variables so callers still get a prompt
+    pub fn render(&self, task: &str, language: Option<&str>, mode: Option<&str>, vars: &[(&str, &str)]) -> String {
+        let Some(prompt) = self.get(task, language, mode) else {
+            tracing::warn!(target: "ai", "Prompts: No template for task '{}'", task);
+            return vars.iter().map(|(_, v)| *v).collect::<Vec<_>>().join("\n\n");
+        };
+
+        let mut output = prompt.template.trim().to_string();
+        for (name, value) in vars {
+            output = output.replace(&format!("{{{{{}}}}}", name), value);
+        }
+        for missing in variables(&output) {
+            tracing::warn!(target: "ai", "Prompts: Template '{}' left {{{{{}}}}} unfilled", task, missing);
+        }
+        output
+    }
+
+    pub fn list(&self) -> Vec<PromptInfo> {
+        let overrides = self.overrides.read().unwrap();
+        let mut keys: Vec<&PromptKey> = self.defaults.keys().chain(overrides.keys()).collect();
+        keys.sort();
+        keys.dedup();
+
+        keys.into_iter()
+            .filter_map(|key| {
+                let overridden = overrides.contains_key(key);
+                let prompt = overrides.get(key).or_else(|| self.defaults.get(key))?;
+                Some(PromptInfo {
+                    task: prompt.task.clone(),
+                    language: prompt.language.clone(),
+                    mode: prompt.mode.clone(),
+                    description: prompt
+                        .description
+                        .clone()
+                        .or_else(|| self.defaults.get(key).and_then(|d| d.description.clone())),
+                    variables: variables(&prompt.template),
+                    template: prompt.template.clone(),
+                    overridden,
+                })
+            })
+            .collect()
+    }
+
+    // Set (Some) or reset (None) a user override and persist the override file
+    pub fn set_override(&self, task: &str, language: &str, mode: Option<&str>, template: Option<String>) -> Result<(), PromptError> {
+        let key: PromptKey = (task.to_string(), language.to_string(), mode.map(|m| m.to_string()));
+        {
+            let mut overrides = self.overrides.write().unwrap();
+            match template {
+                Some(template) if !template.trim().is_empty() => {
+                    // Overrides must keep the variables callers provide
+                    if let Some(default) = self.defaults.get(&key) {
+                        let missing: Vec<String> = variables(&default.template)
+                            .into_iter()
+                            .filter(|v| !template.contains(&format!("{{{{{}}}}}", v)))
+                            .collect();
+                        if !missing.is_empty() {
+                            return Err(PromptError::MissingVariables(missing));
+                        }
+                    }
+                    overrides.insert(key.clone(), PromptTemplate {
+                        task: key.0.clone(),
+                        language: key.1.clone(),
+                        mode: key.2.clone(),
+                        description: None,
+                        template,
+                    });
+                }
+                _ => {
+                    overrides.remove(&key);
+                }
+            }
+        }
+        self.persist()
+    }
+
+    fn persist(&self) -> Result<(), PromptError> {
+        let Some(path) = self.overrides_path.read().unwrap().clone() else {
+            return Err(PromptError::Io("prompt overrides path not initialized".to_string()));
+        };
+        let file = PromptFile { prompt: self.overrides.read().unwrap().values().cloned().collect() };
+        let contents = toml::to_string_pretty(&file).map_err(|e| PromptError::Io(e.to_string()))?;
+        write_file(&path, &contents)
+    }
+}
+
+fn key_of(prompt: &PromptTemplate) -> PromptKey {
+    (prompt.task.clone(), prompt.language.clone(), prompt.mode.clone())
+}
+
+// Names of {{variables}} in a template, in order of first use
+pub fn variables(template: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}") else { break };
+        let name = rest[start + 2..start + 2 + end].trim().to_string();
+        if !name.is_empty() && !names.contains(&name) {
+            names.push(name);
+        }
+        rest = &rest[start + 2 + end + 2..];
+    }
+    names
+}
+
+fn write_file(path: &Path, contents: &str) -> Result<(), PromptError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| PromptError::Io(e.to_string()))?;
+    }
+    std::fs::write(path, contents).map_err(|e| PromptError::Io(e.to_string()))
+}
+
+#[derive(Debug, Clone)]
+pub enum PromptError {
+    InvalidFile(String),
+    MissingVariables(Vec<String>),
+    Io(String),
+}
+
+impl std::fmt::Display for PromptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PromptError::InvalidFile(msg) => write!(f, "Invalid prompts file: {}", msg),
+            PromptError::MissingVariables(vars) => {
+                write!(f, "Template must keep variables: {}", vars.iter().map(|v| format!("{{{{{}}}}}", v)).collect::<Vec<_>>().join(", "))
+            }
+            PromptError::Io(msg) => write!(f, "Prompt storage error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for PromptError {}
@@ -0,0 +1,224 @@
+// Provider Health - Active probes of every backend the app depends on, for one diagnostics panel
+// Cloud LLMs and keyed APIs reuse the API key test; the rest get a cheap unauthenticated request
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+use crate::apikeys::{ApiKeyStore, Provider};
+use crate::http::{self, HttpError};
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(8);
+// A reachable provider slower than this is reported as degraded
+const SLOW_MS: u64 = 3_000;
+const BROWSER_UA: &str = "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0 Safari/537.36";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderKind {
+    Llm,
+    Search,
+    Broker,                            // Market data, quotes, and option chains
+    Local,                             // Services on this machine
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub enum HealthStatus {
+    Ok,
+    Degraded,                          // Reachable but slow, rate limited, or refusing the request
+    Down,
+    Unconfigured,                      // No API key or URL set; not probed
+    Blocked,                           // Remote host while local-only mode is on; not probed
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderHealth {
+    pub id: String,
+    pub name: String,
+    pub kind: ProviderKind,
+    pub endpoint: String,              // Host (and port) that was probed
+    pub status: HealthStatus,
+    pub latency_ms: Option<u64>,
+    pub http_status: Option<u16>,
+    pub message: Option<String>,       // Why it isn't Ok
+    pub last_error: Option<String>,    // Most recent failure of real traffic to the host (see http_stats)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    pub checked_at: i64,               // Unix ms
+    pub elapsed_ms: u64,
+    pub offline: bool,
+    pub local_only: bool,
+    pub providers: Vec<ProviderHealth>,
+}
+
+struct Target {
+    id: &'static str,
+    name: &'static str,
+    kind: ProviderKind,
+    url: String,
+    probe: Probe,
+}
+
+impl Target {
+    fn new(id: &'static str, name: &'static str, kind: ProviderKind, url: impl Into<String>, probe: Probe) -> Self {
+        Target { id, name, kind, url: url.into(), probe }
+    }
+}
+
+enum Probe {
+    Get(Option<&'static str>),         // Plain GET, optionally with a browser user agent
+    Key(Provider),                     // ApiKeyStore::test
+    Missing(&'static str),             // Not configured; the reason
+}
+
+// Probe everything at once; the slowest target bounds the call at about PROBE_TIMEOUT
+pub async fn check(app: &tauri::AppHandle) -> HealthReport {
+    let started = Instant::now();
+    let config = crate::config::current();
+    let api_keys = app.try_state::<ApiKeyStore>().map(|k| k.inner().clone());
+    let keyed = |provider: Provider| match &api_keys {
+        Some(keys) if keys.get(provider).is_some() => Probe::Key(provider),
+        _ => Probe::Missing("no API key configured"),
+    };
+    let base = |url: &str| url.trim_end_matches('/').to_string();
+
+    let targets = vec![
+        Target::new("ollama", "Ollama", ProviderKind::Local, format!("{}/api/version", base(&config.ai.ollama_url)), Probe::Get(None)),
+        Target::new("meilisearch", "Meilisearch", ProviderKind::Local, format!("{}/health", base(&config.search.meilisearch_url)), Probe::Get(None)),
+        // The Regen server also hosts the WebSocket endpoint
+        Target::new("server", "Regen server (WebSocket)", ProviderKind::Local, format!("{}/health", base(&config.server.url)), Probe::Get(None)),
+        Target::new("openai", "OpenAI", ProviderKind::Llm, format!("{}/models", base(&config.providers.openai_base_url)), keyed(Provider::OpenAI)),
+        Target::new("anthropic", "Anthropic", ProviderKind::Llm, format!("{}/models", base(&config.providers.anthropic_base_url)), keyed(Provider::Anthropic)),
+        Target::new("groq", "Groq", ProviderKind::Llm, "https://api.groq.com/openai/v1/models", keyed(Provider::Groq)),
+        Target::new("mistral", "Mistral", ProviderKind::Llm, "https://api.mistral.ai/v1/models", keyed(Provider::Mistral)),
+        Target::new("huggingface", "Hugging Face", ProviderKind::Llm, "https://huggingface.co/api/whoami-v2", keyed(Provider::HuggingFace)),
+        Target::new("duckduckgo", "DuckDuckGo", ProviderKind::Search, "https://html.duckduckgo.com/html/", Probe::Get(Some(BROWSER_UA))),
+        Target::new("brave", "Brave Search", ProviderKind::Search, "https://api.search.brave.com/res/v1/web/search", keyed(Provider::Brave)),
+        match &config.search.searxng_url {
+            Some(url) => Target::new("searxng", "SearxNG", ProviderKind::Search, format!("{}/healthz", base(url)), Probe::Get(None)),
+            None => Target::new("searxng", "SearxNG", ProviderKind::Search, "", Probe::Missing("search.searxngUrl is not set")),
+        },
+        Target::new("yahoo-finance", "Yahoo Finance", ProviderKind::Broker, "https://query1.finance.yahoo.com/v8/finance/chart/AAPL?range=1d", Probe::Get(Some(BROWSER_UA))),
+        Target::new("nse", "NSE India", ProviderKind::Broker, "https://www.nseindia.com/option-chain", Probe::Get(Some(BROWSER_UA))),
+        Target::new("finnhub", "Finnhub", ProviderKind::Broker, "https://finnhub.io/api/v1/quote", keyed(Provider::Finnhub)),
+    ];
+
+    let client = http::Client::new(PROBE_TIMEOUT).with_retries(0).with_purpose("health-check");
+    let probes = targets.into_iter().map(|target| probe(target, client.clone(), api_keys.clone()));
+    let mut providers = futures::future::join_all(probes).await;
+
+    // Real traffic may have failed where the probe didn't (or the other way round)
+    let stats = http::stats();
+    for provider in &mut providers {
+        provider.last_error = stats.hosts.iter().find(|h| h.host == provider.endpoint).and_then(|h| h.last_error.clone());
+    }
+    let down = providers.iter().filter(|p| p.status == HealthStatus::Down).count();
+    tracing::info!(target: "app", "Provider health: {} checked, {} down, in {}ms", providers.len(), down, started.elapsed().as_millis());
+
+    HealthReport {
+        checked_at: chrono::Utc::now().timestamp_millis(),
+        elapsed_ms: started.elapsed().as_millis() as u64,
+        offline: crate::connectivity::is_offline(),
+        local_only: http::local_only(),
+        providers,
+    }
+}
+
+async fn probe(target: Target, client: http::Client, api_keys: Option<ApiKeyStore>) -> ProviderHealth {
+    let parsed = url::Url::parse(&target.url).ok();
+    let endpoint = parsed
+        .as_ref()
+        .map(|u| match u.port() {
+            Some(port) => format!("{}:{}", u.host_str().unwrap_or_default(), port),
+            None => u.host_str().unwrap_or_default().to_string(),
+        })
+        .unwrap_or_default();
+    let mut health = ProviderHealth {
+        id: target.id.to_string(),
+        name: target.name.to_string(),
+        kind: target.kind,
+        endpoint,
+        status: HealthStatus::Down,
+        latency_ms: None,
+        http_status: None,
+        message: None,
+        last_error: None,
+    };
+    let local = parsed.as_ref().and_then(|u| u.host_str()).is_some_and(http::is_local);
+
+    match target.probe {
+        Probe::Missing(reason) => {
+            health.status = HealthStatus::Unconfigured;
+            health.message = Some(reason.to_string());
+        }
+        _ if http::local_only() && !local => {
+            health.status = HealthStatus::Blocked;
+            health.message = Some("local-only mode is on".to_string());
+        }
+        Probe::Key(provider) => {
+            let Some(keys) = api_keys else {
+                health.message = Some("API key storage is unavailable".to_string());
+                return health;
+            };
+            match keys.test(provider).await {
+                Ok(test) => {
+                    health.latency_ms = Some(test.latency_ms);
+                    health.http_status = test.status;
+                    health.status = classify(test.status, test.latency_ms);
+                    if health.status != HealthStatus::Ok {
+                        health.message = Some(test.message);
+                    }
+                }
+                Err(e) => health.message = Some(e.to_string()),
+            }
+        }
+        Probe::Get(user_agent) => {
+            let client = match user_agent {
+                Some(ua) => client.with_user_agent(ua),
+                None => client,
+            };
+            let started = Instant::now();
+            let result = client.send(client.get(&target.url)).await;
+            let latency_ms = started.elapsed().as_millis() as u64;
+            match result {
+                Ok(response) => {
+                    let status = response.status().as_u16();
+                    health.latency_ms = Some(latency_ms);
+                    health.http_status = Some(status);
+                    health.status = classify(Some(status), latency_ms);
+                    if health.status != HealthStatus::Ok {
+                        health.message = Some(match status {
+                            200..=299 => format!("slow response ({}ms)", latency_ms),
+                            429 => "rate limited".to_string(),
+                            code => format!("HTTP {}", code),
+                        });
+                    }
+                }
+                Err(HttpError::LocalOnly(_)) => {
+                    health.status = HealthStatus::Blocked;
+                    health.message = Some("local-only mode is on".to_string());
+                }
+                Err(e) => health.message = Some(e.to_string()),
+            }
+        }
+    }
+    health
+}
+
+fn classify(status: Option<u16>, latency_ms: u64) -> HealthStatus {
+    match status {
+        Some(200..=299) if latency_ms > SLOW_MS => HealthStatus::Degraded,
+        Some(200..=299) => HealthStatus::Ok,
+        Some(401 | 403) | Some(500..) | None => HealthStatus::Down,
+        Some(_) => HealthStatus::Degraded,
+    }
+}
@@ -0,0 +1,151 @@
+// Reader Mode - Sanitized, self-styled article view
+// Builds on extractor.rs; output never contains scripts, iframes, or remote styles
+
+use ammonia::{Builder, UrlRelative};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::extractor::ExtractedPage;
+
+const WORDS_PER_MINUTE: usize = 220;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ReaderView {
+    pub url: Option<String>,
+    pub title: String,
+    pub byline: Option<String>,
+    pub published_at: Option<String>,
+    pub language: Option<String>,
+    pub html: String,               // Complete standalone document
+    pub headings: Vec<String>,
+    pub images: Vec<String>,
+    pub word_count: usize,
+    pub reading_time_minutes: usize,
+    pub cached: bool,               // Served from the pages table
+}
+
+// Render an extracted page as reader HTML
+pub fn render(page: &ExtractedPage) -> ReaderView {
+    let body = sanitize(&page.content_html, page.url.as_deref());
+    let reading_time_minutes = reading_time(page.word_count);
+
+    let mut meta_line = Vec::new();
+    if let Some(byline) = &page.byline {
+        meta_line.push(escape(byline));
+    }
+    if let Some(site) = &page.site_name {
+        meta_line.push(escape(site));
+    }
+    meta_line.push(format!("{} min read", reading_time_minutes));
+
+    let lang_attr = page
+        .language
+        .as_deref()
+        .map(|l| format!(" lang=\"{}\"", escape(l)))
+        .unwrap_or_default();
+
+    let html = format!(
+        r#"<!DOCTYPE html>
+<html{lang_attr}>
+<head>
+<meta charset="utf-8">
+<meta http-equiv="Content-Security-Policy" content="default-src 'none'; img-src https: data:; style-src 'unsafe-inline'">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title}</title>
+<style>{css}</style>
+</head>
+<body>
+<article class="regen-reader">
+<header>
+<h1>{title}</h1>
+<p class="regen-reader-meta">{meta}</p>
+</header>
+{body}
+</article>
+</body>
+</html>"#,
+        lang_attr = lang_attr,
+        title = escape(&page.title),
+        css = READER_CSS,
+        meta = meta_line.join(" · "),
+        body = body,
+    );
+
+    ReaderView {
+        url: page.url.clone(),
+        title: page.title.clone(),
+        byline: page.byline.clone(),
+        published_at: page.published_at.clone(),
+        language: page.language.clone(),
+        html,
+        headings: page.headings.clone(),
+        images: page.images.clone(),
+        word_count: page.word_count,
+        reading_time_minutes,
+        cached: false,
+    }
+}
+
+// Estimated reading time in minutes (never less than 1)
+pub fn reading_time(word_count: usize) -> usize {
+    word_count.div_ceil(WORDS_PER_MINUTE).max(1)
+}
+
+// Strip everything except article structure; rewrite relative URLs against the page
+fn sanitize(fragment: &str, base_url: Option<&str>) -> String {
+    let tags: HashSet<&str> = [
+        "a", "abbr", "b", "blockquote", "br", "caption", "code", "dd", "del", "dl", "dt", "em",
+        "figcaption", "figure", "h1", "h2", "h3", "h4", "h5", "h6", "hr", "i", "img", "li",
+        "mark", "ol", "p", "pre", "q", "s", "small", "strong", "sub", "sup", "table", "tbody",
+        "td", "tfoot", "th", "thead", "tr", "u", "ul",
+    ]
+    .into_iter()
+    .collect();
+
+    let mut builder = Builder::default();
+    builder
+        .tags(tags)
+        .add_tag_attributes("img", &["src", "alt", "width", "height"])
+        .add_tag_attributes("a", &["href", "title"])
+        .add_tag_attributes("td", &["colspan", "rowspan"])
+        .add_tag_attributes("th", &["colspan", "rowspan"])
+        .url_schemes(["http", "https", "mailto", "data"].into_iter().collect())
+        .link_rel(Some("noopener noreferrer nofollow"))
+        .strip_comments(true);
+
+    match base_url.and_then(|u| url::Url::parse(u).ok()) {
+        Some(base) => {
+            builder.url_relative(UrlRelative::RewriteWithBase(base));
+        }
+        None => {
+            builder.url_relative(UrlRelative::Deny);
+        }
+    }
+
+    builder.clean(fragment).to_string()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const READER_CSS: &str = r#"
+:root { color-scheme: light dark; }
+body { margin: 0; background: #faf8f5; color: #1f1f1f; font: 19px/1.7 Georgia, "Noto Serif", "Noto Serif Devanagari", serif; }
+@media (prefers-color-scheme: dark) { body { background: #17181a; color: #e4e4e4; } a { color: #8ab4f8; } }
+.regen-reader { max-width: 42rem; margin: 0 auto; padding: 3rem 1.5rem 5rem; }
+.regen-reader h1 { font-size: 2.1rem; line-height: 1.25; margin: 0 0 .5rem; }
+.regen-reader h2, .regen-reader h3 { line-height: 1.3; margin-top: 2.2rem; }
+.regen-reader-meta { color: #777; font: 14px/1.4 system-ui, sans-serif; margin-bottom: 2.5rem; }
+.regen-reader img { max-width: 100%; height: auto; display: block; margin: 1.5rem auto; border-radius: 4px; }
+.regen-reader figcaption { color: #777; font-size: .85rem; text-align: center; }
+.regen-reader blockquote { border-left: 3px solid #c9c2b8; margin: 1.5rem 0; padding: 0 1rem; color: #555; }
+.regen-reader pre { overflow-x: auto; padding: 1rem; background: rgba(127,127,127,.12); border-radius: 4px; font-size: .85rem; }
+.regen-reader table { border-collapse: collapse; width: 100%; font-size: .9rem; }
+.regen-reader td, .regen-reader th { border: 1px solid rgba(127,127,127,.3); padding: .4rem .6rem; }
+"#;
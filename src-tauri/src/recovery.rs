@@ -0,0 +1,191 @@
+// Crash Recovery - Unsaved research/agent UI state survives a crash or forced quit
+// The frontend checkpoints its in-memory state; it is written to disk every few seconds and from the panic hook.
+// After an unclean exit the last checkpoint is set aside until the user restores or discards it.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Manager;
+
+use crate::privacy::PrivacyEnforcer;
+
+const CHECKPOINT_FILE: &str = "recovery.json";
+// The previous run's checkpoint, waiting for recovery_restore / recovery_discard
+const RECOVERABLE_FILE: &str = "recovery.crashed.json";
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+pub const MAX_STATE_BYTES: usize = 5 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct RecoverySnapshot {
+    pub saved_at: i64,
+    pub state: serde_json::Value,      // Opaque to the backend; shaped by the frontend
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryInfo {
+    pub saved_at: i64,
+    pub size_bytes: u64,
+}
+
+struct Recovery {
+    dir: Option<PathBuf>,
+    pending: Option<String>,           // Serialized snapshot not yet on disk
+    allow_disk: bool,
+}
+
+static RECOVERY: Mutex<Recovery> = Mutex::new(Recovery { dir: None, pending: None, allow_disk: false });
+
+// Startup: after an unclean exit the last checkpoint becomes recoverable; after a clean one it is stale
+pub fn init(app: &tauri::AppHandle, dir: PathBuf, unclean_exit: bool) {
+    let checkpoint = dir.join(CHECKPOINT_FILE);
+    if checkpoint.exists() {
+        if unclean_exit {
+            match std::fs::rename(&checkpoint, dir.join(RECOVERABLE_FILE)) {
+                Ok(()) => tracing::warn!(target: "stability", "Recovery: Unsaved state from the last run can be restored"),
+                Err(e) => tracing::warn!(target: "stability", "Recovery: Failed to set aside last checkpoint: {}", e),
+            }
+        } else {
+            let _ = std::fs::remove_file(&checkpoint);
+        }
+    }
+    {
+        let mut recovery = RECOVERY.lock().unwrap();
+        recovery.dir = Some(dir);
+        recovery.allow_disk = disk_allowed(app);
+    }
+    install_panic_hook();
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(FLUSH_INTERVAL).await;
+            let allow_disk = disk_allowed(&app);
+            RECOVERY.lock().unwrap().allow_disk = allow_disk;
+            flush();
+        }
+    });
+}
+
+// Keep the latest UI state; it reaches disk on the next flush (or at a panic)
+pub fn checkpoint(state: serde_json::Value) -> Result<(), RecoveryError> {
+    let snapshot = RecoverySnapshot { saved_at: chrono::Utc::now().timestamp(), state };
+    let json = serde_json::to_string(&snapshot).map_err(|e| RecoveryError::Invalid(e.to_string()))?;
+    if json.len() > MAX_STATE_BYTES {
+        return Err(RecoveryError::TooLarge(json.len()));
+    }
+    RECOVERY.lock().unwrap().pending = Some(json);
+    Ok(())
+}
+
+pub fn available() -> Option<RecoveryInfo> {
+    let path = recoverable_path()?;
+    let size_bytes = std::fs::metadata(&path).ok()?.len();
+    let snapshot = read(&path).ok()?;
+    Some(RecoveryInfo { saved_at: snapshot.saved_at, size_bytes })
+}
+
+// Hand the previous run's state back (once); None when there is nothing to recover
+pub fn restore() -> Result<Option<RecoverySnapshot>, RecoveryError> {
+    let Some(path) = recoverable_path().filter(|p| p.exists()) else {
+        return Ok(None);
+    };
+    let snapshot = read(&path)?;
+    std::fs::remove_file(&path).map_err(|e| RecoveryError::Io(e.to_string()))?;
+    tracing::info!(target: "stability", "Recovery: Restored state saved at {}", snapshot.saved_at);
+    Ok(Some(snapshot))
+}
+
+pub fn discard() -> Result<bool, RecoveryError> {
+    let Some(path) = recoverable_path().filter(|p| p.exists()) else {
+        return Ok(false);
+    };
+    std::fs::remove_file(&path).map_err(|e| RecoveryError::Io(e.to_string()))?;
+    Ok(true)
+}
+
+// Clean exit: nothing to recover next time
+pub fn end_session() {
+    let mut recovery = RECOVERY.lock().unwrap();
+    recovery.pending = None;
+    if let Some(dir) = &recovery.dir {
+        let _ = std::fs::remove_file(dir.join(CHECKPOINT_FILE));
+    }
+}
+
+fn flush() {
+    let (dir, json) = {
+        let mut recovery = RECOVERY.lock().unwrap();
+        if !recovery.allow_disk {
+            // Private/Ghost state stays in memory, and an older checkpoint must not outlive the switch
+            if let Some(dir) = &recovery.dir {
+                let _ = std::fs::remove_file(dir.join(CHECKPOINT_FILE));
+            }
+            return;
+        }
+        match (recovery.dir.clone(), recovery.pending.take()) {
+            (Some(dir), Some(json)) => (dir, json),
+            _ => return,
+        }
+    };
+    if let Err(e) = write_atomic(&dir.join(CHECKPOINT_FILE), &json) {
+        tracing::warn!(target: "stability", "Recovery: Failed to write checkpoint: {}", e);
+    }
+}
+
+// Write whatever is pending before the process goes down; try_lock, since the panic may hold the lock
+fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if let Ok(mut recovery) = RECOVERY.try_lock() {
+            if let (true, Some(dir), Some(json)) = (recovery.allow_disk, recovery.dir.clone(), recovery.pending.take()) {
+                let _ = write_atomic(&dir.join(CHECKPOINT_FILE), &json);
+            }
+        }
+        previous(info);
+    }));
+}
+
+fn write_atomic(path: &Path, contents: &str) -> std::io::Result<()> {
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, contents)?;
+    std::fs::rename(&tmp, path)
+}
+
+fn read(path: &Path) -> Result<RecoverySnapshot, RecoveryError> {
+    let json = std::fs::read_to_string(path).map_err(|e| RecoveryError::Io(e.to_string()))?;
+    serde_json::from_str(&json).map_err(|e| RecoveryError::Invalid(e.to_string()))
+}
+
+fn recoverable_path() -> Option<PathBuf> {
+    RECOVERY.lock().unwrap().dir.as_ref().map(|dir| dir.join(RECOVERABLE_FILE))
+}
+
+fn disk_allowed(app: &tauri::AppHandle) -> bool {
+    app.try_state::<Mutex<PrivacyEnforcer>>()
+        .map(|enforcer| enforcer.lock().unwrap().can_write_to_disk())
+        .unwrap_or(true)
+}
+
+#[derive(Debug, Clone)]
+pub enum RecoveryError {
+    TooLarge(usize),
+    Invalid(String),
+    Io(String),
+}
+
+impl std::fmt::Display for RecoveryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecoveryError::TooLarge(bytes) => write!(f, "Recovery state is {} bytes (limit {})", bytes, MAX_STATE_BYTES),
+            RecoveryError::Invalid(e) => write!(f, "Invalid recovery state: {}", e),
+            RecoveryError::Io(e) => write!(f, "Recovery file error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for RecoveryError {}
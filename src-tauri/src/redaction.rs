@@ -0,0 +1,326 @@
+// Redaction - Replaces personal data in prompts with placeholders before they go to a cloud LLM
+// The backend's cloud router redacts each prompt (per [redaction] and its provider overrides) and restores the response.
+// Originals stay in memory only; the redaction log records kinds and placeholders, never the values.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+// Mappings outlive the request so streamed chunks and retries can still be restored
+const MAPPING_TTL: Duration = Duration::from_secs(60 * 60);
+const MAX_MAPPINGS: usize = 500;
+const LOG_CAPACITY: usize = 500;
+const DEFAULT_LOG_LIMIT: usize = 100;
+// Words an honorific or "my name is" can carry into a name
+const MAX_NAME_WORDS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum PiiKind {
+    Email,
+    Phone,
+    Aadhaar,                           // 12-digit UIDAI number with a valid Verhoeff check digit
+    Pan,                               // Indian income-tax PAN, e.g. ABCPE1234F
+    Name,
+}
+
+impl PiiKind {
+    pub const ALL: [PiiKind; 5] = [PiiKind::Email, PiiKind::Phone, PiiKind::Aadhaar, PiiKind::Pan, PiiKind::Name];
+
+    fn label(&self) -> &'static str {
+        match self {
+            PiiKind::Email => "EMAIL",
+            PiiKind::Phone => "PHONE",
+            PiiKind::Aadhaar => "AADHAAR",
+            PiiKind::Pan => "PAN",
+            PiiKind::Name => "NAME",
+        }
+    }
+}
+
+// [redaction.providers.<provider>]; unset fields follow [redaction]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProviderRedaction {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kinds: Option<Vec<PiiKind>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct Redaction {
+    pub kind: PiiKind,
+    pub placeholder: String,           // "[EMAIL_1]"; the same value always gets the same placeholder
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct RedactedText {
+    pub id: Option<String>,            // Pass to pii_restore; None when nothing was replaced
+    pub text: String,
+    pub redactions: Vec<Redaction>,
+    pub applied: bool,                 // False for local providers or when redaction is off for the provider
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionLogEntry {
+    pub id: String,
+    pub timestamp: i64,                // Unix ms
+    pub provider: String,
+    pub redactions: Vec<Redaction>,
+}
+
+struct Mapping {
+    created: Instant,
+    placeholders: Vec<(String, String)>, // (placeholder, original)
+}
+
+struct State {
+    mappings: Mutex<HashMap<String, Mapping>>,
+    log: Mutex<VecDeque<RedactionLogEntry>>,
+}
+
+fn state() -> &'static State {
+    static STATE: OnceLock<State> = OnceLock::new();
+    STATE.get_or_init(|| State { mappings: Mutex::new(HashMap::new()), log: Mutex::new(VecDeque::with_capacity(LOG_CAPACITY)) })
+}
+
+// Kinds to redact for a provider; empty for local providers and when redaction is off
+pub fn policy(provider: &str) -> Vec<PiiKind> {
+    if crate::llm_usage::LOCAL_PROVIDERS.contains(&provider) {
+        return Vec::new();
+    }
+    let config = crate::config::current().redaction;
+    let overrides = config.providers.get(provider).cloned().unwrap_or_default();
+    if !overrides.enabled.unwrap_or(config.enabled) {
+        return Vec::new();
+    }
+    overrides.kinds.unwrap_or(config.kinds)
+}
+
+// Redact `text` for `provider`; `known_names` (e.g. autofill profile names) are redacted wherever they appear
+pub fn redact(provider: &str, text: &str, known_names: &[String]) -> RedactedText {
+    let kinds = policy(provider);
+    if kinds.is_empty() {
+        return RedactedText { id: None, text: text.to_string(), redactions: Vec::new(), applied: false };
+    }
+    let config = crate::config::current().redaction;
+    let names: Vec<&str> = known_names.iter().chain(&config.names).map(|n| n.trim()).filter(|n| n.chars().count() > 1).collect();
+
+    let mut spans: Vec<(usize, usize, PiiKind)> = detect(text, &kinds, &names);
+    spans.retain(|(start, end, _)| !config.allow.iter().any(|a| a.trim().eq_ignore_ascii_case(&text[*start..*end])));
+    // Earliest first; at the same start the longer match wins, and overlaps keep the first
+    spans.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| b.1.cmp(&a.1)));
+
+    let mut out = String::with_capacity(text.len());
+    let mut placeholders: Vec<(String, String)> = Vec::new();
+    let mut redactions: Vec<Redaction> = Vec::new();
+    let mut counts: HashMap<PiiKind, usize> = HashMap::new();
+    let mut cursor = 0;
+    for (start, end, kind) in spans {
+        if start < cursor {
+            continue;
+        }
+        let original = &text[start..end];
+        let placeholder = match placeholders.iter().find(|(_, o)| o.eq_ignore_ascii_case(original)) {
+            Some((placeholder, _)) => placeholder.clone(),
+            None => {
+                let count = counts.entry(kind).or_default();
+                *count += 1;
+                let placeholder = format!("[{}_{}]", kind.label(), count);
+                placeholders.push((placeholder.clone(), original.to_string()));
+                redactions.push(Redaction { kind, placeholder: placeholder.clone() });
+                placeholder
+            }
+        };
+        out.push_str(&text[cursor..start]);
+        out.push_str(&placeholder);
+        cursor = end;
+    }
+    out.push_str(&text[cursor..]);
+    if placeholders.is_empty() {
+        return RedactedText { id: None, text: out, redactions, applied: true };
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    {
+        let mut mappings = state().mappings.lock().unwrap();
+        mappings.retain(|_, m| m.created.elapsed() < MAPPING_TTL);
+        if mappings.len() >= MAX_MAPPINGS {
+            if let Some(oldest) = mappings.iter().min_by_key(|(_, m)| m.created).map(|(id, _)| id.clone()) {
+                mappings.remove(&oldest);
+            }
+        }
+        mappings.insert(id.clone(), Mapping { created: Instant::now(), placeholders });
+    }
+    {
+        let mut log = state().log.lock().unwrap();
+        if log.len() == LOG_CAPACITY {
+            log.pop_front();
+        }
+        log.push_back(RedactionLogEntry {
+            id: id.clone(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            provider: provider.to_string(),
+            redactions: redactions.clone(),
+        });
+    }
+    let mut summary: Vec<String> = counts.iter().map(|(kind, n)| format!("{} {}", n, kind.label().to_lowercase())).collect();
+    summary.sort();
+    tracing::info!(target: "privacy", "Redaction: Replaced {} before sending to {}", summary.join(", "), provider);
+    RedactedText { id: Some(id), text: out, redactions, applied: true }
+}
+
+// Put the originals back into a response; models sometimes drop the brackets, so "EMAIL_1" is restored too
+pub fn restore(id: &str, text: &str) -> Result<String, RedactionError> {
+    static PLACEHOLDER_RE: OnceLock<Regex> = OnceLock::new();
+    let re = PLACEHOLDER_RE.get_or_init(|| Regex::new(r"\[?\b(EMAIL|PHONE|AADHAAR|PAN|NAME)_(\d+)\b\]?").unwrap());
+    let mappings = state().mappings.lock().unwrap();
+    let mapping = mappings
+        .get(id)
+        .filter(|m| m.created.elapsed() < MAPPING_TTL)
+        .ok_or_else(|| RedactionError::UnknownId(id.to_string()))?;
+    Ok(re
+        .replace_all(text, |caps: &regex::Captures| {
+            let placeholder = format!("[{}_{}]", &caps[1], &caps[2]);
+            match mapping.placeholders.iter().find(|(p, _)| *p == placeholder) {
+                Some((_, original)) => original.clone(),
+                None => caps[0].to_string(),
+            }
+        })
+        .into_owned())
+}
+
+// Newest first
+pub fn log(limit: Option<usize>) -> Vec<RedactionLogEntry> {
+    let log = state().log.lock().unwrap();
+    log.iter().rev().take(limit.unwrap_or(DEFAULT_LOG_LIMIT)).cloned().collect()
+}
+
+// Byte spans of everything to redact, unsorted and possibly overlapping
+fn detect(text: &str, kinds: &[PiiKind], names: &[&str]) -> Vec<(usize, usize, PiiKind)> {
+    static EMAIL_RE: OnceLock<Regex> = OnceLock::new();
+    static AADHAAR_RE: OnceLock<Regex> = OnceLock::new();
+    static PHONE_RE: OnceLock<Regex> = OnceLock::new();
+    static PAN_RE: OnceLock<Regex> = OnceLock::new();
+    static TITLED_NAME_RE: OnceLock<Regex> = OnceLock::new();
+
+    let mut spans = Vec::new();
+    if kinds.contains(&PiiKind::Email) {
+        let re = EMAIL_RE.get_or_init(|| Regex::new(r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}\b").unwrap());
+        spans.extend(re.find_iter(text).map(|m| (m.start(), m.end(), PiiKind::Email)));
+    }
+    if kinds.contains(&PiiKind::Aadhaar) {
+        let re = AADHAAR_RE.get_or_init(|| Regex::new(r"\b[2-9]\d{3}[ -]?\d{4}[ -]?\d{4}\b").unwrap());
+        spans.extend(
+            re.find_iter(text)
+                .filter(|m| verhoeff_valid(&digits(m.as_str())))
+                .map(|m| (m.start(), m.end(), PiiKind::Aadhaar)),
+        );
+    }
+    if kinds.contains(&PiiKind::Phone) {
+        let re = PHONE_RE.get_or_init(|| Regex::new(r"(?:\+\d{1,3}[ .-]?)?(?:\(\d{2,5}\)[ .-]?)?\d{2,5}(?:[ .-]?\d{2,5}){1,3}").unwrap());
+        spans.extend(re.find_iter(text).filter(|m| is_phone(m.as_str())).map(|m| (m.start(), m.end(), PiiKind::Phone)));
+    }
+    if kinds.contains(&PiiKind::Pan) {
+        // The fourth letter is the holder type: P(erson), C(ompany), H(UF), F(irm), ...
+        let re = PAN_RE.get_or_init(|| Regex::new(r"\b[A-Z]{3}[ABCFGHJLPT][A-Z]\d{4}[A-Z]\b").unwrap());
+        spans.extend(re.find_iter(text).map(|m| (m.start(), m.end(), PiiKind::Pan)));
+    }
+    if kinds.contains(&PiiKind::Name) {
+        for name in names {
+            let pattern = format!(r"(?i)\b{}\b", regex::escape(name));
+            if let Ok(re) = Regex::new(&pattern) {
+                spans.extend(re.find_iter(text).map(|m| (m.start(), m.end(), PiiKind::Name)));
+            }
+        }
+        // The honorific or phrase stays; only the name after it is replaced
+        let re = TITLED_NAME_RE.get_or_init(|| {
+            Regex::new(&format!(
+                r"(?:\b(?:Mr|Mrs|Ms|Miss|Dr|Prof|Shri|Smt|Sri|Kumari)\.?\s+|\b(?i:my name is|signed,?)\s+|(?:श्रीमती|श्री|सुश्री|डॉ\.?)\s*|मेरा नाम\s+)((?:\p{{Lu}}[\p{{L}}'-]+|\p{{Devanagari}}+)(?:\s+(?:\p{{Lu}}[\p{{L}}'-]+|\p{{Devanagari}}+)){{0,{}}})",
+                MAX_NAME_WORDS - 1
+            ))
+            .unwrap()
+        });
+        for caps in re.captures_iter(text) {
+            if let Some(name) = caps.get(1) {
+                // "मेरा नाम राहुल है": the trailing copula isn't part of the name
+                let trimmed = name.as_str().trim_end_matches(" है").trim_end_matches(" हूँ").trim_end_matches(" हूं");
+                spans.push((name.start(), name.start() + trimmed.len(), PiiKind::Name));
+            }
+        }
+    }
+    spans
+}
+
+fn digits(text: &str) -> Vec<u8> {
+    text.bytes().filter(u8::is_ascii_digit).map(|b| b - b'0').collect()
+}
+
+// International numbers need a country code; bare numbers must look like an Indian mobile or landline
+fn is_phone(candidate: &str) -> bool {
+    let digits = digits(candidate);
+    match (candidate.starts_with('+'), digits.len()) {
+        (true, 8..=15) => true,
+        (false, 10) => digits[0] >= 6,
+        (false, 11) => digits[0] == 0,
+        (false, 12) => digits[..2] == [9, 1] && digits[2] >= 6,
+        _ => false,
+    }
+}
+
+// Verhoeff checksum, which UIDAI uses for the last Aadhaar digit
+fn verhoeff_valid(digits: &[u8]) -> bool {
+    const D: [[u8; 10]; 10] = [
+        [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+        [1, 2, 3, 4, 0, 6, 7, 8, 9, 5],
+        [2, 3, 4, 0, 1, 7, 8, 9, 5, 6],
+        [3, 4, 0, 1, 2, 8, 9, 5, 6, 7],
+        [4, 0, 1, 2, 3, 9, 5, 6, 7, 8],
+        [5, 9, 8, 7, 6, 0, 4, 3, 2, 1],
+        [6, 5, 9, 8, 7, 1, 0, 4, 3, 2],
+        [7, 6, 5, 9, 8, 2, 1, 0, 4, 3],
+        [8, 7, 6, 5, 9, 3, 2, 1, 0, 4],
+        [9, 8, 7, 6, 5, 4, 3, 2, 1, 0],
+    ];
+    const P: [[u8; 10]; 8] = [
+        [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+        [1, 5, 7, 6, 2, 8, 3, 0, 9, 4],
+        [5, 8, 0, 3, 7, 9, 6, 1, 4, 2],
+        [8, 9, 1, 6, 0, 4, 3, 5, 2, 7],
+        [9, 4, 5, 3, 1, 2, 6, 8, 7, 0],
+        [4, 2, 8, 6, 5, 7, 3, 9, 0, 1],
+        [2, 7, 9, 3, 8, 0, 6, 4, 1, 5],
+        [7, 0, 4, 6, 9, 1, 3, 2, 5, 8],
+    ];
+    if digits.len() != 12 {
+        return false;
+    }
+    let check = digits.iter().rev().enumerate().fold(0u8, |c, (i, &d)| D[c as usize][P[i % 8][d as usize] as usize]);
+    check == 0
+}
+
+#[derive(Debug, Clone)]
+pub enum RedactionError {
+    UnknownId(String),
+}
+
+impl std::fmt::Display for RedactionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RedactionError::UnknownId(id) => write!(f, "Redaction {} not found or expired", id),
+        }
+    }
+}
+
+impl std::error::Error for RedactionError {}
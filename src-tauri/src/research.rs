@@ -0,0 +1,397 @@
+// Research Sessions - Rust-side store + Markdown/HTML report export
+// Mirrors the frontend ResearchSession shape (src/core/workspace/SessionWorkspace.ts)
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// Exported reports embed the session JSON so they can be imported back losslessly
+const EMBED_MARKER: &str = "regen-session:";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ResearchSession {
+    pub id: String,
+    pub title: String,
+    pub created_at: i64,               // Unix millis (frontend Date.now())
+    pub updated_at: i64,
+    #[serde(default)]
+    pub tabs: Vec<SessionTab>,
+    #[serde(default)]
+    pub notes: Vec<SessionNote>,
+    #[serde(default)]
+    pub summaries: Vec<SessionSummary>,
+    #[serde(default)]
+    pub highlights: Vec<SessionHighlight>,
+    #[serde(default)]
+    pub metadata: SessionMetadata,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SessionMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    #[serde(default)]
+    pub sources: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTab {
+    pub id: String,
+    pub url: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub favicon: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SessionNote {
+    pub id: String,
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selection: Option<String>,
+    pub created_at: i64,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSummary {
+    pub id: String,
+    pub url: String,
+    pub summary: String,
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    pub length: String,                // "short" | "medium" | "long"
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SessionHighlight {
+    pub id: String,
+    pub url: String,
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    Html,
+}
+
+impl ExportFormat {
+    pub fn parse(format: &str) -> Result<Self, ResearchError> {
+        match format.to_lowercase().as_str() {
+            "md" | "markdown" => Ok(ExportFormat::Markdown),
+            "html" | "htm" => Ok(ExportFormat::Html),
+            other => Err(ResearchError::UnsupportedFormat(other.to_string())),
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Html => "html",
+        }
+    }
+}
+
+// Everything the report says about one URL
+struct Section<'a> {
+    title: String,
+    url: &'a str,
+    summaries: Vec<&'a SessionSummary>,
+    highlights: Vec<&'a SessionHighlight>,
+    notes: Vec<&'a SessionNote>,
+}
+
+// Group summaries/highlights/notes under the tab they came from (tab order first)
+fn sections(session: &ResearchSession) -> (Vec<Section<'_>>, Vec<&SessionNote>) {
+    let mut order: Vec<&str> = Vec::new();
+    let mut titles: BTreeMap<&str, String> = BTreeMap::new();
+    for tab in &session.tabs {
+        if !titles.contains_key(tab.url.as_str()) {
+            order.push(&tab.url);
+            titles.insert(&tab.url, tab.title.clone());
+        }
+    }
+    let extra_urls = session
+        .summaries
+        .iter()
+        .map(|s| s.url.as_str())
+        .chain(session.highlights.iter().map(|h| h.url.as_str()))
+        .chain(session.notes.iter().filter_map(|n| n.url.as_deref()));
+    for url in extra_urls {
+        if !titles.contains_key(url) {
+            order.push(url);
+            titles.insert(url, url.to_string());
+        }
+    }
+
+    let sections = order
+        .into_iter()
+        .map(|url| Section {
+            title: titles.remove(url).unwrap_or_default(),
+            url,
+            summaries: session.summaries.iter().filter(|s| s.url == url).collect(),
+            highlights: session.highlights.iter().filter(|h| h.url == url).collect(),
+            notes: session.notes.iter().filter(|n| n.url.as_deref() == Some(url)).collect(),
+        })
+        .collect();
+    let loose_notes = session.notes.iter().filter(|n| n.url.is_none()).collect();
+    (sections, loose_notes)
+}
+
+// Render a session as a Markdown or HTML report
+pub fn export(session: &ResearchSession, format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Markdown => to_markdown(session),
+        ExportFormat::Html => to_html(session),
+    }
+}
+
+fn to_markdown(session: &ResearchSession) -> String {
+    let (sections, loose_notes) = sections(session);
+    let mut md = format!("# {}\n\n", session.title);
+    md.push_str(&format!(
+        "_Created {} · Updated {}_\n\n",
+        format_time(session.created_at),
+        format_time(session.updated_at)
+    ));
+
+    if let Some(query) = &session.metadata.query {
+        md.push_str(&format!("**Query:** {}\n\n", query));
+    }
+    if !session.metadata.keywords.is_empty() {
+        md.push_str(&format!("**Keywords:** {}\n\n", session.metadata.keywords.join(", ")));
+    }
+
+    for (i, section) in sections.iter().enumerate() {
+        md.push_str(&format!("## {} [{}]\n\n<{}>\n\n", section.title, i + 1, section.url));
+        for summary in &section.summaries {
+            md.push_str(&format!("{}\n\n", summary.summary.trim()));
+            if !summary.keywords.is_empty() {
+                md.push_str(&format!("**Keywords:** {}\n\n", summary.keywords.join(", ")));
+            }
+        }
+        if !section.highlights.is_empty() {
+            md.push_str("### Highlights\n\n");
+            for highlight in &section.highlights {
+                md.push_str(&format!("> {} [[{}]]({})\n", highlight.text.trim().replace('\n', "\n> "), i + 1, section.url));
+                if let Some(note) = &highlight.note {
+                    md.push_str(&format!(">\n> — {}\n", note.trim()));
+                }
+                md.push('\n');
+            }
+        }
+        if !section.notes.is_empty() {
+            md.push_str("### Notes\n\n");
+            for note in &section.notes {
+                md.push_str(&format!("- {}\n", note.content.trim().replace('\n', "\n  ")));
+            }
+            md.push('\n');
+        }
+    }
+
+    if !loose_notes.is_empty() {
+        md.push_str("## Notes\n\n");
+        for note in &loose_notes {
+            md.push_str(&format!("- {} _({})_\n", note.content.trim().replace('\n', "\n  "), format_time(note.created_at)));
+        }
+        md.push('\n');
+    }
+
+    md.push_str("## Sources\n\n");
+    for (i, url) in citation_urls(session, &sections).iter().enumerate() {
+        md.push_str(&format!("{}. <{}>\n", i + 1, url));
+    }
+
+    md.push_str(&format!("\n<!-- {}{} -->\n", EMBED_MARKER, embed(session)));
+    md
+}
+
+fn to_html(session: &ResearchSession) -> String {
+    let (sections, loose_notes) = sections(session);
+    let mut body = format!("<h1>{}</h1>\n", escape(&session.title));
+    body.push_str(&format!(
+        "<p class=\"meta\">Created {} · Updated {}</p>\n",
+        format_time(session.created_at),
+        format_time(session.updated_at)
+    ));
+
+    if let Some(query) = &session.metadata.query {
+        body.push_str(&format!("<p><strong>Query:</strong> {}</p>\n", escape(query)));
+    }
+    if !session.metadata.keywords.is_empty() {
+        body.push_str(&format!(
+            "<p><strong>Keywords:</strong> {}</p>\n",
+            escape(&session.metadata.keywords.join(", "))
+        ));
+    }
+
+    for (i, section) in sections.iter().enumerate() {
+        body.push_str(&format!(
+            "<section>\n<h2>{} <sup><a href=\"#src-{}\">[{}]</a></sup></h2>\n<p class=\"url\"><a href=\"{}\">{}</a></p>\n",
+            escape(&section.title),
+            i + 1,
+            i + 1,
+            escape(section.url),
+            escape(section.url)
+        ));
+        for summary in &section.summaries {
+            body.push_str(&format!("<p>{}</p>\n", escape(summary.summary.trim())));
+        }
+        if !section.highlights.is_empty() {
+            body.push_str("<h3>Highlights</h3>\n");
+            for highlight in &section.highlights {
+                body.push_str(&format!(
+                    "<blockquote>{} <a href=\"{}\">[{}]</a>",
+                    escape(highlight.text.trim()),
+                    escape(section.url),
+                    i + 1
+                ));
+                if let Some(note) = &highlight.note {
+                    body.push_str(&format!("<footer>{}</footer>", escape(note.trim())));
+                }
+                body.push_str("</blockquote>\n");
+            }
+        }
+        if !section.notes.is_empty() {
+            body.push_str("<h3>Notes</h3>\n<ul>\n");
+            for note in &section.notes {
+                body.push_str(&format!("<li>{}</li>\n", escape(note.content.trim())));
+            }
+            body.push_str("</ul>\n");
+        }
+        body.push_str("</section>\n");
+    }
+
+    if !loose_notes.is_empty() {
+        body.push_str("<h2>Notes</h2>\n<ul>\n");
+        for note in &loose_notes {
+            body.push_str(&format!("<li>{}</li>\n", escape(note.content.trim())));
+        }
+        body.push_str("</ul>\n");
+    }
+
+    body.push_str("<h2>Sources</h2>\n<ol>\n");
+    for (i, url) in citation_urls(session, &sections).iter().enumerate() {
+        body.push_str(&format!(
+            "<li id=\"src-{}\"><a href=\"{}\">{}</a></li>\n",
+            i + 1,
+            escape(url),
+            escape(url)
+        ));
+    }
+    body.push_str("</ol>\n");
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ max-width: 46rem; margin: 2rem auto; padding: 0 1.5rem; font: 16px/1.6 system-ui, sans-serif; color: #1f1f1f; }}
+.meta, .url {{ color: #777; font-size: .85rem; }}
+blockquote {{ border-left: 3px solid #c9c2b8; margin: 1rem 0; padding: 0 1rem; }}
+blockquote footer {{ color: #555; font-size: .9rem; margin-top: .3rem; }}
+section {{ margin-bottom: 2rem; }}
+</style>
+</head>
+<body>
+{body}<!-- {marker}{embedded} -->
+</body>
+</html>
+"#,
+        title = escape(&session.title),
+        body = body,
+        marker = EMBED_MARKER,
+        embedded = embed(session),
+    )
+}
+
+// Section URLs first (matching the [n] markers), then any extra metadata sources
+fn citation_urls(session: &ResearchSession, sections: &[Section]) -> Vec<String> {
+    let mut urls: Vec<String> = sections.iter().map(|s| s.url.to_string()).collect();
+    for source in &session.metadata.sources {
+        if !urls.contains(source) {
+            urls.push(source.clone());
+        }
+    }
+    urls
+}
+
+// Parse a report previously produced by `export`
+pub fn import(contents: &str) -> Result<ResearchSession, ResearchError> {
+    let start = contents
+        .rfind(EMBED_MARKER)
+        .ok_or(ResearchError::NotAnExport)?
+        + EMBED_MARKER.len();
+    let end = contents[start..]
+        .find("-->")
+        .map(|i| start + i)
+        .ok_or(ResearchError::NotAnExport)?;
+
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(contents[start..end].trim())
+        .map_err(|e| ResearchError::Corrupt(e.to_string()))?;
+    serde_json::from_slice(&bytes).map_err(|e| ResearchError::Corrupt(e.to_string()))
+}
+
+fn embed(session: &ResearchSession) -> String {
+    let json = serde_json::to_vec(session).unwrap_or_default();
+    base64::engine::general_purpose::STANDARD.encode(json)
+}
+
+fn format_time(millis: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(millis)
+        .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[derive(Debug, Clone)]
+pub enum ResearchError {
+    UnsupportedFormat(String),
+    NotAnExport,
+    Corrupt(String),
+}
+
+impl std::fmt::Display for ResearchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResearchError::UnsupportedFormat(format) => write!(f, "Unsupported export format: {}", format),
+            ResearchError::NotAnExport => write!(f, "File is not a Regen session export"),
+            ResearchError::Corrupt(msg) => write!(f, "Session export is corrupt: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ResearchError {}
@@ -0,0 +1,391 @@
+// Research Agent - Plans a question into sub-queries, researches each, and synthesizes a cited answer
+// Progress is reported as structured events so research views can show the plan filling in
+
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::Manager;
+
+use crate::agent_runs::RunRecorder;
+use crate::ai::AIService;
+use crate::apikeys::ApiKeyStore;
+use crate::context_window::{ContextPacker, ContextReport, Fit, Priority};
+use crate::markdown::MarkdownEvent;
+
+const MIN_SUB_QUERIES: usize = 2;
+const MAX_SUB_QUERIES: usize = 5;
+const DEFAULT_CONCURRENCY: usize = 2;
+const MAX_CONCURRENCY: usize = 5;
+const RESULTS_PER_STEP: usize = 5;
+const PAGES_PER_STEP: usize = 3;
+// Page text handed to the model per source, at most; the context window may allow less
+const SOURCE_CHARS: usize = 3000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct Citation {
+    pub id: String,                    // "<step>.<source>", e.g. "2.1"; how the final answer cites it
+    pub title: String,
+    pub url: String,
+    pub fetched: bool,                 // Page text was read; otherwise only the search snippet was used
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct StepResult {
+    pub index: usize,                  // 1-based
+    pub query: String,
+    pub summary: Option<String>,
+    pub sources: Vec<Citation>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ResearchReport {
+    pub run_id: String,                // Transcript id for agent_run_get / agent_run_replay
+    pub query: String,
+    pub planned: bool,                 // False when planning failed and the question was searched as-is
+    pub steps: Vec<StepResult>,
+    pub answer: String,
+    pub citations: Vec<Citation>,      // Every source across steps, in citation-id order
+    pub elapsed_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case", rename_all_fields = "camelCase")]
+pub enum ResearchEvent {
+    Plan { query: String, sub_queries: Vec<String>, planned: bool },
+    StepStarted { index: usize, query: String },
+    StepSources { index: usize, sources: Vec<Citation> },
+    StepDone { index: usize, summary: String },
+    StepFailed { index: usize, error: String },
+    ContextTrimmed { index: Option<usize>, report: ContextReport }, // index None for the synthesis
+    SynthesisToken { token: String, events: Vec<MarkdownEvent> },
+    Done { report: ResearchReport },
+    Failed { error: String },
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct ResearchOptions {
+    pub max_sub_queries: Option<usize>,
+    pub concurrency: Option<usize>,    // Sub-queries researched at once; model calls are further limited by the mode profile
+    pub providers: Option<Vec<String>>,
+    pub language: Option<String>,
+}
+
+// Plan, research each sub-query (a few at a time), then stream the synthesis.
+// `emit` sees every event, including the final Done/Failed; all of it goes into the run transcript.
+pub async fn run(
+    app: tauri::AppHandle,
+    request_id: Option<&str>,
+    query: String,
+    options: ResearchOptions,
+    emit: impl Fn(ResearchEvent) + Send + Sync + 'static,
+) -> Result<ResearchReport, ResearchAgentError> {
+    let recorder = Arc::new(RunRecorder::start(&app, "research", request_id, &query));
+    let emit = {
+        let recorder = recorder.clone();
+        Arc::new(move |event: ResearchEvent| {
+            recorder.event(&event);
+            emit(event);
+        })
+    };
+    let result = run_inner(app, query, options, recorder.clone(), emit.clone()).await;
+    match &result {
+        Ok(report) => {
+            emit(ResearchEvent::Done { report: report.clone() });
+            recorder.finish(Some(&report.answer), None);
+        }
+        Err(e) => {
+            emit(ResearchEvent::Failed { error: e.to_string() });
+            recorder.finish(None, Some(&e.to_string()));
+        }
+    }
+    result
+}
+
+async fn run_inner(
+    app: tauri::AppHandle,
+    query: String,
+    options: ResearchOptions,
+    recorder: Arc<RunRecorder>,
+    emit: Arc<impl Fn(ResearchEvent) + Send + Sync + 'static>,
+) -> Result<ResearchReport, ResearchAgentError> {
+    let started = std::time::Instant::now();
+    let query = query.trim().to_string();
+    if query.is_empty() {
+        return Err(ResearchAgentError::EmptyQuery);
+    }
+    let language = options
+        .language
+        .clone()
+        .unwrap_or_else(|| crate::language::detect_language(&query).language);
+    let max = options.max_sub_queries.unwrap_or(MAX_SUB_QUERIES).clamp(MIN_SUB_QUERIES, MAX_SUB_QUERIES);
+
+    let (sub_queries, planned) = {
+        let ai = app.state::<AIService>();
+        match plan(&ai, &recorder, &query, &language, max).await {
+            Ok(sub_queries) => (sub_queries, true),
+            Err(e) => {
+                tracing::warn!(target: "ai", "Research: Planning failed ({}); searching the question as-is", e);
+                (vec![query.clone()], false)
+            }
+        }
+    };
+    emit(ResearchEvent::Plan { query: query.clone(), sub_queries: sub_queries.clone(), planned });
+
+    let concurrency = options.concurrency.unwrap_or(DEFAULT_CONCURRENCY).clamp(1, MAX_CONCURRENCY);
+    let permits = Arc::new(tokio::sync::Semaphore::new(concurrency));
+    let handles: Vec<_> = sub_queries
+        .iter()
+        .enumerate()
+        .map(|(i, sub_query)| {
+            let (app, emit, permits, recorder) = (app.clone(), emit.clone(), permits.clone(), recorder.clone());
+            let (sub_query, language, providers) = (sub_query.clone(), language.clone(), options.providers.clone());
+            // Spawned so one step's blocking model call doesn't hold up the others
+            tauri::async_runtime::spawn(async move {
+                let _permit = permits.acquire_owned().await.ok();
+                let index = i + 1;
+                emit(ResearchEvent::StepStarted { index, query: sub_query.clone() });
+                let step = research_step(&app, &recorder, index, &sub_query, &language, providers.as_deref(), emit.as_ref()).await;
+                match (&step.summary, &step.error) {
+                    (Some(summary), _) => emit(ResearchEvent::StepDone { index, summary: summary.clone() }),
+                    (None, Some(error)) => emit(ResearchEvent::StepFailed { index, error: error.clone() }),
+                    (None, None) => {}
+                }
+                step
+            })
+        })
+        .collect();
+
+    let mut steps = Vec::new();
+    for (i, handle) in handles.into_iter().enumerate() {
+        steps.push(handle.await.unwrap_or_else(|e| StepResult {
+            index: i + 1,
+            query: sub_queries[i].clone(),
+            summary: None,
+            sources: Vec::new(),
+            error: Some(e.to_string()),
+        }));
+    }
+    if steps.iter().all(|s| s.summary.is_none()) {
+        let reason = steps.iter().find_map(|s| s.error.clone()).unwrap_or_default();
+        return Err(ResearchAgentError::NoFindings(reason));
+    }
+
+    let findings: Vec<String> = steps
+        .iter()
+        .filter_map(|step| {
+            let summary = step.summary.as_ref()?;
+            let sources = step
+                .sources
+                .iter()
+                .map(|c| format!("[{}] {} <{}>", c.id, c.title, c.url))
+                .collect::<Vec<_>>()
+                .join("\n");
+            // Step summaries cite [n]; the synthesis needs [step.n]
+            Some(format!("## Finding {}: {}\n{}\n\nSources:\n{}", step.index, step.query, qualify_citations(summary, step.index), sources))
+        })
+        .collect();
+    let ai = app.state::<AIService>();
+    let packed = ContextPacker::new(ai.model())
+        .text("query", &query, Priority::Required, Fit::KeepStart)
+        .list("findings", findings, Priority::High, Fit::ShareEvenly)
+        .render("research_synthesis", Some(&language), Some("Research"));
+    if packed.report.trimmed() {
+        emit(ResearchEvent::ContextTrimmed { index: None, report: packed.report });
+    }
+    let prompt = packed.prompt;
+    let asked = std::time::Instant::now();
+    let answer = ai
+        .complete_stream_markdown(&prompt, |chunk| {
+            emit(ResearchEvent::SynthesisToken { token: chunk.token, events: chunk.events });
+        })
+        .await;
+    recorder.prompt(&ai, "research_synthesis", &prompt, &answer, asked.elapsed());
+    let answer = answer.map_err(|e| ResearchAgentError::Synthesis(e.to_string()))?;
+
+    let citations = steps.iter().flat_map(|s| s.sources.iter().cloned()).collect();
+    Ok(ResearchReport {
+        run_id: recorder.id().to_string(),
+        query,
+        planned,
+        steps,
+        answer: answer.trim().to_string(),
+        citations,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+// Ask the model for sub-queries; fewer than two usable lines counts as a failed plan
+async fn plan(
+    ai: &AIService,
+    recorder: &RunRecorder,
+    query: &str,
+    language: &str,
+    max: usize,
+) -> Result<Vec<String>, ResearchAgentError> {
+    let prompt = crate::prompts::render("research_plan", Some(language), Some("Research"), &[("query", query)]);
+    let asked = std::time::Instant::now();
+    let response = ai.complete_task("research_plan", &prompt).await;
+    recorder.prompt(ai, "research_plan", &prompt, &response, asked.elapsed());
+    let response = response.map_err(|e| ResearchAgentError::Planning(e.to_string()))?;
+    let sub_queries = parse_plan(&response, max);
+    if sub_queries.len() < MIN_SUB_QUERIES {
+        return Err(ResearchAgentError::Planning(format!("model returned {} usable sub-queries", sub_queries.len())));
+    }
+    Ok(sub_queries)
+}
+
+// One query per line; list markers, numbering, and quotes are stripped and duplicates dropped
+pub fn parse_plan(response: &str, max: usize) -> Vec<String> {
+    let mut sub_queries: Vec<String> = Vec::new();
+    for line in response.lines() {
+        let line = line.trim().trim_start_matches(['-', '*', '•']).trim_start();
+        let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+        let line = if digits > 0 { line[digits..].trim_start_matches(['.', ')', ':']) } else { line };
+        let line = line.trim().trim_matches(['"', '\'', '`']).trim();
+        if line.split_whitespace().count() < 2 || line.ends_with(':') {
+            continue;
+        }
+        if !sub_queries.iter().any(|q| q.eq_ignore_ascii_case(line)) {
+            sub_queries.push(line.to_string());
+        }
+        if sub_queries.len() == max {
+            break;
+        }
+    }
+    sub_queries
+}
+
+// Search, read the top pages, and summarize them with [n] citations
+async fn research_step(
+    app: &tauri::AppHandle,
+    recorder: &RunRecorder,
+    index: usize,
+    sub_query: &str,
+    language: &str,
+    providers: Option<&[String]>,
+    emit: &(impl Fn(ResearchEvent) + Send + Sync),
+) -> StepResult {
+    let mut step = StepResult { index, query: sub_query.to_string(), summary: None, sources: Vec::new(), error: None };
+
+    let results = {
+        let api_keys = app.state::<ApiKeyStore>();
+        let providers = with_feeds(providers);
+        match crate::web_search::search(sub_query, Some(&providers), Some(RESULTS_PER_STEP), &api_keys).await {
+            Ok(response) => response.results,
+            Err(e) => {
+                step.error = Some(e.to_string());
+                return step;
+            }
+        }
+    };
+    if results.is_empty() {
+        step.error = Some("no search results".to_string());
+        return step;
+    }
+
+    // Read the top pages; the rest (and any page that fails) contribute their snippet
+    let pages = futures::future::join_all(
+        results.iter().take(PAGES_PER_STEP).map(|r| crate::extractor::extract_url(&r.url)),
+    )
+    .await;
+    let mut sources_text = Vec::new();
+    for (n, result) in results.iter().enumerate() {
+        let page_text = pages.get(n).and_then(|p| p.as_ref().ok()).map(|p| p.text.trim()).filter(|t| !t.is_empty());
+        let text = page_text.unwrap_or(result.snippet.trim());
+        sources_text.push(format!("[{}] {} ({})\n{}", n + 1, result.title, result.url, truncate_chars(text, SOURCE_CHARS)));
+        step.sources.push(Citation {
+            id: format!("{}.{}", index, n + 1),
+            title: result.title.clone(),
+            url: result.url.clone(),
+            fetched: page_text.is_some(),
+        });
+    }
+    emit(ResearchEvent::StepSources { index, sources: step.sources.clone() });
+
+    let ai = app.state::<AIService>();
+    let packed = ContextPacker::new(ai.model())
+        .text("sub_query", sub_query, Priority::Required, Fit::KeepStart)
+        .list("sources", sources_text, Priority::High, Fit::ShareEvenly)
+        .render("research_step", Some(language), Some("Research"));
+    if packed.report.trimmed() {
+        emit(ResearchEvent::ContextTrimmed { index: Some(index), report: packed.report });
+    }
+    let prompt = packed.prompt;
+    let asked = std::time::Instant::now();
+    let response = ai.complete_task("research_step", &prompt).await;
+    recorder.prompt(&ai, "research_step", &prompt, &response, asked.elapsed());
+    match response {
+        Ok(summary) => step.summary = Some(summary.trim().to_string()),
+        Err(e) => step.error = Some(e.to_string()),
+    }
+    step
+}
+
+// Recent entries from subscribed feeds are searched alongside the requested (or configured) providers
+fn with_feeds(requested: Option<&[String]>) -> Vec<String> {
+    let mut providers = requested.map(<[String]>::to_vec).unwrap_or_else(|| crate::config::current().search.providers);
+    if !providers.iter().any(|p| p == "feeds") {
+        providers.push("feeds".to_string());
+    }
+    providers
+}
+
+// "[2]" -> "[3.2]" and "[1, 2]" -> "[3.1, 3.2]" for step 3
+fn qualify_citations(text: &str, step: usize) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(open) = rest.find('[') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let inner = after.find(']').map(|close| &after[..close]);
+        match inner.filter(|s| !s.is_empty() && s.split(',').all(|n| n.trim().parse::<usize>().is_ok())) {
+            Some(inner) => {
+                let ids: Vec<String> = inner.split(',').map(|n| format!("{}.{}", step, n.trim())).collect();
+                out.push_str(&format!("[{}]", ids.join(", ")));
+                rest = &after[inner.len() + 1..];
+            }
+            None => {
+                out.push('[');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn truncate_chars(text: &str, max: usize) -> &str {
+    match text.char_indices().nth(max) {
+        Some((i, _)) => &text[..i],
+        None => text,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ResearchAgentError {
+    EmptyQuery,
+    Planning(String),
+    NoFindings(String),
+    Synthesis(String),
+}
+
+impl std::fmt::Display for ResearchAgentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResearchAgentError::EmptyQuery => write!(f, "Research question is empty"),
+            ResearchAgentError::Planning(msg) => write!(f, "Could not plan the research: {}", msg),
+            ResearchAgentError::NoFindings(msg) => write!(f, "No sub-query produced findings: {}", msg),
+            ResearchAgentError::Synthesis(msg) => write!(f, "Could not write the final answer: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ResearchAgentError {}
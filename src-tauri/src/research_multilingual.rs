@@ -0,0 +1,276 @@
+// Research Multilingual - Researches a question asked in one language against English sources
+// and answers in the user's language, keeping technical terms consistent through a glossary
+
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::Manager;
+
+use crate::ai::AIService;
+use crate::markdown::MarkdownEvent;
+use crate::research_agent::{self, ResearchEvent, ResearchOptions, ResearchReport};
+
+// Sources are searched and summarized in this language
+const SOURCE_LANGUAGE: &str = "en";
+const MAX_GLOSSARY_TERMS: usize = 25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "kebab-case")]
+pub enum MultilingualStage {
+    Detect,
+    TranslateQuery,
+    Research,
+    Glossary,
+    Render,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct GlossaryEntry {
+    pub term: String,                  // As it appears in the English answer
+    pub translation: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct MultilingualReport {
+    pub query: String,
+    pub query_language: String,
+    pub search_query: String,          // The English query the sources were found with
+    pub answer_language: String,
+    pub glossary: Vec<GlossaryEntry>,
+    pub answer: String,                // In answer_language, with the research citations kept
+    pub source_answer: String,         // The English synthesis it was rendered from
+    pub research: ResearchReport,
+    pub elapsed_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case", rename_all_fields = "camelCase")]
+pub enum MultilingualEvent {
+    Stage { stage: MultilingualStage },
+    Detected { language: String, answer_language: String },
+    QueryTranslated { query: String, search_query: String },
+    Research { event: ResearchEvent },
+    Glossary { entries: Vec<GlossaryEntry> },
+    AnswerToken { token: String, events: Vec<MarkdownEvent> },
+    Done { report: MultilingualReport },
+    Failed { stage: MultilingualStage, error: String },
+}
+
+// Detect, translate the query to English, research it, build a glossary, then render the answer.
+// `answer_language` defaults to the query's language; English answers skip the last two stages.
+pub async fn run(
+    app: tauri::AppHandle,
+    request_id: Option<&str>,
+    query: String,
+    answer_language: Option<String>,
+    options: ResearchOptions,
+    emit: impl Fn(MultilingualEvent) + Send + Sync + 'static,
+) -> Result<MultilingualReport, MultilingualError> {
+    let emit = Arc::new(emit);
+    let result = run_inner(app, request_id, query, answer_language, options, emit.clone()).await;
+    match &result {
+        Ok(report) => emit(MultilingualEvent::Done { report: report.clone() }),
+        Err(e) => emit(MultilingualEvent::Failed { stage: e.stage(), error: e.to_string() }),
+    }
+    result
+}
+
+async fn run_inner(
+    app: tauri::AppHandle,
+    request_id: Option<&str>,
+    query: String,
+    answer_language: Option<String>,
+    options: ResearchOptions,
+    emit: Arc<impl Fn(MultilingualEvent) + Send + Sync + 'static>,
+) -> Result<MultilingualReport, MultilingualError> {
+    let started = std::time::Instant::now();
+    let query = query.trim().to_string();
+    if query.is_empty() {
+        return Err(MultilingualError::EmptyQuery);
+    }
+
+    emit(MultilingualEvent::Stage { stage: MultilingualStage::Detect });
+    let query_language = crate::language::detect_language(&query).language;
+    let answer_language = answer_language
+        .map(|l| l.trim().to_lowercase())
+        .filter(|l| !l.is_empty())
+        .unwrap_or_else(|| query_language.clone());
+    emit(MultilingualEvent::Detected { language: query_language.clone(), answer_language: answer_language.clone() });
+
+    // Romanized Hindi is detected as "hi" too, so it gets translated like Devanagari
+    let search_query = if query_language == SOURCE_LANGUAGE {
+        query.clone()
+    } else {
+        emit(MultilingualEvent::Stage { stage: MultilingualStage::TranslateQuery });
+        let ai = app.state::<AIService>();
+        let prompt = crate::prompts::render(
+            "translate",
+            None,
+            None,
+            &[("content", &query), ("target_language", language_name(SOURCE_LANGUAGE))],
+        );
+        let translated = ai.complete_task("translate", &prompt).await.map_err(|e| MultilingualError::Translation(e.to_string()))?;
+        let translated = translated.trim().trim_matches(['"', '\'']).trim().to_string();
+        if translated.is_empty() {
+            return Err(MultilingualError::Translation("the model returned an empty translation".to_string()));
+        }
+        translated
+    };
+    emit(MultilingualEvent::QueryTranslated { query: query.clone(), search_query: search_query.clone() });
+
+    emit(MultilingualEvent::Stage { stage: MultilingualStage::Research });
+    let forward = emit.clone();
+    let options = ResearchOptions { language: Some(SOURCE_LANGUAGE.to_string()), ..options };
+    let research = research_agent::run(app.clone(), request_id, search_query.clone(), options, move |event| {
+        forward(MultilingualEvent::Research { event });
+    })
+    .await
+    .map_err(|e| MultilingualError::Research(e.to_string()))?;
+
+    if answer_language == SOURCE_LANGUAGE {
+        return Ok(MultilingualReport {
+            query,
+            query_language,
+            search_query,
+            answer_language,
+            glossary: Vec::new(),
+            answer: research.answer.clone(),
+            source_answer: research.answer.clone(),
+            research,
+            elapsed_ms: started.elapsed().as_millis() as u64,
+        });
+    }
+
+    let ai = app.state::<AIService>();
+    let target_name = language_name(&answer_language);
+
+    // A missing glossary only costs consistency, so the answer is still rendered without one
+    emit(MultilingualEvent::Stage { stage: MultilingualStage::Glossary });
+    let prompt = crate::prompts::render(
+        "research_glossary",
+        None,
+        Some("Research"),
+        &[("content", &research.answer), ("target_language", target_name)],
+    );
+    let glossary = match ai.complete_task("research_glossary", &prompt).await {
+        Ok(response) => parse_glossary(&response, &research.answer),
+        Err(e) => {
+            tracing::warn!(target: "ai", "Research: Glossary failed ({}); rendering without one", e);
+            Vec::new()
+        }
+    };
+    emit(MultilingualEvent::Glossary { entries: glossary.clone() });
+
+    emit(MultilingualEvent::Stage { stage: MultilingualStage::Render });
+    let glossary_text = if glossary.is_empty() {
+        "(none)".to_string()
+    } else {
+        glossary.iter().map(|g| format!("{} = {}", g.term, g.translation)).collect::<Vec<_>>().join("\n")
+    };
+    let prompt = crate::prompts::render(
+        "research_localize",
+        None,
+        Some("Research"),
+        &[
+            ("query", &query),
+            ("answer", &research.answer),
+            ("glossary", &glossary_text),
+            ("target_language", target_name),
+        ],
+    );
+    let answer = ai
+        .complete_stream_markdown(&prompt, |chunk| {
+            emit(MultilingualEvent::AnswerToken { token: chunk.token, events: chunk.events });
+        })
+        .await
+        .map_err(|e| MultilingualError::Render(e.to_string()))?;
+    let answer = answer.trim().to_string();
+
+    let dropped = research.citations.iter().filter(|c| research.answer.contains(&c.id) && !answer.contains(&c.id)).count();
+    if dropped > 0 {
+        tracing::warn!(target: "ai", "Research: Rendered {} answer dropped {} citations", answer_language, dropped);
+    }
+    tracing::info!(
+        target: "ai",
+        "Research: Answered a {} question in {} from English sources ({} glossary terms)",
+        query_language,
+        answer_language,
+        glossary.len()
+    );
+
+    Ok(MultilingualReport {
+        query,
+        query_language,
+        search_query,
+        answer_language,
+        glossary,
+        answer,
+        source_answer: research.answer.clone(),
+        research,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+// "English term = translation" per line; terms the answer doesn't contain are dropped
+pub fn parse_glossary(response: &str, answer: &str) -> Vec<GlossaryEntry> {
+    let answer = answer.to_lowercase();
+    let mut entries: Vec<GlossaryEntry> = Vec::new();
+    for line in response.lines() {
+        let line = line.trim().trim_start_matches(['-', '*', '•']).trim();
+        let Some((term, translation)) = line.split_once(" = ").or_else(|| line.split_once('=')) else {
+            continue;
+        };
+        let clean = |s: &str| s.replace("**", "").trim().trim_matches(['"', '\'', '`']).trim().to_string();
+        let (term, translation) = (clean(term), clean(translation));
+        if term.is_empty() || translation.is_empty() || !answer.contains(&term.to_lowercase()) {
+            continue;
+        }
+        if !entries.iter().any(|e| e.term.eq_ignore_ascii_case(&term)) {
+            entries.push(GlossaryEntry { term, translation });
+        }
+        if entries.len() == MAX_GLOSSARY_TERMS {
+            break;
+        }
+    }
+    entries
+}
+
+fn language_name(code: &str) -> &str {
+    crate::selection::LANGUAGE_NAMES.iter().find(|(c, _)| *c == code).map(|(_, name)| *name).unwrap_or(code)
+}
+
+#[derive(Debug, Clone)]
+pub enum MultilingualError {
+    EmptyQuery,
+    Translation(String),
+    Research(String),
+    Render(String),
+}
+
+impl MultilingualError {
+    pub fn stage(&self) -> MultilingualStage {
+        match self {
+            MultilingualError::EmptyQuery => MultilingualStage::Detect,
+            MultilingualError::Translation(_) => MultilingualStage::TranslateQuery,
+            MultilingualError::Research(_) => MultilingualStage::Research,
+            MultilingualError::Render(_) => MultilingualStage::Render,
+        }
+    }
+}
+
+impl std::fmt::Display for MultilingualError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MultilingualError::EmptyQuery => write!(f, "Research question is empty"),
+            MultilingualError::Translation(msg) => write!(f, "Could not translate the question: {}", msg),
+            MultilingualError::Research(msg) => write!(f, "Research failed: {}", msg),
+            MultilingualError::Render(msg) => write!(f, "Could not write the answer: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for MultilingualError {}
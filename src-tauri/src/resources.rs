@@ -0,0 +1,343 @@
+// Resource Manager - Global memory budget across loaded models, local services, and tabs
+// Over budget it takes one graded step per tick: unload an Ollama model, pause Meilisearch, freeze tabs
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::Emitter;
+
+use crate::browser::TabManager;
+use crate::embeddings::ollama_url;
+use crate::services::supervisor::ServiceSupervisor;
+
+// Webview memory isn't observable per tab; these are typical figures
+const TAB_AWAKE_BYTES: u64 = 150 * 1024 * 1024;
+const TAB_SLEEPING_BYTES: u64 = 15 * 1024 * 1024;
+// Services paused for memory come back once usage drops below this share of the budget
+const RESUME_BELOW: f64 = 0.75;
+// Only services that are safe to stop are paused (Ollama is handled by unloading models)
+const PAUSABLE_SERVICES: &[&str] = &["meilisearch"];
+const MAX_ACTIONS: usize = 50;
+const OLLAMA_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum ComponentKind {
+    App,
+    Model,
+    Service,
+    Tab,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentUsage {
+    pub kind: ComponentKind,
+    pub name: String,
+    pub bytes: u64,
+    pub estimated: bool,               // Heuristic rather than measured
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub enum ActionKind {
+    UnloadModel,
+    PauseService,
+    ResumeService,
+    FreezeTab,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceAction {
+    pub kind: ActionKind,
+    pub targets: Vec<String>,
+    pub freed_bytes: u64,              // Estimated
+    pub at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceReport {
+    pub budget_bytes: u64,
+    pub used_bytes: u64,
+    pub system_total_bytes: u64,
+    pub over_budget: bool,
+    pub components: Vec<ComponentUsage>,
+    pub recent_actions: Vec<ResourceAction>,
+}
+
+#[derive(Deserialize)]
+struct OllamaPsResponse {
+    #[serde(default)]
+    models: Vec<OllamaPsModel>,
+}
+
+#[derive(Deserialize)]
+struct OllamaPsModel {
+    name: String,
+    #[serde(default)]
+    size: u64,
+    #[serde(default)]
+    size_vram: u64,
+}
+
+#[derive(Clone)]
+pub struct ResourceManager {
+    budget: Arc<AtomicU64>,
+    actions: Arc<Mutex<VecDeque<ResourceAction>>>,
+    paused_services: Arc<Mutex<Vec<String>>>,
+    http: crate::http::Client,
+}
+
+impl ResourceManager {
+    pub fn new() -> Self {
+        let budget = configured_budget();
+        // Enforcement runs on a timer; a slow Ollama is skipped rather than retried
+        let http = crate::http::Client::new(OLLAMA_TIMEOUT).with_retries(0).with_purpose("ollama-status");
+
+        Self {
+            budget: Arc::new(AtomicU64::new(budget)),
+            actions: Arc::new(Mutex::new(VecDeque::new())),
+            paused_services: Arc::new(Mutex::new(Vec::new())),
+            http,
+        }
+    }
+
+    pub fn budget(&self) -> u64 {
+        self.budget.load(Ordering::Relaxed)
+    }
+
+    pub fn set_budget(&self, bytes: u64) {
+        self.budget.store(bytes, Ordering::Relaxed);
+    }
+
+    pub async fn report(&self, tabs: &TabManager, supervisor: &ServiceSupervisor) -> ResourceReport {
+        let components = self.measure(tabs, supervisor).await;
+        let used_bytes = components.iter().map(|c| c.bytes).sum();
+        ResourceReport {
+            budget_bytes: self.budget(),
+            used_bytes,
+            system_total_bytes: crate::stability::get_system_ram().unwrap_or(0),
+            over_budget: used_bytes > self.budget(),
+            components,
+            recent_actions: self.actions.lock().unwrap().iter().cloned().collect(),
+        }
+    }
+
+    async fn measure(&self, tabs: &TabManager, supervisor: &ServiceSupervisor) -> Vec<ComponentUsage> {
+        let mut components = Vec::new();
+
+        if let Some(bytes) = process_rss(std::process::id()) {
+            components.push(ComponentUsage { kind: ComponentKind::App, name: "app".to_string(), bytes, estimated: false });
+        }
+        for (name, pid) in supervisor.pids() {
+            if let Some(bytes) = process_rss(pid) {
+                components.push(ComponentUsage { kind: ComponentKind::Service, name, bytes, estimated: false });
+            }
+        }
+        for (name, bytes) in self.loaded_models().await {
+            components.push(ComponentUsage { kind: ComponentKind::Model, name, bytes, estimated: false });
+        }
+        for tab in tabs.list_tabs() {
+            let bytes = estimated_tab_bytes(tab.is_sleeping);
+            components.push(ComponentUsage { kind: ComponentKind::Tab, name: tab.id, bytes, estimated: true });
+        }
+        components
+    }
+
+    // Models resident in Ollama, counting only the part held in system RAM
+    async fn loaded_models(&self) -> Vec<(String, u64)> {
+        let Ok(response) = self.http.send(self.http.get(format!("{}/api/ps", ollama_url()))).await else {
+            return Vec::new();
+        };
+        let Ok(ps) = response.json::<OllamaPsResponse>().await else {
+            return Vec::new();
+        };
+        // Apple Silicon's "VRAM" is system RAM
+        let unified = crate::hardware::capabilities().acceleration.metal;
+        ps.models
+            .into_iter()
+            .map(|m| {
+                let ram = if unified { m.size } else { m.size.saturating_sub(m.size_vram) };
+                (m.name, ram)
+            })
+            .collect()
+    }
+
+    // One graded step toward the budget (or resuming paused services when well under it)
+    pub async fn enforce(&self, tabs: &TabManager, supervisor: &ServiceSupervisor) -> Option<ResourceAction> {
+        let components = self.measure(tabs, supervisor).await;
+        let used: u64 = components.iter().map(|c| c.bytes).sum();
+        let budget = self.budget();
+
+        if used <= budget {
+            if (used as f64) < budget as f64 * RESUME_BELOW {
+                return self.resume_paused(supervisor);
+            }
+            return None;
+        }
+        let excess = used - budget;
+
+        // 1. Unload the largest model (Ollama reloads it on the next request)
+        if let Some(model) = components
+            .iter()
+            .filter(|c| c.kind == ComponentKind::Model)
+            .max_by_key(|c| c.bytes)
+        {
+            if self.unload_model(&model.name).await {
+                return Some(self.record(ActionKind::UnloadModel, vec![model.name.clone()], model.bytes));
+            }
+        }
+
+        // 2. Pause optional services
+        for service in components
+            .iter()
+            .filter(|c| c.kind == ComponentKind::Service && PAUSABLE_SERVICES.contains(&c.name.as_str()))
+        {
+            if supervisor.pause(&service.name).is_ok() {
+                self.paused_services.lock().unwrap().push(service.name.clone());
+                return Some(self.record(ActionKind::PauseService, vec![service.name.clone()], service.bytes));
+            }
+        }
+
+        // 3. Freeze the least recently used background tabs until the estimate covers the excess
+        let mut candidates: Vec<_> = tabs
+            .list_tabs()
+            .into_iter()
+            .filter(|t| !t.is_active && !t.is_pinned && !t.is_sleeping)
+            .collect();
+        candidates.sort_by_key(|t| t.last_active_at);
+
+        let mut frozen = Vec::new();
+        let mut freed = 0;
+        for tab in candidates {
+            if freed >= excess {
+                break;
+            }
+            if tabs.freeze_tab(&tab.id).is_ok() {
+                freed += TAB_AWAKE_BYTES - TAB_SLEEPING_BYTES;
+                frozen.push(tab.id);
+            }
+        }
+        if frozen.is_empty() {
+            tracing::warn!(target: "stability", "Resources: Over budget by {} MB with nothing left to reclaim", excess / (1024 * 1024));
+            return None;
+        }
+        Some(self.record(ActionKind::FreezeTab, frozen, freed))
+    }
+
+    fn resume_paused(&self, supervisor: &ServiceSupervisor) -> Option<ResourceAction> {
+        let name = self.paused_services.lock().unwrap().pop()?;
+        if let Err(e) = supervisor.resume(&name) {
+            tracing::warn!(target: "stability", "Resources: Failed to resume {}: {}", name, e);
+            return None;
+        }
+        Some(self.record(ActionKind::ResumeService, vec![name], 0))
+    }
+
+    async fn unload_model(&self, model: &str) -> bool {
+        // keep_alive 0 with no prompt evicts the model immediately
+        let body = serde_json::json!({ "model": model, "keep_alive": 0 });
+        matches!(
+            self.http.send(self.http.post(format!("{}/api/generate", ollama_url())).json(&body)).await,
+            Ok(r) if r.status().is_success()
+        )
+    }
+
+    fn record(&self, kind: ActionKind, targets: Vec<String>, freed_bytes: u64) -> ResourceAction {
+        let action = ResourceAction { kind, targets, freed_bytes, at: chrono::Utc::now().timestamp() };
+        tracing::info!(target: "stability", "Resources: {:?} {:?} (~{} MB)", action.kind, action.targets, freed_bytes / (1024 * 1024));
+        let mut actions = self.actions.lock().unwrap();
+        actions.push_back(action.clone());
+        while actions.len() > MAX_ACTIONS {
+            actions.pop_front();
+        }
+        action
+    }
+
+    // Periodic enforcement; each action is emitted as "resource:action"
+    pub fn spawn_enforcer(
+        &self,
+        app: tauri::AppHandle,
+        tabs: Arc<TabManager>,
+        supervisor: ServiceSupervisor,
+        check_interval: Duration,
+    ) {
+        let manager = self.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(check_interval);
+            loop {
+                interval.tick().await;
+                if let Some(action) = manager.enforce(&tabs, &supervisor).await {
+                    let _ = app.emit("resource:action", &action);
+                }
+            }
+        });
+    }
+}
+
+impl Default for ResourceManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Budget from config.toml (resources.budgetMb), else 60% of physical RAM (4GB when RAM can't be read).
+// Mode profiles with their own budgetMb replace this while active.
+pub fn configured_budget() -> u64 {
+    let total = crate::stability::get_system_ram().unwrap_or(0);
+    match crate::config::current().resources.budget_mb {
+        Some(mb) => mb * 1024 * 1024,
+        None if total > 0 => total / 10 * 6,
+        None => 4 * 1024 * 1024 * 1024,
+    }
+}
+
+pub fn estimated_tab_bytes(sleeping: bool) -> u64 {
+    if sleeping { TAB_SLEEPING_BYTES } else { TAB_AWAKE_BYTES }
+}
+
+// Resident set size of a process, in bytes
+#[cfg(target_os = "linux")]
+pub fn process_rss(pid: u32) -> Option<u64> {
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(target_os = "macos")]
+pub fn process_rss(pid: u32) -> Option<u64> {
+    let output = std::process::Command::new("ps")
+        .args(["-o", "rss=", "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    let kb: u64 = String::from_utf8_lossy(&output.stdout).trim().parse().ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(target_os = "windows")]
+pub fn process_rss(pid: u32) -> Option<u64> {
+    // "name","pid","session","#","12,345 K"
+    let output = std::process::Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {}", pid), "/FO", "CSV", "/NH"])
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let field = stdout.lines().next()?.rsplit("\",\"").next()?;
+    let kb: u64 = field.chars().filter(char::is_ascii_digit).collect::<String>().parse().ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+pub fn process_rss(_pid: u32) -> Option<u64> {
+    None
+}
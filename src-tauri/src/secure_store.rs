@@ -0,0 +1,185 @@
+// Secure Store - Encrypted key/value vault in the profile's secure storage directory
+// AES-256-GCM over the whole map; the key lives in the OS keychain (Keychain, Credential Manager,
+// Secret Service), one entry per vault directory, so copying the profile folder doesn't copy the key
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+const VAULT_FILE: &str = "vault.bin";
+// Where older builds kept the key; moved into the keychain on first open
+const LEGACY_KEY_FILE: &str = "vault.key";
+const KEYCHAIN_SERVICE: &str = "com.regen.app";
+const NONCE_LEN: usize = 12;
+
+struct Vault {
+    dir: PathBuf,
+    cipher: Aes256Gcm,
+    entries: BTreeMap<String, String>,
+}
+
+#[derive(Clone)]
+pub struct SecureStore {
+    vault: Arc<Mutex<Vault>>,
+}
+
+impl SecureStore {
+    pub fn open(dir: PathBuf) -> Result<Self, SecureStoreError> {
+        Ok(Self { vault: Arc::new(Mutex::new(Vault::open(dir)?)) })
+    }
+
+    // Point this store (and every clone of it) at another profile's directory
+    pub fn reopen(&self, dir: PathBuf) -> Result<(), SecureStoreError> {
+        *self.vault.lock().unwrap() = Vault::open(dir)?;
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<String> {
+        self.vault.lock().unwrap().entries.get(name).cloned()
+    }
+
+    pub fn set(&self, name: &str, value: &str) -> Result<(), SecureStoreError> {
+        let mut vault = self.vault.lock().unwrap();
+        vault.entries.insert(name.to_string(), value.to_string());
+        vault.save()
+    }
+
+    pub fn remove(&self, name: &str) -> Result<bool, SecureStoreError> {
+        let mut vault = self.vault.lock().unwrap();
+        let removed = vault.entries.remove(name).is_some();
+        if removed {
+            vault.save()?;
+        }
+        Ok(removed)
+    }
+
+    // Entry names starting with `prefix` (values stay in the vault)
+    pub fn names(&self, prefix: &str) -> Vec<String> {
+        self.vault
+            .lock()
+            .unwrap()
+            .entries
+            .keys()
+            .filter(|k| k.starts_with(prefix))
+            .cloned()
+            .collect()
+    }
+}
+
+impl Vault {
+    fn open(dir: PathBuf) -> Result<Self, SecureStoreError> {
+        std::fs::create_dir_all(&dir).map_err(|e| SecureStoreError::Io(e.to_string()))?;
+        let cipher = Aes256Gcm::new(&load_or_create_key(&dir)?);
+
+        let entries = match std::fs::read(dir.join(VAULT_FILE)) {
+            Ok(bytes) => decrypt(&cipher, &bytes)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(SecureStoreError::Io(e.to_string())),
+        };
+        Ok(Self { dir, cipher, entries })
+    }
+
+    // Fresh nonce per write; temp file + rename so a crash never leaves a torn vault
+    fn save(&self) -> Result<(), SecureStoreError> {
+        let plaintext = serde_json::to_vec(&self.entries).map_err(|e| SecureStoreError::Corrupt(e.to_string()))?;
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|_| SecureStoreError::Crypto)?;
+
+        let mut bytes = nonce.to_vec();
+        bytes.extend_from_slice(&ciphertext);
+        let path = self.dir.join(VAULT_FILE);
+        let tmp = path.with_extension("bin.tmp");
+        write_private(&tmp, &bytes)?;
+        std::fs::rename(&tmp, &path).map_err(|e| SecureStoreError::Io(e.to_string()))
+    }
+}
+
+fn decrypt(cipher: &Aes256Gcm, bytes: &[u8]) -> Result<BTreeMap<String, String>, SecureStoreError> {
+    if bytes.len() <= NONCE_LEN {
+        return Err(SecureStoreError::Corrupt("vault file is truncated".to_string()));
+    }
+    let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| SecureStoreError::Crypto)?;
+    serde_json::from_slice(&plaintext).map_err(|e| SecureStoreError::Corrupt(e.to_string()))
+}
+
+fn load_or_create_key(dir: &Path) -> Result<Key<Aes256Gcm>, SecureStoreError> {
+    let entry = keyring::Entry::new(KEYCHAIN_SERVICE, &format!("vault:{}", dir.display()))
+        .map_err(|e| SecureStoreError::Keychain(e.to_string()))?;
+    let legacy = dir.join(LEGACY_KEY_FILE);
+    match entry.get_secret() {
+        Ok(bytes) if bytes.len() == 32 => {
+            // A previous migration stored the key but didn't get to delete the file
+            let _ = std::fs::remove_file(&legacy);
+            Ok(*Key::<Aes256Gcm>::from_slice(&bytes))
+        }
+        Ok(_) => Err(SecureStoreError::Corrupt("vault key has the wrong length".to_string())),
+        Err(keyring::Error::NoEntry) => {
+            let key = match std::fs::read(&legacy) {
+                Ok(bytes) if bytes.len() == 32 => *Key::<Aes256Gcm>::from_slice(&bytes),
+                Ok(_) => return Err(SecureStoreError::Corrupt("vault key has the wrong length".to_string())),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Aes256Gcm::generate_key(OsRng),
+                Err(e) => return Err(SecureStoreError::Io(e.to_string())),
+            };
+            entry
+                .set_secret(key.as_slice())
+                .map_err(|e| SecureStoreError::Keychain(e.to_string()))?;
+            // Only once the keychain holds it, so a failed write never loses the key
+            if legacy.exists() {
+                std::fs::remove_file(&legacy).map_err(|e| SecureStoreError::Io(e.to_string()))?;
+                tracing::info!(target: "privacy", "Secure storage: Moved vault key into the OS keychain");
+            }
+            Ok(key)
+        }
+        Err(e) => Err(SecureStoreError::Keychain(e.to_string())),
+    }
+}
+
+#[cfg(unix)]
+fn write_private(path: &Path, bytes: &[u8]) -> Result<(), SecureStoreError> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)
+        .map_err(|e| SecureStoreError::Io(e.to_string()))?;
+    file.write_all(bytes).map_err(|e| SecureStoreError::Io(e.to_string()))
+}
+
+// Files under the per-user app data directory are private to the account on Windows
+#[cfg(not(unix))]
+fn write_private(path: &Path, bytes: &[u8]) -> Result<(), SecureStoreError> {
+    std::fs::write(path, bytes).map_err(|e| SecureStoreError::Io(e.to_string()))
+}
+
+#[derive(Debug, Clone)]
+pub enum SecureStoreError {
+    Io(String),
+    Keychain(String),                  // OS keychain missing or refused access
+    Crypto,
+    Corrupt(String),
+}
+
+impl std::fmt::Display for SecureStoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SecureStoreError::Io(msg) => write!(f, "Secure storage error: {}", msg),
+            SecureStoreError::Keychain(msg) => write!(f, "OS keychain unavailable: {}", msg),
+            SecureStoreError::Crypto => write!(f, "Secure storage could not be decrypted"),
+            SecureStoreError::Corrupt(msg) => write!(f, "Secure storage is corrupt: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SecureStoreError {}
@@ -0,0 +1,276 @@
+// Selection Actions - Backend work behind the page context menu, one entry point for every action
+// Quick actions (highlight, note) finish inline; model and network work runs as a job reported by event
+
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use tauri::{Emitter, Manager};
+
+use crate::ai::AIService;
+use crate::apikeys::ApiKeyStore;
+use crate::db::Database;
+use crate::highlights::{Highlight, Selector};
+use crate::local_index::LocalIndex;
+use crate::notes::Note;
+
+// Longer selections are cut before going to the model
+const MAX_MODEL_CHARS: usize = 12_000;
+// Research queries are built from the start of the selection
+const MAX_QUERY_WORDS: usize = 24;
+const RESEARCH_RESULTS: usize = 8;
+
+// Target names for the translate prompt (ISO 639-1 codes as used by language detection)
+pub(crate) const LANGUAGE_NAMES: &[(&str, &str)] = &[
+    ("en", "English"),
+    ("hi", "Hindi"),
+    ("mr", "Marathi"),
+    ("bn", "Bengali"),
+    ("ta", "Tamil"),
+    ("te", "Telugu"),
+    ("gu", "Gujarati"),
+    ("kn", "Kannada"),
+    ("ml", "Malayalam"),
+    ("pa", "Punjabi"),
+    ("ur", "Urdu"),
+    ("es", "Spanish"),
+    ("fr", "French"),
+    ("de", "German"),
+    ("pt", "Portuguese"),
+    ("ar", "Arabic"),
+    ("zh", "Chinese"),
+    ("ja", "Japanese"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub enum SelectionAction {
+    Summarize,
+    Translate,
+    Highlight,
+    Note,
+    Research,
+}
+
+impl SelectionAction {
+    pub fn parse(value: &str) -> Result<Self, SelectionError> {
+        match value {
+            "summarize" => Ok(SelectionAction::Summarize),
+            "translate" => Ok(SelectionAction::Translate),
+            "highlight" => Ok(SelectionAction::Highlight),
+            "note" => Ok(SelectionAction::Note),
+            "research" => Ok(SelectionAction::Research),
+            other => Err(SelectionError::UnknownAction(other.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct SelectionPayload {
+    pub text: String,
+    pub url: Option<String>,
+    pub title: Option<String>,
+    pub prefix: String,                // Context around the selection, for anchoring highlights
+    pub suffix: String,
+    pub target_language: Option<String>, // Translate; defaults to the UI language
+    pub mode: Option<String>,          // App mode, picks mode-specific prompts
+    pub color: Option<String>,         // Highlight
+    pub note: Option<String>,          // Highlight annotation
+    pub tags: Vec<String>,             // Note
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub enum JobStatus {
+    Running,
+    Done,
+    Failed,
+}
+
+// Returned by selection_action; while Running, the result arrives as "selection-action:done"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SelectionJob {
+    pub job_id: String,
+    pub action: SelectionAction,
+    pub status: JobStatus,
+    pub result: Option<serde_json::Value>,
+    pub error: Option<String>,
+    pub elapsed_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationResult {
+    pub text: String,
+    pub source_language: String,
+    pub target_language: String,
+}
+
+pub fn validate(payload: &SelectionPayload) -> Result<(), SelectionError> {
+    if payload.text.trim().is_empty() {
+        return Err(SelectionError::EmptySelection);
+    }
+    Ok(())
+}
+
+// Highlight anchored by a text quote with its surrounding context
+pub fn highlight(db: &Database, payload: &SelectionPayload) -> Result<Highlight, SelectionError> {
+    let url = payload.url.clone().filter(|u| !u.trim().is_empty()).ok_or(SelectionError::MissingUrl)?;
+    let selector = Selector::TextQuote {
+        exact: payload.text.clone(),
+        prefix: payload.prefix.clone(),
+        suffix: payload.suffix.clone(),
+    };
+    let highlight = Highlight::new(url, vec![selector], payload.color.clone(), payload.note.clone())
+        .map_err(|e| SelectionError::Failed(e.to_string()))?;
+    db.save_highlight(&highlight).map_err(|e| SelectionError::Failed(e.to_string()))?;
+    Ok(highlight)
+}
+
+pub fn note(db: &Database, local_index: &LocalIndex, payload: &SelectionPayload) -> Result<Note, SelectionError> {
+    let note = Note::new(payload.text.trim().to_string(), payload.title.clone(), payload.url.clone(), payload.tags.clone());
+    db.save_note(&note).map_err(|e| SelectionError::Failed(e.to_string()))?;
+    local_index.note_saved(&note);
+    Ok(note)
+}
+
+// Selections too long for one call go through the map-reduce summarizer instead of being cut
+pub async fn summarize(app: &tauri::AppHandle, payload: &SelectionPayload) -> Result<String, SelectionError> {
+    let content = payload.text.trim();
+    if content.chars().count() > MAX_MODEL_CHARS {
+        let options = crate::summarizer::SummaryOptions {
+            title: payload.title.clone(),
+            mode: payload.mode.clone(),
+            ..Default::default()
+        };
+        return crate::summarizer::summarize(app.clone(), content.to_string(), options, |_| {})
+            .await
+            .map(|s| s.summary)
+            .map_err(|e| SelectionError::Failed(e.to_string()));
+    }
+    let ai = app.state::<AIService>();
+    let language = crate::language::detect_language(content).language;
+    let prompt = crate::prompts::render("summarize", Some(&language), payload.mode.as_deref(), &[("content", content)]);
+    ai.complete_task("summarize", &prompt)
+        .await
+        .map(|s| s.trim().to_string())
+        .map_err(|e| SelectionError::Failed(e.to_string()))
+}
+
+pub async fn translate(ai: &AIService, payload: &SelectionPayload, default_language: &str) -> Result<TranslationResult, SelectionError> {
+    let content = truncate_chars(payload.text.trim(), MAX_MODEL_CHARS);
+    let target = payload
+        .target_language
+        .as_deref()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .unwrap_or(default_language)
+        .to_lowercase();
+    let source = crate::language::detect_language(content).language;
+    if source == target {
+        return Ok(TranslationResult { text: content.to_string(), source_language: source, target_language: target });
+    }
+
+    let target_name = LANGUAGE_NAMES
+        .iter()
+        .find(|(code, _)| *code == target)
+        .map(|(_, name)| name.to_string())
+        .unwrap_or_else(|| target.clone());
+    let prompt = crate::prompts::render("translate", None, None, &[("content", content), ("target_language", &target_name)]);
+    let text = ai
+        .complete_task("translate", &prompt)
+        .await
+        .map_err(|e| SelectionError::Failed(e.to_string()))?;
+    Ok(TranslationResult { text: text.trim().to_string(), source_language: source, target_language: target })
+}
+
+// Web search seeded with the selection
+pub async fn research(api_keys: &ApiKeyStore, payload: &SelectionPayload) -> Result<crate::web_search::WebSearchResponse, SelectionError> {
+    let query = payload.text.split_whitespace().take(MAX_QUERY_WORDS).collect::<Vec<_>>().join(" ");
+    crate::web_search::search(&query, None, Some(RESEARCH_RESULTS), api_keys)
+        .await
+        .map_err(|e| SelectionError::Failed(e.to_string()))
+}
+
+// Run a model/network action in the background; emits "selection-action:done" with the finished job
+pub fn spawn_job(app: tauri::AppHandle, action: SelectionAction, payload: SelectionPayload, default_language: String) -> SelectionJob {
+    let job_id = format!("sel-{}", uuid::Uuid::new_v4());
+    let job = SelectionJob {
+        job_id: job_id.clone(),
+        action,
+        status: JobStatus::Running,
+        result: None,
+        error: None,
+        elapsed_ms: 0,
+    };
+
+    tauri::async_runtime::spawn(async move {
+        let started = Instant::now();
+        let outcome: Result<serde_json::Value, SelectionError> = match action {
+            SelectionAction::Summarize => {
+                summarize(&app, &payload).await.map(|summary| serde_json::json!({ "summary": summary }))
+            }
+            SelectionAction::Translate => {
+                let ai = app.state::<AIService>();
+                translate(&ai, &payload, &default_language).await.and_then(to_value)
+            }
+            SelectionAction::Research => {
+                let api_keys = app.state::<ApiKeyStore>();
+                research(&api_keys, &payload).await.and_then(to_value)
+            }
+            SelectionAction::Highlight | SelectionAction::Note => Err(SelectionError::Failed("not a background action".to_string())),
+        };
+
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+        let finished = match outcome {
+            Ok(result) => SelectionJob { job_id, action, status: JobStatus::Done, result: Some(result), error: None, elapsed_ms },
+            Err(e) => {
+                tracing::warn!(target: "ai", "Selection action: {:?} failed: {}", action, e);
+                SelectionJob { job_id, action, status: JobStatus::Failed, result: None, error: Some(e.to_string()), elapsed_ms }
+            }
+        };
+        let _ = app.emit("selection-action:done", finished);
+    });
+
+    job
+}
+
+fn to_value<T: Serialize>(value: T) -> Result<serde_json::Value, SelectionError> {
+    serde_json::to_value(value).map_err(|e| SelectionError::Failed(e.to_string()))
+}
+
+fn truncate_chars(text: &str, max: usize) -> &str {
+    match text.char_indices().nth(max) {
+        Some((i, _)) => &text[..i],
+        None => text,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum SelectionError {
+    UnknownAction(String),
+    EmptySelection,
+    MissingUrl,
+    Failed(String),
+}
+
+impl std::fmt::Display for SelectionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SelectionError::UnknownAction(action) => write!(
+                f,
+                "Unknown selection action '{}' (expected summarize, translate, highlight, note, or research)",
+                action
+            ),
+            SelectionError::EmptySelection => write!(f, "Nothing is selected"),
+            SelectionError::MissingUrl => write!(f, "Highlights need the page URL"),
+            SelectionError::Failed(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SelectionError {}
@@ -0,0 +1,230 @@
+// Semantic History - History entries embedded as they're visited, searchable by meaning
+// Each URL's vector comes from its title plus the start of the cached page text; ranking blends similarity with frecency
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use crate::db::Database;
+use crate::embeddings;
+
+const DEFAULT_K: usize = 10;
+const MAX_K: usize = 50;
+// Page text embedded per entry, after the title
+const EXTRACT_CHARS: usize = 500;
+// Visits arriving close together are embedded as one batch
+const BATCH_WINDOW: Duration = Duration::from_secs(2);
+const BATCH_SIZE: usize = 32;
+// Startup catch-up for entries without a vector from the current model
+const BACKFILL_DELAY: Duration = Duration::from_secs(30);
+const BACKFILL_ROWS: usize = 1000;
+// Most recent vectors compared per query
+const MAX_CANDIDATES: usize = 5000;
+// Share of the score from frecency; the rest is similarity
+const FRECENCY_WEIGHT: f32 = 0.2;
+// Hashed vectors only match on shared words, so their similarities run much lower
+const MIN_SIMILARITY: f32 = 0.3;
+const MIN_SIMILARITY_HASHED: f32 = 0.1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SemanticHistoryHit {
+    pub url: String,
+    pub title: String,
+    pub visited_at: i64,               // Unix seconds, last visit
+    pub visit_count: i64,
+    pub similarity: f32,               // Cosine similarity to the query
+    pub frecency: f64,
+    pub score: f32,                    // Blended ranking score
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SemanticHistoryResponse {
+    pub query: String,
+    pub model: String,                 // Only entries embedded with this model were compared
+    pub searched: usize,               // Entries compared
+    pub hits: Vec<SemanticHistoryHit>,
+    pub took_ms: u64,
+}
+
+// A stored history vector joined with its history row
+#[derive(Debug, Clone)]
+pub struct HistoryVector {
+    pub url: String,
+    pub title: String,
+    pub visited_at: i64,
+    pub visit_count: i64,
+    pub frecency: f64,
+    pub vector: Vec<f32>,
+}
+
+#[derive(Clone)]
+pub struct SemanticHistory {
+    queue: mpsc::UnboundedSender<String>,
+}
+
+impl SemanticHistory {
+    // Starts the embedding worker; older history is backfilled shortly after startup
+    pub fn start(db: Database) -> Self {
+        let (queue, rx) = mpsc::unbounded_channel();
+        tauri::async_runtime::spawn(worker(db, rx, queue.clone()));
+        Self { queue }
+    }
+
+    // A history visit or a newly cached page text; re-embedded if its text changed
+    pub fn visited(&self, url: &str) {
+        // Only fails once the worker is gone during shutdown
+        let _ = self.queue.send(url.to_string());
+    }
+}
+
+async fn worker(db: Database, mut rx: mpsc::UnboundedReceiver<String>, queue: mpsc::UnboundedSender<String>) {
+    tauri::async_runtime::spawn(backfill(db.clone(), queue));
+    while let Some(url) = rx.recv().await {
+        tokio::time::sleep(BATCH_WINDOW).await;
+        let mut urls = vec![url];
+        while urls.len() < BATCH_SIZE {
+            match rx.try_recv() {
+                Ok(url) => urls.push(url),
+                Err(_) => break,
+            }
+        }
+        let mut seen = HashSet::new();
+        urls.retain(|u| seen.insert(u.clone()));
+        match embed_urls(&db, &urls).await {
+            Ok(0) => {}
+            Ok(n) => tracing::debug!(target: "ai", "Semantic history: Embedded {} entries", n),
+            Err(e) => tracing::warn!(target: "ai", "Semantic history: Failed to embed visits: {}", e),
+        }
+    }
+}
+
+// Queue entries with no vector (or one from another model), newest first
+async fn backfill(db: Database, queue: mpsc::UnboundedSender<String>) {
+    tokio::time::sleep(BACKFILL_DELAY).await;
+    if crate::modes::background_paused() {
+        return;
+    }
+    match db.history_missing_embeddings(&embeddings::default_model(), BACKFILL_ROWS) {
+        Ok(urls) => {
+            if !urls.is_empty() {
+                tracing::debug!(target: "ai", "Semantic history: Backfilling {} entries", urls.len());
+            }
+            for url in urls {
+                let _ = queue.send(url);
+            }
+        }
+        Err(e) => tracing::warn!(target: "db", "Semantic history: Failed to list entries to backfill: {}", e),
+    }
+}
+
+// Embed entries whose text changed since they were last embedded; returns how many were stored
+async fn embed_urls(db: &Database, urls: &[String]) -> Result<usize, SemanticHistoryError> {
+    let err = |e: rusqlite::Error| SemanticHistoryError::Storage(e.to_string());
+    let model = embeddings::default_model();
+    let mut pending: Vec<(String, String, String)> = Vec::new();   // (url, key, text)
+    for url in urls {
+        let Some(title) = db.history_title(url).map_err(err)? else {
+            continue;                  // Deleted since it was queued
+        };
+        let extract = db
+            .get_page(url)
+            .ok()
+            .flatten()
+            .map(|p| p.content.chars().take(EXTRACT_CHARS).collect::<String>())
+            .unwrap_or_default();
+        let text = format!("{}\n{}\n{}", title, url, extract);
+        let key = embeddings::content_key(&text);
+        if db.history_embedding_key(url).map_err(err)?.is_some_and(|(k, m)| k == key && m == model) {
+            continue;
+        }
+        pending.push((url.clone(), key, text));
+    }
+    if pending.is_empty() {
+        return Ok(0);
+    }
+
+    let texts: Vec<String> = pending.iter().map(|(_, _, t)| t.clone()).collect();
+    let embedded = embeddings::embed(&texts).await;
+    let rows: Vec<(String, String, Vec<f32>)> = pending
+        .into_iter()
+        .zip(embedded.vectors)
+        .map(|((url, key, _), vector)| (url, key, vector))
+        .collect();
+    db.save_history_embeddings(&rows, &embedded.model).map_err(err)?;
+    Ok(rows.len())
+}
+
+// History entries closest in meaning to `query`, blended with frecency; k defaults to 10
+pub async fn search(db: &Database, query: &str, k: Option<usize>) -> Result<SemanticHistoryResponse, SemanticHistoryError> {
+    let started = std::time::Instant::now();
+    let query = query.trim();
+    if query.is_empty() {
+        return Err(SemanticHistoryError::EmptyQuery);
+    }
+    let k = k.unwrap_or(DEFAULT_K).clamp(1, MAX_K);
+    let embedded = embeddings::embed(&[query.to_string()]).await;
+    let Some(query_vector) = embedded.vectors.first() else {
+        return Err(SemanticHistoryError::Storage("no query vector".to_string()));
+    };
+    let candidates = db
+        .history_vectors(&embedded.model, MAX_CANDIDATES)
+        .map_err(|e| SemanticHistoryError::Storage(e.to_string()))?;
+
+    let min_similarity = match embedded.source {
+        embeddings::EmbeddingSource::Hashed => MIN_SIMILARITY_HASHED,
+        _ => MIN_SIMILARITY,
+    };
+    // Log-scaled against the busiest entry, so a few heavily visited sites don't swamp similarity
+    let max_frecency = candidates.iter().map(|c| c.frecency).fold(0.0, f64::max);
+    let mut hits: Vec<SemanticHistoryHit> = candidates
+        .iter()
+        .filter_map(|c| {
+            let similarity = embeddings::cosine(query_vector, &c.vector);
+            if similarity < min_similarity {
+                return None;
+            }
+            let frecency = if max_frecency > 0.0 { (c.frecency.ln_1p() / max_frecency.ln_1p()) as f32 } else { 0.0 };
+            Some(SemanticHistoryHit {
+                url: c.url.clone(),
+                title: c.title.clone(),
+                visited_at: c.visited_at,
+                visit_count: c.visit_count,
+                similarity,
+                frecency: c.frecency,
+                score: (1.0 - FRECENCY_WEIGHT) * similarity + FRECENCY_WEIGHT * frecency,
+            })
+        })
+        .collect();
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    hits.truncate(k);
+
+    Ok(SemanticHistoryResponse {
+        query: query.to_string(),
+        model: embedded.model,
+        searched: candidates.len(),
+        hits,
+        took_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+#[derive(Debug, Clone)]
+pub enum SemanticHistoryError {
+    EmptyQuery,
+    Storage(String),
+}
+
+impl std::fmt::Display for SemanticHistoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SemanticHistoryError::EmptyQuery => write!(f, "Query is empty"),
+            SemanticHistoryError::Storage(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SemanticHistoryError {}
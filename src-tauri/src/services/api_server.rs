@@ -0,0 +1,512 @@
+// API Server - Optional localhost REST API so external tools (Raycast, Alfred, scripts) can drive Regen
+// Off unless config.api.enabled. Binds 127.0.0.1 only, and every route but the OpenAPI document needs the
+// bearer token kept in secure storage. Routes are a fixed allowlist of registered commands run through the
+// palette's dispatcher; GET /v1/openapi.json describes them from the build-time command schemas.
+// The MCP server (mcp.rs) rides along under /mcp when config.mcp.enabled.
+use http_body_util::BodyExt;
+use hyper::body::{Bytes, Frame, Incoming};
+use hyper::{Method, Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::sync::Mutex;
+use tauri::Manager;
+use tokio::sync::oneshot;
+
+use crate::config::ApiConfig;
+use crate::palette::{self, CommandSpec};
+use crate::secure_store::SecureStore;
+
+const TOKEN_KEY: &str = "api-server:token";
+const TOKEN_PREFIX: &str = "rgn_";
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+const OPENAPI_PATH: &str = "/v1/openapi.json";
+const MCP_PATH: &str = "/mcp";
+const MCP_SSE_PATH: &str = "/mcp/sse";
+const MCP_MESSAGES_PATH: &str = "/mcp/messages";
+
+type Body = http_body_util::combinators::UnsyncBoxBody<Bytes, Infallible>;
+
+// One REST route onto one registered command; `{name}` segments fill the argument of that name
+pub struct Route {
+    pub method: &'static str,
+    pub path: &'static str,
+    pub command: &'static str,
+    pub summary: &'static str,
+}
+
+pub const ROUTES: &[Route] = &[
+    Route { method: "GET", path: "/v1/search", command: "search_local", summary: "Search history, notes, and saved pages" },
+    Route { method: "GET", path: "/v1/search/web", command: "search_web", summary: "Search the web with the configured providers" },
+    Route { method: "POST", path: "/v1/research", command: "research_run", summary: "Run a research query and return the report" },
+    Route { method: "GET", path: "/v1/notes", command: "note_list", summary: "List notes, optionally by tag" },
+    Route { method: "POST", path: "/v1/notes", command: "note_create", summary: "Create a note" },
+    Route { method: "GET", path: "/v1/notes/search", command: "note_search", summary: "Full-text search of notes" },
+    Route { method: "GET", path: "/v1/notes/{id}", command: "note_get", summary: "Get a note" },
+    Route { method: "PATCH", path: "/v1/notes/{id}", command: "note_update", summary: "Update a note" },
+    Route { method: "DELETE", path: "/v1/notes/{id}", command: "note_delete", summary: "Delete a note" },
+    Route { method: "GET", path: "/v1/quotes/{symbol}", command: "trade_quote", summary: "Latest daily close for a ticker" },
+    Route { method: "GET", path: "/v1/news/{symbol}", command: "trade_news", summary: "Headlines for a ticker" },
+    Route { method: "GET", path: "/v1/fx/convert", command: "fx_convert", summary: "Convert between currencies" },
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ApiServerStatus {
+    pub enabled: bool,                 // config.api.enabled
+    pub running: bool,
+    pub port: u16,
+    pub url: Option<String>,           // Base URL while running
+    pub token_set: bool,
+    pub error: Option<String>,         // Why the server is not running
+}
+
+#[derive(Default)]
+struct Server {
+    stop: Option<oneshot::Sender<()>>,
+    port: Option<u16>,
+    error: Option<String>,
+}
+
+static SERVER: Mutex<Option<Server>> = Mutex::new(None);
+
+fn with_server<T>(f: impl FnOnce(&mut Server) -> T) -> T {
+    let mut guard = SERVER.lock().unwrap();
+    f(guard.get_or_insert_with(Server::default))
+}
+
+/// Start, restart on a new port, or stop to match config (called from config::apply)
+pub fn apply(app: &tauri::AppHandle, api: &ApiConfig) {
+    let running_port = with_server(|s| s.stop.as_ref().and(s.port));
+    if !api.enabled {
+        shutdown();
+        return;
+    }
+    if running_port == Some(api.port) {
+        return;
+    }
+    shutdown();
+    if let Err(e) = start(app, api.port) {
+        tracing::warn!(target: "services", "API server: {}", e);
+        with_server(|s| s.error = Some(e.to_string()));
+    }
+}
+
+pub fn shutdown() {
+    if let Some(stop) = with_server(|s| s.stop.take()) {
+        let _ = stop.send(());
+        tracing::info!(target: "services", "API server: Stopped");
+    }
+}
+
+pub fn status(app: &tauri::AppHandle) -> ApiServerStatus {
+    let api = crate::config::current().api;
+    let token_set = app.try_state::<SecureStore>().is_some_and(|store| store.get(TOKEN_KEY).is_some());
+    with_server(|s| {
+        let running = s.stop.is_some();
+        ApiServerStatus {
+            enabled: api.enabled,
+            running,
+            port: s.port.filter(|_| running).unwrap_or(api.port),
+            url: s.port.filter(|_| running).map(|port| format!("http://127.0.0.1:{}", port)),
+            token_set,
+            error: s.error.clone(),
+        }
+    })
+}
+
+/// The bearer token, created on first use; `regenerate` invalidates the old one
+pub fn token(app: &tauri::AppHandle, regenerate: bool) -> Result<String, ApiServerError> {
+    let store = app.try_state::<SecureStore>().ok_or(ApiServerError::StoreUnavailable)?;
+    if !regenerate {
+        if let Some(token) = store.get(TOKEN_KEY) {
+            return Ok(token);
+        }
+    }
+    let token = format!("{}{}{}", TOKEN_PREFIX, uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+    store.set(TOKEN_KEY, &token).map_err(|e| ApiServerError::Store(e.to_string()))?;
+    Ok(token)
+}
+
+// OpenAPI 3 description of ROUTES, with parameters and bodies taken from the command signatures
+pub fn openapi(port: u16) -> serde_json::Value {
+    let mut paths = serde_json::Map::new();
+    for route in ROUTES {
+        let Some(spec) = command_spec(route.command) else {
+            continue;
+        };
+        let in_path = path_params(route.path);
+        let mut parameters = Vec::new();
+        let mut body = Vec::new();
+        for param in spec.params {
+            let (schema, optional) = palette::type_schema(param.rust_type);
+            if in_path.contains(&param.name) {
+                parameters.push(serde_json::json!({ "name": param.name, "in": "path", "required": true, "schema": schema }));
+            } else if param.name == "requestId" {
+                // Filled in by the server
+            } else if takes_body(route.method) {
+                body.push(param);
+            } else {
+                parameters.push(serde_json::json!({ "name": param.name, "in": "query", "required": !optional, "schema": schema }));
+            }
+        }
+        let mut operation = serde_json::json!({
+            "operationId": route.command,
+            "summary": route.summary,
+            "description": spec.description,
+            "tags": [spec.category],
+            "parameters": parameters,
+            "responses": {
+                "200": { "description": "The command's result", "content": { "application/json": { "schema": {} } } },
+                "400": { "$ref": "#/components/responses/Error" },
+                "401": { "$ref": "#/components/responses/Error" },
+                "500": { "$ref": "#/components/responses/Error" },
+            },
+        });
+        if !body.is_empty() {
+            let params: Vec<palette::ParamSpec> = body
+                .iter()
+                .map(|p| palette::ParamSpec { name: p.name, rust_type: p.rust_type })
+                .collect();
+            operation["requestBody"] = serde_json::json!({
+                "required": true,
+                "content": { "application/json": { "schema": palette::params_schema(&params) } },
+            });
+        }
+        let item = paths.entry(route.path.to_string()).or_insert_with(|| serde_json::json!({}));
+        item[route.method.to_lowercase()] = operation;
+    }
+    serde_json::json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Regen local API",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "Drive Regen from scripts and launchers. Send the token from Settings as `Authorization: Bearer <token>`.",
+        },
+        "servers": [{ "url": format!("http://127.0.0.1:{}", port) }],
+        "security": [{ "bearer": [] }],
+        "paths": paths,
+        "components": {
+            "securitySchemes": { "bearer": { "type": "http", "scheme": "bearer" } },
+            "responses": {
+                "Error": {
+                    "description": "Failure",
+                    "content": { "application/json": { "schema": {
+                        "type": "object",
+                        "properties": { "error": { "type": "string" } },
+                        "required": ["error"],
+                    } } },
+                },
+            },
+        },
+    })
+}
+
+fn start(app: &tauri::AppHandle, port: u16) -> Result<(), ApiServerError> {
+    // Bind up front so a taken port is reported to config::apply's caller rather than lost in the task
+    let listener = std::net::TcpListener::bind(("127.0.0.1", port)).map_err(|e| ApiServerError::Bind(port, e.to_string()))?;
+    listener.set_nonblocking(true).map_err(|e| ApiServerError::Bind(port, e.to_string()))?;
+    token(app, false)?;
+
+    let (tx, mut rx) = oneshot::channel();
+    with_server(|s| {
+        s.stop = Some(tx);
+        s.port = Some(port);
+        s.error = None;
+    });
+    tracing::info!(target: "services", "API server: Listening on http://127.0.0.1:{}", port);
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let listener = match tokio::net::TcpListener::from_std(listener) {
+            Ok(listener) => listener,
+            Err(e) => {
+                tracing::warn!(target: "services", "API server: {}", e);
+                with_server(|s| {
+                    s.stop = None;
+                    s.error = Some(e.to_string());
+                });
+                return;
+            }
+        };
+        loop {
+            tokio::select! {
+                _ = &mut rx => break,
+                accepted = listener.accept() => {
+                    let Ok((stream, _)) = accepted else {
+                        continue;
+                    };
+                    let app = app.clone();
+                    tokio::spawn(async move {
+                        let service = hyper::service::service_fn(move |request| handle(app.clone(), request));
+                        let io = hyper_util::rt::TokioIo::new(stream);
+                        if let Err(e) = hyper::server::conn::http1::Builder::new().serve_connection(io, service).await {
+                            tracing::debug!(target: "services", "API server: Connection error: {}", e);
+                        }
+                    });
+                }
+            }
+        }
+    });
+    Ok(())
+}
+
+async fn handle(app: tauri::AppHandle, request: Request<Incoming>) -> Result<Response<Body>, Infallible> {
+    let method = request.method().clone();
+    let path = request.uri().path().trim_end_matches('/').to_string();
+    let response = match check_host(&request) {
+        Err(e) => Err(e),
+        Ok(()) if path == MCP_PATH || path.starts_with("/mcp/") => mcp_route(&app, request, &path).await,
+        Ok(()) => route(&app, request, &path).await.map(|value| respond(StatusCode::OK, &value)),
+    };
+    let response = response.unwrap_or_else(|(status, message)| respond(status, &serde_json::json!({ "error": message })));
+    tracing::debug!(target: "services", "API server: {} {} -> {}", method, path, response.status());
+    Ok(response)
+}
+
+// A Host other than loopback means a DNS-rebound page is talking to us
+fn check_host(request: &Request<Incoming>) -> Result<(), (StatusCode, String)> {
+    let host = request.headers().get(hyper::header::HOST).and_then(|h| h.to_str().ok()).unwrap_or_default();
+    let hostname = host.rsplit_once(':').map_or(host, |(name, _)| name);
+    if !matches!(hostname, "127.0.0.1" | "localhost" | "[::1]") {
+        return Err((StatusCode::FORBIDDEN, format!("Host '{}' is not allowed", host)));
+    }
+    Ok(())
+}
+
+async fn route(app: &tauri::AppHandle, request: Request<Incoming>, path: &str) -> Result<serde_json::Value, (StatusCode, String)> {
+    if request.method() == Method::GET && path == OPENAPI_PATH {
+        let port = with_server(|s| s.port).unwrap_or_else(|| crate::config::current().api.port);
+        return Ok(openapi(port));
+    }
+    authorize(app, &request)?;
+
+    let mut matched_path = false;
+    let mut found = None;
+    for route in ROUTES {
+        if let Some(captures) = match_path(route.path, path) {
+            matched_path = true;
+            if request.method().as_str() == route.method {
+                found = Some((route, captures));
+                break;
+            }
+        }
+    }
+    let (route, captures) = match found {
+        Some(found) => found,
+        None if matched_path => return Err((StatusCode::METHOD_NOT_ALLOWED, format!("{} is not supported on {}", request.method(), path))),
+        None => return Err((StatusCode::NOT_FOUND, format!("No route for {}", path))),
+    };
+    let spec = command_spec(route.command).ok_or_else(|| (StatusCode::NOT_FOUND, format!("{} is not available", route.command)))?;
+
+    // Arguments: query string, then JSON body, then path segments, each overriding the one before
+    let mut args = serde_json::Map::new();
+    for (name, value) in url::form_urlencoded::parse(request.uri().query().unwrap_or_default().as_bytes()) {
+        if let Some(param) = spec.params.iter().find(|p| p.name == name) {
+            args.insert(param.name.to_string(), coerce(&value, param.rust_type));
+        }
+    }
+    if takes_body(route.method) {
+        match read_json(request).await? {
+            Some(serde_json::Value::Object(map)) => args.extend(map),
+            Some(_) => return Err((StatusCode::BAD_REQUEST, "Body must be a JSON object".to_string())),
+            None => {}
+        }
+    }
+    for (name, value) in captures {
+        if let Some(param) = spec.params.iter().find(|p| p.name == name) {
+            args.insert(name, coerce(&value, param.rust_type));
+        }
+    }
+    // Streaming commands tag their events with a request id the caller has no use for here
+    if spec.params.iter().any(|p| p.name == "requestId") && !args.contains_key("requestId") {
+        args.insert("requestId".to_string(), serde_json::json!(format!("api-{}", uuid::Uuid::new_v4())));
+    }
+
+    palette::invoke(app, route.command, serde_json::Value::Object(args)).await.map_err(|e| match e {
+        palette::PaletteError::Failed(message) if message.starts_with("Missing argument") || message.starts_with("Invalid argument") => {
+            (StatusCode::BAD_REQUEST, message)
+        }
+        palette::PaletteError::InvalidArgs(message) => (StatusCode::BAD_REQUEST, message),
+        e => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    })
+}
+
+// JSON-RPC over POST /mcp, or the SSE transport: GET /mcp/sse streams replies to POST /mcp/messages?sessionId=
+async fn mcp_route(app: &tauri::AppHandle, request: Request<Incoming>, path: &str) -> Result<Response<Body>, (StatusCode, String)> {
+    if !crate::config::current().mcp.enabled {
+        return Err((StatusCode::NOT_FOUND, "The MCP server is off (mcp.enabled)".to_string()));
+    }
+    authorize(app, &request)?;
+    let method = request.method().clone();
+    match (method.as_str(), path) {
+        ("POST", MCP_PATH) => {
+            let reply = match read_json(request).await {
+                Ok(Some(message)) => crate::mcp::handle(app, message).await,
+                Ok(None) => Some(crate::mcp::parse_error("Empty body")),
+                Err((StatusCode::BAD_REQUEST, message)) => Some(crate::mcp::parse_error(&message)),
+                Err(e) => return Err(e),
+            };
+            Ok(match reply {
+                Some(reply) => respond(StatusCode::OK, &reply),
+                None => empty(StatusCode::ACCEPTED),
+            })
+        }
+        ("GET", MCP_SSE_PATH) => {
+            let (session, receiver) = crate::mcp::open_session()
+                .ok_or_else(|| (StatusCode::TOO_MANY_REQUESTS, "Too many open MCP sessions".to_string()))?;
+            // The client learns where to post from the first event; replies follow as "message" events
+            let endpoint = futures::stream::once(std::future::ready(sse_event("endpoint", &format!("{}?sessionId={}", MCP_MESSAGES_PATH, session))));
+            let replies = futures::stream::unfold(receiver, |mut receiver| async move {
+                let reply = receiver.recv().await?;
+                Some((sse_event("message", &reply.to_string()), receiver))
+            });
+            let mut response = Response::new(http_body_util::StreamBody::new(futures::StreamExt::chain(endpoint, replies)).boxed_unsync());
+            let headers = response.headers_mut();
+            headers.insert(hyper::header::CONTENT_TYPE, hyper::header::HeaderValue::from_static("text/event-stream"));
+            headers.insert(hyper::header::CACHE_CONTROL, hyper::header::HeaderValue::from_static("no-cache"));
+            Ok(response)
+        }
+        ("POST", MCP_MESSAGES_PATH) => {
+            let session = url::form_urlencoded::parse(request.uri().query().unwrap_or_default().as_bytes())
+                .find(|(name, _)| name == "sessionId")
+                .map(|(_, value)| value.into_owned())
+                .ok_or_else(|| (StatusCode::BAD_REQUEST, "sessionId is required".to_string()))?;
+            let message = read_json(request).await?.ok_or_else(|| (StatusCode::BAD_REQUEST, "Empty body".to_string()))?;
+            crate::mcp::post(app, &session, message).map_err(|e| (StatusCode::NOT_FOUND, e.to_string()))?;
+            Ok(empty(StatusCode::ACCEPTED))
+        }
+        (_, MCP_PATH | MCP_SSE_PATH | MCP_MESSAGES_PATH) => Err((StatusCode::METHOD_NOT_ALLOWED, format!("{} is not supported on {}", method, path))),
+        _ => Err((StatusCode::NOT_FOUND, format!("No route for {}", path))),
+    }
+}
+
+fn sse_event(event: &str, data: &str) -> Result<Frame<Bytes>, Infallible> {
+    Ok(Frame::data(Bytes::from(format!("event: {}\ndata: {}\n\n", event, data))))
+}
+
+// The body as JSON, capped at MAX_BODY_BYTES; None when empty
+async fn read_json(request: Request<Incoming>) -> Result<Option<serde_json::Value>, (StatusCode, String)> {
+    let body = http_body_util::Limited::new(request.into_body(), MAX_BODY_BYTES)
+        .collect()
+        .await
+        .map_err(|e| (StatusCode::PAYLOAD_TOO_LARGE, e.to_string()))?
+        .to_bytes();
+    if body.is_empty() {
+        return Ok(None);
+    }
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid JSON body: {}", e)))
+}
+
+fn authorize(app: &tauri::AppHandle, request: &Request<Incoming>) -> Result<(), (StatusCode, String)> {
+    let unauthorized = || (StatusCode::UNAUTHORIZED, "Missing or wrong token (Authorization: Bearer <token>)".to_string());
+    let presented = request
+        .headers()
+        .get(hyper::header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .ok_or_else(unauthorized)?;
+    let expected = app
+        .try_state::<SecureStore>()
+        .and_then(|store| store.get(TOKEN_KEY))
+        .ok_or_else(unauthorized)?;
+    // Compare every byte so timing doesn't reveal how much of a guess was right
+    let same = presented.len() == expected.len()
+        && presented.bytes().zip(expected.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0;
+    if same {
+        Ok(())
+    } else {
+        Err(unauthorized())
+    }
+}
+
+fn respond(status: StatusCode, value: &serde_json::Value) -> Response<Body> {
+    let mut response = Response::new(http_body_util::Full::new(Bytes::from(value.to_string())).boxed_unsync());
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(hyper::header::CONTENT_TYPE, hyper::header::HeaderValue::from_static("application/json"));
+    response
+}
+
+fn empty(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(http_body_util::Empty::new().boxed_unsync());
+    *response.status_mut() = status;
+    response
+}
+
+fn command_spec(id: &str) -> Option<&'static CommandSpec> {
+    crate::commands::REGISTERED_COMMANDS.iter().find(|spec| spec.id == id)
+}
+
+fn takes_body(method: &str) -> bool {
+    matches!(method, "POST" | "PATCH" | "PUT")
+}
+
+fn path_params(pattern: &str) -> Vec<&str> {
+    pattern
+        .split('/')
+        .filter_map(|segment| segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')))
+        .collect()
+}
+
+// (name, value) for each `{name}` segment when `path` fits `pattern`
+fn match_path(pattern: &str, path: &str) -> Option<Vec<(String, String)>> {
+    let pattern: Vec<&str> = pattern.split('/').collect();
+    let path: Vec<&str> = path.split('/').collect();
+    if pattern.len() != path.len() {
+        return None;
+    }
+    let mut captures = Vec::new();
+    for (expected, actual) in pattern.iter().zip(&path) {
+        match expected.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+            Some(name) if !actual.is_empty() => {
+                let value = url::form_urlencoded::parse(format!("v={}", actual).as_bytes())
+                    .next()
+                    .map(|(_, v)| v.into_owned())
+                    .unwrap_or_default();
+                captures.push((name.to_string(), value));
+            }
+            Some(_) => return None,
+            None if expected == actual => {}
+            None => return None,
+        }
+    }
+    Some(captures)
+}
+
+// Query strings and path segments are text; give the command the JSON type its parameter expects
+fn coerce(value: &str, rust_type: &str) -> serde_json::Value {
+    let (schema, _) = palette::type_schema(rust_type);
+    let parsed = match schema.get("type").and_then(|t| t.as_str()) {
+        Some("integer") => value.parse::<i64>().ok().map(serde_json::Value::from),
+        Some("number") => value.parse::<f64>().ok().map(serde_json::Value::from),
+        Some("boolean") => value.parse::<bool>().ok().map(serde_json::Value::from),
+        Some("array") => Some(serde_json::Value::from(
+            value.split(',').map(str::trim).filter(|v| !v.is_empty()).collect::<Vec<_>>(),
+        )),
+        _ => None,
+    };
+    parsed.unwrap_or_else(|| serde_json::Value::String(value.to_string()))
+}
+
+#[derive(Debug, Clone)]
+pub enum ApiServerError {
+    Bind(u16, String),
+    StoreUnavailable,
+    Store(String),
+}
+
+impl std::fmt::Display for ApiServerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiServerError::Bind(port, e) => write!(f, "Could not listen on 127.0.0.1:{}: {}", port, e),
+            ApiServerError::StoreUnavailable => write!(f, "Secure storage is not available for the API token"),
+            ApiServerError::Store(e) => write!(f, "Failed to save the API token: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ApiServerError {}
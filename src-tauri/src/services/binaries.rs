@@ -0,0 +1,199 @@
+// Bundled Binaries - Per-OS discovery and preparation of sidecar executables
+// Looks in the app's bin/ directories before PATH; marks bundled files executable on Unix
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum BinarySource {
+    Bundled,
+    Path,
+    Missing,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocatedBinary {
+    pub path: PathBuf,
+    pub source: BinarySource,
+    pub executable: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct BinaryCapability {
+    pub service: String,
+    pub binary: String,
+    pub available: bool,
+    pub source: BinarySource,
+    pub path: Option<String>,
+    pub executable: bool,
+    pub candidates: Vec<String>,       // File names searched for, in order
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct CapabilityReport {
+    pub os: String,
+    pub arch: String,
+    pub target: String,
+    pub search_dirs: Vec<String>,
+    pub binaries: Vec<BinaryCapability>,
+}
+
+// Upstream release asset names, so a downloaded binary can be dropped into bin/ unrenamed
+// (binary, os, arch, file name without extension)
+const RELEASE_NAMES: &[(&str, &str, &str, &str)] = &[
+    ("meilisearch", "linux", "x86_64", "meilisearch-linux-amd64"),
+    ("meilisearch", "linux", "aarch64", "meilisearch-linux-aarch64"),
+    ("meilisearch", "macos", "x86_64", "meilisearch-macos-amd64"),
+    ("meilisearch", "macos", "aarch64", "meilisearch-macos-apple-silicon"),
+    ("meilisearch", "windows", "x86_64", "meilisearch-windows-amd64"),
+];
+
+// Rust target triple for the running platform (Tauri names sidecars `<name>-<triple>`)
+pub fn target_triple() -> String {
+    let arch = std::env::consts::ARCH;
+    match std::env::consts::OS {
+        "windows" => format!("{}-pc-windows-msvc", arch),
+        "macos" => format!("{}-apple-darwin", arch),
+        "linux" => format!("{}-unknown-linux-gnu", arch),
+        os => format!("{}-unknown-{}", arch, os),
+    }
+}
+
+// bin/ next to the bundled resources, next to the executable, and in the working directory
+pub fn search_dirs(resource_dir: Option<&Path>) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(resource_dir) = resource_dir {
+        dirs.push(resource_dir.join("bin"));
+    }
+    if let Some(exe_dir) = std::env::current_exe().ok().and_then(|p| p.parent().map(Path::to_path_buf)) {
+        dirs.push(exe_dir.join("bin"));
+        // Tauri places externalBin sidecars beside the main executable
+        dirs.push(exe_dir);
+    }
+    dirs.push(PathBuf::from("bin"));
+
+    let mut unique: Vec<PathBuf> = Vec::new();
+    for dir in dirs {
+        let dir = dir.canonicalize().unwrap_or(dir);
+        if !unique.contains(&dir) {
+            unique.push(dir);
+        }
+    }
+    unique
+}
+
+// File names to look for, most specific first
+pub fn candidate_names(binary: &str) -> Vec<String> {
+    let os = std::env::consts::OS;
+    let arch = std::env::consts::ARCH;
+    let mut stems = vec![binary.to_string(), format!("{}-{}", binary, target_triple())];
+    stems.extend(
+        RELEASE_NAMES
+            .iter()
+            .filter(|(b, o, a, _)| *b == binary && *o == os && *a == arch)
+            .map(|(_, _, _, name)| name.to_string()),
+    );
+
+    let mut names: Vec<String> = stems
+        .iter()
+        .map(|stem| format!("{}{}", stem, std::env::consts::EXE_SUFFIX))
+        .collect();
+    if cfg!(windows) {
+        // npm-installed tools (n8n) ship as .cmd shims
+        names.push(format!("{}.cmd", binary));
+    }
+    names
+}
+
+// Bundled copies win over PATH so the app runs the version it was tested with
+pub fn locate(binary: &str, dirs: &[PathBuf]) -> Option<LocatedBinary> {
+    let names = candidate_names(binary);
+    for dir in dirs {
+        for name in &names {
+            let path = dir.join(name);
+            if path.is_file() {
+                let executable = prepare(&path);
+                return Some(LocatedBinary { path, source: BinarySource::Bundled, executable });
+            }
+        }
+    }
+
+    let path = which::which(binary).ok()?;
+    let executable = is_executable(&path);
+    Some(LocatedBinary { path, source: BinarySource::Path, executable })
+}
+
+pub fn report(binaries: &[(String, String)], dirs: &[PathBuf]) -> CapabilityReport {
+    CapabilityReport {
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        target: target_triple(),
+        search_dirs: dirs.iter().map(|d| d.to_string_lossy().to_string()).collect(),
+        binaries: binaries
+            .iter()
+            .map(|(service, binary)| {
+                let located = locate(binary, dirs);
+                BinaryCapability {
+                    service: service.clone(),
+                    binary: binary.clone(),
+                    available: located.as_ref().map(|l| l.executable).unwrap_or(false),
+                    source: located.as_ref().map(|l| l.source).unwrap_or(BinarySource::Missing),
+                    path: located.as_ref().map(|l| l.path.to_string_lossy().to_string()),
+                    executable: located.as_ref().map(|l| l.executable).unwrap_or(false),
+                    candidates: candidate_names(binary),
+                }
+            })
+            .collect(),
+    }
+}
+
+// Make a bundled binary runnable (installers and zip extraction often drop the exec bit)
+#[cfg(unix)]
+fn prepare(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    if let Ok(metadata) = std::fs::metadata(path) {
+        let mode = metadata.permissions().mode();
+        if mode & 0o111 == 0 {
+            let mut permissions = metadata.permissions();
+            permissions.set_mode(mode | 0o755);
+            if let Err(e) = std::fs::set_permissions(path, permissions) {
+                tracing::warn!(target: "services", "Failed to chmod {}: {}", path.display(), e);
+            }
+        }
+    }
+    #[cfg(target_os = "macos")]
+    {
+        // Downloaded binaries are quarantined by Gatekeeper and refuse to launch
+        let _ = std::process::Command::new("xattr")
+            .args(["-d", "com.apple.quarantine"])
+            .arg(path)
+            .output();
+    }
+    is_executable(path)
+}
+
+#[cfg(not(unix))]
+fn prepare(path: &Path) -> bool {
+    is_executable(path)
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path)
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
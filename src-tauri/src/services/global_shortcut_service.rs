@@ -1,61 +1,242 @@
-// Global Shortcut Service - Ctrl+Space app wake functionality
+// Global Shortcut Service - Registry of OS-wide shortcuts dispatched to named actions
+// Bindings are defaults overlaid by the [shortcuts] table in config.toml (an empty accelerator unbinds)
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
 use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+use crate::privacy::PrivacyEnforcer;
+use crate::state::PrivacyMode;
+
+/// Actions a global shortcut can trigger
+pub const ACTIONS: &[&str] = &["app-wake", "wispr-wake", "new-tab", "screenshot", "toggle-privacy-mode"];
+
+/// Bound out of the box; everything else waits for the user, since global keys are taken from every app
+const DEFAULT_BINDINGS: &[(&str, &str)] = &[("app-wake", "CommandOrControl+Space")];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutBinding {
+    pub action: String,
+    pub accelerator: Option<String>,
+    pub default_accelerator: Option<String>,
+    pub registered: bool,
+    pub error: Option<String>,         // Why the OS refused it (usually taken by another app)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutTriggered {
+    pub action: String,
+    pub accelerator: String,
+}
 
 pub struct GlobalShortcutService {
-    shortcuts: Vec<String>,
+    bindings: Vec<ShortcutBinding>,
+    actions_by_id: HashMap<u32, (String, Shortcut)>,
 }
 
 impl GlobalShortcutService {
     pub fn new() -> Self {
         GlobalShortcutService {
-            shortcuts: vec![],
+            bindings: Vec::new(),
+            actions_by_id: HashMap::new(),
         }
     }
 
-    /// Register Ctrl+Space (Cmd+Space on macOS) to bring app to foreground
-    pub fn register_app_wake(&mut self) -> Result<(), String> {
-        let shortcut = if cfg!(target_os = "macos") {
-            "cmd+space"
-        } else {
-            "ctrl+space"
-        };
-
-        self.shortcuts.push(shortcut.to_string());
-        println!("[GlobalShortcut] Registered shortcut: {}", shortcut);
-        Ok(())
+    /// Get the current bindings, one per action
+    pub fn list_shortcuts(&self) -> Vec<ShortcutBinding> {
+        self.bindings.clone()
     }
 
-    /// Get list of registered shortcuts
-    pub fn list_shortcuts(&self) -> Vec<String> {
-        self.shortcuts.clone()
+    /// Unregister all shortcuts
+    pub fn unregister_all(&mut self, app: &tauri::AppHandle) {
+        for (_, shortcut) in self.actions_by_id.values() {
+            let _ = app.global_shortcut().unregister(*shortcut);
+        }
+        self.actions_by_id.clear();
+        for binding in &mut self.bindings {
+            binding.registered = false;
+        }
     }
+}
 
-    /// Unregister all shortcuts
-    pub fn unregister_all(&mut self) -> Result<(), String> {
-        self.shortcuts.clear();
-        println!("[GlobalShortcut] All shortcuts unregistered");
-        Ok(())
+impl Default for GlobalShortcutService {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 /// Global shortcut service instance
 static GLOBAL_SHORTCUT_SERVICE: Mutex<Option<GlobalShortcutService>> = Mutex::new(None);
 
-/// Initialize global shortcut service
-pub fn initialize_global_shortcuts() -> Result<(), String> {
-    let mut service = GlobalShortcutService::new();
-    service.register_app_wake()?;
-    
-    let mut global = GLOBAL_SHORTCUT_SERVICE
-        .lock()
-        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
-    *global = Some(service);
-    
+/// Default accelerator for an action, if it has one
+pub fn default_accelerator(action: &str) -> Option<&'static str> {
+    DEFAULT_BINDINGS.iter().find(|(a, _)| *a == action).map(|(_, accel)| *accel)
+}
+
+/// Action -> accelerator after applying config overrides
+pub fn effective_bindings(overrides: &BTreeMap<String, String>) -> BTreeMap<String, String> {
+    ACTIONS
+        .iter()
+        .filter_map(|action| {
+            let accelerator = match overrides.get(*action) {
+                Some(accel) => accel.trim().to_string(),
+                None => default_accelerator(action)?.to_string(),
+            };
+            (!accelerator.is_empty()).then(|| (action.to_string(), accelerator))
+        })
+        .collect()
+}
+
+/// Check a [shortcuts] table: known actions, parseable accelerators, no two actions on one key combo.
+/// Errors carry the offending action.
+pub fn validate(overrides: &BTreeMap<String, String>) -> Result<(), (String, String)> {
+    if let Some(action) = overrides.keys().find(|a| !ACTIONS.contains(&a.as_str())) {
+        return Err((action.clone(), format!("unknown action (expected one of {})", ACTIONS.join(", "))));
+    }
+    let mut seen: HashMap<u32, String> = HashMap::new();
+    for (action, accelerator) in effective_bindings(overrides) {
+        let shortcut = Shortcut::from_str(&accelerator)
+            .map_err(|e| (action.clone(), format!("invalid accelerator '{}': {}", accelerator, e)))?;
+        if let Some(other) = seen.insert(shortcut.id(), action.clone()) {
+            return Err((action, format!("'{}' is already bound to {}", accelerator, other)));
+        }
+    }
     Ok(())
 }
 
-/// Trigger app wake (called when global shortcut is pressed)
-pub fn on_shortcut_triggered() -> Result<(), String> {
-    println!("[GlobalShortcut] App wake triggered!");
+/// Initialize global shortcut service from the loaded config
+pub fn initialize_global_shortcuts(app: &tauri::AppHandle) -> Result<(), String> {
+    apply(app, &crate::config::current().shortcuts);
     Ok(())
 }
+
+/// (Re)register every binding; called at startup and whenever the config changes
+pub fn apply(app: &tauri::AppHandle, overrides: &BTreeMap<String, String>) {
+    let effective = effective_bindings(overrides);
+    let mut global = GLOBAL_SHORTCUT_SERVICE.lock().unwrap();
+    // Config changes elsewhere shouldn't re-register unchanged shortcuts
+    if let Some(service) = global.as_ref() {
+        let current: BTreeMap<String, String> = service
+            .bindings
+            .iter()
+            .filter_map(|b| b.accelerator.clone().map(|a| (b.action.clone(), a)))
+            .collect();
+        if current == effective {
+            return;
+        }
+    }
+    let service = global.get_or_insert_with(GlobalShortcutService::new);
+    service.unregister_all(app);
+
+    service.bindings = ACTIONS
+        .iter()
+        .map(|action| {
+            let accelerator = effective.get(*action).cloned();
+            let mut binding = ShortcutBinding {
+                action: action.to_string(),
+                accelerator: accelerator.clone(),
+                default_accelerator: default_accelerator(action).map(str::to_string),
+                registered: false,
+                error: None,
+            };
+            let Some(accelerator) = accelerator else {
+                return binding;
+            };
+            let result = Shortcut::from_str(&accelerator)
+                .map_err(|e| e.to_string())
+                .and_then(|shortcut| app.global_shortcut().register(shortcut).map(|_| shortcut).map_err(|e| e.to_string()));
+            match result {
+                Ok(shortcut) => {
+                    service.actions_by_id.insert(shortcut.id(), (action.to_string(), shortcut));
+                    binding.registered = true;
+                    tracing::info!(target: "app", "Global shortcut: Registered {} for {}", accelerator, action);
+                }
+                Err(e) => {
+                    tracing::warn!(target: "app", "Global shortcut: Could not register {} for {}: {}", accelerator, action, e);
+                    binding.error = Some(e);
+                }
+            }
+            binding
+        })
+        .collect();
+}
+
+/// Current bindings, including ones the OS refused
+pub fn list() -> Vec<ShortcutBinding> {
+    GLOBAL_SHORTCUT_SERVICE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|s| s.list_shortcuts())
+        .unwrap_or_default()
+}
+
+/// Plugin handler: dispatch a pressed shortcut to its action
+pub fn on_shortcut_event(app: &tauri::AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed {
+        return;
+    }
+    let action = GLOBAL_SHORTCUT_SERVICE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|s| s.actions_by_id.get(&shortcut.id()).map(|(action, _)| action.clone()));
+    let Some(action) = action else {
+        return;
+    };
+    let accelerator = list()
+        .into_iter()
+        .find(|b| b.action == action)
+        .and_then(|b| b.accelerator)
+        .unwrap_or_default();
+    trigger(app, &action, &accelerator);
+}
+
+/// Run an action and announce it on "shortcut-action"; `accelerator` names the source for non-keyboard triggers
+pub fn trigger(app: &tauri::AppHandle, action: &str, accelerator: &str) {
+    if let Err(e) = on_shortcut_triggered(app, action) {
+        tracing::warn!(target: "app", "Global shortcut: {} failed: {}", action, e);
+    }
+    let _ = app.emit(
+        "shortcut-action",
+        ShortcutTriggered { action: action.to_string(), accelerator: accelerator.to_string() },
+    );
+}
+
+/// Run a named action (called when its global shortcut is pressed)
+pub fn on_shortcut_triggered(app: &tauri::AppHandle, action: &str) -> Result<(), String> {
+    tracing::info!(target: "app", "Global shortcut: {} triggered", action);
+    match action {
+        // The frontend opens the tab, starts listening, or captures on "shortcut-action"; they all need the window up
+        "app-wake" | "wispr-wake" | "new-tab" | "screenshot" => bring_to_front(app),
+        "toggle-privacy-mode" => toggle_privacy_mode(app),
+        _ => Err(format!("Unknown shortcut action '{}'", action)),
+    }
+}
+
+fn bring_to_front(app: &tauri::AppHandle) -> Result<(), String> {
+    let window = app
+        .get_webview_window("main")
+        .or_else(|| app.webview_windows().into_values().next())
+        .ok_or("No window to show")?;
+    let _ = window.unminimize();
+    window.show().map_err(|e| e.to_string())?;
+    window.set_focus().map_err(|e| e.to_string())
+}
+
+/// Normal <-> Private; Ghost is left alone, since it is only entered deliberately
+fn toggle_privacy_mode(app: &tauri::AppHandle) -> Result<(), String> {
+    let enforcer = app.try_state::<Mutex<PrivacyEnforcer>>().ok_or("Privacy enforcer not ready")?;
+    let next = match enforcer.lock().unwrap().get_policy().mode {
+        PrivacyMode::Normal => PrivacyMode::Private,
+        PrivacyMode::Private => PrivacyMode::Normal,
+        PrivacyMode::Ghost => return Err("Ghost mode can't be toggled by shortcut".to_string()),
+    };
+    crate::privacy::apply_privacy_mode(app, next).map(|_| ())
+}
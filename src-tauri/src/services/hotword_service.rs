@@ -0,0 +1,357 @@
+// Hotword Service - Optional always-on "Hey Regen" detector that fires wispr-wake without the keyboard
+// Runs whisper.cpp's streaming recognizer (whisper-stream) at low priority and fuzzy-matches what it hears.
+// Private and Ghost modes are a hard mute: the listening process is killed, not just ignored.
+use serde::{Deserialize, Serialize};
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::oneshot;
+
+use crate::config::VoiceConfig;
+use crate::privacy::PrivacyEnforcer;
+use crate::services::binaries;
+use crate::services::global_shortcut_service;
+use crate::state::PrivacyMode;
+
+pub const WAKE_PHRASE: &str = "hey regen";
+const WAKE_ACTION: &str = "wispr-wake";
+// Reported as the accelerator in "shortcut-action" so the frontend can tell voice from keyboard wakes
+const WAKE_SOURCE: &str = "hotword";
+// How whisper tends to spell "Regen"
+const ALIASES: &[&str] = &["hey reagan", "hey regan", "hey region", "hey rejen", "hey raygen", "hey ray gen"];
+const STREAM_BINARIES: &[&str] = &["whisper-stream", "whisper-cpp-stream", "stream"];
+// The stream re-transcribes a sliding window, so one utterance is heard several times
+const COOLDOWN: Duration = Duration::from_secs(5);
+const STREAM_THREADS: &str = "2";
+const STEP_MS: &str = "1500";
+const WINDOW_MS: &str = "3000";
+#[cfg(windows)]
+const BELOW_NORMAL_PRIORITY_CLASS: u32 = 0x0000_4000;
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct HotwordStatus {
+    pub enabled: bool,                 // config.voice.hotword
+    pub running: bool,
+    pub muted: bool,                   // Private/Ghost mode; enabled but not listening
+    pub sensitivity: f64,
+    pub phrase: String,
+    pub binary: Option<String>,
+    pub model: Option<String>,
+    pub last_wake: Option<i64>,        // Unix seconds
+    pub error: Option<String>,         // Why the detector is not running
+}
+
+#[derive(Default)]
+struct Detector {
+    stop: Option<oneshot::Sender<()>>,
+    generation: u64,                   // Bumped per start so a stale reader can't clear a newer detector
+    pid: Option<u32>,
+    muted: bool,
+    binary: Option<String>,
+    model: Option<String>,
+    last_wake: Option<Instant>,
+    last_wake_at: Option<i64>,
+    error: Option<String>,
+}
+
+static DETECTOR: Mutex<Option<Detector>> = Mutex::new(None);
+
+fn with_detector<T>(f: impl FnOnce(&mut Detector) -> T) -> T {
+    let mut guard = DETECTOR.lock().unwrap();
+    f(guard.get_or_insert_with(Detector::default))
+}
+
+/// Start or stop the detector to match config (called from config::apply)
+pub fn apply(app: &tauri::AppHandle, voice: &VoiceConfig) {
+    let muted = current_mode(app).is_some_and(|mode| !matches!(mode, PrivacyMode::Normal));
+    with_detector(|d| d.muted = muted);
+    if voice.hotword && !muted {
+        if let Err(e) = start(app) {
+            tracing::warn!(target: "app", "Hotword: {}", e);
+        }
+    } else {
+        stop();
+    }
+}
+
+/// Hard mute in Private/Ghost; resume in Normal if enabled
+pub fn set_privacy_mode(app: &tauri::AppHandle, mode: &PrivacyMode) {
+    let muted = !matches!(mode, PrivacyMode::Normal);
+    with_detector(|d| d.muted = muted);
+    if muted {
+        stop();
+    } else {
+        apply(app, &crate::config::current().voice);
+    }
+}
+
+pub fn status() -> HotwordStatus {
+    let voice = crate::config::current().voice;
+    with_detector(|d| HotwordStatus {
+        enabled: voice.hotword,
+        running: d.stop.is_some(),
+        muted: d.muted,
+        sensitivity: voice.hotword_sensitivity,
+        phrase: WAKE_PHRASE.to_string(),
+        binary: d.binary.clone(),
+        model: d.model.clone(),
+        last_wake: d.last_wake_at,
+        error: d.error.clone(),
+    })
+}
+
+/// Persist voice.hotword and start/stop accordingly; enabling while muted is refused
+pub fn set_enabled(app: &tauri::AppHandle, enabled: bool) -> Result<HotwordStatus, HotwordError> {
+    if enabled && with_detector(|d| d.muted) {
+        return Err(HotwordError::Muted);
+    }
+    let config = crate::config::store()
+        .set("voice.hotword", serde_json::Value::Bool(enabled))
+        .map_err(|e| HotwordError::Config(e.to_string()))?;
+    apply(app, &config.voice);
+    let status = status();
+    match (&status.error, enabled && !status.running) {
+        (Some(e), true) => Err(HotwordError::Unavailable(e.clone())),
+        _ => Ok(status),
+    }
+}
+
+/// Persist voice.hotwordSensitivity; the running detector picks it up on its next line
+pub fn set_sensitivity(sensitivity: f64) -> Result<HotwordStatus, HotwordError> {
+    if !(0.0..=1.0).contains(&sensitivity) {
+        return Err(HotwordError::InvalidSensitivity(sensitivity));
+    }
+    crate::config::store()
+        .set("voice.hotwordSensitivity", serde_json::json!(sensitivity))
+        .map_err(|e| HotwordError::Config(e.to_string()))?;
+    Ok(status())
+}
+
+fn current_mode(app: &tauri::AppHandle) -> Option<PrivacyMode> {
+    let enforcer = app.try_state::<Mutex<PrivacyEnforcer>>()?;
+    let mode = enforcer.lock().unwrap().get_policy().mode.clone();
+    Some(mode)
+}
+
+fn start(app: &tauri::AppHandle) -> Result<(), HotwordError> {
+    if with_detector(|d| d.stop.is_some()) {
+        return Ok(());
+    }
+    let dirs = binaries::search_dirs(app.path().resource_dir().ok().as_deref());
+    let located = STREAM_BINARIES.iter().find_map(|name| binaries::locate(name, &dirs).filter(|b| b.executable));
+    let model = crate::wispr::whisper_model(app).filter(|m| m.is_file());
+    let (binary, model) = match (located, model) {
+        (Some(binary), Some(model)) => (binary.path, model),
+        (None, _) => return Err(record_error(HotwordError::ToolMissing)),
+        (_, None) => return Err(record_error(HotwordError::ModelMissing)),
+    };
+
+    let mut command = Command::new(&binary);
+    command
+        .arg("-m")
+        .arg(&model)
+        .args(["-t", STREAM_THREADS, "--step", STEP_MS, "--length", WINDOW_MS, "--keep", "0", "-l", "en"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true);
+    #[cfg(windows)]
+    command.creation_flags(BELOW_NORMAL_PRIORITY_CLASS | CREATE_NO_WINDOW);
+    let mut child = command.spawn().map_err(|e| record_error(HotwordError::Spawn(e.to_string())))?;
+    // Lowest scheduling priority, so listening never competes with the browser
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        unsafe {
+            libc::setpriority(libc::PRIO_PROCESS, pid as libc::id_t, 19);
+        }
+    }
+    let Some(stdout) = child.stdout.take() else {
+        return Err(record_error(HotwordError::Spawn("no output stream".to_string())));
+    };
+
+    let (tx, mut rx) = oneshot::channel();
+    let generation = with_detector(|d| {
+        d.generation += 1;
+        d.stop = Some(tx);
+        d.pid = child.id();
+        d.binary = Some(binary.display().to_string());
+        d.model = Some(model.display().to_string());
+        d.error = None;
+        d.generation
+    });
+    tracing::info!(target: "app", "Hotword: Listening for \"{}\" ({})", WAKE_PHRASE, binary.display());
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut lines = BufReader::new(stdout).lines();
+        let exit = loop {
+            tokio::select! {
+                _ = &mut rx => break None,
+                line = lines.next_line() => match line {
+                    Ok(Some(line)) => on_line(&app, &line),
+                    Ok(None) => break Some("whisper-stream exited".to_string()),
+                    Err(e) => break Some(e.to_string()),
+                },
+            }
+        };
+        let _ = child.kill().await;
+        with_detector(|d| {
+            if d.generation == generation {
+                d.stop = None;
+                d.pid = None;
+                if let Some(e) = &exit {
+                    d.error = Some(e.clone());
+                }
+            }
+        });
+        match exit {
+            Some(e) => tracing::warn!(target: "app", "Hotword: Stopped: {}", e),
+            None => tracing::info!(target: "app", "Hotword: Stopped"),
+        }
+    });
+    Ok(())
+}
+
+fn stop() {
+    if let Some(stop) = with_detector(|d| d.stop.take()) {
+        let _ = stop.send(());
+    }
+}
+
+/// App exit: the reader task may never run again, so signal the listener directly
+pub fn shutdown() {
+    let pid = with_detector(|d| {
+        d.stop = None;
+        d.pid.take()
+    });
+    #[cfg(unix)]
+    if let Some(pid) = pid {
+        unsafe {
+            libc::kill(pid as libc::pid_t, libc::SIGTERM);
+        }
+    }
+    // Elsewhere kill_on_drop ends it when the runtime drops the task
+    #[cfg(not(unix))]
+    let _ = pid;
+}
+
+fn record_error(error: HotwordError) -> HotwordError {
+    with_detector(|d| d.error = Some(error.to_string()));
+    error
+}
+
+fn on_line(app: &tauri::AppHandle, line: &str) {
+    let sensitivity = crate::config::current().voice.hotword_sensitivity;
+    if !is_wake(line, sensitivity) {
+        return;
+    }
+    let fire = with_detector(|d| {
+        // A mute that raced this line wins
+        if d.muted || d.last_wake.is_some_and(|at| at.elapsed() < COOLDOWN) {
+            return false;
+        }
+        d.last_wake = Some(Instant::now());
+        d.last_wake_at = Some(chrono::Utc::now().timestamp());
+        true
+    });
+    if !fire {
+        return;
+    }
+    global_shortcut_service::trigger(app, WAKE_ACTION, WAKE_SOURCE);
+}
+
+// whisper-stream redraws its line with ANSI escapes and marks silence as [BLANK_AUDIO], (wind), ...
+fn normalize(line: &str) -> String {
+    let mut text = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    let mut depth = 0usize;
+    while let Some(c) = chars.next() {
+        match c {
+            '\u{1b}' => {
+                // CSI sequence: ESC [ params final-letter
+                if chars.peek() == Some(&'[') {
+                    chars.next();
+                    for c in chars.by_ref() {
+                        if c.is_ascii_alphabetic() {
+                            break;
+                        }
+                    }
+                }
+            }
+            '[' | '(' => depth += 1,
+            ']' | ')' => depth = depth.saturating_sub(1),
+            _ if depth > 0 => {}
+            c if c.is_alphanumeric() => text.extend(c.to_lowercase()),
+            _ => text.push(' '),
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// Sensitivity 0 needs the phrase (or a known mishearing) verbatim; 1 accepts 70% similarity
+fn is_wake(line: &str, sensitivity: f64) -> bool {
+    let text = normalize(line);
+    if text.is_empty() {
+        return false;
+    }
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let threshold = 1.0 - 0.3 * sensitivity.clamp(0.0, 1.0);
+    std::iter::once(WAKE_PHRASE).chain(ALIASES.iter().copied()).any(|phrase| {
+        let n = phrase.split_whitespace().count();
+        words.windows(n).any(|window| similarity(&window.join(" "), phrase) >= threshold)
+    })
+}
+
+fn similarity(a: &str, b: &str) -> f64 {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    // Levenshtein distance, one row at a time
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == cb { diagonal } else { 1 + diagonal.min(above).min(row[j]) };
+            diagonal = above;
+        }
+    }
+    1.0 - row[b.len()] as f64 / longest as f64
+}
+
+#[derive(Debug, Clone)]
+pub enum HotwordError {
+    Muted,
+    InvalidSensitivity(f64),
+    ToolMissing,
+    ModelMissing,
+    Spawn(String),
+    Unavailable(String),
+    Config(String),
+}
+
+impl std::fmt::Display for HotwordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HotwordError::Muted => write!(f, "The hotword is muted in Private and Ghost modes"),
+            HotwordError::InvalidSensitivity(value) => write!(f, "Sensitivity must be between 0 and 1 (got {})", value),
+            HotwordError::ToolMissing => write!(f, "whisper.cpp streaming binary (whisper-stream) not found"),
+            HotwordError::ModelMissing => write!(f, "No whisper model found; set voice.whisperModel or add a ggml-*.bin to the models directory"),
+            HotwordError::Spawn(e) => write!(f, "Failed to start the hotword detector: {}", e),
+            HotwordError::Unavailable(e) => write!(f, "Hotword detector is not running: {}", e),
+            HotwordError::Config(e) => write!(f, "Config error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for HotwordError {}
@@ -4,6 +4,9 @@ use std::process::{Command, Child};
 use std::time::Duration;
 use std::thread;
 
+use super::binaries;
+use super::supervisor::ServiceSpec;
+
 pub struct OllamaService {
     process: Option<Child>,
 }
@@ -15,56 +18,33 @@ impl OllamaService {
 
     /// Start Ollama service with CORS enabled and resource optimizations
     pub fn start(&mut self) -> Result<(), String> {
-        // Allow CORS access
+        // Allow CORS access; export the tuning too so `ollama` CLI calls agree with the server
         env::set_var("OLLAMA_ORIGINS", "*");
-        env::set_var("OLLAMA_HOST", "127.0.0.1:11434");
-        
-        // Resource optimizations for low-spec devices
-        // Limit loaded models to prevent RAM explosion
-        env::set_var("OLLAMA_MAX_LOADED_MODELS", "2");
-        // Limit parallel requests to prevent CPU overload
-        env::set_var("OLLAMA_NUM_PARALLEL", "4");
-        // Auto-unload models after 5 minutes idle
-        env::set_var("OLLAMA_KEEP_ALIVE", "5m");
-        // Use flash attention for efficiency
-        env::set_var("OLLAMA_FLASH_ATTENTION", "1");
-
-        let ollama_path = if cfg!(windows) {
-            // Try bundled ollama.exe first, then system PATH
-            std::path::PathBuf::from("./bin/ollama.exe")
-                .canonicalize()
-                .or_else(|_| {
-                    // Fallback to system PATH
-                    which::which("ollama").map_err(|e| format!("{:?}", e))
-                })
-                .map_err(|e| format!("Ollama not found: {:?}", e))?
-        } else {
-            which::which("ollama").map_err(|e| format!("Ollama not found: {:?}", e))?
-        };
+        for (key, value) in ollama_env() {
+            env::set_var(key, value);
+        }
+
+        // Bundled binary (any OS) first, then system PATH
+        let ollama_path = binaries::locate("ollama", &binaries::search_dirs(None))
+            .filter(|b| b.executable)
+            .map(|b| b.path)
+            .ok_or_else(|| "Ollama not found".to_string())?;
 
         // Check if Ollama already running
         if self.check_running() {
-            println!("[OllamaService] Ollama already running");
+            tracing::info!(target: "services", "Ollama already running");
             return Ok(());
         }
 
-        // Get CPU core count for optimal thread allocation
-        let num_threads = num_cpus::get();
-        let optimal_threads = std::cmp::max(2, (num_threads as f64 * 0.75) as usize);
-        
         // Start Ollama process with optimized environment variables
         let child = Command::new(&ollama_path)
+            .arg("serve")
             .env("OLLAMA_ORIGINS", "*")
-            .env("OLLAMA_HOST", "127.0.0.1:11434")
-            .env("OLLAMA_MAX_LOADED_MODELS", "2")
-            .env("OLLAMA_NUM_PARALLEL", "4")
-            .env("OLLAMA_NUM_THREAD", optimal_threads.to_string())
-            .env("OLLAMA_KEEP_ALIVE", "5m")
-            .env("OLLAMA_FLASH_ATTENTION", "1")
+            .envs(ollama_env())
             .spawn()
             .map_err(|e| format!("Failed to start Ollama: {}", e))?;
 
-        println!("[OllamaService] Started Ollama process");
+        tracing::info!(target: "services", "Started Ollama process");
         self.process = Some(child);
 
         // Wait for Ollama to be ready
@@ -80,7 +60,7 @@ impl OllamaService {
     fn check_running(&self) -> bool {
         match std::net::TcpStream::connect("127.0.0.1:11434") {
             Ok(_) => {
-                println!("[OllamaService] Ollama is running");
+                tracing::debug!(target: "services", "Ollama is running");
                 true
             }
             Err(_) => false,
@@ -91,7 +71,7 @@ impl OllamaService {
     fn wait_ready(&self) -> Result<(), String> {
         for i in 0..60 {
             if self.check_running() {
-                println!("[OllamaService] Ollama ready after {} seconds", i);
+                tracing::info!(target: "services", "Ollama ready after {} seconds", i);
                 return Ok(());
             }
             thread::sleep(Duration::from_millis(500));
@@ -101,22 +81,23 @@ impl OllamaService {
 
     /// Pull required models
     fn pull_models(&self) -> Result<(), String> {
-        let models = vec!["phi3:mini", "llava:7b"];
+        let ai = crate::config::current().ai;
+        let models = vec![ai.chat_model(), ai.embed_model(), "llava:7b".to_string()];
 
-        for model in models {
-            println!("[OllamaService] Pulling model: {}", model);
+        for model in &models {
+            tracing::info!(target: "services", "Pulling model: {}", model);
 
             let output = Command::new("ollama")
-                .args(&["pull", model])
+                .args(&["pull", model.as_str()])
                 .output()
                 .map_err(|e| format!("Failed to pull {}: {}", model, e))?;
 
             if !output.status.success() {
                 let stderr = String::from_utf8_lossy(&output.stderr);
-                println!("[OllamaService] Failed to pull {}: {}", model, stderr);
+                tracing::warn!(target: "services", "Failed to pull {}: {}", model, stderr);
                 // Don't fail, just warn
             } else {
-                println!("[OllamaService] Successfully pulled {}", model);
+                tracing::info!(target: "services", "Successfully pulled {}", model);
             }
         }
 
@@ -127,12 +108,49 @@ impl OllamaService {
     pub fn stop(&mut self) -> Result<(), String> {
         if let Some(mut process) = self.process.take() {
             process.kill().map_err(|e| format!("Failed to kill Ollama: {}", e))?;
-            println!("[OllamaService] Stopped Ollama");
+            tracing::info!(target: "services", "Stopped Ollama");
         }
         Ok(())
     }
 }
 
+// Environment shared by OllamaService and the supervisor
+fn ollama_env() -> Vec<(String, String)> {
+    // Sized to this machine (threads from physical cores, parallelism from the hardware tier)
+    let defaults = &crate::hardware::capabilities().models;
+
+    [
+        ("OLLAMA_HOST", "127.0.0.1:11434".to_string()),
+        // Limit loaded models to prevent RAM explosion
+        ("OLLAMA_MAX_LOADED_MODELS", defaults.max_loaded_models.to_string()),
+        // Limit parallel requests to prevent CPU overload
+        ("OLLAMA_NUM_PARALLEL", defaults.num_parallel.to_string()),
+        ("OLLAMA_NUM_THREAD", defaults.threads.to_string()),
+        // Auto-unload models after 5 minutes idle
+        ("OLLAMA_KEEP_ALIVE", "5m".to_string()),
+        // Use flash attention for efficiency
+        ("OLLAMA_FLASH_ATTENTION", "1".to_string()),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_string(), v))
+    .collect()
+}
+
+// Supervised `ollama serve`
+pub fn service_spec() -> ServiceSpec {
+    let mut env = ollama_env();
+    env.push(("OLLAMA_ORIGINS".to_string(), "*".to_string()));
+    ServiceSpec {
+        name: "ollama".to_string(),
+        binary: "ollama".to_string(),
+        args: vec!["serve".to_string()],
+        env,
+        port: 11434,
+        health_path: "/api/version".to_string(),
+        autostart: true,
+    }
+}
+
 impl Drop for OllamaService {
     fn drop(&mut self) {
         let _ = self.stop();
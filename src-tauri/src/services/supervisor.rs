@@ -0,0 +1,478 @@
+// Service Supervisor - Lifecycle for local sidecar services (Ollama, Meilisearch, n8n)
+// Tracks child processes, health-checks them, restarts crashes with backoff, and stops them on exit
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::net::{SocketAddr, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::binaries::{self, BinarySource, CapabilityReport, LocatedBinary};
+use super::ollama_service;
+
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(2);
+// A freshly spawned service gets this long to answer its first health check
+const STARTUP_GRACE: Duration = Duration::from_secs(60);
+// Failed checks in a row before a running child is considered hung and restarted
+const MAX_FAILED_CHECKS: u32 = 3;
+const BACKOFF_BASE: Duration = Duration::from_secs(2);
+const BACKOFF_MAX: Duration = Duration::from_secs(300);
+// Restarts allowed without a stable run before the service is marked failed
+const MAX_RESTARTS: u32 = 8;
+// Uptime after which the restart counter resets
+const STABLE_AFTER: Duration = Duration::from_secs(600);
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone)]
+pub struct ServiceSpec {
+    pub name: String,
+    pub binary: String,                // Executable name without extension
+    pub args: Vec<String>,
+    pub env: Vec<(String, String)>,
+    pub port: u16,
+    pub health_path: String,           // Any 2xx response counts as healthy
+    pub autostart: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum ServiceState {
+    Stopped,
+    Starting,
+    Healthy,
+    Unhealthy,
+    External,                          // Running, but not started (or owned) by us
+    Backoff,                           // Exited; waiting to be restarted
+    Failed,                            // Restart budget exhausted
+    Paused,                            // Stopped on purpose (e.g. memory pressure); not restarted
+    Unavailable,                       // Binary not found
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceStatus {
+    pub name: String,
+    pub state: ServiceState,
+    pub pid: Option<u32>,
+    pub port: u16,
+    pub binary_path: Option<String>,
+    pub binary_source: BinarySource,
+    pub restarts: u32,
+    pub last_error: Option<String>,
+    pub last_check: Option<i64>,
+    pub started_at: Option<i64>,
+    pub next_restart_in_secs: Option<u64>,
+}
+
+struct Service {
+    spec: ServiceSpec,
+    binary: Option<LocatedBinary>,
+    child: Option<Child>,
+    state: ServiceState,
+    restarts: u32,
+    failed_checks: u32,
+    started: Option<Instant>,
+    started_at: Option<i64>,
+    restart_at: Option<Instant>,
+    last_error: Option<String>,
+    last_check: Option<i64>,
+}
+
+#[derive(Clone)]
+pub struct ServiceSupervisor {
+    services: Arc<Mutex<BTreeMap<String, Service>>>,
+    bin_dirs: Arc<Vec<PathBuf>>,
+    shutting_down: Arc<AtomicBool>,
+    http: reqwest::Client,
+}
+
+// Ollama, plus Meilisearch and n8n when their binaries are present
+pub fn default_specs(data_dir: &Path) -> Vec<ServiceSpec> {
+    let meili_dir = data_dir.join("meilisearch");
+    let n8n_dir = data_dir.join("n8n");
+    vec![
+        ollama_service::service_spec(),
+        ServiceSpec {
+            name: "meilisearch".to_string(),
+            binary: "meilisearch".to_string(),
+            args: vec![
+                "--http-addr".to_string(),
+                "127.0.0.1:7700".to_string(),
+                "--db-path".to_string(),
+                meili_dir.join("data.ms").to_string_lossy().to_string(),
+                "--dump-dir".to_string(),
+                meili_dir.join("dumps").to_string_lossy().to_string(),
+                "--no-analytics".to_string(),
+            ],
+            env: vec![],
+            port: 7700,
+            health_path: "/health".to_string(),
+            autostart: true,
+        },
+        ServiceSpec {
+            name: "n8n".to_string(),
+            binary: "n8n".to_string(),
+            args: vec!["start".to_string()],
+            env: vec![
+                ("N8N_PORT".to_string(), "5678".to_string()),
+                ("N8N_LISTEN_ADDRESS".to_string(), "127.0.0.1".to_string()),
+                ("N8N_USER_FOLDER".to_string(), n8n_dir.to_string_lossy().to_string()),
+                ("N8N_DIAGNOSTICS_ENABLED".to_string(), "false".to_string()),
+            ],
+            port: 5678,
+            health_path: "/healthz".to_string(),
+            autostart: true,
+        },
+    ]
+}
+
+fn port_open(port: u16) -> bool {
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    TcpStream::connect_timeout(&addr, Duration::from_millis(300)).is_ok()
+}
+
+impl ServiceSupervisor {
+    // `bin_dirs` are searched for bundled binaries before PATH (see binaries::search_dirs)
+    pub fn new(specs: Vec<ServiceSpec>, bin_dirs: Vec<PathBuf>) -> Self {
+        let services = specs
+            .into_iter()
+            .map(|spec| {
+                let binary = binaries::locate(&spec.binary, &bin_dirs).filter(|b| b.executable);
+                let service = Service {
+                    state: if binary.is_some() { ServiceState::Stopped } else { ServiceState::Unavailable },
+                    binary,
+                    spec,
+                    child: None,
+                    restarts: 0,
+                    failed_checks: 0,
+                    started: None,
+                    started_at: None,
+                    restart_at: None,
+                    last_error: None,
+                    last_check: None,
+                };
+                (service.spec.name.clone(), service)
+            })
+            .collect();
+
+        // Health probes bypass crate::http: they must see every failure, not a tripped circuit
+        let http = reqwest::Client::builder()
+            .timeout(HEALTH_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            services: Arc::new(Mutex::new(services)),
+            bin_dirs: Arc::new(bin_dirs),
+            shutting_down: Arc::new(AtomicBool::new(false)),
+            http,
+        }
+    }
+
+    // Start every autostart service (already-running instances are adopted as external)
+    pub fn start_all(&self) {
+        let mut services = self.services.lock().unwrap();
+        for service in services.values_mut().filter(|s| s.spec.autostart) {
+            if let Err(e) = service.start() {
+                tracing::warn!(target: "services", "{}: {}", service.spec.name, e);
+            }
+        }
+    }
+
+    // Periodic health checks and restarts on the Tauri runtime
+    pub fn spawn_monitor(&self, check_interval: Duration) {
+        let supervisor = self.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(check_interval);
+            loop {
+                interval.tick().await;
+                if supervisor.shutting_down.load(Ordering::Relaxed) {
+                    break;
+                }
+                supervisor.check_all().await;
+            }
+        });
+    }
+
+    pub async fn check_all(&self) {
+        // Reap exited children and fire due restarts, then probe without holding the lock
+        let probes: Vec<(String, String)> = {
+            let mut services = self.services.lock().unwrap();
+            services
+                .values_mut()
+                .filter_map(|service| {
+                    service.reap();
+                    if service.state == ServiceState::Backoff
+                        && service.restart_at.map(|at| Instant::now() >= at).unwrap_or(false)
+                    {
+                        if let Err(e) = service.start() {
+                            tracing::warn!(target: "services", "{}: restart failed: {}", service.spec.name, e);
+                            service.schedule_restart(e);
+                        }
+                    }
+                    matches!(
+                        service.state,
+                        ServiceState::Starting | ServiceState::Healthy | ServiceState::Unhealthy | ServiceState::External
+                    )
+                    .then(|| (service.spec.name.clone(), service.health_url()))
+                })
+                .collect()
+        };
+
+        let results = futures::future::join_all(probes.into_iter().map(|(name, url)| {
+            let http = self.http.clone();
+            async move {
+                let healthy = matches!(http.get(&url).send().await, Ok(r) if r.status().is_success());
+                (name, healthy)
+            }
+        }))
+        .await;
+
+        let mut services = self.services.lock().unwrap();
+        for (name, healthy) in results {
+            if let Some(service) = services.get_mut(&name) {
+                service.record_check(healthy);
+            }
+        }
+    }
+
+    pub fn status(&self) -> Vec<ServiceStatus> {
+        self.services.lock().unwrap().values().map(Service::status).collect()
+    }
+
+    // Which service binaries this platform can run, and where they were found
+    pub fn capabilities(&self) -> CapabilityReport {
+        let binaries: Vec<(String, String)> = self
+            .services
+            .lock()
+            .unwrap()
+            .values()
+            .map(|s| (s.spec.name.clone(), s.spec.binary.clone()))
+            .collect();
+        binaries::report(&binaries, &self.bin_dirs)
+    }
+
+    // Stop (if ours) and start again with a fresh restart budget
+    pub fn restart(&self, name: &str) -> Result<ServiceStatus, String> {
+        let mut services = self.services.lock().unwrap();
+        let service = services
+            .get_mut(name)
+            .ok_or_else(|| format!("Unknown service: {}", name))?;
+
+        if service.child.is_none() && port_open(service.spec.port) {
+            return Err(format!("{} is running outside the app and cannot be restarted from here", name));
+        }
+        if let Some(mut child) = service.child.take() {
+            terminate(&mut [&mut child]);
+        }
+        service.binary = binaries::locate(&service.spec.binary, &self.bin_dirs).filter(|b| b.executable);
+        service.restarts = 0;
+        service.restart_at = None;
+        service.last_error = None;
+        service.start()?;
+        Ok(service.status())
+    }
+
+    // Stop an owned service without scheduling a restart
+    pub fn pause(&self, name: &str) -> Result<(), String> {
+        let mut services = self.services.lock().unwrap();
+        let service = services
+            .get_mut(name)
+            .ok_or_else(|| format!("Unknown service: {}", name))?;
+        let Some(mut child) = service.child.take() else {
+            return Err(format!("{} is not running under the app", name));
+        };
+        terminate(&mut [&mut child]);
+        service.state = ServiceState::Paused;
+        service.started = None;
+        tracing::info!(target: "services", "Paused {}", name);
+        Ok(())
+    }
+
+    pub fn resume(&self, name: &str) -> Result<(), String> {
+        let mut services = self.services.lock().unwrap();
+        let service = services
+            .get_mut(name)
+            .ok_or_else(|| format!("Unknown service: {}", name))?;
+        if service.state != ServiceState::Paused {
+            return Ok(());
+        }
+        service.start()
+    }
+
+    // (name, pid) of running children we own
+    pub fn pids(&self) -> Vec<(String, u32)> {
+        self.services
+            .lock()
+            .unwrap()
+            .values()
+            .filter_map(|s| s.child.as_ref().map(|c| (s.spec.name.clone(), c.id())))
+            .collect()
+    }
+
+    // Stop all owned children (SIGTERM, then kill after a grace period); called on app exit
+    pub fn shutdown(&self) {
+        self.shutting_down.store(true, Ordering::Relaxed);
+        let mut services = self.services.lock().unwrap();
+        let mut children: Vec<Child> = services
+            .values_mut()
+            .filter_map(|service| {
+                service.state = ServiceState::Stopped;
+                service.child.take()
+            })
+            .collect();
+        if !children.is_empty() {
+            tracing::info!(target: "services", "Stopping {} service(s)", children.len());
+            terminate(&mut children.iter_mut().collect::<Vec<_>>());
+        }
+    }
+}
+
+impl Service {
+    fn start(&mut self) -> Result<(), String> {
+        if port_open(self.spec.port) {
+            self.state = ServiceState::External;
+            return Ok(());
+        }
+        let Some(path) = self.binary.as_ref().map(|b| b.path.clone()) else {
+            self.state = ServiceState::Unavailable;
+            return Err(format!("{} binary not found", self.spec.binary));
+        };
+
+        let child = Command::new(&path)
+            .args(&self.spec.args)
+            .envs(self.spec.env.iter().map(|(k, v)| (k, v)))
+            .stdin(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Failed to start {}: {}", self.spec.name, e))?;
+
+        tracing::info!(target: "services", "Started {} (pid {})", self.spec.name, child.id());
+        self.child = Some(child);
+        self.state = ServiceState::Starting;
+        self.failed_checks = 0;
+        self.started = Some(Instant::now());
+        self.started_at = Some(chrono::Utc::now().timestamp());
+        self.restart_at = None;
+        Ok(())
+    }
+
+    // Notice a child that exited on its own
+    fn reap(&mut self) {
+        let Some(child) = self.child.as_mut() else { return };
+        if let Ok(Some(exit)) = child.try_wait() {
+            self.child = None;
+            tracing::warn!(target: "services", "{} exited ({})", self.spec.name, exit);
+            self.schedule_restart(format!("Exited with {}", exit));
+        }
+    }
+
+    fn schedule_restart(&mut self, reason: String) {
+        self.last_error = Some(reason);
+        self.started = None;
+        if self.restarts >= MAX_RESTARTS {
+            self.state = ServiceState::Failed;
+            self.restart_at = None;
+            tracing::error!(target: "services", "{} failed {} times; giving up", self.spec.name, self.restarts);
+            return;
+        }
+        let delay = BACKOFF_BASE
+            .saturating_mul(1 << self.restarts.min(16))
+            .min(BACKOFF_MAX);
+        self.restarts += 1;
+        self.restart_at = Some(Instant::now() + delay);
+        self.state = ServiceState::Backoff;
+    }
+
+    fn record_check(&mut self, healthy: bool) {
+        self.last_check = Some(chrono::Utc::now().timestamp());
+        let owned = self.child.is_some();
+
+        if healthy {
+            self.failed_checks = 0;
+            self.state = if owned { ServiceState::Healthy } else { ServiceState::External };
+            if self.started.map(|s| s.elapsed() >= STABLE_AFTER).unwrap_or(false) {
+                self.restarts = 0;
+            }
+            return;
+        }
+
+        if !owned {
+            // An external instance went away; take over if we're meant to run it
+            self.state = ServiceState::Stopped;
+            if self.spec.autostart && self.binary.is_some() {
+                self.schedule_restart("External instance stopped responding".to_string());
+            }
+            return;
+        }
+        if self.state == ServiceState::Starting
+            && self.started.map(|s| s.elapsed() < STARTUP_GRACE).unwrap_or(false)
+        {
+            return;
+        }
+
+        self.failed_checks += 1;
+        self.state = ServiceState::Unhealthy;
+        if self.failed_checks >= MAX_FAILED_CHECKS {
+            tracing::warn!(target: "services", "{} unresponsive; restarting", self.spec.name);
+            if let Some(mut child) = self.child.take() {
+                terminate(&mut [&mut child]);
+            }
+            self.schedule_restart(format!("Failed {} health checks", self.failed_checks));
+        }
+    }
+
+    fn health_url(&self) -> String {
+        format!("http://127.0.0.1:{}{}", self.spec.port, self.spec.health_path)
+    }
+
+    fn status(&self) -> ServiceStatus {
+        ServiceStatus {
+            name: self.spec.name.clone(),
+            state: self.state,
+            pid: self.child.as_ref().map(Child::id),
+            port: self.spec.port,
+            binary_path: self.binary.as_ref().map(|b| b.path.to_string_lossy().to_string()),
+            binary_source: self.binary.as_ref().map(|b| b.source).unwrap_or(BinarySource::Missing),
+            restarts: self.restarts,
+            last_error: self.last_error.clone(),
+            last_check: self.last_check,
+            started_at: self.started_at,
+            next_restart_in_secs: self
+                .restart_at
+                .map(|at| at.saturating_duration_since(Instant::now()).as_secs()),
+        }
+    }
+}
+
+// Ask children to exit, wait out the grace period, then kill stragglers
+fn terminate(children: &mut [&mut Child]) {
+    #[cfg(unix)]
+    for child in children.iter() {
+        unsafe {
+            libc::kill(child.id() as libc::pid_t, libc::SIGTERM);
+        }
+    }
+    #[cfg(unix)]
+    {
+        let deadline = Instant::now() + SHUTDOWN_GRACE;
+        while Instant::now() < deadline
+            && children.iter_mut().any(|c| matches!(c.try_wait(), Ok(None)))
+        {
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = SHUTDOWN_GRACE;
+
+    for child in children.iter_mut() {
+        if matches!(child.try_wait(), Ok(None)) {
+            let _ = child.kill();
+        }
+        let _ = child.wait();
+    }
+}
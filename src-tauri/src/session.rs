@@ -0,0 +1,113 @@
+// Session Checkpoints - Versioned, named snapshots of the tab set
+// The `sessions` table keeps only the live session; checkpoints are append-only with retention
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::browser::Tab;
+use crate::db::Database;
+
+// Oldest unnamed checkpoints are pruned past this count
+pub const MAX_CHECKPOINTS: usize = 50;
+// Named checkpoints are kept longer, but not forever
+pub const MAX_NAMED_CHECKPOINTS: usize = 200;
+// Holds the session that safe mode skipped restoring
+pub const SAFE_MODE_CHECKPOINT: &str = "Before safe mode";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionCheckpoint {
+    pub id: String,
+    pub name: Option<String>,
+    pub active_tab_id: Option<String>,
+    pub tabs: Vec<Tab>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct CheckpointSummary {
+    pub id: String,
+    pub name: Option<String>,
+    pub tab_count: usize,
+    pub created_at: i64,
+    pub diff: Option<CheckpointDiff>,  // Against the previous (older) checkpoint
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct CheckpointDiff {
+    pub tab_count_delta: i64,
+    pub added_urls: Vec<String>,
+    pub removed_urls: Vec<String>,
+}
+
+impl SessionCheckpoint {
+    pub fn new(name: Option<String>, active_tab_id: Option<String>, tabs: Vec<Tab>) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()),
+            active_tab_id,
+            tabs,
+            created_at: chrono::Utc::now().timestamp(),
+        }
+    }
+
+    pub fn summary(&self, previous: Option<&SessionCheckpoint>) -> CheckpointSummary {
+        CheckpointSummary {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            tab_count: self.tabs.len(),
+            created_at: self.created_at,
+            diff: previous.map(|prev| diff(prev, self)),
+        }
+    }
+}
+
+// What changed going from `before` to `after`
+pub fn diff(before: &SessionCheckpoint, after: &SessionCheckpoint) -> CheckpointDiff {
+    let before_urls: HashSet<&str> = before.tabs.iter().map(|t| t.url.as_str()).collect();
+    let after_urls: HashSet<&str> = after.tabs.iter().map(|t| t.url.as_str()).collect();
+
+    let mut added_urls: Vec<String> = after_urls
+        .difference(&before_urls)
+        .map(|u| u.to_string())
+        .collect();
+    let mut removed_urls: Vec<String> = before_urls
+        .difference(&after_urls)
+        .map(|u| u.to_string())
+        .collect();
+    added_urls.sort();
+    removed_urls.sort();
+
+    CheckpointDiff {
+        tab_count_delta: after.tabs.len() as i64 - before.tabs.len() as i64,
+        added_urls,
+        removed_urls,
+    }
+}
+
+// Safe mode starts from a blank tab; keep the saved session as a named checkpoint instead
+pub fn park_for_safe_mode(db: &Database) -> Result<Option<SessionCheckpoint>, String> {
+    let Some((active_id, tabs_json, _)) = db.load_session().map_err(|e| e.to_string())? else {
+        return Ok(None);
+    };
+    let tabs: Vec<Tab> = serde_json::from_str(&tabs_json).map_err(|e| e.to_string())?;
+    // Nothing worth keeping (e.g. the blank tab of an earlier safe-mode launch)
+    if tabs.iter().all(|t| t.url == "about:blank") {
+        return Ok(None);
+    }
+    let checkpoint = SessionCheckpoint::new(Some(SAFE_MODE_CHECKPOINT.to_string()), active_id, tabs);
+    db.save_checkpoint(&checkpoint).map_err(|e| e.to_string())?;
+    Ok(Some(checkpoint))
+}
+
+// Most recent session parked by safe mode
+pub fn parked_session(db: &Database) -> Result<Option<SessionCheckpoint>, String> {
+    let checkpoints = db.list_checkpoints(MAX_NAMED_CHECKPOINTS).map_err(|e| e.to_string())?;
+    Ok(checkpoints
+        .into_iter()
+        .find(|c| c.name.as_deref() == Some(SAFE_MODE_CHECKPOINT)))
+}
@@ -0,0 +1,248 @@
+// Similarity - Flags note passages that closely copy cached source pages, for attribution
+// Word shingles catch near-verbatim copying; embeddings (when a real model is available) catch close paraphrase
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::db::{Database, PageCache};
+use crate::embeddings::{self, cosine};
+use crate::notes::Note;
+use crate::text;
+
+// Sentences shorter than this (in words) are too generic to call copied
+const MIN_SENTENCE_WORDS: usize = 6;
+// Words per shingle; five in a row rarely repeat by chance
+const SHINGLE_WORDS: usize = 5;
+// Share of a sentence's shingles found in one source window
+const VERBATIM_CONTAINMENT: f32 = 0.5;
+// Rescaled cosine (see grounding.rs) above which a sentence is a paraphrase of the window
+const PARAPHRASE_SIMILARITY: f32 = 0.85;
+const MAX_SOURCES: usize = 20;
+const MAX_SENTENCES: usize = 300;
+const MAX_WINDOWS: usize = 2_000;
+const SEARCH_TERMS: usize = 12;
+// Source text is compared in windows of this many sentences
+const CHUNK_SENTENCES: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum MatchKind {
+    Verbatim,
+    Paraphrase,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SimilarPassage {
+    pub sentence: String,
+    pub kind: MatchKind,
+    pub score: f32,                    // 0..1: shingle containment, or rescaled cosine for paraphrase
+    pub source_url: String,
+    pub source_title: String,
+    pub source_text: String,           // The matching window of the source
+    pub quoted: bool,                  // The sentence is in quotation marks or a "> " block
+    pub cited: bool,                   // The note links the source (or was taken on it)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SimilarityReport {
+    pub note_id: String,
+    pub sentences_checked: usize,
+    pub sources_checked: usize,
+    pub passages: Vec<SimilarPassage>,
+    pub unattributed: usize,           // Passages neither quoted nor cited
+    pub model: String,                 // Embedding model; empty when only shingles were compared
+}
+
+struct Window<'a> {
+    page: &'a PageCache,
+    text: String,
+}
+
+pub async fn check(db: &Database, note_id: &str) -> Result<SimilarityReport, SimilarityError> {
+    let note = db
+        .get_note(note_id)
+        .map_err(|e| SimilarityError::Storage(e.to_string()))?
+        .ok_or_else(|| SimilarityError::NotFound(note_id.to_string()))?;
+    let sentences: Vec<String> = text::split_sentences(&note.content)
+        .into_iter()
+        .filter(|s| words(s).len() >= MIN_SENTENCE_WORDS)
+        .take(MAX_SENTENCES)
+        .collect();
+    let pages = candidate_pages(db, &note)?;
+    let mut report = SimilarityReport {
+        note_id: note.id.clone(),
+        sentences_checked: sentences.len(),
+        sources_checked: pages.len(),
+        passages: Vec::new(),
+        unattributed: 0,
+        model: String::new(),
+    };
+    if sentences.is_empty() || pages.is_empty() {
+        return Ok(report);
+    }
+
+    let windows: Vec<Window> = pages
+        .iter()
+        .flat_map(|page| {
+            text::split_sentences(&page.content)
+                .chunks(CHUNK_SENTENCES)
+                .map(|chunk| Window { page, text: chunk.join(" ") })
+                .collect::<Vec<_>>()
+        })
+        .take(MAX_WINDOWS)
+        .collect();
+
+    // Inverted index: shingle -> windows containing it
+    let mut index: HashMap<String, Vec<usize>> = HashMap::new();
+    for (i, window) in windows.iter().enumerate() {
+        for shingle in shingles(&window.text) {
+            let entry = index.entry(shingle).or_default();
+            if entry.last() != Some(&i) {
+                entry.push(i);
+            }
+        }
+    }
+
+    let cited: HashSet<String> = note.referenced_urls().into_iter().collect();
+    let mut unmatched = Vec::new();
+    for sentence in sentences {
+        let own = shingles(&sentence);
+        let mut hits: HashMap<usize, usize> = HashMap::new();
+        for shingle in &own {
+            for &i in index.get(shingle).into_iter().flatten() {
+                *hits.entry(i).or_default() += 1;
+            }
+        }
+        let best = hits
+            .into_iter()
+            .map(|(i, count)| (i, count as f32 / own.len().max(1) as f32))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        match best {
+            Some((i, score)) if score >= VERBATIM_CONTAINMENT => {
+                report.passages.push(passage(sentence, MatchKind::Verbatim, score, &windows[i], &cited));
+            }
+            _ => unmatched.push(sentence),
+        }
+    }
+
+    if !unmatched.is_empty() {
+        let items: Vec<(String, String)> = unmatched
+            .iter()
+            .chain(windows.iter().map(|w| &w.text))
+            .map(|t| (embeddings::content_key(t), t.clone()))
+            .collect();
+        let embedded = embeddings::embed_cached(db, &items).await;
+        // Hashed vectors only measure word overlap, which the shingles already covered
+        if embedded.source != embeddings::EmbeddingSource::Hashed {
+            let (sentence_vectors, window_vectors) = embedded.vectors.split_at(unmatched.len());
+            for (sentence, vector) in unmatched.into_iter().zip(sentence_vectors) {
+                let best = window_vectors
+                    .iter()
+                    .enumerate()
+                    .map(|(i, w)| (i, ((cosine(vector, w) - 0.4) / 0.45).clamp(0.0, 1.0)))
+                    .max_by(|a, b| a.1.total_cmp(&b.1));
+                if let Some((i, score)) = best.filter(|(_, score)| *score >= PARAPHRASE_SIMILARITY) {
+                    report.passages.push(passage(sentence, MatchKind::Paraphrase, score, &windows[i], &cited));
+                }
+            }
+            report.model = embedded.model;
+        }
+    }
+
+    report.unattributed = report.passages.iter().filter(|p| !p.quoted && !p.cited).count();
+    tracing::info!(
+        target: "ai",
+        "Similarity: Note {} has {} similar passages ({} unattributed) across {} sources",
+        note.id,
+        report.passages.len(),
+        report.unattributed,
+        report.sources_checked
+    );
+    Ok(report)
+}
+
+// Cached copies of the pages the note references, then cached pages sharing its most distinctive words
+fn candidate_pages(db: &Database, note: &Note) -> Result<Vec<PageCache>, SimilarityError> {
+    let storage = |e: rusqlite::Error| SimilarityError::Storage(e.to_string());
+    let mut pages = Vec::new();
+    let mut seen = HashSet::new();
+    for url in note.url.iter().cloned().chain(note.referenced_urls()) {
+        if let Some(page) = db.get_page(&url).map_err(storage)? {
+            if seen.insert(page.url.clone()) {
+                pages.push(page);
+            }
+        }
+    }
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for word in text::content_words(&note.content).into_iter().filter(|w| w.chars().count() > 4) {
+        *counts.entry(word).or_default() += 1;
+    }
+    let mut terms: Vec<(String, usize)> = counts.into_iter().collect();
+    terms.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| b.0.chars().count().cmp(&a.0.chars().count())).then_with(|| a.0.cmp(&b.0)));
+    let query = terms
+        .into_iter()
+        .take(SEARCH_TERMS)
+        .map(|(word, _)| format!("\"{}\"", word))
+        .collect::<Vec<_>>()
+        .join(" OR ");
+    if !query.is_empty() && pages.len() < MAX_SOURCES {
+        for page in db.search_pages(&query, MAX_SOURCES).map_err(storage)? {
+            if pages.len() == MAX_SOURCES {
+                break;
+            }
+            if seen.insert(page.url.clone()) {
+                pages.push(page);
+            }
+        }
+    }
+    Ok(pages)
+}
+
+fn passage(sentence: String, kind: MatchKind, score: f32, window: &Window, cited: &HashSet<String>) -> SimilarPassage {
+    let trimmed = sentence.trim();
+    let quoted = trimmed.starts_with('>')
+        || (trimmed.starts_with(['"', '“', '\'']) && trimmed.trim_end_matches(['.', '!', '?']).ends_with(['"', '”', '\'']));
+    SimilarPassage {
+        quoted,
+        cited: crate::notes::normalize_url(&window.page.url).is_some_and(|url| cited.contains(&url)),
+        kind,
+        score,
+        source_url: window.page.url.clone(),
+        source_title: window.page.title.clone(),
+        source_text: window.text.clone(),
+        sentence,
+    }
+}
+
+// Lowercased words of any length; short words matter for verbatim matching
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).map(|w| w.to_lowercase()).collect()
+}
+
+fn shingles(text: &str) -> HashSet<String> {
+    let words = words(text);
+    words.windows(SHINGLE_WORDS).map(|w| w.join(" ")).collect()
+}
+
+#[derive(Debug, Clone)]
+pub enum SimilarityError {
+    NotFound(String),
+    Storage(String),
+}
+
+impl std::fmt::Display for SimilarityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SimilarityError::NotFound(id) => write!(f, "Note {} not found", id),
+            SimilarityError::Storage(msg) => write!(f, "Storage error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SimilarityError {}
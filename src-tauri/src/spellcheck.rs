@@ -0,0 +1,605 @@
+// Spellcheck - Offline spell-checking with Hunspell dictionaries (.aff/.dic)
+// Dictionaries come from the app data "dictionaries" folder, DICPATH, and the system's hunspell/myspell folders.
+// Affix rules are expanded into a word set when a dictionary is first used; compounding rules are not supported.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+const MAX_SUGGESTIONS: usize = 5;
+const MAX_TEXT_CHARS: usize = 200_000;
+// Caps the report for long notes full of names and jargon
+const MAX_MISSPELLINGS: usize = 500;
+// Preferred regional dictionaries, after an exact "<lang>.dic"
+const REGIONS: &[&str] = &["IN", "US", "GB"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct Misspelling {
+    pub start: usize,                  // Char offsets into the checked text
+    pub end: usize,
+    pub word: String,
+    pub suggestions: Vec<String>,      // Closest first; may be empty
+    pub language: String,              // Dictionary the word was checked against
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SpellReport {
+    pub language: String,
+    pub words_checked: usize,
+    pub misspellings: Vec<Misspelling>,
+    pub missing_dictionaries: Vec<String>, // Languages in the text with no installed dictionary
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct DictionaryInfo {
+    pub language: String,
+    pub path: String,                  // The .dic file
+    pub loaded: bool,
+    pub words: Option<usize>,          // Expanded word forms, once loaded
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SpellingFix {
+    pub start: usize,                  // Char offsets, as reported by spellcheck
+    pub end: usize,
+    pub word: String,                  // Must still be at [start, end) for the fix to apply
+    pub replacement: String,
+}
+
+pub struct SpellChecker {
+    dirs: Vec<PathBuf>,
+    // None caches "no dictionary installed" so each check doesn't rescan the folders
+    loaded: Mutex<HashMap<String, Option<Arc<Dictionary>>>>,
+}
+
+impl SpellChecker {
+    // `user_dir` is searched first so installed dictionaries override the system's
+    pub fn new(user_dir: Option<PathBuf>) -> Self {
+        let mut dirs: Vec<PathBuf> = user_dir.into_iter().collect();
+        if let Some(paths) = std::env::var_os("DICPATH") {
+            dirs.extend(std::env::split_paths(&paths));
+        }
+        #[cfg(target_os = "macos")]
+        {
+            if let Some(home) = std::env::var_os("HOME") {
+                dirs.push(PathBuf::from(home).join("Library/Spelling"));
+            }
+            dirs.push(PathBuf::from("/Library/Spelling"));
+        }
+        #[cfg(all(unix, not(target_os = "macos")))]
+        dirs.extend(["/usr/share/hunspell", "/usr/share/myspell", "/usr/share/myspell/dicts"].map(PathBuf::from));
+        Self { dirs, loaded: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn dictionaries(&self) -> Vec<DictionaryInfo> {
+        let loaded = self.loaded.lock().unwrap();
+        let mut found: Vec<DictionaryInfo> = Vec::new();
+        for dir in &self.dirs {
+            let Ok(entries) = std::fs::read_dir(dir) else { continue };
+            let mut paths: Vec<PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+            paths.sort();
+            for path in paths.into_iter().filter(|p| p.extension().is_some_and(|e| e == "dic") && p.with_extension("aff").is_file()) {
+                let Some(language) = path.file_stem().and_then(|s| s.to_str()).map(language_of) else { continue };
+                if found.iter().any(|d| d.path == path.to_string_lossy()) {
+                    continue;
+                }
+                let dictionary = loaded.get(&language).and_then(|d| d.as_ref()).filter(|d| d.path == path);
+                found.push(DictionaryInfo {
+                    language,
+                    path: path.to_string_lossy().to_string(),
+                    loaded: dictionary.is_some(),
+                    words: dictionary.map(|d| d.words.len()),
+                });
+            }
+        }
+        found
+    }
+
+    // Loads (and caches) the dictionary for a language; blocking, so call it off the async runtime
+    pub fn dictionary(&self, language: &str) -> Result<Option<Arc<Dictionary>>, SpellcheckError> {
+        if let Some(cached) = self.loaded.lock().unwrap().get(language) {
+            return Ok(cached.clone());
+        }
+        let dictionary = match self.find(language) {
+            Some(path) => {
+                let started = std::time::Instant::now();
+                let dictionary = Dictionary::load(&path)?;
+                tracing::info!(
+                    target: "app",
+                    "Spellcheck: Loaded {} ({} word forms) in {}ms",
+                    path.display(),
+                    dictionary.words.len(),
+                    started.elapsed().as_millis()
+                );
+                Some(Arc::new(dictionary))
+            }
+            None => None,
+        };
+        self.loaded.lock().unwrap().insert(language.to_string(), dictionary.clone());
+        Ok(dictionary)
+    }
+
+    // "<lang>.dic", then a preferred region ("hi_IN", "en_US"), then any "<lang>_*.dic", in folder order
+    fn find(&self, language: &str) -> Option<PathBuf> {
+        let usable = |path: &Path| path.is_file() && path.with_extension("aff").is_file();
+        for dir in &self.dirs {
+            let exact = dir.join(format!("{}.dic", language));
+            if usable(&exact) {
+                return Some(exact);
+            }
+            for region in REGIONS {
+                let regional = dir.join(format!("{}_{}.dic", language, region));
+                if usable(&regional) {
+                    return Some(regional);
+                }
+            }
+            let Ok(entries) = std::fs::read_dir(dir) else { continue };
+            let mut paths: Vec<PathBuf> = entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|e| e == "dic"))
+                .filter(|p| p.file_stem().and_then(|s| s.to_str()).is_some_and(|stem| language_of(stem) == language))
+                .collect();
+            paths.sort();
+            if let Some(path) = paths.into_iter().find(|p| usable(p)) {
+                return Some(path);
+            }
+        }
+        None
+    }
+
+    // Check `text` against `language` (detected when None). Latin-script words in an Indic text are checked
+    // against English, unless the text is romanized Hindi; words in other scripts are skipped.
+    pub fn check(&self, text: &str, language: Option<&str>) -> Result<SpellReport, SpellcheckError> {
+        if text.chars().count() > MAX_TEXT_CHARS {
+            return Err(SpellcheckError::Invalid(format!("text is longer than {} characters", MAX_TEXT_CHARS)));
+        }
+        let (language, romanized) = match language.map(|l| l.trim().to_lowercase()).filter(|l| !l.is_empty()) {
+            Some(language) => (language, false),
+            None => {
+                let detected = crate::language::detect_language(text);
+                (detected.language, detected.romanized)
+            }
+        };
+        let primary = self.dictionary(&language)?;
+        let english = match (language.as_str(), romanized) {
+            ("en", _) | (_, true) => None,
+            _ => self.dictionary("en")?,
+        };
+
+        let mut report = SpellReport { language: language.clone(), words_checked: 0, misspellings: Vec::new(), missing_dictionaries: Vec::new() };
+        if primary.is_none() {
+            report.missing_dictionaries.push(language.clone());
+        }
+        let mut suggested: HashMap<String, Vec<String>> = HashMap::new();
+        for (start, end, word) in words(text) {
+            let latin = word.chars().all(|c| c.is_ascii() || matches!(c, '\u{00C0}'..='\u{024F}' | '’'));
+            let dictionary = match (&primary, &english) {
+                (Some(primary), _) if latin == primary.latin => primary,
+                (_, Some(english)) if latin => english,
+                _ => continue,
+            };
+            report.words_checked += 1;
+            if dictionary.is_correct(&word) {
+                continue;
+            }
+            let suggestions = suggested.entry(word.clone()).or_insert_with(|| dictionary.suggest(&word)).clone();
+            report.misspellings.push(Misspelling { start, end, word, suggestions, language: dictionary.language.clone() });
+            if report.misspellings.len() == MAX_MISSPELLINGS {
+                break;
+            }
+        }
+        Ok(report)
+    }
+}
+
+// Apply fixes to `text`; a fix whose word is no longer at its range (the text changed since the check) is skipped.
+// Returns the corrected text and how many fixes were applied.
+pub fn apply_fixes(text: &str, fixes: &[SpellingFix]) -> (String, usize) {
+    let mut chars: Vec<char> = text.chars().collect();
+    let mut fixes: Vec<&SpellingFix> = fixes.iter().filter(|f| f.start < f.end && f.end <= chars.len()).collect();
+    // Back to front so earlier offsets stay valid; overlapping fixes keep the first
+    fixes.sort_by(|a, b| b.start.cmp(&a.start));
+    let mut applied = 0;
+    let mut floor = usize::MAX;
+    for fix in fixes {
+        if fix.end > floor || chars[fix.start..fix.end].iter().collect::<String>() != fix.word {
+            continue;
+        }
+        chars.splice(fix.start..fix.end, fix.replacement.chars());
+        floor = fix.start;
+        applied += 1;
+    }
+    (chars.into_iter().collect(), applied)
+}
+
+// Unambiguous fixes: misspellings with exactly one suggestion
+pub fn confident_fixes(report: &SpellReport) -> Vec<SpellingFix> {
+    report
+        .misspellings
+        .iter()
+        .filter(|m| m.suggestions.len() == 1)
+        .map(|m| SpellingFix { start: m.start, end: m.end, word: m.word.clone(), replacement: m.suggestions[0].clone() })
+        .collect()
+}
+
+// (start, end, word) in char offsets; URLs, emails, `code`, numbers, and all-caps acronyms are skipped
+fn words(text: &str) -> Vec<(usize, usize, String)> {
+    static SKIP_RE: OnceLock<Regex> = OnceLock::new();
+    let skip_re = SKIP_RE.get_or_init(|| Regex::new(r"https?://\S+|www\.\S+|\S+@\S+\.\S+|`[^`\n]*`").unwrap());
+    let skipped: Vec<(usize, usize)> = skip_re.find_iter(text).map(|m| (m.start(), m.end())).collect();
+
+    let mut out = Vec::new();
+    let mut current: Option<(usize, usize, String)> = None;
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    for (i, &(byte, c)) in chars.iter().enumerate() {
+        let inner_apostrophe = matches!(c, '\'' | '’')
+            && current.is_some()
+            && chars.get(i + 1).is_some_and(|(_, next)| next.is_alphabetic());
+        if is_word_char(c) || inner_apostrophe {
+            let entry = current.get_or_insert_with(|| (i, byte, String::new()));
+            entry.2.push(c);
+        } else if let Some(word) = current.take() {
+            out.push(word);
+        }
+    }
+    out.extend(current);
+
+    out.into_iter()
+        .filter(|(_, byte, word)| !skipped.iter().any(|(s, e)| byte >= s && byte < e) && keep(word))
+        .map(|(start, _, word)| (start, start + word.chars().count(), word))
+        .collect()
+}
+
+fn is_word_char(c: char) -> bool {
+    // Indic vowel signs, viramas, and nuktas are marks rather than letters; dandas are punctuation
+    c.is_alphanumeric() || (matches!(c, '\u{0900}'..='\u{0D7F}') && !matches!(c, '\u{0964}' | '\u{0965}')) || matches!(c, '\u{200C}' | '\u{200D}')
+}
+
+fn keep(word: &str) -> bool {
+    let letters = word.chars().filter(|c| c.is_alphabetic()).count();
+    letters >= 2
+        && !word.chars().any(|c| c.is_numeric())
+        && !(word.chars().any(|c| c.is_uppercase()) && !word.chars().any(|c| c.is_lowercase()))
+}
+
+// "hi_IN" -> "hi", "en-GB" -> "en"
+fn language_of(stem: &str) -> String {
+    stem.split(['_', '-']).next().unwrap_or(stem).to_lowercase()
+}
+
+pub struct Dictionary {
+    pub language: String,
+    path: PathBuf,
+    words: HashSet<String>,
+    latin: bool,                       // Checks Latin-script words (English); otherwise the native script
+    try_chars: Vec<char>,
+    replacements: Vec<(String, String)>,
+}
+
+#[derive(Clone, Copy)]
+enum FlagMode {
+    Char,
+    Long,
+    Num,
+}
+
+#[derive(Clone)]
+enum Condition {
+    Any,
+    Char(char),
+    Set(bool, Vec<char>),              // (negated, chars)
+}
+
+struct Affix {
+    prefix: bool,
+    cross: bool,
+    strip: Vec<char>,
+    add: String,
+    condition: Vec<Condition>,
+}
+
+impl Dictionary {
+    pub fn load(dic_path: &Path) -> Result<Self, SpellcheckError> {
+        let io = |path: &Path, e: std::io::Error| SpellcheckError::Io(format!("{}: {}", path.display(), e));
+        let aff_path = dic_path.with_extension("aff");
+        let aff_bytes = std::fs::read(&aff_path).map_err(|e| io(&aff_path, e))?;
+        let dic_bytes = std::fs::read(dic_path).map_err(|e| io(dic_path, e))?;
+        // Most dictionaries are UTF-8; older English ones declare ISO8859-1
+        let latin1 = String::from_utf8_lossy(&aff_bytes)
+            .lines()
+            .find_map(|l| l.trim().strip_prefix("SET "))
+            .is_some_and(|set| set.trim().eq_ignore_ascii_case("ISO8859-1"));
+        let decode = |bytes: &[u8]| {
+            if latin1 {
+                bytes.iter().map(|&b| b as char).collect::<String>()
+            } else {
+                String::from_utf8_lossy(bytes).trim_start_matches('\u{feff}').to_string()
+            }
+        };
+        let (aff, dic) = (decode(&aff_bytes), decode(&dic_bytes));
+
+        let mut mode = FlagMode::Char;
+        let mut try_chars = Vec::new();
+        let mut replacements = Vec::new();
+        let mut affixes: HashMap<String, Vec<Affix>> = HashMap::new();
+        let mut cross: HashMap<String, bool> = HashMap::new();
+        let mut forbidden_flag: Option<String> = None;
+        // Stems carrying these flags are only valid with an affix (or inside compounds)
+        let mut bare_excluded: Vec<String> = Vec::new();
+        for line in aff.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["FLAG", kind, ..] => {
+                    mode = match *kind {
+                        "long" => FlagMode::Long,
+                        "num" => FlagMode::Num,
+                        _ => FlagMode::Char,
+                    }
+                }
+                ["TRY", chars, ..] => try_chars = chars.chars().collect(),
+                ["REP", from, to, ..] => replacements.push((from.replace('_', " "), to.replace('_', " "))),
+                ["FORBIDDENWORD", flag, ..] => forbidden_flag = Some(flag.to_string()),
+                ["NEEDAFFIX" | "ONLYINCOMPOUND", flag, ..] => bare_excluded.push(flag.to_string()),
+                [kind @ ("PFX" | "SFX"), flag, yes, count] if count.parse::<usize>().is_ok() => {
+                    cross.insert(format!("{}{}", kind, flag), *yes == "Y");
+                }
+                [kind @ ("PFX" | "SFX"), flag, strip, add, rest @ ..] => {
+                    let prefix = *kind == "PFX";
+                    let add = add.split('/').next().unwrap_or_default();
+                    affixes.entry(flag.to_string()).or_default().push(Affix {
+                        prefix,
+                        cross: cross.get(&format!("{}{}", kind, flag)).copied().unwrap_or(false),
+                        strip: if *strip == "0" { Vec::new() } else { strip.chars().collect() },
+                        add: if add == "0" { String::new() } else { add.to_string() },
+                        condition: parse_condition(rest.first().copied().unwrap_or(".")),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        let mut words = HashSet::new();
+        let mut forbidden = HashSet::new();
+        // The first line is the approximate word count
+        for line in dic.lines().skip(1) {
+            // Morphological fields follow a tab or space
+            let entry = line.split(['\t', ' ']).next().unwrap_or_default().trim();
+            if entry.is_empty() || entry.starts_with('#') {
+                continue;
+            }
+            let (stem, flags) = match entry.split_once('/') {
+                Some((stem, flags)) => (stem, parse_flags(flags, mode)),
+                None => (entry, Vec::new()),
+            };
+            if forbidden_flag.as_ref().is_some_and(|forbidden| flags.contains(forbidden)) {
+                forbidden.insert(stem.to_string());
+                continue;
+            }
+            if !flags.iter().any(|f| bare_excluded.contains(f)) {
+                words.insert(stem.to_string());
+            }
+            let rules: Vec<&Affix> = flags.iter().filter_map(|f| affixes.get(f)).flatten().collect();
+            let mut suffixed = Vec::new();
+            for rule in rules.iter().filter(|r| !r.prefix) {
+                if let Some(word) = rule.apply(stem) {
+                    if rule.cross {
+                        suffixed.push(word.clone());
+                    }
+                    words.insert(word);
+                }
+            }
+            for rule in rules.iter().filter(|r| r.prefix) {
+                if let Some(word) = rule.apply(stem) {
+                    words.insert(word);
+                }
+                if rule.cross {
+                    words.extend(suffixed.iter().filter_map(|w| rule.apply(w)));
+                }
+            }
+        }
+        for word in &forbidden {
+            words.remove(word);
+        }
+
+        let language = dic_path.file_stem().and_then(|s| s.to_str()).map(language_of).unwrap_or_default();
+        let sample: Vec<&String> = words.iter().take(1_000).collect();
+        let latin = sample.iter().filter(|w| w.chars().all(|c| c.is_ascii() || matches!(c, '\u{00C0}'..='\u{024F}'))).count() * 2 > sample.len();
+        Ok(Self { language, path: dic_path.to_path_buf(), words, latin, try_chars, replacements })
+    }
+
+    // Exact form, or a lowercase entry for Capitalized text ("The" -> "the")
+    pub fn is_correct(&self, word: &str) -> bool {
+        let word = word.replace('’', "'");
+        if self.words.contains(&word) {
+            return true;
+        }
+        self.words.contains(&word.to_lowercase())
+    }
+
+    // REP table substitutions and one-edit variants, then (if none fit) dictionary words two edits away
+    pub fn suggest(&self, word: &str) -> Vec<String> {
+        let word = word.replace('’', "'");
+        let capitalized = word.chars().next().is_some_and(|c| c.is_uppercase());
+        let lower = word.to_lowercase();
+        let mut candidates: Vec<(usize, String)> = Vec::new();
+        let push = |rank: usize, candidate: String, candidates: &mut Vec<(usize, String)>| {
+            if candidate != lower && self.words.contains(&candidate) && !candidates.iter().any(|(_, c)| *c == candidate) {
+                candidates.push((rank, candidate));
+            }
+        };
+
+        for (from, to) in &self.replacements {
+            for (i, _) in lower.match_indices(from.as_str()) {
+                push(0, format!("{}{}{}", &lower[..i], to, &lower[i + from.len()..]), &mut candidates);
+            }
+        }
+        let chars: Vec<char> = lower.chars().collect();
+        let alphabet: Vec<char> = if self.try_chars.is_empty() {
+            let mut own = chars.clone();
+            own.extend('a'..='z');
+            own
+        } else {
+            self.try_chars.iter().flat_map(|c| c.to_lowercase()).collect()
+        };
+        for i in 0..=chars.len() {
+            if i < chars.len() {
+                let mut deleted = chars.clone();
+                deleted.remove(i);
+                push(1, deleted.into_iter().collect(), &mut candidates);
+            }
+            if i + 1 < chars.len() {
+                let mut swapped = chars.clone();
+                swapped.swap(i, i + 1);
+                push(1, swapped.into_iter().collect(), &mut candidates);
+            }
+            for &c in &alphabet {
+                if i < chars.len() && chars[i] != c {
+                    let mut replaced = chars.clone();
+                    replaced[i] = c;
+                    push(1, replaced.into_iter().collect(), &mut candidates);
+                }
+                let mut inserted = chars.clone();
+                inserted.insert(i, c);
+                push(1, inserted.into_iter().collect(), &mut candidates);
+            }
+        }
+        if candidates.is_empty() && chars.len() > 3 {
+            let first = chars[0];
+            for candidate in &self.words {
+                let length = candidate.chars().count();
+                if length.abs_diff(chars.len()) > 2 || !candidate.starts_with(first) {
+                    continue;
+                }
+                let distance = edit_distance(&chars, &candidate.chars().collect::<Vec<_>>());
+                if distance <= 2 {
+                    candidates.push((distance, candidate.clone()));
+                }
+            }
+        }
+
+        candidates.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+        candidates
+            .into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|(_, candidate)| match capitalized {
+                true => {
+                    let mut chars = candidate.chars();
+                    chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or(candidate)
+                }
+                false => candidate,
+            })
+            .collect()
+    }
+}
+
+impl Affix {
+    fn apply(&self, stem: &str) -> Option<String> {
+        let chars: Vec<char> = stem.chars().collect();
+        if chars.len() < self.condition.len() || chars.len() < self.strip.len() {
+            return None;
+        }
+        let (matched, stripped) = if self.prefix {
+            (
+                chars[..self.condition.len()].iter().zip(&self.condition).all(|(c, cond)| cond.matches(*c)),
+                chars.starts_with(&self.strip),
+            )
+        } else {
+            let tail = &chars[chars.len() - self.condition.len()..];
+            (tail.iter().zip(&self.condition).all(|(c, cond)| cond.matches(*c)), chars.ends_with(&self.strip))
+        };
+        if !matched || !stripped {
+            return None;
+        }
+        Some(if self.prefix {
+            format!("{}{}", self.add, chars[self.strip.len()..].iter().collect::<String>())
+        } else {
+            format!("{}{}", chars[..chars.len() - self.strip.len()].iter().collect::<String>(), self.add)
+        })
+    }
+}
+
+impl Condition {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Condition::Any => true,
+            Condition::Char(expected) => c == *expected,
+            Condition::Set(negated, chars) => chars.contains(&c) != *negated,
+        }
+    }
+}
+
+// Hunspell conditions are a restricted regex: literal chars, '.', and [abc] / [^abc] sets
+fn parse_condition(condition: &str) -> Vec<Condition> {
+    if condition == "." {
+        return Vec::new();
+    }
+    let mut parts = Vec::new();
+    let mut chars = condition.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => parts.push(Condition::Any),
+            '[' => {
+                let mut set: Vec<char> = chars.by_ref().take_while(|&c| c != ']').collect();
+                let negated = set.first() == Some(&'^');
+                if negated {
+                    set.remove(0);
+                }
+                parts.push(Condition::Set(negated, set));
+            }
+            c => parts.push(Condition::Char(c)),
+        }
+    }
+    parts
+}
+
+fn parse_flags(flags: &str, mode: FlagMode) -> Vec<String> {
+    match mode {
+        FlagMode::Char => flags.chars().map(String::from).collect(),
+        FlagMode::Long => {
+            let chars: Vec<char> = flags.chars().collect();
+            chars.chunks(2).map(|pair| pair.iter().collect()).collect()
+        }
+        FlagMode::Num => flags.split(',').map(|f| f.trim().to_string()).filter(|f| !f.is_empty()).collect(),
+    }
+}
+
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            current.push((previous[j] + cost).min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[derive(Debug, Clone)]
+pub enum SpellcheckError {
+    Invalid(String),
+    Io(String),
+}
+
+impl std::fmt::Display for SpellcheckError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpellcheckError::Invalid(msg) => write!(f, "Invalid spellcheck request: {}", msg),
+            SpellcheckError::Io(msg) => write!(f, "Failed to read dictionary: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SpellcheckError {}
@@ -1,9 +1,13 @@
 // Stability Features - Watchdog, Safe Mode, Memory Guard
 // Crash-proof, low-RAM, real app stability
 
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
-use tauri;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
 
 // Platform-specific RAM detection
 #[cfg(target_os = "windows")]
@@ -90,6 +94,28 @@ pub struct SafeMode {
     enabled: Arc<Mutex<bool>>,
     crash_count: Arc<Mutex<u32>>,
     max_crashes: u32,
+    state_path: Arc<Mutex<Option<PathBuf>>>, // Crash count survives restarts to detect crash loops
+    last_crash_at: Arc<Mutex<Option<i64>>>,
+    unclean_exit: Arc<Mutex<bool>>,   // The run before this one never reached end_session
+}
+
+// Persisted between launches; `running` still set at startup means the last run never exited cleanly
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SafeModeFile {
+    running: bool,
+    crash_count: u32,
+    last_crash_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SafeModeStatus {
+    pub enabled: bool,
+    pub crash_count: u32,
+    pub max_crashes: u32,
+    pub last_crash_at: Option<i64>,
 }
 
 impl SafeMode {
@@ -98,7 +124,42 @@ impl SafeMode {
             enabled: Arc::new(Mutex::new(false)),
             crash_count: Arc::new(Mutex::new(0)),
             max_crashes,
+            state_path: Arc::new(Mutex::new(None)),
+            last_crash_at: Arc::new(Mutex::new(None)),
+            unclean_exit: Arc::new(Mutex::new(false)),
+        }
+    }
+
+    // Load the persisted crash count at startup; an unclean previous exit counts as a crash.
+    // Returns whether this launch should run in safe mode.
+    pub fn begin_session(&self, path: PathBuf) -> bool {
+        let file: SafeModeFile = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        *self.state_path.lock().unwrap() = Some(path);
+        *self.crash_count.lock().unwrap() = file.crash_count;
+        *self.last_crash_at.lock().unwrap() = file.last_crash_at;
+
+        *self.unclean_exit.lock().unwrap() = file.running;
+        if file.running {
+            tracing::warn!(target: "stability", "Safe mode: Previous run did not exit cleanly");
+            self.record_crash();
+        } else {
+            *self.enabled.lock().unwrap() = self.should_enable();
+            self.persist(true);
         }
+        self.is_enabled()
+    }
+
+    // Clean shutdown
+    pub fn end_session(&self) {
+        self.persist(false);
+    }
+
+    // Whether the previous run crashed or was killed (known once begin_session ran)
+    pub fn previous_exit_unclean(&self) -> bool {
+        *self.unclean_exit.lock().unwrap()
     }
 
     // Check if safe mode should be enabled
@@ -109,14 +170,18 @@ impl SafeMode {
 
     // Record a crash
     pub fn record_crash(&self) {
-        let mut count = self.crash_count.lock().unwrap();
-        *count += 1;
-        
-        if *count >= self.max_crashes {
-            let mut enabled = self.enabled.lock().unwrap();
-            *enabled = true;
-            eprintln!("[SafeMode] Enabled after {} crashes", *count);
+        {
+            let mut count = self.crash_count.lock().unwrap();
+            *count += 1;
+            *self.last_crash_at.lock().unwrap() = Some(chrono::Utc::now().timestamp());
+
+            if *count >= self.max_crashes {
+                let mut enabled = self.enabled.lock().unwrap();
+                *enabled = true;
+                tracing::warn!(target: "stability", "Safe mode: Enabled after {} crashes", *count);
+            }
         }
+        self.persist(true);
     }
 
     // Check if safe mode is enabled
@@ -127,10 +192,13 @@ impl SafeMode {
 
     // Reset crash count (after successful session)
     pub fn reset_crash_count(&self) {
-        let mut count = self.crash_count.lock().unwrap();
-        *count = 0;
-        let mut enabled = self.enabled.lock().unwrap();
-        *enabled = false;
+        {
+            let mut count = self.crash_count.lock().unwrap();
+            *count = 0;
+            let mut enabled = self.enabled.lock().unwrap();
+            *enabled = false;
+        }
+        self.persist(true);
     }
 
     // Get crash count
@@ -138,6 +206,31 @@ impl SafeMode {
         let count = self.crash_count.lock().unwrap();
         *count
     }
+
+    pub fn status(&self) -> SafeModeStatus {
+        SafeModeStatus {
+            enabled: self.is_enabled(),
+            crash_count: self.get_crash_count(),
+            max_crashes: self.max_crashes,
+            last_crash_at: *self.last_crash_at.lock().unwrap(),
+        }
+    }
+
+    fn persist(&self, running: bool) {
+        let Some(path) = self.state_path.lock().unwrap().clone() else {
+            return;
+        };
+        let file = SafeModeFile {
+            running,
+            crash_count: self.get_crash_count(),
+            last_crash_at: *self.last_crash_at.lock().unwrap(),
+        };
+        if let Ok(json) = serde_json::to_string(&file) {
+            if let Err(e) = std::fs::write(&path, json) {
+                tracing::warn!(target: "stability", "Safe mode: Failed to save state: {}", e);
+            }
+        }
+    }
 }
 
 // Memory Guard - Manages tab freezing and unloading
@@ -222,10 +315,64 @@ impl MemoryGuard {
 }
 
 // Watchdog - Monitors WebView responsiveness and auto-recovers
-// Note: Actual tab checking will be done via IPC commands
+// Each tick pings every awake tab ("watchdog:ping"); tabs answer via the watchdog_ack command.
+// Tabs that have never answered aren't judged, so webviews without the ack hook are left alone.
+#[derive(Clone)]
 pub struct Watchdog {
     check_interval: Duration,
     response_timeout: Duration,
+    seq: Arc<AtomicU64>,
+    heartbeats: Arc<Mutex<HashMap<String, Heartbeat>>>,
+    stats: Arc<Mutex<WatchdogStats>>,
+}
+
+#[derive(Debug, Default)]
+struct Heartbeat {
+    last_sent: u64,
+    last_acked: u64,
+    armed: bool,                       // Answered at least once
+    missed: u32,
+    unresponsive_since: Option<Instant>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct WatchdogStats {
+    pub pings_sent: u64,
+    pub monitored_tabs: usize,
+    pub unresponsive_events: u64,
+    pub recoveries: u64,
+    pub reloads: u64,
+    pub slept: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum RecoveryAction {
+    Reload,
+    Sleep,                             // Crash limit reached; tab is unloaded instead of reloaded
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct TabUnresponsive {
+    pub tab_id: String,
+    pub missed_heartbeats: u32,
+    pub crash_count: u32,
+    pub action: RecoveryAction,
+    pub stats: WatchdogStats,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct TabRecovered {
+    pub tab_id: String,
+    pub downtime_ms: u64,
+    pub stats: WatchdogStats,
 }
 
 impl Watchdog {
@@ -236,6 +383,9 @@ impl Watchdog {
         Self {
             check_interval,
             response_timeout,
+            seq: Arc::new(AtomicU64::new(0)),
+            heartbeats: Arc::new(Mutex::new(HashMap::new())),
+            stats: Arc::new(Mutex::new(WatchdogStats::default())),
         }
     }
 
@@ -248,26 +398,138 @@ impl Watchdog {
     pub fn get_response_timeout(&self) -> Duration {
         self.response_timeout
     }
+
+    // Heartbeats a tab may miss before it counts as unresponsive (timeout / interval, at least 2)
+    pub fn max_missed(&self) -> u32 {
+        let interval = self.check_interval.as_millis().max(1);
+        let missed = self.response_timeout.as_millis().div_ceil(interval);
+        (missed as u32).max(2)
+    }
+
+    pub fn stats(&self) -> WatchdogStats {
+        self.stats.lock().unwrap().clone()
+    }
+
+    // Record a heartbeat answer; returns the recovery if the tab had been unresponsive
+    pub fn acknowledge(&self, tab_id: &str, seq: u64) -> Option<TabRecovered> {
+        let mut heartbeats = self.heartbeats.lock().unwrap();
+        let heartbeat = heartbeats.entry(tab_id.to_string()).or_default();
+        heartbeat.last_acked = heartbeat.last_acked.max(seq);
+        heartbeat.armed = true;
+        heartbeat.missed = 0;
+        let since = heartbeat.unresponsive_since.take()?;
+
+        let mut stats = self.stats.lock().unwrap();
+        stats.recoveries += 1;
+        Some(TabRecovered {
+            tab_id: tab_id.to_string(),
+            downtime_ms: since.elapsed().as_millis() as u64,
+            stats: stats.clone(),
+        })
+    }
+
+    // Count missed answers, pick a recovery for newly unresponsive tabs, and return the next ping
+    pub fn tick(&self, tabs: &crate::browser::TabManager) -> (u64, Vec<String>, Vec<TabUnresponsive>) {
+        let seq = self.seq.fetch_add(1, Ordering::Relaxed) + 1;
+        let awake: Vec<String> = tabs
+            .list_tabs()
+            .into_iter()
+            .filter(|t| !t.is_sleeping)
+            .map(|t| t.id)
+            .collect();
+        let max_missed = self.max_missed();
+
+        let mut heartbeats = self.heartbeats.lock().unwrap();
+        heartbeats.retain(|id, _| awake.contains(id));
+
+        let mut unresponsive = Vec::new();
+        let mut stats = self.stats.lock().unwrap();
+        for id in &awake {
+            let heartbeat = heartbeats.entry(id.clone()).or_default();
+            if heartbeat.armed && heartbeat.last_sent > heartbeat.last_acked {
+                heartbeat.missed += 1;
+            }
+            heartbeat.last_sent = seq;
+
+            // One recovery per episode; the tab stays flagged until it answers again
+            if heartbeat.missed < max_missed || heartbeat.unresponsive_since.is_some() {
+                continue;
+            }
+            heartbeat.unresponsive_since = Some(Instant::now());
+            stats.unresponsive_events += 1;
+
+            let crash_limit_reached = tabs.record_tab_crash(id).unwrap_or(false);
+            let action = if crash_limit_reached {
+                let _ = tabs.freeze_tab(id);
+                stats.slept += 1;
+                RecoveryAction::Sleep
+            } else {
+                stats.reloads += 1;
+                RecoveryAction::Reload
+            };
+            unresponsive.push(TabUnresponsive {
+                tab_id: id.clone(),
+                missed_heartbeats: heartbeat.missed,
+                crash_count: tabs.get_tab(id).map(|t| t.crash_count).unwrap_or(0),
+                action,
+                stats: WatchdogStats::default(),
+            });
+        }
+        stats.pings_sent += awake.len() as u64;
+        stats.monitored_tabs = heartbeats.values().filter(|h| h.armed).count();
+        for event in &mut unresponsive {
+            event.stats = stats.clone();
+        }
+
+        (seq, awake, unresponsive)
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct WatchdogPing {
+    seq: u64,
+    tab_ids: Vec<String>,
 }
 
 // Watchdog task (to be called from Tauri setup after runtime is initialized)
-// This function should be called from within Tauri's setup() closure where async runtime exists
+// Emits "tab-unresponsive" (and reloads native webviews labelled with the tab id); "tab-recovered" comes from watchdog_ack
 pub fn start_watchdog_task_async(
-    check_interval: Duration,
-    _response_timeout: Duration,
+    app: tauri::AppHandle,
+    tabs: Arc<crate::browser::TabManager>,
+    watchdog: Watchdog,
 ) {
     // Use Tauri's async runtime (available in setup context)
     tauri::async_runtime::spawn(async move {
-        let mut interval = tokio::time::interval(check_interval);
-        
+        let mut interval = tokio::time::interval(watchdog.get_check_interval());
+
         loop {
             interval.tick().await;
-            
-            // TODO: Access tab_manager via Tauri state and check responsiveness
-            // For now, just log that watchdog is running
-            // This will be implemented when WebView integration is complete
-            // eprintln!("[Watchdog] Running check...");
+
+            let (seq, tab_ids, unresponsive) = watchdog.tick(&tabs);
+            for event in unresponsive {
+                tracing::warn!(
+                    target: "stability",
+                    "Watchdog: Tab {} missed {} heartbeats; action: {:?}",
+                    event.tab_id, event.missed_heartbeats, event.action
+                );
+                if event.action == RecoveryAction::Reload {
+                    if let Some(webview) = app.get_webview_window(&event.tab_id) {
+                        let _ = webview.reload();
+                    }
+                } else if let Some(safe_mode) = app.try_state::<SafeMode>() {
+                    // Same escalation as tabs_record_crash
+                    safe_mode.record_crash();
+                    if let Some(db) = app.try_state::<crate::db::Database>() {
+                        let _ = tabs.save_session(&db);
+                    }
+                }
+                let _ = app.emit("tab-unresponsive", &event);
+            }
+            if !tab_ids.is_empty() {
+                let _ = app.emit("watchdog:ping", WatchdogPing { seq, tab_ids });
+            }
         }
     });
 }
-
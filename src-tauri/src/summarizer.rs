@@ -0,0 +1,495 @@
+// Summarizer - Map-reduce summaries of documents too long for one model call
+// Chunks are summarized concurrently (map) and their summaries merged in document order (reduce).
+// Presets (tl;dr, brief, detailed, ELI5, exam notes) return structured JSON instead of prose.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use tauri::Manager;
+
+use crate::ai::AIService;
+use crate::chunker::{self, ChunkOptions};
+use crate::markdown::MarkdownEvent;
+use crate::text::estimate_tokens;
+
+// Anything beyond this many chunks is left out (and reported as truncated)
+const MAX_CHUNKS: usize = 32;
+const DEFAULT_CONCURRENCY: usize = 3;
+const MAX_CONCURRENCY: usize = 8;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct SummaryOptions {
+    pub title: Option<String>,
+    pub language: Option<String>,      // Detected from the text when unset
+    pub mode: Option<String>,          // App mode, for mode-specific prompts
+    pub concurrency: Option<usize>,    // Chunks summarized at once; model calls are further limited by the mode profile
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkSummary {
+    pub index: usize,                  // 1-based, in document order
+    pub chars: usize,
+    pub summary: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentSummary {
+    pub summary: String,
+    pub language: String,
+    pub chunks: Vec<ChunkSummary>,     // Always in document order, whatever order they finished in
+    pub truncated: bool,               // The text had more than MAX_CHUNKS chunks; the rest was left out
+    pub reduce_passes: usize,          // Extra merge rounds needed before the partial summaries fit one call
+    pub elapsed_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "kebab-case")]
+pub enum SummaryPreset {
+    Tldr,                              // Headline + one or two sentences
+    Brief,                             // Headline + 3-5 bullets
+    Detailed,                          // Headline + paragraphs + key points
+    Eli5,                              // Plain-language explanation
+    ExamNotes,                         // Key facts/definitions + review questions
+}
+
+impl SummaryPreset {
+    pub fn parse(preset: &str) -> Result<Self, SummaryError> {
+        match preset.trim().to_lowercase().replace(['_', ' '], "-").as_str() {
+            "tldr" | "tl;dr" => Ok(SummaryPreset::Tldr),
+            "brief" | "bullet-brief" | "bullets" => Ok(SummaryPreset::Brief),
+            "detailed" => Ok(SummaryPreset::Detailed),
+            "eli5" => Ok(SummaryPreset::Eli5),
+            "exam-notes" | "exam" => Ok(SummaryPreset::ExamNotes),
+            other => Err(SummaryError::UnknownPreset(other.to_string())),
+        }
+    }
+
+    // Prompt task name (see prompts/defaults.toml)
+    pub fn task(&self) -> &'static str {
+        match self {
+            SummaryPreset::Tldr => "summary_tldr",
+            SummaryPreset::Brief => "summary_brief",
+            SummaryPreset::Detailed => "summary_detailed",
+            SummaryPreset::Eli5 => "summary_eli5",
+            SummaryPreset::ExamNotes => "summary_exam_notes",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SummaryEntity {
+    pub name: String,
+    #[serde(default)]
+    pub kind: String,                  // "person" | "organization" | "place" | "date" | "other"
+}
+
+// What every preset prompt asks the model to return; fields a preset doesn't use stay empty
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct StructuredSummary {
+    pub headline: String,
+    pub summary: String,
+    pub bullets: Vec<String>,
+    pub entities: Vec<SummaryEntity>,
+    pub questions: Vec<String>,        // Exam notes only
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct PresetSummary {
+    pub preset: SummaryPreset,
+    pub language: String,
+    #[serde(flatten)]
+    pub output: StructuredSummary,
+    pub condensed: bool,               // The text was too long and went through map-reduce first
+    pub cached: bool,
+    pub elapsed_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case", rename_all_fields = "camelCase")]
+pub enum SummaryEvent {
+    Plan { chunks: usize, truncated: bool },
+    ChunkStarted { index: usize },
+    ChunkDone { index: usize, summary: String },
+    ChunkFailed { index: usize, error: String },
+    SummaryToken { token: String, events: Vec<MarkdownEvent> },
+    Done { summary: DocumentSummary },
+    Failed { error: String },
+}
+
+// Summarize `text`: one streamed call when it fits, otherwise map over chunks and reduce.
+// Chunk events arrive as chunks finish, tagged with their index; `emit` also sees the final Done/Failed.
+pub async fn summarize(
+    app: tauri::AppHandle,
+    text: String,
+    options: SummaryOptions,
+    emit: impl Fn(SummaryEvent) + Send + Sync + 'static,
+) -> Result<DocumentSummary, SummaryError> {
+    let emit = Arc::new(emit);
+    let result = summarize_inner(app, text, options, emit.clone()).await;
+    match &result {
+        Ok(summary) => emit(SummaryEvent::Done { summary: summary.clone() }),
+        Err(e) => emit(SummaryEvent::Failed { error: e.to_string() }),
+    }
+    result
+}
+
+async fn summarize_inner(
+    app: tauri::AppHandle,
+    text: String,
+    options: SummaryOptions,
+    emit: Arc<impl Fn(SummaryEvent) + Send + Sync + 'static>,
+) -> Result<DocumentSummary, SummaryError> {
+    let started = std::time::Instant::now();
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(SummaryError::EmptyText);
+    }
+    let language = options
+        .language
+        .clone()
+        .unwrap_or_else(|| crate::language::detect_language(text).language);
+    let mode = options.mode.as_deref();
+    let title = options
+        .title
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(|t| format!("Title: {}", t))
+        .unwrap_or_default();
+    let title = title.as_str();
+
+    // Text handed to the model per call, sized to its context window
+    let budget = ChunkOptions::summary(app.state::<AIService>().model());
+    let max_tokens = budget.max_tokens;
+    let mut chunks = chunker::split(text, &budget);
+    let truncated = chunks.len() > MAX_CHUNKS;
+    chunks.truncate(MAX_CHUNKS);
+    emit(SummaryEvent::Plan { chunks: chunks.len(), truncated });
+
+    // Short enough for one call: no map step
+    if chunks.len() == 1 {
+        let prompt = crate::prompts::render("summarize", Some(&language), mode, &[("content", &chunks[0])]);
+        let summary = stream(&app, &prompt, emit.as_ref()).await?;
+        return Ok(DocumentSummary {
+            summary,
+            language,
+            chunks: vec![ChunkSummary { index: 1, chars: chunks[0].chars().count(), summary: None, error: None }],
+            truncated,
+            reduce_passes: 0,
+            elapsed_ms: started.elapsed().as_millis() as u64,
+        });
+    }
+
+    // Map
+    let total = chunks.len().to_string();
+    let prompts = chunks
+        .iter()
+        .enumerate()
+        .map(|(i, chunk)| {
+            let index = (i + 1).to_string();
+            let vars = [("index", index.as_str()), ("total", total.as_str()), ("title", title), ("content", chunk.as_str())];
+            crate::prompts::render("summarize_chunk", Some(&language), mode, &vars)
+        })
+        .collect();
+    let concurrency = options.concurrency.unwrap_or(DEFAULT_CONCURRENCY).clamp(1, MAX_CONCURRENCY);
+    let results = {
+        let (started_emit, done_emit) = (emit.clone(), emit.clone());
+        map_parallel(
+            &app,
+            "summarize_chunk",
+            prompts,
+            concurrency,
+            move |index| started_emit(SummaryEvent::ChunkStarted { index }),
+            move |index, result| match result {
+                Ok(summary) => done_emit(SummaryEvent::ChunkDone { index, summary: summary.clone() }),
+                Err(error) => done_emit(SummaryEvent::ChunkFailed { index, error: error.clone() }),
+            },
+        )
+        .await
+    };
+    let chunk_summaries: Vec<ChunkSummary> = results
+        .into_iter()
+        .zip(&chunks)
+        .enumerate()
+        .map(|(i, (result, chunk))| ChunkSummary {
+            index: i + 1,
+            chars: chunk.chars().count(),
+            summary: result.as_ref().ok().cloned(),
+            error: result.err(),
+        })
+        .collect();
+    if chunk_summaries.iter().all(|c| c.summary.is_none()) {
+        let reason = chunk_summaries.iter().find_map(|c| c.error.clone()).unwrap_or_default();
+        return Err(SummaryError::NoPartials(reason));
+    }
+
+    // Reduce: merge neighbouring partials until they fit one call, then stream the final pass
+    let mut parts: Vec<String> = chunk_summaries
+        .iter()
+        .filter_map(|c| c.summary.as_ref().map(|s| format!("Part {} of {}:\n{}", c.index, total, s)))
+        .collect();
+    let mut reduce_passes = 0;
+    while parts.len() > 1 && parts.iter().map(|p| estimate_tokens(p) + 1).sum::<usize>() > max_tokens {
+        let groups = group_parts(&parts, max_tokens);
+        // A pass that can't merge anything would loop forever
+        if groups.len() == parts.len() {
+            break;
+        }
+        let prompts = groups
+            .iter()
+            .map(|group| crate::prompts::render("summarize_reduce", Some(&language), mode, &[("title", title), ("parts", group)]))
+            .collect();
+        let merged = map_parallel(&app, "summarize_reduce", prompts, concurrency, |_| {}, |_, _| {}).await;
+        parts = merged
+            .into_iter()
+            .zip(groups)
+            .enumerate()
+            .map(|(i, (result, group))| {
+                // A failed merge keeps its inputs, cut to size, rather than losing that stretch of the document
+                let summary = result.unwrap_or_else(|_| chunker::truncate_tokens(&group, max_tokens / 2));
+                format!("Section {}:\n{}", i + 1, summary)
+            })
+            .collect();
+        reduce_passes += 1;
+    }
+    let joined = chunker::truncate_tokens(&parts.join("\n\n"), max_tokens);
+    let prompt = crate::prompts::render("summarize_reduce", Some(&language), mode, &[("title", title), ("parts", &joined)]);
+    let summary = stream(&app, &prompt, emit.as_ref()).await?;
+
+    Ok(DocumentSummary {
+        summary,
+        language,
+        chunks: chunk_summaries,
+        truncated,
+        reduce_passes,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+// Structured summary in one of the presets. Results are cached per (text hash, preset, language)
+// for as long as the LLM cache keeps entries; text longer than one call is condensed by `summarize` first.
+pub async fn summarize_preset(
+    app: tauri::AppHandle,
+    text: String,
+    preset: SummaryPreset,
+    language: Option<String>,
+) -> Result<PresetSummary, SummaryError> {
+    let started = std::time::Instant::now();
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(SummaryError::EmptyText);
+    }
+    let language = language
+        .map(|l| l.trim().to_lowercase())
+        .filter(|l| !l.is_empty())
+        .unwrap_or_else(|| crate::language::detect_language(text).language);
+
+    let ai = app.state::<AIService>();
+    let hash = hex_digest(text);
+    let cache_key = format!("{}:{}", language, hash);
+    if let Some(cached) = ai.cache().and_then(|c| c.get(preset.task(), ai.model(), &cache_key)) {
+        if let Ok(output) = serde_json::from_str::<StructuredSummary>(&cached) {
+            return Ok(PresetSummary {
+                preset,
+                language,
+                output,
+                condensed: false,
+                cached: true,
+                elapsed_ms: started.elapsed().as_millis() as u64,
+            });
+        }
+    }
+
+    let condensed = estimate_tokens(text) > ChunkOptions::summary(ai.model()).max_tokens;
+    let content = if condensed {
+        let options = SummaryOptions { language: Some(language.clone()), ..Default::default() };
+        summarize(app.clone(), text.to_string(), options, |_| {}).await?.summary
+    } else {
+        text.to_string()
+    };
+    let language_name = crate::selection::LANGUAGE_NAMES
+        .iter()
+        .find(|(code, _)| *code == language)
+        .map(|(_, name)| name.to_string())
+        .unwrap_or_else(|| language.clone());
+    let prompt = crate::prompts::render(preset.task(), Some(&language), None, &[("language", &language_name), ("content", &content)]);
+    // Cached below under the text's hash, not the prompt
+    let response = ai.complete_fresh(&prompt).await.map_err(|e| SummaryError::AIError(e.to_string()))?;
+    let output = parse_structured(&response);
+    if output.headline.is_empty() && output.summary.is_empty() && output.bullets.is_empty() {
+        return Err(SummaryError::AIError("the model returned an empty summary".to_string()));
+    }
+    if let Some(cache) = ai.cache() {
+        cache.store_exact(preset.task(), ai.model(), &cache_key, &serde_json::to_string(&output).unwrap_or_default());
+    }
+    Ok(PresetSummary { preset, language, output, condensed, cached: false, elapsed_ms: started.elapsed().as_millis() as u64 })
+}
+
+// The JSON object in the reply (models wrap it in code fences or prose, and entities may come as
+// plain strings); a reply without one becomes headline + bullets + paragraphs
+fn parse_structured(response: &str) -> StructuredSummary {
+    let json = match (response.find('{'), response.rfind('}')) {
+        (Some(start), Some(end)) if start < end => serde_json::from_str::<serde_json::Value>(&response[start..=end]).ok(),
+        _ => None,
+    };
+    if let Some(json) = json.filter(serde_json::Value::is_object) {
+        let text = |key: &str| json.get(key).and_then(|v| v.as_str()).unwrap_or_default().trim().to_string();
+        let list = |key: &str| -> Vec<String> {
+            json.get(key)
+                .and_then(|v| v.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|v| v.as_str())
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .collect()
+        };
+        let entities = json
+            .get("entities")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|entity| match entity {
+                serde_json::Value::String(name) => Some(SummaryEntity { name: name.trim().to_string(), kind: "other".to_string() }),
+                entity => Some(SummaryEntity {
+                    name: entity.get("name")?.as_str()?.trim().to_string(),
+                    kind: entity.get("kind").or_else(|| entity.get("type")).and_then(|k| k.as_str()).unwrap_or("other").to_lowercase(),
+                }),
+            })
+            .filter(|entity| !entity.name.is_empty())
+            .collect();
+        return StructuredSummary {
+            headline: text("headline"),
+            summary: text("summary"),
+            bullets: list("bullets"),
+            entities,
+            questions: list("questions"),
+        };
+    }
+
+    let mut output = StructuredSummary::default();
+    let mut paragraphs = Vec::new();
+    for line in response.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with("```")) {
+        if let Some(bullet) = ["- ", "* ", "• "].iter().find_map(|p| line.strip_prefix(p)) {
+            output.bullets.push(bullet.trim().to_string());
+        } else if output.headline.is_empty() {
+            output.headline = line.trim_start_matches('#').trim().trim_matches('*').to_string();
+        } else {
+            paragraphs.push(line);
+        }
+    }
+    output.summary = paragraphs.join("\n\n");
+    output
+}
+
+fn hex_digest(text: &str) -> String {
+    Sha256::digest(text.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// Run prompts a few at a time; results come back in prompt order however the calls interleave.
+// Callbacks get 1-based indexes.
+async fn map_parallel(
+    app: &tauri::AppHandle,
+    task: &'static str,
+    prompts: Vec<String>,
+    concurrency: usize,
+    on_start: impl Fn(usize) + Send + Sync + 'static,
+    on_done: impl Fn(usize, &Result<String, String>) + Send + Sync + 'static,
+) -> Vec<Result<String, String>> {
+    let permits = Arc::new(tokio::sync::Semaphore::new(concurrency));
+    let (on_start, on_done) = (Arc::new(on_start), Arc::new(on_done));
+    let handles: Vec<_> = prompts
+        .into_iter()
+        .enumerate()
+        .map(|(i, prompt)| {
+            let (app, permits, on_start, on_done) = (app.clone(), permits.clone(), on_start.clone(), on_done.clone());
+            // Spawned so one chunk's blocking model call doesn't hold up the others
+            tauri::async_runtime::spawn(async move {
+                let _permit = permits.acquire_owned().await.ok();
+                on_start(i + 1);
+                let ai = app.state::<AIService>();
+                let result = ai
+                    .complete_task(task, &prompt)
+                    .await
+                    .map(|s| s.trim().to_string())
+                    .map_err(|e| e.to_string())
+                    .and_then(|s| if s.is_empty() { Err("empty response".to_string()) } else { Ok(s) });
+                on_done(i + 1, &result);
+                result
+            })
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(handles.len());
+    for handle in handles {
+        results.push(handle.await.unwrap_or_else(|e| Err(e.to_string())));
+    }
+    results
+}
+
+async fn stream(app: &tauri::AppHandle, prompt: &str, emit: &(impl Fn(SummaryEvent) + Send + Sync)) -> Result<String, SummaryError> {
+    let ai = app.state::<AIService>();
+    let summary = ai
+        .complete_stream_markdown(prompt, |chunk| {
+            emit(SummaryEvent::SummaryToken { token: chunk.token, events: chunk.events });
+        })
+        .await
+        .map_err(|e| SummaryError::AIError(e.to_string()))?;
+    Ok(summary.trim().to_string())
+}
+
+// Consecutive parts joined into groups of at most `max_tokens` (a single oversized part stays alone)
+fn group_parts(parts: &[String], max_tokens: usize) -> Vec<String> {
+    let mut groups: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for part in parts {
+        if !current.is_empty() && estimate_tokens(&current) + 1 + estimate_tokens(part) > max_tokens {
+            groups.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push_str("\n\n");
+        }
+        current.push_str(part);
+    }
+    if !current.is_empty() {
+        groups.push(current);
+    }
+    groups
+}
+
+#[derive(Debug, Clone)]
+pub enum SummaryError {
+    EmptyText,
+    UnknownPreset(String),
+    NoPartials(String),
+    AIError(String),
+}
+
+impl std::fmt::Display for SummaryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SummaryError::EmptyText => write!(f, "Nothing to summarize"),
+            SummaryError::UnknownPreset(preset) => {
+                write!(f, "Unknown summary preset '{}' (use tldr, brief, detailed, eli5, or exam-notes)", preset)
+            }
+            SummaryError::NoPartials(msg) => write!(f, "No chunk could be summarized: {}", msg),
+            SummaryError::AIError(msg) => write!(f, "AI error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SummaryError {}
@@ -0,0 +1,206 @@
+// Tab Discard - Puts idle background tabs to sleep using MemoryGuard's freeze policy
+// The frontend snapshots a tab's state (scroll, forms, Redix runtime) before it sleeps and gets it back on activation
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+
+use crate::browser::TabManager;
+use crate::stability::MemoryGuard;
+
+// The frontend gets this long to hand over a snapshot before the tab is discarded without one
+const SNAPSHOT_TIMEOUT: Duration = Duration::from_secs(10);
+// Snapshots live in memory; anything larger belongs in the Redix store, not here
+const MAX_SNAPSHOT_BYTES: usize = 256 * 1024;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct DiscardStats {
+    pub discarded: u64,
+    pub restored: u64,
+    pub snapshots_taken: u64,
+    pub snapshots_missed: u64,         // Discarded without a snapshot (no answer in time)
+    pub pending: usize,
+    pub sleeping: usize,
+    pub last_discard_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct DiscardRequest {
+    pub tab_id: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct TabDiscarded {
+    pub tab_id: String,
+    pub has_snapshot: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct TabRestored {
+    pub tab_id: String,
+    pub snapshot: Option<serde_json::Value>,
+}
+
+#[derive(Default)]
+struct Inner {
+    pending: HashMap<String, Instant>, // Snapshot requested, waiting for tabs_discard_ack
+    snapshots: HashMap<String, serde_json::Value>,
+    discarded: HashSet<String>,
+    stats: DiscardStats,
+}
+
+#[derive(Clone, Default)]
+pub struct TabDiscarder {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl TabDiscarder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // One pass: discard tabs whose snapshot never arrived, then ask for snapshots of newly idle tabs
+    pub fn tick(&self, app: &tauri::AppHandle, tabs: &TabManager, guard: &MemoryGuard) {
+        let expired: Vec<String> = {
+            let inner = self.inner.lock().unwrap();
+            inner
+                .pending
+                .iter()
+                .filter(|(_, requested)| requested.elapsed() >= SNAPSHOT_TIMEOUT)
+                .map(|(id, _)| id.clone())
+                .collect()
+        };
+        for tab_id in expired {
+            self.discard(app, tabs, &tab_id, None);
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        for tab in tabs.list_tabs() {
+            // Each tab follows its own mode's profile, not the active one
+            let profile = crate::modes::profile_for(&tab.app_mode);
+            if tab.is_pinned || profile.keep_alive {
+                continue;
+            }
+            let idle = match profile.freeze_after_minutes {
+                Some(minutes) => !tab.is_active && now - tab.last_active_at > i64::from(minutes) * 60,
+                None => guard.should_freeze_tab(tab.last_active_at, tab.is_active),
+            };
+            if !idle {
+                continue;
+            }
+            let _ = self.request(app, tabs, &tab.id);
+        }
+    }
+
+    // Ask the frontend for a snapshot; the tab is discarded on tabs_discard_ack or after SNAPSHOT_TIMEOUT
+    pub fn request(&self, app: &tauri::AppHandle, tabs: &TabManager, tab_id: &str) -> Result<bool, String> {
+        let tab = tabs.get_tab(tab_id).ok_or_else(|| format!("Tab {} not found", tab_id))?;
+        if tab.is_active || tab.is_sleeping {
+            return Ok(false);
+        }
+        let mut inner = self.inner.lock().unwrap();
+        if inner.pending.contains_key(tab_id) {
+            return Ok(false);
+        }
+        inner.pending.insert(tab.id.clone(), Instant::now());
+        drop(inner);
+        let _ = app.emit("tab-discard-request", DiscardRequest { tab_id: tab.id, url: tab.url });
+        Ok(true)
+    }
+
+    // The frontend's answer to "tab-discard-request" (snapshot None if it had nothing to save)
+    pub fn acknowledge(&self, app: &tauri::AppHandle, tabs: &TabManager, tab_id: &str, snapshot: Option<serde_json::Value>) -> Result<(), String> {
+        if let Some(snapshot) = &snapshot {
+            let size = serde_json::to_vec(snapshot).map(|v| v.len()).unwrap_or(0);
+            if size > MAX_SNAPSHOT_BYTES {
+                return Err(format!("Tab snapshot is too large ({} KB, max {} KB)", size / 1024, MAX_SNAPSHOT_BYTES / 1024));
+            }
+        }
+        let tab = tabs.get_tab(tab_id).ok_or_else(|| format!("Tab {} not found", tab_id))?;
+        // The user may have switched to the tab while it was being snapshotted
+        if tab.is_active {
+            self.inner.lock().unwrap().pending.remove(tab_id);
+            return Ok(());
+        }
+        self.discard(app, tabs, tab_id, snapshot);
+        Ok(())
+    }
+
+    fn discard(&self, app: &tauri::AppHandle, tabs: &TabManager, tab_id: &str, snapshot: Option<serde_json::Value>) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.pending.remove(tab_id);
+        let still_idle = tabs.get_tab(tab_id).map(|t| !t.is_active && !t.is_sleeping).unwrap_or(false);
+        if !still_idle || tabs.freeze_tab(tab_id).is_err() {
+            return;
+        }
+
+        let has_snapshot = snapshot.is_some();
+        match snapshot {
+            Some(snapshot) => {
+                inner.snapshots.insert(tab_id.to_string(), snapshot);
+                inner.stats.snapshots_taken += 1;
+            }
+            None => inner.stats.snapshots_missed += 1,
+        }
+        inner.discarded.insert(tab_id.to_string());
+        inner.stats.discarded += 1;
+        inner.stats.last_discard_at = Some(chrono::Utc::now().timestamp());
+        drop(inner);
+
+        tracing::debug!(target: "stability", "Tab discard: Discarded {} (snapshot: {})", tab_id, has_snapshot);
+        let _ = app.emit("tab-discarded", TabDiscarded { tab_id: tab_id.to_string(), has_snapshot });
+    }
+
+    // Called when a tab becomes active; hands its snapshot back if it was discarded
+    pub fn restore(&self, app: &tauri::AppHandle, tab_id: &str) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.pending.remove(tab_id);
+        if !inner.discarded.remove(tab_id) {
+            return;
+        }
+        let snapshot = inner.snapshots.remove(tab_id);
+        inner.stats.restored += 1;
+        drop(inner);
+        let _ = app.emit("tab-restored", TabRestored { tab_id: tab_id.to_string(), snapshot });
+    }
+
+    // Drop state for a closed tab
+    pub fn forget(&self, tab_id: &str) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.pending.remove(tab_id);
+        inner.snapshots.remove(tab_id);
+        inner.discarded.remove(tab_id);
+    }
+
+    pub fn stats(&self, tabs: &TabManager) -> DiscardStats {
+        let inner = self.inner.lock().unwrap();
+        DiscardStats {
+            pending: inner.pending.len(),
+            sleeping: tabs.list_tabs().iter().filter(|t| t.is_sleeping).count(),
+            ..inner.stats.clone()
+        }
+    }
+
+    pub fn spawn(&self, app: tauri::AppHandle, tabs: Arc<TabManager>, interval: Duration) {
+        let discarder = self.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let guard = app.state::<MemoryGuard>();
+                discarder.tick(&app, &tabs, &guard);
+            }
+        });
+    }
+}
@@ -0,0 +1,93 @@
+// Tab Metrics - Per-tab memory, CPU time, and network bytes for the task-manager view
+// Webviews don't expose per-tab numbers to the backend: the frontend reports heap and CPU, the proxy layer counts bytes
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use crate::browser::TabManager;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct TabMetrics {
+    pub tab_id: String,
+    pub title: String,
+    pub url: String,
+    pub memory_bytes: u64,
+    pub memory_estimated: bool,        // No report from the webview yet; typical figure for an awake/sleeping tab
+    pub cpu_time_ms: Option<u64>,      // None where the webview doesn't expose it
+    pub network_rx_bytes: u64,
+    pub network_tx_bytes: u64,
+    pub last_active_at: i64,
+    pub is_active: bool,
+    pub is_sleeping: bool,
+    pub is_pinned: bool,
+}
+
+#[derive(Default, Clone)]
+struct Usage {
+    heap_bytes: Option<u64>,
+    cpu_time_ms: Option<u64>,
+    rx_bytes: u64,
+    tx_bytes: u64,
+}
+
+fn registry() -> &'static Mutex<HashMap<String, Usage>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<String, Usage>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+// Latest figures sampled by the tab's webview (performance.memory, long-task time)
+pub fn report_usage(tab_id: &str, heap_bytes: Option<u64>, cpu_time_ms: Option<u64>) {
+    let mut registry = registry().lock().unwrap();
+    let usage = registry.entry(tab_id.to_string()).or_default();
+    if heap_bytes.is_some() {
+        usage.heap_bytes = heap_bytes;
+    }
+    if cpu_time_ms.is_some() {
+        usage.cpu_time_ms = cpu_time_ms;
+    }
+}
+
+// Called by the proxy layer for traffic it can attribute to a tab
+pub fn record_network(tab_id: &str, rx_bytes: u64, tx_bytes: u64) {
+    let mut registry = registry().lock().unwrap();
+    let usage = registry.entry(tab_id.to_string()).or_default();
+    usage.rx_bytes = usage.rx_bytes.saturating_add(rx_bytes);
+    usage.tx_bytes = usage.tx_bytes.saturating_add(tx_bytes);
+}
+
+pub fn forget(tab_id: &str) {
+    registry().lock().unwrap().remove(tab_id);
+}
+
+// Heaviest tabs first
+pub fn collect(tabs: &TabManager) -> Vec<TabMetrics> {
+    let registry = registry().lock().unwrap();
+    let mut metrics: Vec<TabMetrics> = tabs
+        .list_tabs()
+        .into_iter()
+        .map(|tab| {
+            let usage = registry.get(&tab.id).cloned().unwrap_or_default();
+            // A sleeping tab's last heap report is stale; its page has been unloaded
+            let reported = usage.heap_bytes.filter(|_| !tab.is_sleeping);
+            TabMetrics {
+                memory_bytes: reported.unwrap_or_else(|| crate::resources::estimated_tab_bytes(tab.is_sleeping)),
+                memory_estimated: reported.is_none(),
+                cpu_time_ms: usage.cpu_time_ms,
+                network_rx_bytes: usage.rx_bytes,
+                network_tx_bytes: usage.tx_bytes,
+                last_active_at: tab.last_active_at,
+                is_active: tab.is_active,
+                is_sleeping: tab.is_sleeping,
+                is_pinned: tab.is_pinned,
+                tab_id: tab.id,
+                title: tab.title,
+                url: tab.url,
+            }
+        })
+        .collect();
+    metrics.sort_by(|a, b| b.memory_bytes.cmp(&a.memory_bytes));
+    metrics
+}
@@ -0,0 +1,368 @@
+// Tables - Typed tables detected in PDFs, HTML pages, and spreadsheets
+// PDF tables come from glyph positions (rows by baseline, columns by whitespace gaps); recent tables are kept for CSV export
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock};
+
+use pdf_extract::{MediaBox, OutputDev, OutputError, Transform};
+
+// Rows kept per table; total_rows still counts the rest
+const MAX_ROWS: usize = 2000;
+const MAX_COLUMNS: usize = 50;
+// Tables remembered for table_export_csv
+const RECENT_CAPACITY: usize = 200;
+// A PDF table needs a header line plus at least two rows
+const MIN_PDF_LINES: usize = 3;
+// Gaps wider than this (in font sizes) separate cells on a PDF line
+const CELL_GAP: f64 = 1.0;
+// Lines further apart than this (in font sizes) end a PDF table
+const ROW_GAP: f64 = 3.0;
+// Longer cells read as prose; side-by-side text columns otherwise look like a two-column table
+const MAX_MEDIAN_CELL_CHARS: usize = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnType {
+    Number,                            // Including currency, percentages, and (negative) accounting values
+    Date,
+    Boolean,
+    Text,
+    Empty,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(tag = "kind", rename_all = "lowercase", rename_all_fields = "camelCase")]
+pub enum TableSource {
+    Html { url: Option<String>, index: usize },   // index among the page's <table> elements
+    Pdf { page: u32 },
+    Sheet { name: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct Table {
+    pub id: String,                    // Content hash, stable for the same table at the same place
+    pub source: TableSource,
+    pub caption: Option<String>,
+    pub headers: Vec<String>,
+    pub column_types: Vec<ColumnType>,
+    pub rows: Vec<Vec<String>>,        // Every row is headers.len() wide
+    pub total_rows: usize,             // Before the MAX_ROWS cut
+}
+
+impl Table {
+    // Pads ragged rows, names unnamed columns, and infers column types. Without headers every row is data.
+    pub fn new(source: TableSource, caption: Option<String>, headers: Vec<String>, rows: Vec<Vec<String>>) -> Self {
+        let width = rows.iter().map(|r| r.len()).chain([headers.len()]).max().unwrap_or(0).min(MAX_COLUMNS);
+        let headers: Vec<String> = (0..width)
+            .map(|i| match headers.get(i).map(|h| h.trim()) {
+                Some(h) if !h.is_empty() => h.to_string(),
+                _ => format!("Column {}", i + 1),
+            })
+            .collect();
+        let total_rows = rows.len();
+        let rows: Vec<Vec<String>> = rows
+            .into_iter()
+            .take(MAX_ROWS)
+            .map(|mut row| {
+                row.resize(width, String::new());
+                row
+            })
+            .collect();
+        let column_types = (0..width).map(|i| infer_type(rows.iter().map(|r| r[i].as_str()))).collect();
+
+        let mut hasher = Sha256::new();
+        hasher.update(serde_json::to_string(&source).unwrap_or_default());
+        for cell in headers.iter().chain(rows.iter().flatten()) {
+            hasher.update(cell.as_bytes());
+            hasher.update([0]);
+        }
+        let id = hasher.finalize()[..8].iter().map(|b| format!("{:02x}", b)).collect();
+
+        Self { id, source, caption, headers, column_types, rows, total_rows }
+    }
+
+    // RFC 4180 CSV, header line first
+    pub fn to_csv(&self) -> String {
+        std::iter::once(&self.headers)
+            .chain(&self.rows)
+            .map(|row| row.iter().map(|cell| csv_field(cell)).collect::<Vec<_>>().join(","))
+            .map(|line| line + "\r\n")
+            .collect()
+    }
+}
+
+fn csv_field(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+// The most specific type every non-empty cell fits
+fn infer_type<'a>(cells: impl Iterator<Item = &'a str>) -> ColumnType {
+    let cells: Vec<&str> = cells.map(str::trim).filter(|c| !c.is_empty()).collect();
+    if cells.is_empty() {
+        ColumnType::Empty
+    } else if cells.iter().all(|c| is_number(c)) {
+        ColumnType::Number
+    } else if cells.iter().all(|c| is_date(c)) {
+        ColumnType::Date
+    } else if cells.iter().all(|c| matches!(c.to_lowercase().as_str(), "true" | "false" | "yes" | "no")) {
+        ColumnType::Boolean
+    } else {
+        ColumnType::Text
+    }
+}
+
+fn is_number(cell: &str) -> bool {
+    let cleaned: String = cell
+        .trim_matches(|c| matches!(c, '(' | ')'))
+        .chars()
+        .filter(|c| !matches!(c, ',' | '$' | '€' | '£' | '¥' | '₹' | '%' | ' ' | '\u{a0}'))
+        .collect();
+    let cleaned = cleaned.trim_start_matches(['+', '-', '−']);
+    !cleaned.is_empty() && cleaned.chars().any(|c| c.is_ascii_digit()) && cleaned.parse::<f64>().is_ok()
+}
+
+fn is_date(cell: &str) -> bool {
+    const FORMATS: &[&str] = &["%Y-%m-%d", "%d/%m/%Y", "%m/%d/%Y", "%d.%m.%Y", "%b %d, %Y", "%B %d, %Y", "%d %b %Y", "%d %B %Y"];
+    FORMATS.iter().any(|f| chrono::NaiveDate::parse_from_str(cell, f).is_ok())
+        || chrono::DateTime::parse_from_rfc3339(cell).is_ok()
+}
+
+fn recent() -> &'static Mutex<VecDeque<Table>> {
+    static RECENT: OnceLock<Mutex<VecDeque<Table>>> = OnceLock::new();
+    RECENT.get_or_init(|| Mutex::new(VecDeque::with_capacity(RECENT_CAPACITY)))
+}
+
+// Keep tables handed to the frontend so they can be exported by id; oldest are dropped first
+pub fn remember(tables: &[Table]) {
+    let mut recent = recent().lock().unwrap();
+    for table in tables {
+        recent.retain(|t| t.id != table.id);
+        if recent.len() == RECENT_CAPACITY {
+            recent.pop_front();
+        }
+        recent.push_back(table.clone());
+    }
+}
+
+pub fn get(id: &str) -> Option<Table> {
+    recent().lock().unwrap().iter().find(|t| t.id == id).cloned()
+}
+
+pub fn export_csv(id: &str) -> Result<String, TableError> {
+    get(id).map(|t| t.to_csv()).ok_or_else(|| TableError::NotFound(id.to_string()))
+}
+
+// Sheet rows as a table, the first non-empty row as headers
+pub fn from_sheet(name: &str, mut rows: Vec<Vec<String>>) -> Table {
+    let headers = if rows.is_empty() { Vec::new() } else { rows.remove(0) };
+    Table::new(TableSource::Sheet { name: name.to_string() }, None, headers, rows)
+}
+
+// ============================================================================
+// PDF LAYOUT DETECTION
+// ============================================================================
+
+struct Glyph {
+    x: f64,
+    end: f64,
+    y: f64,                            // Baseline, PDF space (grows upward)
+    size: f64,
+    text: String,
+}
+
+// Collects positioned glyphs per page from pdf_extract's content stream walk
+#[derive(Default)]
+struct GlyphCollector {
+    pages: Vec<(u32, Vec<Glyph>)>,
+}
+
+impl OutputDev for GlyphCollector {
+    fn begin_page(&mut self, page_num: u32, _media_box: &MediaBox, _art_box: Option<(f64, f64, f64, f64)>) -> Result<(), OutputError> {
+        self.pages.push((page_num, Vec::new()));
+        Ok(())
+    }
+
+    fn end_page(&mut self) -> Result<(), OutputError> {
+        Ok(())
+    }
+
+    fn output_character(&mut self, trm: &Transform, width: f64, _spacing: f64, font_size: f64, char: &str) -> Result<(), OutputError> {
+        if char.trim().is_empty() {
+            return Ok(());                 // Spacing comes from positions
+        }
+        let size = font_size * (trm.m11 * trm.m22 - trm.m12 * trm.m21).abs().sqrt();
+        if let Some((_, glyphs)) = self.pages.last_mut() {
+            glyphs.push(Glyph { x: trm.m31, end: trm.m31 + width * size, y: trm.m32, size, text: char.to_string() });
+        }
+        Ok(())
+    }
+
+    fn begin_word(&mut self) -> Result<(), OutputError> {
+        Ok(())
+    }
+
+    fn end_word(&mut self) -> Result<(), OutputError> {
+        Ok(())
+    }
+
+    fn end_line(&mut self) -> Result<(), OutputError> {
+        Ok(())
+    }
+}
+
+// A run of text on one line with no cell-sized gap inside it
+struct Segment {
+    x0: f64,
+    x1: f64,
+    text: String,
+}
+
+struct Line {
+    y: f64,
+    size: f64,
+    segments: Vec<Segment>,
+}
+
+// Tables on every page of a PDF. Scanned pages have no glyphs and yield nothing.
+pub fn from_pdf(bytes: &[u8]) -> Result<Vec<Table>, TableError> {
+    let mut document = pdf_extract::Document::load_mem(bytes).map_err(|e| TableError::Parse(e.to_string()))?;
+    if document.is_encrypted() {
+        document.decrypt("").map_err(|e| TableError::Parse(e.to_string()))?;
+    }
+    let mut collector = GlyphCollector::default();
+    pdf_extract::output_doc(&document, &mut collector).map_err(|e| TableError::Parse(e.to_string()))?;
+    Ok(collector
+        .pages
+        .into_iter()
+        .flat_map(|(page, glyphs)| page_tables(page, lines(glyphs)))
+        .collect())
+}
+
+// Group glyphs into lines (top to bottom) and each line into segments (left to right)
+fn lines(mut glyphs: Vec<Glyph>) -> Vec<Line> {
+    glyphs.sort_by(|a, b| b.y.total_cmp(&a.y).then(a.x.total_cmp(&b.x)));
+    let mut rows: Vec<Vec<Glyph>> = Vec::new();
+    for glyph in glyphs {
+        match rows.last_mut() {
+            Some(row) if (row[0].y - glyph.y).abs() < row[0].size.max(glyph.size) * 0.5 => row.push(glyph),
+            _ => rows.push(vec![glyph]),
+        }
+    }
+
+    rows.into_iter()
+        .map(|mut row| {
+            row.sort_by(|a, b| a.x.total_cmp(&b.x));
+            let size = row.iter().map(|g| g.size).sum::<f64>() / row.len() as f64;
+            let mut segments: Vec<Segment> = Vec::new();
+            for glyph in &row {
+                match segments.last_mut() {
+                    Some(segment) if glyph.x - segment.x1 < size * CELL_GAP => {
+                        if glyph.x - segment.x1 > size * 0.15 {
+                            segment.text.push(' ');
+                        }
+                        segment.text.push_str(&glyph.text);
+                        segment.x1 = segment.x1.max(glyph.end);
+                    }
+                    _ => segments.push(Segment { x0: glyph.x, x1: glyph.end, text: glyph.text.clone() }),
+                }
+            }
+            Line { y: row[0].y, size, segments }
+        })
+        .collect()
+}
+
+// Runs of multi-segment lines whose segments line up into the same columns
+fn page_tables(page: u32, lines: Vec<Line>) -> Vec<Table> {
+    let mut tables = Vec::new();
+    let mut block: Vec<Line> = Vec::new();
+    for line in lines {
+        let continues = block.last().is_some_and(|last| last.y - line.y < ROW_GAP * last.size.max(line.size));
+        if line.segments.len() >= 2 && (block.is_empty() || continues) {
+            block.push(line);
+            continue;
+        }
+        tables.extend(block_table(page, std::mem::take(&mut block)));
+        if line.segments.len() >= 2 {
+            block.push(line);
+        }
+    }
+    tables.extend(block_table(page, block));
+    tables
+}
+
+fn block_table(page: u32, block: Vec<Line>) -> Option<Table> {
+    if block.len() < MIN_PDF_LINES {
+        return None;
+    }
+    // Columns are the x ranges left after merging every overlapping segment (whitespace gap projection)
+    let size = block.iter().map(|l| l.size).sum::<f64>() / block.len() as f64;
+    let mut spans: Vec<(f64, f64)> = block.iter().flat_map(|l| l.segments.iter().map(|s| (s.x0, s.x1))).collect();
+    spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut columns: Vec<(f64, f64)> = Vec::new();
+    for (x0, x1) in spans {
+        match columns.last_mut() {
+            Some(column) if x0 <= column.1 + size * 0.3 => column.1 = column.1.max(x1),
+            _ => columns.push((x0, x1)),
+        }
+    }
+    if columns.len() < 2 || columns.len() > MAX_COLUMNS {
+        return None;
+    }
+
+    let grid: Vec<Vec<String>> = block
+        .iter()
+        .map(|line| {
+            let mut cells = vec![String::new(); columns.len()];
+            for segment in &line.segments {
+                let column = columns.iter().position(|c| segment.x0 >= c.0 - size * 0.3 && segment.x0 <= c.1).unwrap_or(0);
+                if !cells[column].is_empty() {
+                    cells[column].push(' ');
+                }
+                cells[column].push_str(&segment.text);
+            }
+            cells
+        })
+        .collect();
+
+    let mut lengths: Vec<usize> = grid.iter().flatten().filter(|c| !c.is_empty()).map(|c| c.chars().count()).collect();
+    lengths.sort_unstable();
+    if lengths.get(lengths.len() / 2).is_some_and(|median| *median > MAX_MEDIAN_CELL_CHARS) {
+        return None;
+    }
+    // Most rows should fill most columns; sparse grids are usually captions next to figures
+    let dense = grid.iter().filter(|row| row.iter().filter(|c| !c.is_empty()).count() * 2 >= columns.len()).count();
+    if dense * 3 < grid.len() * 2 {
+        return None;
+    }
+
+    let mut rows = grid;
+    let headers = rows.remove(0);
+    Some(Table::new(TableSource::Pdf { page }, None, headers, rows))
+}
+
+#[derive(Debug, Clone)]
+pub enum TableError {
+    Parse(String),
+    NotFound(String),
+}
+
+impl std::fmt::Display for TableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TableError::Parse(msg) => write!(f, "Could not read tables: {}", msg),
+            TableError::NotFound(id) => write!(f, "Table '{}' not found; extract it again", id),
+        }
+    }
+}
+
+impl std::error::Error for TableError {}
@@ -0,0 +1,109 @@
+// Text Utilities - Sentence splitting and tokenizing shared by AI features
+// Handles Latin and Devanagari sentence terminators (। ॥)
+
+// Common abbreviations that end with a period but not a sentence
+const ABBREVIATIONS: &[&str] = &[
+    "mr", "mrs", "ms", "dr", "prof", "sr", "jr", "st", "vs", "etc", "e.g", "i.e", "inc", "ltd",
+    "co", "no", "fig", "approx", "u.s", "u.k",
+];
+
+// Split text into trimmed sentences; line breaks always end a sentence
+pub fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    for line in text.lines() {
+        let chars: Vec<char> = line.chars().collect();
+        let mut start = 0;
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            let terminal = matches!(c, '.' | '!' | '?' | '।' | '॥');
+            if terminal {
+                // Swallow runs like "?!" or "..." and closing quotes/brackets
+                let mut end = i + 1;
+                while end < chars.len() && matches!(chars[end], '.' | '!' | '?' | '"' | '\'' | ')' | '”' | '’') {
+                    end += 1;
+                }
+                let at_boundary = end >= chars.len() || chars[end].is_whitespace();
+                if at_boundary && !(c == '.' && is_abbreviation_or_number(&chars[start..i], chars.get(end + 1))) {
+                    push_sentence(&mut sentences, &chars[start..end]);
+                    start = end;
+                }
+                i = end;
+            } else {
+                i += 1;
+            }
+        }
+        push_sentence(&mut sentences, &chars[start..]);
+    }
+    sentences
+}
+
+fn push_sentence(sentences: &mut Vec<String>, chars: &[char]) {
+    let sentence: String = chars.iter().collect();
+    let sentence = sentence.trim();
+    if !sentence.is_empty() {
+        sentences.push(sentence.to_string());
+    }
+}
+
+fn is_abbreviation_or_number(before: &[char], next_word_start: Option<&char>) -> bool {
+    let word: String = before
+        .iter()
+        .rev()
+        .take_while(|c| !c.is_whitespace())
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    let word = word.trim_start_matches(['(', '"', '\'']).to_lowercase();
+    if ABBREVIATIONS.contains(&word.as_str()) {
+        return true;
+    }
+    // Single initials ("J. Smith") followed by a capitalised word
+    word.chars().count() == 1
+        && word.chars().all(char::is_alphabetic)
+        && next_word_start.map(|c| c.is_uppercase()).unwrap_or(false)
+}
+
+// Rough model token count when the provider doesn't report one, shaped like BPE output:
+// a word is one token (long words more), digits go in threes, each symbol is its own token,
+// a line break (with its indentation) is one, and other scripts (Devanagari, CJK) are denser
+pub fn estimate_tokens(text: &str) -> usize {
+    let mut tokens = 0;
+    let mut other = 0usize;
+    let (mut letters, mut digits) = (0usize, 0usize);
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            'a'..='z' | 'A'..='Z' => letters += 1,
+            '0'..='9' => digits += 1,
+            '\n' => {
+                tokens += 1;
+                while chars.next_if(|c| *c == ' ' || *c == '\t').is_some() {}
+            }
+            c if c.is_whitespace() => {}
+            c if c.is_ascii() => tokens += 1,
+            _ => other += 1,
+        }
+        let next_letter = chars.peek().is_some_and(char::is_ascii_alphabetic);
+        let next_digit = chars.peek().is_some_and(char::is_ascii_digit);
+        if letters > 0 && !next_letter {
+            tokens += ((letters + 4) / 8).max(1);
+            letters = 0;
+        }
+        if digits > 0 && !next_digit {
+            tokens += digits.div_ceil(3);
+            digits = 0;
+        }
+    }
+    tokens + (other * 2).div_ceil(3)
+}
+
+// Lowercased alphanumeric tokens, dropping very short words
+pub fn content_words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() > 2)
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
@@ -0,0 +1,961 @@
+// Tools - Registry of actions the agent can call, each described by a JSON schema
+// Calls go through one dispatcher: the tool must be enabled, its arguments must match the schema, and its permission gate must pass
+
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+
+use crate::apikeys::{ApiKeyStore, Provider};
+use crate::browser::TabManager;
+use crate::db::Database;
+use crate::http;
+use crate::local_index::LocalIndex;
+use crate::notes::Note;
+use crate::privacy::PrivacyEnforcer;
+use crate::stability::MemoryGuard;
+
+pub const NAMES: &[&str] = &["open_tab", "search", "fetch_page", "get_quote", "save_note", "run_calculation", "get_active_tab_content", "search_history", "list_workflows", "trigger_workflow"];
+
+const SEARCH_LIMIT: u64 = 5;
+const PAGE_CHARS: u64 = 4000;
+const MAX_PAGE_CHARS: u64 = 20_000;
+const QUOTE_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_EXPRESSION_LEN: usize = 500;
+const MAX_EXPRESSION_DEPTH: usize = 64;
+
+// What a tool may touch; checked before every call
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub enum Permission {
+    ReadOnly,                          // Pure computation
+    Network,                           // Remote requests; refused while offline
+    WritesData,                        // Saves to the database; refused when the privacy mode forbids disk writes
+    OpensTabs,                         // Opens browser tabs; refused at the tab limit
+}
+
+// How much harm a wrong call could do; decides whether it waits for the user's approval
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum Risk {
+    Low,                               // Reads only
+    Medium,                            // Changes browser state or user data, or sends a URL the model chose
+    High,                              // Acts outside the browser
+}
+
+impl Risk {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Risk::Low => "low",
+            Risk::Medium => "medium",
+            Risk::High => "high",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        [Risk::Low, Risk::Medium, Risk::High].into_iter().find(|r| r.as_str() == value)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ToolSpec {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub permission: Permission,
+    pub risk: Risk,
+    pub parameters: Value,             // JSON schema for the arguments object
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ToolInfo {
+    #[serde(flatten)]
+    pub spec: ToolSpec,
+    pub enabled: bool,                 // False when listed in [tools] disabled
+}
+
+// One dispatched call, as reported back to the caller and the agent transcript
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ToolCallRecord {
+    pub tool: String,
+    pub arguments: Value,
+    pub ok: bool,
+    pub output: Option<Value>,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+pub trait Tool: Send + Sync {
+    fn spec(&self) -> ToolSpec;
+
+    // Arguments have already been validated against spec().parameters
+    fn call<'a>(&'a self, app: &'a tauri::AppHandle, args: Value) -> BoxFuture<'a, Result<Value, ToolError>>;
+}
+
+pub struct ToolRegistry {
+    tools: Vec<Box<dyn Tool>>,
+}
+
+pub fn registry() -> &'static ToolRegistry {
+    static REGISTRY: OnceLock<ToolRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(|| ToolRegistry {
+        tools: vec![
+            Box::new(OpenTab),
+            Box::new(Search),
+            Box::new(FetchPage),
+            Box::new(GetQuote { http: http::Client::new(QUOTE_TIMEOUT).with_purpose("quote") }),
+            Box::new(SaveNote),
+            Box::new(RunCalculation),
+            Box::new(GetActiveTabContent),
+            Box::new(SearchHistory),
+            Box::new(ListWorkflows),
+            Box::new(TriggerWorkflow),
+        ],
+    })
+}
+
+impl ToolRegistry {
+    pub fn list(&self) -> Vec<ToolInfo> {
+        let disabled = crate::config::current().tools.disabled;
+        self.tools
+            .iter()
+            .map(|tool| {
+                let spec = tool.spec();
+                let enabled = !disabled.iter().any(|d| d == spec.name);
+                ToolInfo { spec, enabled }
+            })
+            .collect()
+    }
+
+    // Enabled tools as the compact JSON the agent prompt lists
+    pub fn prompt_schema(&self) -> String {
+        self.list()
+            .into_iter()
+            .filter(|info| info.enabled)
+            .map(|info| {
+                json!({
+                    "name": info.spec.name,
+                    "description": info.spec.description,
+                    "parameters": info.spec.parameters,
+                })
+                .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    // Enabled, known, and given valid arguments; returns the spec and the arguments as they'll be passed
+    pub fn check(&self, name: &str, arguments: Value) -> Result<(ToolSpec, Value), ToolError> {
+        let spec = self
+            .tools
+            .iter()
+            .map(|t| t.spec())
+            .find(|spec| spec.name == name)
+            .ok_or_else(|| ToolError::Unknown(name.to_string()))?;
+        if crate::config::current().tools.disabled.iter().any(|d| d == spec.name) {
+            return Err(ToolError::Disabled(name.to_string()));
+        }
+        // Models often send null for a tool without arguments
+        let arguments = if arguments.is_null() { json!({}) } else { arguments };
+        validate(&spec.parameters, &arguments, "arguments").map_err(ToolError::InvalidArguments)?;
+        Ok((spec, arguments))
+    }
+
+    pub async fn call(&self, app: &tauri::AppHandle, name: &str, arguments: Value) -> Result<Value, ToolError> {
+        let (spec, arguments) = self.check(name, arguments)?;
+        check_permission(app, spec.permission)?;
+        let tool = self.tools.iter().find(|t| t.spec().name == name).ok_or_else(|| ToolError::Unknown(name.to_string()))?;
+
+        tracing::debug!(target: "ai", "Tools: Calling {}", name);
+        tool.call(app, arguments).await
+    }
+
+    // Dispatch and time a call, keeping failures as data for the model to read
+    pub async fn call_recorded(&self, app: &tauri::AppHandle, name: &str, arguments: Value) -> ToolCallRecord {
+        let started = Instant::now();
+        let result = self.call(app, name, arguments.clone()).await;
+        let duration_ms = started.elapsed().as_millis() as u64;
+        match result {
+            Ok(output) => ToolCallRecord { tool: name.to_string(), arguments, ok: true, output: Some(output), error: None, duration_ms },
+            Err(e) => ToolCallRecord { tool: name.to_string(), arguments, ok: false, output: None, error: Some(e.to_string()), duration_ms },
+        }
+    }
+}
+
+fn check_permission(app: &tauri::AppHandle, permission: Permission) -> Result<(), ToolError> {
+    match permission {
+        Permission::ReadOnly => Ok(()),
+        Permission::Network => {
+            if crate::connectivity::is_offline() {
+                return Err(ToolError::Offline);
+            }
+            Ok(())
+        }
+        Permission::WritesData => app
+            .state::<Mutex<PrivacyEnforcer>>()
+            .lock()
+            .unwrap()
+            .enforce_disk_write()
+            .map_err(|e| ToolError::Denied(e.to_string())),
+        Permission::OpensTabs => {
+            let open = app.state::<TabManager>().list_tabs().len();
+            let max = app.state::<MemoryGuard>().get_max_tabs() as usize;
+            if open >= max {
+                return Err(ToolError::Denied(format!("Tab limit reached (max {} tabs in current mode)", max)));
+            }
+            Ok(())
+        }
+    }
+}
+
+// ============================================================================
+// SCHEMA VALIDATION
+// ============================================================================
+
+// The subset of JSON schema the tool specs use: type, properties, required,
+// additionalProperties: false, items, enum, minimum/maximum, minLength/maxLength, maxItems
+pub fn validate(schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+    if let Some(expected) = schema.get("type").and_then(Value::as_str) {
+        let matches = match expected {
+            "object" => value.is_object(),
+            "array" => value.is_array(),
+            "string" => value.is_string(),
+            "boolean" => value.is_boolean(),
+            "number" => value.is_number(),
+            "integer" => value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0),
+            _ => true,
+        };
+        if !matches {
+            return Err(format!("{} must be {} {}", path, if expected.starts_with(['a', 'i', 'o']) { "an" } else { "a" }, expected));
+        }
+    }
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            let options: Vec<String> = allowed.iter().map(Value::to_string).collect();
+            return Err(format!("{} must be one of {}", path, options.join(", ")));
+        }
+    }
+    if let Some(n) = value.as_f64() {
+        if let Some(min) = schema.get("minimum").and_then(Value::as_f64).filter(|min| n < *min) {
+            return Err(format!("{} must be at least {}", path, min));
+        }
+        if let Some(max) = schema.get("maximum").and_then(Value::as_f64).filter(|max| n > *max) {
+            return Err(format!("{} must be at most {}", path, max));
+        }
+    }
+    if let Some(s) = value.as_str() {
+        let len = s.chars().count() as u64;
+        if let Some(min) = schema.get("minLength").and_then(Value::as_u64).filter(|min| len < *min) {
+            return Err(format!("{} must be at least {} characters", path, min));
+        }
+        if let Some(max) = schema.get("maxLength").and_then(Value::as_u64).filter(|max| len > *max) {
+            return Err(format!("{} must be at most {} characters", path, max));
+        }
+    }
+    if let Some(items) = value.as_array() {
+        if let Some(max) = schema.get("maxItems").and_then(Value::as_u64).filter(|max| items.len() as u64 > *max) {
+            return Err(format!("{} must have at most {} items", path, max));
+        }
+        if let Some(item_schema) = schema.get("items") {
+            for (i, item) in items.iter().enumerate() {
+                validate(item_schema, item, &format!("{}[{}]", path, i))?;
+            }
+        }
+    }
+    if let Some(object) = value.as_object() {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for required in schema.get("required").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str) {
+            if object.get(required).is_none_or(Value::is_null) {
+                return Err(format!("{}.{} is required", path, required));
+            }
+        }
+        for (key, field) in object {
+            match properties.and_then(|p| p.get(key)) {
+                // An explicit null for an optional field means "not given"
+                Some(_) if field.is_null() => {}
+                Some(field_schema) => validate(field_schema, field, &format!("{}.{}", path, key))?,
+                None if schema.get("additionalProperties") == Some(&Value::Bool(false)) => {
+                    return Err(format!("{}.{} is not a known parameter", path, key));
+                }
+                None => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// TOOLS
+// ============================================================================
+
+struct OpenTab;
+
+impl Tool for OpenTab {
+    fn spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: "open_tab",
+            description: "Open a web page in a new browser tab for the user",
+            permission: Permission::OpensTabs,
+            risk: Risk::Medium,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "url": { "type": "string", "minLength": 1, "maxLength": 2048, "description": "http(s) URL; a bare domain gets https://" }
+                },
+                "required": ["url"],
+                "additionalProperties": false
+            }),
+        }
+    }
+
+    fn call<'a>(&'a self, app: &'a tauri::AppHandle, args: Value) -> BoxFuture<'a, Result<Value, ToolError>> {
+        Box::pin(async move {
+            let url = normalize_url(str_arg(&args, "url"))?;
+            let (mode, persist) = {
+                let privacy = app.state::<Mutex<PrivacyEnforcer>>();
+                let enforcer = privacy.lock().unwrap();
+                (enforcer.get_policy().mode.clone(), enforcer.can_write_to_disk())
+            };
+            let tab_manager = app.state::<TabManager>();
+            let tab_id = tab_manager
+                .create_tab(url.clone(), mode, crate::state::AppMode::Browse)
+                .map_err(ToolError::Denied)?;
+            if persist {
+                let _ = tab_manager.save_session(&app.state::<Database>());
+            }
+            let _ = app.emit("tab-opened", json!({ "tabId": &tab_id, "url": &url, "source": "agent" }));
+            Ok(json!({ "tabId": tab_id, "url": url }))
+        })
+    }
+}
+
+struct Search;
+
+impl Tool for Search {
+    fn spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: "search",
+            description: "Search the web; returns titles, URLs and snippets",
+            permission: Permission::Network,
+            risk: Risk::Low,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "minLength": 1, "maxLength": 400 },
+                    "limit": { "type": "integer", "minimum": 1, "maximum": 10, "description": "Results to return (default 5)" }
+                },
+                "required": ["query"],
+                "additionalProperties": false
+            }),
+        }
+    }
+
+    fn call<'a>(&'a self, app: &'a tauri::AppHandle, args: Value) -> BoxFuture<'a, Result<Value, ToolError>> {
+        Box::pin(async move {
+            let limit = args.get("limit").and_then(Value::as_u64).unwrap_or(SEARCH_LIMIT) as usize;
+            let api_keys = app.state::<ApiKeyStore>();
+            let response = crate::web_search::search(str_arg(&args, "query"), None, Some(limit), &api_keys)
+                .await
+                .map_err(|e| ToolError::Failed(e.to_string()))?;
+            let results: Vec<Value> = response
+                .results
+                .iter()
+                .map(|r| json!({ "title": r.title, "url": r.url, "snippet": r.snippet }))
+                .collect();
+            Ok(json!({ "query": response.query, "results": results }))
+        })
+    }
+}
+
+struct FetchPage;
+
+impl Tool for FetchPage {
+    fn spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: "fetch_page",
+            description: "Download a web page and return its readable main text",
+            permission: Permission::Network,
+            risk: Risk::Medium,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "url": { "type": "string", "minLength": 1, "maxLength": 2048 },
+                    "maxChars": { "type": "integer", "minimum": 200, "maximum": MAX_PAGE_CHARS, "description": "Text to return (default 4000)" }
+                },
+                "required": ["url"],
+                "additionalProperties": false
+            }),
+        }
+    }
+
+    fn call<'a>(&'a self, _app: &'a tauri::AppHandle, args: Value) -> BoxFuture<'a, Result<Value, ToolError>> {
+        Box::pin(async move {
+            let url = normalize_url(str_arg(&args, "url"))?;
+            let max_chars = args.get("maxChars").and_then(Value::as_u64).unwrap_or(PAGE_CHARS) as usize;
+            let page = crate::extractor::extract_url(&url).await.map_err(|e| ToolError::Failed(e.to_string()))?;
+            let text = page.text.trim();
+            let truncated = text.chars().count() > max_chars;
+            let text: String = text.chars().take(max_chars).collect();
+            Ok(json!({
+                "url": page.url.unwrap_or(url),
+                "title": page.title,
+                "text": text,
+                "truncated": truncated,
+                "wordCount": page.word_count,
+            }))
+        })
+    }
+}
+
+struct GetQuote {
+    http: http::Client,
+}
+
+impl Tool for GetQuote {
+    fn spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: "get_quote",
+            description: "Latest stock quote for a ticker symbol (price, change, day range)",
+            permission: Permission::Network,
+            risk: Risk::Low,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "symbol": { "type": "string", "minLength": 1, "maxLength": 15, "description": "Ticker, e.g. AAPL" }
+                },
+                "required": ["symbol"],
+                "additionalProperties": false
+            }),
+        }
+    }
+
+    fn call<'a>(&'a self, app: &'a tauri::AppHandle, args: Value) -> BoxFuture<'a, Result<Value, ToolError>> {
+        Box::pin(async move {
+            let symbol = str_arg(&args, "symbol").trim().to_uppercase();
+            if !symbol.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':' | '^')) {
+                return Err(ToolError::InvalidArguments(format!("'{}' is not a ticker symbol", symbol)));
+            }
+            let key = app
+                .state::<ApiKeyStore>()
+                .get(Provider::Finnhub)
+                .ok_or_else(|| ToolError::Unavailable("no Finnhub API key is configured".to_string()))?;
+            let request = self
+                .http
+                .get("https://finnhub.io/api/v1/quote")
+                .query(&[("symbol", symbol.as_str())])
+                .header("X-Finnhub-Token", key);
+            let response = self.http.send(request).await.map_err(|e| ToolError::Failed(e.to_string()))?;
+            if !response.status().is_success() {
+                return Err(ToolError::Failed(format!("quote provider returned HTTP {}", response.status().as_u16())));
+            }
+            let quote: Value = response.json().await.map_err(|e| ToolError::Failed(e.to_string()))?;
+            let field = |name: &str| quote.get(name).and_then(Value::as_f64);
+            // Finnhub answers unknown symbols with all zeroes
+            let timestamp = quote.get("t").and_then(Value::as_i64).unwrap_or(0);
+            if timestamp == 0 {
+                return Err(ToolError::Failed(format!("no quote for '{}'", symbol)));
+            }
+            Ok(json!({
+                "symbol": symbol,
+                "price": field("c"),
+                "change": field("d"),
+                "changePercent": field("dp"),
+                "open": field("o"),
+                "high": field("h"),
+                "low": field("l"),
+                "previousClose": field("pc"),
+                "timestamp": timestamp,
+            }))
+        })
+    }
+}
+
+struct SaveNote;
+
+impl Tool for SaveNote {
+    fn spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: "save_note",
+            description: "Save a note to the user's notes",
+            permission: Permission::WritesData,
+            risk: Risk::Medium,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "content": { "type": "string", "minLength": 1, "maxLength": 100_000 },
+                    "title": { "type": "string", "maxLength": 200 },
+                    "url": { "type": "string", "maxLength": 2048, "description": "Page the note is about" },
+                    "tags": { "type": "array", "items": { "type": "string", "minLength": 1, "maxLength": 50 }, "maxItems": 20 }
+                },
+                "required": ["content"],
+                "additionalProperties": false
+            }),
+        }
+    }
+
+    fn call<'a>(&'a self, app: &'a tauri::AppHandle, args: Value) -> BoxFuture<'a, Result<Value, ToolError>> {
+        Box::pin(async move {
+            let optional = |name: &str| args.get(name).and_then(Value::as_str).map(str::to_string);
+            let tags = args
+                .get("tags")
+                .and_then(Value::as_array)
+                .map(|tags| tags.iter().filter_map(Value::as_str).map(str::to_string).collect())
+                .unwrap_or_default();
+            let note = Note::new(str_arg(&args, "content").to_string(), optional("title"), optional("url"), tags);
+            app.state::<Database>().save_note(&note).map_err(|e| ToolError::Failed(e.to_string()))?;
+            app.state::<LocalIndex>().note_saved(&note);
+            Ok(json!({ "noteId": note.id, "title": note.title }))
+        })
+    }
+}
+
+struct RunCalculation;
+
+impl Tool for RunCalculation {
+    fn spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: "run_calculation",
+            description: "Evaluate an arithmetic expression: + - * / % ^, parentheses, pi, e, and sqrt abs ln log exp sin cos tan round floor ceil min max",
+            permission: Permission::ReadOnly,
+            risk: Risk::Low,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "expression": { "type": "string", "minLength": 1, "maxLength": MAX_EXPRESSION_LEN }
+                },
+                "required": ["expression"],
+                "additionalProperties": false
+            }),
+        }
+    }
+
+    fn call<'a>(&'a self, _app: &'a tauri::AppHandle, args: Value) -> BoxFuture<'a, Result<Value, ToolError>> {
+        Box::pin(async move {
+            let expression = str_arg(&args, "expression");
+            let result = calculate(expression).map_err(ToolError::InvalidArguments)?;
+            Ok(json!({ "expression": expression, "result": result }))
+        })
+    }
+}
+
+struct GetActiveTabContent;
+
+impl Tool for GetActiveTabContent {
+    fn spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: "get_active_tab_content",
+            description: "Title, URL and readable main text of the page the user is looking at",
+            permission: Permission::Network,
+            risk: Risk::Low,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "maxChars": { "type": "integer", "minimum": 200, "maximum": MAX_PAGE_CHARS, "description": "Text to return (default 4000)" }
+                },
+                "additionalProperties": false
+            }),
+        }
+    }
+
+    fn call<'a>(&'a self, app: &'a tauri::AppHandle, args: Value) -> BoxFuture<'a, Result<Value, ToolError>> {
+        Box::pin(async move {
+            let tab = app
+                .state::<TabManager>()
+                .get_active_tab()
+                .ok_or_else(|| ToolError::Unavailable("no tab is open".to_string()))?;
+            let private_mode = !matches!(
+                app.state::<Mutex<PrivacyEnforcer>>().lock().unwrap().get_policy().mode,
+                crate::state::PrivacyMode::Normal
+            );
+            if tab.privacy_mode != "normal" || private_mode {
+                return Err(ToolError::Denied("the active tab is private".to_string()));
+            }
+            // Re-fetched without the tab's cookies, so pages behind a login come back as their public version
+            let url = normalize_url(&tab.url).map_err(|_| ToolError::Unavailable(format!("'{}' has no readable content", tab.url)))?;
+            let max_chars = args.get("maxChars").and_then(Value::as_u64).unwrap_or(PAGE_CHARS) as usize;
+            let page = crate::extractor::extract_url(&url).await.map_err(|e| ToolError::Failed(e.to_string()))?;
+            let text = page.text.trim();
+            let truncated = text.chars().count() > max_chars;
+            let text: String = text.chars().take(max_chars).collect();
+            Ok(json!({
+                "tabId": tab.id,
+                "url": url,
+                "title": if page.title.is_empty() { tab.title } else { page.title },
+                "text": text,
+                "truncated": truncated,
+            }))
+        })
+    }
+}
+
+struct SearchHistory;
+
+impl Tool for SearchHistory {
+    fn spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: "search_history",
+            description: "Search the user's browsing history by title or URL; newest visits first",
+            permission: Permission::ReadOnly,
+            risk: Risk::Low,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "minLength": 1, "maxLength": 400 },
+                    "limit": { "type": "integer", "minimum": 1, "maximum": 50, "description": "Results to return (default 5)" }
+                },
+                "required": ["query"],
+                "additionalProperties": false
+            }),
+        }
+    }
+
+    fn call<'a>(&'a self, app: &'a tauri::AppHandle, args: Value) -> BoxFuture<'a, Result<Value, ToolError>> {
+        Box::pin(async move {
+            if !app.state::<Mutex<PrivacyEnforcer>>().lock().unwrap().can_save_history() {
+                return Err(ToolError::Denied("history is off in the current privacy mode".to_string()));
+            }
+            let limit = args.get("limit").and_then(Value::as_u64).unwrap_or(SEARCH_LIMIT) as usize;
+            let history = app
+                .state::<Database>()
+                .search_history(str_arg(&args, "query"))
+                .map_err(|e| ToolError::Failed(e.to_string()))?;
+            let results: Vec<Value> = history
+                .into_iter()
+                .take(limit)
+                .map(|(url, title, visited_at)| json!({ "url": url, "title": title, "visitedAt": visited_at }))
+                .collect();
+            Ok(json!({ "query": str_arg(&args, "query"), "results": results }))
+        })
+    }
+}
+
+struct ListWorkflows;
+
+impl Tool for ListWorkflows {
+    fn spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: "list_workflows",
+            description: "The user's n8n automation workflows and the webhook ids that trigger them",
+            permission: Permission::Network,
+            risk: Risk::Low,
+            parameters: json!({
+                "type": "object",
+                "properties": {},
+                "additionalProperties": false
+            }),
+        }
+    }
+
+    fn call<'a>(&'a self, app: &'a tauri::AppHandle, _args: Value) -> BoxFuture<'a, Result<Value, ToolError>> {
+        Box::pin(async move {
+            let workflows = crate::workflows::list(&app.state::<ApiKeyStore>()).await.map_err(|e| match e {
+                crate::workflows::WorkflowError::NoApiKey => ToolError::Unavailable(e.to_string()),
+                e => ToolError::Failed(e.to_string()),
+            })?;
+            let workflows: Vec<Value> = workflows
+                .into_iter()
+                .filter(|w| w.active && w.webhooks.iter().any(|h| h.method == "POST"))
+                .map(|w| {
+                    let webhooks: Vec<&str> = w.webhooks.iter().filter(|h| h.method == "POST").map(|h| h.webhook_id.as_str()).collect();
+                    json!({ "name": w.name, "tags": w.tags, "webhookIds": webhooks })
+                })
+                .collect();
+            Ok(json!({ "workflows": workflows }))
+        })
+    }
+}
+
+struct TriggerWorkflow;
+
+impl Tool for TriggerWorkflow {
+    fn spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: "trigger_workflow",
+            description: "Start one of the user's n8n workflows by webhook id, passing data such as research results",
+            permission: Permission::Network,
+            risk: Risk::High,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "webhookId": { "type": "string", "minLength": 1, "maxLength": 200, "description": "From list_workflows" },
+                    "payload": { "type": "object", "description": "JSON sent to the workflow" }
+                },
+                "required": ["webhookId"],
+                "additionalProperties": false
+            }),
+        }
+    }
+
+    fn call<'a>(&'a self, _app: &'a tauri::AppHandle, args: Value) -> BoxFuture<'a, Result<Value, ToolError>> {
+        Box::pin(async move {
+            let payload = args.get("payload").cloned().unwrap_or_else(|| json!({}));
+            let run = crate::workflows::trigger(str_arg(&args, "webhookId"), &payload).await.map_err(|e| match e {
+                crate::workflows::WorkflowError::InvalidWebhook(_) => ToolError::InvalidArguments(e.to_string()),
+                e => ToolError::Failed(e.to_string()),
+            })?;
+            Ok(json!({ "webhookId": run.webhook_id, "status": run.status, "response": run.response }))
+        })
+    }
+}
+
+// Required string arguments are guaranteed by validation
+fn str_arg<'a>(args: &'a Value, name: &str) -> &'a str {
+    args.get(name).and_then(Value::as_str).unwrap_or_default()
+}
+
+// "example.com/page" -> "https://example.com/page"; only http(s) is accepted
+fn normalize_url(raw: &str) -> Result<String, ToolError> {
+    let raw = raw.trim();
+    let candidate = if raw.contains("://") { raw.to_string() } else { format!("https://{}", raw) };
+    match url::Url::parse(&candidate) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") && url.host_str().is_some() => Ok(url.to_string()),
+        _ => Err(ToolError::InvalidArguments(format!("'{}' is not an http(s) URL", raw))),
+    }
+}
+
+// ============================================================================
+// CALCULATOR
+// ============================================================================
+
+// Evaluate an arithmetic expression; no variables, no side effects
+pub fn calculate(expression: &str) -> Result<f64, String> {
+    if expression.len() > MAX_EXPRESSION_LEN {
+        return Err(format!("expression is longer than {} characters", MAX_EXPRESSION_LEN));
+    }
+    let mut parser = Calculator { chars: expression.chars().filter(|c| !c.is_whitespace() && *c != '_').collect(), pos: 0, depth: 0 };
+    let value = parser.expr()?;
+    if let Some(c) = parser.peek() {
+        return Err(format!("unexpected '{}'", c));
+    }
+    if !value.is_finite() {
+        return Err("result is not a finite number".to_string());
+    }
+    Ok(value)
+}
+
+struct Calculator {
+    chars: Vec<char>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Calculator {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    // expr := term (('+' | '-') term)*
+    fn expr(&mut self) -> Result<f64, String> {
+        self.depth += 1;
+        if self.depth > MAX_EXPRESSION_DEPTH {
+            return Err("expression is nested too deeply".to_string());
+        }
+        let mut value = self.term()?;
+        loop {
+            if self.eat('+') {
+                value += self.term()?;
+            } else if self.eat('-') {
+                value -= self.term()?;
+            } else {
+                break;
+            }
+        }
+        self.depth -= 1;
+        Ok(value)
+    }
+
+    // term := unary (('*' | '/' | '%') unary)*
+    fn term(&mut self) -> Result<f64, String> {
+        let mut value = self.unary()?;
+        loop {
+            if self.eat('*') || self.eat('×') {
+                value *= self.unary()?;
+            } else if self.eat('/') || self.eat('÷') {
+                let divisor = self.unary()?;
+                if divisor == 0.0 {
+                    return Err("division by zero".to_string());
+                }
+                value /= divisor;
+            } else if self.eat('%') {
+                let divisor = self.unary()?;
+                if divisor == 0.0 {
+                    return Err("division by zero".to_string());
+                }
+                value %= divisor;
+            } else {
+                break;
+            }
+        }
+        Ok(value)
+    }
+
+    // unary := ('-' | '+') unary | power; so -2^2 is -4
+    fn unary(&mut self) -> Result<f64, String> {
+        self.depth += 1;
+        if self.depth > MAX_EXPRESSION_DEPTH {
+            return Err("expression is nested too deeply".to_string());
+        }
+        let value = if self.eat('-') {
+            -self.unary()?
+        } else if self.eat('+') {
+            self.unary()?
+        } else {
+            self.power()?
+        };
+        self.depth -= 1;
+        Ok(value)
+    }
+
+    // power := atom ('^' unary)?, right-associative
+    fn power(&mut self) -> Result<f64, String> {
+        let base = self.atom()?;
+        if self.eat('^') {
+            return Ok(base.powf(self.unary()?));
+        }
+        Ok(base)
+    }
+
+    fn atom(&mut self) -> Result<f64, String> {
+        match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                let value = self.expr()?;
+                if !self.eat(')') {
+                    return Err("missing ')'".to_string());
+                }
+                Ok(value)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => self.number(),
+            Some(c) if c.is_ascii_alphabetic() => {
+                let start = self.pos;
+                while self.peek().is_some_and(|c| c.is_ascii_alphanumeric()) {
+                    self.pos += 1;
+                }
+                let name: String = self.chars[start..self.pos].iter().collect::<String>().to_lowercase();
+                if self.eat('(') {
+                    let mut args = vec![self.expr()?];
+                    while self.eat(',') {
+                        args.push(self.expr()?);
+                    }
+                    if !self.eat(')') {
+                        return Err("missing ')'".to_string());
+                    }
+                    return apply_function(&name, &args);
+                }
+                match name.as_str() {
+                    "pi" => Ok(std::f64::consts::PI),
+                    "e" => Ok(std::f64::consts::E),
+                    _ => Err(format!("unknown name '{}'", name)),
+                }
+            }
+            Some(c) => Err(format!("unexpected '{}'", c)),
+            None => Err("expression ended early".to_string()),
+        }
+    }
+
+    fn number(&mut self) -> Result<f64, String> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit() || c == '.') {
+            self.pos += 1;
+        }
+        // Exponent: 1.5e3, 2E-4
+        if matches!(self.peek(), Some('e' | 'E')) {
+            let mark = self.pos;
+            self.pos += 1;
+            if matches!(self.peek(), Some('+' | '-')) {
+                self.pos += 1;
+            }
+            if self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                    self.pos += 1;
+                }
+            } else {
+                self.pos = mark;
+            }
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        text.parse::<f64>().map_err(|_| format!("'{}' is not a number", text))
+    }
+}
+
+fn apply_function(name: &str, args: &[f64]) -> Result<f64, String> {
+    let one = |f: fn(f64) -> f64| match args {
+        [x] => Ok(f(*x)),
+        _ => Err(format!("{}() takes one argument", name)),
+    };
+    match name {
+        "sqrt" => match args {
+            [x] if *x < 0.0 => Err("sqrt() of a negative number".to_string()),
+            _ => one(f64::sqrt),
+        },
+        "abs" => one(f64::abs),
+        "ln" => one(f64::ln),
+        "log" => match args {
+            [x] => Ok(x.log10()),
+            [x, base] => Ok(x.log(*base)),
+            _ => Err("log() takes one or two arguments".to_string()),
+        },
+        "exp" => one(f64::exp),
+        "sin" => one(f64::sin),
+        "cos" => one(f64::cos),
+        "tan" => one(f64::tan),
+        "floor" => one(f64::floor),
+        "ceil" => one(f64::ceil),
+        "round" => match args {
+            [x] => Ok(x.round()),
+            [x, digits] if (0.0..=15.0).contains(digits) => {
+                let scale = 10f64.powi(*digits as i32);
+                Ok((x * scale).round() / scale)
+            }
+            _ => Err("round() takes a value and 0-15 digits".to_string()),
+        },
+        "min" => args.iter().copied().reduce(f64::min).ok_or_else(|| "min() needs an argument".to_string()),
+        "max" => args.iter().copied().reduce(f64::max).ok_or_else(|| "max() needs an argument".to_string()),
+        _ => Err(format!("unknown function '{}'", name)),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ToolError {
+    Unknown(String),
+    Disabled(String),
+    InvalidArguments(String),
+    Denied(String),
+    Offline,
+    Unavailable(String),
+    Failed(String),
+}
+
+impl std::fmt::Display for ToolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ToolError::Unknown(name) => write!(f, "Unknown tool '{}'", name),
+            ToolError::Disabled(name) => write!(f, "Tool '{}' is disabled in settings", name),
+            ToolError::InvalidArguments(msg) => write!(f, "Invalid arguments: {}", msg),
+            ToolError::Denied(msg) => write!(f, "Not allowed: {}", msg),
+            ToolError::Offline => write!(f, "Network is offline"),
+            ToolError::Unavailable(msg) => write!(f, "Tool unavailable: {}", msg),
+            ToolError::Failed(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ToolError {}
@@ -0,0 +1,366 @@
+// Trade News - Headlines for a ticker from Finnhub and RSS sources (config.trade.newsSources)
+// Results are deduplicated across sources, cached per symbol, and optionally scored for sentiment by the local model
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+use crate::ai::AIService;
+use crate::apikeys::{ApiKeyStore, Provider};
+
+const USER_AGENT: &str = "Mozilla/5.0 (compatible; RegenBrowser/0.3; +https://regen.app)";
+const FETCH_TIMEOUT: Duration = Duration::from_secs(12);
+const FINNHUB_URL: &str = "https://finnhub.io/api/v1/company-news";
+const FINNHUB_DAYS: i64 = 7;
+pub const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 100;
+const MAX_CACHED: usize = 64;
+// Headlines per sentiment prompt; the model answers one line per headline
+const SENTIMENT_BATCH: usize = 25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum Sentiment {
+    Positive,
+    Negative,
+    Neutral,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct NewsArticle {
+    pub id: String,                    // Stable across refreshes (hash of the article URL)
+    pub symbol: String,
+    pub title: String,
+    pub url: String,
+    pub source: String,                // Publisher when known, otherwise the article's host
+    pub summary: Option<String>,
+    pub published_at: Option<i64>,
+    pub sentiment: Option<Sentiment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SourceOutcome {
+    pub source: String,                // "finnhub" or the feed's host
+    pub articles: usize,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct NewsResponse {
+    pub symbol: String,
+    pub articles: Vec<NewsArticle>,
+    pub sources: Vec<SourceOutcome>,
+    pub cached: bool,
+    pub stale: bool,                   // Offline: an expired cache entry was returned as-is
+    pub fetched_at: i64,
+}
+
+struct CacheEntry {
+    response: NewsResponse,
+    expires: Instant,
+}
+
+fn cache() -> &'static Mutex<HashMap<String, CacheEntry>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, CacheEntry>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+pub fn normalize_symbol(symbol: &str) -> Result<String, NewsError> {
+    let symbol = symbol.trim().to_uppercase();
+    let valid = !symbol.is_empty()
+        && symbol.len() <= 15
+        && symbol.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':' | '^'));
+    if valid { Ok(symbol) } else { Err(NewsError::InvalidSymbol(symbol)) }
+}
+
+pub async fn news(app: &tauri::AppHandle, symbol: &str, limit: usize, sentiment: Option<bool>) -> Result<NewsResponse, NewsError> {
+    let symbol = normalize_symbol(symbol)?;
+    let limit = limit.clamp(1, MAX_LIMIT);
+    let config = crate::config::current().trade;
+    let score = sentiment.unwrap_or(config.news_sentiment);
+
+    let mut response = match cached(&symbol) {
+        Some((response, true)) => response,
+        Some((response, false)) if crate::connectivity::is_offline() => NewsResponse { stale: true, ..response },
+        None if crate::connectivity::is_offline() => return Err(NewsError::Offline),
+        expired => match fetch(app, &symbol, &config.news_sources).await {
+            Ok(response) => {
+                store(&response, Duration::from_secs(config.news_cache_minutes as u64 * 60));
+                response
+            }
+            // Every source failed; an expired copy beats no headlines at all
+            Err(e) => match expired {
+                Some((response, _)) => NewsResponse { stale: true, ..response },
+                None => return Err(e),
+            },
+        },
+    };
+
+    response.articles.truncate(limit);
+    if score && response.articles.iter().any(|a| a.sentiment.is_none()) {
+        score_sentiment(app, &mut response.articles).await;
+        remember_sentiment(&symbol, &response.articles);
+    }
+    Ok(response)
+}
+
+// (response, still fresh)
+fn cached(symbol: &str) -> Option<(NewsResponse, bool)> {
+    let cache = cache().lock().unwrap();
+    let entry = cache.get(symbol)?;
+    Some((NewsResponse { cached: true, ..entry.response.clone() }, entry.expires > Instant::now()))
+}
+
+fn store(response: &NewsResponse, ttl: Duration) {
+    let mut cache = cache().lock().unwrap();
+    if cache.len() >= MAX_CACHED {
+        let now = Instant::now();
+        cache.retain(|_, e| e.expires > now);
+        if cache.len() >= MAX_CACHED {
+            cache.clear();
+        }
+    }
+    cache.insert(response.symbol.clone(), CacheEntry { response: response.clone(), expires: Instant::now() + ttl });
+}
+
+// Keep scores on the cached copy so the next call doesn't ask the model again
+fn remember_sentiment(symbol: &str, scored: &[NewsArticle]) {
+    let mut cache = cache().lock().unwrap();
+    let Some(entry) = cache.get_mut(symbol) else { return };
+    let scores: HashMap<&str, Sentiment> = scored.iter().filter_map(|a| Some((a.id.as_str(), a.sentiment?))).collect();
+    for article in &mut entry.response.articles {
+        if let Some(sentiment) = scores.get(article.id.as_str()) {
+            article.sentiment = Some(*sentiment);
+        }
+    }
+}
+
+async fn fetch(app: &tauri::AppHandle, symbol: &str, sources: &[String]) -> Result<NewsResponse, NewsError> {
+    if sources.is_empty() {
+        return Err(NewsError::NoSources);
+    }
+    let client = crate::http::Client::new(FETCH_TIMEOUT).with_user_agent(USER_AGENT).with_purpose("trade-news");
+    let finnhub_key = app.try_state::<ApiKeyStore>().and_then(|keys| keys.get(Provider::Finnhub));
+
+    let results = futures::future::join_all(sources.iter().map(|source| {
+        let (client, key) = (&client, finnhub_key.clone());
+        async move {
+            if source == "finnhub" {
+                let result = match key {
+                    Some(key) => fetch_finnhub(client, symbol, &key).await,
+                    None => Err("No Finnhub API key is configured".to_string()),
+                };
+                ("finnhub".to_string(), result)
+            } else {
+                let url = source.replace("{symbol}", &url::form_urlencoded::byte_serialize(symbol.as_bytes()).collect::<String>());
+                (host(&url).unwrap_or_else(|| url.clone()), fetch_feed(client, symbol, &url).await)
+            }
+        }
+    }))
+    .await;
+
+    let mut outcomes = Vec::new();
+    let mut articles = Vec::new();
+    for (source, result) in results {
+        match result {
+            Ok(found) => {
+                outcomes.push(SourceOutcome { source, articles: found.len(), error: None });
+                articles.extend(found);
+            }
+            Err(e) => {
+                tracing::debug!(target: "app", "Trade news: {} failed for {}: {}", source, symbol, e);
+                outcomes.push(SourceOutcome { source, articles: 0, error: Some(e) });
+            }
+        }
+    }
+    if outcomes.iter().all(|o| o.error.is_some()) {
+        let errors: Vec<String> = outcomes.iter().map(|o| format!("{}: {}", o.source, o.error.as_deref().unwrap_or_default())).collect();
+        return Err(NewsError::Unavailable(errors.join("; ")));
+    }
+
+    Ok(NewsResponse {
+        symbol: symbol.to_string(),
+        articles: dedup(articles),
+        sources: outcomes,
+        cached: false,
+        stale: false,
+        fetched_at: chrono::Utc::now().timestamp(),
+    })
+}
+
+// [{"datetime":1760600000,"headline":"...","source":"Reuters","summary":"...","url":"https://..."}]
+async fn fetch_finnhub(client: &crate::http::Client, symbol: &str, key: &str) -> Result<Vec<NewsArticle>, String> {
+    let today = chrono::Utc::now().date_naive();
+    let from = (today - chrono::Duration::days(FINNHUB_DAYS)).to_string();
+    let to = today.to_string();
+    let request = client
+        .get(FINNHUB_URL)
+        .query(&[("symbol", symbol), ("from", from.as_str()), ("to", to.as_str())])
+        .header("X-Finnhub-Token", key);
+    let response = client.send(request).await.map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status().as_u16()));
+    }
+    let items: Vec<serde_json::Value> = response.json().await.map_err(|e| e.to_string())?;
+    let text = |item: &serde_json::Value, key: &str| {
+        item.get(key).and_then(|v| v.as_str()).map(str::trim).filter(|s| !s.is_empty()).map(str::to_string)
+    };
+    Ok(items
+        .iter()
+        .filter_map(|item| {
+            let url = text(item, "url")?;
+            Some(article(
+                symbol,
+                text(item, "headline")?,
+                text(item, "source").or_else(|| host(&url)).unwrap_or_default(),
+                url,
+                text(item, "summary"),
+                item.get("datetime").and_then(|v| v.as_i64()).filter(|t| *t > 0),
+            ))
+        })
+        .collect())
+}
+
+async fn fetch_feed(client: &crate::http::Client, symbol: &str, url: &str) -> Result<Vec<NewsArticle>, String> {
+    let response = client.send(client.get(url)).await.map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status().as_u16()));
+    }
+    let body = response.text().await.map_err(|e| e.to_string())?;
+    let feed = crate::feeds::parse(&body, url).map_err(|e| e.to_string())?;
+    let aggregator = host(url).is_some_and(|h| h == "news.google.com");
+    Ok(feed
+        .entries
+        .into_iter()
+        .filter_map(|entry| {
+            let link = entry.url?;
+            let mut title = entry.title.trim().to_string();
+            let mut source = host(&link).unwrap_or_default();
+            // Google News titles end in " - Publisher" and link through its own redirector
+            if aggregator {
+                if let Some((headline, publisher)) = title.rsplit_once(" - ") {
+                    source = publisher.trim().to_string();
+                    title = headline.trim().to_string();
+                }
+            }
+            (!title.is_empty()).then(|| article(symbol, title, source, link, entry.summary.filter(|s| !s.is_empty()), entry.published_at))
+        })
+        .collect())
+}
+
+fn article(symbol: &str, title: String, source: String, url: String, summary: Option<String>, published_at: Option<i64>) -> NewsArticle {
+    let id = format!("{:x}", Sha256::digest(url.as_bytes()))[..16].to_string();
+    NewsArticle { id, symbol: symbol.to_string(), title, url, source, summary, published_at, sentiment: None }
+}
+
+fn host(url: &str) -> Option<String> {
+    let url = url::Url::parse(url).ok()?;
+    let host = url.host_str()?;
+    Some(host.strip_prefix("www.").unwrap_or(host).to_string())
+}
+
+// The same story often arrives from several sources; keep the first copy of each URL or headline, newest first
+fn dedup(mut articles: Vec<NewsArticle>) -> Vec<NewsArticle> {
+    articles.sort_by(|a, b| b.published_at.cmp(&a.published_at));
+    let mut urls = HashSet::new();
+    let mut titles = HashSet::new();
+    articles.retain(|a| {
+        let url = a.url.split('#').next().unwrap_or(&a.url).trim_end_matches('/').to_lowercase();
+        let title = title_key(&a.title);
+        let fresh = !urls.contains(&url) && !titles.contains(&title);
+        urls.insert(url);
+        titles.insert(title);
+        fresh
+    });
+    articles
+}
+
+fn title_key(title: &str) -> String {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Scoring is best effort: when the model is unavailable or answers oddly, articles stay unscored
+async fn score_sentiment(app: &tauri::AppHandle, articles: &mut [NewsArticle]) {
+    let ai = app.state::<AIService>();
+    if !ai.is_available() {
+        return;
+    }
+    let symbol = articles.first().map(|a| a.symbol.clone()).unwrap_or_default();
+    for batch in articles.chunks_mut(SENTIMENT_BATCH) {
+        let headlines = batch
+            .iter()
+            .enumerate()
+            .map(|(i, a)| format!("{}. {}", i + 1, a.title))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let prompt = crate::prompts::render("news_sentiment", None, None, &[("symbol", &symbol), ("headlines", &headlines)]);
+        match ai.complete_task("news_sentiment", &prompt).await {
+            Ok(answer) => {
+                for (index, sentiment) in parse_sentiment(&answer) {
+                    if let Some(article) = batch.get_mut(index) {
+                        article.sentiment = Some(sentiment);
+                    }
+                }
+            }
+            Err(e) => {
+                tracing::debug!(target: "ai", "Trade news: Sentiment scoring failed: {}", e);
+                return;
+            }
+        }
+    }
+}
+
+// "3: negative" / "3. Negative" -> (2, Negative)
+fn parse_sentiment(answer: &str) -> Vec<(usize, Sentiment)> {
+    answer
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim().trim_start_matches(['-', '*']).trim();
+            let (number, label) = line.split_once([':', '.', ')'])?;
+            let index = number.trim().parse::<usize>().ok()?.checked_sub(1)?;
+            let sentiment = match label.trim().trim_matches(|c: char| !c.is_alphabetic()).to_lowercase().as_str() {
+                "positive" | "bullish" => Sentiment::Positive,
+                "negative" | "bearish" => Sentiment::Negative,
+                "neutral" | "mixed" => Sentiment::Neutral,
+                _ => return None,
+            };
+            Some((index, sentiment))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub enum NewsError {
+    InvalidSymbol(String),
+    NoSources,
+    Offline,                           // Nothing cached for the symbol and no network to fetch it
+    Unavailable(String),               // Every source failed
+}
+
+impl std::fmt::Display for NewsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NewsError::InvalidSymbol(symbol) => write!(f, "'{}' is not a ticker symbol", symbol),
+            NewsError::NoSources => write!(f, "No news sources are configured (trade.newsSources)"),
+            NewsError::Offline => write!(f, "No cached news for this symbol and the network is offline"),
+            NewsError::Unavailable(e) => write!(f, "News sources are unavailable: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for NewsError {}
@@ -0,0 +1,190 @@
+// System Tray - Tray icon with quick actions (show/hide, privacy, voice, pause background work, quit)
+// Menu labels and checkmarks follow the app's state; every action is announced on "tray-action".
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::menu::{CheckMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tauri::tray::TrayIconBuilder;
+use tauri::{Emitter, Manager, Wry};
+
+use crate::privacy::PrivacyEnforcer;
+use crate::services::global_shortcut_service;
+use crate::services::supervisor::ServiceSupervisor;
+use crate::state::PrivacyMode;
+
+const TRAY_ID: &str = "main";
+const MAIN_WINDOW: &str = "main";
+// Reported as the accelerator when the tray fires a shortcut action
+const TRAY_SOURCE: &str = "tray";
+
+const TOGGLE_WINDOW: &str = "toggle-window";
+const TOGGLE_PRIVACY: &str = "toggle-privacy";
+const VOICE: &str = "voice";
+const PAUSE_BACKGROUND: &str = "pause-background";
+const QUIT: &str = "quit";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct TrayState {
+    pub window_visible: bool,
+    pub privacy_mode: String,
+    pub background_paused: bool,
+}
+
+// Emitted as "tray-action" after the action ran
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct TrayAction {
+    pub action: String,
+    pub state: TrayState,
+}
+
+// Items whose text or checkmark changes with the app's state
+struct TrayMenu {
+    window: MenuItem<Wry>,
+    privacy: CheckMenuItem<Wry>,
+    pause: CheckMenuItem<Wry>,
+    paused_services: Mutex<Vec<String>>, // Supervised services the tray paused (resumed on unpause)
+}
+
+pub fn init(app: &tauri::AppHandle) -> tauri::Result<()> {
+    let state = state(app);
+    let window = MenuItem::with_id(app, TOGGLE_WINDOW, window_label(state.window_visible), true, None::<&str>)?;
+    let privacy = CheckMenuItem::with_id(app, TOGGLE_PRIVACY, "Private mode", true, false, None::<&str>)?;
+    let voice = MenuItem::with_id(app, VOICE, "Start voice capture", true, None::<&str>)?;
+    let pause = CheckMenuItem::with_id(app, PAUSE_BACKGROUND, "Pause background services", true, false, None::<&str>)?;
+    let quit = MenuItem::with_id(app, QUIT, "Quit Regen", true, None::<&str>)?;
+    let menu = Menu::with_items(
+        app,
+        &[
+            &window,
+            &PredefinedMenuItem::separator(app)?,
+            &privacy,
+            &voice,
+            &pause,
+            &PredefinedMenuItem::separator(app)?,
+            &quit,
+        ],
+    )?;
+
+    let mut tray = TrayIconBuilder::with_id(TRAY_ID).tooltip("Regen").menu(&menu).on_menu_event(on_menu_event);
+    if let Some(icon) = app.default_window_icon() {
+        tray = tray.icon(icon.clone());
+    }
+    tray.build(app)?;
+    app.manage(TrayMenu { window, privacy, pause, paused_services: Mutex::new(Vec::new()) });
+
+    // Showing, hiding and minimizing from outside the tray all move focus
+    if let Some(main) = app.get_webview_window(MAIN_WINDOW) {
+        let handle = app.clone();
+        main.on_window_event(move |event| {
+            if matches!(event, tauri::WindowEvent::Focused(_)) {
+                refresh(&handle);
+            }
+        });
+    }
+    refresh(app);
+    Ok(())
+}
+
+pub fn state(app: &tauri::AppHandle) -> TrayState {
+    let mode = app
+        .try_state::<Mutex<PrivacyEnforcer>>()
+        .map(|enforcer| enforcer.lock().unwrap().get_policy().mode.clone())
+        .unwrap_or(PrivacyMode::Normal);
+    TrayState {
+        window_visible: app
+            .get_webview_window(MAIN_WINDOW)
+            .and_then(|w| w.is_visible().ok())
+            .unwrap_or(false),
+        privacy_mode: format!("{:?}", mode).to_lowercase(),
+        background_paused: crate::modes::user_paused(),
+    }
+}
+
+// Bring labels and checkmarks in line with the app (call after anything the menu mirrors changes)
+pub fn refresh(app: &tauri::AppHandle) {
+    let Some(menu) = app.try_state::<TrayMenu>() else {
+        return;
+    };
+    let state = state(app);
+    let _ = menu.window.set_text(window_label(state.window_visible));
+    let _ = menu.privacy.set_checked(state.privacy_mode != "normal");
+    // Ghost is only left deliberately, from the app
+    let _ = menu.privacy.set_enabled(state.privacy_mode != "ghost");
+    let _ = menu.pause.set_checked(state.background_paused);
+}
+
+// Pollers skip their work and supervised services are stopped until unpaused
+pub fn set_background_paused(app: &tauri::AppHandle, paused: bool) {
+    crate::modes::set_user_paused(paused);
+    let (Some(menu), Some(supervisor)) = (app.try_state::<TrayMenu>(), app.try_state::<ServiceSupervisor>()) else {
+        return;
+    };
+    let mut paused_services = menu.paused_services.lock().unwrap();
+    if paused {
+        for (name, _) in supervisor.pids() {
+            match supervisor.pause(&name) {
+                Ok(()) => paused_services.push(name),
+                Err(e) => tracing::warn!(target: "services", "Tray: {}", e),
+            }
+        }
+    } else {
+        for name in paused_services.drain(..) {
+            if let Err(e) = supervisor.resume(&name) {
+                tracing::warn!(target: "services", "Tray: Failed to resume {}: {}", name, e);
+            }
+        }
+    }
+    tracing::info!(target: "app", "Tray: Background services {}", if paused { "paused" } else { "resumed" });
+}
+
+fn on_menu_event(app: &tauri::AppHandle, event: MenuEvent) {
+    let action = event.id().as_ref().to_string();
+    let result = match action.as_str() {
+        TOGGLE_WINDOW => toggle_window(app),
+        TOGGLE_PRIVACY => global_shortcut_service::on_shortcut_triggered(app, "toggle-privacy-mode"),
+        // Same path as the wispr-wake shortcut, so the frontend starts listening
+        VOICE => {
+            global_shortcut_service::trigger(app, "wispr-wake", TRAY_SOURCE);
+            Ok(())
+        }
+        PAUSE_BACKGROUND => {
+            set_background_paused(app, !crate::modes::user_paused());
+            Ok(())
+        }
+        QUIT => {
+            app.exit(0);
+            return;
+        }
+        _ => return,
+    };
+    if let Err(e) = result {
+        tracing::warn!(target: "app", "Tray: {} failed: {}", action, e);
+    }
+    // A check item flips itself when clicked; put it back if the action didn't take
+    refresh(app);
+    let _ = app.emit("tray-action", TrayAction { action, state: state(app) });
+}
+
+fn toggle_window(app: &tauri::AppHandle) -> Result<(), String> {
+    let visible = app
+        .get_webview_window(MAIN_WINDOW)
+        .and_then(|w| w.is_visible().ok())
+        .unwrap_or(false);
+    if !visible {
+        return global_shortcut_service::on_shortcut_triggered(app, "app-wake");
+    }
+    let window = app.get_webview_window(MAIN_WINDOW).ok_or("No window to hide")?;
+    window.hide().map_err(|e| e.to_string())
+}
+
+fn window_label(visible: bool) -> &'static str {
+    if visible {
+        "Hide Regen"
+    } else {
+        "Show Regen"
+    }
+}
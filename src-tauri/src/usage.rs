@@ -0,0 +1,265 @@
+// Usage Analytics - Local time tracking per domain and app mode, kept as daily totals in SQLite
+// Nothing leaves the machine; Private/Ghost tabs and modes are never recorded
+
+use chrono::{Duration as ChronoDuration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+use crate::browser::{Tab, TabManager};
+use crate::db::Database;
+use crate::privacy::PrivacyEnforcer;
+
+// Time is written out this often, so a crash loses at most one interval
+const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+// A longer gap between flushes means the machine slept; don't count it
+const MAX_CHUNK: Duration = Duration::from_secs(120);
+const TOP_DOMAINS: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct DayUsage {
+    pub date: String,                  // YYYY-MM-DD, local time
+    pub seconds: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct DomainUsage {
+    pub domain: String,
+    pub seconds: u64,
+    pub visits: u64,                   // Times the domain came to the foreground
+    pub share: f64,                    // Fraction of total time, 0-1
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ModeUsage {
+    pub mode: String,
+    pub seconds: u64,
+    pub share: f64,
+}
+
+// Shaped for charts: one entry per day in the range (zeros included), top domains, and the mode split
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct UsageReport {
+    pub range: String,
+    pub from: String,
+    pub to: String,
+    pub total_seconds: u64,
+    pub days: Vec<DayUsage>,
+    pub domains: Vec<DomainUsage>,
+    pub modes: Vec<ModeUsage>,
+}
+
+// Daily aggregate row in usage_daily
+#[derive(Debug, Clone)]
+pub struct UsageRow {
+    pub day: String,
+    pub domain: String,
+    pub mode: String,
+    pub seconds: u64,
+    pub visits: u64,
+}
+
+// Foreground tab currently being timed
+struct Interval {
+    tab_id: String,
+    domain: String,
+    mode: String,
+    since: Instant,
+}
+
+fn current() -> &'static Mutex<Option<Interval>> {
+    static CURRENT: OnceLock<Mutex<Option<Interval>>> = OnceLock::new();
+    CURRENT.get_or_init(|| Mutex::new(None))
+}
+
+// A tab came to the foreground (activation, navigation, or window focus); `None` stops timing
+pub fn activate(db: &Database, tab: Option<&Tab>, recording_allowed: bool) {
+    let next = tab
+        .filter(|t| recording_allowed && t.privacy_mode == "normal")
+        .and_then(|t| domain_of(&t.url).map(|domain| (t, domain)));
+
+    let mut current = current().lock().unwrap();
+    if let (Some(interval), Some((tab, domain))) = (current.as_ref(), next.as_ref()) {
+        if interval.tab_id == tab.id && interval.domain == *domain {
+            return;
+        }
+    }
+    if let Some(interval) = current.take() {
+        record(db, &interval, interval.since.elapsed().min(MAX_CHUNK), 0);
+    }
+    if let Some((tab, domain)) = next {
+        let interval = Interval { tab_id: tab.id.clone(), domain, mode: tab.app_mode.clone(), since: Instant::now() };
+        record(db, &interval, Duration::ZERO, 1);
+        *current = Some(interval);
+    }
+}
+
+// Window lost focus or the app is going away: bank the running interval and stop
+pub fn pause(db: &Database) {
+    activate(db, None, false);
+}
+
+// Periodic flush; also drops the running interval once the privacy mode stops allowing history
+pub fn spawn_flush(app: tauri::AppHandle, db: Database) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(FLUSH_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let allowed = recording_allowed(&app);
+            let mut current = current().lock().unwrap();
+            if !allowed {
+                *current = None;
+                continue;
+            }
+            if let Some(interval) = current.as_mut() {
+                let elapsed = interval.since.elapsed().min(MAX_CHUNK);
+                record(&db, interval, elapsed, 0);
+                interval.since = Instant::now();
+            }
+        }
+    });
+}
+
+// Follow the active tab when the window regains focus
+pub fn on_window_focus(app: &tauri::AppHandle, focused: bool) {
+    let Some(db) = app.try_state::<Database>() else { return };
+    if !focused {
+        pause(&db);
+        return;
+    }
+    let tab = app.try_state::<TabManager>().and_then(|t| t.get_active_tab());
+    activate(&db, tab.as_ref(), recording_allowed(app));
+}
+
+pub fn recording_allowed(app: &tauri::AppHandle) -> bool {
+    app.try_state::<Mutex<PrivacyEnforcer>>()
+        .map(|p| p.lock().unwrap().can_save_history())
+        .unwrap_or(false)
+}
+
+// range: today, week (7 days), month (30), year (365), or a number of days
+pub fn report(db: &Database, range: &str) -> Result<UsageReport, UsageError> {
+    let days = match range.trim().to_lowercase().as_str() {
+        "today" => 1,
+        "week" => 7,
+        "month" => 30,
+        "year" => 365,
+        other => other
+            .trim_end_matches('d')
+            .parse::<i64>()
+            .ok()
+            .filter(|d| (1..=366).contains(d))
+            .ok_or_else(|| UsageError::InvalidRange(range.to_string()))?,
+    };
+    let to = Local::now().date_naive();
+    let from = to - ChronoDuration::days(days - 1);
+    let rows = db
+        .usage_between(&day_key(from), &day_key(to))
+        .map_err(|e| UsageError::Storage(e.to_string()))?;
+
+    let total_seconds: u64 = rows.iter().map(|r| r.seconds).sum();
+    let share = |seconds: u64| if total_seconds == 0 { 0.0 } else { seconds as f64 / total_seconds as f64 };
+
+    let mut per_day: HashMap<&str, u64> = HashMap::new();
+    let mut per_domain: HashMap<&str, (u64, u64)> = HashMap::new();
+    let mut per_mode: BTreeMap<&str, u64> = BTreeMap::new();
+    for row in &rows {
+        *per_day.entry(&row.day).or_default() += row.seconds;
+        let domain = per_domain.entry(&row.domain).or_default();
+        domain.0 += row.seconds;
+        domain.1 += row.visits;
+        *per_mode.entry(&row.mode).or_default() += row.seconds;
+    }
+
+    let days = from
+        .iter_days()
+        .take_while(|d| *d <= to)
+        .map(|d| {
+            let date = day_key(d);
+            let seconds = per_day.get(date.as_str()).copied().unwrap_or(0);
+            DayUsage { date, seconds }
+        })
+        .collect();
+    let mut domains: Vec<DomainUsage> = per_domain
+        .into_iter()
+        .filter(|(_, (seconds, _))| *seconds > 0)
+        .map(|(domain, (seconds, visits))| DomainUsage { domain: domain.to_string(), seconds, visits, share: share(seconds) })
+        .collect();
+    domains.sort_by(|a, b| b.seconds.cmp(&a.seconds).then_with(|| a.domain.cmp(&b.domain)));
+    domains.truncate(TOP_DOMAINS);
+    let mut modes: Vec<ModeUsage> = per_mode
+        .into_iter()
+        .filter(|(_, seconds)| *seconds > 0)
+        .map(|(mode, seconds)| ModeUsage { mode: mode.to_string(), seconds, share: share(seconds) })
+        .collect();
+    modes.sort_by(|a, b| b.seconds.cmp(&a.seconds));
+
+    Ok(UsageReport {
+        range: range.to_string(),
+        from: day_key(from),
+        to: day_key(to),
+        total_seconds,
+        days,
+        domains,
+        modes,
+    })
+}
+
+fn record(db: &Database, interval: &Interval, elapsed: Duration, visits: u64) {
+    let seconds = elapsed.as_secs();
+    if seconds == 0 && visits == 0 {
+        return;
+    }
+    let row = UsageRow {
+        day: day_key(Local::now().date_naive()),
+        domain: interval.domain.clone(),
+        mode: interval.mode.clone(),
+        seconds,
+        visits,
+    };
+    if let Err(e) = db.usage_add(&row) {
+        tracing::warn!(target: "db", "Usage: Failed to record time for {}: {}", interval.domain, e);
+    }
+}
+
+// Web pages only; "www." is folded into the bare domain
+fn domain_of(url: &str) -> Option<String> {
+    let url = url::Url::parse(url).ok().filter(|u| matches!(u.scheme(), "http" | "https"))?;
+    let host = url.host_str()?.trim_end_matches('.').to_lowercase();
+    Some(host.strip_prefix("www.").map(str::to_string).unwrap_or(host))
+}
+
+fn day_key(day: NaiveDate) -> String {
+    day.format("%Y-%m-%d").to_string()
+}
+
+#[derive(Debug, Clone)]
+pub enum UsageError {
+    InvalidRange(String),
+    Storage(String),
+}
+
+impl std::fmt::Display for UsageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UsageError::InvalidRange(range) => write!(
+                f,
+                "Invalid usage range '{}' (expected today, week, month, year, or a number of days)",
+                range
+            ),
+            UsageError::Storage(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for UsageError {}
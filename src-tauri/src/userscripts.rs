@@ -0,0 +1,494 @@
+// User Scripts - Extension-lite customization: per-site scripts and styles injected by the frontend
+// Stored in SQLite; the frontend asks `for_url` at navigation what to inject. Greasemonkey-style
+// metadata blocks (==UserScript== / ==UserStyle==) are parsed on import, and @updateURL is re-checked.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::db::Database;
+
+pub const MAX_CODE_BYTES: usize = 1024 * 1024;
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+const MAX_NAME_CHARS: usize = 200;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum ScriptKind {
+    #[default]
+    Script,
+    Style,
+}
+
+// When the frontend injects; styles always go in at document-start
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "kebab-case")]
+pub enum RunAt {
+    DocumentStart,
+    DocumentEnd,
+    #[default]
+    DocumentIdle,
+}
+
+impl RunAt {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RunAt::DocumentStart => "document-start",
+            RunAt::DocumentEnd => "document-end",
+            RunAt::DocumentIdle => "document-idle",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "document-start" => Some(RunAt::DocumentStart),
+            "document-end" => Some(RunAt::DocumentEnd),
+            "document-idle" => Some(RunAt::DocumentIdle),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct UserScript {
+    pub id: String,
+    pub name: String,
+    pub namespace: Option<String>,     // With name, identifies a script across updates and re-imports
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub kind: ScriptKind,
+    pub matches: Vec<String>,          // Match patterns (https://*.example.com/*) or @include globs
+    pub excludes: Vec<String>,
+    pub run_at: RunAt,
+    pub grants: Vec<String>,           // @grant values; GM_* APIs are not provided, the frontend may warn
+    pub code: String,
+    pub enabled: bool,
+    pub update_url: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+// Scripts and styles written in the app rather than imported
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct UserScriptInput {
+    pub id: Option<String>,            // Set to edit an existing script
+    pub name: String,
+    pub kind: ScriptKind,
+    pub code: String,
+    pub origin: Option<String>,        // Shorthand for matching one site: https://example.com
+    pub matches: Vec<String>,
+    pub excludes: Vec<String>,
+    pub run_at: Option<RunAt>,
+    pub enabled: Option<bool>,
+    pub update_url: Option<String>,
+}
+
+// What the frontend injects into a page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct Injection {
+    pub id: String,
+    pub name: String,
+    pub kind: ScriptKind,
+    pub run_at: RunAt,
+    pub code: String,
+}
+
+// Parsed ==UserScript== / ==UserStyle== block
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Metadata {
+    pub kind: ScriptKind,
+    pub name: Option<String>,
+    pub namespace: Option<String>,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub matches: Vec<String>,
+    pub excludes: Vec<String>,
+    pub run_at: Option<RunAt>,
+    pub grants: Vec<String>,
+    pub update_url: Option<String>,
+    pub download_url: Option<String>,
+}
+
+// Scripts and styles to inject into `url`, styles first, then scripts by run-at
+pub fn for_url(db: &Database, url: &str) -> Result<Vec<Injection>, UserScriptError> {
+    let mut scripts: Vec<UserScript> = db
+        .userscript_list()
+        .map_err(|e| UserScriptError::Storage(e.to_string()))?
+        .into_iter()
+        .filter(|s| s.enabled && applies_to(s, url))
+        .collect();
+    scripts.sort_by_key(|s| (s.kind == ScriptKind::Script, s.run_at, s.created_at));
+    Ok(scripts
+        .into_iter()
+        .map(|s| Injection {
+            id: s.id,
+            name: s.name,
+            kind: s.kind,
+            run_at: if s.kind == ScriptKind::Style { RunAt::DocumentStart } else { s.run_at },
+            code: s.code,
+        })
+        .collect())
+}
+
+pub fn applies_to(script: &UserScript, url: &str) -> bool {
+    script.matches.iter().any(|p| pattern_matches(p, url)) && !script.excludes.iter().any(|p| pattern_matches(p, url))
+}
+
+pub fn save(db: &Database, input: UserScriptInput) -> Result<UserScript, UserScriptError> {
+    let name = input.name.trim().to_string();
+    if name.is_empty() || name.chars().count() > MAX_NAME_CHARS {
+        return Err(UserScriptError::Invalid(format!("name must be 1-{} characters", MAX_NAME_CHARS)));
+    }
+    let mut matches = input.matches;
+    if let Some(origin) = input.origin.as_deref().map(str::trim).filter(|o| !o.is_empty()) {
+        matches.push(origin_pattern(origin)?);
+    }
+    let existing = match &input.id {
+        Some(id) => Some(get(db, id)?),
+        None => None,
+    };
+    let now = chrono::Utc::now().timestamp();
+    let script = UserScript {
+        id: existing.as_ref().map(|s| s.id.clone()).unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        name,
+        namespace: existing.as_ref().and_then(|s| s.namespace.clone()),
+        version: existing.as_ref().and_then(|s| s.version.clone()),
+        description: existing.as_ref().and_then(|s| s.description.clone()),
+        kind: input.kind,
+        matches,
+        excludes: input.excludes,
+        run_at: input.run_at.unwrap_or_default(),
+        grants: existing.as_ref().map(|s| s.grants.clone()).unwrap_or_default(),
+        code: input.code,
+        enabled: input.enabled.or(existing.as_ref().map(|s| s.enabled)).unwrap_or(true),
+        update_url: input.update_url.filter(|u| !u.trim().is_empty()),
+        created_at: existing.as_ref().map(|s| s.created_at).unwrap_or(now),
+        updated_at: now,
+    };
+    store(db, &script)?;
+    Ok(script)
+}
+
+// Import a script or style with a metadata block; the same name + namespace replaces the earlier
+// copy (keeping its id and enabled flag)
+pub fn import(db: &Database, source: &str, source_url: Option<&str>) -> Result<UserScript, UserScriptError> {
+    let meta = parse_metadata(source).ok_or(UserScriptError::NoMetadata)?;
+    let name = meta.name.clone().ok_or_else(|| UserScriptError::Invalid("metadata has no @name".to_string()))?;
+    let existing = db
+        .userscript_find(&name, meta.namespace.as_deref())
+        .map_err(|e| UserScriptError::Storage(e.to_string()))?;
+    let now = chrono::Utc::now().timestamp();
+    let script = UserScript {
+        id: existing.as_ref().map(|s| s.id.clone()).unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        name,
+        namespace: meta.namespace,
+        version: meta.version,
+        description: meta.description,
+        kind: meta.kind,
+        matches: meta.matches,
+        excludes: meta.excludes,
+        run_at: meta.run_at.unwrap_or_default(),
+        grants: meta.grants,
+        code: source.to_string(),
+        enabled: existing.as_ref().map(|s| s.enabled).unwrap_or(true),
+        // Plain-http update URLs from the metadata are dropped rather than refusing the script
+        update_url: [meta.update_url, meta.download_url, source_url.map(str::to_string)]
+            .into_iter()
+            .flatten()
+            .find(|u| is_https(u)),
+        created_at: existing.as_ref().map(|s| s.created_at).unwrap_or(now),
+        updated_at: now,
+    };
+    store(db, &script)?;
+    Ok(script)
+}
+
+// Fetch a .user.js / .user.css and import it
+pub async fn install(db: &Database, url: &str) -> Result<UserScript, UserScriptError> {
+    let source = fetch(url).await?;
+    import(db, &source, Some(url))
+}
+
+// Re-fetch from the update URL and replace the script when the published @version is newer.
+// Returns the updated script, or None when it is already current.
+pub async fn check_update(db: &Database, id: &str) -> Result<Option<UserScript>, UserScriptError> {
+    let script = get(db, id)?;
+    let url = script.update_url.clone().ok_or_else(|| UserScriptError::NoUpdateUrl(script.name.clone()))?;
+    let source = fetch(&url).await?;
+    let meta = parse_metadata(&source).ok_or(UserScriptError::NoMetadata)?;
+    let newer = match (&meta.version, &script.version) {
+        (Some(remote), Some(local)) => compare_versions(remote, local).is_gt(),
+        (Some(_), None) => true,
+        // Unversioned: any change counts
+        (None, _) => source != script.code,
+    };
+    if !newer {
+        return Ok(None);
+    }
+    if meta.name.as_deref() != Some(script.name.as_str()) || meta.namespace != script.namespace {
+        return Err(UserScriptError::Invalid(format!("{} now publishes a different script", url)));
+    }
+    let updated = UserScript {
+        version: meta.version,
+        description: meta.description.or(script.description),
+        kind: meta.kind,
+        matches: meta.matches,
+        excludes: meta.excludes,
+        run_at: meta.run_at.unwrap_or_default(),
+        grants: meta.grants,
+        code: source,
+        update_url: meta.update_url.filter(|u| is_https(u)).or(Some(url)),
+        updated_at: chrono::Utc::now().timestamp(),
+        ..script
+    };
+    store(db, &updated)?;
+    tracing::info!(target: "app", "User scripts: Updated {} to {}", updated.name, updated.version.as_deref().unwrap_or("latest"));
+    Ok(Some(updated))
+}
+
+pub fn get(db: &Database, id: &str) -> Result<UserScript, UserScriptError> {
+    db.userscript_get(id)
+        .map_err(|e| UserScriptError::Storage(e.to_string()))?
+        .ok_or_else(|| UserScriptError::NotFound(id.to_string()))
+}
+
+fn store(db: &Database, script: &UserScript) -> Result<(), UserScriptError> {
+    if script.code.trim().is_empty() {
+        return Err(UserScriptError::Invalid("code is empty".to_string()));
+    }
+    if script.code.len() > MAX_CODE_BYTES {
+        return Err(UserScriptError::TooLarge);
+    }
+    if let Some(url) = script.update_url.as_deref().filter(|u| !is_https(u)) {
+        return Err(UserScriptError::Invalid(format!("update URL {} is not https://", url)));
+    }
+    if script.matches.is_empty() {
+        return Err(UserScriptError::Invalid("no @match, @include or origin; the script would never run".to_string()));
+    }
+    if let Some(pattern) = script.matches.iter().chain(&script.excludes).find(|p| !valid_pattern(p)) {
+        return Err(UserScriptError::Invalid(format!("bad match pattern '{}'", pattern)));
+    }
+    db.userscript_save(script).map_err(|e| UserScriptError::Storage(e.to_string()))
+}
+
+// Code is injected into every matching page, so it is only ever fetched over TLS
+fn is_https(url: &str) -> bool {
+    url::Url::parse(url).is_ok_and(|u| u.scheme() == "https")
+}
+
+async fn fetch(url: &str) -> Result<String, UserScriptError> {
+    if !is_https(url) {
+        return Err(UserScriptError::Fetch(format!("{} is not an https:// URL", url)));
+    }
+    let client = crate::http::Client::new(FETCH_TIMEOUT).with_retries(1).with_purpose("userscripts");
+    let response = client.send(client.get(url)).await.map_err(|e| UserScriptError::Fetch(e.to_string()))?;
+    // A redirect could still land on plain http
+    if response.url().scheme() != "https" {
+        return Err(UserScriptError::Fetch(format!("{} redirected to {}", url, response.url())));
+    }
+    if !response.status().is_success() {
+        return Err(UserScriptError::Fetch(format!("{} returned {}", url, response.status())));
+    }
+    let body = crate::http::read_body(response, MAX_CODE_BYTES).await.map_err(|e| match e {
+        crate::http::HttpError::TooLarge(_) => UserScriptError::TooLarge,
+        e => UserScriptError::Fetch(e.to_string()),
+    })?;
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+// ----------------------------------------------------------------------------
+// Metadata
+// ----------------------------------------------------------------------------
+
+// Reads `// @key value` lines between ==UserScript== and ==/UserScript== (or the ==UserStyle== block
+// of a user style, whose lines may or may not carry the `//` prefix)
+pub fn parse_metadata(source: &str) -> Option<Metadata> {
+    let mut meta = Metadata::default();
+    let mut inside = false;
+    let mut found = false;
+    for line in source.lines() {
+        let line = line.trim().trim_start_matches("/*").trim_start_matches("//").trim();
+        if !inside {
+            match line {
+                "==UserScript==" => meta.kind = ScriptKind::Script,
+                "==UserStyle==" => meta.kind = ScriptKind::Style,
+                _ => continue,
+            }
+            inside = true;
+            continue;
+        }
+        if line.starts_with("==/UserScript==") || line.starts_with("==/UserStyle==") {
+            found = true;
+            break;
+        }
+        let Some(rest) = line.strip_prefix('@') else {
+            continue;
+        };
+        let (key, value) = match rest.split_once(char::is_whitespace) {
+            Some((key, value)) => (key, value.trim().to_string()),
+            None => (rest, String::new()),
+        };
+        match key {
+            "name" => meta.name = Some(value).filter(|v| !v.is_empty()),
+            "namespace" => meta.namespace = Some(value).filter(|v| !v.is_empty()),
+            "version" => meta.version = Some(value).filter(|v| !v.is_empty()),
+            "description" => meta.description = Some(value).filter(|v| !v.is_empty()),
+            "match" | "include" if !value.is_empty() => meta.matches.push(value),
+            "exclude" | "exclude-match" if !value.is_empty() => meta.excludes.push(value),
+            "run-at" => meta.run_at = RunAt::parse(&value),
+            "grant" if !value.is_empty() => meta.grants.push(value),
+            "updateURL" => meta.update_url = Some(value).filter(|v| !v.is_empty()),
+            "downloadURL" => meta.download_url = Some(value).filter(|v| !v.is_empty()),
+            _ => {}
+        }
+    }
+    found.then_some(meta)
+}
+
+// Dotted versions compared numerically part by part ("1.10" > "1.9"); non-numeric parts as text
+pub fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    let mut left = a.trim().split(['.', '-']);
+    let mut right = b.trim().split(['.', '-']);
+    loop {
+        let ordering = match (left.next(), right.next()) {
+            (None, None) => return std::cmp::Ordering::Equal,
+            (Some(x), None) => if x.trim_matches('0').is_empty() { continue } else { return std::cmp::Ordering::Greater },
+            (None, Some(y)) => if y.trim_matches('0').is_empty() { continue } else { return std::cmp::Ordering::Less },
+            (Some(x), Some(y)) => match (x.parse::<u64>(), y.parse::<u64>()) {
+                (Ok(x), Ok(y)) => x.cmp(&y),
+                _ => x.cmp(y),
+            },
+        };
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// URL patterns
+// ----------------------------------------------------------------------------
+
+// https://example.com -> https://example.com/*
+fn origin_pattern(origin: &str) -> Result<String, UserScriptError> {
+    let url = url::Url::parse(origin).map_err(|e| UserScriptError::Invalid(format!("origin '{}': {}", origin, e)))?;
+    let host = url.host_str().ok_or_else(|| UserScriptError::Invalid(format!("origin '{}' has no host", origin)))?;
+    Ok(match url.port() {
+        Some(port) => format!("{}://{}:{}/*", url.scheme(), host, port),
+        None => format!("{}://{}/*", url.scheme(), host),
+    })
+}
+
+fn valid_pattern(pattern: &str) -> bool {
+    pattern == "<all_urls>" || pattern.contains('*') || pattern.contains("://")
+}
+
+// Match patterns (scheme://host/path, with `*.` subdomain wildcards) when the pattern has that shape;
+// otherwise a Greasemonkey @include glob over the whole URL
+pub fn pattern_matches(pattern: &str, url: &str) -> bool {
+    if pattern == "<all_urls>" || pattern == "*" {
+        return url.starts_with("http://") || url.starts_with("https://") || url.starts_with("file://");
+    }
+    if let Some(matched) = match_pattern(pattern, url) {
+        return matched;
+    }
+    glob_matches(pattern, url)
+}
+
+// None when `pattern` isn't a well-formed match pattern (then it is treated as a glob)
+fn match_pattern(pattern: &str, url: &str) -> Option<bool> {
+    let (scheme, rest) = pattern.split_once("://")?;
+    if !matches!(scheme, "*" | "http" | "https" | "file") {
+        return None;
+    }
+    let (host, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => return None,
+    };
+    if host.contains('*') && host != "*" && !(host.starts_with("*.") && !host[2..].contains('*')) {
+        return None;
+    }
+
+    let Ok(parsed) = url::Url::parse(url) else {
+        return Some(false);
+    };
+    let scheme_ok = match scheme {
+        "*" => matches!(parsed.scheme(), "http" | "https"),
+        s => parsed.scheme() == s,
+    };
+    let url_host = parsed.host_str().unwrap_or_default().to_lowercase();
+    // A port in the pattern must match; without one any port is fine
+    let (host, port) = match host.rsplit_once(':') {
+        Some((h, p)) if p.parse::<u16>().is_ok() => (h, p.parse::<u16>().ok()),
+        _ => (host, None),
+    };
+    let host = host.to_lowercase();
+    let host_ok = if host == "*" {
+        true
+    } else if let Some(domain) = host.strip_prefix("*.") {
+        url_host == domain || url_host.ends_with(&format!(".{}", domain))
+    } else {
+        url_host == host
+    };
+    let port_ok = port.map_or(true, |p| parsed.port_or_known_default() == Some(p));
+    let mut full_path = parsed.path().to_string();
+    if let Some(query) = parsed.query() {
+        full_path.push('?');
+        full_path.push_str(query);
+    }
+    Some(scheme_ok && host_ok && port_ok && glob_matches(path, &full_path))
+}
+
+// `*` matches any run of characters; everything else literally
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == text;
+    }
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !text.starts_with(first) || text.len() < first.len() + last.len() || !text.ends_with(last) {
+        return false;
+    }
+    let mut rest = &text[first.len()..text.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+#[derive(Debug, Clone)]
+pub enum UserScriptError {
+    Invalid(String),
+    NoMetadata,
+    NoUpdateUrl(String),
+    TooLarge,
+    NotFound(String),
+    Fetch(String),
+    Storage(String),
+}
+
+impl std::fmt::Display for UserScriptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UserScriptError::Invalid(e) => write!(f, "Invalid user script: {}", e),
+            UserScriptError::NoMetadata => write!(f, "No ==UserScript== or ==UserStyle== metadata block found"),
+            UserScriptError::NoUpdateUrl(name) => write!(f, "{} has no update URL", name),
+            UserScriptError::TooLarge => write!(f, "User script is over the {} byte limit", MAX_CODE_BYTES),
+            UserScriptError::NotFound(id) => write!(f, "User script {} not found", id),
+            UserScriptError::Fetch(e) => write!(f, "Failed to fetch user script: {}", e),
+            UserScriptError::Storage(e) => write!(f, "User script storage error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for UserScriptError {}
@@ -0,0 +1,415 @@
+// Verify - Checksums and detached signatures for downloaded files
+// Hashes stream the file (MD5, SHA-1, SHA-256, SHA-512). Signatures are minisign (checked in-process) or
+// OpenPGP (checked by the local gpg, in a throwaway keyring when a key is supplied). Downloads can run
+// both as a post-download step; the result is stored on the download row (see `verify_download`).
+
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tauri::{Emitter, Manager};
+
+use crate::db::Database;
+use crate::services::binaries;
+
+const BUFFER_SIZE: usize = 64 * 1024;
+// Signatures and keys longer than this are taken as text, never as a path
+const MAX_PATH_LEN: usize = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgo {
+    Md5,
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+impl HashAlgo {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HashAlgo::Md5 => "md5",
+            HashAlgo::Sha1 => "sha1",
+            HashAlgo::Sha256 => "sha256",
+            HashAlgo::Sha512 => "sha512",
+        }
+    }
+
+    // From the digest's hex length, for when the caller doesn't say
+    fn from_hex_len(len: usize) -> Option<Self> {
+        match len {
+            32 => Some(HashAlgo::Md5),
+            40 => Some(HashAlgo::Sha1),
+            64 => Some(HashAlgo::Sha256),
+            128 => Some(HashAlgo::Sha512),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureKind {
+    Minisign,
+    Gpg,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ChecksumCheck {
+    pub algo: HashAlgo,
+    pub expected: String,
+    pub actual: String,
+    pub matches: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureCheck {
+    pub kind: SignatureKind,
+    pub valid: bool,
+    pub signer: Option<String>,        // GPG user id and fingerprint
+    pub trusted_comment: Option<String>, // Minisign only
+    pub detail: String,
+}
+
+// Post-download checks requested by the download manager; every field is optional
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct VerifyRequest {
+    pub expected_hash: Option<String>,
+    pub algo: Option<HashAlgo>,
+    pub signature: Option<String>,     // Signature text or a path to the .minisig/.sig/.asc file
+    pub public_key: Option<String>,    // Key text or path; GPG falls back to the user's keyring
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadVerification {
+    pub download_id: String,
+    pub checksum: Option<ChecksumCheck>,
+    pub signature: Option<SignatureCheck>,
+    pub error: Option<String>,         // Set when a check could not run at all
+    pub passed: bool,
+    pub verified_at: i64,
+}
+
+// Hex digest of a file
+pub fn hash_file(path: &Path, algo: HashAlgo) -> Result<String, VerifyError> {
+    fn digest<D: sha2::Digest>(mut file: File) -> Result<String, VerifyError> {
+        let mut hasher = D::new();
+        let mut buffer = vec![0u8; BUFFER_SIZE];
+        loop {
+            let read = file.read(&mut buffer).map_err(|e| VerifyError::Io(e.to_string()))?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+        }
+        Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+    }
+
+    let file = open(path)?;
+    match algo {
+        HashAlgo::Md5 => digest::<md5::Md5>(file),
+        HashAlgo::Sha1 => digest::<sha1::Sha1>(file),
+        HashAlgo::Sha256 => digest::<sha2::Sha256>(file),
+        HashAlgo::Sha512 => digest::<sha2::Sha512>(file),
+    }
+}
+
+// `expected` may be a bare hex digest, "sha256:<hex>", or a line copied from a SHA256SUMS file
+pub fn verify_file(path: &Path, expected: &str, algo: Option<HashAlgo>) -> Result<ChecksumCheck, VerifyError> {
+    let expected = expected.trim();
+    let expected = expected.split_once(':').map_or(expected, |(_, digest)| digest);
+    let expected = expected
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .trim_start_matches('*')
+        .to_ascii_lowercase();
+    if expected.is_empty() || !expected.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(VerifyError::InvalidHash(expected));
+    }
+    let algo = match algo {
+        Some(algo) => algo,
+        None => HashAlgo::from_hex_len(expected.len()).ok_or_else(|| VerifyError::InvalidHash(expected.clone()))?,
+    };
+    let actual = hash_file(path, algo)?;
+    Ok(ChecksumCheck { algo, matches: actual == expected, expected, actual })
+}
+
+// Detached signature check; the kind is told apart by the signature's header
+pub fn verify_signature(
+    app: &tauri::AppHandle,
+    path: &Path,
+    signature: &str,
+    key: Option<&str>,
+) -> Result<SignatureCheck, VerifyError> {
+    open(path)?;
+    let signature = read_text_or_file(signature)?;
+    let key = key.map(read_text_or_file).transpose()?;
+    if signature.trim_start().starts_with("untrusted comment:") {
+        let key = key.ok_or(VerifyError::KeyRequired)?;
+        verify_minisign(path, &signature, &key)
+    } else {
+        verify_gpg(app, path, signature.as_bytes(), key.as_deref())
+    }
+}
+
+// Run the requested checks on a finished download, store them on its row, and emit "downloads:verified".
+// The row reads "verifying" meanwhile; a failed check also marks it "warning" and posts a notification.
+pub async fn verify_download(app: tauri::AppHandle, id: String, request: VerifyRequest) -> Result<DownloadVerification, VerifyError> {
+    let db = app.state::<Database>();
+    let path = db
+        .download_path(&id)
+        .map_err(|e| VerifyError::Storage(e.to_string()))?
+        .ok_or_else(|| VerifyError::NotFound(id.clone()))?;
+    db.download_set_status(&id, "verifying").map_err(|e| VerifyError::Storage(e.to_string()))?;
+
+    let handle = app.clone();
+    let file = PathBuf::from(&path);
+    let checks = request;
+    let outcome = tauri::async_runtime::spawn_blocking(move || {
+        let checksum = checks
+            .expected_hash
+            .as_deref()
+            .filter(|hash| !hash.trim().is_empty())
+            .map(|hash| verify_file(&file, hash, checks.algo))
+            .transpose()?;
+        let signature = checks
+            .signature
+            .as_deref()
+            .filter(|sig| !sig.trim().is_empty())
+            .map(|sig| verify_signature(&handle, &file, sig, checks.public_key.as_deref()))
+            .transpose()?;
+        Ok::<_, VerifyError>((checksum, signature))
+    })
+    .await
+    .map_err(|e| VerifyError::Io(e.to_string()))?;
+
+    let (checksum, signature, error) = match outcome {
+        Ok((checksum, signature)) => (checksum, signature, None),
+        Err(e) => (None, None, Some(e.to_string())),
+    };
+    let passed = error.is_none()
+        && checksum.as_ref().is_none_or(|c| c.matches)
+        && signature.as_ref().is_none_or(|s| s.valid);
+    let verification = DownloadVerification {
+        download_id: id.clone(),
+        checksum,
+        signature,
+        error,
+        passed,
+        verified_at: chrono::Utc::now().timestamp(),
+    };
+
+    let stored = checksum_label(&verification);
+    let json = serde_json::to_string(&verification).map_err(|e| VerifyError::Storage(e.to_string()))?;
+    let safety = (!passed).then_some("warning");
+    db.download_set_verification(&id, stored.as_deref(), safety, &json)
+        .map_err(|e| VerifyError::Storage(e.to_string()))?;
+    if !passed {
+        tracing::warn!(target: "privacy", "Verify: Download {} failed verification", id);
+        let name = Path::new(&path).file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_else(|| path.clone());
+        crate::notifications::post(&app, crate::notifications::NotificationRequest {
+            title: "Download failed verification".to_string(),
+            body: name,
+            kind: crate::notifications::NotificationKind::Download,
+            payload: Some(serde_json::json!({ "downloadId": id, "path": path })),
+            ..Default::default()
+        });
+    }
+    let _ = app.emit("downloads:verified", &verification);
+    Ok(verification)
+}
+
+// "sha256:<hex>" of the computed digest, for the downloads table's checksum column
+fn checksum_label(verification: &DownloadVerification) -> Option<String> {
+    verification.checksum.as_ref().map(|c| format!("{}:{}", c.algo.as_str(), c.actual))
+}
+
+fn verify_minisign(path: &Path, signature: &str, key: &str) -> Result<SignatureCheck, VerifyError> {
+    let key = key.trim();
+    // Either the whole .pub file or just its base64 line
+    let public_key = if key.starts_with("untrusted comment:") {
+        minisign_verify::PublicKey::decode(key)
+    } else {
+        minisign_verify::PublicKey::from_base64(key)
+    }
+    .map_err(|e| VerifyError::InvalidKey(e.to_string()))?;
+    let signature = minisign_verify::Signature::decode(signature).map_err(|e| VerifyError::InvalidSignature(e.to_string()))?;
+
+    // Prehashed signatures (the default since minisign 0.8) stream; legacy ones need the whole file
+    let result = match public_key.verify_stream(&signature) {
+        Ok(mut verifier) => {
+            let mut file = open(path)?;
+            let mut buffer = vec![0u8; BUFFER_SIZE];
+            loop {
+                let read = file.read(&mut buffer).map_err(|e| VerifyError::Io(e.to_string()))?;
+                if read == 0 {
+                    break;
+                }
+                verifier.update(&buffer[..read]);
+            }
+            verifier.finalize()
+        }
+        Err(_) => {
+            let contents = std::fs::read(path).map_err(|e| VerifyError::Io(e.to_string()))?;
+            public_key.verify(&contents, &signature, true)
+        }
+    };
+    Ok(SignatureCheck {
+        kind: SignatureKind::Minisign,
+        valid: result.is_ok(),
+        signer: None,
+        trusted_comment: Some(signature.trusted_comment().to_string()),
+        detail: match result {
+            Ok(()) => "Signature matches the public key".to_string(),
+            Err(e) => e.to_string(),
+        },
+    })
+}
+
+fn verify_gpg(app: &tauri::AppHandle, path: &Path, signature: &[u8], key: Option<&str>) -> Result<SignatureCheck, VerifyError> {
+    let dirs = binaries::search_dirs(app.path().resource_dir().ok().as_deref());
+    let gpg = binaries::locate("gpg", &dirs)
+        .filter(|b| b.executable)
+        .ok_or_else(|| VerifyError::ToolMissing("gpg".to_string()))?;
+
+    // A supplied key goes into a throwaway home so the user's keyring is neither used nor changed
+    let scratch = std::env::temp_dir().join(format!("regen-verify-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&scratch).map_err(|e| VerifyError::Io(e.to_string()))?;
+    let result = (|| {
+        let signature_path = scratch.join("file.sig");
+        std::fs::write(&signature_path, signature).map_err(|e| VerifyError::Io(e.to_string()))?;
+        let mut home = Vec::new();
+        if let Some(key) = key {
+            let key_path = scratch.join("key.asc");
+            std::fs::write(&key_path, key).map_err(|e| VerifyError::Io(e.to_string()))?;
+            home = vec!["--homedir".into(), scratch.as_os_str().to_owned()];
+            let import = Command::new(&gpg.path)
+                .args(&home)
+                .args(["--batch", "--quiet", "--import"])
+                .arg(&key_path)
+                .output()
+                .map_err(|e| VerifyError::Gpg(e.to_string()))?;
+            if !import.status.success() {
+                return Err(VerifyError::InvalidKey(String::from_utf8_lossy(&import.stderr).trim().to_string()));
+            }
+        }
+        let output = Command::new(&gpg.path)
+            .args(&home)
+            .args(["--batch", "--status-fd", "1", "--verify"])
+            .arg(&signature_path)
+            .arg(path)
+            .output()
+            .map_err(|e| VerifyError::Gpg(e.to_string()))?;
+        Ok(parse_gpg_status(&String::from_utf8_lossy(&output.stdout), &String::from_utf8_lossy(&output.stderr)))
+    })();
+    let _ = std::fs::remove_dir_all(&scratch);
+    result
+}
+
+// [GNUPG:] status lines: GOODSIG/BADSIG carry the user id, VALIDSIG the fingerprint
+fn parse_gpg_status(status: &str, stderr: &str) -> SignatureCheck {
+    let mut check = SignatureCheck {
+        kind: SignatureKind::Gpg,
+        valid: false,
+        signer: None,
+        trusted_comment: None,
+        detail: stderr.trim().lines().last().unwrap_or("gpg reported no signature").to_string(),
+    };
+    let mut user = None;
+    let mut fingerprint = None;
+    for line in status.lines() {
+        let Some(rest) = line.strip_prefix("[GNUPG:] ") else {
+            continue;
+        };
+        let mut fields = rest.splitn(3, ' ');
+        match fields.next() {
+            Some("GOODSIG") => {
+                check.valid = true;
+                user = fields.nth(1).map(str::to_string);
+            }
+            Some("BADSIG") => {
+                check.valid = false;
+                user = fields.nth(1).map(str::to_string);
+                check.detail = "Bad signature".to_string();
+            }
+            Some("VALIDSIG") => fingerprint = fields.next().map(str::to_string),
+            Some("NO_PUBKEY") => check.detail = "The signing key is not in the keyring".to_string(),
+            Some("EXPKEYSIG") | Some("REVKEYSIG") => {
+                check.valid = false;
+                check.detail = format!("Signed with {} key", if rest.starts_with("EXP") { "an expired" } else { "a revoked" });
+            }
+            _ => {}
+        }
+    }
+    if check.valid {
+        check.detail = "Good signature".to_string();
+    }
+    check.signer = match (user, fingerprint) {
+        (Some(user), Some(fingerprint)) => Some(format!("{} ({})", user, fingerprint)),
+        (user, fingerprint) => user.or(fingerprint),
+    };
+    check
+}
+
+// Signatures and keys arrive inline or as a path to the file holding them
+fn read_text_or_file(value: &str) -> Result<String, VerifyError> {
+    let path = Path::new(value.trim());
+    if value.len() <= MAX_PATH_LEN && !value.contains('\n') && path.is_file() {
+        return std::fs::read_to_string(path).map_err(|e| VerifyError::Io(e.to_string()));
+    }
+    Ok(value.to_string())
+}
+
+fn open(path: &Path) -> Result<File, VerifyError> {
+    File::open(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => VerifyError::FileNotFound(path.display().to_string()),
+        _ => VerifyError::Io(e.to_string()),
+    })
+}
+
+#[derive(Debug, Clone)]
+pub enum VerifyError {
+    FileNotFound(String),
+    NotFound(String),
+    InvalidHash(String),
+    InvalidSignature(String),
+    InvalidKey(String),
+    KeyRequired,
+    ToolMissing(String),
+    Gpg(String),
+    Io(String),
+    Storage(String),
+}
+
+impl std::fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VerifyError::FileNotFound(path) => write!(f, "{} not found", path),
+            VerifyError::NotFound(id) => write!(f, "Download {} not found or has no file yet", id),
+            VerifyError::InvalidHash(hash) => {
+                write!(f, "'{}' is not an MD5, SHA-1, SHA-256, or SHA-512 hex digest", hash)
+            }
+            VerifyError::InvalidSignature(e) => write!(f, "Invalid signature: {}", e),
+            VerifyError::InvalidKey(e) => write!(f, "Invalid public key: {}", e),
+            VerifyError::KeyRequired => write!(f, "A minisign signature needs the public key to check against"),
+            VerifyError::ToolMissing(tool) => write!(f, "{} is not installed", tool),
+            VerifyError::Gpg(e) => write!(f, "gpg failed: {}", e),
+            VerifyError::Io(e) => write!(f, "Verification I/O error: {}", e),
+            VerifyError::Storage(e) => write!(f, "Download storage error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
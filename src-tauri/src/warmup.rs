@@ -0,0 +1,244 @@
+// Warm-up - Loads the configured chat and embedding models into Ollama right after startup
+// Ollama loads models lazily, so without this the first answer or embedding pays the whole load time
+
+use serde::{Deserialize, Serialize};
+use std::sync::{OnceLock, RwLock};
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+use crate::embeddings::ollama_url;
+
+// Ollama is started by the supervisor alongside this; give it (and a first-run pull) time to come up
+const READY_TIMEOUT: Duration = Duration::from_secs(120);
+const READY_POLL: Duration = Duration::from_secs(2);
+// A large model from a cold disk can take a while to load
+const LOAD_TIMEOUT: Duration = Duration::from_secs(180);
+const PS_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum ModelRole {
+    Chat,
+    Embed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum ModelState {
+    Pending,                           // Waiting for Ollama or for the model before it
+    Loading,
+    Ready,                             // Resident in Ollama
+    Unloaded,                          // Was warmed, since evicted (idle keep-alive or the memory budget)
+    Failed,
+    Skipped,                           // Not warmed; see `error` for why
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ModelWarmup {
+    pub model: String,
+    pub role: ModelRole,
+    pub state: ModelState,
+    pub load_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct WarmupStatus {
+    pub enabled: bool,                 // [ai] warmup, and not in safe mode
+    pub started_at: Option<i64>,       // Unix ms
+    pub finished_at: Option<i64>,
+    pub ready: bool,                   // Every warmed model is resident right now
+    pub models: Vec<ModelWarmup>,
+}
+
+#[derive(Deserialize)]
+struct OllamaPsResponse {
+    #[serde(default)]
+    models: Vec<OllamaPsModel>,
+}
+
+#[derive(Deserialize)]
+struct OllamaPsModel {
+    name: String,
+}
+
+fn status() -> &'static RwLock<WarmupStatus> {
+    static STATUS: OnceLock<RwLock<WarmupStatus>> = OnceLock::new();
+    STATUS.get_or_init(|| RwLock::new(WarmupStatus::default()))
+}
+
+// Start warming in the background if [ai] warmup is on; progress is emitted as "models:warmup"
+pub fn spawn(app: tauri::AppHandle, enabled: bool) {
+    let ai = crate::config::current().ai;
+    let enabled = enabled && ai.warmup;
+    let mut models = vec![(ai.chat_model(), ModelRole::Chat)];
+    let embed_model = ai.embed_model();
+    if embed_model != models[0].0 {
+        models.push((embed_model, ModelRole::Embed));
+    }
+
+    {
+        let mut status = status().write().unwrap();
+        status.enabled = enabled;
+        status.models = models
+            .iter()
+            .map(|(model, role)| ModelWarmup {
+                model: model.clone(),
+                role: *role,
+                state: if enabled { ModelState::Pending } else { ModelState::Skipped },
+                load_ms: None,
+                error: (!enabled).then(|| "warm-up is off".to_string()),
+            })
+            .collect();
+    }
+    if !enabled {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        status().write().unwrap().started_at = Some(chrono::Utc::now().timestamp_millis());
+        emit(&app);
+
+        if let Err(e) = wait_for_ollama().await {
+            tracing::warn!(target: "ai", "Warm-up: {}", e);
+            finish_all(ModelState::Failed, &e);
+            emit(&app);
+            return;
+        }
+
+        // With room for one resident model, loading the embedder would only evict the chat model
+        let max_loaded = crate::hardware::capabilities().models.max_loaded_models;
+        for (index, (model, role)) in models.iter().enumerate() {
+            if index >= max_loaded {
+                set(model, ModelState::Skipped, None, Some("Ollama keeps only one model loaded on this machine".to_string()));
+                continue;
+            }
+            set(model, ModelState::Loading, None, None);
+            emit(&app);
+            let started = Instant::now();
+            match load(model, *role).await {
+                Ok(()) => {
+                    let load_ms = started.elapsed().as_millis() as u64;
+                    tracing::info!(target: "ai", "Warm-up: Loaded {} in {}ms", model, load_ms);
+                    set(model, ModelState::Ready, Some(load_ms), None);
+                }
+                Err(e) => {
+                    tracing::warn!(target: "ai", "Warm-up: Failed to load {}: {}", model, e);
+                    set(model, ModelState::Failed, None, Some(e));
+                }
+            }
+            emit(&app);
+        }
+
+        status().write().unwrap().finished_at = Some(chrono::Utc::now().timestamp_millis());
+        emit(&app);
+    });
+}
+
+// The warm-up results, with Ready models re-checked against what Ollama has resident now
+pub async fn current() -> WarmupStatus {
+    let resident = resident_models().await;
+    let mut status = status().write().unwrap();
+    if let Some(resident) = resident {
+        for entry in status.models.iter_mut() {
+            let loaded = resident.iter().any(|name| same_model(name, &entry.model));
+            match entry.state {
+                ModelState::Ready if !loaded => entry.state = ModelState::Unloaded,
+                ModelState::Unloaded if loaded => entry.state = ModelState::Ready,
+                _ => {}
+            }
+        }
+    }
+    update_ready(&mut status);
+    status.clone()
+}
+
+async fn wait_for_ollama() -> Result<(), String> {
+    let client = crate::http::Client::new(READY_POLL).with_retries(0).with_purpose("model-warmup");
+    let deadline = Instant::now() + READY_TIMEOUT;
+    loop {
+        if let Ok(response) = client.send(client.get(format!("{}/api/version", ollama_url()))).await {
+            if response.status().is_success() {
+                return Ok(());
+            }
+        }
+        if Instant::now() >= deadline {
+            return Err(format!("Ollama did not answer within {}s", READY_TIMEOUT.as_secs()));
+        }
+        tokio::time::sleep(READY_POLL).await;
+    }
+}
+
+// One-token generate (or a one-word embed); either makes Ollama load the model and keep it resident
+async fn load(model: &str, role: ModelRole) -> Result<(), String> {
+    let client = crate::http::Client::new(LOAD_TIMEOUT).with_retries(0).with_purpose("model-warmup");
+    let request = match role {
+        ModelRole::Chat => client.post(format!("{}/api/generate", ollama_url())).json(&serde_json::json!({
+            "model": model,
+            "prompt": "Hi",
+            "stream": false,
+            "options": { "num_predict": 1 },
+        })),
+        ModelRole::Embed => client
+            .post(format!("{}/api/embed", ollama_url()))
+            .json(&serde_json::json!({ "model": model, "input": "warm-up" })),
+    };
+    let response = client.send(request).await.map_err(|e| e.to_string())?;
+    match response.status().as_u16() {
+        200..=299 => Ok(()),
+        404 => Err(format!("{} is not installed (ollama pull {})", model, model)),
+        status => Err(format!("HTTP {}: {}", status, response.text().await.unwrap_or_default().trim())),
+    }
+}
+
+// None when Ollama can't be asked, so the last known states stand
+async fn resident_models() -> Option<Vec<String>> {
+    let client = crate::http::Client::new(PS_TIMEOUT).with_retries(0).with_purpose("model-warmup");
+    let response = client.send(client.get(format!("{}/api/ps", ollama_url()))).await.ok()?;
+    let ps: OllamaPsResponse = response.json().await.ok()?;
+    Some(ps.models.into_iter().map(|m| m.name).collect())
+}
+
+// Ollama reports "nomic-embed-text:latest" for a model configured as "nomic-embed-text"
+fn same_model(resident: &str, configured: &str) -> bool {
+    resident == configured || (!configured.contains(':') && resident.strip_suffix(":latest") == Some(configured))
+}
+
+fn set(model: &str, state: ModelState, load_ms: Option<u64>, error: Option<String>) {
+    let mut status = status().write().unwrap();
+    if let Some(entry) = status.models.iter_mut().find(|m| m.model == model) {
+        entry.state = state;
+        entry.load_ms = load_ms;
+        entry.error = error;
+    }
+}
+
+fn finish_all(state: ModelState, error: &str) {
+    let mut status = status().write().unwrap();
+    for entry in status.models.iter_mut().filter(|m| m.state == ModelState::Pending) {
+        entry.state = state;
+        entry.error = Some(error.to_string());
+    }
+    status.finished_at = Some(chrono::Utc::now().timestamp_millis());
+}
+
+fn update_ready(status: &mut WarmupStatus) {
+    status.ready = status.enabled
+        && status.finished_at.is_some()
+        && status.models.iter().all(|m| matches!(m.state, ModelState::Ready | ModelState::Skipped));
+}
+
+fn emit(app: &tauri::AppHandle) {
+    let status = {
+        let mut status = status().write().unwrap();
+        update_ready(&mut status);
+        status.clone()
+    };
+    let _ = app.emit("models:warmup", status);
+}
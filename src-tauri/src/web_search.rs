@@ -0,0 +1,568 @@
+// Web Search - One provider trait over DuckDuckGo HTML, Brave, SearxNG, local Meilisearch and subscribed feeds
+// Providers run concurrently; results are merged by URL and ranked with reciprocal rank fusion
+
+use futures::future::BoxFuture;
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use crate::apikeys::{ApiKeyStore, Provider};
+use crate::coalesce::{self, Coalescer, Join};
+use crate::http::{self, HttpError};
+use crate::local_index::IndexKind;
+
+pub const PROVIDERS: &[&str] = &["duckduckgo", "brave", "searxng", "meilisearch", "feeds"];
+
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(8);
+const DEFAULT_LIMIT: usize = 10;
+const MAX_LIMIT: usize = 50;
+// Standard RRF constant: dampens the gap between first and second place
+const RRF_K: f64 = 60.0;
+// The HTML endpoint serves a stripped page to unknown agents
+const BROWSER_UA: &str = "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0 Safari/537.36";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct WebResult {
+    pub title: String,
+    pub url: String,
+    pub snippet: String,
+    pub score: f64,
+    pub sources: Vec<String>,          // Providers that returned this URL
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderOutcome {
+    pub provider: String,
+    pub ok: bool,
+    pub count: usize,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct WebSearchResponse {
+    pub query: String,
+    pub results: Vec<WebResult>,
+    pub providers: Vec<ProviderOutcome>,
+    pub offline: bool,                 // Remote providers were skipped
+}
+
+// A single provider's hit, before merging
+#[derive(Debug, Clone)]
+pub struct Hit {
+    pub title: String,
+    pub url: String,
+    pub snippet: String,
+}
+
+pub trait SearchProvider: Send + Sync {
+    fn id(&self) -> &'static str;
+
+    // Local providers keep working while offline
+    fn is_remote(&self) -> bool {
+        true
+    }
+
+    fn search<'a>(&'a self, query: &'a str, limit: usize) -> BoxFuture<'a, Result<Vec<Hit>, SearchError>>;
+}
+
+// ============================================================================
+// PROVIDERS
+// ============================================================================
+
+pub struct DuckDuckGo {
+    http: http::Client,
+}
+
+impl SearchProvider for DuckDuckGo {
+    fn id(&self) -> &'static str {
+        "duckduckgo"
+    }
+
+    fn search<'a>(&'a self, query: &'a str, limit: usize) -> BoxFuture<'a, Result<Vec<Hit>, SearchError>> {
+        Box::pin(async move {
+            let request = self.http.get("https://html.duckduckgo.com/html/").query(&[("q", query)]);
+            let html = self.http.send(request).await?.error_for_status()?.text().await?;
+            Ok(parse_duckduckgo(&html, limit))
+        })
+    }
+}
+
+fn parse_duckduckgo(html: &str, limit: usize) -> Vec<Hit> {
+    let document = Html::parse_document(html);
+    let result = Selector::parse("div.result:not(.result--ad)").unwrap();
+    let link = Selector::parse("a.result__a").unwrap();
+    let snippet = Selector::parse(".result__snippet").unwrap();
+
+    document
+        .select(&result)
+        .filter_map(|node| {
+            let anchor = node.select(&link).next()?;
+            let url = duckduckgo_target(anchor.value().attr("href")?)?;
+            Some(Hit {
+                title: collapse(&anchor.text().collect::<String>()),
+                url,
+                snippet: node.select(&snippet).next().map(|s| collapse(&s.text().collect::<String>())).unwrap_or_default(),
+            })
+        })
+        .take(limit)
+        .collect()
+}
+
+// Result links go through a redirect (//duckduckgo.com/l/?uddg=<target>)
+fn duckduckgo_target(href: &str) -> Option<String> {
+    let absolute = if href.starts_with("//") { format!("https:{}", href) } else { href.to_string() };
+    let url = url::Url::parse(&absolute).ok()?;
+    if url.path() == "/l/" {
+        return url.query_pairs().find(|(k, _)| k == "uddg").map(|(_, v)| v.into_owned());
+    }
+    matches!(url.scheme(), "http" | "https").then_some(absolute)
+}
+
+pub struct Brave {
+    http: http::Client,
+    key: String,
+}
+
+#[derive(Deserialize)]
+struct BraveResponse {
+    #[serde(default)]
+    web: Option<BraveWeb>,
+}
+
+#[derive(Deserialize)]
+struct BraveWeb {
+    #[serde(default)]
+    results: Vec<BraveResult>,
+}
+
+#[derive(Deserialize)]
+struct BraveResult {
+    title: String,
+    url: String,
+    #[serde(default)]
+    description: String,
+}
+
+impl SearchProvider for Brave {
+    fn id(&self) -> &'static str {
+        "brave"
+    }
+
+    fn search<'a>(&'a self, query: &'a str, limit: usize) -> BoxFuture<'a, Result<Vec<Hit>, SearchError>> {
+        Box::pin(async move {
+            let request = self
+                .http
+                .get("https://api.search.brave.com/res/v1/web/search")
+                .query(&[("q", query), ("count", &limit.min(20).to_string())])
+                .header("X-Subscription-Token", &self.key)
+                .header("Accept", "application/json");
+            let response: BraveResponse = self.http.send(request).await?.error_for_status()?.json().await?;
+            Ok(response
+                .web
+                .map(|web| web.results)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|r| Hit { title: strip_tags(&r.title), url: r.url, snippet: strip_tags(&r.description) })
+                .collect())
+        })
+    }
+}
+
+pub struct Searxng {
+    http: http::Client,
+    base_url: String,
+}
+
+#[derive(Deserialize)]
+struct SearxngResponse {
+    #[serde(default)]
+    results: Vec<SearxngResult>,
+}
+
+#[derive(Deserialize)]
+struct SearxngResult {
+    #[serde(default)]
+    title: String,
+    url: String,
+    #[serde(default)]
+    content: String,
+}
+
+impl SearchProvider for Searxng {
+    fn id(&self) -> &'static str {
+        "searxng"
+    }
+
+    fn is_remote(&self) -> bool {
+        !is_local_url(&self.base_url)
+    }
+
+    fn search<'a>(&'a self, query: &'a str, limit: usize) -> BoxFuture<'a, Result<Vec<Hit>, SearchError>> {
+        Box::pin(async move {
+            // Instances must have the json format enabled in settings.yml
+            let request = self
+                .http
+                .get(format!("{}/search", self.base_url.trim_end_matches('/')))
+                .query(&[("q", query), ("format", "json")]);
+            let response: SearxngResponse = self.http.send(request).await?.error_for_status()?.json().await?;
+            Ok(response
+                .results
+                .into_iter()
+                .take(limit)
+                .map(|r| Hit { title: collapse(&r.title), url: r.url, snippet: collapse(&r.content) })
+                .collect())
+        })
+    }
+}
+
+pub struct Meilisearch {
+    http: http::Client,
+    base_url: String,
+}
+
+#[derive(Deserialize)]
+struct MeiliResponse {
+    #[serde(default)]
+    hits: Vec<MeiliHit>,
+}
+
+#[derive(Deserialize)]
+struct MeiliHit {
+    url: String,
+    #[serde(default)]
+    title: String,
+    #[serde(rename = "_formatted", default)]
+    formatted: Option<MeiliFormatted>,
+}
+
+#[derive(Deserialize)]
+struct MeiliFormatted {
+    #[serde(default)]
+    content: String,
+}
+
+impl SearchProvider for Meilisearch {
+    fn id(&self) -> &'static str {
+        "meilisearch"
+    }
+
+    fn is_remote(&self) -> bool {
+        !is_local_url(&self.base_url)
+    }
+
+    fn search<'a>(&'a self, query: &'a str, limit: usize) -> BoxFuture<'a, Result<Vec<Hit>, SearchError>> {
+        Box::pin(async move {
+            let request = self
+                .http
+                .post(format!("{}/indexes/{}/search", self.base_url.trim_end_matches('/'), IndexKind::Pages.uid()))
+                .json(&serde_json::json!({
+                    "q": query,
+                    "limit": limit,
+                    "attributesToRetrieve": ["url", "title", "content"],
+                    "attributesToCrop": ["content"],
+                    "cropLength": 30,
+                }));
+            let response: MeiliResponse = self.http.send(request).await?.error_for_status()?.json().await?;
+            Ok(response
+                .hits
+                .into_iter()
+                .map(|h| Hit {
+                    title: h.title,
+                    url: h.url,
+                    snippet: h.formatted.map(|f| collapse(&f.content)).unwrap_or_default(),
+                })
+                .collect())
+        })
+    }
+}
+
+// Recent entries from subscribed RSS/Atom feeds (see feeds.rs)
+pub struct Feeds;
+
+impl SearchProvider for Feeds {
+    fn id(&self) -> &'static str {
+        "feeds"
+    }
+
+    fn is_remote(&self) -> bool {
+        false
+    }
+
+    fn search<'a>(&'a self, query: &'a str, limit: usize) -> BoxFuture<'a, Result<Vec<Hit>, SearchError>> {
+        Box::pin(async move {
+            let entries = crate::feeds::search_recent(query, limit).map_err(|e| SearchError::Request(e.to_string()))?;
+            Ok(entries
+                .into_iter()
+                .filter_map(|entry| {
+                    Some(Hit {
+                        title: entry.title,
+                        url: entry.url?,
+                        snippet: entry.summary.unwrap_or_default(),
+                    })
+                })
+                .collect())
+        })
+    }
+}
+
+// ============================================================================
+// SEARCH
+// ============================================================================
+
+// Build the requested providers (or the configured defaults). Explicitly requested providers that
+// can't be used are reported as errors; unusable defaults are skipped quietly.
+pub fn providers(
+    requested: Option<&[String]>,
+    apikeys: &ApiKeyStore,
+) -> (Vec<Box<dyn SearchProvider>>, Vec<ProviderOutcome>) {
+    let config = crate::config::current().search;
+    let explicit = requested.is_some();
+    let ids: Vec<String> = match requested {
+        Some(ids) => ids.iter().map(|id| id.trim().to_lowercase()).collect(),
+        None => config.providers.clone(),
+    };
+    let client = || http::Client::new(SEARCH_TIMEOUT).with_retries(1).with_purpose("web-search");
+
+    let mut built: Vec<Box<dyn SearchProvider>> = Vec::new();
+    let mut skipped = Vec::new();
+    for id in ids {
+        if built.iter().any(|p| p.id() == id) {
+            continue;
+        }
+        let provider: Result<Box<dyn SearchProvider>, SearchError> = match id.as_str() {
+            "duckduckgo" => Ok(Box::new(DuckDuckGo { http: client().with_user_agent(BROWSER_UA) })),
+            "brave" => apikeys
+                .get(Provider::Brave)
+                .map(|key| Box::new(Brave { http: client(), key }) as Box<dyn SearchProvider>)
+                .ok_or_else(|| SearchError::Unavailable("no Brave API key configured".to_string())),
+            "searxng" => config
+                .searxng_url
+                .clone()
+                .map(|base_url| Box::new(Searxng { http: client(), base_url }) as Box<dyn SearchProvider>)
+                .ok_or_else(|| SearchError::Unavailable("search.searxngUrl is not set".to_string())),
+            "meilisearch" => Ok(Box::new(Meilisearch { http: client(), base_url: config.meilisearch_url.clone() })),
+            "feeds" => Ok(Box::new(Feeds)),
+            _ => Err(SearchError::UnknownProvider(id.clone())),
+        };
+        match provider {
+            Ok(provider) => built.push(provider),
+            Err(e) if explicit => skipped.push(outcome(&id, Err(&e), 0)),
+            Err(_) => {}
+        }
+    }
+    (built, skipped)
+}
+
+fn flights() -> &'static Coalescer<(), Result<WebSearchResponse, SearchError>> {
+    static FLIGHTS: OnceLock<Coalescer<(), Result<WebSearchResponse, SearchError>>> = OnceLock::new();
+    FLIGHTS.get_or_init(Coalescer::new)
+}
+
+// Identical searches running at the same time (e.g. two tabs researching one question) share one set of requests
+pub async fn search(
+    query: &str,
+    requested: Option<&[String]>,
+    limit: Option<usize>,
+    apikeys: &ApiKeyStore,
+) -> Result<WebSearchResponse, SearchError> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Err(SearchError::EmptyQuery);
+    }
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let providers = requested.map(|r| r.join(",")).unwrap_or_default();
+    let key = coalesce::key("web-search", &[query, &providers, &limit.to_string()]);
+    loop {
+        match flights().join(&key) {
+            Join::Follower(flight) => {
+                if let Some(result) = flight.follow(|_| {}).await {
+                    tracing::debug!(target: "app", "Web search: Joined an identical search in flight");
+                    return result;
+                }
+            }
+            Join::Leader(leader) => {
+                let result = run(query, requested, limit, apikeys).await;
+                leader.finish(result.clone());
+                return result;
+            }
+        }
+    }
+}
+
+async fn run(
+    query: &str,
+    requested: Option<&[String]>,
+    limit: usize,
+    apikeys: &ApiKeyStore,
+) -> Result<WebSearchResponse, SearchError> {
+    let (mut providers, mut outcomes) = providers(requested, apikeys);
+
+    // Don't wait on remote timeouts when the network is known to be down
+    let offline = crate::connectivity::is_offline();
+    if offline {
+        providers.retain(|p| {
+            if p.is_remote() {
+                outcomes.push(outcome(p.id(), Err(&SearchError::Offline), 0));
+            }
+            !p.is_remote()
+        });
+    }
+    if providers.is_empty() && outcomes.is_empty() {
+        return Err(SearchError::NoProviders);
+    }
+
+    let runs = providers.iter().map(|provider| async move {
+        let started = Instant::now();
+        let result = provider.search(query, limit).await;
+        (provider.id(), result, started.elapsed().as_millis() as u64)
+    });
+    let mut ranked = Vec::new();
+    for (id, result, latency_ms) in futures::future::join_all(runs).await {
+        match &result {
+            Ok(hits) => tracing::debug!(target: "app", "Web search: {} returned {} in {}ms", id, hits.len(), latency_ms),
+            Err(e) => tracing::warn!(target: "app", "Web search: {} failed: {}", id, e),
+        }
+        outcomes.push(outcome(id, result.as_ref().map(|h| h.len()), latency_ms));
+        if let Ok(hits) = result {
+            ranked.push((id, hits));
+        }
+    }
+
+    Ok(WebSearchResponse {
+        query: query.to_string(),
+        results: merge(ranked, limit),
+        providers: outcomes,
+        offline,
+    })
+}
+
+// Reciprocal rank fusion: a URL found by several providers outranks one found high by a single one
+pub fn merge(ranked: Vec<(&str, Vec<Hit>)>, limit: usize) -> Vec<WebResult> {
+    let mut merged: Vec<WebResult> = Vec::new();
+    let mut by_key: HashMap<String, usize> = HashMap::new();
+
+    for (provider, hits) in ranked {
+        for (rank, hit) in hits.into_iter().enumerate() {
+            let Some(key) = url_key(&hit.url) else { continue };
+            let score = 1.0 / (RRF_K + rank as f64 + 1.0);
+            match by_key.get(&key) {
+                Some(&i) => {
+                    let existing = &mut merged[i];
+                    existing.score += score;
+                    if !existing.sources.iter().any(|s| s == provider) {
+                        existing.sources.push(provider.to_string());
+                    }
+                    // Keep the most informative text any provider gave
+                    if existing.title.is_empty() {
+                        existing.title = hit.title;
+                    }
+                    if hit.snippet.len() > existing.snippet.len() {
+                        existing.snippet = hit.snippet;
+                    }
+                }
+                None => {
+                    by_key.insert(key, merged.len());
+                    merged.push(WebResult {
+                        title: hit.title,
+                        url: hit.url,
+                        snippet: hit.snippet,
+                        score,
+                        sources: vec![provider.to_string()],
+                    });
+                }
+            }
+        }
+    }
+
+    merged.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    merged.truncate(limit);
+    merged
+}
+
+// Same page regardless of scheme, "www.", fragment, or trailing slash
+fn url_key(raw: &str) -> Option<String> {
+    let url = url::Url::parse(raw).ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    let host = url.host_str()?.trim_start_matches("www.").to_lowercase();
+    let path = url.path().trim_end_matches('/');
+    Some(match url.query() {
+        Some(query) => format!("{}{}?{}", host, path, query),
+        None => format!("{}{}", host, path),
+    })
+}
+
+fn is_local_url(raw: &str) -> bool {
+    url::Url::parse(raw)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| matches!(h, "127.0.0.1" | "localhost" | "::1" | "[::1]")))
+        .unwrap_or(false)
+}
+
+fn outcome(provider: &str, result: Result<usize, &SearchError>, latency_ms: u64) -> ProviderOutcome {
+    ProviderOutcome {
+        provider: provider.to_string(),
+        ok: result.is_ok(),
+        count: *result.as_ref().unwrap_or(&0),
+        latency_ms,
+        error: result.err().map(|e| e.to_string()),
+    }
+}
+
+fn strip_tags(html: &str) -> String {
+    collapse(&Html::parse_fragment(html).root_element().text().collect::<String>())
+}
+
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[derive(Debug, Clone)]
+pub enum SearchError {
+    EmptyQuery,
+    NoProviders,
+    UnknownProvider(String),
+    Unavailable(String),
+    Offline,
+    Request(String),
+}
+
+impl From<HttpError> for SearchError {
+    fn from(e: HttpError) -> Self {
+        match e {
+            HttpError::Offline => SearchError::Offline,
+            e => SearchError::Request(e.to_string()),
+        }
+    }
+}
+
+impl From<reqwest::Error> for SearchError {
+    fn from(e: reqwest::Error) -> Self {
+        SearchError::Request(e.to_string())
+    }
+}
+
+impl std::fmt::Display for SearchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SearchError::EmptyQuery => write!(f, "Search query is empty"),
+            SearchError::NoProviders => write!(f, "No search providers are available"),
+            SearchError::UnknownProvider(p) => write!(f, "Unknown search provider: {}", p),
+            SearchError::Unavailable(msg) => write!(f, "Provider unavailable: {}", msg),
+            SearchError::Offline => write!(f, "Skipped while offline"),
+            SearchError::Request(msg) => write!(f, "Search request failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SearchError {}
@@ -0,0 +1,301 @@
+// WebRTC Guard - Keeps WebRTC ICE candidates from revealing local and public IPs in Private/Ghost modes
+// Engine-level switches only exist on some platforms, so a page-level guard script covers the rest
+//
+// Platform support:
+//   Windows (WebView2)  --force-webrtc-ip-handling-policy at launch, when the app starts in Private/Ghost,
+//                       plus the guard script for mode switches at runtime
+//   Linux (WebKitGTK)   WebRTC ships disabled (enable-webrtc is off by default); the guard script covers builds with it on
+//   macOS (WKWebView)   no candidate policy API; host IPs are mDNS-obfuscated, the guard script hides the rest
+
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+use tauri::Manager;
+use tokio::net::UdpSocket;
+
+use crate::state::PrivacyMode;
+
+// Public STUN server used by the self-check
+const STUN_SERVER: &str = "stun.cloudflare.com:3478";
+const STUN_TIMEOUT: Duration = Duration::from_secs(3);
+const STUN_ATTEMPTS: usize = 2;
+const STUN_MAGIC_COOKIE: u32 = 0x2112_A442;
+const STUN_BINDING_REQUEST: u16 = 0x0001;
+const STUN_BINDING_SUCCESS: u16 = 0x0101;
+const ATTR_MAPPED_ADDRESS: u16 = 0x0001;
+const ATTR_XOR_MAPPED_ADDRESS: u16 = 0x0020;
+
+// WebView2 reads extra Chromium switches from this variable when the first webview is created
+const WEBVIEW2_ARGS_VAR: &str = "WEBVIEW2_ADDITIONAL_BROWSER_ARGUMENTS";
+const WEBVIEW2_POLICY_ARG: &str = "--force-webrtc-ip-handling-policy=disable_non_proxied_udp";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub enum WebRtcPolicy {
+    Default,                           // Host, reflexive, and relay candidates (normal browsing)
+    RelayOnly,                         // Only TURN relay candidates; neither local nor public IP is exposed
+    Disabled,                          // No peer connections at all
+}
+
+impl WebRtcPolicy {
+    pub fn for_mode(mode: &PrivacyMode) -> Self {
+        match mode {
+            PrivacyMode::Normal => WebRtcPolicy::Default,
+            PrivacyMode::Private => WebRtcPolicy::RelayOnly,
+            PrivacyMode::Ghost => WebRtcPolicy::Disabled,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct PlatformSupport {
+    pub platform: String,
+    pub engine: String,
+    pub engine_policy: bool,           // The webview itself can be told to withhold candidates
+    pub guard_script: bool,
+    pub notes: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct LeakTestReport {
+    pub mode: String,
+    pub policy: WebRtcPolicy,
+    pub stun_server: Option<String>,   // None when the probe was skipped (Ghost or local-only mode)
+    pub public_ip: Option<String>,     // What a STUN server sees: the address a reflexive candidate would carry
+    pub local_ip: Option<String>,      // Outbound interface address: what a host candidate would carry
+    pub public_ip_exposed: bool,
+    pub local_ip_exposed: bool,
+    pub support: PlatformSupport,
+    pub message: String,
+}
+
+pub fn support() -> PlatformSupport {
+    let (platform, engine, engine_policy, notes) = if cfg!(target_os = "windows") {
+        ("windows", "WebView2", true, "Chromium IP handling policy is set at launch in Private/Ghost; runtime switches rely on the guard script")
+    } else if cfg!(target_os = "macos") {
+        ("macos", "WKWebView", false, "No candidate policy API; local IPs are mDNS-obfuscated by WebKit and the guard script hides reflexive candidates")
+    } else if cfg!(target_os = "linux") {
+        ("linux", "WebKitGTK", false, "WebRTC is off unless the WebKitGTK build enables it; the guard script covers builds that do")
+    } else {
+        (std::env::consts::OS, "unknown", false, "Only the guard script is available")
+    };
+    PlatformSupport {
+        platform: platform.to_string(),
+        engine: engine.to_string(),
+        engine_policy,
+        guard_script: true,
+        notes: notes.to_string(),
+    }
+}
+
+// Before the first webview exists: in Private/Ghost, ask WebView2 to keep UDP off non-proxied routes
+pub fn configure_engine(mode: &PrivacyMode) {
+    if !cfg!(target_os = "windows") || WebRtcPolicy::for_mode(mode) == WebRtcPolicy::Default {
+        return;
+    }
+    let existing = std::env::var(WEBVIEW2_ARGS_VAR).unwrap_or_default();
+    if existing.contains("webrtc-ip-handling-policy") {
+        return;
+    }
+    let args = format!("{} {}", existing, WEBVIEW2_POLICY_ARG);
+    std::env::set_var(WEBVIEW2_ARGS_VAR, args.trim());
+}
+
+// Page script enforcing a policy: forces iceTransportPolicy "relay" or removes RTCPeerConnection.
+// Installing Default restores the original constructors.
+pub fn guard_script(policy: WebRtcPolicy) -> String {
+    let policy = match policy {
+        WebRtcPolicy::Default => "default",
+        WebRtcPolicy::RelayOnly => "relay",
+        WebRtcPolicy::Disabled => "disabled",
+    };
+    format!(
+        r#"(function () {{
+  var policy = "{policy}";
+  var names = ["RTCPeerConnection", "webkitRTCPeerConnection"];
+  var saved = window.__regenWebRtc || (window.__regenWebRtc = {{}});
+  names.forEach(function (name) {{
+    var original = saved[name] || window[name];
+    if (!original) return;
+    saved[name] = original;
+    if (policy === "default") {{ window[name] = original; return; }}
+    if (policy === "disabled") {{
+      window[name] = function () {{ throw new DOMException("WebRTC is disabled in this privacy mode", "NotAllowedError"); }};
+      return;
+    }}
+    var Guarded = function (config, constraints) {{
+      var relayed = Object.assign({{}}, config || {{}}, {{ iceTransportPolicy: "relay" }});
+      return new original(relayed, constraints);
+    }};
+    Guarded.prototype = original.prototype;
+    Guarded.generateCertificate = original.generateCertificate;
+    original.prototype.setConfiguration = (function (set) {{
+      return function (config) {{ return set.call(this, Object.assign({{}}, config || {{}}, {{ iceTransportPolicy: "relay" }})); }};
+    }})(saved.setConfiguration || (saved.setConfiguration = original.prototype.setConfiguration));
+    window[name] = Guarded;
+  }});
+  if (policy === "default" && saved.setConfiguration && window.RTCPeerConnection) {{
+    window.RTCPeerConnection.prototype.setConfiguration = saved.setConfiguration;
+  }}
+}})();"#
+    )
+}
+
+// Privacy mode changed: re-install the guard in every open webview
+pub fn apply(app: &tauri::AppHandle, mode: &PrivacyMode) {
+    let policy = WebRtcPolicy::for_mode(mode);
+    let script = guard_script(policy);
+    for (label, window) in app.webview_windows() {
+        if let Err(e) = window.eval(&script) {
+            tracing::warn!(target: "privacy", "WebRTC guard: Failed to update {}: {}", label, e);
+        }
+    }
+    tracing::info!(target: "privacy", "WebRTC guard: Policy {:?}", policy);
+}
+
+// STUN self-check: what would a page's WebRTC code learn about this machine right now?
+pub async fn leak_test(mode: &PrivacyMode) -> LeakTestReport {
+    let policy = WebRtcPolicy::for_mode(mode);
+    let mode_name = format!("{:?}", mode).to_lowercase();
+
+    // Ghost mode doesn't contact third parties just to confirm what it already blocks
+    if policy == WebRtcPolicy::Disabled {
+        return LeakTestReport {
+            mode: mode_name,
+            policy,
+            stun_server: None,
+            public_ip: None,
+            local_ip: None,
+            public_ip_exposed: false,
+            local_ip_exposed: false,
+            support: support(),
+            message: "WebRTC is disabled; no addresses can be exposed".to_string(),
+        };
+    }
+
+    // The probe is raw UDP to a public server, outside http::Client
+    let local_only = crate::http::local_only();
+    let (public_ip, local_ip, error) = if local_only {
+        (None, None, Some("local-only mode is on".to_string()))
+    } else {
+        match stun_probe(STUN_SERVER).await {
+            Ok((public, local)) => (Some(public), local, None),
+            Err(e) => (None, None, Some(e)),
+        }
+    };
+    let exposed = policy == WebRtcPolicy::Default;
+    let public_ip_exposed = exposed && public_ip.is_some();
+    // A host candidate only adds something when the interface address differs from the public one
+    let local_ip_exposed = exposed && local_ip.is_some() && local_ip != public_ip;
+    let message = match (&error, exposed) {
+        (Some(e), _) => format!("STUN check failed ({}); result is based on the active policy only", e),
+        (None, true) => "Pages can see your public IP through WebRTC; switch to Private or Ghost mode to hide it".to_string(),
+        (None, false) => "Only relay candidates are allowed; your public and local IPs stay hidden".to_string(),
+    };
+
+    LeakTestReport {
+        mode: mode_name,
+        policy,
+        stun_server: (!local_only).then(|| STUN_SERVER.to_string()),
+        public_ip: public_ip.map(|ip| ip.to_string()),
+        local_ip: local_ip.map(|ip| ip.to_string()),
+        public_ip_exposed,
+        local_ip_exposed,
+        support: support(),
+        message,
+    }
+}
+
+// One STUN binding request; returns the mapped (public) address and the socket's local address
+async fn stun_probe(server: &str) -> Result<(IpAddr, Option<IpAddr>), String> {
+    let socket = UdpSocket::bind("0.0.0.0:0").await.map_err(|e| e.to_string())?;
+    socket.connect(server).await.map_err(|e| format!("{}: {}", server, e))?;
+    let local_ip = socket.local_addr().ok().map(|a| a.ip()).filter(|ip| !ip.is_unspecified());
+
+    let mut transaction_id = [0u8; 12];
+    transaction_id.copy_from_slice(&uuid::Uuid::new_v4().as_bytes()[..12]);
+    let mut request = Vec::with_capacity(20);
+    request.extend_from_slice(&STUN_BINDING_REQUEST.to_be_bytes());
+    request.extend_from_slice(&0u16.to_be_bytes());
+    request.extend_from_slice(&STUN_MAGIC_COOKIE.to_be_bytes());
+    request.extend_from_slice(&transaction_id);
+
+    let mut buf = [0u8; 512];
+    for _ in 0..STUN_ATTEMPTS {
+        socket.send(&request).await.map_err(|e| e.to_string())?;
+        match tokio::time::timeout(STUN_TIMEOUT, socket.recv(&mut buf)).await {
+            Ok(Ok(len)) => {
+                let mapped = parse_binding_response(&buf[..len], &transaction_id)
+                    .ok_or("unexpected STUN response")?;
+                return Ok((mapped.ip(), local_ip));
+            }
+            Ok(Err(e)) => return Err(e.to_string()),
+            Err(_) => continue,
+        }
+    }
+    Err("no response from STUN server".to_string())
+}
+
+fn parse_binding_response(msg: &[u8], transaction_id: &[u8; 12]) -> Option<SocketAddr> {
+    if msg.len() < 20
+        || u16::from_be_bytes([msg[0], msg[1]]) != STUN_BINDING_SUCCESS
+        || u32::from_be_bytes([msg[4], msg[5], msg[6], msg[7]]) != STUN_MAGIC_COOKIE
+        || &msg[8..20] != transaction_id
+    {
+        return None;
+    }
+    let length = u16::from_be_bytes([msg[2], msg[3]]) as usize;
+    let body = msg.get(20..20 + length)?;
+
+    let mut mapped = None;
+    let mut offset = 0;
+    while offset + 4 <= body.len() {
+        let kind = u16::from_be_bytes([body[offset], body[offset + 1]]);
+        let len = u16::from_be_bytes([body[offset + 2], body[offset + 3]]) as usize;
+        let value = body.get(offset + 4..offset + 4 + len)?;
+        match kind {
+            ATTR_XOR_MAPPED_ADDRESS => return parse_address(value, Some(transaction_id)),
+            ATTR_MAPPED_ADDRESS => mapped = parse_address(value, None),
+            _ => {}
+        }
+        // Attributes are padded to 4 bytes
+        offset += 4 + len.div_ceil(4) * 4;
+    }
+    mapped
+}
+
+// (XOR-)MAPPED-ADDRESS value: reserved, family, port, address
+fn parse_address(value: &[u8], xor_with: Option<&[u8; 12]>) -> Option<SocketAddr> {
+    if value.len() < 8 {
+        return None;
+    }
+    let cookie = STUN_MAGIC_COOKIE.to_be_bytes();
+    let mut port = u16::from_be_bytes([value[2], value[3]]);
+    if xor_with.is_some() {
+        port ^= (STUN_MAGIC_COOKIE >> 16) as u16;
+    }
+    let ip = match value[1] {
+        0x01 => {
+            let mut octets: [u8; 4] = value[4..8].try_into().ok()?;
+            if xor_with.is_some() {
+                octets.iter_mut().zip(cookie).for_each(|(b, k)| *b ^= k);
+            }
+            IpAddr::V4(Ipv4Addr::from(octets))
+        }
+        0x02 => {
+            let mut octets: [u8; 16] = value.get(4..20)?.try_into().ok()?;
+            if let Some(transaction_id) = xor_with {
+                let key: Vec<u8> = cookie.iter().chain(transaction_id.iter()).copied().collect();
+                octets.iter_mut().zip(key).for_each(|(b, k)| *b ^= k);
+            }
+            IpAddr::V6(Ipv6Addr::from(octets))
+        }
+        _ => return None,
+    };
+    Some(SocketAddr::new(ip, port))
+}
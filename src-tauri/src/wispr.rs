@@ -0,0 +1,463 @@
+// WISPR - Voice assistant pipeline: transcribe (whisper.cpp) -> understand -> act -> speak (system TTS)
+// Simple commands (open, search, instant answers, calendar) are handled directly; anything else goes to the tool agent.
+// Every stage is reported through `emit` so the frontend can show listening/understanding/acting/speaking.
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+use tokio::process::Command;
+
+use crate::agent::{self, AgentEvent};
+use crate::calendar::{self, NewEvent};
+use crate::db::Database;
+use crate::instant_answers::{self, InstantAnswer};
+use crate::privacy::PrivacyEnforcer;
+use crate::services::binaries;
+
+const WHISPER_BINARIES: &[&str] = &["whisper-cli", "whisper-cpp", "whisper"];
+const SEARCH_URL: &str = "https://duckduckgo.com/?q=";
+const MAX_AUDIO_BYTES: usize = 25 * 1024 * 1024;
+const MAX_TEXT_CHARS: usize = 2_000;
+// Long agent answers are cut at a sentence near this length before being read aloud
+const MAX_SPOKEN_CHARS: usize = 600;
+const TRANSCRIBE_TIMEOUT: Duration = Duration::from_secs(120);
+const SPEAK_TIMEOUT: Duration = Duration::from_secs(90);
+
+// Either recorded audio (base64 16 kHz WAV, as whisper.cpp expects) or text typed/recognized elsewhere
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct WisprInput {
+    pub text: Option<String>,
+    pub audio: Option<String>,
+    pub language: Option<String>,      // Overrides config.voice.language for this utterance
+    pub speak: Option<bool>,           // Overrides config.voice.speak
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Stage {
+    Listening,
+    Understanding,
+    Acting,
+    Speaking,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS), ts(rename = "WisprIntent"))]
+#[serde(tag = "kind", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum Intent {
+    Open { url: String },
+    Search { query: String },
+    Answer { answer: InstantAnswer },
+    AddEvent { event: NewEvent },
+    Schedule { from: i64, to: i64, label: String },  // Window of a schedule question, e.g. "tomorrow"
+    Agent { query: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct WisprResult {
+    pub transcript: String,
+    pub intent: Intent,
+    pub reply: String,
+    pub tab_id: Option<String>,        // Tab opened by an open/search command
+    pub agent_run_id: Option<String>,
+    pub spoken: bool,
+    pub elapsed_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case", rename_all_fields = "camelCase")]
+pub enum WisprEvent {
+    Stage { stage: Stage },
+    Transcript { text: String },
+    Intent { intent: Intent },
+    Agent { event: AgentEvent },       // Tool calls and results while the agent acts
+    Reply { text: String },
+    Done { result: WisprResult },
+    Failed { error: String },
+}
+
+pub async fn handle(
+    app: &tauri::AppHandle,
+    request_id: &str,
+    input: WisprInput,
+    emit: impl Fn(WisprEvent) + Send + Sync,
+) -> Result<WisprResult, WisprError> {
+    let result = handle_inner(app, request_id, input, &emit).await;
+    match &result {
+        Ok(result) => emit(WisprEvent::Done { result: result.clone() }),
+        Err(e) => emit(WisprEvent::Failed { error: e.to_string() }),
+    }
+    result
+}
+
+async fn handle_inner(
+    app: &tauri::AppHandle,
+    request_id: &str,
+    input: WisprInput,
+    emit: &(impl Fn(WisprEvent) + Send + Sync),
+) -> Result<WisprResult, WisprError> {
+    let started = Instant::now();
+    let config = crate::config::current().voice;
+    let language = input.language.clone().unwrap_or_else(|| config.language.clone());
+
+    emit(WisprEvent::Stage { stage: Stage::Listening });
+    let transcript = match (input.text.as_deref().map(str::trim).filter(|t| !t.is_empty()), input.audio.as_deref()) {
+        (Some(text), _) => text.chars().take(MAX_TEXT_CHARS).collect(),
+        (None, Some(audio)) => transcribe(app, audio, &language).await?,
+        (None, None) => return Err(WisprError::EmptyInput),
+    };
+    if transcript.is_empty() {
+        return Err(WisprError::NothingHeard);
+    }
+    emit(WisprEvent::Transcript { text: transcript.clone() });
+
+    emit(WisprEvent::Stage { stage: Stage::Understanding });
+    let intent = parse_intent(&transcript);
+    emit(WisprEvent::Intent { intent: intent.clone() });
+
+    emit(WisprEvent::Stage { stage: Stage::Acting });
+    let mut tab_id = None;
+    let mut agent_run_id = None;
+    let reply = match &intent {
+        Intent::Open { url } => {
+            tab_id = open_tab(app, url).await?;
+            format!("Opening {}", url::Url::parse(url).ok().and_then(|u| u.host_str().map(str::to_string)).unwrap_or_else(|| url.clone()))
+        }
+        Intent::Search { query } => {
+            let url = format!("{}{}", SEARCH_URL, url::form_urlencoded::byte_serialize(query.as_bytes()).collect::<String>());
+            tab_id = open_tab(app, &url).await?;
+            format!("Searching for {}", query)
+        }
+        Intent::Answer { answer } => spoken_answer(answer),
+        Intent::AddEvent { event } => {
+            app.state::<Mutex<PrivacyEnforcer>>()
+                .lock()
+                .unwrap()
+                .enforce_disk_write()
+                .map_err(|e| WisprError::Action(e.to_string()))?;
+            let event = calendar::add(&app.state::<Database>(), event.clone(), "voice").map_err(|e| WisprError::Action(e.to_string()))?;
+            format!("Added {} {}", event.title, calendar::describe_when(event.starts_at, event.all_day, chrono::Local::now()))
+        }
+        Intent::Schedule { from, to, label } => {
+            let events = calendar::between(&app.state::<Database>(), *from, *to).map_err(|e| WisprError::Action(e.to_string()))?;
+            spoken_schedule(&events, label)
+        }
+        Intent::Agent { query } => {
+            let answer = agent::run(app, request_id, query, |event| emit(WisprEvent::Agent { event }))
+                .await
+                .map_err(|e| WisprError::Agent(e.to_string()))?;
+            agent_run_id = Some(answer.run_id);
+            answer.answer
+        }
+    };
+    emit(WisprEvent::Reply { text: reply.clone() });
+
+    let mut spoken = false;
+    if input.speak.unwrap_or(config.speak) {
+        emit(WisprEvent::Stage { stage: Stage::Speaking });
+        match speak(&speakable(&reply), config.tts_voice.as_deref(), config.tts_rate).await {
+            Ok(()) => spoken = true,
+            // The reply is already on screen; a missing synthesizer shouldn't fail the request
+            Err(e) => tracing::warn!(target: "app", "WISPR: Speech failed: {}", e),
+        }
+    }
+
+    tracing::info!(target: "app", "WISPR: Handled {} intent in {}ms", intent_kind(&intent), started.elapsed().as_millis());
+    Ok(WisprResult {
+        transcript,
+        intent,
+        reply,
+        tab_id,
+        agent_run_id,
+        spoken,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+fn intent_kind(intent: &Intent) -> &'static str {
+    match intent {
+        Intent::Open { .. } => "open",
+        Intent::Search { .. } => "search",
+        Intent::Answer { .. } => "answer",
+        Intent::AddEvent { .. } => "add-event",
+        Intent::Schedule { .. } => "schedule",
+        Intent::Agent { .. } => "agent",
+    }
+}
+
+// Commands with an obvious meaning skip the model; everything else is the agent's
+pub fn parse_intent(transcript: &str) -> Intent {
+    let text = transcript.trim().trim_end_matches(['.', '!', '?']).trim();
+    let after = |prefixes: &[&str]| {
+        prefixes.iter().find_map(|p| {
+            let head = text.get(..p.len()).filter(|head| head.eq_ignore_ascii_case(p))?;
+            Some(text[head.len()..].trim().to_string())
+        })
+    };
+
+    if let Some(target) = after(&["open ", "go to ", "navigate to ", "take me to "]).filter(|t| !t.is_empty()) {
+        // Speech recognizers write "github dot com"
+        let spelled = target.to_lowercase().replace(" dot ", ".").replace(' ', "");
+        if crate::omnibox::looks_like_url(&spelled) {
+            let candidate = if spelled.contains("://") { spelled } else { format!("https://{}", spelled) };
+            if let Ok(url) = url::Url::parse(&candidate) {
+                return Intent::Open { url: url.to_string() };
+            }
+        }
+        return Intent::Search { query: target };
+    }
+    if let Some(query) = after(&["search for ", "search the web for ", "search ", "look up ", "google "]).filter(|q| !q.is_empty()) {
+        return Intent::Search { query };
+    }
+    // Needs a day or time plus an add verb, or a schedule word; the '?' still marks a question here
+    let now = chrono::Local::now();
+    if let Some(event) = calendar::parse_event(transcript.trim(), now) {
+        return Intent::AddEvent { event };
+    }
+    if let Some((from, to, label)) = calendar::parse_schedule_query(transcript.trim(), now) {
+        return Intent::Schedule { from, to, label };
+    }
+    let question = after(&["what is ", "what's ", "how much is ", "calculate ", "convert "]).unwrap_or_else(|| text.to_string());
+    if let Some(answer) = instant_answers::answer(&question).or_else(|| instant_answers::answer(text)) {
+        return Intent::Answer { answer };
+    }
+    Intent::Agent { query: text.to_string() }
+}
+
+fn spoken_answer(answer: &InstantAnswer) -> String {
+    match &answer.note {
+        Some(note) => format!("{} is {} ({})", answer.expression, answer.result, note),
+        None => format!("{} is {}", answer.expression, answer.result),
+    }
+}
+
+fn spoken_schedule(events: &[calendar::CalendarEvent], label: &str) -> String {
+    if events.is_empty() {
+        return format!("Nothing on your calendar {}.", label);
+    }
+    let mut chars = label.chars();
+    let label: String = chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default();
+    let items: Vec<String> = events.iter().map(calendar::describe).collect();
+    format!("{}: {}.", label, items.join("; "))
+}
+
+async fn open_tab(app: &tauri::AppHandle, url: &str) -> Result<Option<String>, WisprError> {
+    let opened = crate::tools::registry()
+        .call(app, "open_tab", serde_json::json!({ "url": url }))
+        .await
+        .map_err(|e| WisprError::Action(e.to_string()))?;
+    Ok(opened.get("tabId").and_then(|v| v.as_str()).map(str::to_string))
+}
+
+pub(crate) fn whisper_model(app: &tauri::AppHandle) -> Option<PathBuf> {
+    if let Some(model) = crate::config::current().voice.whisper_model {
+        return Some(PathBuf::from(model));
+    }
+    let dir = app.path().app_data_dir().ok()?.join("models");
+    let mut models: Vec<PathBuf> = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            let name = p.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            name.starts_with("ggml-") && name.ends_with(".bin")
+        })
+        .collect();
+    models.sort();
+    models.into_iter().next()
+}
+
+async fn transcribe(app: &tauri::AppHandle, audio: &str, language: &str) -> Result<String, WisprError> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(audio.trim())
+        .map_err(|e| WisprError::InvalidAudio(e.to_string()))?;
+    if bytes.len() > MAX_AUDIO_BYTES {
+        return Err(WisprError::InvalidAudio(format!("recordings are limited to {} MB", MAX_AUDIO_BYTES / (1024 * 1024))));
+    }
+    if bytes.len() < 12 || &bytes[..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err(WisprError::InvalidAudio("expected a WAV recording".to_string()));
+    }
+    if language != "auto" && !(language.len() == 2 && language.chars().all(|c| c.is_ascii_lowercase())) {
+        return Err(WisprError::InvalidAudio(format!("invalid language '{}'", language)));
+    }
+
+    let dirs = binaries::search_dirs(app.path().resource_dir().ok().as_deref());
+    let whisper = WHISPER_BINARIES
+        .iter()
+        .find_map(|name| binaries::locate(name, &dirs).filter(|b| b.executable))
+        .ok_or_else(|| WisprError::ToolMissing("whisper.cpp (whisper-cli)".to_string()))?;
+    let model = whisper_model(app).filter(|m| m.is_file()).ok_or(WisprError::ModelMissing)?;
+
+    let path = std::env::temp_dir().join(format!("regen-wispr-{}.wav", uuid::Uuid::new_v4()));
+    std::fs::write(&path, &bytes).map_err(|e| WisprError::Transcription(e.to_string()))?;
+    let output = run_whisper(&whisper.path, &model, &path, language).await;
+    let _ = std::fs::remove_file(&path);
+    output
+}
+
+async fn run_whisper(binary: &Path, model: &Path, audio: &Path, language: &str) -> Result<String, WisprError> {
+    let started = Instant::now();
+    let run = Command::new(binary)
+        .arg("-m")
+        .arg(model)
+        .arg("-f")
+        .arg(audio)
+        .args(["-l", language, "--no-timestamps", "--no-prints"])
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(TRANSCRIBE_TIMEOUT, run)
+        .await
+        .map_err(|_| WisprError::Transcription("whisper timed out".to_string()))?
+        .map_err(|e| WisprError::Transcription(e.to_string()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(WisprError::Transcription(format!("whisper exited with {}: {}", output.status, stderr.trim())));
+    }
+    let text = clean_transcript(&String::from_utf8_lossy(&output.stdout));
+    tracing::debug!(target: "app", "WISPR: Transcribed {} chars in {}ms", text.len(), started.elapsed().as_millis());
+    Ok(text)
+}
+
+// whisper.cpp marks silence and noise as [BLANK_AUDIO], (music), ...
+fn clean_transcript(raw: &str) -> String {
+    let mut text = String::new();
+    for line in raw.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let is_marker = (line.starts_with('[') && line.ends_with(']')) || (line.starts_with('(') && line.ends_with(')'));
+        if !is_marker {
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            text.push_str(line);
+        }
+    }
+    text
+}
+
+// Markdown read aloud sounds like punctuation; keep the words and stop at a sentence boundary
+fn speakable(reply: &str) -> String {
+    let plain: String = reply
+        .lines()
+        .map(|l| l.trim().trim_start_matches(['#', '-', '*', '>']).trim())
+        .filter(|l| !l.is_empty() && !l.starts_with("```"))
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace(['*', '`', '_'], "");
+    if plain.chars().count() <= MAX_SPOKEN_CHARS {
+        return plain;
+    }
+    let cut: String = plain.chars().take(MAX_SPOKEN_CHARS).collect();
+    match cut.rfind(['.', '!', '?']) {
+        Some(end) if end > MAX_SPOKEN_CHARS / 3 => cut[..=end].to_string(),
+        _ => format!("{}...", cut.trim_end()),
+    }
+}
+
+// macOS `say`, espeak-ng/espeak on Linux, SAPI through PowerShell on Windows
+async fn speak(text: &str, voice: Option<&str>, rate: u32) -> Result<(), WisprError> {
+    if text.is_empty() {
+        return Ok(());
+    }
+    let mut command = match std::env::consts::OS {
+        "macos" => {
+            let mut command = Command::new("say");
+            if let Some(voice) = voice {
+                command.args(["-v", voice]);
+            }
+            if rate > 0 {
+                command.args(["-r", &rate.to_string()]);
+            }
+            command.arg("--").arg(text);
+            command
+        }
+        "windows" => {
+            // Text goes through stdin so nothing in it is parsed as PowerShell
+            let mut script = String::from("Add-Type -AssemblyName System.Speech; $s = New-Object System.Speech.Synthesis.SpeechSynthesizer;");
+            if let Some(voice) = voice.filter(|v| v.chars().all(|c| c.is_alphanumeric() || c == ' ')) {
+                script.push_str(&format!(" $s.SelectVoice('{}');", voice));
+            }
+            if rate > 0 {
+                // SAPI rates run -10..10 around roughly 180 words per minute
+                script.push_str(&format!(" $s.Rate = {};", ((rate as i32 - 180) / 20).clamp(-10, 10)));
+            }
+            script.push_str(" $s.Speak([Console]::In.ReadToEnd())");
+            let mut command = Command::new("powershell");
+            command.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+            command
+        }
+        _ => {
+            let binary = ["espeak-ng", "espeak"]
+                .into_iter()
+                .find(|b| which::which(b).is_ok())
+                .ok_or_else(|| WisprError::ToolMissing("espeak-ng".to_string()))?;
+            let mut command = Command::new(binary);
+            if let Some(voice) = voice {
+                command.args(["-v", voice]);
+            }
+            if rate > 0 {
+                command.args(["-s", &rate.to_string()]);
+            }
+            command.arg("--").arg(text);
+            command
+        }
+    };
+
+    let windows = std::env::consts::OS == "windows";
+    let mut child = command
+        .stdin(if windows { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| WisprError::Speech(e.to_string()))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        use tokio::io::AsyncWriteExt;
+        stdin.write_all(text.as_bytes()).await.map_err(|e| WisprError::Speech(e.to_string()))?;
+    }
+    let output = tokio::time::timeout(SPEAK_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| WisprError::Speech("speech timed out".to_string()))?
+        .map_err(|e| WisprError::Speech(e.to_string()))?;
+    if !output.status.success() {
+        return Err(WisprError::Speech(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+pub enum WisprError {
+    EmptyInput,
+    NothingHeard,                      // The recording transcribed to silence
+    InvalidAudio(String),
+    ToolMissing(String),
+    ModelMissing,
+    Transcription(String),
+    Action(String),
+    Agent(String),
+    Speech(String),
+}
+
+impl std::fmt::Display for WisprError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WisprError::EmptyInput => write!(f, "Provide text or audio"),
+            WisprError::NothingHeard => write!(f, "No speech was recognized"),
+            WisprError::InvalidAudio(msg) => write!(f, "Invalid audio: {}", msg),
+            WisprError::ToolMissing(tool) => write!(f, "{} is not installed", tool),
+            WisprError::ModelMissing => write!(f, "No whisper model found; set voice.whisperModel or add a ggml-*.bin to the models directory"),
+            WisprError::Transcription(e) => write!(f, "Transcription failed: {}", e),
+            WisprError::Action(e) => write!(f, "Action failed: {}", e),
+            WisprError::Agent(e) => write!(f, "Agent failed: {}", e),
+            WisprError::Speech(e) => write!(f, "Speech failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for WisprError {}
@@ -0,0 +1,186 @@
+// Workflows - Trigger and list the user's n8n automations on the supervised local n8n
+// Triggering posts to a webhook (no key needed); listing uses n8n's public API with the key saved under "n8n"
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::apikeys::{ApiKeyStore, Provider};
+use crate::http::{self, HttpError};
+
+// Where the supervisor runs n8n (see services/supervisor.rs)
+const N8N_URL: &str = "http://127.0.0.1:5678";
+const WEBHOOK_NODE: &str = "n8n-nodes-base.webhook";
+const LIST_TIMEOUT: Duration = Duration::from_secs(15);
+// Webhooks set to answer when the workflow finishes hold the request open until then
+const TRIGGER_TIMEOUT: Duration = Duration::from_secs(120);
+const PAGE_SIZE: u32 = 100;
+const MAX_PAGES: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct Workflow {
+    pub id: String,
+    pub name: String,
+    pub active: bool,                  // Production webhooks only answer while active
+    pub tags: Vec<String>,
+    pub webhooks: Vec<WorkflowWebhook>,
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowWebhook {
+    pub webhook_id: String,            // What workflow_trigger takes: the node's path, or its generated id
+    pub method: String,
+    pub node: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowRun {
+    pub webhook_id: String,
+    pub status: u16,
+    pub response: Value,               // The webhook's reply; text replies come back as a string
+}
+
+fn list_client() -> &'static http::Client {
+    static CLIENT: OnceLock<http::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| http::Client::new(LIST_TIMEOUT).with_purpose("workflows"))
+}
+
+// Never retried: a second POST would run the workflow twice
+fn trigger_client() -> &'static http::Client {
+    static CLIENT: OnceLock<http::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| http::Client::new(TRIGGER_TIMEOUT).with_retries(0).with_purpose("workflows"))
+}
+
+pub async fn list(keys: &ApiKeyStore) -> Result<Vec<Workflow>, WorkflowError> {
+    let key = keys.get(Provider::N8n).ok_or(WorkflowError::NoApiKey)?;
+    let client = list_client();
+    let mut workflows = Vec::new();
+    let mut cursor: Option<String> = None;
+    for _ in 0..MAX_PAGES {
+        let mut request = client
+            .get(format!("{}/api/v1/workflows", N8N_URL))
+            .query(&[("limit", PAGE_SIZE.to_string())])
+            .header("X-N8N-API-KEY", &key);
+        if let Some(cursor) = &cursor {
+            request = request.query(&[("cursor", cursor)]);
+        }
+        let response = client.send(request).await.map_err(WorkflowError::from)?;
+        let status = response.status().as_u16();
+        if status == 401 || status == 403 {
+            return Err(WorkflowError::Rejected("n8n refused the API key".to_string()));
+        }
+        if !response.status().is_success() {
+            return Err(WorkflowError::Rejected(format!("n8n returned HTTP {}", status)));
+        }
+        let page: Value = response.json().await.map_err(|e| WorkflowError::Failed(e.to_string()))?;
+        workflows.extend(page.get("data").and_then(Value::as_array).into_iter().flatten().map(parse_workflow));
+        cursor = page.get("nextCursor").and_then(Value::as_str).map(str::to_string);
+        if cursor.is_none() {
+            break;
+        }
+    }
+    Ok(workflows)
+}
+
+// POST `payload` as JSON to the production webhook; the workflow must be active
+pub async fn trigger(webhook_id: &str, payload: &Value) -> Result<WorkflowRun, WorkflowError> {
+    let webhook_id = webhook_id.trim().trim_matches('/');
+    let valid = !webhook_id.is_empty()
+        && webhook_id.len() <= 200
+        && webhook_id.split('/').all(|segment| !segment.is_empty() && segment != "." && segment != "..")
+        && webhook_id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '/' | ':' | '.'));
+    if !valid {
+        return Err(WorkflowError::InvalidWebhook(webhook_id.to_string()));
+    }
+    let client = trigger_client();
+    let request = client.post(format!("{}/webhook/{}", N8N_URL, webhook_id)).json(payload);
+    let response = client.send(request).await.map_err(WorkflowError::from)?;
+    let status = response.status().as_u16();
+    let text = response.text().await.unwrap_or_default();
+    let reply = serde_json::from_str(&text).unwrap_or(Value::String(text));
+    if status == 404 {
+        return Err(WorkflowError::Rejected(format!(
+            "No active workflow listens on webhook '{}' for POST (activate it in n8n)",
+            webhook_id
+        )));
+    }
+    if !(200..300).contains(&status) {
+        let message = reply.get("message").and_then(Value::as_str).map(str::to_string).unwrap_or_else(|| reply.to_string());
+        return Err(WorkflowError::Rejected(format!("Webhook returned HTTP {}: {}", status, message)));
+    }
+    tracing::info!(target: "services", "Workflows: Triggered webhook {}", webhook_id);
+    Ok(WorkflowRun { webhook_id: webhook_id.to_string(), status, response: reply })
+}
+
+fn parse_workflow(workflow: &Value) -> Workflow {
+    let text = |value: &Value, name: &str| value.get(name).and_then(Value::as_str).unwrap_or_default().to_string();
+    let webhooks = workflow
+        .get("nodes")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|node| node.get("type").and_then(Value::as_str) == Some(WEBHOOK_NODE))
+        .filter(|node| node.get("disabled").and_then(Value::as_bool) != Some(true))
+        .filter_map(|node| {
+            let parameters = node.get("parameters").cloned().unwrap_or(Value::Null);
+            let path = text(&parameters, "path");
+            let webhook_id = if path.is_empty() { text(node, "webhookId") } else { path };
+            let method = parameters.get("httpMethod").and_then(Value::as_str).unwrap_or("GET").to_string();
+            (!webhook_id.is_empty()).then(|| WorkflowWebhook { webhook_id, method, node: text(node, "name") })
+        })
+        .collect();
+    Workflow {
+        id: text(workflow, "id"),
+        name: text(workflow, "name"),
+        active: workflow.get("active").and_then(Value::as_bool).unwrap_or(false),
+        tags: workflow
+            .get("tags")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|tag| tag.get("name").and_then(Value::as_str).map(str::to_string))
+            .collect(),
+        webhooks,
+        updated_at: workflow.get("updatedAt").and_then(Value::as_str).map(str::to_string),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum WorkflowError {
+    Unreachable(String),
+    NoApiKey,
+    InvalidWebhook(String),
+    Rejected(String),
+    Failed(String),
+}
+
+impl From<HttpError> for WorkflowError {
+    fn from(e: HttpError) -> Self {
+        match e {
+            HttpError::Request(msg) => WorkflowError::Unreachable(msg),
+            e => WorkflowError::Failed(e.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for WorkflowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WorkflowError::Unreachable(msg) => write!(f, "Could not reach n8n (is it running? see Services): {}", msg),
+            WorkflowError::NoApiKey => write!(f, "Listing workflows needs an n8n API key (Settings > API keys)"),
+            WorkflowError::InvalidWebhook(id) => write!(f, "'{}' is not a webhook id", id),
+            WorkflowError::Rejected(msg) => write!(f, "{}", msg),
+            WorkflowError::Failed(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for WorkflowError {}